@@ -6,13 +6,48 @@ use sgx_urts::SgxEnclave;
 use std::env;
 
 extern crate enclave_verifier;
+use enclave_verifier::attestation;
+use enclave_verifier::attestation::Evidence;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::host_verify;
+use enclave_verifier::enclave::identity;
+use enclave_verifier::enclave::identity::SealPolicy;
+use enclave_verifier::enclave::allowlist::CheckerAllowlist;
+use enclave_verifier::sealed_store::SealedStore;
+use enclave_verifier::session;
+use enclave_verifier::interpreter;
+use enclave_verifier::enclave::executor;
+use enclave_verifier::enclave::executor::ReportBody;
 
 static ENCLAVE_FILE: &'static str = "enclave.signed.so";
 
+/// Directory of the sealed identity of the enclave.
+static SEALED_DIR: &'static str = "sealed";
+
 extern {
+	fn init_identity(eid: sgx_enclave_id_t, retval: *mut sgx_status_t,
+		sealed_identity: *const u8, sealed_identity_len: usize,
+		seal_policy: u8,
+		out_sealed_identity: * mut u8, out_sealed_identity_len: usize,
+		out_sealed_identity_written: * mut u64) -> sgx_status_t;
+
+	fn provision_checkers(eid: sgx_enclave_id_t, retval: *mut sgx_status_t,
+		checker_allowlist: *const u8, checker_allowlist_len: usize) -> sgx_status_t;
+
 	fn interpret_byte_code(eid: sgx_enclave_id_t, retval: *mut sgx_status_t,
 		byte_code: *const u8, byte_code_len: usize,
-		param_list: *const u8, param_list_len: usize,) -> sgx_status_t;
+		param_list: *const u8, param_list_len: usize,
+		oblivious: u8,
+		sealed: u8,
+		nonce: *const u8, nonce_len: usize,
+		target_info: *const u8, target_info_len: usize,
+		out_evidence: * mut u8, out_evidence_len: usize,
+		out_result: * mut u8, out_result_len: usize,
+		out_result_size: * mut u64,
+		out_report: * mut u8, out_report_len: usize,
+		out_report_size: * mut u64,
+		out_signature: * mut u8, out_signature_len: usize,
+		out_signature_size: * mut u64) -> sgx_status_t;
 }
 
 fn read_byte_code_from_file(byte_code_dir : &str, prog_name : &str, suffix : &str) -> Vec<u8>
@@ -43,17 +78,253 @@ fn read_byte_code_from_file(byte_code_dir : &str, prog_name : &str, suffix : &st
 	byte_code
 }
 
-fn make_encl_func_call(enclave : &SgxEnclave, prog_bytes : &[u8], param_list_bytes : &[u8]) -> sgx_status_t
+fn write_bytes_to_file(byte_code_dir : &str, name : &str, suffix : &str, bytes : &[u8])
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}/{}.{}", byte_code_dir, name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::create(&file_path)
+	{
+		Err(why) => panic!("couldn't create {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	match file.write_all(bytes)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't write to {}: {}", file_path.display(), why),
+	}
+	println!("[App]: Written {} {} bytes.", file_path.display(), bytes.len());
+}
+
+/// Target info of the quoting enclave, so the report of the enclave can be
+/// turned into a quote; empty if the quoting enclave isn't available.
+fn get_qe_target_info() -> Vec<u8>
+{
+	let mut target_info = sgx_target_info_t::default();
+	let mut epid_group_id = sgx_epid_group_id_t::default();
+
+	let result = unsafe { sgx_init_quote(&mut target_info as *mut sgx_target_info_t, &mut epid_group_id as *mut sgx_epid_group_id_t) };
+	if result != sgx_status_t::SGX_SUCCESS
+	{
+		println!("[App]: Quoting enclave unavailable ({}); the report won't target it.", result.as_str());
+		return vec![];
+	}
+
+	let target_info_bytes = unsafe
+	{
+		std::slice::from_raw_parts(&target_info as *const sgx_target_info_t as *const u8, std::mem::size_of::<sgx_target_info_t>())
+	};
+
+	target_info_bytes.to_vec()
+}
+
+/// Outputs of `interpret_byte_code`.
+///
+struct EnclOutput
+{
+	evidence  : [u8; attestation::SGX_REPORT_EVIDENCE_SIZE],
+	/// Result bytes, or the result sealed for the client, if the parameters
+	/// were sealed.
+	result    : Vec<u8>,
+	/// Report body bytes, see `ReportBody::to_bytes`.
+	report    : Vec<u8>,
+	signature : Vec<u8>,
+}
+
+/// How many times the ECALL is made again with the buffer sizes returned by
+/// the enclave.
+const ENCL_FUNC_CALL_MAX_ATTEMPTS : usize = 2;
+
+fn too_large(size : u64, buf : &[u8]) -> bool
+{
+	size as usize > buf.len()
+}
+
+fn make_encl_func_call(
+	enclave : &SgxEnclave, prog_bytes : &[u8], param_list_bytes : &[u8], oblivious : bool, sealed : bool, nonce : &[u8], target_info : &[u8],
+	out : &mut EnclOutput) -> sgx_status_t
 {
 	let mut retval = sgx_status_t::SGX_SUCCESS;
+	let out_evidence_tmp : [u8; attestation::SGX_REPORT_EVIDENCE_SIZE] = [0; attestation::SGX_REPORT_EVIDENCE_SIZE];
+
+	let result_max_size = if sealed { session::SEALED_RESULT_MAX_SIZE } else { interpreter::exp::FUNC_RET_MAX_SIZE };
+	let mut out_result_tmp : Vec<u8> = vec![0; result_max_size];
+	let mut out_report_tmp : Vec<u8> = vec![0; executor::REPORT_BODY_SIZE];
+	let mut out_signature_tmp : Vec<u8> = vec![0; executor::REPORT_SIGNATURE_SIZE];
+	let mut out_result_size : u64 = 0;
+	let mut out_report_size : u64 = 0;
+	let mut out_signature_size : u64 = 0;
+
+	let mut attempt = 0;
+	loop
+	{
+		attempt += 1;
+
+		let result = unsafe {
+			interpret_byte_code(enclave.geteid(),
+			&mut retval,
+			prog_bytes.as_ptr() as * const u8,
+			prog_bytes.len(),
+			param_list_bytes.as_ptr() as * const u8,
+			param_list_bytes.len(),
+			oblivious as u8,
+			sealed as u8,
+			nonce.as_ptr() as * const u8,
+			nonce.len(),
+			target_info.as_ptr() as * const u8,
+			target_info.len(),
+			out_evidence_tmp.as_ptr() as * mut u8,
+			out_evidence_tmp.len(),
+			out_result_tmp.as_mut_ptr() as * mut u8,
+			out_result_tmp.len(),
+			&mut out_result_size as * mut u64,
+			out_report_tmp.as_mut_ptr() as * mut u8,
+			out_report_tmp.len(),
+			&mut out_report_size as * mut u64,
+			out_signature_tmp.as_mut_ptr() as * mut u8,
+			out_signature_tmp.len(),
+			&mut out_signature_size as * mut u64)
+		};
+
+		match result
+		{
+			sgx_status_t::SGX_SUCCESS => {},
+			_ =>
+			{
+				println!("[App]: ECALL Enclave Failed {}!", result.as_str());
+				return result;
+			}
+		};
+
+		// The enclave returns the sizes it needs before executing anything;
+		// buffers too small are grown to those sizes, and the ECALL made again.
+		let too_small =
+			too_large(out_result_size, &out_result_tmp) ||
+			too_large(out_report_size, &out_report_tmp) ||
+			too_large(out_signature_size, &out_signature_tmp);
+
+		if retval == sgx_status_t::SGX_ERROR_INVALID_PARAMETER && too_small && attempt < ENCL_FUNC_CALL_MAX_ATTEMPTS
+		{
+			println!("[App]: Growing the output buffers to {}, {}, and {} byte(s).", out_result_size, out_report_size, out_signature_size);
+
+			out_result_tmp.resize(out_result_size as usize, 0);
+			out_report_tmp.resize(out_report_size as usize, 0);
+			out_signature_tmp.resize(out_signature_size as usize, 0);
+			continue;
+		}
+
+		match retval
+		{
+			sgx_status_t::SGX_SUCCESS => break,
+			_ =>
+			{
+				println!("[App]: ECALL Enclave returned {}!", retval.as_str());
+				return retval;
+			}
+		};
+	}
+
+	if too_large(out_result_size, &out_result_tmp) || too_large(out_report_size, &out_report_tmp) || too_large(out_signature_size, &out_signature_tmp)
+	{
+		println!("[App]: The enclave returned sizes larger than the output buffers.");
+		return sgx_status_t::SGX_ERROR_UNEXPECTED;
+	}
+
+	out_result_tmp.truncate(out_result_size as usize);
+	out_report_tmp.truncate(out_report_size as usize);
+	out_signature_tmp.truncate(out_signature_size as usize);
+
+	out.evidence.copy_from_slice(&out_evidence_tmp);
+	out.result = out_result_tmp;
+	out.report = out_report_tmp;
+	out.signature = out_signature_tmp;
+
+	sgx_status_t::SGX_SUCCESS
+}
+
+/// Run the test functions in the test section of a program on the host, and
+/// report each of them; returns whether all tests passed.
+///
+fn run_unit_tests(prog_bytes : &[u8]) -> bool
+{
+	use enclave_verifier::ast;
+	use enclave_verifier::interpreter;
+
+	let (bytes_left, prog) = match ast::cmd::Cmd::from_bytes(prog_bytes)
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("[App]: Couldn't construct AST from byte code. {}", why),
+	};
+
+	let tests = match ast::test_fn::tests_from_bytes(bytes_left)
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("[App]: Couldn't parse the test section. {}", why),
+	};
+
+	println!("[App]: Running {} test(s).", tests.len());
+
+	let results = interpreter::unit_test::run_tests(&prog, &tests);
+	for result in results.iter()
+	{
+		println!("[App]: {}", result);
+	}
+
+	let num_failed = results.iter().filter(|r| !r.passed()).count();
+	println!("[App]: {} passed; {} failed.", results.len() - num_failed, num_failed);
+
+	num_failed == 0
+}
+
+/// The seal policy of the identity of the enclave, given by the environment
+/// variable `SEAL_POLICY`, i.e. `mrenclave` (default) or `mrsigner`.
+fn get_seal_policy() -> SealPolicy
+{
+	match env::var("SEAL_POLICY")
+	{
+		Ok(v)  => match SealPolicy::parse(&v)
+		{
+			Ok(p)    => p,
+			Err(why) => panic!("[App]: {}", why),
+		},
+		Err(_) => SealPolicy::MrEnclave,
+	}
+}
+
+/// Hand the sealed identity of the enclave in the store, if any, to the
+/// enclave, which unseals it, or generates a new one; then store the sealed
+/// identity the enclave returns.
+fn init_enclave_identity(enclave : &SgxEnclave, store : &SealedStore, name : &str, policy : SealPolicy) -> sgx_status_t
+{
+	let sealed_in = match store.load(name)
+	{
+		Ok(Some(v)) => v,
+		Ok(None)    =>
+		{
+			println!("[App]: No sealed identity {} stored; the enclave will generate one.", name);
+			vec![]
+		},
+		Err(why)    => panic!("[App]: {}", why),
+	};
+
+	let mut retval = sgx_status_t::SGX_ERROR_UNEXPECTED;
+	let mut sealed_out : Vec<u8> = vec![0; identity::SEALED_IDENTITY_MAX_SIZE];
+	let mut sealed_written : u64 = 0;
 
 	let result = unsafe {
-		interpret_byte_code(enclave.geteid(),
+		init_identity(enclave.geteid(),
 		&mut retval,
-		prog_bytes.as_ptr() as * const u8,
-		prog_bytes.len(),
-		param_list_bytes.as_ptr() as * const u8,
-		param_list_bytes.len())
+		sealed_in.as_ptr() as * const u8,
+		sealed_in.len(),
+		policy.to_byte(),
+		sealed_out.as_mut_ptr() as * mut u8,
+		sealed_out.len(),
+		&mut sealed_written as * mut u64)
 	};
 
 	match result
@@ -71,12 +342,60 @@ fn make_encl_func_call(enclave : &SgxEnclave, prog_bytes : &[u8], param_list_byt
 		sgx_status_t::SGX_SUCCESS => {},
 		_ =>
 		{
-			println!("[App]: ECALL Enclave returned {}!", retval.as_str());
+			println!("[App]: ECALL Enclave Failed {}!", retval.as_str());
 			return retval;
 		}
 	};
 
-	sgx_status_t::SGX_SUCCESS
+	sealed_out.truncate(sealed_written as usize);
+
+	match identity::sealed_public_key(&sealed_out)
+	{
+		Ok((p, pkey)) => println!("[App]: Enclave identity {}, sealed with policy {}.", host_verify::encode_b64_pair(&pkey), p),
+		Err(why)      => panic!("[App]: Invalid sealed identity from the enclave. {}", why),
+	}
+
+	if let Err(why) = store.store(name, &sealed_out)
+	{
+		panic!("[App]: {}", why);
+	}
+
+	result
+}
+
+/// Provision the enclave with the allowlist of type-checkers it trusts.
+fn do_provision_checkers(enclave : &SgxEnclave, allowlist : &[u8]) -> sgx_status_t
+{
+	let mut retval = sgx_status_t::SGX_ERROR_UNEXPECTED;
+
+	let result = unsafe {
+		provision_checkers(enclave.geteid(),
+		&mut retval,
+		allowlist.as_ptr() as * const u8,
+		allowlist.len())
+	};
+
+	match result
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_ =>
+		{
+			println!("[App]: ECALL Enclave Failed {}!", result.as_str());
+			return result;
+		}
+	};
+
+	match retval
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_ =>
+		{
+			println!("[App]: ECALL Enclave Failed {}!", retval.as_str());
+			return retval;
+		}
+	};
+
+	result
 }
 
 fn init_enclave() -> SgxResult<SgxEnclave>
@@ -99,15 +418,52 @@ fn main()
 	let byte_code_dir : &'static str = "../../../rs-sources";
 
 	let args : Vec<String> = env::args().collect();
-	if args.len() != 3
+
+	// `app test <prog>` runs the tests of the (unsigned) program on the host.
+	if args.len() == 3 && args[1] == "test"
+	{
+		let example_prog_bytes = read_byte_code_from_file(byte_code_dir, &args[2], "impc");
+		if !run_unit_tests(&example_prog_bytes)
+		{
+			std::process::exit(1);
+		}
+		return;
+	}
+
+	if args.len() < 3 || args.len() > 5
 	{
 		panic!("[App]: Incorrect number of arguments provided.")
 	}
 
 	let example_prog_name = &args[1];
 	let example_param_name = &args[2];
+
+	// With `sealed`, the parameters are the ones sealed by the client with
+	// `verify seal`, and the result is sealed for the client.
+	let (mut oblivious, mut sealed) = (false, false);
+	for mode in args[3..].iter()
+	{
+		match mode.as_str()
+		{
+			"oblivious" => oblivious = true,
+			"sealed"    => sealed = true,
+			s           => panic!("[App]: Unknown execution mode {}.", s),
+		}
+	}
+
 	let example_prog_bytes = read_byte_code_from_file(byte_code_dir, example_prog_name, "vimpc");
-	let example_param_bytes = read_byte_code_from_file(byte_code_dir, example_param_name, "param");
+	let example_param_bytes = read_byte_code_from_file(byte_code_dir, example_param_name, if sealed { "sparam" } else { "param" });
+
+	// The nonce of the client, e.g. written by `verify nonce`.
+	let example_nonce_bytes = read_byte_code_from_file(byte_code_dir, example_param_name, "nonce");
+
+	// The type-checkers the enclave trusts, e.g. written by `verify allowlist`.
+	let checker_allowlist = read_byte_code_from_file(byte_code_dir, "checkers", "allow");
+	match CheckerAllowlist::from_bytes(&checker_allowlist)
+	{
+		Ok((_, v)) => println!("[App]: Read {}.", v),
+		Err(why)   => panic!("[App]: Invalid allowlist of type-checkers. {}", why),
+	}
 
 	println!("[App]: Read bytecode file ({} byte(s)).", example_prog_bytes.len());
 
@@ -122,7 +478,62 @@ fn main()
 		},
 	};
 
-	make_encl_func_call(&enclave, &example_prog_bytes, &example_param_bytes);
+	let store = SealedStore::new(SEALED_DIR);
+	match init_enclave_identity(&enclave, &store, "interpreter", get_seal_policy())
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_                         =>
+		{
+			enclave.destroy();
+			return;
+		},
+	}
+
+	match do_provision_checkers(&enclave, &checker_allowlist)
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_                         =>
+		{
+			enclave.destroy();
+			return;
+		},
+	}
+
+	let target_info = get_qe_target_info();
+
+	let mut output = EnclOutput
+	{
+		evidence  : [0; attestation::SGX_REPORT_EVIDENCE_SIZE],
+		result    : Vec::new(),
+		report    : Vec::new(),
+		signature : Vec::new(),
+	};
+	match make_encl_func_call(&enclave, &example_prog_bytes, &example_param_bytes, oblivious, sealed, &example_nonce_bytes, &target_info, &mut output)
+	{
+		sgx_status_t::SGX_SUCCESS =>
+		{
+			match ReportBody::from_bytes(&output.report)
+			{
+				Ok((_, body)) => println!("[App]: Report number {}.", body.freshness.counter),
+				Err(why)      => panic!("[App]: Invalid report from the enclave. {}", why),
+			}
+
+			// The evidence binds the key of the report signature to the enclave.
+			match Evidence::from_bytes(&output.evidence)
+			{
+				Ok((_, evidence)) => println!("[App]: Received {}.", evidence),
+				Err(why)          => panic!("[App]: Invalid evidence from the enclave. {}", why),
+			}
+
+			// Verifiers check these with `verify output`; only the client can
+			// open a sealed result.
+			write_bytes_to_file(byte_code_dir, example_param_name, if sealed { "sresult" } else { "result" }, &output.result);
+			write_bytes_to_file(byte_code_dir, example_param_name, "report", &output.report);
+			write_bytes_to_file(byte_code_dir, example_param_name, "sig", &output.signature);
+			write_bytes_to_file(byte_code_dir, example_param_name, "evidence", &output.evidence);
+		},
+		_                         => {},
+	}
 
 	enclave.destroy();
 }
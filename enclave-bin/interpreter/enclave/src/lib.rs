@@ -5,25 +5,29 @@
 #![cfg_attr(target_env = "sgx", feature(rustc_private))]
 
 extern crate sgx_types;
-extern crate sgx_tcrypto;
 extern crate sgx_trts;
 #[cfg(not(target_env = "sgx"))]
 #[macro_use]
 extern crate sgx_tstd as std;
 
 use std::vec::Vec;
-use std::string::String;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 
 use sgx_types::*;
-use sgx_tcrypto::*;
 
 extern crate base64;
 
 extern crate enclave_verifier;
 
-use enclave_verifier::ast;
-use enclave_verifier::ast::Deserializible;
+use enclave_verifier::enclave;
+use enclave_verifier::enclave::EnclaveBackend;
+use enclave_verifier::enclave::identity;
+use enclave_verifier::enclave::allowlist;
+use enclave_verifier::attestation;
 use enclave_verifier::interpreter;
+use enclave_verifier::session;
+use enclave_verifier::ast::Serializible;
 
 pub fn concat_vec<T>(mut a : Vec<T>, mut b : Vec<T>) -> Vec<T>
 {
@@ -31,329 +35,356 @@ pub fn concat_vec<T>(mut a : Vec<T>, mut b : Vec<T>) -> Vec<T>
 	a
 }
 
-pub fn func_call_res_to_bytes(res : &Option<interpreter::exp::ExpValue>) -> Result<Vec<u8>, String>
+/// Identity of this enclave instance, set by `init_identity`.
+static IDENTITY : identity::IdentitySlot = identity::IdentitySlot::new();
+
+#[no_mangle]
+pub extern "C" fn init_identity(
+	sealed_identity: *const u8, sealed_identity_len: usize,
+	seal_policy: u8,
+	out_sealed_identity: * mut u8, out_sealed_identity_len: usize,
+	out_sealed_identity_written: * mut u64) -> sgx_status_t
 {
-	let mut res_vec : Vec<u8> = Vec::new();
+	// ------------------------------------------
+	// 1. Process input parameters:
+	// ------------------------------------------
+	println!("");
 
-	match res
+	let policy = match identity::SealPolicy::from_byte(seal_policy)
 	{
-		Option::Some(v) =>
-		{
-			res_vec.push(1u8);
-			res_vec.append(&mut (v.to_bytes()?));
-		},
-		Option::None    =>
+		Ok(v)    => v,
+		Err(why) =>
 		{
-			res_vec.push(0u8);
-		},
-	}
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+		}
+	};
 
-	Result::Ok(res_vec)
-}
+	// A new identity is generated if no sealed identity is given.
+	let sealed_slice = if sealed_identity.is_null() { &[] } else { unsafe { std::slice::from_raw_parts(sealed_identity, sealed_identity_len) } };
 
-fn read_pkey_from_bytes(bytes : &[u8]) -> Result<(&[u8], sgx_types::sgx_ec256_public_t), String>
-{
-	if bytes.len() < 2 * 32
+	// ------------------------------------------
+	// 2. Unseal, or generate, the identity:
+	// ------------------------------------------
+	println!("");
+
+	let generate = || match enclave::sgx::SgxBackend::new()
 	{
-		return Result::Err(format!("The bytes given is smaller than the size of a EC256 public key."));
-	}
+		Ok(v)    => Ok(v.identity()),
+		Err(err) => Err(format!("Failed to generate the identity key. {}", err.as_str())),
+	};
 
-	let mut pkey : sgx_types::sgx_ec256_public_t = sgx_types::sgx_ec256_public_t{ gx : [0; 32], gy : [0; 32] };
+	let (encl_identity, sealed_bytes) = match identity::load_or_generate(&enclave::sgx::SgxSealer, sealed_slice, policy, generate)
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED;
+		}
+	};
 
-	pkey.gx.copy_from_slice(&bytes[0..32]);
-	pkey.gy.copy_from_slice(&bytes[32..64]);
+	if sealed_bytes.len() > out_sealed_identity_len
+	{
+		println!("[Enclave-ERROR]: The sealed identity buffer has {} byte(s) instead of {}.", out_sealed_identity_len, sealed_bytes.len());
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
 
-	Result::Ok((&bytes[64..], pkey))
-}
+	println!("[Enclave]: Identity public key {}{}, sealed with policy {}.",
+		base64::encode(&encl_identity.pub_key[0..32]), base64::encode(&encl_identity.pub_key[32..64]), policy);
 
-fn read_signature_from_bytes(bytes : &[u8]) -> Result<(&[u8], sgx_types::sgx_ec256_signature_t), String>
-{
-	if bytes.len() < 2 * 32
+	// ------------------------------------------
+	// 3. Keep the identity, and output it sealed:
+	// ------------------------------------------
+	println!("");
+
+	if let Err(why) = IDENTITY.set(encl_identity)
 	{
-		return Result::Err(format!("The bytes given is smaller than the size of a EC256 signature."));
+		println!("[Enclave-ERROR]: {}", why);
+		return sgx_status_t::SGX_ERROR_INVALID_STATE;
 	}
 
-	let mut sign : sgx_types::sgx_ec256_signature_t = sgx_types::sgx_ec256_signature_t{ x : [0; 8], y : [0; 8] };
+	let out_sealed_slice = unsafe { std::slice::from_raw_parts_mut(out_sealed_identity, sealed_bytes.len()) };
+	out_sealed_slice.copy_from_slice(&sealed_bytes);
 
-	let sign_x_bytes: &mut [u8; 32] = unsafe { std::mem::transmute::<&mut [u32; 8], &mut [u8; 32]>(&mut sign.x) };
-	let sign_y_bytes: &mut [u8; 32] = unsafe { std::mem::transmute::<&mut [u32; 8], &mut [u8; 32]>(&mut sign.y) };
+	unsafe { *out_sealed_identity_written = sealed_bytes.len() as u64; }
 
-	sign_x_bytes.copy_from_slice(&bytes[0..32]);
-	sign_y_bytes.copy_from_slice(&bytes[32..64]);
-
-	Result::Ok((&bytes[64..], sign))
+	sgx_status_t::SGX_SUCCESS
 }
 
+/// Type-checkers trusted by this enclave instance, set by `provision_checkers`.
+static CHECKERS : allowlist::CheckersSlot = allowlist::CheckersSlot::new();
+
 #[no_mangle]
-pub extern "C" fn interpret_byte_code(byte_code: *const u8, byte_code_len: usize, param_list: *const u8, param_list_len: usize) -> sgx_status_t
+pub extern "C" fn provision_checkers(
+	checker_allowlist: *const u8, checker_allowlist_len: usize) -> sgx_status_t
 {
 	// ------------------------------------------
-	// 1. Generate EC key pair:
+	// 1. Process input parameters:
 	// ------------------------------------------
 	println!("");
 
-	let ecc_ctx : sgx_tcrypto::SgxEccHandle = sgx_tcrypto::SgxEccHandle::new();
+	let allowlist_slice = unsafe { std::slice::from_raw_parts(checker_allowlist, checker_allowlist_len) };
 
-	match ecc_ctx.open()
-	{
-		Result::Ok(_)    => {},
-		Result::Err(err) => { return err; },
-	};
+	println!("[Enclave]: Received type-checker allowlist ({} byte(s)).", allowlist_slice.len());
 
-	let (encl_prv_key, encl_pub_key) = match ecc_ctx.create_key_pair()
+	// Only the hash, and the evidence checks, are done with the backend.
+	let backend = match enclave::sgx::SgxBackend::new()
 	{
 		Result::Ok(val)  => val,
 		Result::Err(err) => { return err; },
 	};
 
-	println!("[Enclave]: Enclave public key {}{}.", base64::encode(&encl_pub_key.gx), base64::encode(&encl_pub_key.gy));
-
 	// ------------------------------------------
-	// 2. Process input bytes:
+	// 2. Check the evidence, and keep the trusted keys:
 	// ------------------------------------------
 	println!("");
 
-	let input_slice = unsafe { std::slice::from_raw_parts(byte_code, byte_code_len) };
-	let param_list_input_slice = unsafe { std::slice::from_raw_parts(param_list, param_list_len) };
-
-	println!("[Enclave]: Received input ({} byte(s)).", input_slice.len());
-
-	let (input_bytes_left_1, example_prog) = match ast::cmd::Cmd::from_bytes(input_slice)
+	let checkers = match allowlist::TrustedCheckers::provision(&backend, allowlist_slice)
 	{
-		Ok(v)    => v,
-		Err(why) =>
+		Result::Ok(val)  => val,
+		Result::Err(why) =>
 		{
-			println!("[Enclave-ERROR]: Couldn't construct AST from byte code. {}", why);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		}
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+		},
 	};
 
-	let byte_code_slice = &input_slice[0..(input_slice.len() - input_bytes_left_1.len())];
+	println!("[Enclave]: Provisioned {}; allowlist hash {}.", checkers, base64::encode(checkers.allowlist_hash()));
 
-	println!("[Enclave]: Received bytecode ({} byte(s)).", byte_code_slice.len());
-
-	let byte_code_hash = match rsgx_sha256_slice(&byte_code_slice)
+	if let Result::Err(why) = CHECKERS.set(checkers)
 	{
-		Ok(val)  => val,
-		Err(err) => return err,
-	};
+		println!("[Enclave-ERROR]: {}", why);
+		return sgx_status_t::SGX_ERROR_INVALID_STATE;
+	}
+
+	sgx_status_t::SGX_SUCCESS
+}
 
-	println!("[Enclave]: Bytecode hash SHA256(byte_code): {}.", base64::encode(&byte_code_hash));
+/// Number of the next execution report of this enclave instance.
+static REPORT_COUNTER : AtomicU64 = AtomicU64::new(0);
 
+#[no_mangle]
+pub extern "C" fn interpret_byte_code(
+	byte_code: *const u8, byte_code_len: usize,
+	param_list: *const u8, param_list_len: usize,
+	oblivious: u8,
+	sealed: u8,
+	nonce: *const u8, nonce_len: usize,
+	target_info: *const u8, target_info_len: usize,
+	out_evidence: * mut u8, out_evidence_len: usize,
+	out_result: * mut u8, out_result_len: usize,
+	out_result_size: * mut u64,
+	out_report: * mut u8, out_report_len: usize,
+	out_report_size: * mut u64,
+	out_signature: * mut u8, out_signature_len: usize,
+	out_signature_size: * mut u64) -> sgx_status_t
+{
 	// ------------------------------------------
-	// 3. Read verifier's public key:
+	// 1. Load the identity, or generate an EC key pair:
 	// ------------------------------------------
 	println!("");
 
-	let (input_bytes_left_2, verifier_pkey) = match read_pkey_from_bytes(input_bytes_left_1)
+	// Sealed parameters are sealed for the key of the identity; the key of
+	// a backend without identity is one no client could have sealed for.
+	if sealed != 0 && IDENTITY.get().is_none()
 	{
-		Ok(v)    => v,
-		Err(why) =>
-		{
-			println!("[Enclave-ERROR]: {}", why);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		}
+		println!("[Enclave-ERROR]: {}", "The identity is not initialised; init_identity must be called before sealed executions.");
+		return sgx_status_t::SGX_ERROR_INVALID_STATE;
+	}
+
+	// Signatures are only by a long-lived key once `init_identity` is called.
+	let backend = match IDENTITY.get()
+	{
+		Some(v) => enclave::sgx::SgxBackend::from_identity(v),
+		None    => enclave::sgx::SgxBackend::new(),
 	};
 
-	println!("[Enclave]: Verifier's public key {}{}.", base64::encode(&verifier_pkey.gx), base64::encode(&verifier_pkey.gy));
+	let backend = match backend
+	{
+		Result::Ok(val)  => val,
+		Result::Err(err) => { return err; },
+	};
 
-	// ------------------------------------------
-	// 4. Read verifier's signature:
-	// ------------------------------------------
-	println!("");
+	let target_info_slice = if target_info.is_null() { &[] } else { unsafe { std::slice::from_raw_parts(target_info, target_info_len) } };
 
-	let (_input_bytes_left_3, verifier_sign) = match read_signature_from_bytes(input_bytes_left_2)
+	let backend = match backend.with_target_info_bytes(target_info_slice)
 	{
-		Ok(v)    => v,
-		Err(why) =>
+		Result::Ok(val)  => val,
+		Result::Err(why) =>
 		{
 			println!("[Enclave-ERROR]: {}", why);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		}
+			return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+		},
 	};
 
-	let verifier_sign_x: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(&verifier_sign.x) };
-	let verifier_sign_y: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(&verifier_sign.y) };
+	let encl_pub_key = backend.public_key();
 
-	println!("[Enclave]: Verifier's signature {}{}.", base64::encode(verifier_sign_x), base64::encode(verifier_sign_y));
+	println!("[Enclave]: Enclave public key {}{}.", base64::encode(&encl_pub_key[0..32]), base64::encode(&encl_pub_key[32..64]));
 
 	// ------------------------------------------
-	// 5. Verify verifier's signature:
+	// 2. Process input bytes:
 	// ------------------------------------------
 	println!("");
 
-	let sign_vrfy_res = match ecc_ctx.ecdsa_verify_msg(&byte_code_hash, &verifier_pkey, &verifier_sign)
-	{
-		Ok(val)  => val,
-		Err(err) => return err,
-	};
+	let input_slice = unsafe { std::slice::from_raw_parts(byte_code, byte_code_len) };
+	let param_list_input_slice = unsafe { std::slice::from_raw_parts(param_list, param_list_len) };
+
+	println!("[Enclave]: Received input ({} byte(s)).", input_slice.len());
 
-	if !sign_vrfy_res
+	// The nonce of the client makes the report fresh.
+	if nonce_len != enclave::executor::NONCE_SIZE
 	{
-		println!("[Enclave-ERROR]: {}", "Failed to verify the signature from verifier.");
-		return sgx_status_t::SGX_ERROR_UNEXPECTED;
+		println!("[Enclave-ERROR]: The nonce has {} byte(s) instead of {}.", nonce_len, enclave::executor::NONCE_SIZE);
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
 	}
 
-	println!("[Enclave]: {}", "Signature from verifier is checked.");
-
+	let mut nonce_bytes : [u8; enclave::executor::NONCE_SIZE] = [0; enclave::executor::NONCE_SIZE];
+	nonce_bytes.copy_from_slice(unsafe { std::slice::from_raw_parts(nonce, nonce_len) });
 
-	// ------------------------------------------
-	// 6. Prepare entry function call from input bytes:
-	// ------------------------------------------
-	println!("");
+	println!("[Enclave]: Received nonce {}.", base64::encode(&nonce_bytes));
 
-	let (param_list_bytes_left, param_list) = match Vec::from_bytes(param_list_input_slice)
+	let checkers = match CHECKERS.get()
 	{
-		Result::Ok(val)  => val,
-		Result::Err(why) =>
+		Option::Some(val) => val,
+		Option::None      =>
 		{
-			println!("[Enclave-ERROR]: {}", why);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		}
+			println!("[Enclave-ERROR]: {}", "No type-checker is trusted before provision_checkers is called.");
+			return sgx_status_t::SGX_ERROR_INVALID_STATE;
+		},
 	};
 
-	let param_list_slice = &param_list_input_slice[0..(param_list_input_slice.len() - param_list_bytes_left.len())];
-
-	let param_list_hash = match rsgx_sha256_slice(&param_list_slice)
+	if out_evidence_len != attestation::SGX_REPORT_EVIDENCE_SIZE
 	{
-		Ok(val)  => val,
-		Err(err) => return err,
-	};
+		println!("[Enclave-ERROR]: The evidence buffer has {} byte(s) instead of {}.", out_evidence_len, attestation::SGX_REPORT_EVIDENCE_SIZE);
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
 
-	println!("[Enclave]: Parameter list hash SHA256(param_list): {}.", base64::encode(&param_list_hash));
+	// The sizes the outputs may take are returned first, so a host with too
+	// small buffers learns the sizes to call again with, before anything is
+	// executed, or a report number is taken.
+	let result_max_size = if sealed != 0 { session::SEALED_RESULT_MAX_SIZE } else { interpreter::exp::FUNC_RET_MAX_SIZE };
 
-	let entry_call = ast::func_general::FnCall::new(format!("entry"), param_list);
+	unsafe
+	{
+		*out_result_size = result_max_size as u64;
+		*out_report_size = enclave::executor::REPORT_BODY_SIZE as u64;
+		*out_signature_size = enclave::executor::REPORT_SIGNATURE_SIZE as u64;
+	}
 
+	if out_result_len < result_max_size ||
+		out_report_len < enclave::executor::REPORT_BODY_SIZE ||
+		out_signature_len < enclave::executor::REPORT_SIGNATURE_SIZE
+	{
+		println!("[Enclave-ERROR]: The output buffers have {}, {}, and {} byte(s), instead of {}, {}, and {}.",
+			out_result_len, out_report_len, out_signature_len,
+			result_max_size, enclave::executor::REPORT_BODY_SIZE, enclave::executor::REPORT_SIGNATURE_SIZE);
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
 
 	// ------------------------------------------
-	// 7. Generate program states:
+	// 3. Check the certificate, and execute the entry call:
 	// ------------------------------------------
 	println!("");
 
-	let mut prog_inter = interpreter::Program::new();
-
-	match gen_prog_states(&mut prog_inter, &example_prog)
+	// Sealed parameters are opened with the key of the backend, and the
+	// result is sealed for their client.
+	let execution = if sealed != 0
 	{
-		Result::Ok(_)    => {},
-		Result::Err(why) =>
-		{
-			println!("[Enclave-ERROR]: {}", why);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		}
+		enclave::executor::execute_program_in_session(&backend, checkers, input_slice, param_list_input_slice, oblivious != 0, &nonce_bytes, &REPORT_COUNTER)
+			.map(|e| (e.report, e.sealed_result))
+	}
+	else
+	{
+		enclave::executor::execute_program(&backend, checkers, input_slice, param_list_input_slice, oblivious != 0, &nonce_bytes, &REPORT_COUNTER)
+			.and_then(|r| interpreter::exp::func_ret_to_bytes(&r.func_ret).map(|b| (r, b)))
 	};
 
-	println!("[Enclave]:");
-	println!("========================================================");
-	println!("Program global states:");
-	println!("----------------------");
-	println!("{}", prog_inter.func_states);
-	println!("{}", prog_inter.var_states.borrow());
-	println!("========================================================");
-
-
-	// ------------------------------------------
-	// 8. Make entry function call:
-	// ------------------------------------------
-	println!("");
-
-	let func_call_res = match make_entry_call(&prog_inter, &entry_call)
+	let (report, result_bytes) = match execution
 	{
-		Result::Ok(ok_val)  => match &ok_val
-		{
-			Option::Some(v) =>
-			{
-				println!("[Enclave]: Function call {} returned {}", entry_call, v);
-				ok_val
-			},
-			Option::None    =>
-			{
-				println!("[Enclave]: Function call {} didn't return any value.", entry_call);
-				ok_val
-			}
-		},
-		Result::Err(why)    =>
+		Result::Ok(val)  => val,
+		Result::Err(why) =>
 		{
 			println!("[Enclave-ERROR]: {}", why);
 			return sgx_status_t::SGX_ERROR_UNEXPECTED;
 		},
 	};
 
-	let func_call_res_bytes = match func_call_res_to_bytes(&func_call_res)
+	let report_bytes = match report.body().to_bytes()
 	{
-		Result::Ok(ok_val)  => ok_val,
-		Result::Err(why)    =>
+		Result::Ok(val)  => val,
+		Result::Err(why) =>
 		{
 			println!("[Enclave-ERROR]: {}", why);
 			return sgx_status_t::SGX_ERROR_UNEXPECTED;
 		},
 	};
 
-	let func_call_res_hash = match rsgx_sha256_slice(&func_call_res_bytes)
-	{
-		Ok(val)  => val,
-		Err(err) => return err,
-	};
-
-	println!("[Enclave]: Entry call result hash SHA256(func_ret): {}.", base64::encode(&func_call_res_hash));
-
-
 	// ------------------------------------------
-	// 9. Generate report:
+	// 4. Output report:
 	// ------------------------------------------
 	println!("");
 
-	println!("[Enclave]: <{}> --- <{}> ---> <{}>.", base64::encode(&param_list_hash), base64::encode(&byte_code_hash), base64::encode(&func_call_res_hash));
+	println!("[Enclave]: Bytecode hash SHA256(byte_code): {}.", base64::encode(&report.byte_code_hash));
+	println!("[Enclave]: Parameter list hash SHA256(param_list): {}.", base64::encode(&report.param_list_hash));
+	println!("[Enclave]: Entry call result hash SHA256(func_ret): {}.", base64::encode(&report.func_ret_hash));
+	println!("[Enclave]: Allowlist hash SHA256(allowlist): {}.", base64::encode(&report.allowlist_hash));
+	println!("[Enclave]: Report number {}.", report.freshness.counter);
 
-	let mut combined_bytes : Vec<u8> = Vec::new();
+	println!("[Enclave]: <{}> --- <{}> ---> <{}>.", base64::encode(&report.param_list_hash), base64::encode(&report.byte_code_hash), base64::encode(&report.func_ret_hash));
 
-	combined_bytes.append(&mut param_list_hash.to_vec());
-	combined_bytes.append(&mut byte_code_hash.to_vec());
-	combined_bytes.append(&mut func_call_res_hash.to_vec());
+	println!("[Enclave]: report hash SHA256(SHA256(param_list) | SHA256(byte_code) | SHA256(func_ret) | SHA256(allowlist) | nonce | counter): {}.", base64::encode(&report.report_hash));
 
-	let combined_bytes_hash = match rsgx_sha256_slice(&combined_bytes)
-	{
-		Ok(val)  => val,
-		Err(err) => return err,
-	};
+	println!("[Enclave]: report signature: {}{}.", base64::encode(&report.signature[0..32]), base64::encode(&report.signature[32..64]));
 
-	println!("[Enclave]: report hash SHA256(SHA256(param_list) | SHA256(byte_code) | SHA256(func_ret)): {}.", base64::encode(&combined_bytes_hash));
+	// ------------------------------------------
+	// 5. Output evidence for the enclave key:
+	// ------------------------------------------
+	println!("");
 
-	let sign = match ecc_ctx.ecdsa_sign_slice(&combined_bytes_hash, &encl_prv_key)
+	let evidence_bytes = match attestation::gen_evidence(&backend).and_then(|e| e.to_bytes())
 	{
-		Ok(val)  => val,
-		Err(err) => return err,
+		Result::Ok(val)  => val,
+		Result::Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED;
+		},
 	};
 
-	let sign_x: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(&sign.x) };
-	let sign_y: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(&sign.y) };
-
-	println!("[Enclave]: report signature: {}{}.", base64::encode(&sign_x), base64::encode(&sign_y));
+	println!("[Enclave]: Generated evidence ({} byte(s)).", evidence_bytes.len());
 
-	sgx_status_t::SGX_SUCCESS
-}
+	let out_evidence_slice = unsafe { std::slice::from_raw_parts_mut(out_evidence, out_evidence_len) };
+	out_evidence_slice.copy_from_slice(&evidence_bytes);
 
-pub fn gen_prog_states(prog : &mut interpreter::Program, prog_cmd : &ast::cmd::Cmd) -> Result<(), String>
-{
-	use interpreter::cmd::CanEvalToExpVal;
+	// ------------------------------------------
+	// 6. Output the result, the report, and its signature:
+	// ------------------------------------------
+	println!("");
 
-	let prog_root_res = match prog_cmd.eval_to_exp_val(&mut prog.func_states, &mut prog.var_states)
+	if result_bytes.len() > out_result_len || report_bytes.len() > out_report_len
 	{
-		Result::Ok(ok_v) => ok_v,
-		Result::Err(why) => panic!("{}", why)
-	};
+		println!("[Enclave-ERROR]: The result, or the report, is larger than its output buffer.");
+		return sgx_status_t::SGX_ERROR_UNEXPECTED;
+	}
+
+	let out_result_slice = unsafe { std::slice::from_raw_parts_mut(out_result, result_bytes.len()) };
+	out_result_slice.copy_from_slice(&result_bytes);
+
+	let out_report_slice = unsafe { std::slice::from_raw_parts_mut(out_report, report_bytes.len()) };
+	out_report_slice.copy_from_slice(&report_bytes);
 
-	match prog_root_res
+	let out_signature_slice = unsafe { std::slice::from_raw_parts_mut(out_signature, report.signature.len()) };
+	out_signature_slice.copy_from_slice(&report.signature);
+
+	unsafe
 	{
-		Option::Some(v) => match v
-		{
-			Option::Some(v2) => Result::Err(format!("Program root shouldn't contain return statement; it returned {}.", v2)),
-			Option::None     => Result::Err(format!("Program root shouldn't contain return statement; even it's a void return.")),
-		},
-		Option::None    => Result::Ok(()),
+		*out_result_size = result_bytes.len() as u64;
+		*out_report_size = report_bytes.len() as u64;
+		*out_signature_size = report.signature.len() as u64;
 	}
-}
 
-pub fn make_entry_call(prog : &interpreter::Program, entry_call : &ast::func_general::FnCall) -> Result<Option<interpreter::exp::ExpValue>, String>
-{
-	interpreter::states::func_call(&prog.func_states, &prog.var_states, entry_call, false)
+	println!("[Enclave]: Output result ({} byte(s)), report ({} byte(s)), and signature.", result_bytes.len(), report_bytes.len());
+
+	sgx_status_t::SGX_SUCCESS
 }
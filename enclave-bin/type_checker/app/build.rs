@@ -33,4 +33,10 @@ fn main () {
         "HW" => println!("cargo:rustc-link-lib=dylib=sgx_urts"),
         _    => println!("cargo:rustc-link-lib=dylib=sgx_urts"), // Treat undefined as HW
     }
+
+    // sgx_init_quote, for the target info of the quoting enclave.
+    match is_sim.as_ref() {
+        "SW" => println!("cargo:rustc-link-lib=dylib=sgx_uae_service_sim"),
+        _    => println!("cargo:rustc-link-lib=dylib=sgx_uae_service"),
+    }
 }
@@ -5,23 +5,52 @@ use sgx_urts::SgxEnclave;
 
 use std::env;
 
+extern crate enclave_verifier;
+use enclave_verifier::type_checker::policy::CheckerPolicy;
+use enclave_verifier::certificate;
+use enclave_verifier::certificate::Certificate;
+use enclave_verifier::attestation;
+use enclave_verifier::attestation::Evidence;
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::analyzer::interval::ParamRanges;
+use enclave_verifier::host_verify;
+use enclave_verifier::enclave::identity;
+use enclave_verifier::enclave::identity::SealPolicy;
+use enclave_verifier::sealed_store::SealedStore;
+
 static ENCLAVE_FILE: &'static str = "enclave.signed.so";
 
+/// Directory of the sealed identity of the enclave.
+static SEALED_DIR: &'static str = "sealed";
+
 extern {
+	fn init_identity(eid: sgx_enclave_id_t, retval: *mut sgx_status_t,
+		sealed_identity: *const u8, sealed_identity_len: usize,
+		seal_policy: u8,
+		out_sealed_identity: * mut u8, out_sealed_identity_len: usize,
+		out_sealed_identity_written: * mut u64) -> sgx_status_t;
+
 	fn type_check_byte_code(eid: sgx_enclave_id_t, retval: *mut sgx_status_t,
 		byte_code: *const u8, byte_code_len: usize,
+		policy_flags: u32,
+		param_ranges: *const u8, param_ranges_len: usize,
+		interpreter_evidence: *const u8, interpreter_evidence_len: usize,
+		target_info: *const u8, target_info_len: usize,
 		out_bytes_read: * mut u64,
-		out_pkey_x: * mut u8, out_pkey_y: * mut u8,
-		out_sign_x: * mut u32, out_sign_y: * mut u32) -> sgx_status_t;
+		out_cert: * mut u8, out_cert_len: usize,
+		out_evidence: * mut u8, out_evidence_len: usize,
+		out_sealed_byte_code: * mut u8, out_sealed_byte_code_len: usize,
+		out_sealed_byte_code_size: * mut u64) -> sgx_status_t;
 }
 
-fn read_byte_code_from_file(byte_code_dir : &str, prog_name : &str) -> Vec<u8>
+fn read_byte_code_from_file(byte_code_dir : &str, prog_name : &str, suffix : &str) -> Vec<u8>
 {
 	use std::fs::File;
 	use std::path::Path;
 	use std::io::prelude::*;
 
-	let file_path_string = format!("{}/{}.{}", byte_code_dir, prog_name, "impc");
+	let file_path_string = format!("{}/{}.{}", byte_code_dir, prog_name, suffix);
 	let file_path = Path::new(&file_path_string);
 
 	let mut file = match File::open(&file_path)
@@ -38,12 +67,69 @@ fn read_byte_code_from_file(byte_code_dir : &str, prog_name : &str) -> Vec<u8>
 		Err(why) => panic!("[App]: couldn't read from {}: {}", file_path.display(), why),
 	}
 
-	println!("[App]: Bytecode file read {} bytes total for program {}.", byte_code.len(), prog_name);
+	println!("[App]: File {} read {} bytes total.", file_path.display(), byte_code.len());
 
 	byte_code
 }
 
-fn write_verified_byte_code(byte_code_dir : &str, prog_name : &str, code : &[u8], pkey_x : &[u8; 32], pkey_y : &[u8; 32], sign_x : &[u32; 8], sign_y : &[u32; 8])
+/// Read the ranges of entry's parameters from `<prog_name>.range`, if the file exists.
+fn read_param_ranges_from_file(byte_code_dir : &str, prog_name : &str) -> Vec<u8>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}/{}.{}", byte_code_dir, prog_name, "range");
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(&file_path)
+	{
+		Err(_)   =>
+		{
+			println!("[App]: No parameter ranges given for program {}.", prog_name);
+			return vec![];
+		},
+		Ok(file) => file,
+	};
+
+	let mut ranges : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut ranges)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("[App]: couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	println!("[App]: Parameter ranges file read {} bytes total for program {}.", ranges.len(), prog_name);
+
+	ranges
+}
+
+/// The ranges as the enclave encodes them to hash them into the certificate;
+/// no ranges are an empty list.
+fn encode_param_ranges(param_ranges : &[u8]) -> Vec<u8>
+{
+	let ranges = if param_ranges.is_empty()
+	{
+		ParamRanges::new(vec![])
+	}
+	else
+	{
+		match ParamRanges::from_bytes(param_ranges)
+		{
+			Ok((_, v)) => v,
+			Err(why)   => panic!("[App]: Invalid parameter ranges. {}", why),
+		}
+	};
+
+	match ranges.to_bytes()
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("[App]: {}", why),
+	}
+}
+
+fn write_verified_byte_code(byte_code_dir : &str, prog_name : &str, code : &[u8], ranges_bytes : &[u8], cert_bytes : &[u8])
 {
 	use std::fs::File;
 	use std::path::Path;
@@ -67,37 +153,146 @@ fn write_verified_byte_code(byte_code_dir : &str, prog_name : &str, code : &[u8]
 	}
 	println!("[App]: Written bytecode {} bytes.", code.len());
 
-	match file.write_all(pkey_x)
+	match file.write_all(ranges_bytes)
 	{
 		Ok(_) => {},
 		Err(why) => panic!("[App]: couldn't write to {}: {}", file_path.display(), why),
 	}
-	println!("[App]: Written public key x {} bytes.", pkey_x.len());
+	println!("[App]: Written parameter ranges {} bytes.", ranges_bytes.len());
 
-	match file.write_all(pkey_y)
+	match file.write_all(cert_bytes)
 	{
 		Ok(_) => {},
 		Err(why) => panic!("[App]: couldn't write to {}: {}", file_path.display(), why),
 	}
-	println!("[App]: Written public key y {} bytes.", pkey_y.len());
+	println!("[App]: Written certificate {} bytes.", cert_bytes.len());
+}
+
+fn write_evidence(byte_code_dir : &str, name : &str, evidence_bytes : &[u8])
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}/{}.{}", byte_code_dir, name, "evidence");
+	let file_path = Path::new(&file_path_string);
 
-	let sign_x_bytes: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(sign_x) };
-	let sign_y_bytes: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(sign_y) };
+	let mut file = match File::create(&file_path)
+	{
+		Err(why) => panic!("[App]: couldn't create {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
 
-	match file.write_all(sign_x_bytes)
+	match file.write_all(evidence_bytes)
 	{
 		Ok(_) => {},
 		Err(why) => panic!("[App]: couldn't write to {}: {}", file_path.display(), why),
 	}
-	println!("[App]: Written signature x {} bytes.", sign_x_bytes.len());
+	println!("[App]: Written evidence {} bytes.", evidence_bytes.len());
+}
+
+/// Target info of the quoting enclave, so the report of the enclave can be
+/// turned into a quote; empty if the quoting enclave isn't available.
+fn get_qe_target_info() -> Vec<u8>
+{
+	let mut target_info = sgx_target_info_t::default();
+	let mut epid_group_id = sgx_epid_group_id_t::default();
 
-	match file.write_all(sign_y_bytes)
+	let result = unsafe { sgx_init_quote(&mut target_info as *mut sgx_target_info_t, &mut epid_group_id as *mut sgx_epid_group_id_t) };
+	if result != sgx_status_t::SGX_SUCCESS
 	{
-		Ok(_) => {},
-		Err(why) => panic!("[App]: couldn't write to {}: {}", file_path.display(), why),
+		println!("[App]: Quoting enclave unavailable ({}); the report won't target it.", result.as_str());
+		return vec![];
+	}
+
+	let target_info_bytes = unsafe
+	{
+		std::slice::from_raw_parts(&target_info as *const sgx_target_info_t as *const u8, std::mem::size_of::<sgx_target_info_t>())
+	};
+
+	target_info_bytes.to_vec()
+}
+
+/// The seal policy of the identity of the enclave, given by the environment
+/// variable `SEAL_POLICY`, i.e. `mrenclave` (default) or `mrsigner`.
+fn get_seal_policy() -> SealPolicy
+{
+	match env::var("SEAL_POLICY")
+	{
+		Ok(v)  => match SealPolicy::parse(&v)
+		{
+			Ok(p)    => p,
+			Err(why) => panic!("[App]: {}", why),
+		},
+		Err(_) => SealPolicy::MrEnclave,
 	}
-	println!("[App]: Written signature x {} bytes.", sign_y_bytes.len());
+}
+
+/// Hand the sealed identity of the enclave in the store, if any, to the
+/// enclave, which unseals it, or generates a new one; then store the sealed
+/// identity the enclave returns.
+fn init_enclave_identity(enclave : &SgxEnclave, store : &SealedStore, name : &str, policy : SealPolicy) -> sgx_status_t
+{
+	let sealed_in = match store.load(name)
+	{
+		Ok(Some(v)) => v,
+		Ok(None)    =>
+		{
+			println!("[App]: No sealed identity {} stored; the enclave will generate one.", name);
+			vec![]
+		},
+		Err(why)    => panic!("[App]: {}", why),
+	};
 
+	let mut retval = sgx_status_t::SGX_ERROR_UNEXPECTED;
+	let mut sealed_out : Vec<u8> = vec![0; identity::SEALED_IDENTITY_MAX_SIZE];
+	let mut sealed_written : u64 = 0;
+
+	let result = unsafe {
+		init_identity(enclave.geteid(),
+		&mut retval,
+		sealed_in.as_ptr() as * const u8,
+		sealed_in.len(),
+		policy.to_byte(),
+		sealed_out.as_mut_ptr() as * mut u8,
+		sealed_out.len(),
+		&mut sealed_written as * mut u64)
+	};
+
+	match result
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_ =>
+		{
+			println!("[App]: ECALL Enclave Failed {}!", result.as_str());
+			return result;
+		}
+	};
+
+	match retval
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_ =>
+		{
+			println!("[App]: ECALL Enclave Failed {}!", retval.as_str());
+			return retval;
+		}
+	};
+
+	sealed_out.truncate(sealed_written as usize);
+
+	match identity::sealed_public_key(&sealed_out)
+	{
+		Ok((p, pkey)) => println!("[App]: Enclave identity {}, sealed with policy {}.", host_verify::encode_b64_pair(&pkey), p),
+		Err(why)      => panic!("[App]: Invalid sealed identity from the enclave. {}", why),
+	}
+
+	if let Err(why) = store.store(name, &sealed_out)
+	{
+		panic!("[App]: {}", why);
+	}
+
+	result
 }
 
 fn init_enclave() -> SgxResult<SgxEnclave>
@@ -116,27 +311,40 @@ fn init_enclave() -> SgxResult<SgxEnclave>
 
 fn do_type_check(
 	enclave : &SgxEnclave,
-	prog_bytes : &[u8], out_bytes_read : &mut usize,
-	out_pkey_x : &mut [u8; 32], out_pkey_y : &mut [u8; 32],
-	out_sign_x : &mut [u32; 8], out_sign_y : &mut [u32; 8]) -> sgx_status_t
+	prog_bytes : &[u8], policy_flags : u32, param_ranges : &[u8], interpreter_evidence : &[u8], target_info : &[u8], out_bytes_read : &mut usize,
+	out_cert : &mut [u8; certificate::CERT_SIZE],
+	out_evidence : &mut [u8; attestation::SGX_REPORT_EVIDENCE_SIZE],
+	out_sealed_byte_code : &mut Vec<u8>) -> sgx_status_t
 {
 	let mut retval = sgx_status_t::SGX_ERROR_UNEXPECTED;
 	let out_len : [u64; 4] = [0; 4];
-	let out_pkey_x_tmp : [u8; 32] = [0; 32];
-	let out_pkey_y_tmp : [u8; 32] = [0; 32];
-	let out_sign_x_tmp : [u32; 8] = [0; 8];
-	let out_sign_y_tmp : [u32; 8] = [0; 8];
+	let out_cert_tmp : [u8; certificate::CERT_SIZE] = [0; certificate::CERT_SIZE];
+	let out_evidence_tmp : [u8; attestation::SGX_REPORT_EVIDENCE_SIZE] = [0; attestation::SGX_REPORT_EVIDENCE_SIZE];
+
+	// Sealed bytecode is sealed again for the interpreter, and never grows.
+	let mut out_sealed_tmp : Vec<u8> = vec![0; if interpreter_evidence.is_empty() { 0 } else { prog_bytes.len() }];
+	let mut out_sealed_size : u64 = 0;
 
 	let result = unsafe {
 		type_check_byte_code(enclave.geteid(),
 		&mut retval,
 		prog_bytes.as_ptr() as * const u8,
 		prog_bytes.len(),
+		policy_flags,
+		param_ranges.as_ptr() as * const u8,
+		param_ranges.len(),
+		interpreter_evidence.as_ptr() as * const u8,
+		interpreter_evidence.len(),
+		target_info.as_ptr() as * const u8,
+		target_info.len(),
 		out_len.as_ptr() as * mut u64,
-		out_pkey_x_tmp.as_ptr() as * mut u8,
-		out_pkey_y_tmp.as_ptr() as * mut u8,
-		out_sign_x_tmp.as_ptr() as * mut u32,
-		out_sign_y_tmp.as_ptr() as * mut u32)
+		out_cert_tmp.as_ptr() as * mut u8,
+		out_cert_tmp.len(),
+		out_evidence_tmp.as_ptr() as * mut u8,
+		out_evidence_tmp.len(),
+		out_sealed_tmp.as_mut_ptr() as * mut u8,
+		out_sealed_tmp.len(),
+		&mut out_sealed_size as * mut u64)
 	};
 
 	match result
@@ -159,11 +367,17 @@ fn do_type_check(
 		}
 	};
 
+	if out_sealed_size as usize > out_sealed_tmp.len()
+	{
+		println!("[App]: {}", "The enclave returned more sealed bytecode than the buffer holds.");
+		return sgx_status_t::SGX_ERROR_UNEXPECTED;
+	}
+	out_sealed_tmp.truncate(out_sealed_size as usize);
+
 	*out_bytes_read = out_len[0] as usize;
-	out_pkey_x.copy_from_slice(&out_pkey_x_tmp);
-	out_pkey_y.copy_from_slice(&out_pkey_y_tmp);
-	out_sign_x.copy_from_slice(&out_sign_x_tmp);
-	out_sign_y.copy_from_slice(&out_sign_y_tmp);
+	out_cert.copy_from_slice(&out_cert_tmp);
+	out_evidence.copy_from_slice(&out_evidence_tmp);
+	*out_sealed_byte_code = out_sealed_tmp;
 
 	result
 }
@@ -173,7 +387,7 @@ fn main()
 	let byte_code_dir : &'static str = "../../../rs-sources";
 
 	let args : Vec<String> = env::args().collect();
-	if args.len() != 2
+	if args.len() < 2 || args.len() > 4
 	{
 		panic!("[App]: Incorrect number of arguments provided.")
 	}
@@ -189,18 +403,72 @@ fn main()
 		},
 	};
 
+	let store = SealedStore::new(SEALED_DIR);
+	match init_enclave_identity(&enclave, &store, "type_checker", get_seal_policy())
+	{
+		sgx_status_t::SGX_SUCCESS => {},
+		_                         =>
+		{
+			enclave.destroy();
+			return;
+		},
+	}
+
 	let example_prog_name = &args[1];
-	let example_prog_bytes = read_byte_code_from_file(byte_code_dir, example_prog_name);
-	let mut pkey_x : [u8; 32] = [0; 32];
-	let mut pkey_y : [u8; 32] = [0; 32];
-	let mut sign_x : [u32; 8] = [0u32; 8];
-	let mut sign_y : [u32; 8] = [0u32; 8];
+	let param_ranges = read_param_ranges_from_file(byte_code_dir, example_prog_name);
+
+	// With the evidence of an interpreter, the program is the one its owner
+	// sealed for the type-checker, `<prog>.simpc`, and is only delivered to
+	// that interpreter. Under SGX, the evidence has to be a report targeting
+	// this enclave.
+	let interpreter_evidence = match args.get(3)
+	{
+		Some(v) => read_byte_code_from_file(byte_code_dir, v, "evidence"),
+		None    => vec![],
+	};
+	let sealed = !interpreter_evidence.is_empty();
+
+	let example_prog_bytes = read_byte_code_from_file(byte_code_dir, example_prog_name, if sealed { "simpc" } else { "impc" });
+
+	// Optional policy, a comma separated list of extra checks, e.g. "interval".
+	let policy = match args.get(2)
+	{
+		Some(v) => match CheckerPolicy::parse(v)
+		{
+			Ok(p)    => p,
+			Err(why) => panic!("[App]: {}", why),
+		},
+		None    => CheckerPolicy::type_check_only(),
+	};
+
+	let target_info = get_qe_target_info();
+
+	let mut cert_bytes : [u8; certificate::CERT_SIZE] = [0; certificate::CERT_SIZE];
+	let mut evidence_bytes : [u8; attestation::SGX_REPORT_EVIDENCE_SIZE] = [0; attestation::SGX_REPORT_EVIDENCE_SIZE];
 	let mut out_bytes_read : usize = 0;
-	match do_type_check(&enclave, &example_prog_bytes, &mut out_bytes_read, &mut pkey_x, &mut pkey_y, &mut sign_x, &mut sign_y)
+	let mut sealed_byte_code : Vec<u8> = vec![];
+	match do_type_check(&enclave, &example_prog_bytes, policy.to_flags(), &param_ranges, &interpreter_evidence, &target_info, &mut out_bytes_read, &mut cert_bytes, &mut evidence_bytes, &mut sealed_byte_code)
 	{
 		sgx_status_t::SGX_SUCCESS =>
 		{
-			write_verified_byte_code(byte_code_dir, example_prog_name, &example_prog_bytes[0..out_bytes_read], &pkey_x, &pkey_y, &sign_x, &sign_y);
+			// Validate the certificate before writing it out.
+			match Certificate::from_bytes(&cert_bytes)
+			{
+				Ok((_, cert)) => println!("[App]: Received {}.", cert),
+				Err(why)      => panic!("[App]: Invalid certificate from the enclave. {}", why),
+			}
+
+			// The evidence binds the key in the certificate to the enclave.
+			match Evidence::from_bytes(&evidence_bytes)
+			{
+				Ok((_, evidence)) => println!("[App]: Received {}.", evidence),
+				Err(why)          => panic!("[App]: Invalid evidence from the enclave. {}", why),
+			}
+
+			let byte_code = if sealed { &sealed_byte_code[..] } else { &example_prog_bytes[0..out_bytes_read] };
+
+			write_verified_byte_code(byte_code_dir, example_prog_name, byte_code, &encode_param_ranges(&param_ranges), &cert_bytes);
+			write_evidence(byte_code_dir, example_prog_name, &evidence_bytes);
 		}
 		_                         => {},
 	}
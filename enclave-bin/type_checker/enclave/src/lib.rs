@@ -17,45 +17,148 @@ use std::vec::Vec;
 
 extern crate enclave_verifier;
 
-use enclave_verifier::ast;
 use enclave_verifier::ast::Deserializible;
 use enclave_verifier::type_checker;
+use enclave_verifier::analyzer;
+use enclave_verifier::certificate;
+use enclave_verifier::attestation;
+use enclave_verifier::enclave;
+use enclave_verifier::enclave::EnclaveBackend;
+use enclave_verifier::enclave::identity;
+use enclave_verifier::session;
+use enclave_verifier::ast::Serializible;
+
+use std::sync::atomic::AtomicU64;
+
+/// Number of certificates issued by this enclave instance.
+static CERT_COUNTER : AtomicU64 = AtomicU64::new(0);
+
+/// Identity of this enclave instance, set by `init_identity`.
+static IDENTITY : identity::IdentitySlot = identity::IdentitySlot::new();
+
+#[no_mangle]
+pub extern "C" fn init_identity(
+	sealed_identity: *const u8, sealed_identity_len: usize,
+	seal_policy: u8,
+	out_sealed_identity: * mut u8, out_sealed_identity_len: usize,
+	out_sealed_identity_written: * mut u64) -> sgx_status_t
+{
+	// ------------------------------------------
+	// 1. Process input parameters:
+	// ------------------------------------------
+	println!("");
+
+	let policy = match identity::SealPolicy::from_byte(seal_policy)
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+		}
+	};
+
+	// A new identity is generated if no sealed identity is given.
+	let sealed_slice = if sealed_identity.is_null() { &[] } else { unsafe { std::slice::from_raw_parts(sealed_identity, sealed_identity_len) } };
+
+	// ------------------------------------------
+	// 2. Unseal, or generate, the identity:
+	// ------------------------------------------
+	println!("");
+
+	let generate = || match enclave::sgx::SgxBackend::new()
+	{
+		Ok(v)    => Ok(v.identity()),
+		Err(err) => Err(format!("Failed to generate the identity key. {}", err.as_str())),
+	};
+
+	let (encl_identity, sealed_bytes) = match identity::load_or_generate(&enclave::sgx::SgxSealer, sealed_slice, policy, generate)
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED;
+		}
+	};
+
+	if sealed_bytes.len() > out_sealed_identity_len
+	{
+		println!("[Enclave-ERROR]: The sealed identity buffer has {} byte(s) instead of {}.", out_sealed_identity_len, sealed_bytes.len());
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
+
+	println!("[Enclave]: Identity public key {}{}, sealed with policy {}.",
+		base64::encode(&encl_identity.pub_key[0..32]), base64::encode(&encl_identity.pub_key[32..64]), policy);
+
+	// ------------------------------------------
+	// 3. Keep the identity, and output it sealed:
+	// ------------------------------------------
+	println!("");
+
+	if let Err(why) = IDENTITY.set(encl_identity)
+	{
+		println!("[Enclave-ERROR]: {}", why);
+		return sgx_status_t::SGX_ERROR_INVALID_STATE;
+	}
+
+	let out_sealed_slice = unsafe { std::slice::from_raw_parts_mut(out_sealed_identity, sealed_bytes.len()) };
+	out_sealed_slice.copy_from_slice(&sealed_bytes);
+
+	unsafe { *out_sealed_identity_written = sealed_bytes.len() as u64; }
+
+	sgx_status_t::SGX_SUCCESS
+}
 
 #[no_mangle]
 pub extern "C" fn type_check_byte_code(
 	byte_code: *const u8, byte_code_len: usize,
+	policy_flags: u32,
+	param_ranges: *const u8, param_ranges_len: usize,
+	interpreter_evidence: *const u8, interpreter_evidence_len: usize,
+	target_info: *const u8, target_info_len: usize,
 	out_bytes_read: * mut u64,
-	out_pkey_x: * mut u8, out_pkey_y: * mut u8,
-	out_sign_x: * mut u32, out_sign_y: * mut u32) -> sgx_status_t
+	out_cert: * mut u8, out_cert_len: usize,
+	out_evidence: * mut u8, out_evidence_len: usize,
+	out_sealed_byte_code: * mut u8, out_sealed_byte_code_len: usize,
+	out_sealed_byte_code_size: * mut u64) -> sgx_status_t
 {
 	// ------------------------------------------
-	// 1. Generate EC key pair:
+	// 1. Load the identity, or generate an EC key pair:
 	// ------------------------------------------
 	println!("");
 
-	let ecc_ctx : sgx_tcrypto::SgxEccHandle = sgx_tcrypto::SgxEccHandle::new();
-
-	match ecc_ctx.open()
+	// Signatures are only by a long-lived key once `init_identity` is called.
+	let backend = match IDENTITY.get()
 	{
-		Result::Ok(_)    => {},
-		Result::Err(err) => { return err; },
+		Some(v) => enclave::sgx::SgxBackend::from_identity(v),
+		None    => enclave::sgx::SgxBackend::new(),
 	};
 
-	let (encl_prv_key, encl_pub_key) = match ecc_ctx.create_key_pair()
+	let backend = match backend
 	{
 		Result::Ok(val)  => val,
 		Result::Err(err) => { return err; },
 	};
 
-	println!("[Enclave]: Enclave public key {}{}.", base64::encode(&encl_pub_key.gx), base64::encode(&encl_pub_key.gy));
+	let target_info_slice = if target_info.is_null() { &[] } else { unsafe { std::slice::from_raw_parts(target_info, target_info_len) } };
 
-	let out_pkey_x_slice = unsafe { std::slice::from_raw_parts_mut(out_pkey_x, 32) };
-	let out_pkey_y_slice = unsafe { std::slice::from_raw_parts_mut(out_pkey_y, 32) };
-	out_pkey_x_slice.copy_from_slice(&encl_pub_key.gx);
-	out_pkey_y_slice.copy_from_slice(&encl_pub_key.gy);
+	let backend = match backend.with_target_info_bytes(target_info_slice)
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+		}
+	};
+
+	let encl_pub_key = backend.public_key();
+
+	println!("[Enclave]: Enclave public key {}{}.", base64::encode(&encl_pub_key[0..32]), base64::encode(&encl_pub_key[32..64]));
 
 	// ------------------------------------------
-	// 2. Process input bytes:
+	// 2. Process input parameters:
 	// ------------------------------------------
 	println!("");
 
@@ -63,85 +166,164 @@ pub extern "C" fn type_check_byte_code(
 
 	println!("[Enclave]: Received input ({} byte(s)).", input_slice.len());
 
-	let (input_bytes_left, example_prog) = match ast::cmd::Cmd::from_bytes(input_slice)
+	let policy = match type_checker::policy::CheckerPolicy::from_flags(policy_flags)
 	{
 		Ok(v)    => v,
 		Err(why) =>
 		{
-			println!("[Enclave-ERROR]: Couldn't construct AST from byte code. {}", why);
-			return sgx_status_t::SGX_ERROR_UNEXPECTED;
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
 		}
 	};
 
-	let prog_bytes_read_len : usize = input_slice.len() - input_bytes_left.len();
-	println!("[Enclave]: Received bytecode ({} byte(s)).", prog_bytes_read_len);
-	let out_bytes_read_slice = unsafe { std::slice::from_raw_parts_mut(out_bytes_read, 4) };
-	out_bytes_read_slice[0] = prog_bytes_read_len as u64;
+	// Parameters of entry are not restricted if no range is given.
+	let ranges = if param_ranges.is_null() || param_ranges_len == 0
+	{
+		analyzer::interval::ParamRanges::new(Vec::new())
+	}
+	else
+	{
+		let ranges_slice = unsafe { std::slice::from_raw_parts(param_ranges, param_ranges_len) };
+		match analyzer::interval::ParamRanges::from_bytes(ranges_slice)
+		{
+			Ok((_, v)) => v,
+			Err(why)   =>
+			{
+				println!("[Enclave-ERROR]: Couldn't read parameter ranges. {}", why);
+				return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+			}
+		}
+	};
 
-	let byte_code_slice = &input_slice[0..(input_slice.len() - input_bytes_left.len())];
+	if out_cert_len != certificate::CERT_SIZE
+	{
+		println!("[Enclave-ERROR]: The certificate buffer has {} byte(s) instead of {}.", out_cert_len, certificate::CERT_SIZE);
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
 
-	let byte_code_hash = match sgx_tcrypto::rsgx_sha256_slice(&byte_code_slice)
+	if out_evidence_len != attestation::SGX_REPORT_EVIDENCE_SIZE
 	{
-		Ok(val)  => val,
-		Err(err) => return err,
-	};
+		println!("[Enclave-ERROR]: The evidence buffer has {} byte(s) instead of {}.", out_evidence_len, attestation::SGX_REPORT_EVIDENCE_SIZE);
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
+
+	// A program sealed by its owner is sealed again for the interpreter of
+	// the evidence, which is never larger than the sealed input.
+	let sealed = session::program::is_sealed_program(input_slice);
 
-	println!("[Enclave]: Bytecode hash SHA256(byte_code): {}.", base64::encode(&byte_code_hash));
+	unsafe { *out_sealed_byte_code_size = 0; }
 
-	let mut example_prog_lines : Vec<ast::IndentString> = vec![];
-	example_prog.to_indent_lines(&mut example_prog_lines);
-	println!("[Enclave]: Example program:\n{}\n", ast::indent_lines_to_string(&example_prog_lines, '\t'));
+	let interpreter_evidence = if !sealed
+	{
+		Option::None
+	}
+	else if interpreter_evidence.is_null() || interpreter_evidence_len == 0
+	{
+		println!("[Enclave-ERROR]: {}", "The program is sealed, and no evidence of the interpreter is given.");
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
+	else
+	{
+		let evidence_slice = unsafe { std::slice::from_raw_parts(interpreter_evidence, interpreter_evidence_len) };
+		match attestation::Evidence::from_bytes(evidence_slice)
+		{
+			Ok((_, v)) => Option::Some(v),
+			Err(why)   =>
+			{
+				println!("[Enclave-ERROR]: Couldn't read the evidence of the interpreter. {}", why);
+				return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+			}
+		}
+	};
 
+	if sealed && out_sealed_byte_code_len < input_slice.len()
+	{
+		println!("[Enclave-ERROR]: The sealed bytecode buffer has {} byte(s) instead of {}.", out_sealed_byte_code_len, input_slice.len());
+		return sgx_status_t::SGX_ERROR_INVALID_PARAMETER;
+	}
 
 	// ------------------------------------------
-	// 3. Verification:
+	// 3. Check the program against the policy:
 	// ------------------------------------------
 	println!("");
 
-	println!("[Enclave]: Iteration test:");
+	let output = match &interpreter_evidence
+	{
+		Option::Some(evidence) => enclave::checker::check_sealed_program(&backend, input_slice, evidence, &policy, &ranges, &CERT_COUNTER)
+			.map(|o| (0, o.cert, o.sealed_byte_code)),
+		Option::None           => enclave::checker::check_program(&backend, input_slice, &policy, &ranges, &CERT_COUNTER)
+			.map(|o| (o.bytes_read, o.cert, Vec::new())),
+	};
 
-	let mut glvar_vec: Vec<type_checker::type_checker::VarTypePair> = Vec::new();
-	let mut fn_vec:  Vec<type_checker::type_checker::FuncIdentifierTuple> = Vec::new();
-	match type_checker::type_checker::gather_fn_types(&example_prog, &mut glvar_vec, &mut fn_vec) {
+	let (bytes_read, cert, sealed_byte_code) = match output
+	{
+		Ok(v)    => v,
 		Err(why) =>
 		{
-			println!("[Enclave]: Failed type checking:\n{}", why);
+			println!("[Enclave]: {}", why);
 			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		},
-		_ => (),
+		}
 	};
-	let var_vec: Vec<type_checker::type_checker::VarTypePair> = Vec::new();
-	let res = type_checker::type_checker::iterate_through_ast(example_prog, true, var_vec, &fn_vec, ast::data_type::DataType::Void);
-	match res {
-		Ok(_)    => println!("Successful type checking!"),
+
+	// ------------------------------------------
+	// 4. Output certificate:
+	// ------------------------------------------
+	println!("");
+
+	if sealed
+	{
+		println!("[Enclave]: Sealed the bytecode for the interpreter ({} byte(s)).", sealed_byte_code.len());
+	}
+	else
+	{
+		println!("[Enclave]: Received bytecode ({} byte(s)).", bytes_read);
+	}
+	println!("[Enclave]: Issued {}.", cert);
+	println!("[Enclave]: Certificate signature: {}{}.", base64::encode(&cert.signature[0..32]), base64::encode(&cert.signature[32..64]));
+
+	let cert_bytes = match cert.to_bytes()
+	{
+		Ok(val)  => val,
 		Err(why) =>
 		{
-			println!("[Enclave]: Failed type checking:\n{}", why);
+			println!("[Enclave-ERROR]: {}", why);
 			return sgx_status_t::SGX_ERROR_UNEXPECTED;
-		},
-	}
+		}
+	};
 
 	// ------------------------------------------
-	// 4. Generate signature:
+	// 5. Output evidence for the enclave key:
 	// ------------------------------------------
 	println!("");
 
-	let sign = match ecc_ctx.ecdsa_sign_slice(&byte_code_hash, &encl_prv_key)
+	let evidence_bytes = match attestation::gen_evidence(&backend).and_then(|e| e.to_bytes())
 	{
 		Ok(val)  => val,
-		Err(err) => return err,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			return sgx_status_t::SGX_ERROR_UNEXPECTED;
+		}
 	};
 
-	let sign_x: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(&sign.x) };
-	let sign_y: &[u8; 32] = unsafe { std::mem::transmute::<&[u32; 8], &[u8; 32]>(&sign.y) };
+	println!("[Enclave]: Generated evidence ({} byte(s)).", evidence_bytes.len());
+
+	let out_evidence_slice = unsafe { std::slice::from_raw_parts_mut(out_evidence, out_evidence_len) };
+	out_evidence_slice.copy_from_slice(&evidence_bytes);
+
+	let out_bytes_read_slice = unsafe { std::slice::from_raw_parts_mut(out_bytes_read, 4) };
+	out_bytes_read_slice[0] = bytes_read as u64;
 
-	println!("[Enclave]: report signature: {}{}.", base64::encode(&sign_x), base64::encode(&sign_y));
+	if sealed
+	{
+		let out_sealed_slice = unsafe { std::slice::from_raw_parts_mut(out_sealed_byte_code, sealed_byte_code.len()) };
+		out_sealed_slice.copy_from_slice(&sealed_byte_code);
 
-	let out_sign_x_slice = unsafe { std::slice::from_raw_parts_mut(out_sign_x, 8) };
-	let out_sign_y_slice = unsafe { std::slice::from_raw_parts_mut(out_sign_y, 8) };
+		unsafe { *out_sealed_byte_code_size = sealed_byte_code.len() as u64; }
+	}
 
-	out_sign_x_slice.copy_from_slice(&sign.x);
-	out_sign_y_slice.copy_from_slice(&sign.y);
+	let out_cert_slice = unsafe { std::slice::from_raw_parts_mut(out_cert, out_cert_len) };
+	out_cert_slice.copy_from_slice(&cert_bytes);
 
 	sgx_status_t::SGX_SUCCESS
 }
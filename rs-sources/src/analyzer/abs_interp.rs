@@ -0,0 +1,1111 @@
+use std::fmt;
+use std::rc::Rc;
+use std::vec::Vec;
+use std::string::String;
+use std::string::ToString;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use super::super::ast;
+use super::super::typing;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::cmd::Cmd;
+use ast::exp::Exp;
+use ast::data_type::DataType;
+use ast::func_general::FnCall;
+use ast::func_general::FnProtoType;
+
+use super::interval::Interval;
+use super::interval::ParamRanges;
+
+/// Number of loop iterations analyzed before widening kicks in.
+const WIDEN_DELAY : usize = 3;
+
+/// Maximum depth of (non-recursive) calls analyzed in their calling context.
+/// Deeper calls are analyzed once with unrestricted parameters.
+const MAX_CALL_DEPTH : usize = 32;
+
+/// Abstract value of an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbsValue
+{
+	Int(Interval),
+	Float,
+	/// `Option::None` means the value could be either `true` or `false`.
+	Bool(Option<bool>),
+}
+
+impl AbsValue
+{
+	pub fn top_of(t : &DataType) -> Result<AbsValue, String>
+	{
+		match t
+		{
+			DataType::Int32   => Result::Ok(AbsValue::Int(Interval::full_i32())),
+			DataType::Float32 => Result::Ok(AbsValue::Float),
+			DataType::Bool    => Result::Ok(AbsValue::Bool(Option::None)),
+			DataType::Void    => Result::Err(format!("{}", "A variable can not have void type.")),
+		}
+	}
+
+	/// The abstract value of a declared variable that hasn't been assigned yet.
+	fn uninit_of(t : &DataType) -> Result<AbsValue, String>
+	{
+		match t
+		{
+			DataType::Int32 => Result::Ok(AbsValue::Int(Interval::Empty)),
+			_               => AbsValue::top_of(t),
+		}
+	}
+
+	pub fn get_type(&self) -> DataType
+	{
+		match self
+		{
+			AbsValue::Int(_)   => DataType::Int32,
+			AbsValue::Float    => DataType::Float32,
+			AbsValue::Bool(_)  => DataType::Bool,
+		}
+	}
+
+	fn join(&self, other : &AbsValue) -> AbsValue
+	{
+		match (self, other)
+		{
+			(AbsValue::Int(l), AbsValue::Int(r))   => AbsValue::Int(l.join(r)),
+			(AbsValue::Bool(l), AbsValue::Bool(r)) => AbsValue::Bool(if l == r { *l } else { Option::None }),
+			_                                      => AbsValue::Float,
+		}
+	}
+
+	fn widen(&self, next : &AbsValue) -> AbsValue
+	{
+		match (self, next)
+		{
+			(AbsValue::Int(l), AbsValue::Int(r)) => AbsValue::Int(l.widen(r)),
+			_                                    => self.join(next),
+		}
+	}
+}
+
+impl fmt::Display for AbsValue
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			AbsValue::Int(itv)                => write!(f, "{}", itv),
+			AbsValue::Float                   => write!(f, "{}", "f32"),
+			AbsValue::Bool(Option::Some(v))   => write!(f, "{}", v),
+			AbsValue::Bool(Option::None)      => write!(f, "{}", "bool"),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CheckKind
+{
+	/// The divisor of `Aexp::Div` or `Aexp::Mod` may be zero.
+	DivByZero,
+	/// The result of an `i32` operation may not fit in `i32`.
+	Overflow,
+}
+
+impl CheckKind
+{
+	fn to_byte(&self) -> u8
+	{
+		match self
+		{
+			CheckKind::DivByZero => 0u8,
+			CheckKind::Overflow  => 1u8,
+		}
+	}
+}
+
+impl fmt::Display for CheckKind
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			CheckKind::DivByZero => write!(f, "{}", "division by zero"),
+			CheckKind::Overflow  => write!(f, "{}", "i32 overflow"),
+		}
+	}
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Verdict
+{
+	/// The error can not happen in any execution starting from `entry`.
+	Proven,
+	/// The analysis could not rule out the error.
+	Alarm,
+}
+
+impl fmt::Display for Verdict
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			Verdict::Proven => write!(f, "{}", "PROVEN"),
+			Verdict::Alarm  => write!(f, "{}", "ALARM "),
+		}
+	}
+}
+
+/// The result of one check on one arithmetic expression.
+#[derive(Clone)]
+pub struct ExpCheck
+{
+	pub kind : CheckKind,
+	/// Name of the function containing the expression.
+	pub func : String,
+	pub exp : String,
+	pub verdict : Verdict,
+	/// Range of the exact result (for overflow) or of the divisor (for division by zero).
+	pub range : Interval,
+}
+
+impl fmt::Display for ExpCheck
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "[{}] {}: {} - {} (range {})", self.verdict, self.func, self.exp, self.kind, self.range)
+	}
+}
+
+/// Report of the interval analysis, one entry per checked expression.
+///
+/// Expressions that are not reachable from `entry` are not listed.
+///
+pub struct AnalysisReport
+{
+	pub checks : Vec<ExpCheck>,
+}
+
+impl AnalysisReport
+{
+	/// Whether all division-by-zero and overflow errors are proven absent.
+	pub fn is_safe(&self) -> bool
+	{
+		self.checks.iter().all(|c| c.verdict == Verdict::Proven)
+	}
+
+	pub fn alarms(&self) -> Vec<&ExpCheck>
+	{
+		self.checks.iter().filter(|c| c.verdict == Verdict::Alarm).collect()
+	}
+}
+
+impl fmt::Display for AnalysisReport
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		for check in self.checks.iter()
+		{
+			write!(f, "{}\n", check)?;
+		}
+
+		write!(f, "{} check(s), {} alarm(s).", self.checks.len(), self.alarms().len())
+	}
+}
+
+#[derive(Clone, PartialEq)]
+struct AbsVar
+{
+	t : DataType,
+	v : AbsValue,
+}
+
+/// Abstract program state; scopes follow the interpreter's variable stack.
+#[derive(Clone, PartialEq)]
+struct AbsState
+{
+	scopes : Vec<HashMap<String, AbsVar> >,
+}
+
+impl AbsState
+{
+	fn decl(&mut self, decl : &ast::var_general::VarDecl) -> Result<(), String>
+	{
+		let v = AbsValue::uninit_of(&decl.var_type)?;
+
+		match self.scopes.last_mut()
+		{
+			Option::Some(scope) =>
+			{
+				if scope.contains_key(&decl.name)
+				{
+					return Result::Err(format!("Variable {} is declared repeatedly.", decl.name));
+				}
+				scope.insert(decl.name.clone(), AbsVar {t : decl.var_type, v : v});
+				Result::Ok(())
+			},
+			Option::None        => Result::Err(format!("{}", "Abstract state has no scope.")),
+		}
+	}
+
+	fn get(&self, name : &String) -> Option<&AbsVar>
+	{
+		self.scopes.iter().rev().filter_map(|s| s.get(name)).next()
+	}
+
+	fn get_mut(&mut self, name : &String) -> Option<&mut AbsVar>
+	{
+		self.scopes.iter_mut().rev().filter_map(|s| s.get_mut(name)).next()
+	}
+
+	fn assign(&mut self, name : &String, v : AbsValue) -> Result<(), String>
+	{
+		match self.get_mut(name)
+		{
+			Option::Some(var) =>
+			{
+				var.v = match (&var.t, v)
+				{
+					(DataType::Float32, AbsValue::Int(_)) => AbsValue::Float,
+					(_, v)                                 => v,
+				};
+				Result::Ok(())
+			},
+			Option::None      => Result::Err(format!("Variable {} hasn't been declared.", name)),
+		}
+	}
+
+	fn map_values<F>(&self, other : &AbsState, op : F) -> AbsState
+		where F : Fn(&AbsValue, &AbsValue) -> AbsValue
+	{
+		let mut res = self.clone();
+
+		for (scope, other_scope) in res.scopes.iter_mut().zip(other.scopes.iter())
+		{
+			for (name, var) in scope.iter_mut()
+			{
+				match other_scope.get(name)
+				{
+					Option::Some(other_var) => var.v = op(&var.v, &other_var.v),
+					Option::None            => {},
+				}
+			}
+		}
+
+		res
+	}
+}
+
+fn join_opt(l : Option<AbsState>, r : Option<AbsState>) -> Option<AbsState>
+{
+	match (l, r)
+	{
+		(Option::None, r)              => r,
+		(l, Option::None)              => l,
+		(Option::Some(l), Option::Some(r)) => Option::Some(l.map_values(&r, |a, b| a.join(b))),
+	}
+}
+
+/// Values gathered at the `return` statements of a function.
+struct RetAcc
+{
+	val : Option<AbsValue>,
+	globals : Option<HashMap<String, AbsVar> >,
+}
+
+impl RetAcc
+{
+	fn new() -> RetAcc
+	{
+		RetAcc {val : Option::None, globals : Option::None}
+	}
+
+	fn add(&mut self, val : Option<AbsValue>, st : &AbsState)
+	{
+		self.val = match (self.val.take(), val)
+		{
+			(Option::None, v)                  => v,
+			(v, Option::None)                  => v,
+			(Option::Some(l), Option::Some(r)) => Option::Some(l.join(&r)),
+		};
+
+		let globals = AbsState {scopes : vec![st.scopes[0].clone()]};
+		self.globals = match self.globals.take()
+		{
+			Option::None    => Option::Some(globals.scopes[0].clone()),
+			Option::Some(g) =>
+			{
+				let prev = AbsState {scopes : vec![g]};
+				Option::Some(prev.map_values(&globals, |a, b| a.join(b)).scopes[0].clone())
+			},
+		};
+	}
+}
+
+fn mangle_fn_name(name : &String, types : &Vec<DataType>) -> String
+{
+	let mut mangled_fun_name = String::new();
+	mangled_fun_name.push_str(name);
+	mangled_fun_name.push('_');
+
+	for t in types.iter()
+	{
+		mangled_fun_name.push_str(&t.to_string());
+		mangled_fun_name.push('_');
+	}
+
+	mangled_fun_name
+}
+
+struct Analyzer
+{
+	funcs : HashMap<String, (Rc<FnProtoType>, Rc<Cmd>)>,
+	call_stack : Vec<String>,
+	summarized : HashSet<String>,
+	checks : Vec<ExpCheck>,
+	check_idx : HashMap<(usize, u8), usize>,
+}
+
+impl Analyzer
+{
+	fn new() -> Analyzer
+	{
+		Analyzer
+		{
+			funcs : HashMap::new(),
+			call_stack : Vec::new(),
+			summarized : HashSet::new(),
+			checks : Vec::new(),
+			check_idx : HashMap::new(),
+		}
+	}
+
+	fn curr_func(&self) -> String
+	{
+		match self.call_stack.last()
+		{
+			Option::Some(name) => name.clone(),
+			Option::None       => format!("{}", "<global>"),
+		}
+	}
+
+	fn record(&mut self, e : &Aexp, kind : CheckKind, is_safe : bool, range : Interval)
+	{
+		let key = (e as *const Aexp as usize, kind.to_byte());
+		let verdict = if is_safe { Verdict::Proven } else { Verdict::Alarm };
+
+		match self.check_idx.get(&key)
+		{
+			Option::Some(idx) =>
+			{
+				let check = &mut self.checks[*idx];
+				check.range = check.range.join(&range);
+				if verdict == Verdict::Alarm
+				{
+					check.verdict = Verdict::Alarm;
+				}
+			},
+			Option::None      =>
+			{
+				self.check_idx.insert(key, self.checks.len());
+				self.checks.push(ExpCheck
+				{
+					kind : kind,
+					func : self.curr_func(),
+					exp : e.to_string(),
+					verdict : verdict,
+					range : range,
+				});
+			},
+		}
+	}
+
+	fn eval_exp(&mut self, e : &Exp, st : &mut AbsState) -> Result<AbsValue, String>
+	{
+		match e
+		{
+			Exp::A{e} => self.eval_aexp(e, st),
+			Exp::B{e} => Result::Ok(AbsValue::Bool(self.eval_bexp(e, st)?)),
+		}
+	}
+
+	fn eval_aexp(&mut self, e : &Aexp, st : &mut AbsState) -> Result<AbsValue, String>
+	{
+		match e
+		{
+			Aexp::IntConst{v} => Result::Ok(AbsValue::Int(Interval::constant(*v as i64))),
+			Aexp::FloConst{v:_} => Result::Ok(AbsValue::Float),
+			Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				let l_val = self.eval_aexp(l, st)?;
+				let r_val = self.eval_aexp(r, st)?;
+
+				match (l_val, r_val)
+				{
+					(AbsValue::Int(l_itv), AbsValue::Int(r_itv)) =>
+					{
+						Result::Ok(AbsValue::Int(self.eval_int_op(e, &l_itv, &r_itv)))
+					},
+					(AbsValue::Bool(_), _) | (_, AbsValue::Bool(_)) =>
+						Result::Err(format!("Expecting an AExp value in {}, while a BExp value is given.", e)),
+					_ => Result::Ok(AbsValue::Float),
+				}
+			},
+			Aexp::Var{v} =>
+			{
+				match st.get(&v.name)
+				{
+					Option::Some(var) => Result::Ok(var.v.clone()),
+					Option::None      => Result::Err(format!("AExp Variable {} hasn't been declared.", v.name)),
+				}
+			},
+			Aexp::FnCall{fc} =>
+			{
+				match self.eval_fn_call(fc, st)?
+				{
+					Option::Some(v) => Result::Ok(v),
+					Option::None    => Result::Err(format!("Function {} doesn't return a value.", fc.name)),
+				}
+			},
+		}
+	}
+
+	/// Evaluate an `i32` operation, and record the checks on it.
+	fn eval_int_op(&mut self, e : &Aexp, l_itv : &Interval, r_itv : &Interval) -> Interval
+	{
+		if l_itv.is_empty() || r_itv.is_empty()
+		{
+			return Interval::Empty;
+		}
+
+		let exact = match e
+		{
+			Aexp::Add{l:_, r:_} => l_itv.add(r_itv),
+			Aexp::Sub{l:_, r:_} => l_itv.sub(r_itv),
+			Aexp::Mul{l:_, r:_} => l_itv.mul(r_itv),
+			Aexp::Div{l:_, r:_} => l_itv.div(r_itv),
+			_                   => l_itv.rem(r_itv),
+		};
+
+		match e
+		{
+			Aexp::Div{l:_, r:_} | Aexp::Mod{l:_, r:_} =>
+			{
+				self.record(e, CheckKind::DivByZero, !r_itv.contains(0), *r_itv);
+			},
+			_ => {},
+		}
+
+		// i32::MIN / -1 and i32::MIN % -1 overflow as well.
+		let min_by_neg_one = l_itv.contains(super::interval::I32_MIN) && r_itv.contains(-1);
+		let no_overflow = match e
+		{
+			Aexp::Mod{l:_, r:_} => !min_by_neg_one,
+			_                   => exact.is_within_i32() && !min_by_neg_one,
+		};
+		self.record(e, CheckKind::Overflow, no_overflow, exact);
+
+		exact.wrap_to_i32()
+	}
+
+	fn eval_bexp(&mut self, e : &Bexp, st : &mut AbsState) -> Result<Option<bool>, String>
+	{
+		// Note: the interpreter evaluates both sides of && and || ,
+		// so both sides are always analyzed here as well.
+		match e
+		{
+			Bexp::BoolConst{v} => Result::Ok(Option::Some(*v)),
+			Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+			{
+				let l_val = self.eval_bexp(l, st)?;
+				let r_val = self.eval_bexp(r, st)?;
+
+				Result::Ok(match (e, l_val, r_val)
+				{
+					(Bexp::And{l:_, r:_}, Option::Some(false), _) => Option::Some(false),
+					(Bexp::And{l:_, r:_}, _, Option::Some(false)) => Option::Some(false),
+					(Bexp::Or{l:_, r:_},  Option::Some(true), _)  => Option::Some(true),
+					(Bexp::Or{l:_, r:_},  _, Option::Some(true))  => Option::Some(true),
+					(_, Option::Some(lv), Option::Some(rv)) => match e
+					{
+						Bexp::Beq{l:_, r:_}  => Option::Some(lv == rv),
+						Bexp::Bneq{l:_, r:_} => Option::Some(lv != rv),
+						Bexp::And{l:_, r:_}  => Option::Some(lv && rv),
+						_                    => Option::Some(lv || rv),
+					},
+					_ => Option::None,
+				})
+			},
+			Bexp::Not{e} => Result::Ok(self.eval_bexp(e, st)?.map(|v| !v)),
+			Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+			{
+				let l_val = self.eval_aexp(l, st)?;
+				let r_val = self.eval_aexp(r, st)?;
+
+				match (l_val, r_val)
+				{
+					(AbsValue::Int(l_itv), AbsValue::Int(r_itv)) => Result::Ok(cmp_intervals(e, &l_itv, &r_itv)),
+					_                                            => Result::Ok(Option::None),
+				}
+			},
+			Bexp::Var{v} =>
+			{
+				match st.get(&v.name)
+				{
+					Option::Some(AbsVar{t:_, v : AbsValue::Bool(b)}) => Result::Ok(*b),
+					Option::Some(_) => Result::Err(format!("Expecting an BExp value in {}, while a AExp value is given.", v.name)),
+					Option::None    => Result::Err(format!("BExp Variable {} hasn't been declared.", v.name)),
+				}
+			},
+			Bexp::FnCall{fc} =>
+			{
+				match self.eval_fn_call(fc, st)?
+				{
+					Option::Some(AbsValue::Bool(b)) => Result::Ok(b),
+					Option::Some(_) => Result::Err(format!("Expecting an BExp value from {}, while a AExp value is given.", fc.name)),
+					Option::None    => Result::Err(format!("Function {} doesn't return a value.", fc.name)),
+				}
+			},
+		}
+	}
+
+	fn eval_fn_call(&mut self, fc : &FnCall, st : &mut AbsState) -> Result<Option<AbsValue>, String>
+	{
+		let mut arg_vals : Vec<AbsValue> = Vec::new();
+		for e in fc.exp_list.iter()
+		{
+			arg_vals.push(self.eval_exp(e, st)?);
+		}
+
+		let arg_types : Vec<DataType> = arg_vals.iter().map(|v| v.get_type()).collect();
+		let param_types = typing::resolve_call(fc, &arg_types, self.funcs.values().map(|v| &*v.0))?;
+		let mangled_name = mangle_fn_name(&fc.name, &param_types);
+
+		let (pt, fn_cmd) = match self.funcs.get(&mangled_name)
+		{
+			Option::Some(v) => (v.0.clone(), v.1.clone()),
+			Option::None    => return Result::Err(format!("The function {} called is undefined.", mangled_name)),
+		};
+
+		let is_recursive = self.call_stack.contains(&mangled_name);
+
+		if is_recursive || (self.call_stack.len() >= MAX_CALL_DEPTH)
+		{
+			// Analyze the callee once for all of its calling contexts, and
+			// assume nothing about its result and its effects on globals.
+			if !self.summarized.contains(&mangled_name)
+			{
+				self.summarized.insert(mangled_name.clone());
+
+				let mut havoc_st = havoc_globals(st)?;
+				let mut top_args : Vec<AbsValue> = Vec::new();
+				for var_decl in pt.var_decl_list.iter()
+				{
+					top_args.push(AbsValue::top_of(&var_decl.var_type)?);
+				}
+
+				self.analyze_func(&mangled_name, &pt, &fn_cmd, &mut havoc_st, top_args)?;
+			}
+
+			*st = havoc_globals(st)?;
+			return match pt.ret_type
+			{
+				DataType::Void => Result::Ok(Option::None),
+				_              => Result::Ok(Option::Some(AbsValue::top_of(&pt.ret_type)?)),
+			};
+		}
+
+		self.analyze_func(&mangled_name, &pt, &fn_cmd, st, arg_vals)
+	}
+
+	/// Analyze a function body in the given calling context.
+	///
+	/// The globals in `st` are updated with the effects of the call.
+	///
+	fn analyze_func(&mut self, mangled_name : &String, pt : &FnProtoType, fn_cmd : &Cmd,
+		st : &mut AbsState, arg_vals : Vec<AbsValue>) -> Result<Option<AbsValue>, String>
+	{
+		let mut callee_st = AbsState {scopes : vec![st.scopes[0].clone(), HashMap::new()]};
+
+		for (var_decl, val) in pt.var_decl_list.iter().zip(arg_vals.into_iter())
+		{
+			callee_st.decl(var_decl)?;
+			callee_st.assign(&var_decl.name, val)?;
+		}
+
+		self.call_stack.push(mangled_name.clone());
+
+		let mut ret = RetAcc::new();
+		let end_st = self.exec_cmd(fn_cmd, Option::Some(callee_st), &mut ret);
+
+		self.call_stack.pop();
+
+		match end_st?
+		{
+			Option::Some(end_st) => ret.add(Option::None, &end_st),
+			Option::None         => {},
+		}
+
+		match ret.globals
+		{
+			Option::Some(g) => st.scopes[0] = g,
+			Option::None    => {}, // The call never returns.
+		}
+
+		Result::Ok(match pt.ret_type
+		{
+			DataType::Void => Option::None,
+			_              => Option::Some(match ret.val
+			{
+				Option::Some(v) => match (&pt.ret_type, v)
+				{
+					(DataType::Float32, AbsValue::Int(_)) => AbsValue::Float,
+					(_, v)                                 => v,
+				},
+				Option::None    => AbsValue::top_of(&pt.ret_type)?,
+			}),
+		})
+	}
+
+	fn exec_in_new_scope(&mut self, c : &Cmd, st : AbsState, ret : &mut RetAcc) -> Result<Option<AbsState>, String>
+	{
+		let mut inner_st = st;
+		inner_st.scopes.push(HashMap::new());
+
+		let mut res = self.exec_cmd(c, Option::Some(inner_st), ret)?;
+
+		match &mut res
+		{
+			Option::Some(s) => { s.scopes.pop(); },
+			Option::None    => {},
+		}
+
+		Result::Ok(res)
+	}
+
+	/// Execute a command on the abstract state; `Option::None` stands for unreachable.
+	fn exec_cmd(&mut self, c : &Cmd, st_opt : Option<AbsState>, ret : &mut RetAcc) -> Result<Option<AbsState>, String>
+	{
+		let mut st = match st_opt
+		{
+			Option::Some(s) => s,
+			Option::None    => return Result::Ok(Option::None),
+		};
+
+		match c
+		{
+			Cmd::Skip => {},
+			Cmd::VarDecl{d} => st.decl(d)?,
+			Cmd::Assign{var, e} =>
+			{
+				let val = self.eval_exp(e, &mut st)?;
+				st.assign(&var.name, val)?;
+			},
+			Cmd::FnCall{fc} =>
+			{
+				self.eval_fn_call(fc, &mut st)?;
+			},
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				let cond_val = self.eval_bexp(cond, &mut st)?;
+
+				let tr_st = if cond_val != Option::Some(false) { refine(cond, true, &st) } else { Option::None };
+				let fa_st = if cond_val != Option::Some(true) { refine(cond, false, &st) } else { Option::None };
+
+				let tr_res = match tr_st
+				{
+					Option::Some(s) => self.exec_in_new_scope(tr_cmd, s, ret)?,
+					Option::None    => Option::None,
+				};
+				let fa_res = match fa_st
+				{
+					Option::Some(s) => self.exec_in_new_scope(fa_cmd, s, ret)?,
+					Option::None    => Option::None,
+				};
+
+				return Result::Ok(join_opt(tr_res, fa_res));
+			},
+			Cmd::WhileLoop{cond, lp_cmd, ..} =>
+			{
+				let mut head = st;
+				let mut iter : usize = 0;
+
+				loop
+				{
+					let mut cond_st = head.clone();
+					let cond_val = self.eval_bexp(cond, &mut cond_st)?;
+
+					let body_st = if cond_val != Option::Some(false) { refine(cond, true, &cond_st) } else { Option::None };
+					let body_res = match body_st
+					{
+						Option::Some(s) => self.exec_in_new_scope(lp_cmd, s, ret)?,
+						Option::None    => Option::None,
+					};
+
+					let next = match join_opt(Option::Some(head.clone()), body_res)
+					{
+						Option::Some(s) => s,
+						Option::None    => head.clone(),
+					};
+					let next = if iter >= WIDEN_DELAY { head.map_values(&next, |a, b| a.widen(b)) } else { next };
+
+					if next == head
+					{
+						return Result::Ok(if cond_val != Option::Some(true) { refine(cond, false, &cond_st) } else { Option::None });
+					}
+
+					head = next;
+					iter += 1;
+				}
+			},
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				let fst_res = self.exec_cmd(fst_cmd, Option::Some(st), ret)?;
+				return self.exec_cmd(snd_cmd, fst_res, ret);
+			},
+			Cmd::FnDecl{prototype, fn_cmd} =>
+			{
+				if st.scopes.len() > 1
+				{
+					return Result::Err(format!("Function {} is declared at non-global scope.", prototype.name));
+				}
+
+				let arg_types : Vec<DataType> = prototype.var_decl_list.iter().map(|d| d.var_type).collect();
+				let mangled_name = mangle_fn_name(&prototype.name, &arg_types);
+				if self.funcs.contains_key(&mangled_name)
+				{
+					return Result::Err(format!("Function named {} has already been declared.", prototype.name));
+				}
+				self.funcs.insert(mangled_name, (prototype.clone(), fn_cmd.clone()));
+			},
+			Cmd::Return{e} =>
+			{
+				let val = match e
+				{
+					Option::Some(e_v) => Option::Some(self.eval_exp(e_v, &mut st)?),
+					Option::None      => Option::None,
+				};
+				ret.add(val, &st);
+
+				return Result::Ok(Option::None);
+			},
+			Cmd::Assert{cond} =>
+			{
+				// Executions past the assertion satisfy its condition.
+				let cond_val = self.eval_bexp(cond, &mut st)?;
+				if cond_val == Option::Some(false)
+				{
+					return Result::Ok(Option::None);
+				}
+				return Result::Ok(refine(cond, true, &st));
+			},
+		}
+
+		Result::Ok(Option::Some(st))
+	}
+}
+
+fn havoc_globals(st : &AbsState) -> Result<AbsState, String>
+{
+	let mut res = st.clone();
+
+	for (_, var) in res.scopes[0].iter_mut()
+	{
+		var.v = AbsValue::top_of(&var.t)?;
+	}
+
+	Result::Ok(res)
+}
+
+fn cmp_intervals(e : &Bexp, l : &Interval, r : &Interval) -> Option<bool>
+{
+	let (l_lo, l_hi, r_lo, r_hi) = match (l, r)
+	{
+		(Interval::Range{lo : a, hi : b}, Interval::Range{lo : c, hi : d}) => (*a, *b, *c, *d),
+		_ => return Option::None,
+	};
+
+	let (always, never) = match e
+	{
+		Bexp::Lt{l:_, r:_}   => (l_hi < r_lo, l_lo >= r_hi),
+		Bexp::Lte{l:_, r:_}  => (l_hi <= r_lo, l_lo > r_hi),
+		Bexp::Gt{l:_, r:_}   => (l_lo > r_hi, l_hi <= r_lo),
+		Bexp::Gte{l:_, r:_}  => (l_lo >= r_hi, l_hi < r_lo),
+		Bexp::Aeq{l:_, r:_}  => (l_lo == l_hi && r_lo == r_hi && l_lo == r_lo, l.meet(r).is_empty()),
+		_                    => (l.meet(r).is_empty(), l_lo == l_hi && r_lo == r_hi && l_lo == r_lo),
+	};
+
+	if always { Option::Some(true) } else if never { Option::Some(false) } else { Option::None }
+}
+
+/// Side-effect free evaluation of an integer expression, used to refine conditions.
+fn eval_pure(e : &Aexp, st : &AbsState) -> Option<Interval>
+{
+	match e
+	{
+		Aexp::IntConst{v} => Option::Some(Interval::constant(*v as i64)),
+		Aexp::Var{v} => match st.get(&v.name)
+		{
+			Option::Some(AbsVar{t:_, v : AbsValue::Int(itv)}) => Option::Some(*itv),
+			_                                                  => Option::None,
+		},
+		Aexp::Add{l, r} => Option::Some(eval_pure(l, st)?.add(&eval_pure(r, st)?).wrap_to_i32()),
+		Aexp::Sub{l, r} => Option::Some(eval_pure(l, st)?.sub(&eval_pure(r, st)?).wrap_to_i32()),
+		Aexp::Mul{l, r} => Option::Some(eval_pure(l, st)?.mul(&eval_pure(r, st)?).wrap_to_i32()),
+		Aexp::Div{l, r} => Option::Some(eval_pure(l, st)?.div(&eval_pure(r, st)?).wrap_to_i32()),
+		Aexp::Mod{l, r} => Option::Some(eval_pure(l, st)?.rem(&eval_pure(r, st)?).wrap_to_i32()),
+		_ => Option::None,
+	}
+}
+
+/// Restrict the interval of `x` under the constraint `x <op> other`.
+fn restrict(op : &Bexp, x : &Interval, other : &Interval) -> Interval
+{
+	let (o_lo, o_hi) = match other
+	{
+		Interval::Range{lo, hi} => (*lo, *hi),
+		Interval::Empty         => return Interval::Empty,
+	};
+
+	match op
+	{
+		Bexp::Lt{l:_, r:_}  => x.meet(&Interval::new(std::i64::MIN, o_hi - 1)),
+		Bexp::Lte{l:_, r:_} => x.meet(&Interval::new(std::i64::MIN, o_hi)),
+		Bexp::Gt{l:_, r:_}  => x.meet(&Interval::new(o_lo + 1, std::i64::MAX)),
+		Bexp::Gte{l:_, r:_} => x.meet(&Interval::new(o_lo, std::i64::MAX)),
+		Bexp::Aeq{l:_, r:_} => x.meet(other),
+		_ => match (x, other.get_const())
+		{
+			(Interval::Range{lo, hi}, Option::Some(c)) if *lo == c => Interval::new(lo + 1, *hi),
+			(Interval::Range{lo, hi}, Option::Some(c)) if *hi == c => Interval::new(*lo, hi - 1),
+			_                                                      => *x,
+		},
+	}
+}
+
+/// The comparison `r <op'> l` that is equivalent to `l <op> r`, or its negation.
+fn flip_cmp(e : &Bexp, negate : bool) -> Bexp
+{
+	use ast::bexp::Bexp as B;
+	use std::boxed::Box;
+
+	let dummy = || Box::new(Aexp::IntConst{v : 0});
+	let (swapped, negated) = match e
+	{
+		B::Lt{l:_, r:_}   => (B::Gt{l : dummy(), r : dummy()},   B::Gte{l : dummy(), r : dummy()}),
+		B::Lte{l:_, r:_}  => (B::Gte{l : dummy(), r : dummy()},  B::Gt{l : dummy(), r : dummy()}),
+		B::Gt{l:_, r:_}   => (B::Lt{l : dummy(), r : dummy()},   B::Lte{l : dummy(), r : dummy()}),
+		B::Gte{l:_, r:_}  => (B::Lte{l : dummy(), r : dummy()},  B::Lt{l : dummy(), r : dummy()}),
+		B::Aeq{l:_, r:_}  => (B::Aeq{l : dummy(), r : dummy()},  B::Aneq{l : dummy(), r : dummy()}),
+		_                 => (B::Aneq{l : dummy(), r : dummy()}, B::Aeq{l : dummy(), r : dummy()}),
+	};
+
+	if negate { negated } else { swapped }
+}
+
+fn refine_var(st : &mut AbsState, e : &Aexp, op : &Bexp, other : &Interval) -> bool
+{
+	match e
+	{
+		Aexp::Var{v} => match st.get_mut(&v.name)
+		{
+			Option::Some(AbsVar{t:_, v : AbsValue::Int(itv)}) =>
+			{
+				*itv = restrict(op, itv, other);
+				!itv.is_empty()
+			},
+			_ => true,
+		},
+		_ => true,
+	}
+}
+
+/// Refine the state under the assumption that `cond` evaluates to `truth`.
+///
+/// Returns `Option::None` if the assumption can not hold.
+///
+fn refine(cond : &Bexp, truth : bool, st : &AbsState) -> Option<AbsState>
+{
+	match cond
+	{
+		Bexp::BoolConst{v} => if *v == truth { Option::Some(st.clone()) } else { Option::None },
+		Bexp::Not{e} => refine(e, !truth, st),
+		Bexp::And{l, r} | Bexp::Or{l, r} =>
+		{
+			let is_and = match cond { Bexp::And{l:_, r:_} => true, _ => false };
+
+			if is_and == truth
+			{
+				// Both sides hold (or both fail).
+				refine(r, truth, &refine(l, truth, st)?)
+			}
+			else
+			{
+				join_opt(refine(l, truth, st), refine(r, truth, st))
+			}
+		},
+		Bexp::Var{v} =>
+		{
+			let mut res = st.clone();
+			match res.get_mut(&v.name)
+			{
+				Option::Some(AbsVar{t:_, v : AbsValue::Bool(b)}) =>
+				{
+					if *b == Option::Some(!truth)
+					{
+						return Option::None;
+					}
+					*b = Option::Some(truth);
+				},
+				_ => {},
+			}
+			Option::Some(res)
+		},
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+		{
+			let (l_itv, r_itv) = match (eval_pure(l, st), eval_pure(r, st))
+			{
+				(Option::Some(a), Option::Some(b)) => (a, b),
+				_                                  => return Option::Some(st.clone()),
+			};
+
+			let op = if truth { flip_cmp(&flip_cmp(cond, false), false) } else { flip_cmp(cond, true) };
+			let op_swapped = flip_cmp(&op, false);
+
+			match cmp_intervals(&op, &l_itv, &r_itv)
+			{
+				Option::Some(false) => return Option::None,
+				_                   => {},
+			}
+
+			let mut res = st.clone();
+			if refine_var(&mut res, l, &op, &r_itv) && refine_var(&mut res, r, &op_swapped, &l_itv)
+			{
+				Option::Some(res)
+			}
+			else
+			{
+				Option::None
+			}
+		},
+		_ => Option::Some(st.clone()),
+	}
+}
+
+/// Run the interval analysis on a program, starting from its `entry` function(s).
+///
+/// The global commands of the program are executed first, and then every
+/// function named `entry` is analyzed with its parameters restricted to the
+/// given ranges. The returned report lists every `i32` arithmetic operation
+/// reachable from `entry`, with a verdict on division by zero (for `/` and `%`)
+/// and on overflow.
+///
+pub fn analyze_program(prog : &Cmd, entry_ranges : &ParamRanges) -> Result<AnalysisReport, String>
+{
+	let mut analyzer = Analyzer::new();
+	let mut ret = RetAcc::new();
+
+	let init_st = AbsState {scopes : vec![HashMap::new()]};
+	let mut global_st = match analyzer.exec_cmd(prog, Option::Some(init_st), &mut ret)?
+	{
+		Option::Some(s) => s,
+		Option::None    => return Result::Err(format!("{}", "Program root shouldn't contain return statement.")),
+	};
+
+	let mut entries : Vec<(String, Rc<FnProtoType>, Rc<Cmd>)> = analyzer.funcs.iter()
+		.filter(|(_, v)| v.0.name == "entry")
+		.map(|(k, v)| (k.clone(), v.0.clone(), v.1.clone()))
+		.collect();
+	entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+	if entries.len() == 0
+	{
+		return Result::Err(format!("{}", "Function entry is not declared."));
+	}
+
+	for (mangled_name, pt, fn_cmd) in entries.iter()
+	{
+		let mut arg_vals : Vec<AbsValue> = Vec::new();
+		for (i, var_decl) in pt.var_decl_list.iter().enumerate()
+		{
+			arg_vals.push(match var_decl.var_type
+			{
+				DataType::Int32 => AbsValue::Int(entry_ranges.get(i)),
+				_               => AbsValue::top_of(&var_decl.var_type)?,
+			});
+		}
+
+		let mut entry_st = global_st.clone();
+		analyzer.analyze_func(mangled_name, pt, fn_cmd, &mut entry_st, arg_vals)?;
+		global_st = join_opt(Option::Some(global_st), Option::Some(entry_st)).unwrap_or_else(|| AbsState {scopes : vec![HashMap::new()]});
+	}
+
+	Result::Ok(AnalysisReport {checks : analyzer.checks})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use ast::aexp::constructor_helper::*;
+	use ast::exp::constructor_helper::*;
+	use ast::cmd::constructor_helper::*;
+	use ast::var_general::VarDecl;
+	use ast::var_general::VarRef;
+
+	#[test]
+	fn division_by_zero_depends_on_the_entry_range()
+	{
+		/* Program:
+		 * fn entry(a : Int32) -> Int32 { return 10 / a; }
+		 */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "a".to_string())]);
+		let prog = fn_dc(fn_prototype, ret(Some((10i32.to_aexp() / "a".to_aexp()).to_exp())));
+
+		let report = analyze_program(&prog, &ParamRanges::new(vec![Interval::new(-1, 1)])).unwrap();
+		let alarms = report.alarms();
+		assert_eq!(alarms.len(), 1);
+		assert_eq!(alarms[0].kind, CheckKind::DivByZero);
+		assert_eq!(alarms[0].range, Interval::new(-1, 1));
+
+		let report = analyze_program(&prog, &ParamRanges::new(vec![Interval::new(1, 5)])).unwrap();
+		assert!(report.is_safe());
+		assert!(report.checks.iter().any(|c| c.kind == CheckKind::DivByZero && c.verdict == Verdict::Proven));
+	}
+
+	/// The loop counter is widened to the `i32` limits, then cut back by the
+	/// loop condition when it is a bound on the counter itself.
+	#[test]
+	fn widening_terminates_on_unbounded_counters()
+	{
+		let ranges = ParamRanges::new(vec![Interval::new(0, 100)]);
+
+		/* Program:
+		 * fn entry(n : Int32) -> Int32 { Int32 i; i = 0; while (i < 10) { i = i + 1; } return i; }
+		 */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]);
+		let i_dec = var_dc(VarDecl::new(DataType::Int32, "i".to_string()));
+		let i_asg = assign(VarRef::from_str("i"), 0i32.to_aexp().to_exp());
+		let lp = wh_lp("i".to_aexp().lt(10i32.to_aexp()), assign(VarRef::from_str("i"), ("i".to_aexp() + 1i32.to_aexp()).to_exp()));
+		let prog = fn_dc(fn_prototype, seq(i_dec, seq(i_asg, seq(lp, ret(Some("i".to_aexp().to_exp()))))));
+
+		let report = analyze_program(&prog, &ranges).unwrap();
+		assert!(report.is_safe());
+
+		/* Program:
+		 * fn entry(n : Int32) -> Int32 { Int32 i; i = 0; while (n > 0) { i = i + 1; } return i; }
+		 */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]);
+		let i_dec = var_dc(VarDecl::new(DataType::Int32, "i".to_string()));
+		let i_asg = assign(VarRef::from_str("i"), 0i32.to_aexp().to_exp());
+		let lp = wh_lp("n".to_aexp().gt(0i32.to_aexp()), assign(VarRef::from_str("i"), ("i".to_aexp() + 1i32.to_aexp()).to_exp()));
+		let prog = fn_dc(fn_prototype, seq(i_dec, seq(i_asg, seq(lp, ret(Some("i".to_aexp().to_exp()))))));
+
+		let report = analyze_program(&prog, &ranges).unwrap();
+		let alarms = report.alarms();
+		assert_eq!(alarms.len(), 1);
+		assert_eq!(alarms[0].kind, CheckKind::Overflow);
+		assert!(alarms[0].range.contains(std::i32::MAX as i64 + 1));
+	}
+}
@@ -0,0 +1,1062 @@
+use std::fmt;
+use std::rc::Rc;
+use std::vec::Vec;
+use std::string::String;
+use std::string::ToString;
+use std::collections::HashMap;
+
+use super::super::ast;
+use super::super::typing;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::cmd::Cmd;
+use ast::exp::Exp;
+use ast::data_type::DataType;
+use ast::func_general::FnCall;
+use ast::func_general::FnProtoType;
+use ast::func_general::RESULT_VAR_NAME;
+use ast::var_general::VarDecl;
+
+use super::sat;
+use super::sat::Lit;
+use super::sat::SatResult;
+use super::sat::Solver;
+
+/// Number of bits of an `i32`.
+const INT_WIDTH : usize = 32;
+
+/// Limits of the bounded model checking.
+#[derive(Clone, Copy)]
+pub struct BmcConfig
+{
+	/// Maximum number of iterations of a loop, and of nested calls of the
+	/// same function, that are encoded.
+	pub unwind : usize,
+	/// Maximum number of conflicts of the SAT solver for each check.
+	pub max_conflicts : u64,
+}
+
+impl BmcConfig
+{
+	pub fn new(unwind : usize) -> BmcConfig
+	{
+		BmcConfig { unwind : unwind, max_conflicts : sat::DEFAULT_MAX_CONFLICTS }
+	}
+}
+
+/// Outcome of one check.
+#[derive(Clone)]
+pub enum CheckResult
+{
+	/// No input of `entry` violates the check within the unwinding bound.
+	Holds,
+	/// The parameters of an `entry` call that violates the check; they can
+	/// be passed to the interpreter as they are.
+	Violated(Vec<Exp>),
+	/// The SAT solver gave up.
+	Unknown,
+}
+
+/// A check at a point of the program, e.g. a postcondition of a function,
+/// or the absence of overflow in an arithmetic expression.
+#[derive(Clone)]
+pub struct BmcCheck
+{
+	pub what : String,
+	pub result : CheckResult,
+}
+
+/// Result of the bounded model checking of a program.
+pub struct BmcReport
+{
+	pub params : Vec<VarDecl>,
+	pub unwind : usize,
+	pub checks : Vec<BmcCheck>,
+	/// No execution needs more iterations or nested calls than the unwinding
+	/// bound, so the checks that hold within the bound hold for all inputs.
+	pub is_complete : bool,
+}
+
+impl BmcReport
+{
+	/// No check is violated, or unknown.
+	pub fn is_safe(&self) -> bool
+	{
+		self.checks.iter().all(|c| match c.result { CheckResult::Holds => true, _ => false })
+	}
+
+	/// The first violated check and its counterexample.
+	pub fn first_violation(&self) -> Option<(&BmcCheck, &Vec<Exp>)>
+	{
+		self.checks.iter()
+			.filter_map(|c| match &c.result
+			{
+				CheckResult::Violated(params) => Option::Some((c, params)),
+				_                             => Option::None,
+			})
+			.next()
+	}
+}
+
+impl fmt::Display for BmcReport
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		let params_str : Vec<String> = self.params.iter().map(|p| p.to_string()).collect();
+		writeln!(f, "Bounded model checking of entry({}), unwinding {} time(s):", params_str.join(", "), self.unwind)?;
+
+		for check in self.checks.iter()
+		{
+			match &check.result
+			{
+				CheckResult::Holds            => writeln!(f, "\t[holds]    {}", check.what)?,
+				CheckResult::Unknown          => writeln!(f, "\t[unknown]  {}", check.what)?,
+				CheckResult::Violated(params) =>
+				{
+					let vals_str : Vec<String> = params.iter().map(|e| e.to_string()).collect();
+					writeln!(f, "\t[violated] {}; counterexample: entry({})", check.what, vals_str.join(", "))?
+				},
+			}
+		}
+
+		if self.is_complete
+		{
+			writeln!(f, "The unwinding bound covers all executions.")?;
+		}
+		else
+		{
+			writeln!(f, "Some executions exceed the unwinding bound; they are not checked.")?;
+		}
+
+		let num_holds = self.checks.iter().filter(|c| match c.result { CheckResult::Holds => true, _ => false }).count();
+		write!(f, "{} of {} check(s) hold.", num_holds, self.checks.len())
+	}
+}
+
+/// Bits of an `i32`, the least significant one first.
+type Word = Vec<Lit>;
+
+#[derive(Clone)]
+enum Val
+{
+	Int(Word),
+	Bool(Lit),
+}
+
+#[derive(Clone)]
+struct Slot
+{
+	val : Val,
+	/// The variable has been assigned.
+	init : Lit,
+}
+
+/// Values of the variables, and the condition under which the current
+/// point of the program is reached without any failure or return.
+#[derive(Clone)]
+struct State
+{
+	scopes : Vec<HashMap<String, Slot>>,
+	active : Lit,
+}
+
+/// A function being encoded.
+struct Frame
+{
+	name : String,
+	ret : Option<Val>,
+	/// The function has returned through a return statement.
+	returned : Lit,
+}
+
+fn mangle_fn_name(name : &String, types : &Vec<DataType>) -> String
+{
+	let mut mangled_fun_name = String::new();
+	mangled_fun_name.push_str(name);
+	mangled_fun_name.push('_');
+
+	for t in types.iter()
+	{
+		mangled_fun_name.push_str(&t.to_string());
+		mangled_fun_name.push('_');
+	}
+
+	mangled_fun_name
+}
+
+fn unsupported_float<T>() -> Result<T, String>
+{
+	Result::Err(format!("{}", "Floating-point values are not supported by the bounded model checker."))
+}
+
+/// Encoder of a program into a circuit; the arithmetic follows `i32`
+/// semantics bit by bit, i.e. two's complement with truncating division.
+struct Encoder
+{
+	config : BmcConfig,
+	s : Solver,
+	funcs : HashMap<String, (Rc<FnProtoType>, Rc<Cmd>)>,
+	frames : Vec<Frame>,
+	/// Number of nested encodings of each function.
+	depth : HashMap<String, usize>,
+	/// Checks and the conditions under which they fail.
+	checks : Vec<(String, Vec<Lit>)>,
+	/// Conditions under which an execution exceeds the unwinding bound.
+	cuts : Vec<Lit>,
+}
+
+impl Encoder
+{
+	// ---- Words:
+
+	fn const_word(&self, v : i32) -> Word
+	{
+		(0..INT_WIDTH).map(|i| self.s.lit_const(((v >> i) & 1) != 0)).collect()
+	}
+
+	fn fresh_word(&mut self) -> Word
+	{
+		(0..INT_WIDTH).map(|_| self.s.new_var()).collect()
+	}
+
+	fn full_add(&mut self, a : Lit, b : Lit, c : Lit) -> (Lit, Lit)
+	{
+		let t = self.s.xor(a, b);
+		let sum = self.s.xor(t, c);
+		let ab = self.s.and(a, b);
+		let ct = self.s.and(c, t);
+		let carry = self.s.or(ab, ct);
+		(sum, carry)
+	}
+
+	/// Sum of two words of the same width, and the carry out.
+	fn add_words(&mut self, a : &[Lit], b : &[Lit], carry_in : Lit) -> (Word, Lit)
+	{
+		let mut carry = carry_in;
+		let mut sum : Word = Vec::with_capacity(a.len());
+		for (x, y) in a.iter().zip(b.iter())
+		{
+			let (s, c) = self.full_add(*x, *y, carry);
+			sum.push(s);
+			carry = c;
+		}
+		(sum, carry)
+	}
+
+	fn not_word(a : &[Lit]) -> Word
+	{
+		a.iter().map(|x| !*x).collect()
+	}
+
+	fn neg_word(&mut self, a : &[Lit]) -> Word
+	{
+		let zeros : Word = vec![self.s.lit_false(); a.len()];
+		let t = self.s.lit_true();
+		self.add_words(&Encoder::not_word(a), &zeros, t).0
+	}
+
+	fn mux_word(&mut self, sel : Lit, a : &[Lit], b : &[Lit]) -> Word
+	{
+		a.iter().zip(b.iter()).map(|(x, y)| self.s.mux(sel, *x, *y)).collect()
+	}
+
+	fn is_zero(&mut self, a : &[Lit]) -> Lit
+	{
+		!self.s.or_all(a)
+	}
+
+	fn eq_words(&mut self, a : &[Lit], b : &[Lit]) -> Lit
+	{
+		let diffs : Vec<Lit> = a.iter().zip(b.iter()).map(|(x, y)| self.s.xor(*x, *y)).collect();
+		!self.s.or_all(&diffs)
+	}
+
+	/// Unsigned `a < b`.
+	fn ult(&mut self, a : &[Lit], b : &[Lit]) -> Lit
+	{
+		// a - b borrows iff a < b.
+		let t = self.s.lit_true();
+		!self.add_words(a, &Encoder::not_word(b), t).1
+	}
+
+	/// Signed `a < b`.
+	fn slt(&mut self, a : &[Lit], b : &[Lit]) -> Lit
+	{
+		let mut a_flip = a.to_vec();
+		let mut b_flip = b.to_vec();
+		a_flip[INT_WIDTH - 1] = !a[INT_WIDTH - 1];
+		b_flip[INT_WIDTH - 1] = !b[INT_WIDTH - 1];
+		self.ult(&a_flip, &b_flip)
+	}
+
+	fn abs_word(&mut self, a : &[Lit]) -> Word
+	{
+		let neg = self.neg_word(a);
+		self.mux_word(a[INT_WIDTH - 1], &neg, a)
+	}
+
+	/// Unsigned product of two 32-bit words, in 64 bits.
+	fn umul_wide(&mut self, a : &[Lit], b : &[Lit]) -> Word
+	{
+		let f = self.s.lit_false();
+		let mut acc : Word = vec![f; 2 * INT_WIDTH];
+		for i in 0..INT_WIDTH
+		{
+			let partial : Word = a.iter().map(|x| self.s.and(*x, b[i])).collect();
+			// The sum of the rows so far is below 2^(32 + i).
+			let (sum, carry) = self.add_words(&acc[i..(i + INT_WIDTH)], &partial, f);
+			acc[i..(i + INT_WIDTH)].copy_from_slice(&sum);
+			acc[i + INT_WIDTH] = carry;
+		}
+		acc
+	}
+
+	/// Unsigned quotient and remainder (restoring division).
+	fn udiv(&mut self, a : &[Lit], b : &[Lit]) -> (Word, Word)
+	{
+		let f = self.s.lit_false();
+		let t = self.s.lit_true();
+		let mut rem : Word = vec![f; INT_WIDTH];
+		let mut quot : Word = vec![f; INT_WIDTH];
+		let not_b = Encoder::not_word(b);
+		for i in (0..INT_WIDTH).rev()
+		{
+			// The remainder is below the divisor, so shifting it loses no bit.
+			rem.pop();
+			rem.insert(0, a[i]);
+			let (diff, no_borrow) = self.add_words(&rem, &not_b, t);
+			rem = self.mux_word(no_borrow, &diff, &rem);
+			quot[i] = no_borrow;
+		}
+		(quot, rem)
+	}
+
+	fn mux_val(&mut self, sel : Lit, a : &Val, b : &Val) -> Result<Val, String>
+	{
+		match (a, b)
+		{
+			(Val::Int(x), Val::Int(y))   => Result::Ok(Val::Int(self.mux_word(sel, x, y))),
+			(Val::Bool(x), Val::Bool(y)) => Result::Ok(Val::Bool(self.s.mux(sel, *x, *y))),
+			_                            => Result::Err(format!("{}", "Values of different types are merged.")),
+		}
+	}
+
+	fn fresh_val(&mut self, t : &DataType) -> Result<Val, String>
+	{
+		match t
+		{
+			DataType::Int32   => Result::Ok(Val::Int(self.fresh_word())),
+			DataType::Bool    => Result::Ok(Val::Bool(self.s.new_var())),
+			DataType::Float32 => unsupported_float(),
+			DataType::Void    => Result::Err(format!("{}", "There is no value of type void.")),
+		}
+	}
+
+	// ---- Checks:
+
+	fn context(&self) -> String
+	{
+		match self.frames.last()
+		{
+			Option::Some(frame) => frame.name.clone(),
+			Option::None        => format!("{}", "global commands"),
+		}
+	}
+
+	/// Record a check that fails if `bad` holds; executions go on only if it doesn't.
+	fn check(&mut self, st : &mut State, what : String, bad : Lit)
+	{
+		if bad == self.s.lit_false()
+		{
+			return;
+		}
+
+		let what = format!("{} in {}", what, self.context());
+		let fail = self.s.and(st.active, bad);
+
+		match self.checks.iter_mut().find(|c| c.0 == what)
+		{
+			Option::Some(c) => c.1.push(fail),
+			Option::None    => self.checks.push((what, vec![fail])),
+		}
+
+		st.active = self.s.and(st.active, !bad);
+	}
+
+	/// Stop the executions that exceed the unwinding bound here.
+	fn cut(&mut self, st : &mut State, cond : Lit)
+	{
+		let exceed = self.s.and(st.active, cond);
+		self.cuts.push(exceed);
+		st.active = self.s.and(st.active, !cond);
+	}
+
+	fn is_dead(&self, st : &State) -> bool
+	{
+		st.active == self.s.lit_false()
+	}
+
+	// ---- Expressions:
+
+	fn read_var(&mut self, st : &mut State, name : &String) -> Result<Val, String>
+	{
+		let slot = match st.scopes.iter().rev().find_map(|sc| sc.get(name))
+		{
+			Option::Some(slot) => slot.clone(),
+			Option::None       => return Result::Err(format!("Variable {} hasn't been declared.", name)),
+		};
+
+		self.check(st, format!("{} is initialized before read", name), !slot.init);
+
+		Result::Ok(slot.val)
+	}
+
+	fn eval_aexp(&mut self, e : &Aexp, st : &mut State) -> Result<Word, String>
+	{
+		match e
+		{
+			Aexp::IntConst{v} => Result::Ok(self.const_word(*v)),
+			Aexp::FloConst{..} => unsupported_float(),
+			Aexp::Add{l, r} =>
+			{
+				let a = self.eval_aexp(l, st)?;
+				let b = self.eval_aexp(r, st)?;
+				let f = self.s.lit_false();
+				let (sum, _) = self.add_words(&a, &b, f);
+
+				// Operands of the same sign, and a sum of the other sign.
+				let same_sign = !self.s.xor(a[INT_WIDTH - 1], b[INT_WIDTH - 1]);
+				let sign_flip = self.s.xor(sum[INT_WIDTH - 1], a[INT_WIDTH - 1]);
+				let overflow = self.s.and(same_sign, sign_flip);
+				self.check(st, format!("no overflow in {}", e), overflow);
+
+				Result::Ok(sum)
+			},
+			Aexp::Sub{l, r} =>
+			{
+				let a = self.eval_aexp(l, st)?;
+				let b = self.eval_aexp(r, st)?;
+				let t = self.s.lit_true();
+				let (diff, _) = self.add_words(&a, &Encoder::not_word(&b), t);
+
+				// Operands of different signs, and a difference of the sign of b.
+				let diff_sign = self.s.xor(a[INT_WIDTH - 1], b[INT_WIDTH - 1]);
+				let sign_flip = self.s.xor(diff[INT_WIDTH - 1], a[INT_WIDTH - 1]);
+				let overflow = self.s.and(diff_sign, sign_flip);
+				self.check(st, format!("no overflow in {}", e), overflow);
+
+				Result::Ok(diff)
+			},
+			Aexp::Mul{l, r} =>
+			{
+				let a = self.eval_aexp(l, st)?;
+				let b = self.eval_aexp(r, st)?;
+				let abs_a = self.abs_word(&a);
+				let abs_b = self.abs_word(&b);
+				let prod = self.umul_wide(&abs_a, &abs_b);
+
+				let is_neg = self.s.xor(a[INT_WIDTH - 1], b[INT_WIDTH - 1]);
+				let low = prod[0..INT_WIDTH].to_vec();
+				let neg_low = self.neg_word(&low);
+				let res = self.mux_word(is_neg, &neg_low, &low);
+
+				// |a * b| must be below 2^31, or equal to it for a negative product.
+				let high_set = self.s.or_all(&prod[INT_WIDTH..]);
+				let pos_overflow = self.s.or(high_set, prod[INT_WIDTH - 1]);
+				let low_set = self.s.or_all(&prod[0..(INT_WIDTH - 1)]);
+				let above_min = self.s.and(prod[INT_WIDTH - 1], low_set);
+				let neg_overflow = self.s.or(high_set, above_min);
+				let overflow = self.s.mux(is_neg, neg_overflow, pos_overflow);
+				self.check(st, format!("no overflow in {}", e), overflow);
+
+				Result::Ok(res)
+			},
+			Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				let a = self.eval_aexp(l, st)?;
+				let b = self.eval_aexp(r, st)?;
+
+				let is_zero = self.is_zero(&b);
+				self.check(st, format!("no division by zero in {}", e), is_zero);
+
+				let min = self.const_word(i32::MIN);
+				let minus_one = self.const_word(-1);
+				let a_is_min = self.eq_words(&a, &min);
+				let b_is_minus_one = self.eq_words(&b, &minus_one);
+				let overflow = self.s.and(a_is_min, b_is_minus_one);
+				self.check(st, format!("no overflow in {}", e), overflow);
+
+				let abs_a = self.abs_word(&a);
+				let abs_b = self.abs_word(&b);
+				let (quot, rem) = self.udiv(&abs_a, &abs_b);
+
+				// Truncating division: the remainder has the sign of a.
+				Result::Ok(match e
+				{
+					Aexp::Div{..} =>
+					{
+						let is_neg = self.s.xor(a[INT_WIDTH - 1], b[INT_WIDTH - 1]);
+						let neg_quot = self.neg_word(&quot);
+						self.mux_word(is_neg, &neg_quot, &quot)
+					},
+					_             =>
+					{
+						let neg_rem = self.neg_word(&rem);
+						self.mux_word(a[INT_WIDTH - 1], &neg_rem, &rem)
+					},
+				})
+			},
+			Aexp::Var{v} =>
+			{
+				match self.read_var(st, &v.name)?
+				{
+					Val::Int(w)  => Result::Ok(w),
+					Val::Bool(_) => Result::Err(format!("Variable {} isn't an integer.", v.name)),
+				}
+			},
+			Aexp::FnCall{fc} =>
+			{
+				match self.call_value(fc, st)?
+				{
+					Val::Int(w)  => Result::Ok(w),
+					Val::Bool(_) => Result::Err(format!("Function {} doesn't return an integer.", fc.name)),
+				}
+			},
+		}
+	}
+
+	fn eval_bexp(&mut self, e : &Bexp, st : &mut State) -> Result<Lit, String>
+	{
+		// Both operands of && and || are evaluated, as by the interpreter.
+		match e
+		{
+			Bexp::BoolConst{v} => Result::Ok(self.s.lit_const(*v)),
+			Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+			{
+				let a = self.eval_bexp(l, st)?;
+				let b = self.eval_bexp(r, st)?;
+				Result::Ok(match e
+				{
+					Bexp::Beq{..}  => !self.s.xor(a, b),
+					Bexp::Bneq{..} => self.s.xor(a, b),
+					Bexp::And{..}  => self.s.and(a, b),
+					_              => self.s.or(a, b),
+				})
+			},
+			Bexp::Not{e} => Result::Ok(!self.eval_bexp(e, st)?),
+			Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+			{
+				let a = self.eval_aexp(l, st)?;
+				let b = self.eval_aexp(r, st)?;
+				Result::Ok(match e
+				{
+					Bexp::Aeq{..}  => self.eq_words(&a, &b),
+					Bexp::Aneq{..} => !self.eq_words(&a, &b),
+					Bexp::Lt{..}   => self.slt(&a, &b),
+					Bexp::Lte{..}  => !self.slt(&b, &a),
+					Bexp::Gt{..}   => self.slt(&b, &a),
+					_              => !self.slt(&a, &b),
+				})
+			},
+			Bexp::Var{v} =>
+			{
+				match self.read_var(st, &v.name)?
+				{
+					Val::Bool(b) => Result::Ok(b),
+					Val::Int(_)  => Result::Err(format!("Variable {} isn't a boolean.", v.name)),
+				}
+			},
+			Bexp::FnCall{fc} =>
+			{
+				match self.call_value(fc, st)?
+				{
+					Val::Bool(b) => Result::Ok(b),
+					Val::Int(_)  => Result::Err(format!("Function {} doesn't return a boolean.", fc.name)),
+				}
+			},
+		}
+	}
+
+	fn eval_exp(&mut self, e : &Exp, st : &mut State) -> Result<Val, String>
+	{
+		match e
+		{
+			Exp::A{e} => Result::Ok(Val::Int(self.eval_aexp(e, st)?)),
+			Exp::B{e} => Result::Ok(Val::Bool(self.eval_bexp(e, st)?)),
+		}
+	}
+
+	// ---- Calls:
+
+	/// Encode a call whose value is used.
+	fn call_value(&mut self, fc : &FnCall, st : &mut State) -> Result<Val, String>
+	{
+		let (ret, fell_off) = self.call(fc, st)?;
+		self.check(st, format!("{} returns a value", fc.name), fell_off);
+
+		match ret
+		{
+			Option::Some(v) => Result::Ok(v),
+			Option::None    => Result::Err(format!("Function {} doesn't return a value.", fc.name)),
+		}
+	}
+
+	/// Encode a call; returns the returned value and the condition under
+	/// which the callee ends without a return statement.
+	fn call(&mut self, fc : &FnCall, st : &mut State) -> Result<(Option<Val>, Lit), String>
+	{
+		let mut args : Vec<Val> = Vec::new();
+		let mut types : Vec<DataType> = Vec::new();
+		for e in fc.exp_list.iter()
+		{
+			let v = self.eval_exp(e, st)?;
+			types.push(match v { Val::Int(_) => DataType::Int32, Val::Bool(_) => DataType::Bool });
+			args.push(v);
+		}
+
+		let types = typing::resolve_call(fc, &types, self.funcs.values().map(|v| &*v.0))?;
+		let mangled = mangle_fn_name(&fc.name, &types);
+		let (pt, fn_cmd) = match self.funcs.get(&mangled)
+		{
+			Option::Some(v) => v.clone(),
+			Option::None    => return Result::Err(format!("Function {} hasn't been declared.", fc)),
+		};
+
+		let depth = self.depth.get(&mangled).cloned().unwrap_or(0);
+		if depth >= self.config.unwind
+		{
+			let t = self.s.lit_true();
+			self.cut(st, t);
+			let ret = match pt.ret_type
+			{
+				DataType::Void => Option::None,
+				t              => Option::Some(self.fresh_val(&t)?),
+			};
+			return Result::Ok((ret, self.s.lit_false()));
+		}
+
+		let t = self.s.lit_true();
+		let mut params : HashMap<String, Slot> = HashMap::new();
+		for (decl, v) in pt.var_decl_list.iter().zip(args.into_iter())
+		{
+			params.insert(decl.name.clone(), Slot { val : v, init : t });
+		}
+		let entry_params = params.clone();
+
+		let mut callee_st = State { scopes : vec![st.scopes[0].clone(), params], active : st.active };
+
+		for req in pt.requires.iter()
+		{
+			let c = self.eval_bexp(req, &mut callee_st)?;
+			self.check(&mut callee_st, format!("precondition {} of call {}", req, fc), !c);
+		}
+
+		let (ret, fell_off, exit_st) = self.exec_fn(&pt, &fn_cmd, &mangled, callee_st, entry_params)?;
+
+		st.scopes[0] = exit_st.scopes[0].clone();
+		st.active = exit_st.active;
+
+		Result::Ok((ret, fell_off))
+	}
+
+	/// Encode the body of a function and check its postconditions.
+	fn exec_fn(&mut self, pt : &FnProtoType, fn_cmd : &Cmd, mangled : &String, mut st : State, entry_params : HashMap<String, Slot>)
+		-> Result<(Option<Val>, Lit, State), String>
+	{
+		let f = self.s.lit_false();
+		self.frames.push(Frame { name : pt.name.clone(), ret : Option::None, returned : f });
+		*self.depth.entry(mangled.clone()).or_insert(0) += 1;
+
+		self.exec_cmd(fn_cmd, &mut st)?;
+
+		let frame = self.frames.pop().unwrap();
+		let fell_off = st.active;
+
+		let ret = match (&pt.ret_type, frame.ret)
+		{
+			(DataType::Void, _)         => Option::None,
+			(_, Option::Some(v))        => Option::Some(v),
+			(t, Option::None)           => Option::Some(self.fresh_val(t)?),
+		};
+
+		// Postconditions refer to the parameters' values on entry.
+		let mut post_scope = entry_params;
+		if let Option::Some(v) = &ret
+		{
+			let t = self.s.lit_true();
+			post_scope.insert(RESULT_VAR_NAME.to_string(), Slot { val : v.clone(), init : t });
+		}
+		let exit_active = self.s.or(frame.returned, st.active);
+		let mut post_st = State { scopes : vec![st.scopes[0].clone(), post_scope], active : exit_active };
+
+		self.frames.push(Frame { name : pt.name.clone(), ret : Option::None, returned : f });
+		for ens in pt.ensures.iter()
+		{
+			let c = self.eval_bexp(ens, &mut post_st)?;
+			self.check(&mut post_st, format!("postcondition {} of {}", ens, pt.name), !c);
+		}
+		self.frames.pop();
+
+		if let Option::Some(d) = self.depth.get_mut(mangled)
+		{
+			*d -= 1;
+		}
+
+		Result::Ok((ret, fell_off, post_st))
+	}
+
+	// ---- Commands:
+
+	fn assign(&mut self, st : &mut State, name : &String, v : Val) -> Result<(), String>
+	{
+		let active = st.active;
+		let idx = match st.scopes.iter().rposition(|sc| sc.contains_key(name))
+		{
+			Option::Some(i) => i,
+			Option::None    => return Result::Err(format!("Variable {} hasn't been declared.", name)),
+		};
+
+		// The assignment only happens on the executions that reach it.
+		let old = st.scopes[idx][name].clone();
+		let val = self.mux_val(active, &v, &old.val)?;
+		let init = self.s.or(active, old.init);
+		st.scopes[idx].insert(name.clone(), Slot { val : val, init : init });
+
+		Result::Ok(())
+	}
+
+	/// Merge the states after the branches of a condition `c`.
+	fn merge(&mut self, c : Lit, tr_st : &State, fa_st : &State) -> Result<State, String>
+	{
+		let mut scopes : Vec<HashMap<String, Slot>> = Vec::new();
+		for (tr_sc, fa_sc) in tr_st.scopes.iter().zip(fa_st.scopes.iter())
+		{
+			let mut sc : HashMap<String, Slot> = HashMap::new();
+			for (name, fa_slot) in fa_sc.iter()
+			{
+				let slot = match tr_sc.get(name)
+				{
+					Option::Some(tr_slot) =>
+					{
+						let val = self.mux_val(c, &tr_slot.val, &fa_slot.val)?;
+						let init = self.s.mux(c, tr_slot.init, fa_slot.init);
+						Slot { val : val, init : init }
+					},
+					Option::None          => fa_slot.clone(),
+				};
+				sc.insert(name.clone(), slot);
+			}
+			scopes.push(sc);
+		}
+
+		let active = self.s.or(tr_st.active, fa_st.active);
+
+		Result::Ok(State { scopes : scopes, active : active })
+	}
+
+	/// Encode a command in a new scope.
+	fn exec_block(&mut self, c : &Cmd, st : &mut State) -> Result<(), String>
+	{
+		st.scopes.push(HashMap::new());
+		self.exec_cmd(c, st)?;
+		st.scopes.pop();
+		Result::Ok(())
+	}
+
+	fn check_invariants(&mut self, invariants : &Vec<Bexp>, st : &mut State, when : &str) -> Result<(), String>
+	{
+		for inv in invariants.iter()
+		{
+			let c = self.eval_bexp(inv, st)?;
+			self.check(st, format!("loop invariant {} {}", inv, when), !c);
+		}
+		Result::Ok(())
+	}
+
+	/// Unroll the `iter`-th iteration of a loop, and the ones after it.
+	fn exec_loop(&mut self, cond : &Bexp, lp_cmd : &Cmd, bound : &Option<u64>, invariants : &Vec<Bexp>, st : &mut State, iter : usize)
+		-> Result<(), String>
+	{
+		if self.is_dead(st)
+		{
+			return Result::Ok(());
+		}
+
+		let c = self.eval_bexp(cond, st)?;
+
+		let is_bounded = match bound { Option::Some(b) => *b <= (self.config.unwind as u64), Option::None => false };
+		let limit = match bound { Option::Some(b) if is_bounded => *b as usize, _ => self.config.unwind };
+		if iter >= limit
+		{
+			if is_bounded
+			{
+				self.check(st, format!("loop bound of {} iteration(s) is respected", limit), c);
+			}
+			else
+			{
+				self.cut(st, c);
+			}
+			return Result::Ok(());
+		}
+
+		let mut body_st = st.clone();
+		body_st.active = self.s.and(st.active, c);
+		if !self.is_dead(&body_st)
+		{
+			self.exec_block(lp_cmd, &mut body_st)?;
+			self.check_invariants(invariants, &mut body_st, "is preserved")?;
+			self.exec_loop(cond, lp_cmd, bound, invariants, &mut body_st, iter + 1)?;
+		}
+
+		st.active = self.s.and(st.active, !c);
+		*st = self.merge(c, &body_st, st)?;
+
+		Result::Ok(())
+	}
+
+	fn exec_cmd(&mut self, c : &Cmd, st : &mut State) -> Result<(), String>
+	{
+		if self.is_dead(st)
+		{
+			return Result::Ok(());
+		}
+
+		match c
+		{
+			Cmd::Skip => {},
+			Cmd::VarDecl{d} =>
+			{
+				let zero = match d.var_type
+				{
+					DataType::Int32   => Val::Int(self.const_word(0)),
+					DataType::Bool    => Val::Bool(self.s.lit_false()),
+					DataType::Float32 => return unsupported_float(),
+					DataType::Void    => return Result::Err(format!("Variable {} is declared as void.", d.name)),
+				};
+
+				let f = self.s.lit_false();
+				let scope = st.scopes.last_mut().unwrap();
+				if scope.contains_key(&d.name)
+				{
+					return Result::Err(format!("Variable {} is declared repeatedly.", d.name));
+				}
+				scope.insert(d.name.clone(), Slot { val : zero, init : f });
+			},
+			Cmd::Assign{var, e} =>
+			{
+				let v = self.eval_exp(e, st)?;
+				self.assign(st, &var.name, v)?;
+			},
+			Cmd::FnCall{fc} =>
+			{
+				self.call(fc, st)?;
+			},
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				let c = self.eval_bexp(cond, st)?;
+
+				let mut tr_st = st.clone();
+				tr_st.active = self.s.and(st.active, c);
+				self.exec_block(tr_cmd, &mut tr_st)?;
+
+				let mut fa_st = st.clone();
+				fa_st.active = self.s.and(st.active, !c);
+				self.exec_block(fa_cmd, &mut fa_st)?;
+
+				*st = self.merge(c, &tr_st, &fa_st)?;
+			},
+			Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
+			{
+				self.check_invariants(invariants, st, "holds on entry")?;
+				self.exec_loop(cond, lp_cmd, bound, invariants, st, 0)?;
+			},
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				self.exec_cmd(fst_cmd, st)?;
+				self.exec_cmd(snd_cmd, st)?;
+			},
+			Cmd::FnDecl{prototype, fn_cmd} =>
+			{
+				let types : Vec<DataType> = prototype.var_decl_list.iter().map(|d| d.var_type).collect();
+				let mangled = mangle_fn_name(&prototype.name, &types);
+				if self.funcs.contains_key(&mangled)
+				{
+					return Result::Err(format!("Function named {} has already been declared.", prototype.name));
+				}
+				self.funcs.insert(mangled, (prototype.clone(), fn_cmd.clone()));
+			},
+			Cmd::Return{e} =>
+			{
+				let v = match e
+				{
+					Option::Some(e) => Option::Some(self.eval_exp(e, st)?),
+					Option::None    => Option::None,
+				};
+
+				let active = st.active;
+				let ret = match (&v, self.frames.last().map(|f| f.ret.clone()))
+				{
+					(_, Option::None)                         => return Result::Err(format!("{}", "Program root shouldn't contain return statement.")),
+					(Option::Some(v), Option::Some(Option::Some(old))) => Option::Some(self.mux_val(active, v, &old)?),
+					(v, Option::Some(_))                      => v.clone(),
+				};
+
+				let frame = self.frames.last_mut().unwrap();
+				frame.ret = ret;
+				let returned = frame.returned;
+				let returned = self.s.or(returned, active);
+				self.frames.last_mut().unwrap().returned = returned;
+
+				st.active = self.s.lit_false();
+			},
+			Cmd::Assert{cond} =>
+			{
+				let c = self.eval_bexp(cond, st)?;
+				self.check(st, format!("assertion {} holds", cond), !c);
+			},
+		}
+
+		Result::Ok(())
+	}
+
+	// ---- Solving:
+
+	fn param_list(&self, params : &Vec<(VarDecl, Val)>) -> Vec<Exp>
+	{
+		params.iter()
+			.map(|(_, v)| match v
+			{
+				Val::Int(w)  =>
+				{
+					let bits = w.iter().enumerate().fold(0u32, |acc, (i, b)| acc | ((self.s.model_value(*b) as u32) << i));
+					Exp::A { e : Aexp::IntConst { v : bits as i32 } }
+				},
+				Val::Bool(b) => Exp::B { e : Bexp::BoolConst { v : self.s.model_value(*b) } },
+			})
+			.collect()
+	}
+}
+
+/// Check the `entry` function of a program up to an unwinding bound.
+///
+/// The global commands of the program are encoded first; then `entry` is
+/// encoded with unconstrained parameters, assuming its `requires` clauses.
+/// Every loop is unrolled, and every function inlined, at most
+/// `config.unwind` times; a loop with a smaller declared bound is unrolled
+/// up to its bound instead, and exceeding the bound is a failure. The
+/// arithmetic is encoded bit by bit with `i32` semantics, as evaluated by
+/// the interpreter.
+///
+/// The checks are: no overflow, no division by zero, no read of an
+/// uninitialized variable, the `requires` clauses of the callees, the
+/// `ensures` clauses of the functions, and the loop invariants. An
+/// execution stops at its first failure, so a counterexample of a check
+/// passes all the checks before it. Floating-point values are not supported.
+///
+pub fn check_program(prog : &Cmd, config : &BmcConfig) -> Result<BmcReport, String>
+{
+	let mut enc = Encoder
+	{
+		config : *config,
+		s : Solver::new(),
+		funcs : HashMap::new(),
+		frames : Vec::new(),
+		depth : HashMap::new(),
+		checks : Vec::new(),
+		cuts : Vec::new(),
+	};
+	enc.s.max_conflicts = config.max_conflicts;
+
+	let t = enc.s.lit_true();
+	let mut st = State { scopes : vec![HashMap::new()], active : t };
+	enc.exec_cmd(prog, &mut st)?;
+
+	let entries : Vec<(Rc<FnProtoType>, Rc<Cmd>)> = enc.funcs.iter()
+		.filter(|(_, v)| v.0.name == "entry")
+		.map(|(_, v)| v.clone())
+		.collect();
+
+	if entries.len() != 1
+	{
+		return Result::Err(format!("There should be exactly one entry function, but {} are declared.", entries.len()));
+	}
+	let (pt, fn_cmd) = entries[0].clone();
+
+	let mut params : Vec<(VarDecl, Val)> = Vec::new();
+	let mut param_scope : HashMap<String, Slot> = HashMap::new();
+	for decl in pt.var_decl_list.iter()
+	{
+		let v = enc.fresh_val(&decl.var_type)?;
+		param_scope.insert(decl.name.clone(), Slot { val : v.clone(), init : t });
+		params.push((decl.clone(), v));
+	}
+
+	let f = enc.s.lit_false();
+	let mut entry_st = State { scopes : vec![st.scopes[0].clone(), param_scope.clone()], active : st.active };
+	enc.frames.push(Frame { name : pt.name.clone(), ret : Option::None, returned : f });
+	for req in pt.requires.iter()
+	{
+		let c = enc.eval_bexp(req, &mut entry_st)?;
+		entry_st.active = enc.s.and(entry_st.active, c);
+	}
+	enc.frames.pop();
+
+	let types : Vec<DataType> = pt.var_decl_list.iter().map(|d| d.var_type).collect();
+	let mangled = mangle_fn_name(&pt.name, &types);
+	enc.exec_fn(&pt, &fn_cmd, &mangled, entry_st, param_scope)?;
+
+	let mut checks : Vec<BmcCheck> = Vec::new();
+	for (what, fails) in enc.checks.clone().into_iter()
+	{
+		let fail = enc.s.or_all(&fails);
+		let result = match enc.s.solve(&[fail])
+		{
+			SatResult::Sat     => CheckResult::Violated(enc.param_list(&params)),
+			SatResult::Unsat   => CheckResult::Holds,
+			SatResult::Unknown => CheckResult::Unknown,
+		};
+		checks.push(BmcCheck { what : what, result : result });
+	}
+
+	let exceed = enc.s.or_all(&enc.cuts.clone());
+	let is_complete = enc.s.solve(&[exceed]) == SatResult::Unsat;
+
+	Result::Ok(BmcReport
+	{
+		params : pt.var_decl_list.clone(),
+		unwind : config.unwind,
+		checks : checks,
+		is_complete : is_complete,
+	})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use ast::aexp::constructor_helper::*;
+	use ast::exp::constructor_helper::*;
+	use ast::cmd::constructor_helper::*;
+
+	#[test]
+	fn division_by_zero_has_a_counterexample()
+	{
+		/* Program:
+		 * fn entry(a : Int32) -> Int32 { return 100 / (a - 5); }
+		 */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "a".to_string())]);
+		let prog = fn_dc(fn_prototype, ret(Some((100i32.to_aexp() / ("a".to_aexp() - 5i32.to_aexp())).to_exp())));
+
+		let report = check_program(&prog, &BmcConfig::new(2)).unwrap();
+		assert!(!report.is_safe());
+		assert!(report.is_complete);
+
+		let cex = report.checks.iter()
+			.filter(|c| c.what.starts_with("no division by zero"))
+			.filter_map(|c| match &c.result { CheckResult::Violated(params) => Option::Some(params), _ => Option::None })
+			.next()
+			.unwrap();
+		assert_eq!(cex.len(), 1);
+		assert_eq!(cex[0].to_string(), "5");
+	}
+}
@@ -0,0 +1,606 @@
+use std::fmt;
+use std::rc::Rc;
+use std::vec::Vec;
+use std::string::String;
+use std::string::ToString;
+use std::collections::HashMap;
+
+use super::super::ast;
+use super::super::typing;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::cmd::Cmd;
+use ast::exp::Exp;
+use ast::data_type::DataType;
+use ast::func_general::FnCall;
+use ast::func_general::FnProtoType;
+
+/// Calls between the functions of a program. A function is identified by
+/// its index in `funcs`, i.e. its order of declaration.
+pub struct CallGraph
+{
+	pub funcs : Vec<Rc<FnProtoType>>,
+	/// Functions called by each function, in order of their first call.
+	pub callees : Vec<Vec<usize>>,
+	/// Functions called by the global commands.
+	pub root_callees : Vec<usize>,
+	/// Function called at each call site, by the address of the call in the program.
+	sites : HashMap<*const FnCall, usize>,
+}
+
+/// `name(param types) -> ret type`, which identifies a function among its overloads.
+pub fn signature(pt : &FnProtoType) -> String
+{
+	let types : Vec<String> = pt.var_decl_list.iter().map(|d| d.var_type.to_string()).collect();
+	format!("{}({}) -> {}", pt.name, types.join(", "), pt.ret_type)
+}
+
+fn mangle_fn_name(name : &String, types : &Vec<DataType>) -> String
+{
+	let mut mangled_fun_name = String::new();
+	mangled_fun_name.push_str(name);
+	mangled_fun_name.push('_');
+
+	for t in types.iter()
+	{
+		mangled_fun_name.push_str(&t.to_string());
+		mangled_fun_name.push('_');
+	}
+
+	mangled_fun_name
+}
+
+struct Builder
+{
+	graph : CallGraph,
+	/// Index of each declaration, by the address of its prototype.
+	indices : HashMap<*const FnProtoType, usize>,
+	/// Functions declared at each level, by mangled name.
+	fn_scopes : Vec<HashMap<String, usize>>,
+	var_scopes : Vec<HashMap<String, DataType>>,
+	/// The function whose body is being walked; `Option::None` for the global commands.
+	curr : Option<usize>,
+}
+
+impl Builder
+{
+	/// Declare the functions of a level, so they can be called anywhere in it.
+	fn declare(&mut self, c : &Cmd) -> Result<(), String>
+	{
+		match c
+		{
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				self.declare(fst_cmd)?;
+				self.declare(snd_cmd)
+			},
+			Cmd::FnDecl{prototype, ..} =>
+			{
+				let types : Vec<DataType> = prototype.var_decl_list.iter().map(|d| d.var_type).collect();
+				let mangled = mangle_fn_name(&prototype.name, &types);
+
+				let scope = self.fn_scopes.last_mut().unwrap();
+				if scope.contains_key(&mangled)
+				{
+					return Result::Err(format!("Function named {} has already been declared.", prototype.name));
+				}
+
+				let idx = self.graph.funcs.len();
+				scope.insert(mangled, idx);
+				self.indices.insert(Rc::as_ptr(prototype), idx);
+				self.graph.funcs.push(prototype.clone());
+				self.graph.callees.push(Vec::new());
+
+				Result::Ok(())
+			},
+			_ => Result::Ok(()),
+		}
+	}
+
+	/// Walk a command in a new level of variables and functions.
+	fn walk_block(&mut self, c : &Cmd, vars : HashMap<String, DataType>) -> Result<(), String>
+	{
+		self.fn_scopes.push(HashMap::new());
+		self.var_scopes.push(vars);
+
+		let res = self.declare(c).and_then(|_| self.walk_cmd(c));
+
+		self.var_scopes.pop();
+		self.fn_scopes.pop();
+
+		res
+	}
+
+	/// Resolve a call the way `interpreter::states::func_call` does: among
+	/// the overloads declared at the innermost level declaring a function of
+	/// that name, unless the call records the overload it refers to.
+	fn resolve(&mut self, fc : &FnCall) -> Result<DataType, String>
+	{
+		let mut types : Vec<DataType> = Vec::new();
+		for e in fc.exp_list.iter()
+		{
+			types.push(self.exp_type(e)?);
+		}
+
+		let scope = match &fc.param_types
+		{
+			Option::Some(param_types) =>
+			{
+				let mangled = mangle_fn_name(&fc.name, param_types);
+				self.fn_scopes.iter().rev().find(|sc| sc.contains_key(&mangled))
+			},
+			Option::None =>
+			{
+				let funcs = &self.graph.funcs;
+				self.fn_scopes.iter().rev().find(|sc| sc.values().any(|idx| funcs[*idx].name == fc.name))
+			},
+		};
+		let scope = match scope
+		{
+			Option::Some(sc) => sc,
+			Option::None     =>
+			{
+				let types_str : Vec<String> = types.iter().map(|t| t.to_string()).collect();
+				return Result::Err(format!("Function {}({}) hasn't been declared.", fc.name, types_str.join(", ")));
+			},
+		};
+
+		let funcs = &self.graph.funcs;
+		let param_types = typing::resolve_call(fc, &types, scope.values().map(|idx| &*funcs[*idx]))?;
+		let idx = scope[&mangle_fn_name(&fc.name, &param_types)];
+
+		self.graph.sites.insert(fc as *const FnCall, idx);
+
+		let callees = match self.curr
+		{
+			Option::Some(caller) => &mut self.graph.callees[caller],
+			Option::None         => &mut self.graph.root_callees,
+		};
+		if !callees.contains(&idx)
+		{
+			callees.push(idx);
+		}
+
+		Result::Ok(self.graph.funcs[idx].ret_type)
+	}
+
+	fn aexp_type(&mut self, e : &Aexp) -> Result<DataType, String>
+	{
+		match e
+		{
+			Aexp::IntConst{..} => Result::Ok(DataType::Int32),
+			Aexp::FloConst{..} => Result::Ok(DataType::Float32),
+			Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				// An integer operand is promoted if the other one is a float.
+				let l_type = self.aexp_type(l)?;
+				let r_type = self.aexp_type(r)?;
+				if l_type == DataType::Float32 || r_type == DataType::Float32
+				{
+					Result::Ok(DataType::Float32)
+				}
+				else
+				{
+					Result::Ok(DataType::Int32)
+				}
+			},
+			Aexp::Var{v} =>
+			{
+				match self.var_scopes.iter().rev().find_map(|sc| sc.get(&v.name))
+				{
+					Option::Some(t) => Result::Ok(*t),
+					Option::None    => Result::Err(format!("Variable {} hasn't been declared.", v.name)),
+				}
+			},
+			Aexp::FnCall{fc} => self.resolve(fc),
+		}
+	}
+
+	fn walk_bexp(&mut self, e : &Bexp) -> Result<(), String>
+	{
+		match e
+		{
+			Bexp::BoolConst{..} | Bexp::Var{..} => Result::Ok(()),
+			Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+			{
+				self.walk_bexp(l)?;
+				self.walk_bexp(r)
+			},
+			Bexp::Not{e} => self.walk_bexp(e),
+			Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+			{
+				self.aexp_type(l)?;
+				self.aexp_type(r)?;
+				Result::Ok(())
+			},
+			Bexp::FnCall{fc} =>
+			{
+				self.resolve(fc)?;
+				Result::Ok(())
+			},
+		}
+	}
+
+	fn exp_type(&mut self, e : &Exp) -> Result<DataType, String>
+	{
+		match e
+		{
+			Exp::A{e} => self.aexp_type(e),
+			Exp::B{e} =>
+			{
+				self.walk_bexp(e)?;
+				Result::Ok(DataType::Bool)
+			},
+		}
+	}
+
+	fn walk_cmd(&mut self, c : &Cmd) -> Result<(), String>
+	{
+		match c
+		{
+			Cmd::Skip => Result::Ok(()),
+			Cmd::VarDecl{d} =>
+			{
+				self.var_scopes.last_mut().unwrap().insert(d.name.clone(), d.var_type);
+				Result::Ok(())
+			},
+			Cmd::Assign{e, ..} => self.exp_type(e).map(|_| ()),
+			Cmd::FnCall{fc} => self.resolve(fc).map(|_| ()),
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				self.walk_bexp(cond)?;
+				self.walk_block(tr_cmd, HashMap::new())?;
+				self.walk_block(fa_cmd, HashMap::new())
+			},
+			Cmd::WhileLoop{cond, lp_cmd, ..} =>
+			{
+				self.walk_bexp(cond)?;
+				self.walk_block(lp_cmd, HashMap::new())
+			},
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				self.walk_cmd(fst_cmd)?;
+				self.walk_cmd(snd_cmd)
+			},
+			Cmd::FnDecl{prototype, fn_cmd} =>
+			{
+				let idx = self.indices[&Rc::as_ptr(prototype)];
+				let params : HashMap<String, DataType> = prototype.var_decl_list.iter()
+					.map(|d| (d.name.clone(), d.var_type))
+					.collect();
+
+				let caller = self.curr.replace(idx);
+				let res = self.walk_block(fn_cmd, params);
+				self.curr = caller;
+
+				res
+			},
+			Cmd::Return{e} =>
+			{
+				match e
+				{
+					Option::Some(e) => self.exp_type(e).map(|_| ()),
+					Option::None    => Result::Ok(()),
+				}
+			},
+			Cmd::Assert{cond} => self.walk_bexp(cond),
+		}
+	}
+}
+
+struct Tarjan<'a>
+{
+	graph : &'a CallGraph,
+	index : Vec<Option<usize>>,
+	low_link : Vec<usize>,
+	on_stack : Vec<bool>,
+	stack : Vec<usize>,
+	next_index : usize,
+	sccs : Vec<Vec<usize>>,
+}
+
+impl<'a> Tarjan<'a>
+{
+	fn visit(&mut self, v : usize)
+	{
+		self.index[v] = Option::Some(self.next_index);
+		self.low_link[v] = self.next_index;
+		self.next_index += 1;
+		self.stack.push(v);
+		self.on_stack[v] = true;
+
+		for w in self.graph.callees[v].iter()
+		{
+			match self.index[*w]
+			{
+				Option::None =>
+				{
+					self.visit(*w);
+					self.low_link[v] = self.low_link[v].min(self.low_link[*w]);
+				},
+				Option::Some(w_index) =>
+				{
+					if self.on_stack[*w]
+					{
+						self.low_link[v] = self.low_link[v].min(w_index);
+					}
+				},
+			}
+		}
+
+		if Option::Some(self.low_link[v]) == self.index[v]
+		{
+			let mut scc : Vec<usize> = Vec::new();
+			loop
+			{
+				let w = self.stack.pop().unwrap();
+				self.on_stack[w] = false;
+				scc.push(w);
+				if w == v
+				{
+					break;
+				}
+			}
+			scc.sort();
+			self.sccs.push(scc);
+		}
+	}
+}
+
+impl CallGraph
+{
+	/// Build the call graph of a program. Every declared function is a node,
+	/// including the ones that are never called; the calls in contracts and
+	/// loop invariants are ignored, since they're not executed.
+	pub fn from_program(prog : &Cmd) -> Result<CallGraph, String>
+	{
+		let mut builder = Builder
+		{
+			graph : CallGraph { funcs : Vec::new(), callees : Vec::new(), root_callees : Vec::new(), sites : HashMap::new() },
+			indices : HashMap::new(),
+			fn_scopes : Vec::new(),
+			var_scopes : Vec::new(),
+			curr : Option::None,
+		};
+
+		builder.walk_block(prog, HashMap::new())?;
+
+		Result::Ok(builder.graph)
+	}
+
+	/// Function called by a call of the program the graph is built from.
+	pub fn resolve_call(&self, fc : &FnCall) -> Option<usize>
+	{
+		self.sites.get(&(fc as *const FnCall)).cloned()
+	}
+
+	/// Indices of the overloads of a function.
+	pub fn find(&self, name : &str) -> Vec<usize>
+	{
+		(0..self.funcs.len()).filter(|i| self.funcs[*i].name == name).collect()
+	}
+
+	/// Strongly connected components, each sorted by index. A component
+	/// comes after all the components it calls into, so callees come first.
+	pub fn sccs(&self) -> Vec<Vec<usize>>
+	{
+		let num_funcs = self.funcs.len();
+		let mut tarjan = Tarjan
+		{
+			graph : self,
+			index : vec![Option::None; num_funcs],
+			low_link : vec![0; num_funcs],
+			on_stack : vec![false; num_funcs],
+			stack : Vec::new(),
+			next_index : 0,
+			sccs : Vec::new(),
+		};
+
+		for v in 0..num_funcs
+		{
+			if tarjan.index[v].is_none()
+			{
+				tarjan.visit(v);
+			}
+		}
+
+		tarjan.sccs
+	}
+
+	/// The function can call itself, directly or through other functions.
+	pub fn is_recursive(&self, func : usize) -> bool
+	{
+		self.callees[func].contains(&func) ||
+			self.sccs().iter().any(|scc| scc.len() > 1 && scc.contains(&func))
+	}
+
+	/// Groups of functions that call each other, including the functions
+	/// that call themselves directly.
+	pub fn recursive_sccs(&self) -> Vec<Vec<usize>>
+	{
+		self.sccs().into_iter()
+			.filter(|scc| scc.len() > 1 || self.callees[scc[0]].contains(&scc[0]))
+			.collect()
+	}
+
+	pub fn has_recursion(&self) -> bool
+	{
+		!self.recursive_sccs().is_empty()
+	}
+
+	/// Functions that may be called, directly or not, by a function; the
+	/// function itself is included only if it's recursive.
+	pub fn reachable_from(&self, func : usize) -> Vec<usize>
+	{
+		let mut visited : Vec<bool> = vec![false; self.funcs.len()];
+		let mut pending : Vec<usize> = self.callees[func].clone();
+		while let Option::Some(v) = pending.pop()
+		{
+			if !visited[v]
+			{
+				visited[v] = true;
+				pending.extend(self.callees[v].iter());
+			}
+		}
+
+		(0..self.funcs.len()).filter(|i| visited[*i]).collect()
+	}
+
+	/// The graph in the DOT language of Graphviz. Recursive functions are
+	/// red, and `entry` has a double border.
+	pub fn to_dot(&self) -> String
+	{
+		let recursive : Vec<usize> = self.recursive_sccs().into_iter().flatten().collect();
+
+		let mut dot = String::new();
+		dot.push_str("digraph call_graph\n{\n");
+		dot.push_str("\tnode [shape=box];\n");
+		dot.push_str("\troot [label=\"global commands\", shape=ellipse];\n");
+
+		for (i, pt) in self.funcs.iter().enumerate()
+		{
+			let mut attrs = format!("label=\"{}\"", signature(pt).replace('"', "\\\""));
+			if pt.name == "entry"
+			{
+				attrs.push_str(", peripheries=2");
+			}
+			if recursive.contains(&i)
+			{
+				attrs.push_str(", color=red");
+			}
+			dot.push_str(&format!("\tf{} [{}];\n", i, attrs));
+		}
+
+		for callee in self.root_callees.iter()
+		{
+			dot.push_str(&format!("\troot -> f{};\n", callee));
+		}
+		for (caller, callees) in self.callees.iter().enumerate()
+		{
+			for callee in callees.iter()
+			{
+				dot.push_str(&format!("\tf{} -> f{};\n", caller, callee));
+			}
+		}
+
+		dot.push_str("}\n");
+		dot
+	}
+}
+
+impl fmt::Display for CallGraph
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		let names = |idxs : &Vec<usize>| -> String
+		{
+			let sigs : Vec<String> = idxs.iter().map(|i| signature(&self.funcs[*i])).collect();
+			sigs.join(", ")
+		};
+
+		writeln!(f, "Call graph ({} function(s)):", self.funcs.len())?;
+		writeln!(f, "\tglobal commands calls [{}]", names(&self.root_callees))?;
+		for (i, pt) in self.funcs.iter().enumerate()
+		{
+			writeln!(f, "\t{} calls [{}]", signature(pt), names(&self.callees[i]))?;
+		}
+
+		let recursive = self.recursive_sccs();
+		if recursive.is_empty()
+		{
+			write!(f, "No function is recursive.")
+		}
+		else
+		{
+			for scc in recursive.iter()
+			{
+				writeln!(f, "Recursive: [{}]", names(scc))?;
+			}
+			write!(f, "{} group(s) of recursive functions.", recursive.len())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use ast::aexp::constructor_helper::*;
+	use ast::exp::constructor_helper::*;
+	use ast::cmd::constructor_helper::*;
+	use ast::var_general::VarDecl;
+
+	#[test]
+	fn recursion_is_detected()
+	{
+		/* Program:
+		 * fn leaf(n : Int32) -> Int32 { return n; }
+		 * fn even(n : Int32) -> Int32 { if (n < 1) { return 0; } else { return odd(n - 1); } }
+		 * fn odd(n : Int32) -> Int32 { if (n < 1) { return 0; } else { return even(n - 1); } }
+		 * fn self_rec(n : Int32) -> Int32 { if (n < 1) { return 0; } else { return self_rec(n - 1); } }
+		 * fn entry(n : Int32) -> Int32 { return even(n) + leaf(n); }
+		 */
+		let call = |name : &str, arg : Aexp| Aexp::FnCall{ fc : FnCall::new(name.to_string(), vec![arg.to_exp()]) };
+		let param = || vec![VarDecl::new(DataType::Int32, "n".to_string())];
+
+		let leaf = fn_dc(FnProtoType::new(DataType::Int32, "leaf".to_string(), param()), ret(Some("n".to_aexp().to_exp())));
+		let even = fn_dc(FnProtoType::new(DataType::Int32, "even".to_string(), param()),
+			if_el("n".to_aexp().lt(1i32.to_aexp()),
+				ret(Some(0i32.to_aexp().to_exp())),
+				ret(Some(call("odd", "n".to_aexp() - 1i32.to_aexp()).to_exp()))));
+		let odd = fn_dc(FnProtoType::new(DataType::Int32, "odd".to_string(), param()),
+			if_el("n".to_aexp().lt(1i32.to_aexp()),
+				ret(Some(0i32.to_aexp().to_exp())),
+				ret(Some(call("even", "n".to_aexp() - 1i32.to_aexp()).to_exp()))));
+		let self_rec = fn_dc(FnProtoType::new(DataType::Int32, "self_rec".to_string(), param()),
+			if_el("n".to_aexp().lt(1i32.to_aexp()),
+				ret(Some(0i32.to_aexp().to_exp())),
+				ret(Some(call("self_rec", "n".to_aexp() - 1i32.to_aexp()).to_exp()))));
+		let entry = fn_dc(FnProtoType::new(DataType::Int32, "entry".to_string(), param()),
+			ret(Some((call("even", "n".to_aexp()) + call("leaf", "n".to_aexp())).to_exp())));
+		let prog = seq(leaf, seq(even, seq(odd, seq(self_rec, entry))));
+
+		let graph = CallGraph::from_program(&prog).unwrap();
+		let idx = |name : &str| graph.find(name)[0];
+
+		assert!(graph.has_recursion());
+		assert!(graph.is_recursive(idx("even")));
+		assert!(graph.is_recursive(idx("odd")));
+		assert!(graph.is_recursive(idx("self_rec")));
+		assert!(!graph.is_recursive(idx("leaf")));
+		assert!(!graph.is_recursive(idx("entry")));
+
+		let mut sccs : Vec<Vec<String>> = graph.recursive_sccs().iter()
+			.map(|scc| scc.iter().map(|i| graph.funcs[*i].name.clone()).collect())
+			.collect();
+		sccs.sort();
+		assert_eq!(sccs, vec![vec!["even".to_string(), "odd".to_string()], vec!["self_rec".to_string()]]);
+
+		let mut reachable : Vec<String> = graph.reachable_from(idx("entry")).iter().map(|i| graph.funcs[*i].name.clone()).collect();
+		reachable.sort();
+		assert_eq!(reachable, vec!["even".to_string(), "leaf".to_string(), "odd".to_string()]);
+	}
+
+	#[test]
+	fn program_without_recursion()
+	{
+		/* Program:
+		 * fn leaf(n : Int32) -> Int32 { return n; }
+		 * fn entry(n : Int32) -> Int32 { if (n < 1) { return 0; } else { return leaf(n - 1); } }
+		 */
+		let leaf = fn_dc(FnProtoType::new(DataType::Int32, "leaf".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]),
+			ret(Some("n".to_aexp().to_exp())));
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]);
+		let leaf_call = Aexp::FnCall{ fc : FnCall::new("leaf".to_string(), vec![("n".to_aexp() - 1i32.to_aexp()).to_exp()]) };
+		let if_cmd = if_el("n".to_aexp().lt(1i32.to_aexp()),
+			ret(Some(0i32.to_aexp().to_exp())),
+			ret(Some(leaf_call.to_exp())));
+		let prog = seq(leaf, fn_dc(fn_prototype, if_cmd));
+
+		let graph = CallGraph::from_program(&prog).unwrap();
+
+		assert!(!graph.has_recursion());
+		assert_eq!(graph.callees[graph.find("entry")[0]], vec![graph.find("leaf")[0]]);
+		assert_eq!(signature(&graph.funcs[0]), "leaf(i32) -> i32");
+	}
+}
@@ -0,0 +1,325 @@
+use std::fmt;
+use std::cmp;
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::super::ast::Serializible;
+use super::super::ast::Deserializible;
+use super::super::ast::primit_serialize;
+
+pub const I32_MIN : i64 = std::i32::MIN as i64;
+pub const I32_MAX : i64 = std::i32::MAX as i64;
+
+/// An interval of integers, used as the abstract value of an `i32` expression.
+///
+/// Bounds are kept in `i64` so that the exact result of an `i32` operation can
+/// be represented before it is checked against the `i32` range.
+///
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Interval
+{
+	Empty,
+	Range {lo : i64, hi : i64},
+}
+
+impl Interval
+{
+	pub fn new(lo : i64, hi : i64) -> Interval
+	{
+		if lo <= hi
+		{
+			Interval::Range {lo : lo, hi : hi}
+		}
+		else
+		{
+			Interval::Empty
+		}
+	}
+
+	pub fn constant(v : i64) -> Interval
+	{
+		Interval::Range {lo : v, hi : v}
+	}
+
+	/// The interval of all `i32` values.
+	pub fn full_i32() -> Interval
+	{
+		Interval::Range {lo : I32_MIN, hi : I32_MAX}
+	}
+
+	pub fn is_empty(&self) -> bool
+	{
+		match self
+		{
+			Interval::Empty         => true,
+			Interval::Range {lo:_, hi:_} => false,
+		}
+	}
+
+	pub fn contains(&self, v : i64) -> bool
+	{
+		match self
+		{
+			Interval::Empty        => false,
+			Interval::Range {lo, hi} => (*lo <= v) && (v <= *hi),
+		}
+	}
+
+	pub fn get_const(&self) -> Option<i64>
+	{
+		match self
+		{
+			Interval::Range {lo, hi} if lo == hi => Option::Some(*lo),
+			_                                    => Option::None,
+		}
+	}
+
+	/// Whether all values in this interval can be represented by `i32`.
+	pub fn is_within_i32(&self) -> bool
+	{
+		match self
+		{
+			Interval::Empty        => true,
+			Interval::Range {lo, hi} => (*lo >= I32_MIN) && (*hi <= I32_MAX),
+		}
+	}
+
+	/// Clamp the interval to the `i32` range.
+	///
+	/// If any value in the interval is outside of the `i32` range, the result of
+	/// the operation wraps around, so the full `i32` range is returned instead.
+	///
+	pub fn wrap_to_i32(&self) -> Interval
+	{
+		if self.is_within_i32()
+		{
+			*self
+		}
+		else
+		{
+			Interval::full_i32()
+		}
+	}
+
+	pub fn join(&self, other : &Interval) -> Interval
+	{
+		match (self, other)
+		{
+			(Interval::Empty, _) => *other,
+			(_, Interval::Empty) => *self,
+			(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+				Interval::new(cmp::min(*l1, *l2), cmp::max(*h1, *h2)),
+		}
+	}
+
+	pub fn meet(&self, other : &Interval) -> Interval
+	{
+		match (self, other)
+		{
+			(Interval::Empty, _) => Interval::Empty,
+			(_, Interval::Empty) => Interval::Empty,
+			(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+				Interval::new(cmp::max(*l1, *l2), cmp::min(*h1, *h2)),
+		}
+	}
+
+	/// Standard interval widening; unstable bounds jump to the `i32` limits.
+	pub fn widen(&self, next : &Interval) -> Interval
+	{
+		match (self, next)
+		{
+			(Interval::Empty, _) => *next,
+			(_, Interval::Empty) => *self,
+			(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+			{
+				let lo = if l2 < l1 { cmp::min(I32_MIN, *l2) } else { *l1 };
+				let hi = if h2 > h1 { cmp::max(I32_MAX, *h2) } else { *h1 };
+				Interval::new(lo, hi)
+			},
+		}
+	}
+
+	pub fn add(&self, other : &Interval) -> Interval
+	{
+		match (self, other)
+		{
+			(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+				Interval::new(l1 + l2, h1 + h2),
+			_ => Interval::Empty,
+		}
+	}
+
+	pub fn sub(&self, other : &Interval) -> Interval
+	{
+		match (self, other)
+		{
+			(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+				Interval::new(l1 - h2, h1 - l2),
+			_ => Interval::Empty,
+		}
+	}
+
+	pub fn mul(&self, other : &Interval) -> Interval
+	{
+		match (self, other)
+		{
+			(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+			{
+				let prods = [l1 * l2, l1 * h2, h1 * l2, h1 * h2];
+				Interval::new(
+					*prods.iter().min().unwrap_or(&0),
+					*prods.iter().max().unwrap_or(&0))
+			},
+			_ => Interval::Empty,
+		}
+	}
+
+	/// Split the interval into its strictly negative and strictly positive parts.
+	fn split_nonzero(&self) -> (Interval, Interval)
+	{
+		(self.meet(&Interval::new(std::i64::MIN, -1)), self.meet(&Interval::new(1, std::i64::MAX)))
+	}
+
+	/// Truncating division, over the non-zero values of the divisor only.
+	pub fn div(&self, other : &Interval) -> Interval
+	{
+		let (neg, pos) = other.split_nonzero();
+		let mut res = Interval::Empty;
+
+		for part in [neg, pos].iter()
+		{
+			match (self, part)
+			{
+				(Interval::Range {lo : l1, hi : h1}, Interval::Range {lo : l2, hi : h2}) =>
+				{
+					let quots = [l1 / l2, l1 / h2, h1 / l2, h1 / h2];
+					res = res.join(&Interval::new(
+						*quots.iter().min().unwrap_or(&0),
+						*quots.iter().max().unwrap_or(&0)));
+				},
+				_ => {},
+			}
+		}
+
+		res
+	}
+
+	/// Remainder (sign follows the dividend), over the non-zero values of the divisor only.
+	pub fn rem(&self, other : &Interval) -> Interval
+	{
+		let (neg, pos) = other.split_nonzero();
+
+		let max_abs_divisor = match (neg, pos)
+		{
+			(Interval::Empty, Interval::Empty) => return Interval::Empty,
+			(Interval::Range {lo, hi:_}, Interval::Empty) => -lo,
+			(Interval::Empty, Interval::Range {lo:_, hi}) => hi,
+			(Interval::Range {lo : nl, hi:_}, Interval::Range {lo:_, hi : ph}) => cmp::max(-nl, ph),
+		};
+
+		match self
+		{
+			Interval::Empty          => Interval::Empty,
+			Interval::Range {lo, hi} =>
+			{
+				let res_lo = if *lo < 0 { cmp::max(*lo, -(max_abs_divisor - 1)) } else { 0 };
+				let res_hi = if *hi > 0 { cmp::min(*hi, max_abs_divisor - 1) } else { 0 };
+				Interval::new(res_lo, res_hi)
+			},
+		}
+	}
+}
+
+impl fmt::Display for Interval
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			Interval::Empty          => write!(f, "{}", "[]"),
+			Interval::Range {lo, hi} => write!(f, "[{}, {}]", lo, hi),
+		}
+	}
+}
+
+/// The declared ranges of the parameters passed to `entry`.
+///
+/// The i-th interval bounds the i-th parameter of `entry`. Parameters that
+/// are not integers, or that are not listed, are not restricted.
+///
+#[derive(Clone)]
+pub struct ParamRanges
+{
+	pub list : Vec<Interval>,
+}
+
+impl ParamRanges
+{
+	pub fn new(list : Vec<Interval>) -> ParamRanges
+	{
+		ParamRanges {list : list}
+	}
+
+	pub fn get(&self, idx : usize) -> Interval
+	{
+		match self.list.get(idx)
+		{
+			Option::Some(v) => v.meet(&Interval::full_i32()),
+			Option::None    => Interval::full_i32(),
+		}
+	}
+}
+
+impl Serializible for ParamRanges
+{
+	/// Serialize the parameter ranges into serials of bytes, and return the vector of bytes.
+	///
+	/// Please refer to the documentation on the trait for detail.
+	///
+	/// # ParamRanges layout
+	/// ```text
+	///            | uint64 - 9 Bytes | i32 - 5 bytes | i32 - 5 bytes | ...
+	/// ```
+	///
+	/// An empty interval is stored as `lo = 1, hi = 0`.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res = primit_serialize::uint64_to_bytes(&(self.list.len() as u64));
+
+		for item in self.list.iter()
+		{
+			let (lo, hi) = match item.meet(&Interval::full_i32())
+			{
+				Interval::Empty          => (1i32, 0i32),
+				Interval::Range {lo, hi} => (lo as i32, hi as i32),
+			};
+
+			res.append(&mut primit_serialize::int32_to_bytes(&lo));
+			res.append(&mut primit_serialize::int32_to_bytes(&hi));
+		}
+
+		Result::Ok(res)
+	}
+}
+
+impl Deserializible for ParamRanges
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], ParamRanges), String>
+	{
+		let (mut bytes_left, list_len_u64) = primit_serialize::uint64_from_bytes(bytes)?;
+
+		let mut list : Vec<Interval> = Vec::new();
+
+		for _ in 0..list_len_u64
+		{
+			let (bytes_left_lo, lo) = primit_serialize::int32_from_bytes(bytes_left)?;
+			let (bytes_left_hi, hi) = primit_serialize::int32_from_bytes(bytes_left_lo)?;
+			list.push(Interval::new(lo as i64, hi as i64));
+			bytes_left = bytes_left_hi;
+		}
+
+		Result::Ok((bytes_left, ParamRanges::new(list)))
+	}
+}
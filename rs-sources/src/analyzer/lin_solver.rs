@@ -0,0 +1,766 @@
+use std::vec::Vec;
+use std::string::String;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use super::super::ast;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::data_type::DataType;
+
+/// Maximum number of search nodes visited before the solver gives up.
+const MAX_SEARCH_NODES : usize = 20000;
+
+/// Maximum number of bound propagation rounds at a search node.
+const MAX_PROPAGATION_ROUNDS : usize = 64;
+
+/// Maximum number of constraints during Fourier-Motzkin elimination.
+const MAX_ELIMINATION_CONSTRAINTS : usize = 512;
+
+/// Result of solving a conjunction of path conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveResult
+{
+	/// The conditions are satisfiable; a value for every integer or boolean
+	/// symbol (booleans are `0` or `1`).
+	Sat(HashMap<String, i64>),
+	Unsat,
+	/// The conditions are not linear, or the search budget ran out.
+	Unknown,
+}
+
+/// Linear term `sum(coeff * symbol) + c`.
+#[derive(Debug, Clone)]
+struct Lin
+{
+	terms : BTreeMap<String, i128>,
+	c : i128,
+}
+
+impl Lin
+{
+	fn constant(c : i128) -> Lin
+	{
+		Lin { terms : BTreeMap::new(), c : c }
+	}
+
+	fn symbol(name : &String) -> Lin
+	{
+		let mut terms = BTreeMap::new();
+		terms.insert(name.clone(), 1);
+		Lin { terms : terms, c : 0 }
+	}
+
+	fn add(&self, other : &Lin, sign : i128) -> Option<Lin>
+	{
+		let mut res = self.clone();
+		for (name, a) in other.terms.iter()
+		{
+			let entry = res.terms.entry(name.clone()).or_insert(0);
+			*entry = entry.checked_add(a.checked_mul(sign)?)?;
+		}
+		res.terms.retain(|_, a| *a != 0);
+		res.c = res.c.checked_add(other.c.checked_mul(sign)?)?;
+
+		Option::Some(res)
+	}
+
+	fn scale(&self, k : i128) -> Option<Lin>
+	{
+		let mut res = Lin::constant(self.c.checked_mul(k)?);
+		if k != 0
+		{
+			for (name, a) in self.terms.iter()
+			{
+				res.terms.insert(name.clone(), a.checked_mul(k)?);
+			}
+		}
+
+		Option::Some(res)
+	}
+
+	fn shift(&self, k : i128) -> Option<Lin>
+	{
+		let mut res = self.clone();
+		res.c = res.c.checked_add(k)?;
+		Option::Some(res)
+	}
+}
+
+/// Condition in negation normal form; atoms are `lin <= 0`, `lin == 0` and `lin != 0`.
+#[derive(Debug, Clone)]
+enum Formula
+{
+	Const(bool),
+	Le(Lin),
+	Eq(Lin),
+	Ne(Lin),
+	And(Vec<Formula>),
+	Or(Vec<Formula>),
+}
+
+struct Translator<'a>
+{
+	symbols : &'a HashMap<String, DataType>,
+	/// Auxiliary symbols (quotient and remainder) introduced for every
+	/// division by a constant, keyed by the division expression.
+	div_aux : RefCell<HashMap<String, (String, String)> >,
+	/// Auxiliary symbol of the wrapped value of every term that may overflow,
+	/// keyed by the term.
+	wrap_aux : RefCell<HashMap<String, String> >,
+	/// Domains of the auxiliary symbols.
+	aux_doms : RefCell<Vec<(String, (i128, i128))> >,
+	/// Constraints defining the auxiliary symbols.
+	side_conds : RefCell<Vec<Formula> >,
+}
+
+impl<'a> Translator<'a>
+{
+	fn new(symbols : &'a HashMap<String, DataType>) -> Translator<'a>
+	{
+		Translator
+		{
+			symbols : symbols,
+			div_aux : RefCell::new(HashMap::new()),
+			wrap_aux : RefCell::new(HashMap::new()),
+			aux_doms : RefCell::new(Vec::new()),
+			side_conds : RefCell::new(Vec::new()),
+		}
+	}
+
+	/// Bounds of a linear term, from the domains of its symbols.
+	fn bounds(&self, lin : &Lin) -> Option<(i128, i128)>
+	{
+		let mut lo = lin.c;
+		let mut hi = lin.c;
+		for (name, a) in lin.terms.iter()
+		{
+			let (s_lo, s_hi) = match self.symbols.get(name)
+			{
+				Option::Some(DataType::Int32) => (i32::MIN as i128, i32::MAX as i128),
+				_                             => self.aux_doms.borrow().iter().find(|(n, _)| n == name).map(|(_, d)| *d)?,
+			};
+			let (x, y) = (a.checked_mul(s_lo)?, a.checked_mul(s_hi)?);
+			lo = lo.checked_add(x.min(y))?;
+			hi = hi.checked_add(x.max(y))?;
+		}
+
+		Option::Some((lo, hi))
+	}
+
+	/// Value of the term `e`, whose exact value is `lin`, in `i32`: it wraps
+	/// around as in the interpreter, i.e. it's `lin - 2^32 * k` for the integer
+	/// `k` that brings it in range.
+	fn wrap(&self, e : &Aexp, lin : Lin) -> Option<Lin>
+	{
+		let (lo, hi) = self.bounds(&lin)?;
+		if lo >= i32::MIN as i128 && hi <= i32::MAX as i128
+		{
+			return Option::Some(lin);
+		}
+		if lin.terms.is_empty()
+		{
+			return Option::Some(Lin::constant(lin.c as i32 as i128));
+		}
+
+		let key = format!("{}", e);
+		if let Option::Some(w) = self.wrap_aux.borrow().get(&key)
+		{
+			return Option::Some(Lin::symbol(w));
+		}
+
+		let id = self.aux_doms.borrow().len() / 2;
+		let w_name = format!("$w{}", id);
+		let k_name = format!("$k{}", id);
+
+		// lin == 2^32 * k + w
+		let eq = Formula::Eq(lin.add(&Lin::symbol(&k_name).scale(1i128 << 32)?, -1)?.add(&Lin::symbol(&w_name), -1)?);
+		self.side_conds.borrow_mut().push(eq);
+
+		let k_bound = (lo.abs().max(hi.abs()) >> 32) + 1;
+		self.aux_doms.borrow_mut().push((w_name.clone(), (i32::MIN as i128, i32::MAX as i128)));
+		self.aux_doms.borrow_mut().push((k_name, (-k_bound, k_bound)));
+		self.wrap_aux.borrow_mut().insert(key, w_name.clone());
+
+		Option::Some(Lin::symbol(&w_name))
+	}
+
+	/// Quotient and remainder symbols of `l / r` and `l % r`, where `r` is a
+	/// nonzero constant; the division truncates toward zero, as in the interpreter.
+	fn div_by_const(&self, l : &Aexp, r : &Aexp) -> Option<(Lin, Lin)>
+	{
+		let r_lin = self.lin(r)?;
+		if !r_lin.terms.is_empty() || r_lin.c == 0
+		{
+			return Option::None;
+		}
+		let k = r_lin.c;
+		let l_lin = self.lin(l)?;
+
+		let key = format!("{} / {}", l, r);
+		if let Option::Some((q, r)) = self.div_aux.borrow().get(&key)
+		{
+			return Option::Some((Lin::symbol(q), Lin::symbol(r)));
+		}
+
+		let id = self.aux_doms.borrow().len() / 2;
+		let q_name = format!("$q{}", id);
+		let r_name = format!("$r{}", id);
+		let q = Lin::symbol(&q_name);
+		let rem = Lin::symbol(&r_name);
+		let max_rem = k.abs() - 1;
+
+		// l == k * q + r, where r has the sign of l and |r| < |k|.
+		let eq = Formula::Eq(l_lin.add(&q.scale(k)?, -1)?.add(&rem, -1)?);
+		let non_neg = Formula::And(vec![
+			Formula::Le(l_lin.scale(-1)?),
+			Formula::Le(rem.scale(-1)?),
+			Formula::Le(rem.shift(-max_rem)?),
+		]);
+		let neg = Formula::And(vec![
+			Formula::Le(l_lin.shift(1)?),
+			Formula::Le(rem.scale(-1)?.shift(-max_rem)?),
+			Formula::Le(rem.clone()),
+		]);
+		self.side_conds.borrow_mut().push(eq);
+		self.side_conds.borrow_mut().push(Formula::Or(vec![non_neg, neg]));
+
+		let q_bound = 1i128 << 32;
+		self.aux_doms.borrow_mut().push((q_name.clone(), (-q_bound, q_bound)));
+		self.aux_doms.borrow_mut().push((r_name.clone(), (-max_rem, max_rem)));
+		self.div_aux.borrow_mut().insert(key, (q_name, r_name));
+
+		Option::Some((q, rem))
+	}
+
+	fn lin(&self, e : &Aexp) -> Option<Lin>
+	{
+		match e
+		{
+			Aexp::IntConst{v} => Option::Some(Lin::constant(*v as i128)),
+			Aexp::FloConst{..} => Option::None,
+			Aexp::Add{l, r} => self.wrap(e, self.lin(l)?.add(&self.lin(r)?, 1)?),
+			Aexp::Sub{l, r} => self.wrap(e, self.lin(l)?.add(&self.lin(r)?, -1)?),
+			Aexp::Mul{l, r} =>
+			{
+				let l_lin = self.lin(l)?;
+				let r_lin = self.lin(r)?;
+				if l_lin.terms.is_empty()
+				{
+					self.wrap(e, r_lin.scale(l_lin.c)?)
+				}
+				else if r_lin.terms.is_empty()
+				{
+					self.wrap(e, l_lin.scale(r_lin.c)?)
+				}
+				else
+				{
+					Option::None
+				}
+			},
+			Aexp::Var{v} => match self.symbols.get(&v.name)
+			{
+				Option::Some(DataType::Int32) => Option::Some(Lin::symbol(&v.name)),
+				_                              => Option::None,
+			},
+			// Only `i32::MIN / -1` overflows.
+			Aexp::Div{l, r} => self.wrap(e, self.div_by_const(l, r)?.0),
+			Aexp::Mod{l, r} => self.div_by_const(l, r).map(|(_, rem)| rem),
+			Aexp::FnCall{..} => Option::None,
+		}
+	}
+
+	/// Translate `e` (if `positive`) or `!e` (otherwise) into a formula.
+	fn formula(&self, e : &Bexp, positive : bool) -> Option<Formula>
+	{
+		match e
+		{
+			Bexp::BoolConst{v} => Option::Some(Formula::Const(*v == positive)),
+			Bexp::Beq{l, r} | Bexp::Bneq{l, r} =>
+			{
+				let same = match e
+				{
+					Bexp::Beq{..} => positive,
+					_             => !positive,
+				};
+				let l_t = self.formula(l, true)?;
+				let l_f = self.formula(l, false)?;
+				let r_t = self.formula(r, true)?;
+				let r_f = self.formula(r, false)?;
+				if same
+				{
+					Option::Some(Formula::Or(vec![Formula::And(vec![l_t, r_t]), Formula::And(vec![l_f, r_f])]))
+				}
+				else
+				{
+					Option::Some(Formula::Or(vec![Formula::And(vec![l_t, r_f]), Formula::And(vec![l_f, r_t])]))
+				}
+			},
+			Bexp::And{l, r} =>
+			{
+				let l_f = self.formula(l, positive)?;
+				let r_f = self.formula(r, positive)?;
+				if positive { Option::Some(Formula::And(vec![l_f, r_f])) } else { Option::Some(Formula::Or(vec![l_f, r_f])) }
+			},
+			Bexp::Or{l, r} =>
+			{
+				let l_f = self.formula(l, positive)?;
+				let r_f = self.formula(r, positive)?;
+				if positive { Option::Some(Formula::Or(vec![l_f, r_f])) } else { Option::Some(Formula::And(vec![l_f, r_f])) }
+			},
+			Bexp::Not{e} => self.formula(e, !positive),
+			Bexp::Aeq{l, r} | Bexp::Aneq{l, r} =>
+			{
+				let diff = self.lin(l)?.add(&self.lin(r)?, -1)?;
+				let is_eq = match e
+				{
+					Bexp::Aeq{..} => positive,
+					_             => !positive,
+				};
+				if is_eq { Option::Some(Formula::Eq(diff)) } else { Option::Some(Formula::Ne(diff)) }
+			},
+			Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+			{
+				// Rewrite into `a < b` or `a <= b`.
+				let (a, b, strict) = match e
+				{
+					Bexp::Lt{..}  => (l, r, true),
+					Bexp::Lte{..} => (l, r, false),
+					Bexp::Gt{..}  => (r, l, true),
+					_             => (r, l, false),
+				};
+				let a_lin = self.lin(a)?;
+				let b_lin = self.lin(b)?;
+				match (positive, strict)
+				{
+					// a < b  <=>  a - b + 1 <= 0
+					(true, true)   => Option::Some(Formula::Le(a_lin.add(&b_lin, -1)?.shift(1)?)),
+					// a <= b <=>  a - b <= 0
+					(true, false)  => Option::Some(Formula::Le(a_lin.add(&b_lin, -1)?)),
+					// !(a < b)  <=>  b - a <= 0
+					(false, true)  => Option::Some(Formula::Le(b_lin.add(&a_lin, -1)?)),
+					// !(a <= b) <=>  b - a + 1 <= 0
+					(false, false) => Option::Some(Formula::Le(b_lin.add(&a_lin, -1)?.shift(1)?)),
+				}
+			},
+			Bexp::Var{v} => match self.symbols.get(&v.name)
+			{
+				// A boolean symbol is an integer in [0, 1], where 1 stands for true.
+				Option::Some(DataType::Bool) =>
+				{
+					let target = if positive { -1 } else { 0 };
+					Option::Some(Formula::Eq(Lin::symbol(&v.name).shift(target)?))
+				},
+				_                             => Option::None,
+			},
+			Bexp::FnCall{..} => Option::None,
+		}
+	}
+}
+
+type Domains = BTreeMap<String, (i128, i128)>;
+
+enum Propagation
+{
+	Done,
+	Conflict,
+	Overflow,
+}
+
+fn div_floor(a : i128, b : i128) -> i128
+{
+	let q = a / b;
+	if (a % b != 0) && ((a < 0) != (b < 0)) { q - 1 } else { q }
+}
+
+fn div_ceil(a : i128, b : i128) -> i128
+{
+	let q = a / b;
+	if (a % b != 0) && ((a < 0) == (b < 0)) { q + 1 } else { q }
+}
+
+/// Tighten the domains with the constraints `lin <= 0`.
+fn propagate(doms : &mut Domains, les : &Vec<Lin>) -> Propagation
+{
+	for _ in 0..MAX_PROPAGATION_ROUNDS
+	{
+		let mut changed = false;
+
+		for lin in les.iter()
+		{
+			// Minimum of each term, and of the whole sum.
+			let mut mins : Vec<i128> = Vec::with_capacity(lin.terms.len());
+			let mut total_min = lin.c;
+			for (name, a) in lin.terms.iter()
+			{
+				let (lo, hi) = doms[name];
+				let m = if *a > 0 { a.checked_mul(lo) } else { a.checked_mul(hi) };
+				match m.and_then(|m| total_min.checked_add(m).map(|t| (m, t)))
+				{
+					Option::Some((m, t)) =>
+					{
+						mins.push(m);
+						total_min = t;
+					},
+					Option::None         => return Propagation::Overflow,
+				}
+			}
+
+			if total_min > 0
+			{
+				return Propagation::Conflict;
+			}
+
+			for ((name, a), m) in lin.terms.iter().zip(mins.iter())
+			{
+				// a * x <= -(total_min - m)
+				let bound = m - total_min;
+				let dom = doms.get_mut(name).unwrap();
+				if *a > 0
+				{
+					let new_hi = div_floor(bound, *a);
+					if new_hi < dom.1
+					{
+						dom.1 = new_hi;
+						changed = true;
+					}
+				}
+				else
+				{
+					let new_lo = div_ceil(bound, *a);
+					if new_lo > dom.0
+					{
+						dom.0 = new_lo;
+						changed = true;
+					}
+				}
+
+				if dom.0 > dom.1
+				{
+					return Propagation::Conflict;
+				}
+			}
+		}
+
+		if !changed
+		{
+			break;
+		}
+	}
+
+	Propagation::Done
+}
+
+fn gcd(a : i128, b : i128) -> i128
+{
+	if b == 0 { a.abs() } else { gcd(b, a % b) }
+}
+
+/// Divide `lin <= 0` by the gcd of its coefficients, rounding the constant
+/// up, which is exact for integer symbols.
+fn normalize(lin : Lin) -> Lin
+{
+	let g = lin.terms.values().fold(0, |g, a| gcd(g, *a));
+	if g <= 1
+	{
+		return lin;
+	}
+
+	Lin
+	{
+		terms : lin.terms.iter().map(|(name, a)| (name.clone(), a / g)).collect(),
+		c : div_ceil(lin.c, g),
+	}
+}
+
+/// Eliminate the symbols from the constraints `lin <= 0` and the domains one
+/// by one (Fourier-Motzkin); returns `true` if the constraints turn out to
+/// have no solution. Bound propagation alone only narrows the domains one
+/// step at a time on constraints like `x < y && y <= x`.
+fn is_infeasible(doms : &Domains, les : &Vec<Lin>) -> bool
+{
+	let mut cons : Vec<Lin> = les.iter().cloned().map(normalize).collect();
+
+	let used : BTreeSet<String> = les.iter().flat_map(|l| l.terms.keys().cloned()).collect();
+	for name in used.iter()
+	{
+		let (lo, hi) = doms[name];
+		let x = Lin::symbol(name);
+		match (x.shift(-hi), x.scale(-1).and_then(|l| l.shift(lo)))
+		{
+			(Option::Some(upper), Option::Some(lower)) =>
+			{
+				cons.push(upper);
+				cons.push(lower);
+			},
+			_ => return false,
+		}
+	}
+
+	loop
+	{
+		let mut rest : Vec<Lin> = Vec::new();
+		for l in cons.into_iter()
+		{
+			if l.terms.is_empty()
+			{
+				if l.c > 0
+				{
+					return true;
+				}
+			}
+			else
+			{
+				rest.push(l);
+			}
+		}
+
+		// Eliminate the symbol that yields the fewest new constraints.
+		let names : BTreeSet<String> = rest.iter().flat_map(|l| l.terms.keys().cloned()).collect();
+		let pick = names.iter()
+			.map(|name|
+			{
+				let num_pos = rest.iter().filter(|l| *l.terms.get(name).unwrap_or(&0) > 0).count();
+				let num_neg = rest.iter().filter(|l| *l.terms.get(name).unwrap_or(&0) < 0).count();
+				(num_pos * num_neg, name.clone())
+			})
+			.min();
+
+		let name = match pick
+		{
+			Option::Some((_, name)) => name,
+			Option::None            => return false,
+		};
+
+		let (with_x, mut next) : (Vec<Lin>, Vec<Lin>) = rest.into_iter().partition(|l| l.terms.contains_key(&name));
+		let pos : Vec<&Lin> = with_x.iter().filter(|l| l.terms[&name] > 0).collect();
+		let neg : Vec<&Lin> = with_x.iter().filter(|l| l.terms[&name] < 0).collect();
+
+		for p in pos.iter()
+		{
+			for n in neg.iter()
+			{
+				let combined = n.scale(p.terms[&name]).and_then(|l| l.add(&p.scale(-n.terms[&name])?, 1));
+				match combined
+				{
+					Option::Some(l) => next.push(normalize(l)),
+					Option::None    => return false,
+				}
+			}
+		}
+
+		if next.len() > MAX_ELIMINATION_CONSTRAINTS
+		{
+			return false;
+		}
+		cons = next;
+	}
+}
+
+fn holds(doms : &Domains, lin : &Lin) -> bool
+{
+	let mut sum = lin.c;
+	for (name, a) in lin.terms.iter()
+	{
+		sum += a * doms[name].0;
+	}
+	sum <= 0
+}
+
+struct Search
+{
+	nodes : usize,
+}
+
+impl Search
+{
+	fn run(&mut self, mut doms : Domains, mut les : Vec<Lin>, mut pending : Vec<Formula>) -> Option<Option<Domains>>
+	{
+		// Option::None: unknown; Option::Some(Option::None): unsat.
+		self.nodes += 1;
+		if self.nodes > MAX_SEARCH_NODES
+		{
+			return Option::None;
+		}
+
+		let mut disjs : Vec<Vec<Formula>> = Vec::new();
+		while let Option::Some(f) = pending.pop()
+		{
+			match f
+			{
+				Formula::Const(true)  => {},
+				Formula::Const(false) => return Option::Some(Option::None),
+				Formula::Le(l)        => les.push(l),
+				Formula::Eq(l)        =>
+				{
+					let neg = l.scale(-1)?;
+					les.push(l);
+					les.push(neg);
+				},
+				// l != 0  <=>  l + 1 <= 0  ||  -l + 1 <= 0
+				Formula::Ne(l)        => disjs.push(vec![Formula::Le(l.shift(1)?), Formula::Le(l.scale(-1)?.shift(1)?)]),
+				Formula::And(v)       => pending.extend(v),
+				Formula::Or(v)        => disjs.push(v),
+			}
+		}
+
+		match propagate(&mut doms, &les)
+		{
+			Propagation::Done     => {},
+			Propagation::Conflict => return Option::Some(Option::None),
+			Propagation::Overflow => return Option::None,
+		}
+
+		let mut is_unknown = false;
+
+		if let Option::Some(alts) = disjs.pop()
+		{
+			for alt in alts.into_iter()
+			{
+				let mut next_pending : Vec<Formula> = disjs.iter().map(|d| Formula::Or(d.clone())).collect();
+				next_pending.push(alt);
+				match self.run(doms.clone(), les.clone(), next_pending)
+				{
+					Option::Some(Option::Some(res)) => return Option::Some(Option::Some(res)),
+					Option::Some(Option::None)      => {},
+					Option::None                    => is_unknown = true,
+				}
+			}
+
+			return if is_unknown { Option::None } else { Option::Some(Option::None) };
+		}
+
+		if is_infeasible(&doms, &les)
+		{
+			return Option::Some(Option::None);
+		}
+
+		// Pick the symbol with the smallest non-singleton domain.
+		let mut pick : Option<(String, i128, i128)> = Option::None;
+		for lin in les.iter()
+		{
+			for name in lin.terms.keys()
+			{
+				let (lo, hi) = doms[name];
+				let is_better = match &pick
+				{
+					Option::Some((_, p_lo, p_hi)) => (hi - lo) < (p_hi - p_lo),
+					Option::None                  => true,
+				};
+				if lo < hi && is_better
+				{
+					pick = Option::Some((name.clone(), lo, hi));
+				}
+			}
+		}
+
+		let (name, lo, hi) = match pick
+		{
+			Option::Some(p) => p,
+			Option::None    =>
+			{
+				return if les.iter().all(|l| holds(&doms, l)) { Option::Some(Option::Some(doms)) } else { Option::Some(Option::None) };
+			},
+		};
+
+		// Try the value closest to zero first, then the values above it, then
+		// below it; the ranges are halved to keep the search shallow.
+		let v = if lo > 0 { lo } else if hi < 0 { hi } else { 0 };
+		let mut choices : Vec<(i128, i128)> = vec![(v, v)];
+		if v < hi
+		{
+			let mid = v + 1 + (hi - v - 1) / 2;
+			choices.push((v + 1, mid));
+			if mid < hi
+			{
+				choices.push((mid + 1, hi));
+			}
+		}
+		if v > lo
+		{
+			let mid = v - 1 - (v - 1 - lo) / 2;
+			choices.push((mid, v - 1));
+			if mid > lo
+			{
+				choices.push((lo, mid - 1));
+			}
+		}
+
+		for (c_lo, c_hi) in choices.into_iter()
+		{
+			let mut next_doms = doms.clone();
+			next_doms.insert(name.clone(), (c_lo, c_hi));
+			match self.run(next_doms, les.clone(), Vec::new())
+			{
+				Option::Some(Option::Some(res)) => return Option::Some(Option::Some(res)),
+				Option::Some(Option::None)      => {},
+				Option::None                    => is_unknown = true,
+			}
+		}
+
+		if is_unknown { Option::None } else { Option::Some(Option::None) }
+	}
+}
+
+/// Decide whether a conjunction of path conditions is satisfiable.
+///
+/// `symbols` gives the type of every free variable in `conds`. Integer symbols
+/// range over `i32`, and arithmetic wraps around in `i32`, as in the
+/// interpreter and in the bitvectors of `smtlib2`. Only linear conditions over
+/// integer and boolean symbols, including divisions by constants, are
+/// supported; anything else yields `SolveResult::Unknown`.
+///
+/// Symbols that are not constrained get the value `0` (`false`).
+///
+pub fn solve(symbols : &Vec<(String, DataType)>, conds : &Vec<Bexp>) -> SolveResult
+{
+	let sym_types : HashMap<String, DataType> = symbols.iter().cloned().collect();
+	let translator = Translator::new(&sym_types);
+
+	// Conditions out of reach are left out; the remaining ones can still be unsatisfiable.
+	let mut formulas : Vec<Formula> = Vec::new();
+	let mut is_complete = true;
+	for cond in conds.iter()
+	{
+		match translator.formula(cond, true)
+		{
+			Option::Some(f) => formulas.push(f),
+			Option::None    => is_complete = false,
+		}
+	}
+
+	let mut doms = Domains::new();
+	for (name, t) in symbols.iter()
+	{
+		match t
+		{
+			DataType::Int32 => { doms.insert(name.clone(), (i32::MIN as i128, i32::MAX as i128)); },
+			DataType::Bool  => { doms.insert(name.clone(), (0, 1)); },
+			_               => {},
+		}
+	}
+	doms.extend(translator.aux_doms.into_inner());
+	formulas.extend(translator.side_conds.into_inner());
+
+	let mut search = Search { nodes : 0 };
+	match search.run(doms, Vec::new(), formulas)
+	{
+		Option::Some(Option::Some(_)) if !is_complete => SolveResult::Unknown,
+		Option::Some(Option::Some(res)) =>
+		{
+			let model = res.iter()
+				.filter(|(name, _)| !name.starts_with('$'))
+				.map(|(name, (lo, hi))|
+				{
+					let v = if *lo > 0 { *lo } else if *hi < 0 { *hi } else { 0 };
+					(name.clone(), v as i64)
+				})
+				.collect();
+			SolveResult::Sat(model)
+		},
+		Option::Some(Option::None)      => SolveResult::Unsat,
+		Option::None                    => SolveResult::Unknown,
+	}
+}
@@ -0,0 +1,10 @@
+pub mod interval;
+pub mod abs_interp;
+pub mod lin_solver;
+pub mod smtlib2;
+pub mod symbolic;
+pub mod wp;
+pub mod sat;
+pub mod bmc;
+pub mod call_graph;
+pub mod resources;
@@ -0,0 +1,694 @@
+use std::fmt;
+use std::rc::Rc;
+use std::vec::Vec;
+use std::string::String;
+use std::string::ToString;
+
+use super::super::ast;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::cmd::Cmd;
+use ast::exp::Exp;
+use ast::func_general::FnCall;
+
+use super::call_graph;
+use super::call_graph::CallGraph;
+
+// The estimates below are rough guesses from the size of the interpreter's
+// frames and states; they haven't been validated against measurements of
+// the interpreter enclave, so the bounds derived from them aren't guaranteed.
+
+/// Estimated stack usage of the interpreter besides the evaluation of the program.
+pub const STACK_BYTES_BASE : u64 = 0x4000;
+/// Estimated stack usage of the interpreter for each nested evaluation of
+/// an AST node, e.g. a command of a sequence or an operand of an operator.
+pub const STACK_BYTES_PER_EVAL : u64 = 1024;
+/// Estimated stack usage of the interpreter for each nested function call,
+/// on top of the evaluations of the call and of the function body.
+pub const STACK_BYTES_PER_CALL : u64 = 1024;
+/// Estimated heap usage of the interpreter besides the program and its states.
+pub const HEAP_BYTES_BASE : u64 = 0x4000;
+/// Estimated heap usage of each AST node of the program.
+pub const HEAP_BYTES_PER_AST_NODE : u64 = 64;
+/// Estimated heap usage of each declared function, i.e. its entry in the
+/// function states.
+pub const HEAP_BYTES_PER_FN : u64 = 1024;
+/// Estimated heap usage of each live variable.
+pub const HEAP_BYTES_PER_VAR : u64 = 128;
+/// Estimated heap usage of each nested level of variables and functions.
+pub const HEAP_BYTES_PER_SCOPE : u64 = 256;
+
+/// Resources of the enclave that runs the interpreter.
+#[derive(Clone, Copy, Debug)]
+pub struct ResourceLimits
+{
+	pub stack_size : u64,
+	pub heap_size : u64,
+	/// Maximum number of evaluated AST nodes, if the execution time is limited.
+	pub max_steps : Option<u64>,
+}
+
+impl ResourceLimits
+{
+	/// `StackMaxSize` and `HeapMaxSize` in the `Enclave.config.xml` of the interpreter enclave.
+	pub fn interpreter_enclave() -> ResourceLimits
+	{
+		ResourceLimits { stack_size : 0x40000, heap_size : 0x100000, max_steps : Option::None }
+	}
+}
+
+/// Upper bounds of the resources used to run a program; `Option::None`
+/// means the resource isn't bounded.
+pub struct ResourceReport
+{
+	/// Maximum number of nested function calls, counting the call of `entry`.
+	pub max_call_depth : Option<u64>,
+	/// Maximum number of nested evaluations of AST nodes.
+	pub max_eval_depth : Option<u64>,
+	/// Maximum number of variables alive at once, including the global ones.
+	pub max_live_vars : Option<u64>,
+	/// Maximum number of nested levels of variables and functions.
+	pub max_scope_depth : Option<u64>,
+	/// Maximum number of evaluated AST nodes.
+	pub max_steps : Option<u64>,
+	pub num_ast_nodes : u64,
+	pub num_funcs : u64,
+	/// Why some resources aren't bounded.
+	pub notes : Vec<String>,
+}
+
+impl ResourceReport
+{
+	pub fn stack_bytes(&self) -> Option<u64>
+	{
+		Option::Some(
+			STACK_BYTES_BASE +
+			self.max_eval_depth? * STACK_BYTES_PER_EVAL +
+			self.max_call_depth? * STACK_BYTES_PER_CALL)
+	}
+
+	pub fn heap_bytes(&self) -> Option<u64>
+	{
+		Option::Some(
+			HEAP_BYTES_BASE +
+			self.num_ast_nodes * HEAP_BYTES_PER_AST_NODE +
+			self.num_funcs * HEAP_BYTES_PER_FN +
+			self.max_live_vars? * HEAP_BYTES_PER_VAR +
+			self.max_scope_depth? * HEAP_BYTES_PER_SCOPE)
+	}
+
+	/// Check the program fits in the limits.
+	pub fn check(&self, limits : &ResourceLimits) -> Result<(), String>
+	{
+		let notes = if self.notes.is_empty() { String::new() } else { format!(" {}", self.notes.join("; ")) };
+
+		match self.stack_bytes()
+		{
+			Option::Some(v) if v <= limits.stack_size => {},
+			Option::Some(v) => return Result::Err(format!("The stack usage of up to {} bytes exceeds {} bytes.", v, limits.stack_size)),
+			Option::None    => return Result::Err(format!("The stack usage isn't bounded.{}", notes)),
+		}
+
+		match self.heap_bytes()
+		{
+			Option::Some(v) if v <= limits.heap_size => {},
+			Option::Some(v) => return Result::Err(format!("The heap usage of up to {} bytes exceeds {} bytes.", v, limits.heap_size)),
+			Option::None    => return Result::Err(format!("The heap usage isn't bounded.{}", notes)),
+		}
+
+		match (limits.max_steps, self.max_steps)
+		{
+			(Option::None, _)                       => {},
+			(Option::Some(max), Option::Some(v)) if v <= max => {},
+			(Option::Some(max), Option::Some(v))    => return Result::Err(format!("Up to {} steps are executed, more than {}.", v, max)),
+			(Option::Some(_), Option::None)         => return Result::Err(format!("The number of steps isn't bounded.{}", notes)),
+		}
+
+		Result::Ok(())
+	}
+}
+
+fn fmt_bound(v : Option<u64>) -> String
+{
+	match v
+	{
+		Option::Some(v) => v.to_string(),
+		Option::None    => format!("{}", "unbounded"),
+	}
+}
+
+impl fmt::Display for ResourceReport
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		writeln!(f, "Resource bounds:")?;
+		writeln!(f, "\tcall depth:       {}", fmt_bound(self.max_call_depth))?;
+		writeln!(f, "\tevaluation depth: {}", fmt_bound(self.max_eval_depth))?;
+		writeln!(f, "\tlive variables:   {}", fmt_bound(self.max_live_vars))?;
+		writeln!(f, "\tscope depth:      {}", fmt_bound(self.max_scope_depth))?;
+		writeln!(f, "\tsteps:            {}", fmt_bound(self.max_steps))?;
+		writeln!(f, "\tAST nodes:        {}", self.num_ast_nodes)?;
+		writeln!(f, "\tfunctions:        {}", self.num_funcs)?;
+		writeln!(f, "\tstack (bytes):    {}", fmt_bound(self.stack_bytes()))?;
+		write!(f, "\theap (bytes):     {}", fmt_bound(self.heap_bytes()))?;
+
+		for note in self.notes.iter()
+		{
+			write!(f, "\n\t{}", note)?;
+		}
+
+		Result::Ok(())
+	}
+}
+
+/// Resources used to evaluate a part of the program; the depths are
+/// counted from where it's evaluated.
+#[derive(Clone, Copy)]
+struct Cost
+{
+	eval_depth : u64,
+	call_depth : u64,
+	/// Maximum number of live variables, including the ones live before it.
+	live_vars : u64,
+	scope_depth : u64,
+	steps : Option<u64>,
+}
+
+impl Cost
+{
+	fn leaf(live_vars : u64) -> Cost
+	{
+		Cost { eval_depth : 1, call_depth : 0, live_vars : live_vars, scope_depth : 0, steps : Option::Some(1) }
+	}
+
+	/// A node that evaluates its children one after another.
+	fn node(children : &[Cost], live_vars : u64) -> Cost
+	{
+		let mut cost = Cost::leaf(live_vars);
+		for c in children.iter()
+		{
+			cost.eval_depth = cost.eval_depth.max(1 + c.eval_depth);
+			cost.call_depth = cost.call_depth.max(c.call_depth);
+			cost.live_vars = cost.live_vars.max(c.live_vars);
+			cost.scope_depth = cost.scope_depth.max(c.scope_depth);
+			cost.steps = match (cost.steps, c.steps)
+			{
+				(Option::Some(a), Option::Some(b)) => a.checked_add(b),
+				_                                  => Option::None,
+			};
+		}
+		cost
+	}
+}
+
+/// Resources used by a call of a function, and why they may be unbounded.
+#[derive(Clone)]
+struct Summary
+{
+	/// `Option::None` if the function may recurse.
+	cost : Option<Cost>,
+	notes : Vec<String>,
+}
+
+struct Analyzer<'a>
+{
+	graph : &'a CallGraph,
+	summaries : Vec<Option<Summary>>,
+	/// Name of the function being analyzed.
+	curr : String,
+	/// A recursive function may be called.
+	is_unbounded : bool,
+	notes : Vec<String>,
+}
+
+impl<'a> Analyzer<'a>
+{
+	fn note(&mut self, note : String)
+	{
+		if !self.notes.contains(&note)
+		{
+			self.notes.push(note);
+		}
+	}
+
+	fn call(&mut self, fc : &FnCall, live : u64) -> Result<Cost, String>
+	{
+		let mut args : Vec<Cost> = Vec::new();
+		for e in fc.exp_list.iter()
+		{
+			args.push(self.exp(e, live)?);
+		}
+
+		let callee = match self.graph.resolve_call(fc)
+		{
+			Option::Some(idx) => idx,
+			Option::None      => return Result::Err(format!("Call {} isn't resolved.", fc)),
+		};
+
+		let summary = match &self.summaries[callee]
+		{
+			Option::Some(s) => s.clone(),
+			Option::None    => Summary
+			{
+				cost : Option::None,
+				notes : vec![format!("{} is recursive.", call_graph::signature(&self.graph.funcs[callee]))],
+			},
+		};
+		for note in summary.notes.into_iter()
+		{
+			self.note(note);
+		}
+
+		let mut cost = Cost::node(&args, live);
+		match summary.cost
+		{
+			Option::Some(body) =>
+			{
+				cost.eval_depth = cost.eval_depth.max(1 + body.eval_depth);
+				cost.call_depth = cost.call_depth.max(1 + body.call_depth);
+				cost.live_vars = cost.live_vars.max(live + body.live_vars);
+				cost.scope_depth = cost.scope_depth.max(body.scope_depth);
+				cost.steps = match (cost.steps, body.steps)
+				{
+					(Option::Some(a), Option::Some(b)) => a.checked_add(b),
+					_                                  => Option::None,
+				};
+			},
+			Option::None       => self.is_unbounded = true,
+		}
+
+		Result::Ok(cost)
+	}
+
+	fn aexp(&mut self, e : &Aexp, live : u64) -> Result<Cost, String>
+	{
+		match e
+		{
+			Aexp::IntConst{..} | Aexp::FloConst{..} | Aexp::Var{..} => Result::Ok(Cost::leaf(live)),
+			Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				let l_cost = self.aexp(l, live)?;
+				let r_cost = self.aexp(r, live)?;
+				Result::Ok(Cost::node(&[l_cost, r_cost], live))
+			},
+			Aexp::FnCall{fc} => self.call(fc, live),
+		}
+	}
+
+	fn bexp(&mut self, e : &Bexp, live : u64) -> Result<Cost, String>
+	{
+		match e
+		{
+			Bexp::BoolConst{..} | Bexp::Var{..} => Result::Ok(Cost::leaf(live)),
+			Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+			{
+				let l_cost = self.bexp(l, live)?;
+				let r_cost = self.bexp(r, live)?;
+				Result::Ok(Cost::node(&[l_cost, r_cost], live))
+			},
+			Bexp::Not{e} =>
+			{
+				let e_cost = self.bexp(e, live)?;
+				Result::Ok(Cost::node(&[e_cost], live))
+			},
+			Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+			{
+				let l_cost = self.aexp(l, live)?;
+				let r_cost = self.aexp(r, live)?;
+				Result::Ok(Cost::node(&[l_cost, r_cost], live))
+			},
+			Bexp::FnCall{fc} => self.call(fc, live),
+		}
+	}
+
+	fn exp(&mut self, e : &Exp, live : u64) -> Result<Cost, String>
+	{
+		let e_cost = match e
+		{
+			Exp::A{e} => self.aexp(e, live)?,
+			Exp::B{e} => self.bexp(e, live)?,
+		};
+		Result::Ok(Cost::node(&[e_cost], live))
+	}
+
+	/// Cost of a command in a new level of variables and functions.
+	fn block(&mut self, c : &Cmd, live : u64) -> Result<Cost, String>
+	{
+		let (mut cost, _) = self.cmd(c, live)?;
+		cost.scope_depth += 1;
+		Result::Ok(cost)
+	}
+
+	/// Cost of a command, and the number of live variables after it.
+	fn cmd(&mut self, c : &Cmd, live : u64) -> Result<(Cost, u64), String>
+	{
+		match c
+		{
+			Cmd::Skip | Cmd::FnDecl{..} => Result::Ok((Cost::leaf(live), live)),
+			Cmd::VarDecl{..} => Result::Ok((Cost::leaf(live + 1), live + 1)),
+			Cmd::Assign{e, ..} =>
+			{
+				let e_cost = self.exp(e, live)?;
+				Result::Ok((Cost::node(&[e_cost], live), live))
+			},
+			Cmd::FnCall{fc} =>
+			{
+				let call_cost = self.call(fc, live)?;
+				Result::Ok((Cost::node(&[call_cost], live), live))
+			},
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				let cond_cost = self.bexp(cond, live)?;
+				let tr_cost = self.block(tr_cmd, live)?;
+				let fa_cost = self.block(fa_cmd, live)?;
+
+				let mut cost = Cost::node(&[cond_cost, tr_cost, fa_cost], live);
+				// Only one of the branches is executed.
+				cost.steps = match (cond_cost.steps, tr_cost.steps, fa_cost.steps)
+				{
+					(Option::Some(c), Option::Some(t), Option::Some(f)) => Option::Some(1 + c + t.max(f)),
+					_                                                   => Option::None,
+				};
+				Result::Ok((cost, live))
+			},
+			Cmd::WhileLoop{cond, lp_cmd, bound, ..} =>
+			{
+				let cond_cost = self.bexp(cond, live)?;
+				let body_cost = self.block(lp_cmd, live)?;
+
+				let mut cost = Cost::node(&[cond_cost, body_cost], live);
+				cost.steps = match (bound, cond_cost.steps, body_cost.steps)
+				{
+					(Option::Some(b), Option::Some(c), Option::Some(s)) =>
+						(b + 1).checked_mul(c)
+							.and_then(|x| b.checked_mul(s).and_then(|y| x.checked_add(y)))
+							.and_then(|x| x.checked_add(1)),
+					(Option::None, _, _) =>
+					{
+						self.note(format!("A loop while {} in {} has no declared bound.", cond, self.curr));
+						Option::None
+					},
+					_ => Option::None,
+				};
+				Result::Ok((cost, live))
+			},
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				let (fst_cost, fst_live) = self.cmd(fst_cmd, live)?;
+				let (snd_cost, snd_live) = self.cmd(snd_cmd, fst_live)?;
+				Result::Ok((Cost::node(&[fst_cost, snd_cost], live), snd_live))
+			},
+			Cmd::Return{e} =>
+			{
+				match e
+				{
+					Option::Some(e) =>
+					{
+						let e_cost = self.exp(e, live)?;
+						Result::Ok((Cost::node(&[e_cost], live), live))
+					},
+					Option::None    => Result::Ok((Cost::leaf(live), live)),
+				}
+			},
+			Cmd::Assert{cond} =>
+			{
+				let cond_cost = self.bexp(cond, live)?;
+				Result::Ok((Cost::node(&[cond_cost], live), live))
+			},
+		}
+	}
+
+	/// Summarize a function whose callees are summarized already.
+	fn summarize(&mut self, idx : usize, fn_cmd : &Cmd) -> Result<Summary, String>
+	{
+		let pt = self.graph.funcs[idx].clone();
+		self.curr = call_graph::signature(&pt);
+		self.is_unbounded = false;
+		self.notes = Vec::new();
+
+		let body = self.block(fn_cmd, pt.var_decl_list.len() as u64)?;
+
+		Result::Ok(Summary
+		{
+			cost : if self.is_unbounded { Option::None } else { Option::Some(body) },
+			notes : std::mem::take(&mut self.notes),
+		})
+	}
+}
+
+/// Bodies of the functions declared in a command, by their address.
+fn gather_bodies(c : &Cmd, graph : &CallGraph, bodies : &mut Vec<Option<Rc<Cmd>>>)
+{
+	match c
+	{
+		Cmd::FnDecl{prototype, fn_cmd} =>
+		{
+			if let Option::Some(idx) = graph.funcs.iter().position(|pt| Rc::ptr_eq(pt, prototype))
+			{
+				bodies[idx] = Option::Some(fn_cmd.clone());
+			}
+			gather_bodies(fn_cmd, graph, bodies);
+		},
+		Cmd::Seq{fst_cmd, snd_cmd} =>
+		{
+			gather_bodies(fst_cmd, graph, bodies);
+			gather_bodies(snd_cmd, graph, bodies);
+		},
+		Cmd::IfElse{tr_cmd, fa_cmd, ..} =>
+		{
+			gather_bodies(tr_cmd, graph, bodies);
+			gather_bodies(fa_cmd, graph, bodies);
+		},
+		Cmd::WhileLoop{lp_cmd, ..} => gather_bodies(lp_cmd, graph, bodies),
+		_ => {},
+	}
+}
+
+fn count_aexp(e : &Aexp) -> u64
+{
+	match e
+	{
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} => 1 + count_aexp(l) + count_aexp(r),
+		Aexp::FnCall{fc} => 1 + fc.exp_list.iter().map(count_exp).sum::<u64>(),
+		_ => 1,
+	}
+}
+
+fn count_bexp(e : &Bexp) -> u64
+{
+	match e
+	{
+		Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} => 1 + count_bexp(l) + count_bexp(r),
+		Bexp::Not{e} => 1 + count_bexp(e),
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} => 1 + count_aexp(l) + count_aexp(r),
+		Bexp::FnCall{fc} => 1 + fc.exp_list.iter().map(count_exp).sum::<u64>(),
+		_ => 1,
+	}
+}
+
+fn count_exp(e : &Exp) -> u64
+{
+	match e
+	{
+		Exp::A{e} => 1 + count_aexp(e),
+		Exp::B{e} => 1 + count_bexp(e),
+	}
+}
+
+/// Number of AST nodes of a command, including the contracts.
+fn count_cmd(c : &Cmd) -> u64
+{
+	match c
+	{
+		Cmd::Skip | Cmd::VarDecl{..} => 1,
+		Cmd::Assign{e, ..} => 1 + count_exp(e),
+		Cmd::FnCall{fc} => 1 + fc.exp_list.iter().map(count_exp).sum::<u64>(),
+		Cmd::IfElse{cond, tr_cmd, fa_cmd} => 1 + count_bexp(cond) + count_cmd(tr_cmd) + count_cmd(fa_cmd),
+		Cmd::WhileLoop{cond, lp_cmd, invariants, ..} =>
+			1 + count_bexp(cond) + count_cmd(lp_cmd) + invariants.iter().map(count_bexp).sum::<u64>(),
+		Cmd::Seq{fst_cmd, snd_cmd} => 1 + count_cmd(fst_cmd) + count_cmd(snd_cmd),
+		Cmd::FnDecl{prototype, fn_cmd} =>
+			1 + count_cmd(fn_cmd) + prototype.requires.iter().chain(prototype.ensures.iter()).map(count_bexp).sum::<u64>(),
+		Cmd::Return{e} => 1 + e.as_ref().map_or(0, |e| count_exp(e)),
+		Cmd::Assert{cond} => 1 + count_bexp(cond),
+	}
+}
+
+/// Bound the resources used by the interpreter to run a program, i.e. its
+/// global commands and then a call of `entry`.
+///
+/// The functions are analyzed bottom-up along the call graph; the stack
+/// and heap usage of a program that may call a recursive function isn't
+/// bounded. The number of steps is bounded only if every executed loop
+/// declares its bound. The byte sizes are estimates of the interpreter's
+/// frames and data structures, see `STACK_BYTES_PER_EVAL` and the others.
+///
+pub fn analyze_program(prog : &Cmd) -> Result<ResourceReport, String>
+{
+	let graph = CallGraph::from_program(prog)?;
+
+	let mut bodies : Vec<Option<Rc<Cmd>>> = vec![Option::None; graph.funcs.len()];
+	gather_bodies(prog, &graph, &mut bodies);
+
+	let mut analyzer = Analyzer
+	{
+		graph : &graph,
+		summaries : vec![Option::None; graph.funcs.len()],
+		curr : String::new(),
+		is_unbounded : false,
+		notes : Vec::new(),
+	};
+
+	// Callees come first; recursive functions are left without summary.
+	let recursive : Vec<usize> = graph.recursive_sccs().into_iter().flatten().collect();
+	for scc in graph.sccs().into_iter()
+	{
+		for idx in scc.into_iter()
+		{
+			if recursive.contains(&idx)
+			{
+				continue;
+			}
+
+			let body = match &bodies[idx]
+			{
+				Option::Some(b) => b.clone(),
+				Option::None    => return Result::Err(format!("Body of {} isn't found.", call_graph::signature(&graph.funcs[idx]))),
+			};
+			let summary = analyzer.summarize(idx, &body)?;
+			analyzer.summaries[idx] = Option::Some(summary);
+		}
+	}
+
+	// The global commands.
+	analyzer.curr = format!("{}", "global commands");
+	analyzer.is_unbounded = false;
+	analyzer.notes = Vec::new();
+	let (global_cost, global_live) = analyzer.cmd(prog, 0)?;
+	let global_cost = Cost::node(&[global_cost], 0);
+
+	// The call of entry, after the global commands.
+	let entries = graph.find("entry");
+	if entries.len() != 1
+	{
+		return Result::Err(format!("There should be exactly one entry function, but {} are declared.", entries.len()));
+	}
+	let entry_summary = match &analyzer.summaries[entries[0]]
+	{
+		Option::Some(s) => s.clone(),
+		Option::None    => Summary
+		{
+			cost : Option::None,
+			notes : vec![format!("{} is recursive.", call_graph::signature(&graph.funcs[entries[0]]))],
+		},
+	};
+	for note in entry_summary.notes.into_iter()
+	{
+		analyzer.note(note);
+	}
+	let entry_cost = entry_summary.cost.map(|mut c|
+		{
+			c.eval_depth += 1;
+			c.call_depth += 1;
+			c.live_vars += global_live;
+			c
+		});
+
+	let is_bounded = !analyzer.is_unbounded && entry_cost.is_some();
+	let bound = |f : &dyn Fn(&Cost) -> u64| -> Option<u64>
+	{
+		if is_bounded
+		{
+			entry_cost.map(|c| f(&c).max(f(&global_cost)))
+		}
+		else
+		{
+			Option::None
+		}
+	};
+
+	Result::Ok(ResourceReport
+	{
+		max_call_depth : bound(&|c| c.call_depth),
+		max_eval_depth : bound(&|c| c.eval_depth),
+		max_live_vars : bound(&|c| c.live_vars),
+		max_scope_depth : bound(&|c| c.scope_depth),
+		max_steps : if is_bounded
+			{
+				match (global_cost.steps, entry_cost.and_then(|c| c.steps))
+				{
+					(Option::Some(g), Option::Some(e)) => g.checked_add(e),
+					_                                  => Option::None,
+				}
+			}
+			else
+			{
+				Option::None
+			},
+		num_ast_nodes : count_cmd(prog),
+		num_funcs : graph.funcs.len() as u64,
+		notes : analyzer.notes,
+	})
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use ast::aexp::constructor_helper::*;
+	use ast::exp::constructor_helper::*;
+	use ast::cmd::constructor_helper::*;
+	use ast::data_type::DataType;
+	use ast::func_general::FnProtoType;
+	use ast::var_general::VarDecl;
+	use ast::var_general::VarRef;
+
+	/// The steps of a bounded loop grow linearly with its bound.
+	#[test]
+	fn bounded_loop_has_bounded_steps()
+	{
+		/* Program:
+		 * fn entry(n : Int32) -> Int32 { while (n > 0) [bound] { n = n - 1; } return n; }
+		 */
+		let steps = |bound : u64|
+		{
+			let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]);
+			let lp = wh_lp_bounded("n".to_aexp().gt(0i32.to_aexp()), assign(VarRef::from_str("n"), ("n".to_aexp() - 1i32.to_aexp()).to_exp()), bound);
+			let prog = fn_dc(fn_prototype, seq(lp, ret(Some("n".to_aexp().to_exp()))));
+
+			analyze_program(&prog).unwrap()
+		};
+
+		let report = steps(10);
+		assert_eq!(report.max_call_depth, Option::Some(1));
+		assert!(report.check(&ResourceLimits::interpreter_enclave()).is_ok());
+
+		let max_steps = |bound : u64| steps(bound).max_steps.unwrap();
+		let per_iter = max_steps(11) - max_steps(10);
+		assert!(per_iter > 0);
+		assert_eq!(max_steps(20), max_steps(10) + 10 * per_iter);
+
+		let limits = ResourceLimits { max_steps : Option::Some(max_steps(10)), ..ResourceLimits::interpreter_enclave() };
+		assert!(report.check(&limits).is_ok());
+		assert!(steps(11).check(&limits).is_err());
+	}
+
+	#[test]
+	fn unbounded_loop_has_unbounded_steps()
+	{
+		/* Program:
+		 * fn entry(n : Int32) -> Int32 { while (n > 0) { n = n - 1; } return n; }
+		 */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]);
+		let lp = wh_lp("n".to_aexp().gt(0i32.to_aexp()), assign(VarRef::from_str("n"), ("n".to_aexp() - 1i32.to_aexp()).to_exp()));
+		let prog = fn_dc(fn_prototype, seq(lp, ret(Some("n".to_aexp().to_exp()))));
+
+		let report = analyze_program(&prog).unwrap();
+		assert_eq!(report.max_steps, Option::None);
+		assert!(report.stack_bytes().is_some());
+		assert!(report.notes.iter().any(|n| n.contains("has no declared bound")), "{:?}", report.notes);
+
+		let limits = ResourceLimits { max_steps : Option::Some(1000), ..ResourceLimits::interpreter_enclave() };
+		assert!(report.check(&limits).unwrap_err().contains("isn't bounded"));
+	}
+}
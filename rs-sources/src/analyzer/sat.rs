@@ -0,0 +1,786 @@
+use std::ops;
+use std::vec::Vec;
+use std::collections::HashMap;
+use std::collections::BinaryHeap;
+
+/// Default maximum number of conflicts of one `Solver::solve` call.
+pub const DEFAULT_MAX_CONFLICTS : u64 = 200000;
+
+/// Number of conflicts before the first restart.
+const RESTART_BASE : u64 = 100;
+
+/// A literal of a boolean variable, either positive or negated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(u32);
+
+impl Lit
+{
+	fn new(var : usize, is_neg : bool) -> Lit
+	{
+		Lit(((var as u32) << 1) | (is_neg as u32))
+	}
+
+	fn var(self) -> usize
+	{
+		(self.0 >> 1) as usize
+	}
+
+	fn is_neg(self) -> bool
+	{
+		(self.0 & 1) != 0
+	}
+
+	fn index(self) -> usize
+	{
+		self.0 as usize
+	}
+}
+
+impl ops::Not for Lit
+{
+	type Output = Lit;
+
+	fn not(self) -> Lit
+	{
+		Lit(self.0 ^ 1)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SatResult
+{
+	Sat,
+	Unsat,
+	/// The solver gave up after `max_conflicts` conflicts.
+	Unknown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Gate
+{
+	And,
+	Xor,
+	Mux,
+}
+
+/// A CDCL SAT solver (two watched literals, first-UIP clause learning,
+/// VSIDS branching, phase saving and Luby restarts), with incremental
+/// solving under assumptions.
+///
+/// It also builds the gates of a circuit over its variables; the gates
+/// are folded on constants and hashed, so equal gates share one variable.
+///
+pub struct Solver
+{
+	clauses : Vec<Vec<Lit>>,
+	/// Clauses watching each literal, to be visited when it becomes false.
+	watches : Vec<Vec<usize>>,
+	/// Value of each variable; 0: unassigned, 1: true, -1: false.
+	assigns : Vec<i8>,
+	levels : Vec<usize>,
+	reasons : Vec<Option<usize>>,
+	trail : Vec<Lit>,
+	trail_lim : Vec<usize>,
+	qhead : usize,
+	activity : Vec<f64>,
+	var_inc : f64,
+	order : BinaryHeap<(u64, usize)>,
+	polarity : Vec<bool>,
+	/// Scratch marks of the conflict analysis; all false between analyses.
+	seen : Vec<bool>,
+	model : Vec<bool>,
+	/// False once the clauses are unsatisfiable without any assumption.
+	ok : bool,
+	gates : HashMap<(Gate, Lit, Lit, Lit), Lit>,
+	true_lit : Lit,
+	pub max_conflicts : u64,
+}
+
+fn luby(i : u64) -> u64
+{
+	// 1, 1, 2, 1, 1, 2, 4, 1, 1, 2, ...
+	let mut size : u64 = 1;
+	let mut seq : u64 = 0;
+	while size < i + 1
+	{
+		seq += 1;
+		size = 2 * size + 1;
+	}
+
+	let mut x = i;
+	while size - 1 != x
+	{
+		size = (size - 1) >> 1;
+		seq -= 1;
+		x %= size;
+	}
+
+	1u64 << seq
+}
+
+impl Solver
+{
+	pub fn new() -> Solver
+	{
+		let mut solver = Solver
+		{
+			clauses : Vec::new(),
+			watches : Vec::new(),
+			assigns : Vec::new(),
+			levels : Vec::new(),
+			reasons : Vec::new(),
+			trail : Vec::new(),
+			trail_lim : Vec::new(),
+			qhead : 0,
+			activity : Vec::new(),
+			var_inc : 1.0,
+			order : BinaryHeap::new(),
+			polarity : Vec::new(),
+			seen : Vec::new(),
+			model : Vec::new(),
+			ok : true,
+			gates : HashMap::new(),
+			true_lit : Lit(0),
+			max_conflicts : DEFAULT_MAX_CONFLICTS,
+		};
+
+		let t = solver.new_var();
+		solver.add_clause(&[t]);
+		solver.true_lit = t;
+
+		solver
+	}
+
+	pub fn num_vars(&self) -> usize
+	{
+		self.assigns.len()
+	}
+
+	pub fn num_clauses(&self) -> usize
+	{
+		self.clauses.len()
+	}
+
+	/// The literal that is always true.
+	pub fn lit_true(&self) -> Lit
+	{
+		self.true_lit
+	}
+
+	pub fn lit_false(&self) -> Lit
+	{
+		!self.true_lit
+	}
+
+	pub fn lit_const(&self, v : bool) -> Lit
+	{
+		if v { self.lit_true() } else { self.lit_false() }
+	}
+
+	/// A fresh, unconstrained variable.
+	pub fn new_var(&mut self) -> Lit
+	{
+		let var = self.assigns.len();
+		self.assigns.push(0);
+		self.levels.push(0);
+		self.reasons.push(Option::None);
+		self.activity.push(0.0);
+		self.polarity.push(false);
+		self.seen.push(false);
+		self.watches.push(Vec::new());
+		self.watches.push(Vec::new());
+		self.order.push((0, var));
+
+		Lit::new(var, false)
+	}
+
+	fn lit_value(&self, lit : Lit) -> i8
+	{
+		let v = self.assigns[lit.var()];
+		if lit.is_neg() { -v } else { v }
+	}
+
+	fn decision_level(&self) -> usize
+	{
+		self.trail_lim.len()
+	}
+
+	fn enqueue(&mut self, lit : Lit, reason : Option<usize>)
+	{
+		let var = lit.var();
+		self.assigns[var] = if lit.is_neg() { -1 } else { 1 };
+		self.levels[var] = self.decision_level();
+		self.reasons[var] = reason;
+		self.trail.push(lit);
+	}
+
+	fn cancel_until(&mut self, level : usize)
+	{
+		if self.decision_level() <= level
+		{
+			return;
+		}
+
+		let lim = self.trail_lim[level];
+		for i in (lim..self.trail.len()).rev()
+		{
+			let lit = self.trail[i];
+			let var = lit.var();
+			self.polarity[var] = lit.is_neg();
+			self.assigns[var] = 0;
+			self.reasons[var] = Option::None;
+			self.order.push((self.activity[var].to_bits(), var));
+		}
+		self.trail.truncate(lim);
+		self.trail_lim.truncate(level);
+		self.qhead = lim;
+	}
+
+	/// Add a clause; it's only allowed between calls of `solve`.
+	pub fn add_clause(&mut self, lits : &[Lit])
+	{
+		if !self.ok
+		{
+			return;
+		}
+
+		let mut clause : Vec<Lit> = Vec::new();
+		for lit in lits.iter()
+		{
+			match self.lit_value(*lit)
+			{
+				1 => return,
+				-1 => {},
+				_ =>
+				{
+					if clause.contains(&!*lit)
+					{
+						return;
+					}
+					if !clause.contains(lit)
+					{
+						clause.push(*lit);
+					}
+				},
+			}
+		}
+
+		match clause.len()
+		{
+			0 => self.ok = false,
+			1 =>
+			{
+				self.enqueue(clause[0], Option::None);
+				if self.propagate().is_some()
+				{
+					self.ok = false;
+				}
+			},
+			_ =>
+			{
+				self.attach(clause);
+			},
+		}
+	}
+
+	fn attach(&mut self, clause : Vec<Lit>) -> usize
+	{
+		let ci = self.clauses.len();
+		self.watches[(!clause[0]).index()].push(ci);
+		self.watches[(!clause[1]).index()].push(ci);
+		self.clauses.push(clause);
+		ci
+	}
+
+	/// Unit propagation; returns the conflicting clause, if any.
+	fn propagate(&mut self) -> Option<usize>
+	{
+		while self.qhead < self.trail.len()
+		{
+			let p = self.trail[self.qhead];
+			self.qhead += 1;
+			let false_lit = !p;
+
+			let mut ws = std::mem::take(&mut self.watches[p.index()]);
+			let mut i : usize = 0;
+			let mut j : usize = 0;
+			let mut conflict : Option<usize> = Option::None;
+
+			while i < ws.len()
+			{
+				let ci = ws[i];
+				i += 1;
+
+				let clause = &mut self.clauses[ci];
+				if clause[0] == false_lit
+				{
+					clause.swap(0, 1);
+				}
+
+				let first = clause[0];
+				let first_val = { let v = self.assigns[first.var()]; if first.is_neg() { -v } else { v } };
+				if first_val == 1
+				{
+					ws[j] = ci;
+					j += 1;
+					continue;
+				}
+
+				let mut new_watch : Option<Lit> = Option::None;
+				for k in 2..clause.len()
+				{
+					let lit = clause[k];
+					let v = self.assigns[lit.var()];
+					let lit_val = if lit.is_neg() { -v } else { v };
+					if lit_val != -1
+					{
+						clause.swap(1, k);
+						new_watch = Option::Some(lit);
+						break;
+					}
+				}
+
+				if let Option::Some(lit) = new_watch
+				{
+					self.watches[(!lit).index()].push(ci);
+					continue;
+				}
+
+				ws[j] = ci;
+				j += 1;
+
+				if first_val == -1
+				{
+					conflict = Option::Some(ci);
+					while i < ws.len()
+					{
+						ws[j] = ws[i];
+						j += 1;
+						i += 1;
+					}
+				}
+				else
+				{
+					self.enqueue(first, Option::Some(ci));
+				}
+			}
+
+			ws.truncate(j);
+			self.watches[p.index()] = ws;
+
+			if conflict.is_some()
+			{
+				self.qhead = self.trail.len();
+				return conflict;
+			}
+		}
+
+		Option::None
+	}
+
+	fn bump(&mut self, var : usize)
+	{
+		self.activity[var] += self.var_inc;
+		if self.activity[var] > 1e100
+		{
+			for a in self.activity.iter_mut()
+			{
+				*a *= 1e-100;
+			}
+			self.var_inc *= 1e-100;
+
+			self.order.clear();
+			for v in 0..self.assigns.len()
+			{
+				if self.assigns[v] == 0
+				{
+					self.order.push((self.activity[v].to_bits(), v));
+				}
+			}
+		}
+		else if self.assigns[var] == 0
+		{
+			self.order.push((self.activity[var].to_bits(), var));
+		}
+	}
+
+	/// First-UIP conflict analysis; returns the learnt clause, whose first
+	/// literal is asserting, and the level to backtrack to.
+	fn analyze(&mut self, conflict : usize) -> (Vec<Lit>, usize)
+	{
+		let mut learnt : Vec<Lit> = vec![self.true_lit];
+		let mut num_pending : usize = 0;
+		let mut p : Option<Lit> = Option::None;
+		let mut idx = self.trail.len();
+		let mut confl = conflict;
+
+		loop
+		{
+			let clause = self.clauses[confl].clone();
+			let start = if p.is_some() { 1 } else { 0 };
+			for q in clause[start..].iter()
+			{
+				let var = q.var();
+				if !self.seen[var] && self.levels[var] > 0
+				{
+					self.seen[var] = true;
+					self.bump(var);
+					if self.levels[var] >= self.decision_level()
+					{
+						num_pending += 1;
+					}
+					else
+					{
+						learnt.push(*q);
+					}
+				}
+			}
+
+			loop
+			{
+				idx -= 1;
+				if self.seen[self.trail[idx].var()]
+				{
+					break;
+				}
+			}
+
+			let lit = self.trail[idx];
+			self.seen[lit.var()] = false;
+			p = Option::Some(lit);
+			num_pending -= 1;
+
+			if num_pending == 0
+			{
+				break;
+			}
+
+			confl = match self.reasons[lit.var()]
+			{
+				Option::Some(ci) => ci,
+				Option::None     => unreachable!(),
+			};
+		}
+
+		learnt[0] = !p.unwrap();
+		for lit in learnt[1..].iter()
+		{
+			self.seen[lit.var()] = false;
+		}
+
+		let mut bt_level : usize = 0;
+		if learnt.len() > 1
+		{
+			let mut max_i = 1;
+			for i in 2..learnt.len()
+			{
+				if self.levels[learnt[i].var()] > self.levels[learnt[max_i].var()]
+				{
+					max_i = i;
+				}
+			}
+			learnt.swap(1, max_i);
+			bt_level = self.levels[learnt[1].var()];
+		}
+
+		(learnt, bt_level)
+	}
+
+	fn pick_branch_var(&mut self) -> Option<usize>
+	{
+		while let Option::Some((act, var)) = self.order.pop()
+		{
+			if self.assigns[var] == 0 && act == self.activity[var].to_bits()
+			{
+				return Option::Some(var);
+			}
+		}
+
+		// Entries may be missing after a rescale; fall back to a scan.
+		(0..self.assigns.len()).find(|v| self.assigns[*v] == 0)
+	}
+
+	/// Decide if the clauses and the `assumptions` are satisfiable together.
+	pub fn solve(&mut self, assumptions : &[Lit]) -> SatResult
+	{
+		if !self.ok
+		{
+			return SatResult::Unsat;
+		}
+
+		let mut num_conflicts : u64 = 0;
+		let mut restart_num : u64 = 0;
+		let mut restart_limit = RESTART_BASE * luby(restart_num);
+		let mut conflicts_since_restart : u64 = 0;
+
+		loop
+		{
+			if let Option::Some(confl) = self.propagate()
+			{
+				num_conflicts += 1;
+				conflicts_since_restart += 1;
+
+				if self.decision_level() == 0
+				{
+					self.ok = false;
+					return SatResult::Unsat;
+				}
+
+				let (learnt, bt_level) = self.analyze(confl);
+				self.cancel_until(bt_level);
+
+				if learnt.len() == 1
+				{
+					self.enqueue(learnt[0], Option::None);
+				}
+				else
+				{
+					let asserting = learnt[0];
+					let ci = self.attach(learnt);
+					self.enqueue(asserting, Option::Some(ci));
+				}
+
+				self.var_inc *= 1.0 / 0.95;
+
+				if num_conflicts >= self.max_conflicts
+				{
+					self.cancel_until(0);
+					return SatResult::Unknown;
+				}
+
+				if conflicts_since_restart >= restart_limit
+				{
+					self.cancel_until(0);
+					restart_num += 1;
+					restart_limit = RESTART_BASE * luby(restart_num);
+					conflicts_since_restart = 0;
+				}
+
+				continue;
+			}
+
+			let level = self.decision_level();
+			if level < assumptions.len()
+			{
+				let a = assumptions[level];
+				match self.lit_value(a)
+				{
+					1  => self.trail_lim.push(self.trail.len()),
+					-1 =>
+					{
+						self.cancel_until(0);
+						return SatResult::Unsat;
+					},
+					_  =>
+					{
+						self.trail_lim.push(self.trail.len());
+						self.enqueue(a, Option::None);
+					},
+				}
+				continue;
+			}
+
+			match self.pick_branch_var()
+			{
+				Option::Some(var) =>
+				{
+					self.trail_lim.push(self.trail.len());
+					let lit = Lit::new(var, self.polarity[var]);
+					self.enqueue(lit, Option::None);
+				},
+				Option::None      =>
+				{
+					self.model = self.assigns.iter().map(|v| *v == 1).collect();
+					self.cancel_until(0);
+					return SatResult::Sat;
+				},
+			}
+		}
+	}
+
+	/// Value of a literal in the model found by the last successful `solve`.
+	pub fn model_value(&self, lit : Lit) -> bool
+	{
+		let v = self.model.get(lit.var()).cloned().unwrap_or(false);
+		v != lit.is_neg()
+	}
+
+	fn cached_gate(&mut self, key : (Gate, Lit, Lit, Lit)) -> (Lit, bool)
+	{
+		match self.gates.get(&key)
+		{
+			Option::Some(o) => (*o, false),
+			Option::None    =>
+			{
+				let o = self.new_var();
+				self.gates.insert(key, o);
+				(o, true)
+			},
+		}
+	}
+
+	pub fn and(&mut self, a : Lit, b : Lit) -> Lit
+	{
+		let (t, f) = (self.lit_true(), self.lit_false());
+		if a == f || b == f || a == !b
+		{
+			return f;
+		}
+		if a == t || a == b
+		{
+			return b;
+		}
+		if b == t
+		{
+			return a;
+		}
+
+		let (a, b) = if a < b { (a, b) } else { (b, a) };
+		let (o, is_new) = self.cached_gate((Gate::And, a, b, t));
+		if is_new
+		{
+			self.add_clause(&[!o, a]);
+			self.add_clause(&[!o, b]);
+			self.add_clause(&[o, !a, !b]);
+		}
+		o
+	}
+
+	pub fn or(&mut self, a : Lit, b : Lit) -> Lit
+	{
+		!self.and(!a, !b)
+	}
+
+	pub fn xor(&mut self, a : Lit, b : Lit) -> Lit
+	{
+		let (t, f) = (self.lit_true(), self.lit_false());
+		if a == f
+		{
+			return b;
+		}
+		if b == f
+		{
+			return a;
+		}
+		if a == t
+		{
+			return !b;
+		}
+		if b == t
+		{
+			return !a;
+		}
+		if a == b
+		{
+			return f;
+		}
+		if a == !b
+		{
+			return t;
+		}
+
+		// Normalize to positive inputs: x ^ !y == !(x ^ y).
+		let flip = a.is_neg() != b.is_neg();
+		let (a, b) = (Lit::new(a.var(), false), Lit::new(b.var(), false));
+		let (a, b) = if a < b { (a, b) } else { (b, a) };
+		let (o, is_new) = self.cached_gate((Gate::Xor, a, b, t));
+		if is_new
+		{
+			self.add_clause(&[!o, a, b]);
+			self.add_clause(&[!o, !a, !b]);
+			self.add_clause(&[o, !a, b]);
+			self.add_clause(&[o, a, !b]);
+		}
+		if flip { !o } else { o }
+	}
+
+	/// `if s then a else b`.
+	pub fn mux(&mut self, s : Lit, a : Lit, b : Lit) -> Lit
+	{
+		let (t, f) = (self.lit_true(), self.lit_false());
+		if s == t || a == b
+		{
+			return a;
+		}
+		if s == f
+		{
+			return b;
+		}
+		if a == t || a == s
+		{
+			return self.or(s, b);
+		}
+		if a == f || a == !s
+		{
+			return self.and(!s, b);
+		}
+		if b == t || b == !s
+		{
+			return self.or(!s, a);
+		}
+		if b == f || b == s
+		{
+			return self.and(s, a);
+		}
+
+		let (o, is_new) = self.cached_gate((Gate::Mux, s, a, b));
+		if is_new
+		{
+			self.add_clause(&[!s, !a, o]);
+			self.add_clause(&[!s, a, !o]);
+			self.add_clause(&[s, !b, o]);
+			self.add_clause(&[s, b, !o]);
+		}
+		o
+	}
+
+	/// Disjunction of all `lits`; false if there is none.
+	pub fn or_all(&mut self, lits : &[Lit]) -> Lit
+	{
+		let mut res = self.lit_false();
+		for lit in lits.iter()
+		{
+			res = self.or(res, *lit);
+		}
+		res
+	}
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+
+	/// (a | b) & (!a | b) & (a | !b) is satisfied by a = b = true only.
+	#[test]
+	fn tiny_cnf_is_sat()
+	{
+		let mut solver = Solver::new();
+		let a = solver.new_var();
+		let b = solver.new_var();
+		solver.add_clause(&[a, b]);
+		solver.add_clause(&[!a, b]);
+		solver.add_clause(&[a, !b]);
+
+		assert_eq!(solver.solve(&[]), SatResult::Sat);
+		assert!(solver.model_value(a));
+		assert!(solver.model_value(b));
+
+		assert_eq!(solver.solve(&[!a]), SatResult::Unsat);
+	}
+
+	/// Adding (!a | !b) to the formula above makes it unsatisfiable.
+	#[test]
+	fn tiny_cnf_is_unsat()
+	{
+		let mut solver = Solver::new();
+		let a = solver.new_var();
+		let b = solver.new_var();
+		solver.add_clause(&[a, b]);
+		solver.add_clause(&[!a, b]);
+		solver.add_clause(&[a, !b]);
+		solver.add_clause(&[!a, !b]);
+
+		assert_eq!(solver.solve(&[]), SatResult::Unsat);
+	}
+}
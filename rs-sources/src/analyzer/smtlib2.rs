@@ -0,0 +1,153 @@
+use std::vec::Vec;
+use std::string::String;
+use std::collections::HashMap;
+
+use super::super::ast;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::data_type::DataType;
+
+fn smt_int(v : i32) -> String
+{
+	format!("#x{:08x}", v as u32)
+}
+
+/// An `i32` term as a `Real` one, for the operations mixing them with `f32`.
+fn to_real(s : String, t : DataType) -> String
+{
+	if t == DataType::Int32 { format!("(sbv_to_real {})", s) } else { s }
+}
+
+fn smt_real(v : f32) -> Result<String, String>
+{
+	if !v.is_finite()
+	{
+		return Result::Err(format!("Float constant {} can't be expressed in SMT-LIB2.", v));
+	}
+
+	let mut s = format!("{}", v.abs());
+	if !s.contains('.')
+	{
+		s.push_str(".0");
+	}
+
+	Result::Ok(if v < 0.0 { format!("(- {})", s) } else { s })
+}
+
+fn smt_aexp(e : &Aexp, symbols : &HashMap<String, DataType>) -> Result<(String, DataType), String>
+{
+	match e
+	{
+		Aexp::IntConst{v} => Result::Ok((smt_int(*v), DataType::Int32)),
+		Aexp::FloConst{v} => Result::Ok((smt_real(*v)?, DataType::Float32)),
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+		{
+			let (l_s, l_t) = smt_aexp(l, symbols)?;
+			let (r_s, r_t) = smt_aexp(r, symbols)?;
+			let is_int = l_t == DataType::Int32 && r_t == DataType::Int32;
+			let (l_s, r_s) = if is_int { (l_s, r_s) } else { (to_real(l_s, l_t), to_real(r_s, r_t)) };
+
+			let op = match (e, is_int)
+			{
+				(Aexp::Add{..}, true)  => "bvadd",
+				(Aexp::Sub{..}, true)  => "bvsub",
+				(Aexp::Mul{..}, true)  => "bvmul",
+				(Aexp::Div{..}, true)  => "bvsdiv",
+				(Aexp::Mod{..}, true)  => "bvsrem",
+				(Aexp::Add{..}, false) => "+",
+				(Aexp::Sub{..}, false) => "-",
+				(Aexp::Mul{..}, false) => "*",
+				(Aexp::Div{..}, false) => "/",
+				_                      => return Result::Err(format!("Float remainder {} can't be expressed in SMT-LIB2.", e)),
+			};
+
+			Result::Ok((format!("({} {} {})", op, l_s, r_s), if is_int { DataType::Int32 } else { DataType::Float32 }))
+		},
+		Aexp::Var{v} => match symbols.get(&v.name)
+		{
+			Option::Some(t) => Result::Ok((v.name.clone(), *t)),
+			Option::None    => Result::Err(format!("Unknown symbol {}.", v.name)),
+		},
+		Aexp::FnCall{fc} => Result::Err(format!("Unexpected function call {} in a condition.", fc)),
+	}
+}
+
+fn smt_bexp(e : &Bexp, symbols : &HashMap<String, DataType>) -> Result<String, String>
+{
+	match e
+	{
+		Bexp::BoolConst{v} => Result::Ok(format!("{}", v)),
+		Bexp::Beq{l, r}  => Result::Ok(format!("(= {} {})", smt_bexp(l, symbols)?, smt_bexp(r, symbols)?)),
+		Bexp::Bneq{l, r} => Result::Ok(format!("(distinct {} {})", smt_bexp(l, symbols)?, smt_bexp(r, symbols)?)),
+		Bexp::And{l, r}  => Result::Ok(format!("(and {} {})", smt_bexp(l, symbols)?, smt_bexp(r, symbols)?)),
+		Bexp::Or{l, r}   => Result::Ok(format!("(or {} {})", smt_bexp(l, symbols)?, smt_bexp(r, symbols)?)),
+		Bexp::Not{e}     => Result::Ok(format!("(not {})", smt_bexp(e, symbols)?)),
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+		{
+			let (l_s, l_t) = smt_aexp(l, symbols)?;
+			let (r_s, r_t) = smt_aexp(r, symbols)?;
+			let is_int = l_t == DataType::Int32 && r_t == DataType::Int32;
+			let (l_s, r_s) = if is_int { (l_s, r_s) } else { (to_real(l_s, l_t), to_real(r_s, r_t)) };
+
+			let op = match (e, is_int)
+			{
+				(Bexp::Aeq{..}, _)     => "=",
+				(Bexp::Aneq{..}, _)    => "distinct",
+				(Bexp::Lt{..}, true)   => "bvslt",
+				(Bexp::Lte{..}, true)  => "bvsle",
+				(Bexp::Gt{..}, true)   => "bvsgt",
+				(_, true)              => "bvsge",
+				(Bexp::Lt{..}, false)  => "<",
+				(Bexp::Lte{..}, false) => "<=",
+				(Bexp::Gt{..}, false)  => ">",
+				_                      => ">=",
+			};
+
+			Result::Ok(format!("({} {} {})", op, l_s, r_s))
+		},
+		Bexp::Var{v} => Result::Ok(v.name.clone()),
+		Bexp::FnCall{fc} => Result::Err(format!("Unexpected function call {} in a condition.", fc)),
+	}
+}
+
+/// Emit an SMT-LIB2 script that asserts all the conditions in `asserts`.
+///
+/// `i32` symbols are declared as `(_ BitVec 32)`, so every intermediate term
+/// wraps like the interpreter does; `f32` ones as `Real`, and `bool` ones as
+/// `Bool`. Integer division and remainder are `bvsdiv` and `bvsrem`, which
+/// truncate towards zero, and `i32` terms mixed with `f32` ones are converted
+/// by `sbv_to_real`. `comment` is written on the first line.
+///
+pub fn script(comment : &str, symbols : &Vec<(String, DataType)>, asserts : &Vec<Bexp>) -> Result<String, String>
+{
+	let sym_types : HashMap<String, DataType> = symbols.iter().cloned().collect();
+
+	let mut out = String::new();
+	for line in comment.lines()
+	{
+		out.push_str(&format!("; {}\n", line));
+	}
+	out.push_str("(set-logic ALL)\n");
+	out.push_str("(define-fun sbv_to_real ((a (_ BitVec 32))) Real (ite (bvslt a #x00000000) (- (to_real (bv2nat a)) 4294967296.0) (to_real (bv2nat a))))\n");
+
+	for (name, t) in symbols.iter()
+	{
+		match t
+		{
+			DataType::Int32   => out.push_str(&format!("(declare-const {} (_ BitVec 32))\n", name)),
+			DataType::Float32 => out.push_str(&format!("(declare-const {} Real)\n", name)),
+			DataType::Bool    => out.push_str(&format!("(declare-const {} Bool)\n", name)),
+			DataType::Void    => return Result::Err(format!("Symbol {} has void type.", name)),
+		}
+	}
+
+	for cond in asserts.iter()
+	{
+		out.push_str(&format!("(assert {})\n", smt_bexp(cond, &sym_types)?));
+	}
+
+	out.push_str("(check-sat)\n");
+	out.push_str("(get-model)\n");
+
+	Result::Ok(out)
+}
@@ -0,0 +1,1095 @@
+use std::fmt;
+use std::rc::Rc;
+use std::vec::Vec;
+use std::boxed::Box;
+use std::string::String;
+use std::string::ToString;
+use std::collections::HashMap;
+
+use super::super::ast;
+use super::super::typing;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::cmd::Cmd;
+use ast::exp::Exp;
+use ast::data_type::DataType;
+use ast::func_general::FnCall;
+use ast::func_general::FnProtoType;
+use ast::var_general::VarDecl;
+use ast::var_general::VarRef;
+
+use super::lin_solver;
+use super::smtlib2;
+use super::lin_solver::SolveResult;
+
+/// Maximum number of iterations of a loop on one path, including the ones
+/// whose condition doesn't depend on the entry parameters.
+const MAX_CONCRETE_ITERATIONS : u64 = 100000;
+
+/// Limits of the symbolic execution.
+#[derive(Debug, Clone, Copy)]
+pub struct SymConfig
+{
+	/// Maximum number of iterations of a loop explored on one path, counting
+	/// only the iterations whose condition depends on the entry parameters.
+	pub loop_bound : usize,
+	/// Maximum depth of nested function calls explored on one path.
+	pub max_call_depth : usize,
+	/// Maximum number of paths; the execution fails if there are more.
+	pub max_paths : usize,
+}
+
+impl SymConfig
+{
+	pub fn new(loop_bound : usize) -> SymConfig
+	{
+		SymConfig { loop_bound : loop_bound, max_call_depth : 16, max_paths : 1024 }
+	}
+}
+
+/// How a path of `entry` ends.
+#[derive(Clone)]
+pub enum PathOutcome
+{
+	/// `entry` returns, with a value over the entry parameters if it's not void.
+	Returned(Option<Exp>),
+	/// The execution fails at runtime, e.g. on a division by zero.
+	Error(String),
+	/// The path exceeds the loop unroll bound or the call depth limit.
+	Truncated,
+}
+
+impl fmt::Display for PathOutcome
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			PathOutcome::Returned(Option::Some(e)) => write!(f, "returns {}", e),
+			PathOutcome::Returned(Option::None)    => write!(f, "{}", "returns"),
+			PathOutcome::Error(why)                => write!(f, "fails: {}", why),
+			PathOutcome::Truncated                 => write!(f, "{}", "truncated"),
+		}
+	}
+}
+
+/// A path through `entry`, with the conditions (over the entry parameters)
+/// under which it is taken.
+#[derive(Clone)]
+pub struct SymPath
+{
+	pub conds : Vec<Bexp>,
+	pub outcome : PathOutcome,
+}
+
+impl SymPath
+{
+	pub fn is_truncated(&self) -> bool
+	{
+		match self.outcome
+		{
+			PathOutcome::Truncated => true,
+			_                      => false,
+		}
+	}
+}
+
+impl fmt::Display for SymPath
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		if self.conds.is_empty()
+		{
+			write!(f, "{}", "true")?;
+		}
+		for (i, cond) in self.conds.iter().enumerate()
+		{
+			if i > 0
+			{
+				write!(f, "{}", " && ")?;
+			}
+			write!(f, "{}", cond)?;
+		}
+
+		write!(f, " => {}", self.outcome)
+	}
+}
+
+/// Result of the symbolic execution of a program.
+pub struct SymReport
+{
+	/// The parameters of `entry`, which are the symbols of the path conditions.
+	pub params : Vec<VarDecl>,
+	pub paths : Vec<SymPath>,
+}
+
+impl SymReport
+{
+	pub fn symbols(&self) -> Vec<(String, DataType)>
+	{
+		self.params.iter().map(|p| (p.name.clone(), p.var_type)).collect()
+	}
+
+	/// Find concrete parameters that drive `entry` through the given path.
+	///
+	/// Returns `Result::Ok(Option::None)` if the path is infeasible, and an
+	/// error if the built-in solver can't decide the path condition.
+	///
+	pub fn gen_param_list(&self, path : &SymPath) -> Result<Option<Vec<Exp>>, String>
+	{
+		use ast::aexp::constructor_helper::ToAexp;
+		use ast::bexp::constructor_helper::ToBexp;
+		use ast::exp::constructor_helper::ToExp;
+
+		let model = match lin_solver::solve(&self.symbols(), &path.conds)
+		{
+			SolveResult::Sat(m)  => m,
+			SolveResult::Unsat   => return Result::Ok(Option::None),
+			SolveResult::Unknown => return Result::Err(format!("{}", "The path condition is out of reach of the built-in solver.")),
+		};
+
+		let mut param_list : Vec<Exp> = Vec::new();
+		for p in self.params.iter()
+		{
+			let v = model.get(&p.name).cloned().unwrap_or(0);
+			param_list.push(match p.var_type
+			{
+				DataType::Int32   => (v as i32).to_aexp().to_exp(),
+				DataType::Bool    => (v != 0).to_bexp().to_exp(),
+				// Float parameters never appear in a solvable path condition.
+				DataType::Float32 => 0f32.to_aexp().to_exp(),
+				DataType::Void    => return Result::Err(format!("Parameter {} has void type.", p.name)),
+			});
+		}
+
+		Result::Ok(Option::Some(param_list))
+	}
+}
+
+impl fmt::Display for SymReport
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		for (i, path) in self.paths.iter().enumerate()
+		{
+			write!(f, "path {}: {}\n", i, path)?;
+		}
+		write!(f, "{} path(s).", self.paths.len())
+	}
+}
+
+#[derive(Clone)]
+struct SymVar
+{
+	t : DataType,
+	v : Option<Exp>,
+}
+
+#[derive(Clone)]
+enum Status
+{
+	Running,
+	Returned(Option<Exp>),
+}
+
+/// State of one path; values are expressions over the entry parameters.
+#[derive(Clone)]
+struct SymState
+{
+	scopes : Vec<HashMap<String, SymVar> >,
+	conds : Vec<Bexp>,
+	status : Status,
+}
+
+impl SymState
+{
+	fn get(&self, name : &String) -> Option<&SymVar>
+	{
+		self.scopes.iter().rev().filter_map(|s| s.get(name)).next()
+	}
+
+	fn get_mut(&mut self, name : &String) -> Option<&mut SymVar>
+	{
+		self.scopes.iter_mut().rev().filter_map(|s| s.get_mut(name)).next()
+	}
+
+	fn decl(&mut self, decl : &VarDecl) -> Result<(), String>
+	{
+		let scope = self.scopes.last_mut().unwrap();
+		if scope.contains_key(&decl.name)
+		{
+			return Result::Err(format!("Variable {} is declared repeatedly.", decl.name));
+		}
+		scope.insert(decl.name.clone(), SymVar { t : decl.var_type, v : Option::None });
+
+		Result::Ok(())
+	}
+
+	fn is_running(&self) -> bool
+	{
+		match self.status
+		{
+			Status::Running => true,
+			_               => false,
+		}
+	}
+}
+
+fn mangle_fn_name(name : &String, types : &Vec<DataType>) -> String
+{
+	let mut mangled_fun_name = String::new();
+	mangled_fun_name.push_str(name);
+	mangled_fun_name.push('_');
+
+	for t in types.iter()
+	{
+		mangled_fun_name.push_str(&t.to_string());
+		mangled_fun_name.push('_');
+	}
+
+	mangled_fun_name
+}
+
+/// Type of an arithmetic expression over the entry parameters.
+fn aexp_type(e : &Aexp, symbols : &HashMap<String, DataType>) -> DataType
+{
+	match e
+	{
+		Aexp::IntConst{..} => DataType::Int32,
+		Aexp::FloConst{..} => DataType::Float32,
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+		{
+			match (aexp_type(l, symbols), aexp_type(r, symbols))
+			{
+				(DataType::Int32, DataType::Int32) => DataType::Int32,
+				_                                  => DataType::Float32,
+			}
+		},
+		Aexp::Var{v} => symbols.get(&v.name).cloned().unwrap_or(DataType::Int32),
+		Aexp::FnCall{..} => DataType::Int32,
+	}
+}
+
+/// Fold the constant operands of an arithmetic operation, the way the interpreter evaluates them.
+fn simp_aexp(e : Aexp) -> Aexp
+{
+	use super::super::interpreter::aexp::AexpValue;
+
+	fn to_val(e : &Aexp) -> Option<AexpValue>
+	{
+		match e
+		{
+			Aexp::IntConst{v} => Option::Some(AexpValue::Int32(*v)),
+			Aexp::FloConst{v} => Option::Some(AexpValue::Float32(*v)),
+			_                 => Option::None,
+		}
+	}
+
+	let folded = match &e
+	{
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+		{
+			match (to_val(l), to_val(r))
+			{
+				(Option::Some(AexpValue::Int32(lv)), Option::Some(AexpValue::Int32(rv))) =>
+				{
+					match &e
+					{
+						Aexp::Add{..} => Option::Some(Aexp::IntConst { v : lv.wrapping_add(rv) }),
+						Aexp::Sub{..} => Option::Some(Aexp::IntConst { v : lv.wrapping_sub(rv) }),
+						Aexp::Mul{..} => Option::Some(Aexp::IntConst { v : lv.wrapping_mul(rv) }),
+						Aexp::Div{..} => lv.checked_div(rv).map(|v| Aexp::IntConst { v : v }),
+						_             => lv.checked_rem(rv).map(|v| Aexp::IntConst { v : v }),
+					}
+				},
+				(Option::Some(lv), Option::Some(rv)) =>
+				{
+					let res = match &e
+					{
+						Aexp::Add{..} => lv + rv,
+						Aexp::Sub{..} => lv - rv,
+						Aexp::Mul{..} => lv * rv,
+						Aexp::Div{..} => lv / rv,
+						_             => lv % rv,
+					};
+					Option::Some(res.to_aexp())
+				},
+				_ => Option::None,
+			}
+		},
+		_ => Option::None,
+	};
+
+	folded.unwrap_or(e)
+}
+
+/// Fold the constant operands of a boolean operation.
+fn simp_bexp(e : Bexp) -> Bexp
+{
+	use super::super::interpreter::aexp::AexpValue;
+
+	fn to_val(e : &Aexp) -> Option<AexpValue>
+	{
+		match e
+		{
+			Aexp::IntConst{v} => Option::Some(AexpValue::Int32(*v)),
+			Aexp::FloConst{v} => Option::Some(AexpValue::Float32(*v)),
+			_                 => Option::None,
+		}
+	}
+
+	fn to_bool(e : &Bexp) -> Option<bool>
+	{
+		match e
+		{
+			Bexp::BoolConst{v} => Option::Some(*v),
+			_                  => Option::None,
+		}
+	}
+
+	let folded = match &e
+	{
+		Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+		{
+			match (&e, to_bool(l), to_bool(r))
+			{
+				(Bexp::Beq{..},  Option::Some(lv), Option::Some(rv)) => Option::Some(lv == rv),
+				(Bexp::Bneq{..}, Option::Some(lv), Option::Some(rv)) => Option::Some(lv != rv),
+				(Bexp::And{..},  Option::Some(false), _) | (Bexp::And{..}, _, Option::Some(false)) => Option::Some(false),
+				(Bexp::And{..},  Option::Some(true), _)  => return (**r).clone(),
+				(Bexp::And{..},  _, Option::Some(true))  => return (**l).clone(),
+				(Bexp::Or{..},   Option::Some(true), _) | (Bexp::Or{..}, _, Option::Some(true)) => Option::Some(true),
+				(Bexp::Or{..},   Option::Some(false), _) => return (**r).clone(),
+				(Bexp::Or{..},   _, Option::Some(false)) => return (**l).clone(),
+				_                                        => Option::None,
+			}
+		},
+		Bexp::Not{e} => to_bool(e).map(|v| !v),
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+		{
+			match (to_val(l), to_val(r))
+			{
+				(Option::Some(lv), Option::Some(rv)) => Option::Some(match &e
+				{
+					Bexp::Aeq{..}  => lv == rv,
+					Bexp::Aneq{..} => lv != rv,
+					Bexp::Lt{..}   => lv <  rv,
+					Bexp::Lte{..}  => lv <= rv,
+					Bexp::Gt{..}   => lv >  rv,
+					_              => lv >= rv,
+				}),
+				_ => Option::None,
+			}
+		},
+		_ => Option::None,
+	};
+
+	match folded
+	{
+		Option::Some(v) => Bexp::BoolConst { v : v },
+		Option::None    => e,
+	}
+}
+
+struct Engine
+{
+	config : SymConfig,
+	symbols : HashMap<String, DataType>,
+	funcs : HashMap<String, (Rc<FnProtoType>, Rc<Cmd>)>,
+	call_depth : usize,
+	finished : Vec<SymPath>,
+	num_paths : usize,
+}
+
+impl Engine
+{
+	/// End a path that fails or is truncated.
+	fn finish(&mut self, st : SymState, outcome : PathOutcome)
+	{
+		self.finished.push(SymPath { conds : st.conds, outcome : outcome });
+	}
+
+	fn is_feasible(&self, conds : &Vec<Bexp>) -> bool
+	{
+		let symbols : Vec<(String, DataType)> = self.symbols.iter().map(|(k, v)| (k.clone(), *v)).collect();
+		lin_solver::solve(&symbols, conds) != SolveResult::Unsat
+	}
+
+	/// Split a path on a condition; either side is `Option::None` if it's infeasible.
+	fn fork(&mut self, st : SymState, cond : Bexp) -> Result<(Option<SymState>, Option<SymState>), String>
+	{
+		match simp_bexp(cond)
+		{
+			Bexp::BoolConst{v : true}  => Result::Ok((Option::Some(st), Option::None)),
+			Bexp::BoolConst{v : false} => Result::Ok((Option::None, Option::Some(st))),
+			cond                       =>
+			{
+				let mut tr_st = st.clone();
+				tr_st.conds.push(cond.clone());
+				let mut fa_st = st;
+				fa_st.conds.push(simp_bexp(Bexp::Not { e : Box::new(cond) }));
+
+				let tr_opt = if self.is_feasible(&tr_st.conds) { Option::Some(tr_st) } else { Option::None };
+				let fa_opt = if self.is_feasible(&fa_st.conds) { Option::Some(fa_st) } else { Option::None };
+
+				if tr_opt.is_some() && fa_opt.is_some()
+				{
+					self.num_paths += 1;
+					if self.num_paths > self.config.max_paths
+					{
+						return Result::Err(format!("There are more than {} paths to explore.", self.config.max_paths));
+					}
+				}
+
+				Result::Ok((tr_opt, fa_opt))
+			},
+		}
+	}
+
+	fn promote(&self, e : Exp, t : &DataType) -> Exp
+	{
+		match (t, e)
+		{
+			(DataType::Float32, Exp::A{e}) =>
+			{
+				let promoted = match e
+				{
+					Aexp::IntConst{v} => Aexp::FloConst { v : v as f32 },
+					e                 =>
+					{
+						if aexp_type(&e, &self.symbols) == DataType::Int32
+						{
+							// There is no cast in the language; adding 0.0 makes the expression a float one.
+							Aexp::Add { l : Box::new(e), r : Box::new(Aexp::FloConst { v : 0.0 }) }
+						}
+						else
+						{
+							e
+						}
+					},
+				};
+				Exp::A { e : promoted }
+			},
+			(_, e) => e,
+		}
+	}
+
+	fn exp_type(&self, e : &Exp) -> DataType
+	{
+		match e
+		{
+			Exp::A{e} => aexp_type(e, &self.symbols),
+			Exp::B{..} => DataType::Bool,
+		}
+	}
+
+	fn read_var(&mut self, st : SymState, name : &String) -> Result<Option<(SymState, Exp)>, String>
+	{
+		let val = match st.get(name)
+		{
+			Option::Some(var) => var.v.clone(),
+			Option::None      => return Result::Err(format!("Variable {} hasn't been declared.", name)),
+		};
+
+		match val
+		{
+			Option::Some(v) => Result::Ok(Option::Some((st, v))),
+			Option::None    =>
+			{
+				self.finish(st, PathOutcome::Error(format!("Variable {} hasn't been initialized.", name)));
+				Result::Ok(Option::None)
+			},
+		}
+	}
+
+	fn eval_exp(&mut self, e : &Exp, st : SymState) -> Result<Vec<(SymState, Exp)>, String>
+	{
+		match e
+		{
+			Exp::A{e} => Result::Ok(self.eval_aexp(e, st)?.into_iter().map(|(s, v)| (s, Exp::A { e : v })).collect()),
+			Exp::B{e} => Result::Ok(self.eval_bexp(e, st)?.into_iter().map(|(s, v)| (s, Exp::B { e : v })).collect()),
+		}
+	}
+
+	fn eval_aexp(&mut self, e : &Aexp, st : SymState) -> Result<Vec<(SymState, Aexp)>, String>
+	{
+		match e
+		{
+			Aexp::IntConst{..} | Aexp::FloConst{..} => Result::Ok(vec![(st, e.clone())]),
+			Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				let mut res = Vec::new();
+				for (st_l, l_val) in self.eval_aexp(l, st)?
+				{
+					for (st_r, r_val) in self.eval_aexp(r, st_l)?
+					{
+						let (l_val, r_val) = (Box::new(l_val.clone()), Box::new(r_val));
+						let is_div = match e
+						{
+							Aexp::Div{..} | Aexp::Mod{..} => true,
+							_                             => false,
+						};
+
+						let mut st_r = Option::Some(st_r);
+						if is_div && aexp_type(&r_val, &self.symbols) == DataType::Int32
+						{
+							let zero_cond = Bexp::Aeq { l : r_val.clone(), r : Box::new(Aexp::IntConst { v : 0 }) };
+							let (zero_st, non_zero_st) = self.fork(st_r.take().unwrap(), zero_cond)?;
+							if let Option::Some(zero_st) = zero_st
+							{
+								self.finish(zero_st, PathOutcome::Error(format!("Division by zero in {}.", e)));
+							}
+							st_r = non_zero_st;
+						}
+
+						if let Option::Some(st_r) = st_r
+						{
+							let val = match e
+							{
+								Aexp::Add{..} => Aexp::Add { l : l_val, r : r_val },
+								Aexp::Sub{..} => Aexp::Sub { l : l_val, r : r_val },
+								Aexp::Mul{..} => Aexp::Mul { l : l_val, r : r_val },
+								Aexp::Div{..} => Aexp::Div { l : l_val, r : r_val },
+								_             => Aexp::Mod { l : l_val, r : r_val },
+							};
+							res.push((st_r, simp_aexp(val)));
+						}
+					}
+				}
+
+				Result::Ok(res)
+			},
+			Aexp::Var{v} => match self.read_var(st, &v.name)?
+			{
+				Option::Some((st, Exp::A{e})) => Result::Ok(vec![(st, e)]),
+				Option::Some((_, Exp::B{..})) => Result::Err(format!("Variable {} is not an arithmetic variable.", v.name)),
+				Option::None                  => Result::Ok(vec![]),
+			},
+			Aexp::FnCall{fc} =>
+			{
+				let mut res = Vec::new();
+				for (st, ret) in self.eval_fn_call(fc, st)?
+				{
+					match ret
+					{
+						Option::Some(Exp::A{e}) => res.push((st, e)),
+						Option::Some(Exp::B{..}) => return Result::Err(format!("Function {} doesn't return an arithmetic value.", fc.name)),
+						Option::None            => self.finish(st, PathOutcome::Error(format!("Function {} doesn't return a value.", fc.name))),
+					}
+				}
+
+				Result::Ok(res)
+			},
+		}
+	}
+
+	fn eval_bexp(&mut self, e : &Bexp, st : SymState) -> Result<Vec<(SymState, Bexp)>, String>
+	{
+		match e
+		{
+			Bexp::BoolConst{..} => Result::Ok(vec![(st, e.clone())]),
+			Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+			{
+				let mut res = Vec::new();
+				for (st_l, l_val) in self.eval_bexp(l, st)?
+				{
+					for (st_r, r_val) in self.eval_bexp(r, st_l)?
+					{
+						let (l_val, r_val) = (Box::new(l_val.clone()), Box::new(r_val));
+						let val = match e
+						{
+							Bexp::Beq{..}  => Bexp::Beq  { l : l_val, r : r_val },
+							Bexp::Bneq{..} => Bexp::Bneq { l : l_val, r : r_val },
+							Bexp::And{..}  => Bexp::And  { l : l_val, r : r_val },
+							_              => Bexp::Or   { l : l_val, r : r_val },
+						};
+						res.push((st_r, simp_bexp(val)));
+					}
+				}
+
+				Result::Ok(res)
+			},
+			Bexp::Not{e} =>
+			{
+				Result::Ok(self.eval_bexp(e, st)?.into_iter()
+					.map(|(s, v)| (s, simp_bexp(Bexp::Not { e : Box::new(v) })))
+					.collect())
+			},
+			Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+			{
+				let mut res = Vec::new();
+				for (st_l, l_val) in self.eval_aexp(l, st)?
+				{
+					for (st_r, r_val) in self.eval_aexp(r, st_l)?
+					{
+						let (l_val, r_val) = (Box::new(l_val.clone()), Box::new(r_val));
+						let val = match e
+						{
+							Bexp::Aeq{..}  => Bexp::Aeq  { l : l_val, r : r_val },
+							Bexp::Aneq{..} => Bexp::Aneq { l : l_val, r : r_val },
+							Bexp::Lt{..}   => Bexp::Lt   { l : l_val, r : r_val },
+							Bexp::Lte{..}  => Bexp::Lte  { l : l_val, r : r_val },
+							Bexp::Gt{..}   => Bexp::Gt   { l : l_val, r : r_val },
+							_              => Bexp::Gte  { l : l_val, r : r_val },
+						};
+						res.push((st_r, simp_bexp(val)));
+					}
+				}
+
+				Result::Ok(res)
+			},
+			Bexp::Var{v} => match self.read_var(st, &v.name)?
+			{
+				Option::Some((st, Exp::B{e})) => Result::Ok(vec![(st, e)]),
+				Option::Some((_, Exp::A{..})) => Result::Err(format!("Variable {} is not a boolean variable.", v.name)),
+				Option::None                  => Result::Ok(vec![]),
+			},
+			Bexp::FnCall{fc} =>
+			{
+				let mut res = Vec::new();
+				for (st, ret) in self.eval_fn_call(fc, st)?
+				{
+					match ret
+					{
+						Option::Some(Exp::B{e}) => res.push((st, e)),
+						Option::Some(Exp::A{..}) => return Result::Err(format!("Function {} doesn't return a boolean value.", fc.name)),
+						Option::None            => self.finish(st, PathOutcome::Error(format!("Function {} doesn't return a value.", fc.name))),
+					}
+				}
+
+				Result::Ok(res)
+			},
+		}
+	}
+
+	fn eval_fn_call(&mut self, fc : &FnCall, st : SymState) -> Result<Vec<(SymState, Option<Exp>)>, String>
+	{
+		// Evaluate the arguments on every path.
+		let mut arg_paths : Vec<(SymState, Vec<Exp>)> = vec![(st, Vec::new())];
+		for arg in fc.exp_list.iter()
+		{
+			let mut next_paths = Vec::new();
+			for (st, vals) in arg_paths.into_iter()
+			{
+				for (st, v) in self.eval_exp(arg, st)?
+				{
+					let mut next_vals = vals.clone();
+					next_vals.push(v);
+					next_paths.push((st, next_vals));
+				}
+			}
+			arg_paths = next_paths;
+		}
+
+		let mut res = Vec::new();
+		for (st, vals) in arg_paths.into_iter()
+		{
+			let arg_types : Vec<DataType> = vals.iter().map(|v| self.exp_type(v)).collect();
+			let param_types = typing::resolve_call(fc, &arg_types, self.funcs.values().map(|v| &*v.0))?;
+			let mangled_name = mangle_fn_name(&fc.name, &param_types);
+
+			let (pt, fn_cmd) = match self.funcs.get(&mangled_name)
+			{
+				Option::Some(v) => (v.0.clone(), v.1.clone()),
+				Option::None    => return Result::Err(format!("The function {} called is undefined.", mangled_name)),
+			};
+
+			if self.call_depth >= self.config.max_call_depth
+			{
+				self.finish(st, PathOutcome::Truncated);
+				continue;
+			}
+
+			res.extend(self.exec_func(&pt, &fn_cmd, st, vals)?);
+		}
+
+		Result::Ok(res)
+	}
+
+	/// Execute a function body; the callee sees the globals and its own parameters.
+	fn exec_func(&mut self, pt : &FnProtoType, fn_cmd : &Cmd, st : SymState, vals : Vec<Exp>) -> Result<Vec<(SymState, Option<Exp>)>, String>
+	{
+		let caller_scopes = st.scopes;
+		let mut callee_st = SymState
+		{
+			scopes : vec![caller_scopes[0].clone(), HashMap::new()],
+			conds : st.conds,
+			status : Status::Running,
+		};
+
+		for (var_decl, val) in pt.var_decl_list.iter().zip(vals.into_iter())
+		{
+			callee_st.decl(var_decl)?;
+			let val = self.promote(val, &var_decl.var_type);
+			callee_st.get_mut(&var_decl.name).unwrap().v = Option::Some(val);
+		}
+
+		self.call_depth += 1;
+		let end_sts = self.exec_cmd(fn_cmd, callee_st);
+		self.call_depth -= 1;
+
+		let mut res = Vec::new();
+		for end_st in end_sts?
+		{
+			let ret_val = match end_st.status
+			{
+				Status::Returned(v) => v.map(|v| self.promote(v, &pt.ret_type)),
+				Status::Running     => Option::None,
+			};
+
+			let mut scopes = caller_scopes.clone();
+			scopes[0] = end_st.scopes.into_iter().next().unwrap();
+
+			res.push((SymState { scopes : scopes, conds : end_st.conds, status : Status::Running }, ret_val));
+		}
+
+		Result::Ok(res)
+	}
+
+	fn exec_in_new_scope(&mut self, c : &Cmd, st : SymState) -> Result<Vec<SymState>, String>
+	{
+		let mut inner_st = st;
+		inner_st.scopes.push(HashMap::new());
+
+		let mut res = self.exec_cmd(c, inner_st)?;
+		for s in res.iter_mut()
+		{
+			s.scopes.pop();
+		}
+
+		Result::Ok(res)
+	}
+
+	fn exec_while(&mut self, cond : &Bexp, lp_cmd : &Cmd, bound : &Option<u64>, st : SymState) -> Result<Vec<SymState>, String>
+	{
+		let mut res = Vec::new();
+
+		// Paths at the loop head, with the number of iterations done so far,
+		// and the number of those iterations whose condition depends on the parameters.
+		let mut heads : Vec<(SymState, u64, usize)> = vec![(st, 0, 0)];
+
+		while let Option::Some((st, iter, sym_iter)) = heads.pop()
+		{
+			for (st, cond_val) in self.eval_bexp(cond, st)?
+			{
+				let is_sym = match cond_val
+				{
+					Bexp::BoolConst{..} => false,
+					_                   => true,
+				};
+				let (tr_st, fa_st) = self.fork(st, cond_val)?;
+
+				if let Option::Some(fa_st) = fa_st
+				{
+					res.push(fa_st);
+				}
+
+				let tr_st = match tr_st
+				{
+					Option::Some(s) => s,
+					Option::None    => continue,
+				};
+
+				if let Option::Some(b) = bound
+				{
+					if iter >= *b
+					{
+						self.finish(tr_st, PathOutcome::Error(format!("While loop exceeded its declared bound of {} iterations.", b)));
+						continue;
+					}
+				}
+
+				let sym_iter = if is_sym { sym_iter + 1 } else { sym_iter };
+				if sym_iter > self.config.loop_bound || iter >= MAX_CONCRETE_ITERATIONS
+				{
+					self.finish(tr_st, PathOutcome::Truncated);
+					continue;
+				}
+
+				for body_st in self.exec_in_new_scope(lp_cmd, tr_st)?
+				{
+					if body_st.is_running()
+					{
+						heads.push((body_st, iter + 1, sym_iter));
+					}
+					else
+					{
+						res.push(body_st);
+					}
+				}
+			}
+		}
+
+		Result::Ok(res)
+	}
+
+	/// Execute a command on a running path; the resulting paths are either
+	/// still running or have returned.
+	fn exec_cmd(&mut self, c : &Cmd, st : SymState) -> Result<Vec<SymState>, String>
+	{
+		match c
+		{
+			Cmd::Skip => Result::Ok(vec![st]),
+			Cmd::VarDecl{d} =>
+			{
+				let mut st = st;
+				st.decl(d)?;
+				Result::Ok(vec![st])
+			},
+			Cmd::Assign{var, e} =>
+			{
+				let mut res = Vec::new();
+				for (mut st, val) in self.eval_exp(e, st)?
+				{
+					let var_type = match st.get(&var.name)
+					{
+						Option::Some(v) => v.t,
+						Option::None    => return Result::Err(format!("Variable {} hasn't been declared.", var.name)),
+					};
+					let val = self.promote(val, &var_type);
+					st.get_mut(&var.name).unwrap().v = Option::Some(val);
+					res.push(st);
+				}
+
+				Result::Ok(res)
+			},
+			Cmd::FnCall{fc} =>
+			{
+				Result::Ok(self.eval_fn_call(fc, st)?.into_iter().map(|(s, _)| s).collect())
+			},
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				let mut res = Vec::new();
+				for (st, cond_val) in self.eval_bexp(cond, st)?
+				{
+					let (tr_st, fa_st) = self.fork(st, cond_val)?;
+					if let Option::Some(tr_st) = tr_st
+					{
+						res.extend(self.exec_in_new_scope(tr_cmd, tr_st)?);
+					}
+					if let Option::Some(fa_st) = fa_st
+					{
+						res.extend(self.exec_in_new_scope(fa_cmd, fa_st)?);
+					}
+				}
+
+				Result::Ok(res)
+			},
+			Cmd::WhileLoop{cond, lp_cmd, bound, ..} => self.exec_while(cond, lp_cmd, bound, st),
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				let mut res = Vec::new();
+				for st in self.exec_cmd(fst_cmd, st)?
+				{
+					if st.is_running()
+					{
+						res.extend(self.exec_cmd(snd_cmd, st)?);
+					}
+					else
+					{
+						res.push(st);
+					}
+				}
+
+				Result::Ok(res)
+			},
+			Cmd::FnDecl{prototype, fn_cmd} =>
+			{
+				let arg_types : Vec<DataType> = prototype.var_decl_list.iter().map(|d| d.var_type).collect();
+				let mangled_name = mangle_fn_name(&prototype.name, &arg_types);
+				self.funcs.insert(mangled_name, (prototype.clone(), fn_cmd.clone()));
+
+				Result::Ok(vec![st])
+			},
+			Cmd::Return{e} =>
+			{
+				match e
+				{
+					Option::Some(e) =>
+					{
+						Result::Ok(self.eval_exp(e, st)?.into_iter()
+							.map(|(mut s, v)|
+							{
+								s.status = Status::Returned(Option::Some(v));
+								s
+							})
+							.collect())
+					},
+					Option::None    =>
+					{
+						let mut st = st;
+						st.status = Status::Returned(Option::None);
+						Result::Ok(vec![st])
+					},
+				}
+			},
+			Cmd::Assert{cond} =>
+			{
+				let mut res = Vec::new();
+				for (st, cond_val) in self.eval_bexp(cond, st)?
+				{
+					let (tr_st, fa_st) = self.fork(st, cond_val)?;
+					if let Option::Some(fa_st) = fa_st
+					{
+						self.finish(fa_st, PathOutcome::Error(format!("Assertion {} failed.", cond)));
+					}
+					if let Option::Some(tr_st) = tr_st
+					{
+						res.push(tr_st);
+					}
+				}
+
+				Result::Ok(res)
+			},
+		}
+	}
+}
+
+/// Symbolically execute the `entry` function of a program.
+///
+/// The global commands of the program are executed first; then `entry` is
+/// executed with its parameters as symbols. The execution forks at every
+/// branch whose condition depends on the parameters, including loop
+/// conditions (unrolled up to `config.loop_bound` times) and integer divisors
+/// that may be zero. Branches that the built-in solver proves infeasible are
+/// pruned. The built-in solver, like the scripts of `path_to_smtlib2`, wraps
+/// `i32` arithmetic around, so paths only taken on overflow are kept.
+///
+pub fn sym_exec_program(prog : &Cmd, config : &SymConfig) -> Result<SymReport, String>
+{
+	let mut engine = Engine
+	{
+		config : *config,
+		symbols : HashMap::new(),
+		funcs : HashMap::new(),
+		call_depth : 0,
+		finished : Vec::new(),
+		num_paths : 1,
+	};
+
+	let init_st = SymState { scopes : vec![HashMap::new()], conds : Vec::new(), status : Status::Running };
+	let mut global_sts = engine.exec_cmd(prog, init_st)?;
+
+	if !engine.finished.is_empty() || global_sts.len() != 1 || !global_sts[0].is_running()
+	{
+		return Result::Err(format!("{}", "Program root should run on a single path, without return statement."));
+	}
+	let global_st = global_sts.pop().unwrap();
+
+	let entries : Vec<(Rc<FnProtoType>, Rc<Cmd>)> = engine.funcs.values()
+		.filter(|v| v.0.name == "entry")
+		.cloned()
+		.collect();
+
+	if entries.len() != 1
+	{
+		return Result::Err(format!("There should be exactly one entry function, but {} are declared.", entries.len()));
+	}
+	let (pt, fn_cmd) = entries[0].clone();
+
+	let mut vals : Vec<Exp> = Vec::new();
+	for var_decl in pt.var_decl_list.iter()
+	{
+		let v = VarRef::from_str(&var_decl.name);
+		vals.push(match var_decl.var_type
+		{
+			DataType::Bool => Exp::B { e : Bexp::Var { v : v } },
+			_              => Exp::A { e : Aexp::Var { v : v } },
+		});
+		engine.symbols.insert(var_decl.name.clone(), var_decl.var_type);
+	}
+
+	for (end_st, ret_val) in engine.exec_func(&pt, &fn_cmd, global_st, vals)?
+	{
+		engine.finish(end_st, PathOutcome::Returned(ret_val));
+	}
+
+	Result::Ok(SymReport { params : pt.var_decl_list.clone(), paths : engine.finished })
+}
+
+/// Emit the condition of a path as an SMT-LIB2 script.
+///
+/// Please refer to `smtlib2::script` for how the symbols and operators are encoded.
+///
+pub fn path_to_smtlib2(report : &SymReport, path : &SymPath) -> Result<String, String>
+{
+	smtlib2::script(&format!("{}", path.outcome), &report.symbols(), &path.conds)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use ast::aexp::constructor_helper::*;
+	use ast::exp::constructor_helper::*;
+	use ast::cmd::constructor_helper::*;
+
+	#[test]
+	fn scripts_wrap_intermediate_terms()
+	{
+		/* Program:
+		 * fn entry(a : Int32) -> Int32 {
+		 *   if (a * 15 > 100) { return 1; } else { return 0; }
+		 * } */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "a".to_string())]);
+		let if_cmd = if_el(("a".to_aexp() * 15i32.to_aexp()).gt(100i32.to_aexp()),
+			ret(Some(1i32.to_aexp().to_exp())),
+			ret(Some(0i32.to_aexp().to_exp())));
+		let prog = fn_dc(fn_prototype, if_cmd);
+
+		let report = sym_exec_program(&prog, &SymConfig::new(4)).unwrap();
+		assert_eq!(report.paths.len(), 2);
+
+		let smt = path_to_smtlib2(&report, &report.paths[0]).unwrap();
+		assert!(smt.contains("(declare-const a (_ BitVec 32))"), "{}", smt);
+		assert!(smt.contains("(bvmul a #x0000000f)"), "{}", smt);
+		assert!(!smt.contains(" Int"), "{}", smt);
+	}
+
+	#[test]
+	fn paths_taken_on_overflow_are_kept()
+	{
+		/* Program:
+		 * fn entry(a : Int32) -> Int32 {
+		 *   if (a + 1 < a) { return 1; } else { return 0; }
+		 * } */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "a".to_string())]);
+		let if_cmd = if_el(("a".to_aexp() + 1i32.to_aexp()).lt("a".to_aexp()),
+			ret(Some(1i32.to_aexp().to_exp())),
+			ret(Some(0i32.to_aexp().to_exp())));
+		let prog = fn_dc(fn_prototype, if_cmd);
+
+		let report = sym_exec_program(&prog, &SymConfig::new(4)).unwrap();
+		assert_eq!(report.paths.len(), 2);
+
+		let overflow_path = report.paths.iter().find(|p| p.outcome.to_string() == "returns 1").unwrap();
+		let params = report.gen_param_list(overflow_path).unwrap().unwrap();
+		assert_eq!(params[0].to_string(), i32::MAX.to_string());
+	}
+
+	#[test]
+	fn truncated_paths_declare_the_parameters()
+	{
+		/* Program:
+		 * fn entry(n : Int32) -> Int32 {
+		 *   Int32 i;
+		 *   i = 0;
+		 *   while (i < n) { i = i + 1; }
+		 *   return i;
+		 * } */
+		let fn_prototype = FnProtoType::new(DataType::Int32, "entry".to_string(), vec![VarDecl::new(DataType::Int32, "n".to_string())]);
+		let i_dec = var_dc(VarDecl::new(DataType::Int32, "i".to_string()));
+		let i_asg = assign(VarRef::from_str("i"), 0i32.to_aexp().to_exp());
+		let lp = wh_lp("i".to_aexp().lt("n".to_aexp()), assign(VarRef::from_str("i"), ("i".to_aexp() + 1i32.to_aexp()).to_exp()));
+		let prog = fn_dc(fn_prototype, seq(i_dec, seq(i_asg, seq(lp, ret(Some("i".to_aexp().to_exp()))))));
+
+		let report = sym_exec_program(&prog, &SymConfig::new(2)).unwrap();
+		let truncated : Vec<&SymPath> = report.paths.iter().filter(|p| p.is_truncated()).collect();
+		assert_eq!(truncated.len(), 1);
+		assert_eq!(report.paths.len(), 4);
+
+		let smt = path_to_smtlib2(&report, truncated[0]).unwrap();
+		assert!(smt.contains("(declare-const n (_ BitVec 32))"), "{}", smt);
+		assert!(smt.contains("(bvslt #x00000001 n)"), "{}", smt);
+	}
+}
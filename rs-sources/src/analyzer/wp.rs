@@ -0,0 +1,1160 @@
+use std::fmt;
+use std::rc::Rc;
+use std::vec::Vec;
+use std::boxed::Box;
+use std::string::String;
+use std::string::ToString;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::BTreeSet;
+
+use super::super::ast;
+use super::super::typing;
+use ast::aexp::Aexp;
+use ast::bexp::Bexp;
+use ast::cmd::Cmd;
+use ast::exp::Exp;
+use ast::data_type::DataType;
+use ast::func_general::FnCall;
+use ast::func_general::FnProtoType;
+use ast::func_general::RESULT_VAR_NAME;
+use ast::var_general::VarDecl;
+use ast::var_general::VarRef;
+
+use super::lin_solver;
+use super::lin_solver::SolveResult;
+use super::smtlib2;
+
+/// A verification condition: `goal` must hold whenever all `hyps` hold.
+#[derive(Clone)]
+pub struct Vc
+{
+	/// What the condition guarantees, e.g. a postcondition of the function.
+	pub what : String,
+	pub hyps : Vec<Bexp>,
+	pub goal : Bexp,
+	/// Free variables of the condition and their types.
+	pub symbols : Vec<(String, DataType)>,
+}
+
+/// Outcome of discharging a verification condition with the built-in solver.
+#[derive(Clone)]
+pub enum VcResult
+{
+	Proved,
+	/// The condition doesn't hold; values of the parameters that violate it.
+	Refuted(Vec<(String, i64)>),
+	/// The condition is out of reach of the built-in solver; it can be
+	/// exported with `vc_to_smtlib2` and checked by an external solver.
+	Unknown,
+}
+
+/// A function and its verification conditions.
+pub type FnVcTuple = (Rc<FnProtoType>, Vec<Vc>);
+
+/// Verification conditions of one function, with their results.
+pub struct FnVcs
+{
+	pub prototype : Rc<FnProtoType>,
+	pub vcs : Vec<(Vc, VcResult)>,
+}
+
+/// Result of verifying all functions of a program against their contracts.
+pub struct WpReport
+{
+	pub funcs : Vec<FnVcs>,
+}
+
+impl WpReport
+{
+	/// Whether every verification condition is proved.
+	pub fn is_verified(&self) -> bool
+	{
+		self.funcs.iter().all(|f| f.vcs.iter().all(|(_, res)| match res
+		{
+			VcResult::Proved => true,
+			_                => false,
+		}))
+	}
+}
+
+impl fmt::Display for WpReport
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		let mut num_proved = 0;
+		let mut num_vcs = 0;
+
+		for func in self.funcs.iter()
+		{
+			writeln!(f, "{}:", func.prototype)?;
+			for (vc, res) in func.vcs.iter()
+			{
+				num_vcs += 1;
+				match res
+				{
+					VcResult::Proved       =>
+					{
+						num_proved += 1;
+						writeln!(f, "\t[proved]  {}", vc.what)?;
+					},
+					VcResult::Refuted(cex) =>
+					{
+						let cex_str : Vec<String> = cex.iter().map(|(n, v)| format!("{} = {}", n, v)).collect();
+						writeln!(f, "\t[refuted] {}; counterexample: {}", vc.what, cex_str.join(", "))?;
+					},
+					VcResult::Unknown      => writeln!(f, "\t[unknown] {}", vc.what)?,
+				}
+			}
+		}
+
+		write!(f, "{} of {} verification condition(s) proved.", num_proved, num_vcs)
+	}
+}
+
+/// Obligation tree built by the weakest-precondition calculus.
+#[derive(Clone)]
+enum Obl
+{
+	Goal{what : String, cond : Bexp},
+	And(Vec<Obl>),
+	Implies(Bexp, Box<Obl>),
+}
+
+impl Obl
+{
+	fn subst(&self, m : &HashMap<String, Exp>) -> Obl
+	{
+		match self
+		{
+			Obl::Goal{what, cond} => Obl::Goal{what : what.clone(), cond : subst_bexp(cond, m)},
+			Obl::And(obls)        => Obl::And(obls.iter().map(|o| o.subst(m)).collect()),
+			Obl::Implies(h, o)    => Obl::Implies(subst_bexp(h, m), Box::new(o.subst(m))),
+		}
+	}
+
+	fn flatten(&self, hyps : &mut Vec<Bexp>, out : &mut Vec<(String, Vec<Bexp>, Bexp)>)
+	{
+		match self
+		{
+			Obl::Goal{cond : Bexp::BoolConst{v : true}, ..} => {},
+			Obl::Goal{what, cond} => out.push((what.clone(), hyps.clone(), cond.clone())),
+			Obl::And(obls)        =>
+			{
+				for o in obls.iter()
+				{
+					o.flatten(hyps, out);
+				}
+			},
+			Obl::Implies(h, o)    =>
+			{
+				hyps.push(h.clone());
+				o.flatten(hyps, out);
+				hyps.pop();
+			},
+		}
+	}
+}
+
+fn var_exp(name : &String, t : DataType) -> Exp
+{
+	let v = VarRef::from_str(name);
+	match t
+	{
+		DataType::Bool => Exp::B { e : Bexp::Var { v : v } },
+		_              => Exp::A { e : Aexp::Var { v : v } },
+	}
+}
+
+fn subst_aexp(e : &Aexp, m : &HashMap<String, Exp>) -> Aexp
+{
+	let bin = |l : &Aexp, r : &Aexp| (Box::new(subst_aexp(l, m)), Box::new(subst_aexp(r, m)));
+	match e
+	{
+		Aexp::IntConst{..} | Aexp::FloConst{..} => e.clone(),
+		Aexp::Add{l, r} => { let (l, r) = bin(l, r); Aexp::Add{l, r} },
+		Aexp::Sub{l, r} => { let (l, r) = bin(l, r); Aexp::Sub{l, r} },
+		Aexp::Mul{l, r} => { let (l, r) = bin(l, r); Aexp::Mul{l, r} },
+		Aexp::Div{l, r} => { let (l, r) = bin(l, r); Aexp::Div{l, r} },
+		Aexp::Mod{l, r} => { let (l, r) = bin(l, r); Aexp::Mod{l, r} },
+		Aexp::Var{v} => match m.get(&v.name)
+		{
+			Option::Some(Exp::A{e}) => e.clone(),
+			_                       => e.clone(),
+		},
+		Aexp::FnCall{fc} => Aexp::FnCall{fc : subst_fn_call(fc, m)},
+	}
+}
+
+fn subst_bexp(e : &Bexp, m : &HashMap<String, Exp>) -> Bexp
+{
+	let b_bin = |l : &Bexp, r : &Bexp| (Box::new(subst_bexp(l, m)), Box::new(subst_bexp(r, m)));
+	let a_bin = |l : &Aexp, r : &Aexp| (Box::new(subst_aexp(l, m)), Box::new(subst_aexp(r, m)));
+	match e
+	{
+		Bexp::BoolConst{..} => e.clone(),
+		Bexp::Beq{l, r}  => { let (l, r) = b_bin(l, r); Bexp::Beq{l, r} },
+		Bexp::Bneq{l, r} => { let (l, r) = b_bin(l, r); Bexp::Bneq{l, r} },
+		Bexp::And{l, r}  => { let (l, r) = b_bin(l, r); Bexp::And{l, r} },
+		Bexp::Or{l, r}   => { let (l, r) = b_bin(l, r); Bexp::Or{l, r} },
+		Bexp::Not{e}     => Bexp::Not{e : Box::new(subst_bexp(e, m))},
+		Bexp::Aeq{l, r}  => { let (l, r) = a_bin(l, r); Bexp::Aeq{l, r} },
+		Bexp::Aneq{l, r} => { let (l, r) = a_bin(l, r); Bexp::Aneq{l, r} },
+		Bexp::Lt{l, r}   => { let (l, r) = a_bin(l, r); Bexp::Lt{l, r} },
+		Bexp::Lte{l, r}  => { let (l, r) = a_bin(l, r); Bexp::Lte{l, r} },
+		Bexp::Gt{l, r}   => { let (l, r) = a_bin(l, r); Bexp::Gt{l, r} },
+		Bexp::Gte{l, r}  => { let (l, r) = a_bin(l, r); Bexp::Gte{l, r} },
+		Bexp::Var{v} => match m.get(&v.name)
+		{
+			Option::Some(Exp::B{e}) => e.clone(),
+			_                       => e.clone(),
+		},
+		Bexp::FnCall{fc} => Bexp::FnCall{fc : subst_fn_call(fc, m)},
+	}
+}
+
+fn subst_exp(e : &Exp, m : &HashMap<String, Exp>) -> Exp
+{
+	match e
+	{
+		Exp::A{e} => Exp::A{e : subst_aexp(e, m)},
+		Exp::B{e} => Exp::B{e : subst_bexp(e, m)},
+	}
+}
+
+fn subst_fn_call(fc : &FnCall, m : &HashMap<String, Exp>) -> FnCall
+{
+	FnCall { name : fc.name.clone(), exp_list : fc.exp_list.iter().map(|e| subst_exp(e, m)).collect(), param_types : fc.param_types.clone() }
+}
+
+/// Collect the free variables of an expression; fails on function calls.
+fn aexp_vars(e : &Aexp, out : &mut BTreeSet<String>) -> Result<(), String>
+{
+	match e
+	{
+		Aexp::IntConst{..} | Aexp::FloConst{..} => Result::Ok(()),
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+		{
+			aexp_vars(l, out)?;
+			aexp_vars(r, out)
+		},
+		Aexp::Var{v} => { out.insert(v.name.clone()); Result::Ok(()) },
+		Aexp::FnCall{fc} => Result::Err(format!("Function call {} is not allowed in a contract or loop invariant.", fc)),
+	}
+}
+
+fn bexp_vars(e : &Bexp, out : &mut BTreeSet<String>) -> Result<(), String>
+{
+	match e
+	{
+		Bexp::BoolConst{..} => Result::Ok(()),
+		Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+		{
+			bexp_vars(l, out)?;
+			bexp_vars(r, out)
+		},
+		Bexp::Not{e} => bexp_vars(e, out),
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+		{
+			aexp_vars(l, out)?;
+			aexp_vars(r, out)
+		},
+		Bexp::Var{v} => { out.insert(v.name.clone()); Result::Ok(()) },
+		Bexp::FnCall{fc} => Result::Err(format!("Function call {} is not allowed in a contract or loop invariant.", fc)),
+	}
+}
+
+fn mangle_fn_name(name : &String, types : &Vec<DataType>) -> String
+{
+	let mut mangled_fun_name = String::new();
+	mangled_fun_name.push_str(name);
+	mangled_fun_name.push('_');
+
+	for t in types.iter()
+	{
+		mangled_fun_name.push_str(&t.to_string());
+		mangled_fun_name.push('_');
+	}
+
+	mangled_fun_name
+}
+
+/// Renames the local variables of a function body, so that every
+/// declaration has a distinct name; the weakest-precondition calculus
+/// substitutes variables by name, and would confuse shadowed variables otherwise.
+struct Renamer<'a>
+{
+	scopes : Vec<HashMap<String, String> >,
+	used : HashSet<String>,
+	types : &'a mut HashMap<String, DataType>,
+	next_id : usize,
+}
+
+impl<'a> Renamer<'a>
+{
+	fn name(&self, name : &String) -> String
+	{
+		self.scopes.iter().rev().filter_map(|s| s.get(name)).next().cloned().unwrap_or_else(|| name.clone())
+	}
+
+	fn var(&self, v : &VarRef) -> VarRef
+	{
+		VarRef::from_str(&self.name(&v.name))
+	}
+
+	fn decl(&mut self, d : &VarDecl) -> VarDecl
+	{
+		let mut new_name = d.name.clone();
+		while self.used.contains(&new_name)
+		{
+			new_name = format!("{}$v{}", d.name, self.next_id);
+			self.next_id += 1;
+		}
+
+		self.used.insert(new_name.clone());
+		self.scopes.last_mut().unwrap().insert(d.name.clone(), new_name.clone());
+		self.types.insert(new_name.clone(), d.var_type);
+
+		VarDecl::new_labeled(d.var_type, d.label, new_name)
+	}
+
+	fn aexp(&self, e : &Aexp) -> Aexp
+	{
+		let bin = |l : &Aexp, r : &Aexp| (Box::new(self.aexp(l)), Box::new(self.aexp(r)));
+		match e
+		{
+			Aexp::IntConst{..} | Aexp::FloConst{..} => e.clone(),
+			Aexp::Add{l, r} => { let (l, r) = bin(l, r); Aexp::Add{l, r} },
+			Aexp::Sub{l, r} => { let (l, r) = bin(l, r); Aexp::Sub{l, r} },
+			Aexp::Mul{l, r} => { let (l, r) = bin(l, r); Aexp::Mul{l, r} },
+			Aexp::Div{l, r} => { let (l, r) = bin(l, r); Aexp::Div{l, r} },
+			Aexp::Mod{l, r} => { let (l, r) = bin(l, r); Aexp::Mod{l, r} },
+			Aexp::Var{v} => Aexp::Var{v : self.var(v)},
+			Aexp::FnCall{fc} => Aexp::FnCall{fc : self.fn_call(fc)},
+		}
+	}
+
+	fn bexp(&self, e : &Bexp) -> Bexp
+	{
+		let b_bin = |l : &Bexp, r : &Bexp| (Box::new(self.bexp(l)), Box::new(self.bexp(r)));
+		let a_bin = |l : &Aexp, r : &Aexp| (Box::new(self.aexp(l)), Box::new(self.aexp(r)));
+		match e
+		{
+			Bexp::BoolConst{..} => e.clone(),
+			Bexp::Beq{l, r}  => { let (l, r) = b_bin(l, r); Bexp::Beq{l, r} },
+			Bexp::Bneq{l, r} => { let (l, r) = b_bin(l, r); Bexp::Bneq{l, r} },
+			Bexp::And{l, r}  => { let (l, r) = b_bin(l, r); Bexp::And{l, r} },
+			Bexp::Or{l, r}   => { let (l, r) = b_bin(l, r); Bexp::Or{l, r} },
+			Bexp::Not{e}     => Bexp::Not{e : Box::new(self.bexp(e))},
+			Bexp::Aeq{l, r}  => { let (l, r) = a_bin(l, r); Bexp::Aeq{l, r} },
+			Bexp::Aneq{l, r} => { let (l, r) = a_bin(l, r); Bexp::Aneq{l, r} },
+			Bexp::Lt{l, r}   => { let (l, r) = a_bin(l, r); Bexp::Lt{l, r} },
+			Bexp::Lte{l, r}  => { let (l, r) = a_bin(l, r); Bexp::Lte{l, r} },
+			Bexp::Gt{l, r}   => { let (l, r) = a_bin(l, r); Bexp::Gt{l, r} },
+			Bexp::Gte{l, r}  => { let (l, r) = a_bin(l, r); Bexp::Gte{l, r} },
+			Bexp::Var{v} => Bexp::Var{v : self.var(v)},
+			Bexp::FnCall{fc} => Bexp::FnCall{fc : self.fn_call(fc)},
+		}
+	}
+
+	fn exp(&self, e : &Exp) -> Exp
+	{
+		match e
+		{
+			Exp::A{e} => Exp::A{e : self.aexp(e)},
+			Exp::B{e} => Exp::B{e : self.bexp(e)},
+		}
+	}
+
+	fn fn_call(&self, fc : &FnCall) -> FnCall
+	{
+		FnCall { name : fc.name.clone(), exp_list : fc.exp_list.iter().map(|e| self.exp(e)).collect(), param_types : fc.param_types.clone() }
+	}
+
+	fn cmd_in_new_scope(&mut self, c : &Cmd) -> Result<Cmd, String>
+	{
+		self.scopes.push(HashMap::new());
+		let res = self.cmd(c);
+		self.scopes.pop();
+		res
+	}
+
+	fn cmd(&mut self, c : &Cmd) -> Result<Cmd, String>
+	{
+		use ast::cmd::constructor_helper::*;
+
+		match c
+		{
+			Cmd::Skip => Result::Ok(skip()),
+			Cmd::VarDecl{d} => Result::Ok(var_dc(self.decl(d))),
+			Cmd::Assign{var, e} => Result::Ok(assign(self.var(var), self.exp(e))),
+			Cmd::FnCall{fc} => Result::Ok(c_fncall(self.fn_call(fc))),
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				let cond = self.bexp(cond);
+				let tr_cmd = self.cmd_in_new_scope(tr_cmd)?;
+				let fa_cmd = self.cmd_in_new_scope(fa_cmd)?;
+				Result::Ok(if_el(cond, tr_cmd, fa_cmd))
+			},
+			Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
+			{
+				let cond = self.bexp(cond);
+				let invariants = invariants.iter().map(|i| self.bexp(i)).collect();
+				let lp_cmd = self.cmd_in_new_scope(lp_cmd)?;
+				Result::Ok(wh_lp_inv(cond, lp_cmd, *bound, invariants))
+			},
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				let fst_cmd = self.cmd(fst_cmd)?;
+				let snd_cmd = self.cmd(snd_cmd)?;
+				Result::Ok(seq(fst_cmd, snd_cmd))
+			},
+			Cmd::FnDecl{prototype, ..} => Result::Err(format!("Function {} is declared at non-global scope.", prototype.name)),
+			Cmd::Return{e} => Result::Ok(ret(e.as_ref().map(|e| self.exp(e)))),
+			Cmd::Assert{cond} => Result::Ok(assert(self.bexp(cond))),
+		}
+	}
+}
+
+/// Variables that a command may assign to, including the ones it declares.
+fn modified_vars(c : &Cmd, out : &mut BTreeSet<String>)
+{
+	match c
+	{
+		Cmd::VarDecl{d} => { out.insert(d.name.clone()); },
+		Cmd::Assign{var, ..} => { out.insert(var.name.clone()); },
+		Cmd::IfElse{tr_cmd, fa_cmd, ..} =>
+		{
+			modified_vars(tr_cmd, out);
+			modified_vars(fa_cmd, out);
+		},
+		Cmd::WhileLoop{lp_cmd, ..} => modified_vars(lp_cmd, out),
+		Cmd::Seq{fst_cmd, snd_cmd} =>
+		{
+			modified_vars(fst_cmd, out);
+			modified_vars(snd_cmd, out);
+		},
+		Cmd::Skip | Cmd::FnCall{..} | Cmd::FnDecl{..} | Cmd::Return{..} | Cmd::Assert{..} => {},
+	}
+}
+
+fn aexp_has_call(e : &Aexp) -> bool
+{
+	match e
+	{
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} => aexp_has_call(l) || aexp_has_call(r),
+		Aexp::FnCall{..} => true,
+		_                => false,
+	}
+}
+
+fn bexp_has_call(e : &Bexp) -> bool
+{
+	match e
+	{
+		Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} => bexp_has_call(l) || bexp_has_call(r),
+		Bexp::Not{e} => bexp_has_call(e),
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} => aexp_has_call(l) || aexp_has_call(r),
+		Bexp::FnCall{..} => true,
+		_                => false,
+	}
+}
+
+fn exp_has_call(e : &Exp) -> bool
+{
+	match e
+	{
+		Exp::A{e} => aexp_has_call(e),
+		Exp::B{e} => bexp_has_call(e),
+	}
+}
+
+/// Whether a command may call a function, which may assign to global variables.
+fn cmd_has_call(c : &Cmd) -> bool
+{
+	match c
+	{
+		Cmd::FnCall{..} => true,
+		Cmd::Assign{e, ..} | Cmd::Return{e : Option::Some(e)} => exp_has_call(e),
+		Cmd::IfElse{cond, tr_cmd, fa_cmd} => bexp_has_call(cond) || cmd_has_call(tr_cmd) || cmd_has_call(fa_cmd),
+		Cmd::WhileLoop{cond, lp_cmd, ..} => bexp_has_call(cond) || cmd_has_call(lp_cmd),
+		Cmd::Assert{cond} => bexp_has_call(cond),
+		Cmd::Seq{fst_cmd, snd_cmd} => cmd_has_call(fst_cmd) || cmd_has_call(snd_cmd),
+		_ => false,
+	}
+}
+
+/// One step in the evaluation of an expression, in evaluation order.
+enum Step
+{
+	/// A global variable is read into `snap`; a later call may change it.
+	Read{snap : String, global : String},
+	/// A function is called; its return value is `ret`.
+	Call{prototype : Rc<FnProtoType>, args : Vec<Exp>, ret : String, site : String},
+	/// A condition that must hold for the evaluation not to fail.
+	Check{what : String, cond : Bexp},
+}
+
+struct WpGen<'a>
+{
+	funcs : &'a HashMap<String, Rc<FnProtoType> >,
+	globals : &'a Vec<(String, DataType)>,
+	types : HashMap<String, DataType>,
+	curr_fn : Rc<FnProtoType>,
+	next_id : usize,
+}
+
+impl<'a> WpGen<'a>
+{
+	fn fresh(&mut self, base : &str, kind : &str, t : DataType) -> String
+	{
+		let name = format!("{}${}{}", base, kind, self.next_id);
+		self.next_id += 1;
+		self.types.insert(name.clone(), t);
+		name
+	}
+
+	fn is_global(&self, name : &String) -> bool
+	{
+		self.globals.iter().any(|(n, _)| n == name)
+	}
+
+	fn aexp_type(&self, e : &Aexp) -> DataType
+	{
+		match e
+		{
+			Aexp::IntConst{..} => DataType::Int32,
+			Aexp::FloConst{..} => DataType::Float32,
+			Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				match (self.aexp_type(l), self.aexp_type(r))
+				{
+					(DataType::Int32, DataType::Int32) => DataType::Int32,
+					_                                  => DataType::Float32,
+				}
+			},
+			Aexp::Var{v} => self.types.get(&v.name).cloned().unwrap_or(DataType::Int32),
+			// Calls are hoisted before the type of an expression is needed.
+			Aexp::FnCall{..} => DataType::Int32,
+		}
+	}
+
+	fn exp_type(&self, e : &Exp) -> DataType
+	{
+		match e
+		{
+			Exp::A{e} => self.aexp_type(e),
+			Exp::B{..} => DataType::Bool,
+		}
+	}
+
+	/// Replace the calls and global reads in an expression by fresh variables,
+	/// recording the evaluation steps.
+	fn hoist_aexp(&mut self, e : &Aexp, steps : &mut Vec<Step>) -> Result<Aexp, String>
+	{
+		match e
+		{
+			Aexp::IntConst{..} | Aexp::FloConst{..} => Result::Ok(e.clone()),
+			Aexp::Add{l, r} => Result::Ok(Aexp::Add{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Aexp::Sub{l, r} => Result::Ok(Aexp::Sub{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Aexp::Mul{l, r} => Result::Ok(Aexp::Mul{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+			{
+				let l_h = Box::new(self.hoist_aexp(l, steps)?);
+				let r_h = Box::new(self.hoist_aexp(r, steps)?);
+				if self.aexp_type(&l_h) == DataType::Int32 && self.aexp_type(&r_h) == DataType::Int32
+				{
+					steps.push(Step::Check
+					{
+						what : format!("no division by zero in {}", e),
+						cond : Bexp::Aneq{l : r_h.clone(), r : Box::new(Aexp::IntConst{v : 0})},
+					});
+				}
+				match e
+				{
+					Aexp::Div{..} => Result::Ok(Aexp::Div{l : l_h, r : r_h}),
+					_             => Result::Ok(Aexp::Mod{l : l_h, r : r_h}),
+				}
+			},
+			Aexp::Var{v} => match self.types.get(&v.name).cloned()
+			{
+				Option::Some(t) if self.is_global(&v.name) =>
+				{
+					let snap = self.fresh(&v.name, "s", t);
+					steps.push(Step::Read{snap : snap.clone(), global : v.name.clone()});
+					Result::Ok(Aexp::Var{v : VarRef::from_str(&snap)})
+				},
+				Option::Some(_) => Result::Ok(e.clone()),
+				Option::None    => Result::Err(format!("Variable {} is not declared.", v.name)),
+			},
+			Aexp::FnCall{fc} =>
+			{
+				let ret = self.hoist_call(fc, steps)?;
+				Result::Ok(Aexp::Var{v : VarRef::from_str(&ret)})
+			},
+		}
+	}
+
+	fn hoist_bexp(&mut self, e : &Bexp, steps : &mut Vec<Step>) -> Result<Bexp, String>
+	{
+		match e
+		{
+			Bexp::BoolConst{..} => Result::Ok(e.clone()),
+			Bexp::Beq{l, r}  => Result::Ok(Bexp::Beq{l : Box::new(self.hoist_bexp(l, steps)?), r : Box::new(self.hoist_bexp(r, steps)?)}),
+			Bexp::Bneq{l, r} => Result::Ok(Bexp::Bneq{l : Box::new(self.hoist_bexp(l, steps)?), r : Box::new(self.hoist_bexp(r, steps)?)}),
+			Bexp::And{l, r} | Bexp::Or{l, r} =>
+			{
+				// The right operand is evaluated only if the left one doesn't decide the result.
+				let l_h = self.hoist_bexp(l, steps)?;
+				let mut r_steps : Vec<Step> = Vec::new();
+				let r_h = self.hoist_bexp(r, &mut r_steps)?;
+				if r_steps.iter().any(|s| match s { Step::Read{..} => false, _ => true })
+				{
+					return Result::Err(format!("Calls and divisions in the right operand of {} are not supported.", e));
+				}
+				steps.append(&mut r_steps);
+				match e
+				{
+					Bexp::And{..} => Result::Ok(Bexp::And{l : Box::new(l_h), r : Box::new(r_h)}),
+					_             => Result::Ok(Bexp::Or{l : Box::new(l_h), r : Box::new(r_h)}),
+				}
+			},
+			Bexp::Not{e} => Result::Ok(Bexp::Not{e : Box::new(self.hoist_bexp(e, steps)?)}),
+			Bexp::Aeq{l, r}  => Result::Ok(Bexp::Aeq{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Bexp::Aneq{l, r} => Result::Ok(Bexp::Aneq{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Bexp::Lt{l, r}   => Result::Ok(Bexp::Lt{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Bexp::Lte{l, r}  => Result::Ok(Bexp::Lte{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Bexp::Gt{l, r}   => Result::Ok(Bexp::Gt{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Bexp::Gte{l, r}  => Result::Ok(Bexp::Gte{l : Box::new(self.hoist_aexp(l, steps)?), r : Box::new(self.hoist_aexp(r, steps)?)}),
+			Bexp::Var{v} => match self.types.get(&v.name).cloned()
+			{
+				Option::Some(t) if self.is_global(&v.name) =>
+				{
+					let snap = self.fresh(&v.name, "s", t);
+					steps.push(Step::Read{snap : snap.clone(), global : v.name.clone()});
+					Result::Ok(Bexp::Var{v : VarRef::from_str(&snap)})
+				},
+				Option::Some(_) => Result::Ok(e.clone()),
+				Option::None    => Result::Err(format!("Variable {} is not declared.", v.name)),
+			},
+			Bexp::FnCall{fc} =>
+			{
+				let ret = self.hoist_call(fc, steps)?;
+				Result::Ok(Bexp::Var{v : VarRef::from_str(&ret)})
+			},
+		}
+	}
+
+	fn hoist_exp(&mut self, e : &Exp, steps : &mut Vec<Step>) -> Result<Exp, String>
+	{
+		match e
+		{
+			Exp::A{e} => Result::Ok(Exp::A{e : self.hoist_aexp(e, steps)?}),
+			Exp::B{e} => Result::Ok(Exp::B{e : self.hoist_bexp(e, steps)?}),
+		}
+	}
+
+	fn hoist_call(&mut self, fc : &FnCall, steps : &mut Vec<Step>) -> Result<String, String>
+	{
+		let mut args : Vec<Exp> = Vec::new();
+		for e in fc.exp_list.iter()
+		{
+			args.push(self.hoist_exp(e, steps)?);
+		}
+
+		let arg_types : Vec<DataType> = args.iter().map(|e| self.exp_type(e)).collect();
+		let param_types = typing::resolve_call(fc, &arg_types, self.funcs.values().map(|pt| &**pt))?;
+		let prototype = match self.funcs.get(&mangle_fn_name(&fc.name, &param_types))
+		{
+			Option::Some(pt) => pt.clone(),
+			Option::None     => return Result::Err(format!("The function {} called is undefined.", fc)),
+		};
+
+		let ret = self.fresh(&fc.name, "r", prototype.ret_type);
+		steps.push(Step::Call{prototype : prototype, args : args, ret : ret.clone(), site : fc.to_string()});
+
+		Result::Ok(ret)
+	}
+
+	/// Wrap the obligation after an expression with the obligations of its evaluation steps.
+	fn with_steps(&mut self, steps : Vec<Step>, inner : Obl) -> Obl
+	{
+		let mut obl = inner;
+
+		for step in steps.into_iter().rev()
+		{
+			match step
+			{
+				Step::Read{snap, global} =>
+				{
+					let t = self.types[&global];
+					let mut m = HashMap::new();
+					m.insert(snap, var_exp(&global, t));
+					obl = obl.subst(&m);
+				},
+				Step::Check{what, cond} =>
+				{
+					obl = Obl::And(vec![Obl::Goal{what : what, cond : cond}, obl]);
+				},
+				Step::Call{prototype, args, ret, site} =>
+				{
+					// The callee may assign to any global variable.
+					let mut havoc = HashMap::new();
+					for (g, t) in self.globals.iter()
+					{
+						let fresh = self.fresh(g, "h", *t);
+						havoc.insert(g.clone(), var_exp(&fresh, *t));
+					}
+					obl = obl.subst(&havoc);
+
+					let mut param_m : HashMap<String, Exp> = HashMap::new();
+					for (d, a) in prototype.var_decl_list.iter().zip(args.into_iter())
+					{
+						param_m.insert(d.name.clone(), a);
+					}
+
+					let mut post_m = param_m.clone();
+					post_m.insert(RESULT_VAR_NAME.to_string(), var_exp(&ret, prototype.ret_type));
+					for ens in prototype.ensures.iter().rev()
+					{
+						obl = Obl::Implies(subst_bexp(ens, &post_m), Box::new(obl));
+					}
+
+					let mut obls : Vec<Obl> = prototype.requires.iter()
+						.map(|req| Obl::Goal{what : format!("precondition {} of call {}", req, site), cond : subst_bexp(req, &param_m)})
+						.collect();
+					obls.push(obl);
+					obl = Obl::And(obls);
+				},
+			}
+		}
+
+		obl
+	}
+
+	/// Obligation of returning `ret_val` from the current function.
+	fn ret_obl(&mut self, ret_val : Option<Exp>) -> Obl
+	{
+		// Parameters in the postconditions refer to their values on entry.
+		let mut m : HashMap<String, Exp> = HashMap::new();
+		for d in self.curr_fn.var_decl_list.iter()
+		{
+			m.insert(d.name.clone(), var_exp(&format!("{}$in", d.name), d.var_type));
+		}
+		if let Option::Some(v) = ret_val
+		{
+			m.insert(RESULT_VAR_NAME.to_string(), v);
+		}
+
+		Obl::And(self.curr_fn.ensures.iter()
+			.map(|ens| Obl::Goal{what : format!("postcondition {} of {}", ens, self.curr_fn.name), cond : subst_bexp(ens, &m)})
+			.collect())
+	}
+
+	/// Weakest precondition of `c`, where `post` is the obligation after `c` completes normally.
+	fn wp(&mut self, c : &Cmd, post : Obl) -> Result<Obl, String>
+	{
+		match c
+		{
+			Cmd::Skip => Result::Ok(post),
+			Cmd::VarDecl{d} =>
+			{
+				// A declared variable holds an arbitrary value until it's assigned.
+				let fresh = self.fresh(&d.name, "h", d.var_type);
+				let mut m = HashMap::new();
+				m.insert(d.name.clone(), var_exp(&fresh, d.var_type));
+				Result::Ok(post.subst(&m))
+			},
+			Cmd::Assign{var, e} =>
+			{
+				let mut steps = Vec::new();
+				let e_h = self.hoist_exp(e, &mut steps)?;
+				let mut m = HashMap::new();
+				m.insert(var.name.clone(), e_h);
+				let inner = post.subst(&m);
+				Result::Ok(self.with_steps(steps, inner))
+			},
+			Cmd::FnCall{fc} =>
+			{
+				let mut steps = Vec::new();
+				self.hoist_call(fc, &mut steps)?;
+				Result::Ok(self.with_steps(steps, post))
+			},
+			Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				let mut steps = Vec::new();
+				let cond_h = self.hoist_bexp(cond, &mut steps)?;
+				let tr_obl = self.wp(tr_cmd, post.clone())?;
+				let fa_obl = self.wp(fa_cmd, post)?;
+				let inner = Obl::And(vec![
+					Obl::Implies(cond_h.clone(), Box::new(tr_obl)),
+					Obl::Implies(Bexp::Not{e : Box::new(cond_h)}, Box::new(fa_obl)),
+				]);
+				Result::Ok(self.with_steps(steps, inner))
+			},
+			Cmd::WhileLoop{cond, lp_cmd, invariants, ..} =>
+			{
+				for inv in invariants.iter()
+				{
+					bexp_vars(inv, &mut BTreeSet::new())?;
+				}
+				let inv_goals = |what : &str| Obl::And(invariants.iter()
+					.map(|inv| Obl::Goal{what : format!("loop invariant {} {}", inv, what), cond : inv.clone()})
+					.collect());
+
+				// An arbitrary iteration, where the invariants hold before the condition is evaluated.
+				let body_obl = self.wp(lp_cmd, inv_goals("is preserved"))?;
+				let mut steps = Vec::new();
+				let cond_h = self.hoist_bexp(cond, &mut steps)?;
+				let inner = Obl::And(vec![
+					Obl::Implies(cond_h.clone(), Box::new(body_obl)),
+					Obl::Implies(Bexp::Not{e : Box::new(cond_h)}, Box::new(post)),
+				]);
+				let mut iter_obl = self.with_steps(steps, inner);
+				for inv in invariants.iter().rev()
+				{
+					iter_obl = Obl::Implies(inv.clone(), Box::new(iter_obl));
+				}
+
+				// The variables that the loop may assign to hold arbitrary values in that iteration.
+				let mut modified = BTreeSet::new();
+				modified_vars(lp_cmd, &mut modified);
+				if cmd_has_call(lp_cmd) || bexp_has_call(cond)
+				{
+					for (g, _) in self.globals.iter()
+					{
+						modified.insert(g.clone());
+					}
+				}
+				let mut m = HashMap::new();
+				for name in modified.iter()
+				{
+					if let Option::Some(t) = self.types.get(name).cloned()
+					{
+						let fresh = self.fresh(name, "h", t);
+						m.insert(name.clone(), var_exp(&fresh, t));
+					}
+				}
+
+				Result::Ok(Obl::And(vec![inv_goals("holds on entry"), iter_obl.subst(&m)]))
+			},
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				let snd_obl = self.wp(snd_cmd, post)?;
+				self.wp(fst_cmd, snd_obl)
+			},
+			Cmd::FnDecl{prototype, ..} => Result::Err(format!("Function {} is declared at non-global scope.", prototype.name)),
+			Cmd::Return{e} => match e
+			{
+				Option::Some(e) =>
+				{
+					let mut steps = Vec::new();
+					let e_h = self.hoist_exp(e, &mut steps)?;
+					let inner = self.ret_obl(Option::Some(e_h));
+					Result::Ok(self.with_steps(steps, inner))
+				},
+				Option::None    => Result::Ok(self.ret_obl(Option::None)),
+			},
+			Cmd::Assert{cond} =>
+			{
+				let mut steps = Vec::new();
+				let cond_h = self.hoist_bexp(cond, &mut steps)?;
+				let inner = Obl::And(vec![
+					Obl::Goal{what : format!("assertion {}", cond), cond : cond_h.clone()},
+					Obl::Implies(cond_h, Box::new(post)),
+				]);
+				Result::Ok(self.with_steps(steps, inner))
+			},
+		}
+	}
+}
+
+/// Generate the verification conditions of one function.
+fn gen_fn_vcs(funcs : &HashMap<String, Rc<FnProtoType> >, globals : &Vec<(String, DataType)>, prototype : &Rc<FnProtoType>, fn_cmd : &Cmd) -> Result<Vec<Vc>, String>
+{
+	// Contracts may only refer to the parameters, and to the return value in ensures clauses.
+	let mut params : BTreeSet<String> = prototype.var_decl_list.iter().map(|d| d.name.clone()).collect();
+	for (clauses, what) in [(&prototype.requires, "requires"), (&prototype.ensures, "ensures")].iter()
+	{
+		if *what == "ensures" && prototype.ret_type != DataType::Void
+		{
+			params.insert(RESULT_VAR_NAME.to_string());
+		}
+		for clause in clauses.iter()
+		{
+			let mut vars = BTreeSet::new();
+			bexp_vars(clause, &mut vars)?;
+			if let Option::Some(v) = vars.difference(&params).next()
+			{
+				return Result::Err(format!("The {} clause {} of {} refers to {}, which is not a parameter.", what, clause, prototype.name, v));
+			}
+		}
+	}
+
+	let mut types : HashMap<String, DataType> = globals.iter().cloned().collect();
+	for d in prototype.var_decl_list.iter()
+	{
+		types.insert(d.name.clone(), d.var_type);
+	}
+
+	let renamed_cmd =
+	{
+		let mut renamer = Renamer
+		{
+			scopes : vec![HashMap::new()],
+			used : types.keys().cloned().collect(),
+			types : &mut types,
+			next_id : 0,
+		};
+		renamer.cmd(fn_cmd)?
+	};
+
+	let mut gen = WpGen
+	{
+		funcs : funcs,
+		globals : globals,
+		types : types,
+		curr_fn : prototype.clone(),
+		next_id : 0,
+	};
+
+	let fall_through = if prototype.ret_type == DataType::Void
+	{
+		gen.ret_obl(Option::None)
+	}
+	else
+	{
+		Obl::Goal{what : format!("{} returns a value", prototype.name), cond : Bexp::BoolConst{v : false}}
+	};
+
+	let mut obl = gen.wp(&renamed_cmd, fall_through)?;
+
+	let mut in_m : HashMap<String, Exp> = HashMap::new();
+	for d in prototype.var_decl_list.iter()
+	{
+		in_m.insert(format!("{}$in", d.name), var_exp(&d.name, d.var_type));
+	}
+	obl = obl.subst(&in_m);
+
+	for req in prototype.requires.iter().rev()
+	{
+		obl = Obl::Implies(req.clone(), Box::new(obl));
+	}
+
+	let mut flat = Vec::new();
+	obl.flatten(&mut Vec::new(), &mut flat);
+
+	let mut vcs = Vec::new();
+	for (what, hyps, goal) in flat.into_iter()
+	{
+		let mut vars = BTreeSet::new();
+		for h in hyps.iter()
+		{
+			bexp_vars(h, &mut vars)?;
+		}
+		bexp_vars(&goal, &mut vars)?;
+
+		let mut symbols = Vec::new();
+		for v in vars.into_iter()
+		{
+			match gen.types.get(&v)
+			{
+				Option::Some(t) => symbols.push((v, *t)),
+				Option::None    => return Result::Err(format!("Variable {} is not declared.", v)),
+			}
+		}
+
+		let hyps = hyps.into_iter().filter(|h| match h { Bexp::BoolConst{v : true} => false, _ => true }).collect();
+		vcs.push(Vc { what : what, hyps : hyps, goal : goal, symbols : symbols });
+	}
+
+	Result::Ok(vcs)
+}
+
+/// Generate the verification conditions of every function of a program.
+///
+/// For each function, the weakest precondition of its body with respect to
+/// its `ensures` clauses is computed, and must be implied by its `requires`
+/// clauses. A loop is summarized by its invariants, which must hold on entry
+/// and be preserved by every iteration. A call is summarized by the callee's
+/// contract: its `requires` clauses must hold, and its `ensures` clauses are
+/// assumed afterwards; global variables are arbitrary after a call, and on
+/// entry of a function. Integer divisors must not be zero.
+///
+/// `i32` arithmetic wraps around, as in the interpreter: `discharge`, and the
+/// scripts of `vc_to_smtlib2`, reason over 32-bit values, so a contract that
+/// only holds without overflow is refuted.
+///
+pub fn gen_vcs(prog : &Cmd) -> Result<Vec<FnVcTuple>, String>
+{
+	fn gather(c : &Cmd, globals : &mut Vec<(String, DataType)>, decls : &mut Vec<(Rc<FnProtoType>, Rc<Cmd>)>)
+	{
+		match c
+		{
+			Cmd::VarDecl{d} => globals.push((d.name.clone(), d.var_type)),
+			Cmd::FnDecl{prototype, fn_cmd} => decls.push((prototype.clone(), fn_cmd.clone())),
+			Cmd::Seq{fst_cmd, snd_cmd} =>
+			{
+				gather(fst_cmd, globals, decls);
+				gather(snd_cmd, globals, decls);
+			},
+			_ => {},
+		}
+	}
+
+	let mut globals = Vec::new();
+	let mut decls = Vec::new();
+	gather(prog, &mut globals, &mut decls);
+
+	let mut funcs : HashMap<String, Rc<FnProtoType> > = HashMap::new();
+	for (pt, _) in decls.iter()
+	{
+		let types : Vec<DataType> = pt.var_decl_list.iter().map(|d| d.var_type).collect();
+		funcs.insert(mangle_fn_name(&pt.name, &types), pt.clone());
+	}
+
+	let mut res = Vec::new();
+	for (pt, fn_cmd) in decls.iter()
+	{
+		let vcs = gen_fn_vcs(&funcs, &globals, pt, fn_cmd)?;
+		res.push((pt.clone(), vcs));
+	}
+
+	Result::Ok(res)
+}
+
+/// Discharge a verification condition with the built-in solver for linear integer arithmetic.
+pub fn discharge(vc : &Vc) -> VcResult
+{
+	let mut conds = vc.hyps.clone();
+	conds.push(Bexp::Not{e : Box::new(vc.goal.clone())});
+
+	match lin_solver::solve(&vc.symbols, &conds)
+	{
+		SolveResult::Unsat   => VcResult::Proved,
+		SolveResult::Sat(m)  =>
+		{
+			// Only show the variables that appear in the program.
+			let mut cex : Vec<(String, i64)> = m.into_iter().filter(|(n, _)| !n.contains('$')).collect();
+			cex.sort();
+			VcResult::Refuted(cex)
+		},
+		SolveResult::Unknown => VcResult::Unknown,
+	}
+}
+
+/// Generate and discharge the verification conditions of every function of a program.
+pub fn verify_program(prog : &Cmd) -> Result<WpReport, String>
+{
+	let funcs = gen_vcs(prog)?.into_iter()
+		.map(|(pt, vcs)| FnVcs
+		{
+			prototype : pt,
+			vcs : vcs.into_iter().map(|vc| { let res = discharge(&vc); (vc, res) }).collect(),
+		})
+		.collect();
+
+	Result::Ok(WpReport { funcs : funcs })
+}
+
+/// Emit a verification condition as an SMT-LIB2 script, which is
+/// unsatisfiable if and only if the condition holds.
+pub fn vc_to_smtlib2(vc : &Vc) -> Result<String, String>
+{
+	let mut conds = vc.hyps.clone();
+	conds.push(Bexp::Not{e : Box::new(vc.goal.clone())});
+
+	smtlib2::script(&format!("{}\nunsat means the condition holds", vc.what), &vc.symbols, &conds)
+}
+
+#[cfg(test)]
+mod tests
+{
+	use super::*;
+	use ast::aexp::constructor_helper::*;
+	use ast::exp::constructor_helper::*;
+	use ast::cmd::constructor_helper::*;
+	use ast::sec_label::SecLabel;
+
+	fn results(report : &WpReport) -> Vec<&VcResult>
+	{
+		report.funcs.iter().flat_map(|f| f.vcs.iter().map(|(_, res)| res)).collect()
+	}
+
+	fn refuted(report : &WpReport) -> Vec<(String, i64)>
+	{
+		results(report).into_iter()
+			.filter_map(|res| match res { VcResult::Refuted(cex) => Option::Some(cex.clone()), _ => Option::None })
+			.next()
+			.unwrap()
+	}
+
+	#[test]
+	fn contract_that_holds_is_proved()
+	{
+		/* Program:
+		 * fn inc(x : Int32) -> Int32
+		 *   requires x >= 0, x < 100
+		 *   ensures result > x
+		 * {
+		 *   return x + 1;
+		 * } */
+		let requires = vec!["x".to_aexp().gte(0i32.to_aexp()), "x".to_aexp().lt(100i32.to_aexp())];
+		let ensures = vec![RESULT_VAR_NAME.to_aexp().gt("x".to_aexp())];
+		let fn_prototype = FnProtoType::new_with_contract(DataType::Int32, SecLabel::Public, "inc".to_string(),
+			vec![VarDecl::new(DataType::Int32, "x".to_string())], requires, ensures);
+		let prog = fn_dc(fn_prototype, ret(Some(("x".to_aexp() + 1i32.to_aexp()).to_exp())));
+
+		let report = verify_program(&prog).unwrap();
+		assert!(!results(&report).is_empty());
+		assert!(report.is_verified());
+	}
+
+	#[test]
+	fn contract_that_fails_is_refuted()
+	{
+		/* Program:
+		 * fn inc(x : Int32) -> Int32
+		 *   requires x >= 0, x < 100
+		 *   ensures result > 1
+		 * {
+		 *   return x + 1;
+		 * } */
+		let requires = vec!["x".to_aexp().gte(0i32.to_aexp()), "x".to_aexp().lt(100i32.to_aexp())];
+		let ensures = vec![RESULT_VAR_NAME.to_aexp().gt(1i32.to_aexp())];
+		let fn_prototype = FnProtoType::new_with_contract(DataType::Int32, SecLabel::Public, "inc".to_string(),
+			vec![VarDecl::new(DataType::Int32, "x".to_string())], requires, ensures);
+		let prog = fn_dc(fn_prototype, ret(Some(("x".to_aexp() + 1i32.to_aexp()).to_exp())));
+
+		let report = verify_program(&prog).unwrap();
+		assert!(!report.is_verified());
+		assert_eq!(refuted(&report), vec![("x".to_string(), 0i64)]);
+	}
+
+	#[test]
+	fn contract_that_fails_on_overflow_is_refuted()
+	{
+		/* Program:
+		 * fn inc(x : Int32) -> Int32
+		 *   requires x >= 0
+		 *   ensures result > x
+		 * {
+		 *   return x + 1;
+		 * } */
+		let requires = vec!["x".to_aexp().gte(0i32.to_aexp())];
+		let ensures = vec![RESULT_VAR_NAME.to_aexp().gt("x".to_aexp())];
+		let fn_prototype = FnProtoType::new_with_contract(DataType::Int32, SecLabel::Public, "inc".to_string(),
+			vec![VarDecl::new(DataType::Int32, "x".to_string())], requires, ensures);
+		let prog = fn_dc(fn_prototype, ret(Some(("x".to_aexp() + 1i32.to_aexp()).to_exp())));
+
+		let report = verify_program(&prog).unwrap();
+		assert!(!report.is_verified());
+		assert_eq!(refuted(&report), vec![("x".to_string(), i32::MAX as i64)]);
+
+		// The script of the condition wraps around too.
+		let (vc, _) = &report.funcs[0].vcs[0];
+		let smt = vc_to_smtlib2(vc).unwrap();
+		assert!(smt.contains("(declare-const x (_ BitVec 32))"), "{}", smt);
+		assert!(smt.contains("bvadd"), "{}", smt);
+	}
+}
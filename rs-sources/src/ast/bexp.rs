@@ -279,6 +279,52 @@ impl super::Deserializible for Bexp
 	}
 }
 
+/// A list of boolean expressions, e.g. contract clauses or loop invariants.
+///
+/// # Vec<Bexp> layout
+/// ```text
+///            | uint64 - 9 Bytes | Bexp::bytes | ...
+/// ```
+///
+impl super::Serializible for Vec<Bexp>
+{
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let list_len : u64 = self.len() as u64;
+
+		let mut res = super::primit_serialize::uint64_to_bytes(&list_len);
+
+		for bexp_item in self.iter()
+		{
+			res.append(&mut bexp_item.to_bytes()?);
+		}
+
+		Result::Ok(res)
+	}
+}
+
+impl super::Deserializible for Vec<Bexp>
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], Vec<Bexp>), String>
+	{
+		let (bytes_left_1, list_len_u64) = super::primit_serialize::uint64_from_bytes(bytes)?;
+
+		let list_len = list_len_u64 as usize;
+
+		let mut bexp_list : Vec<Bexp> = Vec::new();
+		let mut bytes_left = bytes_left_1;
+
+		for _ in 0..list_len
+		{
+			let (bytes_left_i, bexp_item) = Bexp::from_bytes(bytes_left)?;
+			bytes_left = bytes_left_i;
+			bexp_list.push(bexp_item);
+		}
+
+		Result::Ok((bytes_left, bexp_list))
+	}
+}
+
 impl fmt::Display for Bexp
 {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
@@ -14,10 +14,11 @@ pub enum Cmd
 	Assign    {var : Box<super::var_general::VarRef>, e : Box<super::exp::Exp>},
 	FnCall    {fc : super::func_general::FnCall},
 	IfElse    {cond : Box<super::bexp::Bexp>, tr_cmd : Box<Cmd>, fa_cmd : Box<Cmd>},
-	WhileLoop {cond : Box<super::bexp::Bexp>, lp_cmd : Box<Cmd>},
+	WhileLoop {cond : Box<super::bexp::Bexp>, lp_cmd : Box<Cmd>, bound : Option<u64>, invariants : Vec<super::bexp::Bexp>},
 	Seq       {fst_cmd : Box<Cmd>, snd_cmd : Box<Cmd>},
 	FnDecl    {prototype : Rc<super::func_general::FnProtoType>, fn_cmd : Rc<Cmd>},
 	Return    {e : Option<Box<super::exp::Exp>>},
+	Assert    {cond : Box<super::bexp::Bexp>},
 }
 
 impl Cmd
@@ -31,10 +32,11 @@ impl Cmd
 			Cmd::Assign{var:_, e:_}                 => ByteId::Assign,
 			Cmd::FnCall{fc:_}                       => ByteId::FnCall,
 			Cmd::IfElse{cond:_, tr_cmd:_, fa_cmd:_} => ByteId::IfElse,
-			Cmd::WhileLoop{cond:_, lp_cmd:_}        => ByteId::WhileLoop,
+			Cmd::WhileLoop{..}                     => ByteId::WhileLoop,
 			Cmd::Seq{fst_cmd:_, snd_cmd:_}          => ByteId::Seq,
 			Cmd::FnDecl{prototype:_, fn_cmd:_}      => ByteId::FnDecl,
 			Cmd::Return{e:_}                        => ByteId::Return,
+			Cmd::Assert{cond:_}                     => ByteId::Assert,
 		}
 	}
 
@@ -65,9 +67,17 @@ impl Cmd
 					},
 				}
 			},
-			Cmd::WhileLoop{cond, lp_cmd}      =>
+			Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
 			{
-				out_lines.push(super::IndentString::Stay(format!("while {}", cond)));
+				match bound
+				{
+					Option::Some(b) => out_lines.push(super::IndentString::Stay(format!("while {} bound {}", cond, b))),
+					Option::None    => out_lines.push(super::IndentString::Stay(format!("while {}", cond))),
+				}
+				for inv in invariants.iter()
+				{
+					out_lines.push(super::IndentString::Stay(format!("invariant {}", inv)));
+				}
 				out_lines.push(super::IndentString::Enter);
 				lp_cmd.to_indent_lines(out_lines);
 				out_lines.push(super::IndentString::Exit);
@@ -89,7 +99,8 @@ impl Cmd
 					Some(expr) => out_lines.push(super::IndentString::Stay(format!("return {};", expr))),
 					None       => out_lines.push(super::IndentString::Stay(format!("return;"))),
 				}
-			}
+			},
+			Cmd::Assert{cond}                 => out_lines.push(super::IndentString::Stay(format!("assert({});", cond))),
 		}
 	}
 }
@@ -105,11 +116,13 @@ impl super::Serializible for Cmd
 	/// Skip:       | type=0 - 1 Byte |
 	/// VarDecl:    | type=1 - 1 Byte | VarDecl::bytes     |
 	/// Assign:     | type=2 - 1 Byte | VarRef::bytes      |  Exp::bytes  |
-	/// IfElse:     | type=3 - 1 Byte | Bexp::bytes        |  Cmd::bytes  |  Cmd::bytes  |
-	/// WhileLoop:  | type=4 - 1 Byte | Bexp::bytes        |  Cmd::bytes  |
-	/// Seq:        | type=5 - 1 Byte | Cmd::bytes         |  Cmd::bytes  |
-	/// FnDecl:     | type=6 - 1 Byte | FnProtoType::bytes |  Cmd::bytes  |
-	/// Return:     | type=7 - 1 Byte | Exp::bytes         |
+	/// FnCall:     | type=3 - 1 Byte | FnCall::bytes      |
+	/// IfElse:     | type=4 - 1 Byte | Bexp::bytes        |  Cmd::bytes  |  Cmd::bytes  |
+	/// WhileLoop:  | type=5 - 1 Byte | Bexp::bytes        |  Cmd::bytes  | has_bound - 1 Byte | (uint64 bound) | Vec<Bexp>::bytes (invariants) |
+	/// Seq:        | type=6 - 1 Byte | Cmd::bytes         |  Cmd::bytes  |
+	/// FnDecl:     | type=7 - 1 Byte | FnProtoType::bytes |  Cmd::bytes  |
+	/// Return:     | type=8 - 1 Byte | has_exp - 1 Byte   | (Exp::bytes) |
+	/// Assert:     | type=9 - 1 Byte | Bexp::bytes        |
 	/// ```
 	///
 	fn to_bytes(&self) -> Result<Vec<u8>, String>
@@ -148,11 +161,26 @@ impl super::Serializible for Cmd
 
 				Result::Ok(res)
 			},
-			Cmd::WhileLoop{cond, lp_cmd} =>
+			Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
 			{
 				res.append(&mut (cond.to_bytes()?));
 				res.append(&mut (lp_cmd.to_bytes()?));
 
+				match bound
+				{
+					Option::Some(b) =>
+					{
+						res.push(1u8); // To indicate a declared bound
+						res.append(&mut super::primit_serialize::uint64_to_bytes(b));
+					},
+					Option::None    =>
+					{
+						res.push(0u8); // To indicate no bound
+					},
+				}
+
+				res.append(&mut (invariants.to_bytes()?));
+
 				Result::Ok(res)
 			},
 			Cmd::Seq{fst_cmd, snd_cmd} =>
@@ -183,6 +211,12 @@ impl super::Serializible for Cmd
 					},
 				}
 			},
+			Cmd::Assert{cond} =>
+			{
+				res.append(&mut (cond.to_bytes()?));
+
+				Result::Ok(res)
+			},
 		}
 	}
 }
@@ -233,7 +267,25 @@ impl super::Deserializible for Cmd
 					let (bytes_left_1, parsed_cond) = super::bexp::Bexp::from_bytes(&bytes[1..])?;
 					let (bytes_left_2, parsed_lp_cmd) = super::cmd::Cmd::from_bytes(bytes_left_1)?;
 
-					Result::Ok((bytes_left_2, wh_lp(parsed_cond, parsed_lp_cmd)))
+					if bytes_left_2.is_empty()
+					{
+						return Result::Err(format!("{}", "Unexpected end of bytes for while loop bound."));
+					}
+
+					let (bytes_left_3, parsed_bound) = match bytes_left_2[0]
+					{
+						1u8 =>
+						{
+							let (bytes_left_3, parsed_bound) = super::primit_serialize::uint64_from_bytes(&bytes_left_2[1..])?;
+							(bytes_left_3, Option::Some(parsed_bound))
+						},
+						0u8 => (&bytes_left_2[1..], Option::None),
+						_   => return Result::Err(format!("Failed to parse while loop. Invalid bound flag {}.", bytes_left_2[0])),
+					};
+
+					let (bytes_left_4, parsed_invariants) = Vec::<super::bexp::Bexp>::from_bytes(bytes_left_3)?;
+
+					Result::Ok((bytes_left_4, wh_lp_inv(parsed_cond, parsed_lp_cmd, parsed_bound, parsed_invariants)))
 				},
 				ByteId::Seq       =>
 				{
@@ -261,6 +313,12 @@ impl super::Deserializible for Cmd
 						Result::Ok((&bytes[2..], ret(Option::None)))
 					}
 				},
+				ByteId::Assert    =>
+				{
+					let (bytes_left_1, parsed_cond) = super::bexp::Bexp::from_bytes(&bytes[1..])?;
+
+					Result::Ok((bytes_left_1, assert(parsed_cond)))
+				},
 			}
 		}
 		else
@@ -288,7 +346,19 @@ impl fmt::Display for Cmd
 					_         => write!(f, "if {0}\n{3}\n{1}\n{4} else {3}\n{2}\n{4}", cond, tr_cmd, fa_cmd, "{", "}"),
 				}
 			},
-			Cmd::WhileLoop{cond, lp_cmd}      => write!(f, "while {0}\n{2}\n{1}\n{3}", cond, lp_cmd, "{", "}"),
+			Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
+			{
+				write!(f, "while {}", cond)?;
+				if let Option::Some(b) = bound
+				{
+					write!(f, " bound {}", b)?;
+				}
+				for inv in invariants.iter()
+				{
+					write!(f, "\ninvariant {}", inv)?;
+				}
+				write!(f, "\n{1}\n{0}\n{2}", lp_cmd, "{", "}")
+			},
 			Cmd::Seq{fst_cmd, snd_cmd}        => write!(f, "{}\n{}", fst_cmd, snd_cmd),
 			Cmd::FnDecl{prototype, fn_cmd}    => write!(f, "{0}\n{2}\n{1}\n{3}", prototype, fn_cmd, "{", "}"),
 			Cmd::Return{e}                    => {
@@ -296,7 +366,8 @@ impl fmt::Display for Cmd
 					Some(expr) => write!(f, "return {};", expr),
 					None       => write!(f, "return;"),
 				}
-			}
+			},
+			Cmd::Assert{cond}                 => write!(f, "assert({});", cond),
 		}
 	}
 }
@@ -305,6 +376,7 @@ pub mod constructor_helper
 {
 	use std::boxed::Box;
 	use std::rc::Rc;
+	use std::vec::Vec;
 
 	pub fn skip() -> super::Cmd
 	{
@@ -333,7 +405,27 @@ pub mod constructor_helper
 
 	pub fn wh_lp(cond : super::super::bexp::Bexp, lp_cmd : super::Cmd) -> super::Cmd
 	{
-		super::Cmd::WhileLoop {cond : Box::new(cond), lp_cmd : Box::new(lp_cmd)}
+		super::Cmd::WhileLoop {cond : Box::new(cond), lp_cmd : Box::new(lp_cmd), bound : Option::None, invariants : Vec::new()}
+	}
+
+	/// While loop with a declared upper bound on the number of iterations.
+	///
+	/// The bound is required to run the loop in the oblivious mode of the
+	/// interpreter, where the loop body is always executed `bound` times.
+	///
+	pub fn wh_lp_bounded(cond : super::super::bexp::Bexp, lp_cmd : super::Cmd, bound : u64) -> super::Cmd
+	{
+		super::Cmd::WhileLoop {cond : Box::new(cond), lp_cmd : Box::new(lp_cmd), bound : Option::Some(bound), invariants : Vec::new()}
+	}
+
+	/// While loop with an optional bound, and loop invariants for the
+	/// weakest-precondition verifier.
+	///
+	/// The invariants must hold before the loop, and after every iteration.
+	///
+	pub fn wh_lp_inv(cond : super::super::bexp::Bexp, lp_cmd : super::Cmd, bound : Option<u64>, invariants : Vec<super::super::bexp::Bexp>) -> super::Cmd
+	{
+		super::Cmd::WhileLoop {cond : Box::new(cond), lp_cmd : Box::new(lp_cmd), bound : bound, invariants : invariants}
 	}
 
 	pub fn seq(fst_cmd : super::Cmd, snd_cmd : super::Cmd) -> super::Cmd
@@ -353,6 +445,15 @@ pub mod constructor_helper
 			None       => super::Cmd::Return {e : None},
 		}
 	}
+
+	/// Assertion that fails the execution if the condition does not hold.
+	///
+	/// Mostly used by the test functions of a program.
+	///
+	pub fn assert(cond : super::super::bexp::Bexp) -> super::Cmd
+	{
+		super::Cmd::Assert {cond : Box::new(cond)}
+	}
 }
 
 enum ByteId
@@ -366,6 +467,7 @@ enum ByteId
 	Seq,
 	FnDecl,
 	Return,
+	Assert,
 }
 
 impl ByteId
@@ -383,6 +485,7 @@ impl ByteId
 			ByteId::Seq       => 6u8,
 			ByteId::FnDecl    => 7u8,
 			ByteId::Return    => 8u8,
+			ByteId::Assert    => 9u8,
 		}
 	}
 
@@ -399,6 +502,7 @@ impl ByteId
 			6u8 => Result::Ok(ByteId::Seq),
 			7u8 => Result::Ok(ByteId::FnDecl),
 			8u8 => Result::Ok(ByteId::Return),
+			9u8 => Result::Ok(ByteId::Assert),
 			_   => Result::Err(format!("{}", "Unrecognized type ID from byte for Cmd."))
 		}
 	}
@@ -3,19 +3,48 @@ use std::fmt;
 use std::vec::Vec;
 use std::string::String;
 
+/// Name that refers to the return value of a function in its `ensures` clauses.
+pub const RESULT_VAR_NAME : &str = "result";
+
 #[derive(Clone)]
 pub struct FnProtoType
 {
 	pub ret_type : super::data_type::DataType,
+	pub ret_label : super::sec_label::SecLabel,
 	pub name : String,
 	pub var_decl_list : Vec<super::var_general::VarDecl>,
+	/// Preconditions over the parameters; the caller must establish them.
+	pub requires : Vec<super::bexp::Bexp>,
+	/// Postconditions over the parameters and `result`; the function must establish them.
+	pub ensures : Vec<super::bexp::Bexp>,
 }
 
 impl FnProtoType
 {
+	/// Declare a function prototype with `SecLabel::Public` return value.
 	pub fn new(ret_type : super::data_type::DataType, name : String, var_decl_list : Vec<super::var_general::VarDecl>) -> FnProtoType
 	{
-		FnProtoType {ret_type : ret_type, name : name, var_decl_list : var_decl_list}
+		FnProtoType::new_labeled(ret_type, super::sec_label::SecLabel::Public, name, var_decl_list)
+	}
+
+	pub fn new_labeled(ret_type : super::data_type::DataType, ret_label : super::sec_label::SecLabel, name : String, var_decl_list : Vec<super::var_general::VarDecl>) -> FnProtoType
+	{
+		FnProtoType {ret_type : ret_type, ret_label : ret_label, name : name, var_decl_list : var_decl_list, requires : Vec::new(), ensures : Vec::new()}
+	}
+
+	/// Declare a function prototype with a contract, i.e. `requires` and `ensures` clauses.
+	///
+	/// The contract is checked by `analyzer::wp`; it has no effect on the interpreter.
+	///
+	pub fn new_with_contract(ret_type : super::data_type::DataType, ret_label : super::sec_label::SecLabel, name : String, var_decl_list : Vec<super::var_general::VarDecl>,
+		requires : Vec<super::bexp::Bexp>, ensures : Vec<super::bexp::Bexp>) -> FnProtoType
+	{
+		FnProtoType {ret_type : ret_type, ret_label : ret_label, name : name, var_decl_list : var_decl_list, requires : requires, ensures : ensures}
+	}
+
+	pub fn has_contract(&self) -> bool
+	{
+		!self.requires.is_empty() || !self.ensures.is_empty()
 	}
 
 	pub fn fmt_var_decl_list(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
@@ -47,13 +76,15 @@ impl super::Serializible for FnProtoType
 	///
 	/// # FnProtoType layout
 	/// ```
-	///            | Datatype - 1 byte | string - 10+ bytes | uint64 - 9 Bytes | VarDecl::bytes | ...
+	///            | Datatype - 1 byte | SecLabel - 1 byte | string - 10+ bytes | uint64 - 9 Bytes | VarDecl::bytes | ...
+	///            | Vec<Bexp>::bytes (requires) | Vec<Bexp>::bytes (ensures) |
 	/// ```
 	///
 	fn to_bytes(&self) -> Result<Vec<u8>, String>
 	{
 		// 1. ret type
 		let mut res = self.ret_type.to_bytes()?;
+		res.append(&mut (self.ret_label.to_bytes()?));
 
 		// 2. func name
 		res.append(&mut (super::primit_serialize::string_to_bytes(&self.name)));
@@ -68,6 +99,10 @@ impl super::Serializible for FnProtoType
 			res.append(&mut (var_decl_item.to_bytes()?));
 		}
 
+		// 5. contract
+		res.append(&mut (self.requires.to_bytes()?));
+		res.append(&mut (self.ensures.to_bytes()?));
+
 		Result::Ok(res)
 	}
 }
@@ -77,7 +112,8 @@ impl super::Deserializible for FnProtoType
 	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], FnProtoType), String>
 	{
 		// 1. ret type
-		let (bytes_left_1, parsed_ret_type) = super::data_type::DataType::from_bytes(bytes)?;
+		let (bytes_left_0, parsed_ret_type) = super::data_type::DataType::from_bytes(bytes)?;
+		let (bytes_left_1, parsed_ret_label) = super::sec_label::SecLabel::from_bytes(bytes_left_0)?;
 
 		// 2. func name
 		let (bytes_left_2, parsed_name) = super::primit_serialize::string_from_bytes(bytes_left_1)?;
@@ -99,8 +135,12 @@ impl super::Deserializible for FnProtoType
 			bytes_left_list.push(bytes_left_i);
 		}
 
-		Result::Ok((bytes_left_list[var_decl_list_len],
-			FnProtoType{ret_type : parsed_ret_type, name : parsed_name, var_decl_list : parsed_var_decl_list}
+		// 5. contract
+		let (bytes_left_4, parsed_requires) = Vec::<super::bexp::Bexp>::from_bytes(bytes_left_list[var_decl_list_len])?;
+		let (bytes_left_5, parsed_ensures) = Vec::<super::bexp::Bexp>::from_bytes(bytes_left_4)?;
+
+		Result::Ok((bytes_left_5,
+			FnProtoType::new_with_contract(parsed_ret_type, parsed_ret_label, parsed_name, parsed_var_decl_list, parsed_requires, parsed_ensures)
 		))
 	}
 }
@@ -111,7 +151,22 @@ impl fmt::Display for FnProtoType
 	{
 		write!(f, "fn {}(", self.name)?;
 		self.fmt_var_decl_list(f)?;
-		write!(f, ") -> {}", self.ret_type)
+		match self.ret_label
+		{
+			super::sec_label::SecLabel::Public => write!(f, ") -> {}", self.ret_type)?,
+			super::sec_label::SecLabel::Secret => write!(f, ") -> {} {}", self.ret_label, self.ret_type)?,
+		}
+
+		for cond in self.requires.iter()
+		{
+			write!(f, " requires {}", cond)?;
+		}
+		for cond in self.ensures.iter()
+		{
+			write!(f, " ensures {}", cond)?;
+		}
+
+		write!(f, "")
 	}
 }
 
@@ -120,13 +175,23 @@ pub struct FnCall
 {
 	pub name : String,
 	pub exp_list : Vec<super::exp::Exp>,
+	/// Parameter types of the overload called, once the call is resolved by
+	/// `type_checker::record_overloads`; otherwise the overload is resolved
+	/// from the types of the arguments.
+	pub param_types : Option<Vec<super::data_type::DataType>>,
 }
 
 impl FnCall
 {
 	pub fn new(name : String, exp_list : Vec<super::exp::Exp>) -> FnCall
 	{
-		FnCall {name : name, exp_list : exp_list}
+		FnCall {name : name, exp_list : exp_list, param_types : Option::None}
+	}
+
+	/// Call of the overload of the function with the given parameter types.
+	pub fn new_resolved(name : String, exp_list : Vec<super::exp::Exp>, param_types : Vec<super::data_type::DataType>) -> FnCall
+	{
+		FnCall {name : name, exp_list : exp_list, param_types : Option::Some(param_types)}
 	}
 
 	pub fn fmt_exp_list(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
@@ -179,14 +244,36 @@ impl super::Serializible for FnCall
 	/// # FnCall layout
 	/// ```
 	///            | string - 10+ bytes | uint64 - 9 Bytes | Exp::bytes | ...
+	///            | is_resolved - 1 Byte | (uint64 - 9 Bytes | DataType::bytes | ...) |
 	/// ```
 	///
+	/// The list of parameter types only follows `is_resolved` when it is 1,
+	/// and holds the types of the declaration the call resolves to.
+	///
+	/// `is_resolved` breaks the format of the byte code written before calls
+	/// were resolved: such byte code isn't parsed anymore, and needs to be
+	/// generated again.
+	///
 	fn to_bytes(&self) -> Result<Vec<u8>, String>
 	{
 		let mut res = super::primit_serialize::string_to_bytes(&self.name);
 
 		res.append(&mut (self.exp_list.to_bytes()?));
 
+		match &self.param_types
+		{
+			Option::Some(types) =>
+			{
+				res.push(1u8); // To indicate a resolved call
+				res.append(&mut super::primit_serialize::uint64_to_bytes(&(types.len() as u64)));
+				for t in types.iter()
+				{
+					res.append(&mut (t.to_bytes()?));
+				}
+			},
+			Option::None        => res.push(0u8), // To indicate an unresolved call
+		}
+
 		Result::Ok(res)
 	}
 }
@@ -199,7 +286,28 @@ impl super::Deserializible for FnCall
 
 		let (bytes_left_2, exp_list) = Vec::from_bytes(bytes_left_1)?;
 
-		Result::Ok((bytes_left_2, FnCall{ name : name, exp_list : exp_list }))
+		if bytes_left_2.is_empty()
+		{
+			return Result::Err(format!("{}", "Unexpected end of bytes for function call."));
+		}
+
+		if bytes_left_2[0] == 0u8
+		{
+			return Result::Ok((&bytes_left_2[1..], FnCall{ name : name, exp_list : exp_list, param_types : Option::None }));
+		}
+
+		let (bytes_left_3, types_len_u64) = super::primit_serialize::uint64_from_bytes(&bytes_left_2[1..])?;
+
+		let mut param_types : Vec<super::data_type::DataType> = Vec::new();
+		let mut bytes_left_4 = bytes_left_3;
+		for _ in 0..types_len_u64
+		{
+			let (bytes_left_i, t) = super::data_type::DataType::from_bytes(bytes_left_4)?;
+			param_types.push(t);
+			bytes_left_4 = bytes_left_i;
+		}
+
+		Result::Ok((bytes_left_4, FnCall{ name : name, exp_list : exp_list, param_types : Option::Some(param_types) }))
 	}
 }
 
@@ -69,6 +69,7 @@ pub fn indent_lines_to_string(in_lines : &Vec<IndentString>, indent_ch : char) -
 pub mod primit_serialize;
 
 pub mod data_type;
+pub mod sec_label;
 pub mod var_general;
 pub mod exp;
 pub mod func_general;
@@ -76,3 +77,5 @@ pub mod aexp;
 pub mod bexp;
 pub mod cmd;
 pub mod states;
+pub mod typed;
+pub mod test_fn;
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use std::vec::Vec;
+use std::string::String;
+
+/// Security label of a variable, a parameter, or a return value.
+///
+/// Labels form a two-point lattice, `Public ⊑ Secret`; information may only
+/// flow from a lower (or equal) label to a higher one.
+///
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SecLabel
+{
+	Public,
+	Secret,
+}
+
+impl SecLabel
+{
+	fn to_byte(&self) -> u8
+	{
+		match self
+		{
+			SecLabel::Public => 0u8,
+			SecLabel::Secret => 1u8,
+		}
+	}
+
+	fn from_byte(b : &u8) -> Result<SecLabel, String>
+	{
+		match b
+		{
+			0u8 => Result::Ok(SecLabel::Public),
+			1u8 => Result::Ok(SecLabel::Secret),
+			_   => Result::Err(format!("{}", "Unrecognized byte ID for security label."))
+		}
+	}
+
+	/// Least upper bound of two labels.
+	pub fn join(&self, other : &SecLabel) -> SecLabel
+	{
+		match (self, other)
+		{
+			(SecLabel::Public, SecLabel::Public) => SecLabel::Public,
+			_                                    => SecLabel::Secret,
+		}
+	}
+
+	/// Greatest lower bound of two labels.
+	pub fn meet(&self, other : &SecLabel) -> SecLabel
+	{
+		match (self, other)
+		{
+			(SecLabel::Secret, SecLabel::Secret) => SecLabel::Secret,
+			_                                    => SecLabel::Public,
+		}
+	}
+
+	/// Whether information labeled `self` is allowed to flow into `other`.
+	pub fn flows_to(&self, other : &SecLabel) -> bool
+	{
+		match (self, other)
+		{
+			(SecLabel::Secret, SecLabel::Public) => false,
+			_                                    => true,
+		}
+	}
+}
+
+impl super::Serializible for SecLabel
+{
+	/// Serialize the AST (of SecLabel type) into serials of bytes, and return the vector of bytes.
+	///
+	/// Please refer to the documentation on the trait for detail.
+	///
+	/// # SecLabel layout
+	/// ```text
+	///            | SecLabel - 1 Byte |
+	/// ```
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		Ok(vec![self.to_byte()])
+	}
+}
+
+impl super::Deserializible for SecLabel
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], SecLabel), String>
+	{
+		if bytes.is_empty()
+		{
+			return Result::Err(format!("{}", "Unexpected end of bytes for security label."));
+		}
+
+		let label = SecLabel::from_byte(&bytes[0])?;
+
+		Result::Ok((&bytes[1..], label))
+	}
+}
+
+impl fmt::Display for SecLabel
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			SecLabel::Public => write!(f, "{}", "public"),
+			SecLabel::Secret => write!(f, "{}", "secret"),
+		}
+	}
+}
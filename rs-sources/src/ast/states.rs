@@ -86,6 +86,20 @@ impl<T : fmt::Display + AnyFunc> FuncStates<T>
 			Option::None    => false,
 		}
 	}
+
+	/// Prototypes of the functions declared, in no particular order.
+	///
+	pub fn prototypes(&self) -> impl Iterator<Item = &super::func_general::FnProtoType>
+	{
+		self.map.values().map(|func| func.get_prototype_ref())
+	}
+
+	/// Some function named `name` (unmangled), i.e. some overload of it, is declared.
+	///
+	pub fn has_func_named(&self, name : &String) -> bool
+	{
+		self.prototypes().any(|pt| pt.name == *name)
+	}
 }
 
 impl<T : fmt::Display + AnyFunc> fmt::Display for FuncStates<T>
@@ -250,6 +264,29 @@ FuncStatesStack<FnStateType>
 		Self::search_fn_internal(curr, name, 0)
 	}
 
+	/// Find the innermost level declaring some overload of the function
+	/// named `name` (unmangled), i.e. the level whose overloads a call of
+	/// `name` is resolved among.
+	///
+	pub fn search_fn_name(curr : &Rc<FuncStatesStack<FnStateType> >, name : &String) -> Option<(Rc<FuncStatesStack<FnStateType> >, usize)>
+	{
+		let mut level : usize = 0;
+		let mut it = curr.clone();
+		loop
+		{
+			if it.state.has_func_named(name)
+			{
+				return Option::Some((it, level));
+			}
+			it = match &it.parent
+			{
+				Option::Some(p) => p.clone(),
+				Option::None    => return Option::None,
+			};
+			level += 1;
+		}
+	}
+
 	pub fn get_fn_at_curr_level(&self, name : &String) -> Option<&FnStateType>
 	{
 		match self.state.get_fn(name)
@@ -0,0 +1,172 @@
+use std::fmt;
+
+use std::vec::Vec;
+use std::string::String;
+
+use std::rc::Rc;
+
+/// Test function of a program, i.e. a function without parameters nor
+/// return value, that asserts the expected behavior of the program.
+///
+/// Test functions are kept out of the program itself; they are serialized in
+/// a separate section after the program bytecode, and only declared when the
+/// program is tested.
+///
+#[derive(Clone)]
+pub struct TestFn
+{
+	pub name   : String,
+	pub fn_cmd : Rc<super::cmd::Cmd>,
+}
+
+impl TestFn
+{
+	pub fn new(name : String, fn_cmd : super::cmd::Cmd) -> TestFn
+	{
+		TestFn { name : name, fn_cmd : Rc::new(fn_cmd) }
+	}
+
+	/// Declaration of the test function as a void function without parameters.
+	///
+	pub fn to_decl(&self) -> super::cmd::Cmd
+	{
+		let prototype = super::func_general::FnProtoType::new(super::data_type::DataType::Void, self.name.clone(), Vec::new());
+
+		super::cmd::Cmd::FnDecl { prototype : Rc::new(prototype), fn_cmd : self.fn_cmd.clone() }
+	}
+
+	pub fn to_indent_lines(&self, out_lines : &mut Vec<super::IndentString>)
+	{
+		out_lines.push(super::IndentString::Stay(format!("#[test]")));
+		out_lines.push(super::IndentString::Stay(format!("fn {}()", self.name)));
+		out_lines.push(super::IndentString::Enter);
+		self.fn_cmd.to_indent_lines(out_lines);
+		out_lines.push(super::IndentString::Exit);
+	}
+}
+
+/// The program to test, followed by the declarations of its test functions.
+///
+/// Test functions are declared last, so they can call every function of the
+/// program, and are type checked and interpreted as any other function.
+///
+pub fn with_tests(prog : &super::cmd::Cmd, tests : &Vec<TestFn>) -> super::cmd::Cmd
+{
+	let mut res = prog.clone();
+
+	for test in tests.iter()
+	{
+		res = super::cmd::constructor_helper::seq(res, test.to_decl());
+	}
+
+	res
+}
+
+/// Parse the test section that follows the program bytecode.
+///
+/// A program without test section has no bytes left after it, and no tests.
+///
+pub fn tests_from_bytes(bytes : &[u8]) -> Result<Vec<TestFn>, String>
+{
+	use super::Deserializible;
+
+	if bytes.is_empty()
+	{
+		return Result::Ok(Vec::new());
+	}
+
+	let (bytes_left, tests) = Vec::<TestFn>::from_bytes(bytes)?;
+
+	if !bytes_left.is_empty()
+	{
+		return Result::Err(format!("There are {} unexpected byte(s) after the test section.", bytes_left.len()));
+	}
+
+	Result::Ok(tests)
+}
+
+impl super::Serializible for TestFn
+{
+	/// Serialize the test function into serials of bytes, and return the vector of bytes.
+	///
+	/// Please refer to the documentation on the trait for detail.
+	///
+	/// # TestFn layout
+	/// ```text
+	/// | String::bytes (name) | Cmd::bytes |
+	/// ```
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = super::primit_serialize::string_to_bytes(&self.name);
+
+		res.append(&mut (self.fn_cmd.to_bytes()?));
+
+		Result::Ok(res)
+	}
+}
+
+impl super::Deserializible for TestFn
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], TestFn), String>
+	{
+		let (bytes_left_1, parsed_name) = super::primit_serialize::string_from_bytes(bytes)?;
+		let (bytes_left_2, parsed_fn_cmd) = super::cmd::Cmd::from_bytes(bytes_left_1)?;
+
+		Result::Ok((bytes_left_2, TestFn::new(parsed_name, parsed_fn_cmd)))
+	}
+}
+
+impl super::Serializible for Vec<TestFn>
+{
+	/// Serialize the test section.
+	///
+	/// # Test section layout
+	/// ```text
+	/// | uint64 num_tests | TestFn::bytes | ... | TestFn::bytes |
+	/// ```
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let list_len : u64 = self.len() as u64;
+
+		let mut res = super::primit_serialize::uint64_to_bytes(&list_len);
+
+		for test_item in self.iter()
+		{
+			res.append(&mut test_item.to_bytes()?);
+		}
+
+		Result::Ok(res)
+	}
+}
+
+impl super::Deserializible for Vec<TestFn>
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], Vec<TestFn>), String>
+	{
+		let (bytes_left_1, list_len_u64) = super::primit_serialize::uint64_from_bytes(bytes)?;
+
+		let list_len = list_len_u64 as usize;
+
+		let mut test_list : Vec<TestFn> = Vec::new();
+		let mut bytes_left = bytes_left_1;
+
+		for _ in 0..list_len
+		{
+			let (bytes_left_i, test_item) = TestFn::from_bytes(bytes_left)?;
+			bytes_left = bytes_left_i;
+			test_list.push(test_item);
+		}
+
+		Result::Ok((bytes_left, test_list))
+	}
+}
+
+impl fmt::Display for TestFn
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "#[test]\nfn {0}()\n{2}\n{1}\n{3}", self.name, self.fn_cmd, "{", "}")
+	}
+}
@@ -0,0 +1,245 @@
+//! Annotated AST, produced by `type_checker::annotate` from a well-typed
+//! program, and executed by `interpreter::typed` without any dynamic type
+//! check or name lookup.
+//!
+//! Every expression is tagged with its type, and the implicit promotions of
+//! `Int32` values into `Float32` ones are explicit `Promote` nodes; every
+//! call refers to the function it calls by its index in
+//! `TypedProgram::funcs`; and every variable reference refers to the
+//! variable by its scope depth and its slot in that scope.
+
+use std::fmt;
+use std::rc::Rc;
+
+use std::vec::Vec;
+use std::boxed::Box;
+use std::string::String;
+use std::string::ToString;
+
+/// A variable, as the `slot`-th variable declared in the scope `depth`
+/// levels out of the innermost one, where the global scope is the outermost
+/// one, a function body is one level in it, and each branch of an if-else
+/// and each iteration of a loop is one more level.
+///
+#[derive(Clone)]
+pub struct VarSlot
+{
+	pub name  : String,
+	pub depth : usize,
+	pub slot  : usize,
+}
+
+impl fmt::Display for VarSlot
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "{}@{}.{}", self.name, self.depth, self.slot)
+	}
+}
+
+#[derive(Clone)]
+pub struct TypedExp
+{
+	pub t : super::data_type::DataType,
+	pub e : TypedExpKind,
+}
+
+/// Expressions, with the operands of arithmetic operators and comparisons
+/// already promoted to the same type.
+///
+#[derive(Clone)]
+pub enum TypedExpKind
+{
+	IntConst {v : i32},
+	FloConst {v : f32},
+	BoolConst{v : bool},
+	Var      {v : VarSlot},
+	/// Promotion of an `Int32` value into `Float32`.
+	Promote  {e : Box<TypedExp>},
+	Add      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Sub      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Mul      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Div      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Mod      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Not      {e : Box<TypedExp>},
+	Beq      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Bneq     {l : Box<TypedExp>, r : Box<TypedExp>},
+	And      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Or       {l : Box<TypedExp>, r : Box<TypedExp>},
+	Aeq      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Aneq     {l : Box<TypedExp>, r : Box<TypedExp>},
+	Lt       {l : Box<TypedExp>, r : Box<TypedExp>},
+	Lte      {l : Box<TypedExp>, r : Box<TypedExp>},
+	Gt       {l : Box<TypedExp>, r : Box<TypedExp>},
+	Gte      {l : Box<TypedExp>, r : Box<TypedExp>},
+	FnCall   {fc : TypedFnCall},
+}
+
+impl fmt::Display for TypedExp
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match &self.e
+		{
+			TypedExpKind::IntConst{v}  => write!(f, "{}", v),
+			TypedExpKind::FloConst{v}  => write!(f, "{}", v),
+			TypedExpKind::BoolConst{v} => write!(f, "{}", v),
+			TypedExpKind::Var{v}       => write!(f, "{}", v),
+			TypedExpKind::Promote{e}   => write!(f, "({}){}", self.t, e),
+			TypedExpKind::Add{l, r}    => write!(f, "({} + {})", l, r),
+			TypedExpKind::Sub{l, r}    => write!(f, "({} - {})", l, r),
+			TypedExpKind::Mul{l, r}    => write!(f, "({} * {})", l, r),
+			TypedExpKind::Div{l, r}    => write!(f, "({} / {})", l, r),
+			TypedExpKind::Mod{l, r}    => write!(f, "({} % {})", l, r),
+			TypedExpKind::Not{e}       => write!(f, "!{}", e),
+			TypedExpKind::Beq{l, r}    => write!(f, "({} == {})", l, r),
+			TypedExpKind::Bneq{l, r}   => write!(f, "({} != {})", l, r),
+			TypedExpKind::And{l, r}    => write!(f, "({} && {})", l, r),
+			TypedExpKind::Or{l, r}     => write!(f, "({} || {})", l, r),
+			TypedExpKind::Aeq{l, r}    => write!(f, "({} == {})", l, r),
+			TypedExpKind::Aneq{l, r}   => write!(f, "({} != {})", l, r),
+			TypedExpKind::Lt{l, r}     => write!(f, "({} < {})", l, r),
+			TypedExpKind::Lte{l, r}    => write!(f, "({} <= {})", l, r),
+			TypedExpKind::Gt{l, r}     => write!(f, "({} > {})", l, r),
+			TypedExpKind::Gte{l, r}    => write!(f, "({} >= {})", l, r),
+			TypedExpKind::FnCall{fc}   => write!(f, "{}", fc),
+		}
+	}
+}
+
+/// Call of the function `func` in `TypedProgram::funcs`, with the arguments
+/// already promoted to the types of its parameters.
+///
+#[derive(Clone)]
+pub struct TypedFnCall
+{
+	pub name     : String,
+	pub func     : usize,
+	pub exp_list : Vec<TypedExp>,
+}
+
+impl fmt::Display for TypedFnCall
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		let args : Vec<String> = self.exp_list.iter().map(|e| e.to_string()).collect();
+		write!(f, "{}#{}({})", self.name, self.func, args.join(", "))
+	}
+}
+
+/// Commands executed in a new scope, which holds `num_slots` variables.
+///
+#[derive(Clone)]
+pub struct TypedBlock
+{
+	pub num_slots : usize,
+	pub cmd       : Box<TypedCmd>,
+}
+
+#[derive(Clone)]
+pub enum TypedCmd
+{
+	Skip,
+	VarDecl  {v : VarSlot, t : super::data_type::DataType},
+	/// Assignment of a value already promoted to the type of the variable.
+	Assign   {v : VarSlot, e : TypedExp},
+	FnCall   {fc : TypedFnCall},
+	IfElse   {cond : TypedExp, tr_cmd : TypedBlock, fa_cmd : TypedBlock},
+	WhileLoop{cond : TypedExp, lp_cmd : TypedBlock, bound : Option<u64>},
+	Seq      {fst_cmd : Box<TypedCmd>, snd_cmd : Box<TypedCmd>},
+	/// Declaration of the function `func` in `TypedProgram::funcs`.
+	FnDecl   {func : usize},
+	/// Return of a value already promoted to the return type of the function.
+	Return   {e : Option<TypedExp>},
+	Assert   {cond : TypedExp},
+}
+
+impl TypedCmd
+{
+	pub fn to_indent_lines(&self, out_lines : &mut Vec<super::IndentString>)
+	{
+		match self
+		{
+			TypedCmd::Skip                         => {},
+			TypedCmd::VarDecl{v, t}                => out_lines.push(super::IndentString::Stay(format!("let {} : {};", v, t))),
+			TypedCmd::Assign{v, e}                 => out_lines.push(super::IndentString::Stay(format!("{} = {} : {};", v, e, e.t))),
+			TypedCmd::FnCall{fc}                   => out_lines.push(super::IndentString::Stay(format!("{};", fc))),
+			TypedCmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			{
+				out_lines.push(super::IndentString::Stay(format!("if {}", cond)));
+				tr_cmd.to_indent_lines(out_lines);
+				out_lines.push(super::IndentString::Stay(format!("else")));
+				fa_cmd.to_indent_lines(out_lines);
+			},
+			TypedCmd::WhileLoop{cond, lp_cmd, bound} =>
+			{
+				match bound
+				{
+					Option::Some(b) => out_lines.push(super::IndentString::Stay(format!("while {} bound {}", cond, b))),
+					Option::None    => out_lines.push(super::IndentString::Stay(format!("while {}", cond))),
+				}
+				lp_cmd.to_indent_lines(out_lines);
+			},
+			TypedCmd::Seq{fst_cmd, snd_cmd}        =>
+			{
+				fst_cmd.to_indent_lines(out_lines);
+				snd_cmd.to_indent_lines(out_lines);
+			},
+			TypedCmd::FnDecl{func}                 => out_lines.push(super::IndentString::Stay(format!("fn #{};", func))),
+			TypedCmd::Return{e}                    =>
+			{
+				match e
+				{
+					Option::Some(expr) => out_lines.push(super::IndentString::Stay(format!("return {} : {};", expr, expr.t))),
+					Option::None       => out_lines.push(super::IndentString::Stay(format!("return;"))),
+				}
+			},
+			TypedCmd::Assert{cond}                 => out_lines.push(super::IndentString::Stay(format!("assert({});", cond))),
+		}
+	}
+}
+
+impl TypedBlock
+{
+	pub fn to_indent_lines(&self, out_lines : &mut Vec<super::IndentString>)
+	{
+		out_lines.push(super::IndentString::Enter);
+		out_lines.push(super::IndentString::Stay(format!("// {} slot(s)", self.num_slots)));
+		self.cmd.to_indent_lines(out_lines);
+		out_lines.push(super::IndentString::Exit);
+	}
+}
+
+/// A function, whose parameters are the first slots of the scope of its body.
+///
+#[derive(Clone)]
+pub struct TypedFn
+{
+	pub prototype : Rc<super::func_general::FnProtoType>,
+	pub body      : TypedBlock,
+}
+
+#[derive(Clone)]
+pub struct TypedProgram
+{
+	pub funcs       : Vec<TypedFn>,
+	pub num_globals : usize,
+	/// The global commands.
+	pub cmd         : TypedCmd,
+}
+
+impl TypedProgram
+{
+	pub fn to_indent_lines(&self, out_lines : &mut Vec<super::IndentString>)
+	{
+		out_lines.push(super::IndentString::Stay(format!("// {} global slot(s)", self.num_globals)));
+		self.cmd.to_indent_lines(out_lines);
+
+		for (i, func) in self.funcs.iter().enumerate()
+		{
+			out_lines.push(super::IndentString::Stay(format!("// fn #{}", i)));
+			out_lines.push(super::IndentString::Stay(format!("{}", func.prototype)));
+			func.body.to_indent_lines(out_lines);
+		}
+	}
+}
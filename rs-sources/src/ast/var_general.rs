@@ -7,14 +7,21 @@ use std::vec::Vec;
 pub struct VarDecl
 {
 	pub var_type : super::data_type::DataType,
+	pub label : super::sec_label::SecLabel,
 	pub name : String,
 }
 
 impl VarDecl
 {
+	/// Declare a variable with `SecLabel::Public` label.
 	pub fn new(var_type : super::data_type::DataType, name : String) -> VarDecl
 	{
-		VarDecl{var_type : var_type, name : name}
+		VarDecl{var_type : var_type, label : super::sec_label::SecLabel::Public, name : name}
+	}
+
+	pub fn new_labeled(var_type : super::data_type::DataType, label : super::sec_label::SecLabel, name : String) -> VarDecl
+	{
+		VarDecl{var_type : var_type, label : label, name : name}
 	}
 }
 
@@ -26,12 +33,13 @@ impl super::Serializible for VarDecl
 	///
 	/// # VarDecl layout
 	/// ```
-	///            | Datatype - 1 Byte | string - 10+ bytes |
+	///            | Datatype - 1 Byte | SecLabel - 1 Byte | string - 10+ bytes |
 	/// ```
 	///
 	fn to_bytes(&self) -> Result<Vec<u8>, String>
 	{
 		let mut res = self.var_type.to_bytes()?;
+		res.append(&mut self.label.to_bytes()?);
 		res.append(&mut super::primit_serialize::string_to_bytes(&self.name));
 
 		Result::Ok(res)
@@ -43,9 +51,10 @@ impl super::Deserializible for VarDecl
 	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], VarDecl), String>
 	{
 		let (bytes_left_1, parsed_var_type) = super::data_type::DataType::from_bytes(bytes)?;
-		let (bytes_left_2, parsed_name) = super::primit_serialize::string_from_bytes(bytes_left_1)?;
+		let (bytes_left_2, parsed_label) = super::sec_label::SecLabel::from_bytes(bytes_left_1)?;
+		let (bytes_left_3, parsed_name) = super::primit_serialize::string_from_bytes(bytes_left_2)?;
 
-		Result::Ok((bytes_left_2, VarDecl {var_type : parsed_var_type, name : parsed_name}))
+		Result::Ok((bytes_left_3, VarDecl {var_type : parsed_var_type, label : parsed_label, name : parsed_name}))
 	}
 }
 
@@ -53,7 +62,11 @@ impl fmt::Display for VarDecl
 {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
 	{
-		write!(f, "{} : {}", self.name, self.var_type)
+		match self.label
+		{
+			super::sec_label::SecLabel::Public => write!(f, "{} : {}", self.name, self.var_type),
+			super::sec_label::SecLabel::Secret => write!(f, "{} : {} {}", self.name, self.label, self.var_type),
+		}
 	}
 }
 
@@ -0,0 +1,58 @@
+//! Mock quotes, for testing attestation without SGX hardware.
+//!
+//! A mock quote is its magic bytes, an SGX report body, and a tag over both,
+//! `SHA256(MOCK_QUOTE_KEY | magic | report body)`. The key is public, so a
+//! mock quote proves nothing; it only exercises the same checks as a quote.
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::QuoteBody;
+use super::MOCK_QUOTE_MAGIC;
+use super::REPORT_BODY_SIZE;
+use super::super::host_verify;
+
+/// Key of the tag of mock quotes.
+pub const MOCK_QUOTE_KEY : [u8; 32] = *b"enclave_verifier mock quote key.";
+
+/// Size of a mock quote, in bytes.
+pub const MOCK_QUOTE_SIZE : usize = 8 + REPORT_BODY_SIZE + 32;
+
+fn mock_tag(signed : &[u8]) -> [u8; 32]
+{
+	let mut tagged : Vec<u8> = MOCK_QUOTE_KEY.to_vec();
+	tagged.extend_from_slice(signed);
+
+	host_verify::sha256(&tagged)
+}
+
+/// Mock quote of a report body with the given fields.
+///
+pub fn mock_quote(body : &QuoteBody) -> Vec<u8>
+{
+	let mut res : Vec<u8> = MOCK_QUOTE_MAGIC.to_vec();
+	res.append(&mut body.to_report_body());
+
+	let tag = mock_tag(&res);
+	res.extend_from_slice(&tag);
+
+	res
+}
+
+/// Check the tag of a mock quote, and return its report body.
+///
+pub fn verify_mock_quote(bytes : &[u8]) -> Result<QuoteBody, String>
+{
+	if bytes.len() != MOCK_QUOTE_SIZE || bytes[0..8] != MOCK_QUOTE_MAGIC
+	{
+		return Result::Err(format!("{}", "Malformed mock quote."));
+	}
+
+	let signed_len = MOCK_QUOTE_SIZE - 32;
+	if mock_tag(&bytes[0..signed_len])[..] != bytes[signed_len..]
+	{
+		return Result::Err(format!("{}", "The tag of the mock quote doesn't match."));
+	}
+
+	QuoteBody::from_report_body(&bytes[8..signed_len])
+}
@@ -0,0 +1,288 @@
+//! Evidence binding the public key of an enclave to the enclave.
+//!
+//! The enclave asks for a report, or a quote, whose report data commits to
+//! the hash of its public key, i.e. the report data is `SHA256(pub_key)`
+//! followed by 32 zero bytes. The evidence returned to the host is the public
+//! key, and the report or quote.
+//!
+//! This module encodes and decodes evidence, and extracts the report body
+//! from it. The binding is checked by `host_verify::verify_evidence` on hosts,
+//! and by `check_evidence` in enclaves.
+
+use std::fmt;
+use std::vec::Vec;
+use std::string::String;
+
+use super::ast;
+use super::enclave::EnclaveBackend;
+
+#[cfg(feature = "host_verifier")]
+pub mod mock;
+
+/// Magic bytes evidence starts with.
+pub const EVIDENCE_MAGIC : [u8; 4] = *b"VEVD";
+
+/// Size of the report data, in bytes.
+pub const REPORT_DATA_SIZE : usize = 64;
+
+/// Size of an SGX report body, `sgx_report_body_t`, in bytes.
+pub const REPORT_BODY_SIZE : usize = 384;
+
+/// Size of an SGX report, `sgx_report_t`, i.e. the report body, the key ID
+/// and the MAC.
+pub const SGX_REPORT_SIZE : usize = REPORT_BODY_SIZE + 32 + 16;
+
+/// Size of encoded evidence holding an SGX report.
+pub const SGX_REPORT_EVIDENCE_SIZE : usize = EVIDENCE_HEADER_SIZE + SGX_REPORT_SIZE;
+
+/// Size of the fields of encoded evidence before the quote.
+const EVIDENCE_HEADER_SIZE : usize = 4 + 1 + 64 + 4;
+
+/// Offset of the report body in an SGX quote, `sgx_quote_t`.
+const QUOTE_REPORT_BODY_OFFSET : usize = 48;
+
+/// Magic bytes a mock quote starts with; the report body follows them.
+pub const MOCK_QUOTE_MAGIC : [u8; 8] = *b"MOCKQUOT";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuoteKind
+{
+	/// SGX report, `sgx_report_t`, targeted at the enclave of the target
+	/// info the host gave, usually the quoting enclave.
+	SgxReport,
+	/// SGX quote, `sgx_quote_t`, signed by the quoting enclave.
+	SgxQuote,
+	/// Quote of `attestation::mock`, for testing without SGX.
+	Mock,
+}
+
+impl QuoteKind
+{
+	fn to_byte(&self) -> u8
+	{
+		match self
+		{
+			QuoteKind::SgxReport => 1u8,
+			QuoteKind::SgxQuote  => 2u8,
+			QuoteKind::Mock      => 3u8,
+		}
+	}
+
+	fn from_byte(b : u8) -> Result<QuoteKind, String>
+	{
+		match b
+		{
+			1u8 => Result::Ok(QuoteKind::SgxReport),
+			2u8 => Result::Ok(QuoteKind::SgxQuote),
+			3u8 => Result::Ok(QuoteKind::Mock),
+			_   => Result::Err(format!("Unknown quote kind {} in evidence.", b)),
+		}
+	}
+}
+
+impl fmt::Display for QuoteKind
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			QuoteKind::SgxReport => write!(f, "SGX report"),
+			QuoteKind::SgxQuote  => write!(f, "SGX quote"),
+			QuoteKind::Mock      => write!(f, "mock quote"),
+		}
+	}
+}
+
+/// Fields of a report body the verifiers look at.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteBody
+{
+	pub mr_enclave  : [u8; 32],
+	pub mr_signer   : [u8; 32],
+	pub report_data : [u8; REPORT_DATA_SIZE],
+}
+
+impl QuoteBody
+{
+	/// Parse an SGX report body, `sgx_report_body_t`.
+	///
+	pub fn from_report_body(bytes : &[u8]) -> Result<QuoteBody, String>
+	{
+		if bytes.len() < REPORT_BODY_SIZE
+		{
+			return Result::Err(format!("{}", "Failed to parse report body. Bytes are shorter than expected."));
+		}
+
+		let mut res = QuoteBody { mr_enclave : [0; 32], mr_signer : [0; 32], report_data : [0; REPORT_DATA_SIZE] };
+
+		res.mr_enclave.copy_from_slice(&bytes[64..96]);
+		res.mr_signer.copy_from_slice(&bytes[128..160]);
+		res.report_data.copy_from_slice(&bytes[320..384]);
+
+		Result::Ok(res)
+	}
+
+	/// Report body with the given fields, and zeros elsewhere.
+	///
+	pub fn to_report_body(&self) -> Vec<u8>
+	{
+		let mut res : Vec<u8> = vec![0u8; REPORT_BODY_SIZE];
+
+		res[64..96].copy_from_slice(&self.mr_enclave);
+		res[128..160].copy_from_slice(&self.mr_signer);
+		res[320..384].copy_from_slice(&self.report_data);
+
+		res
+	}
+}
+
+/// The report data committing to the hash of a public key.
+///
+pub fn bind_report_data(pkey_hash : &[u8; 32]) -> [u8; REPORT_DATA_SIZE]
+{
+	let mut res : [u8; REPORT_DATA_SIZE] = [0; REPORT_DATA_SIZE];
+	res[0..32].copy_from_slice(pkey_hash);
+
+	res
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evidence
+{
+	/// Public key of the enclave, in the layout of SGX.
+	pub pub_key    : [u8; 64],
+	pub quote_kind : QuoteKind,
+	pub quote      : Vec<u8>,
+}
+
+impl Evidence
+{
+	/// The report body in the quote; the quote itself isn't checked.
+	///
+	pub fn quote_body(&self) -> Result<QuoteBody, String>
+	{
+		match self.quote_kind
+		{
+			QuoteKind::SgxReport => QuoteBody::from_report_body(&self.quote[..]),
+			QuoteKind::SgxQuote  =>
+			{
+				if self.quote.len() < QUOTE_REPORT_BODY_OFFSET
+				{
+					return Result::Err(format!("{}", "Failed to parse quote. Bytes are shorter than expected."));
+				}
+				QuoteBody::from_report_body(&self.quote[QUOTE_REPORT_BODY_OFFSET..])
+			},
+			QuoteKind::Mock      =>
+			{
+				if self.quote.len() < MOCK_QUOTE_MAGIC.len()
+				{
+					return Result::Err(format!("{}", "Failed to parse mock quote. Bytes are shorter than expected."));
+				}
+				QuoteBody::from_report_body(&self.quote[MOCK_QUOTE_MAGIC.len()..])
+			},
+		}
+	}
+}
+
+impl ast::Serializible for Evidence
+{
+	/// # Evidence layout
+	/// ```text
+	/// | magic "VEVD" - 4 Bytes | quote_kind - 1 Byte | pub_key - 64 Bytes | quote length - 4 Bytes | quote |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = Vec::with_capacity(EVIDENCE_HEADER_SIZE + self.quote.len());
+
+		res.extend_from_slice(&EVIDENCE_MAGIC);
+		res.push(self.quote_kind.to_byte());
+		res.extend_from_slice(&self.pub_key);
+		res.extend_from_slice(&(self.quote.len() as u32).to_le_bytes());
+		res.extend_from_slice(&self.quote);
+
+		Result::Ok(res)
+	}
+}
+
+impl ast::Deserializible for Evidence
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], Evidence), String>
+	{
+		if bytes.len() < EVIDENCE_HEADER_SIZE
+		{
+			return Result::Err(format!("{}", "Failed to parse evidence. Bytes are shorter than expected."));
+		}
+
+		if bytes[0..4] != EVIDENCE_MAGIC
+		{
+			return Result::Err(format!("{}", "Failed to parse evidence. Magic bytes mismatch."));
+		}
+
+		let quote_kind = QuoteKind::from_byte(bytes[4])?;
+
+		let mut pub_key : [u8; 64] = [0; 64];
+		pub_key.copy_from_slice(&bytes[5..69]);
+
+		let mut len_bytes : [u8; 4] = [0; 4];
+		len_bytes.copy_from_slice(&bytes[69..73]);
+		let quote_len = u32::from_le_bytes(len_bytes) as usize;
+
+		if bytes.len() - EVIDENCE_HEADER_SIZE < quote_len
+		{
+			return Result::Err(format!("{}", "Failed to parse evidence. The quote is shorter than expected."));
+		}
+
+		let quote_end = EVIDENCE_HEADER_SIZE + quote_len;
+		let evidence = Evidence
+		{
+			pub_key    : pub_key,
+			quote_kind : quote_kind,
+			quote      : bytes[EVIDENCE_HEADER_SIZE..quote_end].to_vec(),
+		};
+
+		Result::Ok((&bytes[quote_end..], evidence))
+	}
+}
+
+impl fmt::Display for Evidence
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "evidence with a {} of {} byte(s)", self.quote_kind, self.quote.len())
+	}
+}
+
+/// Evidence for the public key of the enclave of the backend.
+///
+pub fn gen_evidence<B : EnclaveBackend>(backend : &B) -> Result<Evidence, String>
+{
+	let pub_key = backend.public_key();
+	let report_data = bind_report_data(&backend.sha256(&pub_key)?);
+
+	let (quote_kind, quote) = backend.attest(&report_data)?;
+
+	Result::Ok(Evidence
+	{
+		pub_key    : pub_key,
+		quote_kind : quote_kind,
+		quote      : quote,
+	})
+}
+
+/// Check evidence of another enclave with the backend, i.e. its quote, and
+/// that the quote commits to its public key; returns the report body.
+///
+pub fn check_evidence<B : EnclaveBackend>(backend : &B, evidence : &Evidence) -> Result<QuoteBody, String>
+{
+	let body = backend.verify_quote(evidence.quote_kind, &evidence.quote[..])?;
+
+	if body.report_data != bind_report_data(&backend.sha256(&evidence.pub_key)?)
+	{
+		return Result::Err(format!("The {} doesn't commit to the public key of the evidence.", evidence.quote_kind));
+	}
+
+	Result::Ok(body)
+}
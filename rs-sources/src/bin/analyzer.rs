@@ -0,0 +1,86 @@
+use enclave_verifier::ast;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::analyzer;
+
+use std::env;
+
+/// Read a file named `<prog_name>.<suffix>`.
+///
+/// Returns `Option::None` if the file doesn't exist.
+///
+fn read_bytes_from_file(prog_name : &str, suffix : &str) -> Option<Vec<u8>>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", prog_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(file_path)
+	{
+		Err(_)   => return None,
+		Ok(file) => file,
+	};
+
+	let mut bytes : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut bytes)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	println!("File {} read {} bytes total.", file_path.display(), bytes.len());
+
+	Some(bytes)
+}
+
+fn main()
+{
+	let args : Vec<String> = env::args().collect();
+	if args.len() != 2 {
+		panic!("Incorrect number of arguments provided.")
+	}
+
+	// Read AST from bytecode file.
+	let prog_name = &args[1];
+	let prog_bytes = match read_bytes_from_file(prog_name, "impc")
+	{
+		Some(v) => v,
+		None    => panic!("couldn't open {}.impc", prog_name),
+	};
+	let (_bytes_left, prog) = match ast::cmd::Cmd::from_bytes(&prog_bytes[..])
+	{
+		Ok(v) => v,
+		Err(why) => panic!("Couldn't construct AST from byte code for {}. {}", prog_name, why)
+	};
+	let mut prog_lines : Vec<ast::IndentString> = vec![];
+	prog.to_indent_lines(&mut prog_lines);
+	println!("Example program {}:\n{}\n", prog_name, ast::indent_lines_to_string(&prog_lines, '\t'));
+
+	// Read the ranges of entry's parameters, if there are any.
+	let ranges = match read_bytes_from_file(prog_name, "range")
+	{
+		Some(v) => match analyzer::interval::ParamRanges::from_bytes(&v[..])
+		{
+			Ok((_, r)) => r,
+			Err(why)   => panic!("Couldn't read parameter ranges for {}. {}", prog_name, why),
+		},
+		None    => analyzer::interval::ParamRanges::new(vec![]),
+	};
+
+	// Run interval analysis on this AST.
+	match analyzer::abs_interp::analyze_program(&prog, &ranges)
+	{
+		Ok(report) =>
+		{
+			println!("{}", report);
+			if !report.is_safe()
+			{
+				std::process::exit(1);
+			}
+		},
+		Err(why)   => panic!("Failed interval analysis:\n{}", why),
+	}
+}
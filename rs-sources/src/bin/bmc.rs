@@ -0,0 +1,112 @@
+use enclave_verifier::ast;
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::analyzer;
+
+use std::env;
+
+/// Default number of times a loop is unrolled, or a function is inlined.
+const DEFAULT_UNWIND : usize = 8;
+
+fn read_byte_code_from_file(prog_name : &str) -> Vec<u8>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", prog_name, "impc");
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(file_path)
+	{
+		Err(why) => panic!("couldn't open {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	let mut byte_code : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut byte_code)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	println!("Bytecode file read {} bytes total for program {}.", byte_code.len(), prog_name);
+
+	byte_code
+}
+
+fn write_bytes_to_file(bytes : &[u8], file_name : &str, suffix : &str)
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", file_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::create(file_path)
+	{
+		Err(why) => panic!("couldn't create {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	match file.write_all(bytes)
+	{
+		Err(why) => panic!("couldn't write to {}: {}", file_path.display(), why),
+		Ok(_) => println!("successfully wrote to {}", file_path.display()),
+	}
+}
+
+fn main()
+{
+	let args : Vec<String> = env::args().collect();
+	if args.len() != 2 && args.len() != 3
+	{
+		panic!("Incorrect number of arguments provided.")
+	}
+
+	let prog_name = &args[1];
+	let unwind = match args.get(2)
+	{
+		Some(s) => match s.parse::<usize>()
+		{
+			Ok(v)  => v,
+			Err(_) => panic!("Invalid unwinding bound {}.", s),
+		},
+		None    => DEFAULT_UNWIND,
+	};
+
+	let prog_bytes = read_byte_code_from_file(prog_name);
+	let (_bytes_left, prog) = match ast::cmd::Cmd::from_bytes(&prog_bytes[..])
+	{
+		Ok(v) => v,
+		Err(why) => panic!("Couldn't construct AST from byte code for {}. {}", prog_name, why)
+	};
+	let mut prog_lines : Vec<ast::IndentString> = vec![];
+	prog.to_indent_lines(&mut prog_lines);
+	println!("Example program {}:\n{}\n", prog_name, ast::indent_lines_to_string(&prog_lines, '\t'));
+
+	let config = analyzer::bmc::BmcConfig::new(unwind);
+	let report = match analyzer::bmc::check_program(&prog, &config)
+	{
+		Ok(r)    => r,
+		Err(why) => panic!("Failed bounded model checking:\n{}", why),
+	};
+	println!("{}\n", report);
+
+	// Export the counterexample of the first failure, ready for the interpreter.
+	if let Some((check, param_list)) = report.first_violation()
+	{
+		let params_str : Vec<String> = param_list.iter().map(|e| e.to_string()).collect();
+		println!("First failure: {}, with entry({}).", check.what, params_str.join(", "));
+
+		match param_list.to_bytes()
+		{
+			Ok(bytes) => write_bytes_to_file(&bytes, &format!("{}_cex", prog_name), "param"),
+			Err(why)  => panic!("Couldn't generate byte code for the counterexample. {}", why),
+		}
+
+		std::process::exit(1);
+	}
+}
@@ -1,39 +1,8 @@
 extern crate enclave_verifier;
 
 use enclave_verifier::ast::*;
-
-/// Example program 1 - function 1
-fn is_divisible(x : i32, factor : i32) -> bool
-{
-	return (x % factor) == 0;
-}
-
-/// Example program 1 - function 2
-fn is_prime(x : i32) -> bool
-{
-	if x <= 1
-	{
-		return false;
-	}
-
-	let mut is_prime : bool;
-	is_prime = true;
-
-	let mut test_num : i32;
-	test_num = x / 2;
-
-	while is_prime && (test_num > 1)
-	{
-		if is_divisible(x, test_num)
-		{
-			is_prime = false;
-		}
-
-		test_num = test_num - 1;
-	}
-
-	return is_prime;
-}
+use enclave_verifier::analyzer::interval::{Interval, ParamRanges};
+use enclave_verifier::type_checker::type_checker;
 
 fn construct_example_prog_glvar_and_returnv() -> cmd::Cmd
 {
@@ -250,6 +219,9 @@ fn construct_example_prog_overloading() -> cmd::Cmd
 	 *   Int32   o1 = overloaded(t0, t1);
 	 *   Bool    o2 = overloaded(t0, t2);
 	 *   Float32 c3 = overloaded(t2, t1);
+	 *
+	 *   Float32 f0 = 1.5;
+	 *   Float32 o4 = overloaded(f0, t1); // t1 promoted, calls overloaded(Float32, Float32)
 	 * };
 	 *
 	 * fn overloaded(x : Int32, y : Int32) -> Int32 {
@@ -270,6 +242,10 @@ fn construct_example_prog_overloading() -> cmd::Cmd
 	 *   } else {
 	 *     return y+1;
 	 *   }
+	 * };
+	 *
+	 * fn overloaded(x : Float32, y : Float32) -> Float32 {
+	 *   return x*y
 	 * }
 	 */
 
@@ -315,7 +291,20 @@ fn construct_example_prog_overloading() -> cmd::Cmd
 		}).to_exp()
 	);
 
-	let seq_entry = seq(t0_dec, seq(t0_asg, seq(t1_dec, seq(t1_asg, seq(t2_dec, seq(t2_asg, seq(o1_dec, seq(o1_asg, seq(o2_dec, seq(o2_asg, seq(o3_dec, o3_asg)))))))))));
+	// Float32 f0 = 1.5;
+	let f0_dec = var_dc(var_general::VarDecl::new(data_type::DataType::Float32, "f0".to_string()));
+	let f0_asg = assign(var_general::VarRef::from_str("f0"), 1.5f32.to_aexp().to_exp());
+
+	// Float32 o4 = overloaded(f0, t1);
+	let o4_dec = var_dc(var_general::VarDecl::new(data_type::DataType::Float32, "o4".to_string()));
+	let o4_asg = assign(var_general::VarRef::from_str("o4"),
+		(aexp::Aexp::FnCall{
+			fc : func_general::FnCall::new("overloaded".to_string(), vec!["f0".to_aexp().to_exp(), "t1".to_aexp().to_exp()])
+		}).to_exp()
+	);
+
+	let seq_entry = seq(t0_dec, seq(t0_asg, seq(t1_dec, seq(t1_asg, seq(t2_dec, seq(t2_asg, seq(o1_dec, seq(o1_asg, seq(o2_dec, seq(o2_asg, seq(o3_dec, seq(o3_asg,
+		seq(f0_dec, seq(f0_asg, seq(o4_dec, o4_asg)))))))))))))));
 
 	let entry_decl = fn_dc(fn_prototype, seq_entry);
 
@@ -373,8 +362,23 @@ fn construct_example_prog_overloading() -> cmd::Cmd
 
 	let overloaded3_dec = fn_dc(fn_prototype_o3, if_el_o3);
 
+
+	// overloaded(Float32, Float32)
+	// arg list: x: Float32, y: Float32
+	let var_decl_list_o4 = vec![
+		var_general::VarDecl::new(data_type::DataType::Float32, "x".to_string()),
+		var_general::VarDecl::new(data_type::DataType::Float32, "y".to_string()),
+	];
+
+	// fn overloaded(x: Float32, y: Float32) -> Float32
+	let fn_prototype_o4 = func_general::FnProtoType::new(data_type::DataType::Float32, "overloaded".to_string(), var_decl_list_o4);
+
+	// return x*y
+	let ret_o4 = ret(Some(("x".to_aexp() * "y".to_aexp()).to_exp()));
+	let overloaded4_dec = fn_dc(fn_prototype_o4, ret_o4);
+
     // Full program
-    seq(entry_decl, seq(overloaded1_dec, seq(overloaded2_dec, overloaded3_dec)))
+    seq(entry_decl, seq(overloaded1_dec, seq(overloaded2_dec, seq(overloaded3_dec, overloaded4_dec))))
 }
 
 
@@ -522,6 +526,45 @@ fn construct_example_prog_1() -> cmd::Cmd
 	prog
 }
 
+/// Test functions of example program 1, where `entry` is is_prime.
+pub fn construct_example_prog_1_tests() -> Vec<test_fn::TestFn>
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let is_divisible = |x : i32, factor : i32| bexp::Bexp::FnCall{
+		fc : func_general::FnCall::new("is_divisible".to_string(), vec![x.to_aexp().to_exp(), factor.to_aexp().to_exp()])};
+	let is_prime = |x : i32| bexp::Bexp::FnCall{
+		fc : func_general::FnCall::new("entry".to_string(), vec![x.to_aexp().to_exp()])};
+
+	vec![
+		//#[test] fn test_is_divisible() { assert(is_divisible(222, 111)); assert(!is_divisible(211, 2)); }
+		test_fn::TestFn::new("test_is_divisible".to_string(),
+			seq(assert(is_divisible(222, 111)), assert(is_divisible(211, 2).not()))),
+		//#[test] fn test_small_numbers() { assert(!is_prime(1)); assert(is_prime(2)); assert(!is_prime(4)); }
+		test_fn::TestFn::new("test_small_numbers".to_string(),
+			seq(assert(is_prime(1).not()), seq(assert(is_prime(2)), assert(is_prime(4).not())))),
+		//#[test] fn test_211() { assert(is_prime(211)); }
+		test_fn::TestFn::new("test_211".to_string(), assert(is_prime(211))),
+		//#[test] fn test_222() { assert(!is_prime(222)); }
+		test_fn::TestFn::new("test_222".to_string(), assert(is_prime(222).not())),
+	]
+}
+
+/// A program followed by its test section.
+struct ProgWithTests<'a>(&'a cmd::Cmd, &'a Vec<test_fn::TestFn>);
+
+impl<'a> Serializible for ProgWithTests<'a>
+{
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res = self.0.to_bytes()?;
+		res.append(&mut self.1.to_bytes()?);
+		Result::Ok(res)
+	}
+}
+
 fn scope_test_some_func(a : i32) -> i32
 {
 	let b : i32 = 10;
@@ -694,6 +737,208 @@ fn construct_example_prog_scope_test() -> cmd::Cmd
 	prog
 }
 
+fn construct_example_prog_sec_label() -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	/* Program:
+	 * fn entry(x : secret Int32, y : Int32) -> secret Int32 {
+	 *   secret Int32 diff;
+	 *   diff = y - x;
+	 *   if (x > y) {
+	 *     diff = x - y;
+	 *   } else {
+	 *     skip;
+	 *   }
+	 *   return diff;
+	 * } */
+
+	// arg list: x : secret Int32, y : Int32
+	let var_decl_list = vec![
+		var_general::VarDecl::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "x".to_string()),
+		var_general::VarDecl::new(data_type::DataType::Int32, "y".to_string()),
+	];
+
+	//fn entry(x : secret Int32, y : Int32) -> secret Int32
+	let fn_prototype = func_general::FnProtoType::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "entry".to_string(), var_decl_list);
+
+	// secret Int32 diff;
+	let diff_dec = var_dc(var_general::VarDecl::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "diff".to_string()));
+
+	// diff = y - x;
+	let diff_asg = assign(var_general::VarRef::from_str("diff"), ("y".to_aexp() - "x".to_aexp()).to_exp());
+
+	// if (x > y) { diff = x - y; } else { skip; }
+	let asg_t = assign(var_general::VarRef::from_str("diff"), ("x".to_aexp() - "y".to_aexp()).to_exp());
+	let if_el_cmd = if_el("x".to_aexp().gt("y".to_aexp()), asg_t, skip());
+
+	// return diff;
+	let ret_diff = ret(Some("diff".to_aexp().to_exp()));
+
+	fn_dc(fn_prototype, seq(diff_dec, seq(diff_asg, seq(if_el_cmd, ret_diff))))
+}
+
+fn construct_example_prog_oblivious() -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	/* Program:
+	 * fn entry(x : secret Int32) -> secret Int32 {
+	 *   secret Int32 r;
+	 *   r = 0;
+	 *   secret Int32 i;
+	 *   i = 0;
+	 *   while (i < x) bound 16 {
+	 *     r = r + i;
+	 *     i = i + 1;
+	 *   }
+	 *   if (r > 20) {
+	 *     return 100;
+	 *   } else {
+	 *     skip;
+	 *   }
+	 *   return r;
+	 * } */
+
+	// arg list: x : secret Int32
+	let var_decl_list = vec![
+		var_general::VarDecl::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "x".to_string()),
+	];
+
+	//fn entry(x : secret Int32) -> secret Int32
+	let fn_prototype = func_general::FnProtoType::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "entry".to_string(), var_decl_list);
+
+	// secret Int32 r; r = 0;
+	let r_dec = var_dc(var_general::VarDecl::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "r".to_string()));
+	let r_asg = assign(var_general::VarRef::from_str("r"), 0i32.to_aexp().to_exp());
+
+	// secret Int32 i; i = 0;
+	let i_dec = var_dc(var_general::VarDecl::new_labeled(data_type::DataType::Int32, sec_label::SecLabel::Secret, "i".to_string()));
+	let i_asg = assign(var_general::VarRef::from_str("i"), 0i32.to_aexp().to_exp());
+
+	// while (i < x) bound 16 { r = r + i; i = i + 1; }
+	let r_add = assign(var_general::VarRef::from_str("r"), ("r".to_aexp() + "i".to_aexp()).to_exp());
+	let i_inc = assign(var_general::VarRef::from_str("i"), ("i".to_aexp() + 1i32.to_aexp()).to_exp());
+	let while_cmd = wh_lp_bounded("i".to_aexp().lt("x".to_aexp()), seq(r_add, i_inc), 16);
+
+	// if (r > 20) { return 100; } else { skip; }
+	let if_el_cmd = if_el("r".to_aexp().gt(20i32.to_aexp()), ret(Some(100i32.to_aexp().to_exp())), skip());
+
+	// return r;
+	let ret_r = ret(Some("r".to_aexp().to_exp()));
+
+	fn_dc(fn_prototype, seq(r_dec, seq(r_asg, seq(i_dec, seq(i_asg, seq(while_cmd, seq(if_el_cmd, ret_r)))))))
+}
+
+fn construct_example_prog_contract() -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	/* Program:
+	 * fn abs(x : Int32) -> Int32
+	 *   ensures result >= 0
+	 *   ensures (result == x) || (result == 0 - x) {
+	 *   if (x < 0) {
+	 *     return 0 - x;
+	 *   } else {
+	 *     return x;
+	 *   }
+	 * }
+	 * fn twice(n : Int32) -> Int32
+	 *   requires n >= 0
+	 *   ensures result == 2 * n {
+	 *   Int32 r;
+	 *   r = 0;
+	 *   Int32 i;
+	 *   i = 0;
+	 *   while (i < n)
+	 *     invariant i <= n
+	 *     invariant r == 2 * i {
+	 *     r = r + 2;
+	 *     i = i + 1;
+	 *   }
+	 *   return r;
+	 * }
+	 * fn entry(x : Int32) -> Int32
+	 *   requires (x >= -1000) && (x <= 1000)
+	 *   ensures result >= 0 {
+	 *   Int32 a;
+	 *   a = abs(x);
+	 *   return twice(a) % 7;
+	 * } */
+
+	// fn abs(x : Int32) -> Int32 ensures ...
+	let abs_prototype = func_general::FnProtoType::new_with_contract(data_type::DataType::Int32, sec_label::SecLabel::Public, "abs".to_string(),
+		vec![var_general::VarDecl::new(data_type::DataType::Int32, "x".to_string())],
+		vec![],
+		vec![
+			"result".to_aexp().gte(0i32.to_aexp()),
+			"result".to_aexp().aeq("x".to_aexp()).or("result".to_aexp().aeq(0i32.to_aexp() - "x".to_aexp())),
+		]);
+	let abs_if_el = if_el("x".to_aexp().lt(0i32.to_aexp()), ret(Some((0i32.to_aexp() - "x".to_aexp()).to_exp())), ret(Some("x".to_aexp().to_exp())));
+	let abs_fn = fn_dc(abs_prototype, abs_if_el);
+
+	// fn twice(n : Int32) -> Int32 requires n >= 0 ensures result == 2 * n
+	let twice_prototype = func_general::FnProtoType::new_with_contract(data_type::DataType::Int32, sec_label::SecLabel::Public, "twice".to_string(),
+		vec![var_general::VarDecl::new(data_type::DataType::Int32, "n".to_string())],
+		vec!["n".to_aexp().gte(0i32.to_aexp())],
+		vec!["result".to_aexp().aeq(2i32.to_aexp() * "n".to_aexp())]);
+	let r_dec = var_dc(var_general::VarDecl::new(data_type::DataType::Int32, "r".to_string()));
+	let r_asg = assign(var_general::VarRef::from_str("r"), 0i32.to_aexp().to_exp());
+	let i_dec = var_dc(var_general::VarDecl::new(data_type::DataType::Int32, "i".to_string()));
+	let i_asg = assign(var_general::VarRef::from_str("i"), 0i32.to_aexp().to_exp());
+	let r_add = assign(var_general::VarRef::from_str("r"), ("r".to_aexp() + 2i32.to_aexp()).to_exp());
+	let i_inc = assign(var_general::VarRef::from_str("i"), ("i".to_aexp() + 1i32.to_aexp()).to_exp());
+	let while_cmd = wh_lp_inv("i".to_aexp().lt("n".to_aexp()), seq(r_add, i_inc), None,
+		vec!["i".to_aexp().lte("n".to_aexp()), "r".to_aexp().aeq(2i32.to_aexp() * "i".to_aexp())]);
+	let ret_r = ret(Some("r".to_aexp().to_exp()));
+	let twice_fn = fn_dc(twice_prototype, seq(r_dec, seq(r_asg, seq(i_dec, seq(i_asg, seq(while_cmd, ret_r))))));
+
+	// fn entry(x : Int32) -> Int32 requires (x >= -1000) && (x <= 1000) ensures result >= 0
+	let entry_prototype = func_general::FnProtoType::new_with_contract(data_type::DataType::Int32, sec_label::SecLabel::Public, "entry".to_string(),
+		vec![var_general::VarDecl::new(data_type::DataType::Int32, "x".to_string())],
+		vec!["x".to_aexp().gte((-1000i32).to_aexp()).and("x".to_aexp().lte(1000i32.to_aexp()))],
+		vec!["result".to_aexp().gte(0i32.to_aexp())]);
+	let a_dec = var_dc(var_general::VarDecl::new(data_type::DataType::Int32, "a".to_string()));
+	let a_asg = assign(var_general::VarRef::from_str("a"),
+		(aexp::Aexp::FnCall{
+			fc : func_general::FnCall::new("abs".to_string(), vec!["x".to_aexp().to_exp()])
+		}).to_exp());
+	let twice_call = aexp::Aexp::FnCall{
+		fc : func_general::FnCall::new("twice".to_string(), vec!["a".to_aexp().to_exp()])
+	};
+	let ret_mod = ret(Some((twice_call % 7i32.to_aexp()).to_exp()));
+	let entry_fn = fn_dc(entry_prototype, seq(a_dec, seq(a_asg, ret_mod)));
+
+	seq(abs_fn, seq(twice_fn, entry_fn))
+}
+
+/// The example programs, by the names their byte code is written under;
+/// the differential tests (tests/differential.rs) run over them.
+#[allow(dead_code)]
+pub fn example_progs() -> Vec<(&'static str, cmd::Cmd)>
+{
+	vec![
+		("is_prime",               construct_example_prog_1()),
+		("test_bexps",             construct_example_prog_bexps()),
+		("test_ifel",              construct_example_prog_ifel()),
+		("test_overloading",       construct_example_prog_overloading()),
+		("test_glvar_and_returnv", construct_example_prog_glvar_and_returnv()),
+		("scope_test",             construct_example_prog_scope_test()),
+		("test_fcall_1",           construct_example_prog_fcall_1()),
+		("test_fcall_2",           construct_example_prog_fcall_2()),
+		("test_sec_label",         construct_example_prog_sec_label()),
+		("test_oblivious",         construct_example_prog_oblivious()),
+		("test_contract",          construct_example_prog_contract()),
+	]
+}
+
 fn write_byte_code_to_file<T : Serializible>(code : &T, prog_name : &str, suffix : &str)
 {
 	use std::fs::File;
@@ -731,9 +976,6 @@ fn main()
 
 	println!("");
 
-	println!("Example function test result is_prime(x = 211): {}\n", is_prime(211i32));
-	println!("Example function test result is_prime(x = 222): {}\n", is_prime(222i32));
-
 	//---------------
 	// Example prog 1: is_prime
 	//---------------
@@ -742,9 +984,14 @@ fn main()
 	let example_prog_1 = construct_example_prog_1();
 	let mut example_prog_1_lines : Vec<IndentString> = vec![];
 	example_prog_1.to_indent_lines(&mut example_prog_1_lines);
+	let example_prog_1_tests = construct_example_prog_1_tests();
+	for test in example_prog_1_tests.iter()
+	{
+		test.to_indent_lines(&mut example_prog_1_lines);
+	}
 	println!("Example program {}:\n{}\n", example_prog_1_name, indent_lines_to_string(&example_prog_1_lines, '\t'));
 
-	write_byte_code_to_file(&example_prog_1, &example_prog_1_name, "impc");
+	write_byte_code_to_file(&ProgWithTests(&example_prog_1, &example_prog_1_tests), &example_prog_1_name, "impc");
 
 	let example_prog_1_param_list_1 = vec![211i32.to_aexp().to_exp()];
 	write_byte_code_to_file(&example_prog_1_param_list_1, &format!("{}_{}", example_prog_1_name, 1), "param");
@@ -755,6 +1002,9 @@ fn main()
 	let example_prog_1_param_list_3 = vec![222f32.to_aexp().to_exp()];
 	write_byte_code_to_file(&example_prog_1_param_list_3, &format!("{}_{}", example_prog_1_name, 3), "param");
 
+	let example_prog_1_ranges = ParamRanges::new(vec![Interval::new(0, 1000000)]);
+	write_byte_code_to_file(&example_prog_1_ranges, &example_prog_1_name, "range");
+
 	println!("===================================================\n");
 
 	//---------------
@@ -796,7 +1046,9 @@ fn main()
 	//---------------
 
 	let example_prog_4_name = "test_overloading";
-	let example_prog_4 = construct_example_prog_overloading();
+	let mut example_prog_4 = construct_example_prog_overloading();
+	// Record the overload each call resolves to in the byte code.
+	type_checker::record_overloads(&mut example_prog_4).expect("Example program 4 failed to type check");
 	let mut example_prog_4_lines : Vec<IndentString> = vec![];
 	example_prog_4.to_indent_lines(&mut example_prog_4_lines);
 	println!("Example program {}:\n{}\n", example_prog_4_name, indent_lines_to_string(&example_prog_4_lines, '\t'));
@@ -842,6 +1094,9 @@ fn main()
 	let example_prog_6_param_list_2 : Vec<exp::Exp> = vec![4i32.to_aexp().to_exp()];
 	write_byte_code_to_file(&example_prog_6_param_list_2, &format!("{}_{}", example_prog_6_name, 2), "param");
 
+	let example_prog_6_ranges = ParamRanges::new(vec![Interval::new(-1000, 1000)]);
+	write_byte_code_to_file(&example_prog_6_ranges, &example_prog_6_name, "range");
+
 	println!("===================================================\n");
 
 	//---------------
@@ -869,4 +1124,56 @@ fn main()
 	println!("Example program {}:\n{}\n", example_prog_8_name, indent_lines_to_string(&example_prog_8_lines, '\t'));
 
 	write_byte_code_to_file(&example_prog_8, &example_prog_8_name, "impc");
+
+	println!("===================================================\n");
+
+	//---------------
+	// Example prog 9: testing security labels
+	//---------------
+
+	let example_prog_9_name = "test_sec_label";
+	let example_prog_9 = construct_example_prog_sec_label();
+	let mut example_prog_9_lines : Vec<IndentString> = vec![];
+	example_prog_9.to_indent_lines(&mut example_prog_9_lines);
+	println!("Example program {}:\n{}\n", example_prog_9_name, indent_lines_to_string(&example_prog_9_lines, '\t'));
+
+	write_byte_code_to_file(&example_prog_9, &example_prog_9_name, "impc");
+
+	let example_prog_9_param_list_1 : Vec<exp::Exp> = vec![3i32.to_aexp().to_exp(), 10i32.to_aexp().to_exp()];
+	write_byte_code_to_file(&example_prog_9_param_list_1, &format!("{}_{}", example_prog_9_name, 1), "param");
+
+	println!("===================================================\n");
+
+	//---------------
+	// Example prog 10: testing oblivious execution
+	//---------------
+
+	let example_prog_10_name = "test_oblivious";
+	let example_prog_10 = construct_example_prog_oblivious();
+	let mut example_prog_10_lines : Vec<IndentString> = vec![];
+	example_prog_10.to_indent_lines(&mut example_prog_10_lines);
+	println!("Example program {}:\n{}\n", example_prog_10_name, indent_lines_to_string(&example_prog_10_lines, '\t'));
+
+	write_byte_code_to_file(&example_prog_10, &example_prog_10_name, "impc");
+
+	let example_prog_10_param_list_1 : Vec<exp::Exp> = vec![5i32.to_aexp().to_exp()];
+	write_byte_code_to_file(&example_prog_10_param_list_1, &format!("{}_{}", example_prog_10_name, 1), "param");
+
+	let example_prog_10_param_list_2 : Vec<exp::Exp> = vec![8i32.to_aexp().to_exp()];
+	write_byte_code_to_file(&example_prog_10_param_list_2, &format!("{}_{}", example_prog_10_name, 2), "param");
+
+	//---------------
+	// Example prog 11: testing contracts and loop invariants
+	//---------------
+
+	let example_prog_11_name = "test_contract";
+	let example_prog_11 = construct_example_prog_contract();
+	let mut example_prog_11_lines : Vec<IndentString> = vec![];
+	example_prog_11.to_indent_lines(&mut example_prog_11_lines);
+	println!("Example program {}:\n{}\n", example_prog_11_name, indent_lines_to_string(&example_prog_11_lines, '\t'));
+
+	write_byte_code_to_file(&example_prog_11, &example_prog_11_name, "impc");
+
+	let example_prog_11_param_list_1 : Vec<exp::Exp> = vec![(-9i32).to_aexp().to_exp()];
+	write_byte_code_to_file(&example_prog_11_param_list_1, &format!("{}_{}", example_prog_11_name, 1), "param");
 }
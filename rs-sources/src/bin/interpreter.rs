@@ -40,7 +40,7 @@ fn main()
 
 	let example_prog_1_name = "is_prime";
 	let example_prog_1_bytes = read_byte_code_from_file(example_prog_1_name);
-	let (_bytes_left_1, example_prog_1) = match cmd::Cmd::from_bytes(&example_prog_1_bytes[..])
+	let (bytes_left_1, example_prog_1) = match cmd::Cmd::from_bytes(&example_prog_1_bytes[..])
 	{
 		Ok(v) => v,
 		Err(why) => panic!("Couldn't construct AST from byte code for {}. {}", example_prog_1_name, why)
@@ -71,6 +71,38 @@ fn main()
 	let param_list_2 = vec![222i32.to_aexp().to_exp()];
 
 	make_entry_call(&prog_inter_1, param_list_2);
+
+	run_unit_tests(&example_prog_1, bytes_left_1);
+
+	let example_prog_2_name = "test_oblivious";
+	let example_prog_2_bytes = read_byte_code_from_file(example_prog_2_name);
+	let (_bytes_left_2, example_prog_2) = match cmd::Cmd::from_bytes(&example_prog_2_bytes[..])
+	{
+		Ok(v) => v,
+		Err(why) => panic!("Couldn't construct AST from byte code for {}. {}", example_prog_2_name, why)
+	};
+
+	let mut prog_inter_2 = interpreter::Program::new();
+
+	gen_prog_states(&mut prog_inter_2, &example_prog_2);
+
+	let typed_prog_2 = match enclave_verifier::type_checker::annotate::annotate(&example_prog_2)
+	{
+		Ok(v) => v,
+		Err(why) => panic!("Couldn't annotate the AST of {}. {}", example_prog_2_name, why)
+	};
+
+	if let Err(why) = interpreter::oblivious::check_program(&example_prog_2)
+	{
+		panic!("Couldn't execute {} obliviously. {}", example_prog_2_name, why)
+	}
+
+	for x in [5i32, 8i32].iter()
+	{
+		make_entry_call(&prog_inter_2, vec![x.to_aexp().to_exp()]);
+		make_oblivious_entry_call(&prog_inter_2, vec![x.to_aexp().to_exp()]);
+		make_typed_entry_call(&typed_prog_2, vec![x.to_aexp().to_exp()]);
+	}
 }
 
 pub fn gen_prog_states(prog : &mut interpreter::Program, prog_cmd : &ast::cmd::Cmd)
@@ -94,6 +126,20 @@ pub fn gen_prog_states(prog : &mut interpreter::Program, prog_cmd : &ast::cmd::C
 	}
 }
 
+pub fn run_unit_tests(prog_cmd : &ast::cmd::Cmd, test_bytes : &[u8])
+{
+	let tests = match ast::test_fn::tests_from_bytes(test_bytes)
+	{
+		Result::Ok(v)    => v,
+		Result::Err(why) => panic!("Couldn't parse the test section. {}", why)
+	};
+
+	for result in interpreter::unit_test::run_tests(prog_cmd, &tests).iter()
+	{
+		println!("{}", result);
+	}
+}
+
 pub fn make_entry_call(prog : &interpreter::Program, param_list : Vec<ast::exp::Exp>)
 {
 	let entry_call = ast::func_general::FnCall::new(format!("entry"), param_list);
@@ -108,3 +154,39 @@ pub fn make_entry_call(prog : &interpreter::Program, param_list : Vec<ast::exp::
 		Result::Err(why)   => panic!("Entry call failed. {}", why),
 	}
 }
+
+pub fn make_oblivious_entry_call(prog : &interpreter::Program, param_list : Vec<ast::exp::Exp>)
+{
+	let entry_call = ast::func_general::FnCall::new(format!("entry"), param_list);
+
+	match interpreter::oblivious::func_call(&prog.func_states, &prog.var_states, &entry_call)
+	{
+		Result::Ok(ok_val) => match ok_val
+		{
+			Option::Some(v) => println!("Oblivious function call {} returned {}", entry_call, v),
+			Option::None    => println!("Oblivious function call {} didn't return any value.", entry_call)
+		},
+		Result::Err(why)   => panic!("Oblivious entry call failed. {}", why),
+	}
+}
+
+pub fn make_typed_entry_call(prog : &ast::typed::TypedProgram, param_list : Vec<ast::exp::Exp>)
+{
+	let entry_call = ast::func_general::FnCall::new(format!("entry"), param_list);
+
+	let mut typed_states = match interpreter::typed::gen_prog_states(prog)
+	{
+		Result::Ok(v)    => v,
+		Result::Err(why) => panic!("{}", why)
+	};
+
+	match interpreter::typed::func_call(prog, &mut typed_states, &entry_call)
+	{
+		Result::Ok(ok_val) => match ok_val
+		{
+			Option::Some(v) => println!("Typed function call {} returned {}", entry_call, v),
+			Option::None    => println!("Typed function call {} didn't return any value.", entry_call)
+		},
+		Result::Err(why)   => panic!("Typed entry call failed. {}", why),
+	}
+}
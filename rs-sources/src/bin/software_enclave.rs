@@ -0,0 +1,441 @@
+use enclave_verifier::analyzer;
+use enclave_verifier::interpreter;
+use enclave_verifier::enclave;
+use enclave_verifier::attestation;
+use enclave_verifier::host_verify;
+use enclave_verifier::certificate;
+use enclave_verifier::type_checker::policy::CheckerPolicy;
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+
+use enclave::EnclaveBackend;
+use enclave::identity;
+use enclave::identity::SealPolicy;
+use enclave::allowlist::CheckerAllowlist;
+use enclave::allowlist::TrustedCheckers;
+use enclave::software::SoftwareBackend;
+use enclave::software::SoftwareSealer;
+use enclave_verifier::sealed_store::SealedStore;
+
+use std::env;
+use std::sync::atomic::AtomicU64;
+
+fn read_bytes_from_file(file_name : &str, suffix : &str) -> Option<Vec<u8>>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", file_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(file_path)
+	{
+		Err(_)   => return None,
+		Ok(file) => file,
+	};
+
+	let mut bytes : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut bytes)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	println!("File {} read {} bytes total.", file_path.display(), bytes.len());
+
+	Some(bytes)
+}
+
+fn write_bytes_to_file(bytes : &[u8], file_name : &str, suffix : &str)
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", file_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::create(file_path)
+	{
+		Err(why) => panic!("couldn't create {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	match file.write_all(bytes)
+	{
+		Err(why) => panic!("couldn't write to {}: {}", file_path.display(), why),
+		Ok(_) => println!("successfully wrote to {}", file_path.display()),
+	}
+}
+
+fn print_b64_pair(what : &str, bytes : &[u8; 64])
+{
+	println!("[Enclave]: {} {}.", what, host_verify::encode_b64_pair(bytes));
+}
+
+/// Directory of the sealed identities of the software enclaves.
+static SEALED_DIR: &str = "sealed";
+
+/// Backend with the identity sealed as `<name>.sealed` in the store, or a new
+/// identity, which is then sealed, with the policy given by the environment
+/// variable `SEAL_POLICY`, and stored; like `init_identity` of the enclaves.
+///
+fn load_backend(name : &str) -> SoftwareBackend
+{
+	let policy = match std::env::var("SEAL_POLICY")
+	{
+		Ok(v)  => match SealPolicy::parse(&v)
+		{
+			Ok(p)    => p,
+			Err(why) => panic!("{}", why),
+		},
+		Err(_) => SealPolicy::MrEnclave,
+	};
+
+	let store = SealedStore::new(SEALED_DIR);
+	let sealer = SoftwareSealer::new(enclave::software::SOFTWARE_SEAL_ROOT_KEY, [0; 32], [0; 32]);
+
+	let sealed_in = match store.load(name)
+	{
+		Ok(v)    => v.unwrap_or_default(),
+		Err(why) => panic!("{}", why),
+	};
+
+	let generate = || Ok(SoftwareBackend::generate().identity());
+	let (encl_identity, sealed_out) = match identity::load_or_generate(&sealer, &sealed_in[..], policy, generate)
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("Couldn't load the identity {}. {}", name, why),
+	};
+
+	if let Err(why) = store.store(name, &sealed_out[..])
+	{
+		panic!("{}", why);
+	}
+
+	match SoftwareBackend::from_identity(&encl_identity)
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("Couldn't load the identity {}. {}", name, why),
+	}
+}
+
+/// The allowlist of type-checkers in `checkers.allow`. Without one, an
+/// allowlist pinning the sealed identity of the software type-checker is
+/// written there, so reports can be verified against it.
+///
+fn load_allowlist() -> Vec<u8>
+{
+	if let Some(v) = read_bytes_from_file("checkers", "allow")
+	{
+		return v;
+	}
+
+	let sealed = match SealedStore::new(SEALED_DIR).load("type_checker")
+	{
+		Ok(Some(v)) => v,
+		Ok(None)    => panic!("No checkers.allow, and no type-checker identity to pin; run check first."),
+		Err(why)    => panic!("{}", why),
+	};
+
+	let pkey = match identity::sealed_public_key(&sealed[..])
+	{
+		Ok((_, v)) => v,
+		Err(why)   => panic!("{}", why),
+	};
+
+	let allowlist = CheckerAllowlist { keys : vec![pkey], mr_enclaves : vec![], evidence : vec![] };
+	let bytes = match allowlist.to_bytes()
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("{}", why),
+	};
+
+	write_bytes_to_file(&bytes[..], "checkers", "allow");
+
+	bytes
+}
+
+/// Write the evidence of the backend, with a mock quote, to `<name>.evidence`.
+///
+fn write_evidence(backend : &SoftwareBackend, name : &str)
+{
+	match attestation::gen_evidence(backend).and_then(|e| e.to_bytes())
+	{
+		Ok(v)    => write_bytes_to_file(&v[..], name, "evidence"),
+		Err(why) => panic!("Couldn't generate evidence. {}", why),
+	}
+}
+
+/// The parameter ranges of the entry of `<prog>`, in `<prog>.range` if any.
+///
+fn read_ranges(prog_name : &str) -> analyzer::interval::ParamRanges
+{
+	match read_bytes_from_file(prog_name, "range")
+	{
+		Some(v) => match analyzer::interval::ParamRanges::from_bytes(&v[..])
+		{
+			Ok((_, r)) => r,
+			Err(why)   => panic!("Couldn't read parameter ranges. {}", why),
+		},
+		None    => analyzer::interval::ParamRanges::new(Vec::new()),
+	}
+}
+
+/// Type check `<prog>.impc`, with the ranges in `<prog>.range` if any, and
+/// write the verified program to `<prog>.vimpc`, and the evidence of the key
+/// of the certificate to `<prog>.evidence`.
+///
+fn check(prog_name : &str, policy : &CheckerPolicy)
+{
+	let prog_bytes = match read_bytes_from_file(prog_name, "impc")
+	{
+		Some(v) => v,
+		None    => panic!("couldn't open {}.impc", prog_name),
+	};
+
+	let ranges = read_ranges(prog_name);
+
+	let backend = load_backend("type_checker");
+	print_b64_pair("Enclave public key", &backend.public_key());
+
+	let output = match enclave::checker::check_program(&backend, &prog_bytes[..], policy, &ranges, &AtomicU64::new(0))
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave]: {}", why);
+			std::process::exit(1);
+		}
+	};
+
+	println!("[Enclave]: Issued {}.", output.cert);
+
+	let vimpc = match certificate::verified_byte_code(&prog_bytes[0..output.bytes_read], &ranges, &output.cert)
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("{}", why),
+	};
+
+	write_bytes_to_file(&vimpc[..], prog_name, "vimpc");
+	write_evidence(&backend, prog_name);
+}
+
+/// Type check `<prog>.simpc`, the program its owner sealed for the
+/// type-checker, with the ranges in `<prog>.range` if any, and write the
+/// verified program, with the bytecode sealed for the interpreter of
+/// `<interpreter>.evidence`, to `<prog>.vimpc`, and the evidence of the key
+/// of the certificate to `<prog>.evidence`.
+///
+fn check_sealed(prog_name : &str, interpreter_name : &str, policy : &CheckerPolicy)
+{
+	let sealed_bytes = match read_bytes_from_file(prog_name, "simpc")
+	{
+		Some(v) => v,
+		None    => panic!("couldn't open {}.simpc", prog_name),
+	};
+
+	let evidence = match read_bytes_from_file(interpreter_name, "evidence").map(|v| attestation::Evidence::from_bytes(&v[..]).map(|(_, e)| e))
+	{
+		Some(Ok(v))    => v,
+		Some(Err(why)) => panic!("Couldn't read the evidence of the interpreter. {}", why),
+		None           => panic!("couldn't open {}.evidence", interpreter_name),
+	};
+
+	let ranges = read_ranges(prog_name);
+
+	let backend = load_backend("type_checker");
+	print_b64_pair("Enclave public key", &backend.public_key());
+
+	let output = match enclave::checker::check_sealed_program(&backend, &sealed_bytes[..], &evidence, policy, &ranges, &AtomicU64::new(0))
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave]: {}", why);
+			std::process::exit(1);
+		}
+	};
+
+	println!("[Enclave]: Issued {}.", output.cert);
+
+	let vimpc = match certificate::verified_byte_code(&output.sealed_byte_code[..], &ranges, &output.cert)
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("{}", why),
+	};
+
+	write_bytes_to_file(&vimpc[..], prog_name, "vimpc");
+	write_evidence(&backend, prog_name);
+}
+
+/// Execute `<prog>.vimpc` with the parameters in `<params>.param`, and the
+/// nonce of the client in `<params>.nonce`, if it is certified by a
+/// type-checker of `checkers.allow`, and write the evidence of the key of the
+/// report to `<params>.evidence`.
+///
+/// The result bytes are written to `<params>.result`, the report body to
+/// `<params>.report`, and its signature to `<params>.sig`. With `sealed`,
+/// the parameters are the ones the client sealed in `<params>.sparam`, and
+/// the result is sealed for the client in `<params>.sresult`.
+///
+fn run(prog_name : &str, params_name : &str, oblivious : bool, sealed : bool)
+{
+	let prog_bytes = match read_bytes_from_file(prog_name, "vimpc")
+	{
+		Some(v) => v,
+		None    => panic!("couldn't open {}.vimpc", prog_name),
+	};
+
+	let param_suffix = if sealed { "sparam" } else { "param" };
+	let param_bytes = match read_bytes_from_file(params_name, param_suffix)
+	{
+		Some(v) => v,
+		None    => panic!("couldn't open {}.{}", params_name, param_suffix),
+	};
+
+	let nonce_bytes = match read_bytes_from_file(params_name, "nonce")
+	{
+		Some(v) => v,
+		None    => panic!("couldn't open {}.nonce; create one with `verify nonce {}`", params_name, params_name),
+	};
+
+	let mut nonce : [u8; enclave::executor::NONCE_SIZE] = [0; enclave::executor::NONCE_SIZE];
+	if nonce_bytes.len() != nonce.len()
+	{
+		panic!("The nonce has {} byte(s) instead of {}.", nonce_bytes.len(), nonce.len());
+	}
+	nonce.copy_from_slice(&nonce_bytes[..]);
+
+	let allowlist_bytes = load_allowlist();
+
+	let backend = load_backend("interpreter");
+	print_b64_pair("Enclave public key", &backend.public_key());
+
+	let checkers = match TrustedCheckers::provision(&backend, &allowlist_bytes[..])
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			std::process::exit(1);
+		}
+	};
+
+	println!("[Enclave]: Provisioned {}.", checkers);
+
+	// The result output is the result bytes, or, sealed, the sealed result.
+	let execution = if sealed
+	{
+		enclave::executor::execute_program_in_session(&backend, &checkers, &prog_bytes[..], &param_bytes[..], oblivious, &nonce, &AtomicU64::new(0))
+			.map(|e| (e.report, e.sealed_result))
+	}
+	else
+	{
+		enclave::executor::execute_program(&backend, &checkers, &prog_bytes[..], &param_bytes[..], oblivious, &nonce, &AtomicU64::new(0))
+			.and_then(|r| interpreter::exp::func_ret_to_bytes(&r.func_ret).map(|b| (r, b)))
+	};
+
+	let (report, result_bytes) = match execution
+	{
+		Ok(v)    => v,
+		Err(why) =>
+		{
+			println!("[Enclave-ERROR]: {}", why);
+			std::process::exit(1);
+		}
+	};
+
+	match (&report.func_ret, sealed)
+	{
+		(_, true)    => println!("[Enclave]: Result sealed for the client."),
+		(Some(v), _) => println!("[Enclave]: Result {}.", v),
+		(None, _)    => println!("[Enclave]: Result none."),
+	}
+
+	println!("[Enclave]: Report number {}.", report.freshness.counter);
+	print_b64_pair("report signature:", &report.signature);
+
+	let report_bytes = match report.body().to_bytes()
+	{
+		Ok(v)    => v,
+		Err(why) => panic!("{}", why),
+	};
+
+	write_bytes_to_file(&result_bytes[..], params_name, if sealed { "sresult" } else { "result" });
+	write_bytes_to_file(&report_bytes[..], params_name, "report");
+	write_bytes_to_file(&report.signature, params_name, "sig");
+	write_evidence(&backend, params_name);
+}
+
+/// The optional policy, a comma separated list of extra checks, e.g.
+/// "interval".
+///
+fn parse_policy(arg : Option<&String>) -> CheckerPolicy
+{
+	match arg
+	{
+		Some(v) => match CheckerPolicy::parse(v)
+		{
+			Ok(p)    => p,
+			Err(why) => panic!("{}", why),
+		},
+		None    => CheckerPolicy::type_check_only(),
+	}
+}
+
+fn main()
+{
+	let args : Vec<String> = env::args().collect();
+
+	match (args.get(1).map(|s| s.as_str()), args.len())
+	{
+		(Some("check"), 3) | (Some("check"), 4) =>
+		{
+			check(&args[2], &parse_policy(args.get(3)));
+		},
+		(Some("check_sealed"), 4) | (Some("check_sealed"), 5) =>
+		{
+			check_sealed(&args[2], &args[3], &parse_policy(args.get(4)));
+		},
+		(Some("run"), n) if (4..=6).contains(&n) =>
+		{
+			let (mut oblivious, mut sealed) = (false, false);
+			for mode in args[4..].iter()
+			{
+				match mode.as_str()
+				{
+					"oblivious" => oblivious = true,
+					"sealed"    => sealed = true,
+					s           => panic!("Unknown mode {}.", s),
+				}
+			}
+
+			run(&args[2], &args[3], oblivious, sealed);
+		},
+		(Some("attest"), 3) =>
+		{
+			// Evidence of the identity of an enclave, e.g. for clients to
+			// seal parameters for the interpreter before its first run.
+			let backend = match args[2].as_str()
+			{
+				"type_checker" | "interpreter" => load_backend(&args[2]),
+				s                              => panic!("Unknown enclave {}.", s),
+			};
+
+			print_b64_pair("Enclave public key", &backend.public_key());
+			write_evidence(&backend, &args[2]);
+		},
+		_ =>
+		{
+			println!("Usage:");
+			println!("  software_enclave check <prog> [policy]");
+			println!("  software_enclave check_sealed <prog> <interpreter> [policy]");
+			println!("  software_enclave run <prog> <params> [oblivious] [sealed]");
+			println!("  software_enclave attest type_checker|interpreter");
+			std::process::exit(2);
+		},
+	}
+}
@@ -0,0 +1,132 @@
+use enclave_verifier::ast;
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::analyzer;
+
+use std::env;
+
+/// Default number of iterations a loop is unrolled on one path.
+const DEFAULT_LOOP_BOUND : usize = 8;
+
+fn read_byte_code_from_file(prog_name : &str) -> Vec<u8>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", prog_name, "impc");
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(file_path)
+	{
+		Err(why) => panic!("couldn't open {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	let mut byte_code : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut byte_code)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	println!("Bytecode file read {} bytes total for program {}.", byte_code.len(), prog_name);
+
+	byte_code
+}
+
+fn write_bytes_to_file(bytes : &[u8], file_name : &str, suffix : &str)
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", file_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::create(file_path)
+	{
+		Err(why) => panic!("couldn't create {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	match file.write_all(bytes)
+	{
+		Err(why) => panic!("couldn't write to {}: {}", file_path.display(), why),
+		Ok(_) => println!("successfully wrote to {}", file_path.display()),
+	}
+}
+
+fn main()
+{
+	let args : Vec<String> = env::args().collect();
+	if args.len() != 2 && args.len() != 3
+	{
+		panic!("Incorrect number of arguments provided.")
+	}
+
+	let prog_name = &args[1];
+	let loop_bound = match args.get(2)
+	{
+		Some(s) => match s.parse::<usize>()
+		{
+			Ok(v)  => v,
+			Err(_) => panic!("Invalid loop bound {}.", s),
+		},
+		None    => DEFAULT_LOOP_BOUND,
+	};
+
+	let prog_bytes = read_byte_code_from_file(prog_name);
+	let (_bytes_left, prog) = match ast::cmd::Cmd::from_bytes(&prog_bytes[..])
+	{
+		Ok(v) => v,
+		Err(why) => panic!("Couldn't construct AST from byte code for {}. {}", prog_name, why)
+	};
+	let mut prog_lines : Vec<ast::IndentString> = vec![];
+	prog.to_indent_lines(&mut prog_lines);
+	println!("Example program {}:\n{}\n", prog_name, ast::indent_lines_to_string(&prog_lines, '\t'));
+
+	// Explore the paths of entry.
+	let config = analyzer::symbolic::SymConfig::new(loop_bound);
+	let report = match analyzer::symbolic::sym_exec_program(&prog, &config)
+	{
+		Ok(r)    => r,
+		Err(why) => panic!("Failed symbolic execution:\n{}", why),
+	};
+	println!("{}\n", report);
+
+	// Export the path conditions, and a parameter list for every feasible path.
+	for (i, path) in report.paths.iter().enumerate()
+	{
+		let path_name = format!("{}_path_{}", prog_name, i);
+
+		match analyzer::symbolic::path_to_smtlib2(&report, path)
+		{
+			Ok(smt)  => write_bytes_to_file(smt.as_bytes(), &path_name, "smt2"),
+			Err(why) => println!("Couldn't export path {} to SMT-LIB2. {}", i, why),
+		}
+
+		if path.is_truncated()
+		{
+			continue;
+		}
+
+		match report.gen_param_list(path)
+		{
+			Ok(Some(param_list)) =>
+			{
+				let params_str : Vec<String> = param_list.iter().map(|e| e.to_string()).collect();
+				println!("Path {} is taken with entry({}).", i, params_str.join(", "));
+
+				match param_list.to_bytes()
+				{
+					Ok(bytes) => write_bytes_to_file(&bytes, &path_name, "param"),
+					Err(why)  => panic!("Couldn't generate byte code for parameters of path {}. {}", i, why),
+				}
+			},
+			Ok(None)             => println!("Path {} is infeasible.", i),
+			Err(why)             => println!("No parameters generated for path {}. {}", i, why),
+		}
+	}
+}
@@ -74,9 +74,36 @@ fn main()
 		_ => (),
 	};
 	let var_vec: Vec<type_checker::type_checker::VarTypePair> = Vec::new();
-	let res = type_checker::type_checker::iterate_through_ast(example_prog_1, true, var_vec, &fn_vec, ast::data_type::DataType::Void);
+	let res = type_checker::type_checker::iterate_through_ast(example_prog_1.clone(), true, var_vec, &fn_vec, ast::data_type::DataType::Void);
 	match res {
 		Ok(_)    => println!("Successful type checking!"),
-		Err(err) => println!("Failed type checking:\n{}", err),
+		Err(err) =>
+		{
+			println!("Failed type checking:\n{}", err);
+			return;
+		},
+	}
+
+	// Annotate the AST with the types, overloads and variable slots found.
+	match type_checker::annotate::annotate(&example_prog_1) {
+		Ok(typed_prog) =>
+		{
+			let mut typed_prog_lines : Vec<ast::IndentString> = vec![];
+			typed_prog.to_indent_lines(&mut typed_prog_lines);
+			println!("Annotated program:\n{}", ast::indent_lines_to_string(&typed_prog_lines, '\t'));
+		},
+		Err(err) => println!("Failed annotating:\n{}", err),
+	}
+
+	// Run information-flow checker on this AST.
+	match type_checker::info_flow::check_info_flow(&example_prog_1) {
+		Ok(_)    => println!("Successful information-flow checking!"),
+		Err(err) => println!("Failed information-flow checking:\n{}", err),
+	}
+
+	// Run constant-time checker on this AST.
+	match type_checker::const_time::check_const_time(&example_prog_1) {
+		Ok(report) => println!("Constant-time report:\n{}", report),
+		Err(err)   => println!("Failed constant-time checking:\n{}", err),
 	}
 }
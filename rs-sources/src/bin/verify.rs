@@ -0,0 +1,627 @@
+use enclave_verifier::ast;
+use enclave_verifier::interpreter;
+use enclave_verifier::host_verify;
+use enclave_verifier::host_verify::EvidencePolicy;
+use enclave_verifier::host_verify::QuoteVerifier;
+use enclave_verifier::attestation::Evidence;
+use enclave_verifier::enclave::allowlist::CheckerAllowlist;
+use enclave_verifier::enclave::executor::Freshness;
+use enclave_verifier::enclave::executor::NONCE_SIZE;
+use enclave_verifier::enclave::executor::ReportBody;
+use enclave_verifier::session::client;
+use enclave_verifier::session::client::ClientSession;
+use enclave_verifier::session::program;
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+
+use interpreter::aexp::AexpValue;
+use interpreter::exp::ExpValue;
+
+use std::env;
+
+fn read_bytes_from_file(file_name : &str, suffix : &str) -> Vec<u8>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", file_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(file_path)
+	{
+		Err(why) => panic!("couldn't open {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	let mut bytes : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut bytes)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	bytes
+}
+
+fn write_bytes_to_file(bytes : &[u8], file_name : &str, suffix : &str)
+{
+	let file_path_string = format!("{}.{}", file_name, suffix);
+
+	match std::fs::write(&file_path_string, bytes)
+	{
+		Err(why) => panic!("couldn't write to {}: {}", file_path_string, why),
+		Ok(_)    => println!("successfully wrote to {}", file_path_string),
+	}
+}
+
+/// Parse the result of an entry call, as printed by the interpreter enclave;
+/// `none` if the call didn't return any value.
+///
+fn parse_func_ret(s : &str) -> Result<Option<ExpValue>, String>
+{
+	match s
+	{
+		"none"  => return Ok(None),
+		"true"  => return Ok(Some(ExpValue::B(true))),
+		"false" => return Ok(Some(ExpValue::B(false))),
+		_       => {},
+	}
+
+	if s.contains('.')
+	{
+		match s.parse::<f32>()
+		{
+			Ok(v)  => Ok(Some(ExpValue::A(AexpValue::Float32(v)))),
+			Err(_) => Err(format!("Invalid float result {}.", s)),
+		}
+	}
+	else
+	{
+		match s.parse::<i32>()
+		{
+			Ok(v)  => Ok(Some(ExpValue::A(AexpValue::Int32(v)))),
+			Err(_) => Err(format!("Invalid result {}.", s)),
+		}
+	}
+}
+
+/// Parse a measurement, i.e. 32 bytes in hex.
+///
+fn parse_measurement(s : &str) -> Result<[u8; 32], String>
+{
+	if s.len() != 64 || !s.is_ascii()
+	{
+		return Err(format!("Expecting 64 hex digits, while {} are given.", s.len()));
+	}
+
+	let mut res : [u8; 32] = [0; 32];
+	for (i, b) in res.iter_mut().enumerate()
+	{
+		*b = match u8::from_str_radix(&s[(i * 2)..((i + 1) * 2)], 16)
+		{
+			Ok(v)  => v,
+			Err(_) => return Err(format!("Invalid hex digits {}.", &s[(i * 2)..((i + 1) * 2)])),
+		};
+	}
+
+	Ok(res)
+}
+
+/// Check an SGX quote with an external program, e.g. a DCAP quote verifier,
+/// which reads the quote on its standard input, and accepts it by exiting
+/// with status 0.
+///
+fn run_quote_verifier(cmd : &str, quote : &[u8]) -> Result<(), String>
+{
+	use std::io::Write;
+	use std::process::Command;
+	use std::process::Stdio;
+
+	let mut child = match Command::new(cmd).stdin(Stdio::piped()).spawn()
+	{
+		Ok(v)    => v,
+		Err(why) => return Err(format!("Failed to run the quote verifier {}. {}", cmd, why)),
+	};
+
+	if let Some(mut stdin) = child.stdin.take()
+	{
+		if let Err(why) = stdin.write_all(quote)
+		{
+			return Err(format!("Failed to pass the quote to {}. {}", cmd, why));
+		}
+	}
+
+	match child.wait()
+	{
+		Ok(status) if status.success() => Ok(()),
+		Ok(status)                     => Err(format!("The quote verifier {} refused the quote ({}).", cmd, status)),
+		Err(why)                       => Err(format!("Failed to run the quote verifier {}. {}", cmd, why)),
+	}
+}
+
+/// Split the options, `--allow-mock` and `--quote-verifier <cmd>`, from the
+/// command and its arguments.
+///
+fn parse_options(args : Vec<String>) -> (Vec<String>, bool, Option<String>)
+{
+	let mut rest : Vec<String> = vec![];
+	let mut allow_mock = false;
+	let mut quote_verifier : Option<String> = None;
+
+	let mut iter = args.into_iter();
+	while let Some(arg) = iter.next()
+	{
+		match arg.as_str()
+		{
+			"--allow-mock"     => allow_mock = true,
+			"--quote-verifier" => quote_verifier = Some(iter.next().unwrap_or_else(|| usage())),
+			_                  => rest.push(arg),
+		}
+	}
+
+	(rest, allow_mock, quote_verifier)
+}
+
+/// Build an allowlist of type-checkers from entries `key:<pkey>`,
+/// `mrenclave:<hex>`, or `evidence:<name>`, naming `<name>.evidence`. Evidence
+/// is checked as the policy allows on the host.
+///
+fn build_allowlist(entries : &[String], policy : &EvidencePolicy) -> Result<CheckerAllowlist, String>
+{
+	let mut allowlist = CheckerAllowlist { keys : vec![], mr_enclaves : vec![], evidence : vec![] };
+
+	for entry in entries.iter()
+	{
+		match entry.split_once(':')
+		{
+			Some(("key", v))       => allowlist.keys.push(host_verify::decode_b64_pair(v)?),
+			Some(("mrenclave", v)) => allowlist.mr_enclaves.push(parse_measurement(v)?),
+			Some(("evidence", v))  =>
+			{
+				let (_, evidence) = Evidence::from_bytes(&read_bytes_from_file(v, "evidence")[..])?;
+				allowlist.evidence.push(evidence);
+			},
+			_                      => return Err(format!("Unknown allowlist entry {}.", entry)),
+		}
+	}
+
+	for evidence in allowlist.evidence.iter()
+	{
+		let body = host_verify::verify_evidence(evidence, None, policy)?;
+		if !allowlist.mr_enclaves.contains(&body.mr_enclave)
+		{
+			return Err(format!("The {} is for an enclave not in the allowlist.", evidence));
+		}
+	}
+
+	Ok(allowlist)
+}
+
+/// The freshness of a report of an execution with the parameters of the
+/// given name, i.e. with the nonce `<params>.nonce`, and the given report
+/// number.
+///
+fn read_freshness(params_name : &str, counter : &str) -> Result<Freshness, String>
+{
+	let nonce_bytes = read_bytes_from_file(params_name, "nonce");
+
+	let mut freshness = Freshness { nonce : [0; NONCE_SIZE], counter : 0 };
+	if nonce_bytes.len() != NONCE_SIZE
+	{
+		return Err(format!("The nonce has {} byte(s) instead of {}.", nonce_bytes.len(), NONCE_SIZE));
+	}
+	freshness.nonce.copy_from_slice(&nonce_bytes[..]);
+
+	freshness.counter = match counter.parse::<u64>()
+	{
+		Ok(v)  => v,
+		Err(_) => return Err(format!("Invalid report number {}.", counter)),
+	};
+
+	Ok(freshness)
+}
+
+/// The session of the client kept in `<params>.session`, i.e. its secret
+/// scalar followed by the public key of the enclave.
+///
+fn read_session(params_name : &str) -> Result<ClientSession, String>
+{
+	let session_bytes = read_bytes_from_file(params_name, "session");
+	if session_bytes.len() != 32 + 64
+	{
+		return Err(format!("The session has {} byte(s) instead of {}.", session_bytes.len(), 32 + 64));
+	}
+
+	let mut secret : [u8; 32] = [0; 32];
+	secret.copy_from_slice(&session_bytes[0..32]);
+	let mut enclave_pkey : [u8; 64] = [0; 64];
+	enclave_pkey.copy_from_slice(&session_bytes[32..]);
+
+	ClientSession::from_secret(&secret, &enclave_pkey)
+}
+
+fn usage() -> !
+{
+	println!("Usage:");
+	println!("  verify [--allow-mock] [--quote-verifier <cmd>] <command>");
+	println!("where <command> is one of:");
+	println!("  verify cert <prog>");
+	println!("  verify report <prog> <params> <result> <enclave_pkey> <signature> <allowlist> <counter>");
+	println!("  verify evidence <name> [mrenclave]");
+	println!("  verify allowlist <allowlist> <entry>...");
+	println!("  verify nonce <params>");
+	println!("  verify seal <params> <name> <mrenclave>");
+	println!("  verify seal_prog <prog> <name> <mrenclave> <checker_mrenclave>");
+	println!("  verify unseal <prog> <params> <signature> <allowlist> <counter>");
+	println!("  verify output <prog> <params> <allowlist> [sealed]");
+	println!("where <prog> names <prog>.vimpc, <params> names <params>.param, and the nonce");
+	println!("<params>.nonce of the execution, <counter> is the report number, <name> names");
+	println!("<name>.evidence, <allowlist> names <allowlist>.allow, the type-checkers");
+	println!("provisioned to the interpreter, <result> is none, true, false, an integer, or a");
+	println!("float, e.g. 1.5, and <mrenclave> is the expected measurement of the enclave, in");
+	println!("hex. Allowlist entries are key:<pkey>, mrenclave:<mrenclave>, or evidence:<name>.");
+	println!("seal encrypts <params>.param to the key of <name>.evidence, once the evidence");
+	println!("binds it to the interpreter of measurement <mrenclave>, as <params>.sparam, and");
+	println!("keeps the session in <params>.session; unseal verifies the report of the");
+	println!("execution, and decrypts its result <params>.sresult. output");
+	println!("verifies the files written by the interpreter: the result <params>.result, or");
+	println!("<params>.sresult, the report <params>.report, and its signature <params>.sig,");
+	println!("by the key of <params>.evidence, or the one the parameters were sealed for.");
+	println!("seal_prog encrypts <prog>.impc to the key of <name>.evidence, once the evidence");
+	println!("binds it to the type-checker of measurement <checker_mrenclave>, as <prog>.simpc,");
+	println!("for delivery to the interpreter of measurement <mrenclave> only.");
+	println!("Evidence holding an SGX quote is accepted once <cmd> accepts the quote, given on");
+	println!("its standard input, e.g. a DCAP quote verifier; without <cmd>, it is refused.");
+	println!("SGX reports are always refused, and mock quotes unless --allow-mock is given.");
+	println!("Mock quotes prove nothing about the enclave; only allow them for testing.");
+	std::process::exit(2);
+}
+
+fn fail(what : &str, why : String) -> !
+{
+	println!("{} FAILED: {}", what, why);
+	std::process::exit(1);
+}
+
+fn main()
+{
+	let (args, allow_mock, quote_verifier) = parse_options(env::args().collect());
+
+	let run_verifier = |quote : &[u8]| run_quote_verifier(quote_verifier.as_deref().unwrap_or_default(), quote);
+	let policy = EvidencePolicy
+	{
+		allow_mock   : allow_mock,
+		verify_quote : quote_verifier.as_ref().map(|_| &run_verifier as QuoteVerifier),
+	};
+
+	match (args.get(1).map(|s| s.as_str()), args.len())
+	{
+		(Some("cert"), 3) =>
+		{
+			let prog_bytes = read_bytes_from_file(&args[2], "vimpc");
+
+			// Sealed bytecode can't be shown, nor checked against the
+			// certificate, on the host.
+			if program::is_sealed_program(&prog_bytes[..])
+			{
+				match host_verify::verify_sealed_certificate(&prog_bytes[..])
+				{
+					Ok(cert) => println!("Verified {}; the bytecode is sealed for the interpreter.", cert),
+					Err(why) => fail("Certificate verification", why),
+				}
+
+				return;
+			}
+
+			let (prog, cert) = match host_verify::verify_certificate(&prog_bytes[..])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Certificate verification", why),
+			};
+
+			let mut prog_lines : Vec<ast::IndentString> = vec![];
+			prog.to_indent_lines(&mut prog_lines);
+			println!("Program {}:\n{}\n", args[2], ast::indent_lines_to_string(&prog_lines, '\t'));
+
+			println!("Verified {}.", cert);
+		},
+		(Some("report"), 9) =>
+		{
+			let prog_bytes = read_bytes_from_file(&args[2], "vimpc");
+			let param_bytes = read_bytes_from_file(&args[3], "param");
+			let allowlist_bytes = read_bytes_from_file(&args[7], "allow");
+
+			let freshness = match read_freshness(&args[3], &args[8])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", why),
+			};
+
+			let ret_bytes = match parse_func_ret(&args[4]).and_then(|r| interpreter::exp::func_ret_to_bytes(&r))
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", why),
+			};
+
+			let enclave_pkey = match host_verify::decode_b64_pair(&args[5])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", format!("Invalid enclave public key. {}", why)),
+			};
+
+			let sign = match host_verify::decode_b64_pair(&args[6])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", format!("Invalid signature. {}", why)),
+			};
+
+			match host_verify::verify_report(&prog_bytes[..], &param_bytes[..], &ret_bytes[..], &allowlist_bytes[..], &freshness, &enclave_pkey, &sign)
+			{
+				Ok(_)    => println!("Verified report {} of {} returning {}, under allowlist {}.", args[8], args[2], args[4], args[7]),
+				Err(why) => fail("Report verification", why),
+			}
+		},
+		(Some("seal"), 5) =>
+		{
+			let evidence_bytes = read_bytes_from_file(&args[3], "evidence");
+			let param_bytes = read_bytes_from_file(&args[2], "param");
+
+			let mr_enclave = match parse_measurement(&args[4])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Sealing", why),
+			};
+
+			// Only seal for a key bound to the interpreter enclave, by
+			// authenticated evidence: the host may hand over evidence of its
+			// own key.
+			let evidence = match Evidence::from_bytes(&evidence_bytes[..]).and_then(|(_, e)| host_verify::verify_evidence(&e, Some(&mr_enclave), &policy).map(|_| e))
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Sealing", why),
+			};
+
+			let session = match ClientSession::new(&evidence.pub_key)
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Sealing", why),
+			};
+
+			match session.seal_params(&param_bytes[..])
+			{
+				Ok(v)    => write_bytes_to_file(&v[..], &args[2], "sparam"),
+				Err(why) => fail("Sealing", why),
+			}
+
+			let mut session_bytes = session.secret().to_vec();
+			session_bytes.extend_from_slice(&session.enclave_public_key());
+			write_bytes_to_file(&session_bytes[..], &args[2], "session");
+
+			println!("Sealed {} for enclave key {}.", args[2], host_verify::encode_b64_pair(&evidence.pub_key));
+		},
+		(Some("seal_prog"), 6) =>
+		{
+			let prog_bytes = read_bytes_from_file(&args[2], "impc");
+			let evidence_bytes = read_bytes_from_file(&args[3], "evidence");
+
+			let mr_enclave = match parse_measurement(&args[4])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Sealing", why),
+			};
+
+			let checker_mr_enclave = match parse_measurement(&args[5])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Sealing", why),
+			};
+
+			// Only seal for a key bound to the type-checker enclave, by
+			// authenticated evidence: the host may hand over evidence of its
+			// own key.
+			let evidence = match Evidence::from_bytes(&evidence_bytes[..]).and_then(|(_, e)| host_verify::verify_evidence(&e, Some(&checker_mr_enclave), &policy).map(|_| e))
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Sealing", why),
+			};
+
+			match client::seal_program(&evidence.pub_key, &mr_enclave, &prog_bytes[..])
+			{
+				Ok(v)    => write_bytes_to_file(&v[..], &args[2], "simpc"),
+				Err(why) => fail("Sealing", why),
+			}
+
+			println!("Sealed {} for type-checker key {}, to be delivered to {}.", args[2], host_verify::encode_b64_pair(&evidence.pub_key), args[4]);
+		},
+		(Some("unseal"), 7) =>
+		{
+			let prog_bytes = read_bytes_from_file(&args[2], "vimpc");
+			let sealed_params = read_bytes_from_file(&args[3], "sparam");
+			let sealed_result = read_bytes_from_file(&args[3], "sresult");
+			let allowlist_bytes = read_bytes_from_file(&args[5], "allow");
+
+			let freshness = match read_freshness(&args[3], &args[6])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", why),
+			};
+
+			let session = match read_session(&args[3])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", why),
+			};
+
+			let sign = match host_verify::decode_b64_pair(&args[4])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", format!("Invalid signature. {}", why)),
+			};
+
+			// The report is signed by the key the parameters were sealed for.
+			if let Err(why) = host_verify::verify_session_report(&prog_bytes[..], &sealed_params[..], &sealed_result[..], &allowlist_bytes[..], &freshness, &session.enclave_public_key(), &sign)
+			{
+				fail("Report verification", why);
+			}
+
+			let func_ret = match session.open_result(&sealed_params[..], &sealed_result[..]).and_then(|b| interpreter::exp::func_ret_from_bytes(&b[..]).map(|(_, r)| r))
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Unsealing", why),
+			};
+
+			match func_ret
+			{
+				Some(v) => println!("Verified report {} of {} returning {}, under allowlist {}.", args[6], args[2], v, args[5]),
+				None    => println!("Verified report {} of {} returning none, under allowlist {}.", args[6], args[2], args[5]),
+			}
+		},
+		(Some("output"), 5) | (Some("output"), 6) =>
+		{
+			let sealed = match args.get(5).map(|s| s.as_str())
+			{
+				Some("sealed") => true,
+				Some(s)        => fail("Report verification", format!("Unknown mode {}.", s)),
+				None           => false,
+			};
+
+			let prog_bytes = read_bytes_from_file(&args[2], "vimpc");
+			let allowlist_bytes = read_bytes_from_file(&args[4], "allow");
+			let nonce_bytes = read_bytes_from_file(&args[3], "nonce");
+			let report_bytes = read_bytes_from_file(&args[3], "report");
+			let sign_bytes = read_bytes_from_file(&args[3], "sig");
+
+			let body = match ReportBody::from_bytes(&report_bytes[..])
+			{
+				Ok((_, v)) => v,
+				Err(why)   => fail("Report verification", why),
+			};
+
+			let mut nonce : [u8; NONCE_SIZE] = [0; NONCE_SIZE];
+			let mut sign : [u8; 64] = [0; 64];
+			if nonce_bytes.len() != nonce.len() || sign_bytes.len() != sign.len()
+			{
+				fail("Report verification", format!("{}", "The nonce, or the signature, has an unexpected size."));
+			}
+			nonce.copy_from_slice(&nonce_bytes[..]);
+			sign.copy_from_slice(&sign_bytes[..]);
+
+			let byte_code_hash = match host_verify::verified_program_hash(&prog_bytes[..])
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Report verification", why),
+			};
+
+			// The key of the report is the one the parameters were sealed for,
+			// or the one of the evidence of the execution.
+			let (hash, enclave_pkey, ret_bytes) = if sealed
+			{
+				let sealed_params = read_bytes_from_file(&args[3], "sparam");
+				let sealed_result = read_bytes_from_file(&args[3], "sresult");
+
+				let session = match read_session(&args[3])
+				{
+					Ok(v)    => v,
+					Err(why) => fail("Report verification", why),
+				};
+
+				let ret_bytes = match session.open_result(&sealed_params[..], &sealed_result[..])
+				{
+					Ok(v)    => v,
+					Err(why) => fail("Unsealing", why),
+				};
+
+				let hash = host_verify::session_report_hash(&sealed_params[..], &byte_code_hash, &sealed_result[..], &allowlist_bytes[..], &body.freshness);
+
+				(hash, session.enclave_public_key(), ret_bytes)
+			}
+			else
+			{
+				let param_bytes = read_bytes_from_file(&args[3], "param");
+				let ret_bytes = read_bytes_from_file(&args[3], "result");
+				let evidence_bytes = read_bytes_from_file(&args[3], "evidence");
+
+				let evidence = match Evidence::from_bytes(&evidence_bytes[..]).and_then(|(_, e)| host_verify::verify_evidence(&e, None, &policy).map(|_| e))
+				{
+					Ok(v)    => v,
+					Err(why) => fail("Report verification", why),
+				};
+
+				let hash = match host_verify::report_hash(&param_bytes[..], &byte_code_hash, &ret_bytes[..], &allowlist_bytes[..], &body.freshness)
+				{
+					Ok(v)    => v,
+					Err(why) => fail("Report verification", why),
+				};
+
+				(hash, evidence.pub_key, ret_bytes)
+			};
+
+			if let Err(why) = host_verify::verify_report_body(&body, &hash, &nonce, &enclave_pkey, &sign)
+			{
+				fail("Report verification", why);
+			}
+
+			match interpreter::exp::func_ret_from_bytes(&ret_bytes[..])
+			{
+				Ok((_, Some(v))) => println!("Verified report {} of {} returning {}, under allowlist {}.", body.freshness.counter, args[2], v, args[4]),
+				Ok((_, None))    => println!("Verified report {} of {} returning none, under allowlist {}.", body.freshness.counter, args[2], args[4]),
+				Err(why)         => fail("Report verification", why),
+			}
+		},
+		(Some("evidence"), 3) | (Some("evidence"), 4) =>
+		{
+			let evidence_bytes = read_bytes_from_file(&args[2], "evidence");
+
+			let evidence = match Evidence::from_bytes(&evidence_bytes[..])
+			{
+				Ok((_, v)) => v,
+				Err(why)   => fail("Evidence verification", why),
+			};
+
+			let mr_enclave = match args.get(3).map(|s| parse_measurement(s))
+			{
+				Some(Ok(v))    => Some(v),
+				Some(Err(why)) => fail("Evidence verification", why),
+				None           => None,
+			};
+
+			let body = match host_verify::verify_evidence(&evidence, mr_enclave.as_ref(), &policy)
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Evidence verification", why),
+			};
+
+			let mr_enclave_hex : Vec<String> = body.mr_enclave.iter().map(|b| format!("{:02x}", b)).collect();
+			println!("Verified {} of enclave {}.", evidence, mr_enclave_hex.concat());
+			println!("Enclave public key {}.", host_verify::encode_b64_pair(&evidence.pub_key));
+		},
+		(Some("allowlist"), n) if n > 3 =>
+		{
+			let allowlist = match build_allowlist(&args[3..], &policy)
+			{
+				Ok(v)    => v,
+				Err(why) => fail("Allowlist", why),
+			};
+
+			match allowlist.to_bytes()
+			{
+				Ok(v)    => write_bytes_to_file(&v[..], &args[2], "allow"),
+				Err(why) => fail("Allowlist", why),
+			}
+
+			println!("Written {}.", allowlist);
+		},
+		(Some("nonce"), 3) =>
+		{
+			use rand_core::RngCore;
+
+			let mut nonce : [u8; NONCE_SIZE] = [0; NONCE_SIZE];
+			rand_core::OsRng.fill_bytes(&mut nonce);
+
+			write_bytes_to_file(&nonce, &args[2], "nonce");
+		},
+		_ => usage(),
+	}
+}
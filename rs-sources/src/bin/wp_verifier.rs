@@ -0,0 +1,110 @@
+use enclave_verifier::ast;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::analyzer;
+
+use std::env;
+
+fn read_byte_code_from_file(prog_name : &str) -> Vec<u8>
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", prog_name, "impc");
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::open(file_path)
+	{
+		Err(why) => panic!("couldn't open {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	let mut byte_code : Vec<u8> = vec![];
+
+	match file.read_to_end(&mut byte_code)
+	{
+		Ok(_) => {},
+		Err(why) => panic!("couldn't read from {}: {}", file_path.display(), why),
+	}
+
+	println!("Bytecode file read {} bytes total for program {}.", byte_code.len(), prog_name);
+
+	byte_code
+}
+
+fn write_bytes_to_file(bytes : &[u8], file_name : &str, suffix : &str)
+{
+	use std::fs::File;
+	use std::path::Path;
+	use std::io::prelude::*;
+
+	let file_path_string = format!("{}.{}", file_name, suffix);
+	let file_path = Path::new(&file_path_string);
+
+	let mut file = match File::create(file_path)
+	{
+		Err(why) => panic!("couldn't create {}: {}", file_path.display(), why),
+		Ok(file) => file,
+	};
+
+	match file.write_all(bytes)
+	{
+		Err(why) => panic!("couldn't write to {}: {}", file_path.display(), why),
+		Ok(_) => println!("successfully wrote to {}", file_path.display()),
+	}
+}
+
+fn main()
+{
+	let args : Vec<String> = env::args().collect();
+	if args.len() != 2
+	{
+		panic!("Incorrect number of arguments provided.")
+	}
+
+	let prog_name = &args[1];
+	let prog_bytes = read_byte_code_from_file(prog_name);
+	let (_bytes_left, prog) = match ast::cmd::Cmd::from_bytes(&prog_bytes[..])
+	{
+		Ok(v) => v,
+		Err(why) => panic!("Couldn't construct AST from byte code for {}. {}", prog_name, why)
+	};
+	let mut prog_lines : Vec<ast::IndentString> = vec![];
+	prog.to_indent_lines(&mut prog_lines);
+	println!("Example program {}:\n{}\n", prog_name, ast::indent_lines_to_string(&prog_lines, '\t'));
+
+	// Check every function against its contract.
+	let report = match analyzer::wp::verify_program(&prog)
+	{
+		Ok(r)    => r,
+		Err(why) => panic!("Failed to generate verification conditions:\n{}", why),
+	};
+	println!("{}\n", report);
+
+	// Export the conditions that are not proved, for an external solver.
+	let mut vc_idx = 0;
+	for func in report.funcs.iter()
+	{
+		for (vc, res) in func.vcs.iter()
+		{
+			match res
+			{
+				analyzer::wp::VcResult::Proved => {},
+				_                              =>
+				{
+					match analyzer::wp::vc_to_smtlib2(vc)
+					{
+						Ok(smt)  => write_bytes_to_file(smt.as_bytes(), &format!("{}_vc_{}", prog_name, vc_idx), "smt2"),
+						Err(why) => println!("Couldn't export condition \"{}\" to SMT-LIB2. {}", vc.what, why),
+					}
+					vc_idx += 1;
+				},
+			}
+		}
+	}
+
+	if !report.is_verified()
+	{
+		std::process::exit(1);
+	}
+}
@@ -0,0 +1,337 @@
+//! Certificate issued by the type-checker enclave for a program it accepts.
+//!
+//! A verified program (`.vimpc`) is the bytecode of the program followed by
+//! the declared ranges of the parameters of `entry`, and its certificate;
+//! or, for confidential programs, the bytecode sealed for the interpreter
+//! enclave, see `session::program`, followed by the ranges, and the
+//! certificate of the bytecode in clear. The certificate records what was checked, and by whom:
+//! the hashes of the bytecode and of the ranges, the public key of the
+//! type-checker enclave, and the policy the program was checked against.
+//! The interpreter only executes calls of `entry` within the ranges. The enclave signs every field
+//! of the certificate except the signature itself, i.e. the bytes returned by
+//! `Certificate::signed_bytes`, with ECDSA over the P-256 curve and SHA256.
+//!
+//! This module doesn't compute any hash or signature, so it can be used in
+//! enclaves; it only encodes, decodes and validates the structure of
+//! certificates. Signatures are checked by the enclaves with the SGX SDK, or
+//! on hosts by `host_verify`.
+
+use std::fmt;
+use std::vec::Vec;
+use std::string::String;
+
+use super::ast;
+use super::ast::Serializible;
+use super::ast::Deserializible;
+use super::analyzer::interval::ParamRanges;
+use super::type_checker::policy::CheckerPolicy;
+use super::session::program::SealedProgram;
+
+/// Magic bytes a certificate starts with.
+pub const CERT_MAGIC : [u8; 4] = *b"VCRT";
+
+/// Version of the certificate format this crate encodes.
+pub const CERT_VERSION : u8 = 2;
+
+/// Size of an encoded certificate, in bytes.
+pub const CERT_SIZE : usize = SIGNED_SIZE + 64;
+
+/// Size of the signed part of a certificate, in bytes.
+const SIGNED_SIZE : usize = 4 + 1 + 1 + 32 + 32 + 64 + 4 + 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashAlgorithm
+{
+	Sha256,
+}
+
+impl HashAlgorithm
+{
+	fn to_byte(&self) -> u8
+	{
+		match self
+		{
+			HashAlgorithm::Sha256 => 1u8,
+		}
+	}
+
+	fn from_byte(b : u8) -> Result<HashAlgorithm, String>
+	{
+		match b
+		{
+			1u8 => Result::Ok(HashAlgorithm::Sha256),
+			_   => Result::Err(format!("Unknown hash algorithm {} in certificate.", b)),
+		}
+	}
+}
+
+impl fmt::Display for HashAlgorithm
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			HashAlgorithm::Sha256 => write!(f, "SHA256"),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Certificate
+{
+	pub version      : u8,
+	pub hash_alg     : HashAlgorithm,
+	/// Hash of the program bytecode, without any test section.
+	pub prog_hash    : [u8; 32],
+	/// Hash of the serialized ranges of the parameters of `entry`, see
+	/// `analyzer::interval::ParamRanges`, the program was checked with.
+	pub ranges_hash  : [u8; 32],
+	/// Public key of the type-checker enclave, i.e. the x and y coordinates
+	/// of the point, 32 bytes each, in little-endian.
+	pub checker_pkey : [u8; 64],
+	/// Flags of the checks the program passed, in addition to type-checking.
+	pub policy_flags : u32,
+	/// Number of certificates the checker issued before this one, so the
+	/// certificates of a checker are ordered.
+	pub counter      : u64,
+	/// Signature over `signed_bytes`; the x and y components, 32 bytes
+	/// each, in little-endian.
+	pub signature    : [u8; 64],
+}
+
+impl Certificate
+{
+	/// Unsigned certificate of the current version; the signature is filled
+	/// in once the checker has signed `signed_bytes`.
+	///
+	pub fn new(prog_hash : [u8; 32], ranges_hash : [u8; 32], checker_pkey : [u8; 64], policy : &CheckerPolicy, counter : u64) -> Certificate
+	{
+		Certificate
+		{
+			version      : CERT_VERSION,
+			hash_alg     : HashAlgorithm::Sha256,
+			prog_hash    : prog_hash,
+			ranges_hash  : ranges_hash,
+			checker_pkey : checker_pkey,
+			policy_flags : policy.to_flags(),
+			counter      : counter,
+			signature    : [0u8; 64],
+		}
+	}
+
+	pub fn policy(&self) -> Result<CheckerPolicy, String>
+	{
+		CheckerPolicy::from_flags(self.policy_flags)
+	}
+
+	/// The bytes the checker signs, i.e. the encoded certificate without
+	/// its signature.
+	///
+	/// # Certificate layout
+	/// ```text
+	/// | magic "VCRT" - 4 Bytes | version - 1 Byte | hash_alg - 1 Byte | prog_hash - 32 Bytes | ranges_hash - 32 Bytes |
+	/// | checker_pkey - 64 Bytes | policy_flags - 4 Bytes | counter - 8 Bytes | signature - 64 Bytes |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	pub fn signed_bytes(&self) -> Vec<u8>
+	{
+		let mut res : Vec<u8> = Vec::with_capacity(CERT_SIZE);
+
+		res.extend_from_slice(&CERT_MAGIC);
+		res.push(self.version);
+		res.push(self.hash_alg.to_byte());
+		res.extend_from_slice(&self.prog_hash);
+		res.extend_from_slice(&self.ranges_hash);
+		res.extend_from_slice(&self.checker_pkey);
+		res.extend_from_slice(&self.policy_flags.to_le_bytes());
+		res.extend_from_slice(&self.counter.to_le_bytes());
+
+		res
+	}
+
+	/// Check that the certificate is for the program of the given hash.
+	///
+	pub fn check_prog_hash(&self, prog_hash : &[u8]) -> Result<(), String>
+	{
+		if prog_hash != &self.prog_hash[..]
+		{
+			return Result::Err(format!("{}", "The certificate is issued for another program."));
+		}
+
+		Result::Ok(())
+	}
+
+	/// Check that the certificate is for the parameter ranges of the given
+	/// hash.
+	///
+	pub fn check_ranges_hash(&self, ranges_hash : &[u8]) -> Result<(), String>
+	{
+		if ranges_hash != &self.ranges_hash[..]
+		{
+			return Result::Err(format!("{}", "The certificate is issued for other parameter ranges."));
+		}
+
+		Result::Ok(())
+	}
+}
+
+impl ast::Serializible for Certificate
+{
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res = self.signed_bytes();
+		res.extend_from_slice(&self.signature);
+
+		Result::Ok(res)
+	}
+}
+
+impl ast::Deserializible for Certificate
+{
+	/// Parse a certificate, and validate every field of it; only the
+	/// current version is accepted.
+	///
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], Certificate), String>
+	{
+		if bytes.len() < CERT_SIZE
+		{
+			return Result::Err(format!("{}", "Failed to parse certificate. Bytes are shorter than expected."));
+		}
+
+		if bytes[0..4] != CERT_MAGIC
+		{
+			return Result::Err(format!("{}", "Failed to parse certificate. Magic bytes mismatch."));
+		}
+
+		let version = bytes[4];
+		if version != CERT_VERSION
+		{
+			return Result::Err(format!("Unsupported certificate version {}.", version));
+		}
+
+		let hash_alg = HashAlgorithm::from_byte(bytes[5])?;
+
+		let mut prog_hash : [u8; 32] = [0; 32];
+		prog_hash.copy_from_slice(&bytes[6..38]);
+
+		let mut ranges_hash : [u8; 32] = [0; 32];
+		ranges_hash.copy_from_slice(&bytes[38..70]);
+
+		let mut checker_pkey : [u8; 64] = [0; 64];
+		checker_pkey.copy_from_slice(&bytes[70..134]);
+
+		let mut policy_bytes : [u8; 4] = [0; 4];
+		policy_bytes.copy_from_slice(&bytes[134..138]);
+		let policy_flags = u32::from_le_bytes(policy_bytes);
+		CheckerPolicy::from_flags(policy_flags)?;
+
+		let mut counter_bytes : [u8; 8] = [0; 8];
+		counter_bytes.copy_from_slice(&bytes[138..146]);
+		let counter = u64::from_le_bytes(counter_bytes);
+
+		let mut signature : [u8; 64] = [0; 64];
+		signature.copy_from_slice(&bytes[SIGNED_SIZE..CERT_SIZE]);
+
+		if checker_pkey.iter().all(|b| *b == 0) || signature.iter().all(|b| *b == 0)
+		{
+			return Result::Err(format!("{}", "The certificate has no checker key or no signature."));
+		}
+
+		let cert = Certificate
+		{
+			version      : version,
+			hash_alg     : hash_alg,
+			prog_hash    : prog_hash,
+			ranges_hash  : ranges_hash,
+			checker_pkey : checker_pkey,
+			policy_flags : policy_flags,
+			counter      : counter,
+			signature    : signature,
+		};
+
+		Result::Ok((&bytes[CERT_SIZE..], cert))
+	}
+}
+
+impl fmt::Display for Certificate
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "certificate v{} #{} for {} ", self.version, self.counter, self.hash_alg)?;
+		for b in self.prog_hash.iter()
+		{
+			write!(f, "{:02x}", b)?;
+		}
+		write!(f, ", policy {:#x}", self.policy_flags)
+	}
+}
+
+/// Split the declared ranges of the parameters at the start of the bytes;
+/// returns the bytes left, and the bytes of the ranges.
+///
+fn split_ranges(bytes : &[u8]) -> Result<(&[u8], &[u8]), String>
+{
+	let (bytes_left, _) = match ParamRanges::from_bytes(bytes)
+	{
+		Result::Ok(v)    => v,
+		Result::Err(why) => return Result::Err(format!("Failed to parse the parameter ranges. {}", why)),
+	};
+
+	Result::Ok((bytes_left, &bytes[0..(bytes.len() - bytes_left.len())]))
+}
+
+/// Split a verified program into the program, its bytecode, the bytes of
+/// the ranges of its parameters, and its certificate; nothing may follow
+/// the certificate.
+///
+pub fn parse_verified_byte_code(bytes : &[u8]) -> Result<(ast::cmd::Cmd, &[u8], &[u8], Certificate), String>
+{
+	let (bytes_left_1, prog) = ast::cmd::Cmd::from_bytes(bytes)?;
+	let byte_code = &bytes[0..(bytes.len() - bytes_left_1.len())];
+
+	let (bytes_left_2, ranges_bytes) = split_ranges(bytes_left_1)?;
+
+	let (bytes_left_3, cert) = Certificate::from_bytes(bytes_left_2)?;
+	if !bytes_left_3.is_empty()
+	{
+		return Result::Err(format!("There are {} unexpected byte(s) after the certificate.", bytes_left_3.len()));
+	}
+
+	Result::Ok((prog, byte_code, ranges_bytes, cert))
+}
+
+/// Split a verified program whose bytecode is sealed into the sealed
+/// bytecode, the bytes of the ranges of its parameters, and its
+/// certificate; nothing may follow the certificate.
+///
+/// The certificate is over the bytecode in clear, so only the enclave the
+/// bytecode is sealed for can check it is for that bytecode.
+///
+pub fn parse_sealed_verified_byte_code(bytes : &[u8]) -> Result<(&[u8], &[u8], Certificate), String>
+{
+	let (bytes_left_1, _) = SealedProgram::from_bytes(bytes)?;
+	let sealed_byte_code = &bytes[0..(bytes.len() - bytes_left_1.len())];
+
+	let (bytes_left_2, ranges_bytes) = split_ranges(bytes_left_1)?;
+
+	let (bytes_left_3, cert) = Certificate::from_bytes(bytes_left_2)?;
+	if !bytes_left_3.is_empty()
+	{
+		return Result::Err(format!("There are {} unexpected byte(s) after the certificate.", bytes_left_3.len()));
+	}
+
+	Result::Ok((sealed_byte_code, ranges_bytes, cert))
+}
+
+/// Encode a verified program of the bytecode, sealed or not, the ranges of
+/// its parameters, and its certificate.
+///
+pub fn verified_byte_code(byte_code : &[u8], ranges : &ParamRanges, cert : &Certificate) -> Result<Vec<u8>, String>
+{
+	let mut res : Vec<u8> = byte_code.to_vec();
+	res.append(&mut ranges.to_bytes()?);
+	res.append(&mut cert.to_bytes()?);
+
+	Result::Ok(res)
+}
@@ -0,0 +1,237 @@
+//! Type-checker keys trusted by the interpreter enclave.
+//!
+//! The interpreter only runs programs whose certificate is signed by a
+//! trusted type-checker key; the key in the certificate itself comes with
+//! the untrusted input. The host provisions the allowlist once, when the
+//! enclave is initialized, with
+//! - keys pinned directly, e.g. the sealed identity of a type-checker;
+//! - evidence of type-checker keys, trusted if the evidence checks out, by
+//!   the backend, for one of the MRENCLAVEs of the allowlist.
+//!
+//! The hash of the provisioned allowlist is part of every execution report,
+//! so verifiers know which type-checkers a result relies on.
+
+use std::fmt;
+use std::vec::Vec;
+use std::string::String;
+
+use super::EnclaveBackend;
+use super::init_slot::InitSlot;
+
+use super::super::ast;
+use super::super::ast::Deserializible;
+use super::super::attestation;
+use super::super::attestation::Evidence;
+
+/// Magic bytes an allowlist starts with.
+pub const ALLOWLIST_MAGIC : [u8; 4] = *b"VALW";
+
+/// Allowlist of type-checkers, as provisioned by the host.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckerAllowlist
+{
+	/// Trusted type-checker keys, in the layout of SGX.
+	pub keys        : Vec<[u8; 64]>,
+	/// MRENCLAVEs of the trusted type-checker enclaves.
+	pub mr_enclaves : Vec<[u8; 32]>,
+	/// Evidence of type-checker keys, trusted if it is for one of the
+	/// `mr_enclaves`.
+	pub evidence    : Vec<Evidence>,
+}
+
+fn read_count(bytes : &[u8]) -> Result<(&[u8], usize), String>
+{
+	if bytes.len() < 4
+	{
+		return Result::Err(format!("{}", "Failed to parse allowlist. Bytes are shorter than expected."));
+	}
+
+	let mut count_bytes : [u8; 4] = [0; 4];
+	count_bytes.copy_from_slice(&bytes[0..4]);
+
+	Result::Ok((&bytes[4..], u32::from_le_bytes(count_bytes) as usize))
+}
+
+impl ast::Serializible for CheckerAllowlist
+{
+	/// # Allowlist layout
+	/// ```text
+	/// | magic "VALW" - 4 Bytes | key count - 4 Bytes | keys - 64 Bytes each |
+	/// | MRENCLAVE count - 4 Bytes | MRENCLAVEs - 32 Bytes each |
+	/// | evidence count - 4 Bytes | evidence, see `Evidence::to_bytes` |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = ALLOWLIST_MAGIC.to_vec();
+
+		res.extend_from_slice(&(self.keys.len() as u32).to_le_bytes());
+		for key in self.keys.iter()
+		{
+			res.extend_from_slice(key);
+		}
+
+		res.extend_from_slice(&(self.mr_enclaves.len() as u32).to_le_bytes());
+		for mr_enclave in self.mr_enclaves.iter()
+		{
+			res.extend_from_slice(mr_enclave);
+		}
+
+		res.extend_from_slice(&(self.evidence.len() as u32).to_le_bytes());
+		for evidence in self.evidence.iter()
+		{
+			res.append(&mut evidence.to_bytes()?);
+		}
+
+		Result::Ok(res)
+	}
+}
+
+impl ast::Deserializible for CheckerAllowlist
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], CheckerAllowlist), String>
+	{
+		if bytes.len() < ALLOWLIST_MAGIC.len() || bytes[0..4] != ALLOWLIST_MAGIC
+		{
+			return Result::Err(format!("{}", "Failed to parse allowlist. Magic bytes mismatch."));
+		}
+
+		let (mut bytes_left, key_count) = read_count(&bytes[4..])?;
+		if bytes_left.len() / 64 < key_count
+		{
+			return Result::Err(format!("{}", "Failed to parse allowlist. The keys are shorter than expected."));
+		}
+
+		let mut keys : Vec<[u8; 64]> = Vec::with_capacity(key_count);
+		for _ in 0..key_count
+		{
+			let mut key : [u8; 64] = [0; 64];
+			key.copy_from_slice(&bytes_left[0..64]);
+			keys.push(key);
+			bytes_left = &bytes_left[64..];
+		}
+
+		let (mut bytes_left, mr_enclave_count) = read_count(bytes_left)?;
+		if bytes_left.len() / 32 < mr_enclave_count
+		{
+			return Result::Err(format!("{}", "Failed to parse allowlist. The MRENCLAVEs are shorter than expected."));
+		}
+
+		let mut mr_enclaves : Vec<[u8; 32]> = Vec::with_capacity(mr_enclave_count);
+		for _ in 0..mr_enclave_count
+		{
+			let mut mr_enclave : [u8; 32] = [0; 32];
+			mr_enclave.copy_from_slice(&bytes_left[0..32]);
+			mr_enclaves.push(mr_enclave);
+			bytes_left = &bytes_left[32..];
+		}
+
+		let (mut bytes_left, evidence_count) = read_count(bytes_left)?;
+
+		let mut evidence : Vec<Evidence> = Vec::new();
+		for _ in 0..evidence_count
+		{
+			let (evidence_left, e) = Evidence::from_bytes(bytes_left)?;
+			evidence.push(e);
+			bytes_left = evidence_left;
+		}
+
+		Result::Ok((bytes_left, CheckerAllowlist { keys : keys, mr_enclaves : mr_enclaves, evidence : evidence }))
+	}
+}
+
+impl fmt::Display for CheckerAllowlist
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "allowlist of {} key(s), {} MRENCLAVE(s), and {} evidence", self.keys.len(), self.mr_enclaves.len(), self.evidence.len())
+	}
+}
+
+/// Type-checker keys trusted once an allowlist is provisioned.
+///
+pub struct TrustedCheckers
+{
+	keys           : Vec<[u8; 64]>,
+	/// `SHA256` of the bytes of the provisioned allowlist.
+	allowlist_hash : [u8; 32],
+}
+
+impl TrustedCheckers
+{
+	/// Trust the keys of the allowlist given as bytes, and the keys of its
+	/// evidence, once the evidence checks out for one of its MRENCLAVEs.
+	///
+	pub fn provision<B : EnclaveBackend>(backend : &B, allowlist_bytes : &[u8]) -> Result<TrustedCheckers, String>
+	{
+		let (bytes_left, allowlist) = CheckerAllowlist::from_bytes(allowlist_bytes)?;
+
+		// Everything provisioned is hashed, so nothing may be ignored.
+		if !bytes_left.is_empty()
+		{
+			return Result::Err(format!("The allowlist is followed by {} unexpected byte(s).", bytes_left.len()));
+		}
+
+		let mut keys = allowlist.keys.clone();
+
+		for evidence in allowlist.evidence.iter()
+		{
+			let body = match attestation::check_evidence(backend, evidence)
+			{
+				Result::Ok(val)  => val,
+				Result::Err(why) => return Result::Err(format!("Couldn't check the evidence of a type-checker. {}", why)),
+			};
+
+			if !allowlist.mr_enclaves.contains(&body.mr_enclave)
+			{
+				return Result::Err(format!("{}", "The evidence of a type-checker is for an enclave not in the allowlist."));
+			}
+
+			keys.push(evidence.pub_key);
+		}
+
+		if keys.is_empty()
+		{
+			return Result::Err(format!("{}", "The allowlist trusts no type-checker."));
+		}
+
+		Result::Ok(TrustedCheckers
+		{
+			keys           : keys,
+			allowlist_hash : backend.sha256(allowlist_bytes)?,
+		})
+	}
+
+	/// Check that the type-checker key is trusted.
+	///
+	pub fn check_key(&self, pkey : &[u8; 64]) -> Result<(), String>
+	{
+		if self.keys.iter().any(|k| k[..] == pkey[..])
+		{
+			Result::Ok(())
+		}
+		else
+		{
+			Result::Err(format!("{}", "The certificate is signed by a type-checker not in the allowlist."))
+		}
+	}
+
+	pub fn allowlist_hash(&self) -> &[u8; 32]
+	{
+		&self.allowlist_hash
+	}
+}
+
+impl fmt::Display for TrustedCheckers
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		write!(f, "{} trusted type-checker key(s)", self.keys.len())
+	}
+}
+
+/// The trusted type-checkers of an interpreter enclave instance, set by its
+/// provisioning ECALL.
+pub type CheckersSlot = InitSlot<TrustedCheckers>;
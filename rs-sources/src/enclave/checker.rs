@@ -0,0 +1,304 @@
+use std::vec::Vec;
+use std::string::String;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::EnclaveBackend;
+
+use super::super::ast;
+use super::super::ast::Serializible;
+use super::super::ast::Deserializible;
+use super::super::analyzer;
+use super::super::interpreter;
+use super::super::type_checker;
+use super::super::type_checker::policy::CheckerPolicy;
+use super::super::certificate::Certificate;
+use super::super::attestation;
+use super::super::attestation::Evidence;
+use super::super::session::program;
+
+/// Result of checking a program the checker accepts.
+///
+pub struct CheckerOutput
+{
+	/// Number of bytes of the program bytecode, at the start of the input;
+	/// any test section follows it.
+	pub bytes_read : usize,
+	/// Signed certificate of the program.
+	pub cert       : Certificate,
+}
+
+fn type_check(prog : &ast::cmd::Cmd) -> Result<(), String>
+{
+	let mut glvar_vec : Vec<type_checker::type_checker::VarTypePair> = Vec::new();
+	let mut fn_vec : Vec<type_checker::type_checker::FuncIdentifierTuple> = Vec::new();
+
+	type_checker::type_checker::gather_fn_types(prog, &mut glvar_vec, &mut fn_vec)?;
+	type_checker::type_checker::iterate_through_ast(prog.clone(), true, Vec::new(), &fn_vec, ast::data_type::DataType::Void)?;
+
+	Result::Ok(())
+}
+
+/// Check the program at the start of the input against the policy, and sign
+/// its certificate; `counter` is the number of certificates the checker has
+/// issued, and only grows when a certificate is issued.
+///
+/// The input is the program bytecode, optionally followed by its test
+/// section; only the bytecode is covered by the certificate.
+///
+pub fn check_program<B : EnclaveBackend>(
+	backend : &B,
+	input : &[u8],
+	policy : &CheckerPolicy,
+	ranges : &analyzer::interval::ParamRanges,
+	counter : &AtomicU64) -> Result<CheckerOutput, String>
+{
+	check_byte_code(backend, input, policy, ranges, counter, false)
+}
+
+/// Check the program, like `check_program`; the program, and the reports
+/// of the checks, which tell about it, aren't printed if it is confidential.
+///
+fn check_byte_code<B : EnclaveBackend>(
+	backend : &B,
+	input : &[u8],
+	policy : &CheckerPolicy,
+	ranges : &analyzer::interval::ParamRanges,
+	counter : &AtomicU64,
+	confidential : bool) -> Result<CheckerOutput, String>
+{
+	// ------------------------------------------
+	// 1. Process input bytes:
+	// ------------------------------------------
+	let (input_bytes_left, prog) = match ast::cmd::Cmd::from_bytes(input)
+	{
+		Result::Ok(v)    => v,
+		Result::Err(why) => return Result::Err(format!("Couldn't construct AST from byte code. {}", why)),
+	};
+
+	let bytes_read = input.len() - input_bytes_left.len();
+	let byte_code_hash = backend.sha256(&input[0..bytes_read])?;
+
+	if !confidential
+	{
+		let mut prog_lines : Vec<ast::IndentString> = vec![];
+		prog.to_indent_lines(&mut prog_lines);
+		println!("[Enclave]: Example program:\n{}\n", ast::indent_lines_to_string(&prog_lines, '\t'));
+	}
+
+	// ------------------------------------------
+	// 2. Verification:
+	// ------------------------------------------
+	match type_check(&prog)
+	{
+		Result::Ok(_)    => println!("Successful type checking!"),
+		Result::Err(why) => return Result::Err(format!("Failed type checking:\n{}", why)),
+	}
+
+	// ------------------------------------------
+	// 3. Interval analysis:
+	// ------------------------------------------
+	if policy.require_interval_safe
+	{
+		let report = match analyzer::abs_interp::analyze_program(&prog, ranges)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Failed interval analysis:\n{}", why)),
+		};
+
+		if !confidential
+		{
+			println!("[Enclave]: Interval analysis report:\n{}", report);
+		}
+
+		if !report.is_safe()
+		{
+			return Result::Err(format!("{}", "Program is not proven free of division by zero and overflow; refusing to sign."));
+		}
+	}
+
+	// ------------------------------------------
+	// 4. Information-flow checking:
+	// ------------------------------------------
+	if policy.require_noninterference
+	{
+		match type_checker::info_flow::check_info_flow(&prog)
+		{
+			Result::Ok(_)    => println!("[Enclave]: Successful information-flow checking!"),
+			Result::Err(why) => return Result::Err(format!("Failed information-flow checking:\n{}", why)),
+		}
+	}
+
+	// ------------------------------------------
+	// 5. Constant-time checking:
+	// ------------------------------------------
+	if policy.require_constant_time
+	{
+		let report = match type_checker::const_time::check_const_time(&prog)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Failed constant-time checking:\n{}", why)),
+		};
+
+		if !confidential
+		{
+			println!("[Enclave]: Constant-time report:\n{}", report);
+		}
+
+		if !report.is_clean()
+		{
+			return Result::Err(format!("{}", "Program branches on secrets; refusing to sign."));
+		}
+	}
+
+	// ------------------------------------------
+	// 6. Contract verification:
+	// ------------------------------------------
+	if policy.require_contracts
+	{
+		let report = match analyzer::wp::verify_program(&prog)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Failed contract verification:\n{}", why)),
+		};
+
+		if !confidential
+		{
+			println!("[Enclave]: Contract verification report:\n{}", report);
+		}
+
+		if !report.is_verified()
+		{
+			return Result::Err(format!("{}", "Program is not proven to meet its contracts; refusing to sign."));
+		}
+	}
+
+	// ------------------------------------------
+	// 7. Resource bounds:
+	// ------------------------------------------
+	if policy.require_resource_bounds
+	{
+		let report = match analyzer::resources::analyze_program(&prog)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Failed resource analysis:\n{}", why)),
+		};
+
+		if !confidential
+		{
+			println!("[Enclave]: Resource report:\n{}", report);
+		}
+
+		if let Result::Err(why) = report.check(&analyzer::resources::ResourceLimits::interpreter_enclave())
+		{
+			return Result::Err(format!("{} Program may exceed the resources of the interpreter enclave; refusing to sign.", why));
+		}
+	}
+
+	// ------------------------------------------
+	// 8. Unit tests:
+	// ------------------------------------------
+	// The test section follows the program bytecode; it's not part of the
+	// signed bytecode, so the interpreter enclave never sees the tests.
+	if policy.require_tests_pass
+	{
+		let tests = match ast::test_fn::tests_from_bytes(input_bytes_left)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Couldn't parse the test section. {}", why)),
+		};
+
+		if tests.is_empty()
+		{
+			return Result::Err(format!("{}", "Program has no test; refusing to sign."));
+		}
+
+		if let Result::Err(why) = type_check(&ast::test_fn::with_tests(&prog, &tests))
+		{
+			return Result::Err(format!("Failed type checking the tests:\n{}", why));
+		}
+
+		let results = interpreter::unit_test::run_tests(&prog, &tests);
+		if !confidential
+		{
+			for result in results.iter()
+			{
+				println!("[Enclave]: {}", result);
+			}
+		}
+
+		let num_failed = results.iter().filter(|r| !r.passed()).count();
+		if num_failed > 0
+		{
+			return Result::Err(format!("{} of {} test(s) failed; refusing to sign.", num_failed, results.len()));
+		}
+	}
+
+	// ------------------------------------------
+	// 9. Issue certificate:
+	// ------------------------------------------
+	// The ranges the parameters were analyzed with are the ones the
+	// interpreter holds the calls of `entry` to.
+	let ranges_hash = backend.sha256(&ranges.to_bytes()?)?;
+
+	let mut cert = Certificate::new(byte_code_hash, ranges_hash, backend.public_key(), policy, counter.fetch_add(1, Ordering::SeqCst));
+
+	// The policy, and the ranges, are part of the signed bytes, so a
+	// certificate can't claim more checks than the ones the program passed.
+	cert.signature = backend.sign(&cert.signed_bytes())?;
+
+	Result::Ok(CheckerOutput { bytes_read : bytes_read, cert : cert })
+}
+
+/// Result of checking a confidential program the checker accepts.
+///
+pub struct SealedCheckerOutput
+{
+	/// Program bytecode, without any test section, sealed for the
+	/// interpreter enclave.
+	pub sealed_byte_code : Vec<u8>,
+	/// Signed certificate of the program, over the bytecode in clear.
+	pub cert             : Certificate,
+}
+
+/// Open the program its owner sealed for the key of the backend, check it
+/// like `check_program`, and seal its bytecode for the interpreter enclave
+/// of the evidence, once the evidence checks out for the MRENCLAVE the owner
+/// sealed along with the program. See `session::program`.
+///
+/// Nothing about the program is printed, or returned, as the host sees
+/// both; in particular, why a program is refused isn't told.
+///
+pub fn check_sealed_program<B : EnclaveBackend>(
+	backend : &B,
+	sealed_input : &[u8],
+	interpreter_evidence : &Evidence,
+	policy : &CheckerPolicy,
+	ranges : &analyzer::interval::ParamRanges,
+	counter : &AtomicU64) -> Result<SealedCheckerOutput, String>
+{
+	let (mr_enclave, input) = program::open_program(backend, sealed_input)?;
+
+	println!("[Enclave]: Opened the sealed program ({} byte(s)).", input.len());
+
+	// Only the interpreter the owner chose may get the program in clear.
+	let body = match attestation::check_evidence(backend, interpreter_evidence)
+	{
+		Result::Ok(val)  => val,
+		Result::Err(why) => return Result::Err(format!("Couldn't check the evidence of the interpreter. {}", why)),
+	};
+
+	if body.mr_enclave != mr_enclave
+	{
+		return Result::Err(format!("{}", "The evidence of the interpreter is for another enclave than the one the program is sealed for."));
+	}
+
+	let output = match check_byte_code(backend, &input, policy, ranges, counter, true)
+	{
+		Result::Ok(val) => val,
+		Result::Err(_)  => return Result::Err(format!("{}", "The sealed program is refused; why isn't told, as it may reveal the program.")),
+	};
+
+	let sealed_byte_code = program::seal_program(backend, &interpreter_evidence.pub_key, &mr_enclave, &input[0..output.bytes_read])?;
+
+	Result::Ok(SealedCheckerOutput { sealed_byte_code : sealed_byte_code, cert : output.cert })
+}
@@ -0,0 +1,505 @@
+use std::vec::Vec;
+use std::string::String;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use super::EnclaveBackend;
+use super::allowlist::TrustedCheckers;
+
+use super::super::ast;
+use super::super::ast::Deserializible;
+use super::super::interpreter;
+use super::super::interpreter::exp::ExpValue;
+use super::super::interpreter::aexp::AexpValue;
+use super::super::analyzer::interval::ParamRanges;
+use super::super::certificate;
+use super::super::session::EnclaveSession;
+use super::super::session::program;
+
+/// Size of the nonce of a client, in bytes.
+pub const NONCE_SIZE : usize = 32;
+
+/// Freshness of an execution report: the nonce the client chose for the
+/// execution, and the number of the report among the reports signed by the
+/// enclave instance, so old reports can't be replayed as fresh ones.
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Freshness
+{
+	pub nonce   : [u8; NONCE_SIZE],
+	pub counter : u64,
+}
+
+impl Freshness
+{
+	/// The nonce followed by the counter, in little-endian.
+	///
+	pub fn to_bytes(&self) -> [u8; NONCE_SIZE + 8]
+	{
+		let mut res : [u8; NONCE_SIZE + 8] = [0; NONCE_SIZE + 8];
+		res[0..NONCE_SIZE].copy_from_slice(&self.nonce);
+		res[NONCE_SIZE..].copy_from_slice(&self.counter.to_le_bytes());
+
+		res
+	}
+}
+
+/// Magic bytes a report body starts with.
+pub const REPORT_BODY_MAGIC : [u8; 4] = *b"VRPT";
+
+/// Size of a report body, in bytes.
+pub const REPORT_BODY_SIZE : usize = 4 + 32 * 4 + NONCE_SIZE + 8 + 32;
+
+/// Size of the signature of a report, in bytes.
+pub const REPORT_SIGNATURE_SIZE : usize = 64;
+
+/// The fields of an execution report, as output by the interpreter enclave
+/// along with its signature, and the result.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportBody
+{
+	pub param_list_hash : [u8; 32],
+	pub byte_code_hash  : [u8; 32],
+	pub func_ret_hash   : [u8; 32],
+	pub allowlist_hash  : [u8; 32],
+	pub freshness       : Freshness,
+	pub report_hash     : [u8; 32],
+}
+
+impl ast::Serializible for ReportBody
+{
+	/// # Report body layout
+	/// ```text
+	/// | magic "VRPT" - 4 Bytes | param_list_hash - 32 Bytes | byte_code_hash - 32 Bytes |
+	/// | func_ret_hash - 32 Bytes | allowlist_hash - 32 Bytes | nonce - 32 Bytes |
+	/// | counter - 8 Bytes | report_hash - 32 Bytes |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = Vec::with_capacity(REPORT_BODY_SIZE);
+
+		res.extend_from_slice(&REPORT_BODY_MAGIC);
+		res.extend_from_slice(&self.param_list_hash);
+		res.extend_from_slice(&self.byte_code_hash);
+		res.extend_from_slice(&self.func_ret_hash);
+		res.extend_from_slice(&self.allowlist_hash);
+		res.extend_from_slice(&self.freshness.to_bytes());
+		res.extend_from_slice(&self.report_hash);
+
+		Result::Ok(res)
+	}
+}
+
+impl ast::Deserializible for ReportBody
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], ReportBody), String>
+	{
+		if bytes.len() < REPORT_BODY_SIZE
+		{
+			return Result::Err(format!("{}", "Failed to parse report body. Bytes are shorter than expected."));
+		}
+
+		if bytes[0..4] != REPORT_BODY_MAGIC
+		{
+			return Result::Err(format!("{}", "Failed to parse report body. Magic bytes mismatch."));
+		}
+
+		let mut res = ReportBody
+		{
+			param_list_hash : [0; 32],
+			byte_code_hash  : [0; 32],
+			func_ret_hash   : [0; 32],
+			allowlist_hash  : [0; 32],
+			freshness       : Freshness { nonce : [0; NONCE_SIZE], counter : 0 },
+			report_hash     : [0; 32],
+		};
+
+		res.param_list_hash.copy_from_slice(&bytes[4..36]);
+		res.byte_code_hash.copy_from_slice(&bytes[36..68]);
+		res.func_ret_hash.copy_from_slice(&bytes[68..100]);
+		res.allowlist_hash.copy_from_slice(&bytes[100..132]);
+		res.freshness.nonce.copy_from_slice(&bytes[132..(132 + NONCE_SIZE)]);
+
+		let mut counter_bytes : [u8; 8] = [0; 8];
+		counter_bytes.copy_from_slice(&bytes[(132 + NONCE_SIZE)..(140 + NONCE_SIZE)]);
+		res.freshness.counter = u64::from_le_bytes(counter_bytes);
+
+		res.report_hash.copy_from_slice(&bytes[(140 + NONCE_SIZE)..REPORT_BODY_SIZE]);
+
+		Result::Ok((&bytes[REPORT_BODY_SIZE..], res))
+	}
+}
+
+/// Signed report of an execution; the signature is over `report_hash`, i.e.
+/// `SHA256(param_list_hash | byte_code_hash | func_ret_hash | allowlist_hash |
+/// nonce | counter)`.
+///
+pub struct ExecutionReport
+{
+	pub func_ret        : Option<ExpValue>,
+	pub param_list_hash : [u8; 32],
+	pub byte_code_hash  : [u8; 32],
+	pub func_ret_hash   : [u8; 32],
+	/// Hash of the allowlist of type-checkers the certificate was checked
+	/// against.
+	pub allowlist_hash  : [u8; 32],
+	pub freshness       : Freshness,
+	pub report_hash     : [u8; 32],
+	pub signature       : [u8; 64],
+}
+
+impl ExecutionReport
+{
+	/// The fields of the report, without the result, and the signature.
+	///
+	pub fn body(&self) -> ReportBody
+	{
+		ReportBody
+		{
+			param_list_hash : self.param_list_hash,
+			byte_code_hash  : self.byte_code_hash,
+			func_ret_hash   : self.func_ret_hash,
+			allowlist_hash  : self.allowlist_hash,
+			freshness       : self.freshness,
+			report_hash     : self.report_hash,
+		}
+	}
+}
+
+pub fn gen_prog_states(prog : &mut interpreter::Program, prog_cmd : &ast::cmd::Cmd) -> Result<(), String>
+{
+	use interpreter::cmd::CanEvalToExpVal;
+
+	match prog_cmd.eval_to_exp_val(&mut prog.func_states, &mut prog.var_states)?
+	{
+		Option::Some(v) => match v
+		{
+			Option::Some(v2) => Result::Err(format!("Program root shouldn't contain return statement; it returned {}.", v2)),
+			Option::None     => Result::Err(format!("Program root shouldn't contain return statement; even it's a void return.")),
+		},
+		Option::None    => Result::Ok(()),
+	}
+}
+
+pub fn make_entry_call(prog : &interpreter::Program, entry_call : &ast::func_general::FnCall, oblivious : bool) -> Result<Option<ExpValue>, String>
+{
+	if oblivious
+	{
+		interpreter::oblivious::func_call(&prog.func_states, &prog.var_states, entry_call)
+	}
+	else
+	{
+		interpreter::states::func_call(&prog.func_states, &prog.var_states, entry_call, false)
+	}
+}
+
+/// Parse the verified program, opening its bytecode if it is sealed for the
+/// key of the backend, and check its certificate is for its bytecode, and
+/// signed by a trusted type-checker, along with the ranges of its
+/// parameters; returns the program, the hash of its bytecode, the ranges,
+/// and whether it is confidential, i.e. sealed.
+///
+fn load_verified_program<B : EnclaveBackend>(backend : &B, checkers : &TrustedCheckers, input : &[u8]) -> Result<(ast::cmd::Cmd, [u8; 32], ParamRanges, bool), String>
+{
+	let confidential = program::is_sealed_program(input);
+
+	let (prog, byte_code_hash, ranges_bytes, cert) = if confidential
+	{
+		let (prog, byte_code, ranges_bytes, cert) = match open_verified_program(backend, input)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Couldn't parse the verified program. {}", why)),
+		};
+
+		println!("[Enclave]: Opened the sealed program ({} byte(s)).", byte_code.len());
+
+		(prog, backend.sha256(&byte_code)?, ranges_bytes, cert)
+	}
+	else
+	{
+		let (prog, byte_code, ranges_bytes, cert) = match certificate::parse_verified_byte_code(input)
+		{
+			Result::Ok(v)    => v,
+			Result::Err(why) => return Result::Err(format!("Couldn't parse the verified program. {}", why)),
+		};
+
+		(prog, backend.sha256(byte_code)?, ranges_bytes, cert)
+	};
+
+	println!("[Enclave]: Received {}.", cert);
+
+	cert.check_prog_hash(&byte_code_hash)?;
+	cert.check_ranges_hash(&backend.sha256(ranges_bytes)?)?;
+
+	let (_, ranges) = ParamRanges::from_bytes(ranges_bytes)?;
+
+	checkers.check_key(&cert.checker_pkey)?;
+
+	if !backend.verify(&cert.signed_bytes(), &cert.checker_pkey, &cert.signature)?
+	{
+		return Result::Err(format!("{}", "Failed to verify the signature from verifier."));
+	}
+
+	println!("[Enclave]: {}", "Signature from verifier is checked.");
+
+	// The policy flags are validated when the certificate is parsed.
+	if cert.policy().map(|p| p.require_contracts).unwrap_or(false)
+	{
+		println!("[Enclave]: {}", "Program is contract-verified.");
+	}
+
+	Result::Ok((prog, byte_code_hash, ranges, confidential))
+}
+
+/// Split a verified program with sealed bytecode, and open the bytecode;
+/// returns the program, its bytecode, the bytes of the ranges of its
+/// parameters, and its certificate.
+///
+fn open_verified_program<'a, B : EnclaveBackend>(backend : &B, input : &'a [u8]) -> Result<(ast::cmd::Cmd, Vec<u8>, &'a [u8], certificate::Certificate), String>
+{
+	let (sealed_byte_code, ranges_bytes, cert) = certificate::parse_sealed_verified_byte_code(input)?;
+
+	let (mr_enclave, byte_code) = program::open_program(backend, sealed_byte_code)?;
+
+	// The type-checker sealed the bytecode for the key of an interpreter of
+	// that MRENCLAVE; any other enclave holding the key refuses it.
+	if mr_enclave != backend.mr_enclave()?
+	{
+		return Result::Err(format!("{}", "The sealed bytecode is for the interpreter of another MRENCLAVE."));
+	}
+
+	// The certificate is over the bytecode as a whole.
+	let (bytes_left, prog) = ast::cmd::Cmd::from_bytes(&byte_code)?;
+	if !bytes_left.is_empty()
+	{
+		return Result::Err(format!("The sealed bytecode is followed by {} unexpected byte(s).", bytes_left.len()));
+	}
+
+	Result::Ok((prog, byte_code, ranges_bytes, cert))
+}
+
+/// Check the integer parameters of the entry call are within the ranges the
+/// program was certified for; the values aren't told, as they may be
+/// confidential.
+///
+fn check_param_ranges(ranges : &ParamRanges, param_list : &[ast::exp::Exp]) -> Result<(), String>
+{
+	use interpreter::exp::CanEvalToExpVal;
+
+	for (idx, e) in param_list.iter().enumerate()
+	{
+		if let ExpValue::A(AexpValue::Int32(v)) = e.simp_eval_to_exp_val()?
+		{
+			let range = ranges.get(idx);
+			if !range.contains(v as i64)
+			{
+				return Result::Err(format!("Parameter {} of the entry call is outside its certified range {}.", idx, range));
+			}
+		}
+	}
+
+	Result::Ok(())
+}
+
+/// Generate the program states, and make the entry call with the parameter
+/// list, once it is checked to be within the ranges of the certificate.
+/// Confidential parameters, and results, aren't printed; nor are the states
+/// of confidential programs.
+///
+fn run_entry_call(prog : &ast::cmd::Cmd, ranges : &ParamRanges, param_list : Vec<ast::exp::Exp>, oblivious : bool, confidential : bool, confidential_prog : bool) -> Result<Option<ExpValue>, String>
+{
+	check_param_ranges(ranges, &param_list)?;
+
+	let entry_call = ast::func_general::FnCall::new(format!("entry"), param_list);
+
+	// ------------------------------------------
+	// 1. Generate program states:
+	// ------------------------------------------
+	let mut prog_inter = interpreter::Program::new();
+
+	gen_prog_states(&mut prog_inter, prog)?;
+
+	if !confidential_prog
+	{
+		println!("[Enclave]:");
+		println!("========================================================");
+		println!("Program global states:");
+		println!("----------------------");
+		println!("{}", prog_inter.func_states);
+		println!("{}", prog_inter.var_states.borrow());
+		println!("========================================================");
+	}
+
+	// ------------------------------------------
+	// 2. Make entry function call:
+	// ------------------------------------------
+	if oblivious
+	{
+		interpreter::oblivious::check_program(prog)?;
+
+		println!("[Enclave]: {}", "Executing the entry call in oblivious mode.");
+	}
+
+	let func_ret = make_entry_call(&prog_inter, &entry_call, oblivious)?;
+
+	match (&func_ret, confidential)
+	{
+		(_, true)               => println!("[Enclave]: {}", "Function call entry returned; its result is sealed for the client."),
+		(Option::Some(v), false) => println!("[Enclave]: Function call {} returned {}", entry_call, v),
+		(Option::None, false)    => println!("[Enclave]: Function call {} didn't return any value.", entry_call),
+	}
+
+	Result::Ok(func_ret)
+}
+
+/// Sign the report of an execution, which takes the next number of the
+/// counter; the result is left to the caller.
+///
+fn sign_report<B : EnclaveBackend>(
+	backend : &B,
+	checkers : &TrustedCheckers,
+	param_list_hash : [u8; 32],
+	byte_code_hash : [u8; 32],
+	func_ret_hash : [u8; 32],
+	nonce : &[u8; NONCE_SIZE],
+	counter : &AtomicU64) -> Result<ExecutionReport, String>
+{
+	let mut combined_bytes : Vec<u8> = Vec::new();
+
+	combined_bytes.extend_from_slice(&param_list_hash);
+	combined_bytes.extend_from_slice(&byte_code_hash);
+	combined_bytes.extend_from_slice(&func_ret_hash);
+	combined_bytes.extend_from_slice(checkers.allowlist_hash());
+
+	let freshness = Freshness { nonce : *nonce, counter : counter.fetch_add(1, Ordering::SeqCst) };
+	combined_bytes.extend_from_slice(&freshness.to_bytes());
+
+	let report_hash = backend.sha256(&combined_bytes)?;
+	let signature = backend.sign(&report_hash)?;
+
+	Result::Ok(ExecutionReport
+	{
+		func_ret        : Option::None,
+		param_list_hash : param_list_hash,
+		byte_code_hash  : byte_code_hash,
+		func_ret_hash   : func_ret_hash,
+		allowlist_hash  : *checkers.allowlist_hash(),
+		freshness       : freshness,
+		report_hash     : report_hash,
+		signature       : signature,
+	})
+}
+
+/// Execute the entry function of a verified program with the given
+/// parameter list, once its certificate is checked, and signed by a trusted
+/// type-checker, and sign the report of the execution, with the nonce of the
+/// client. The report takes the next number of the counter, only once the
+/// execution succeeded.
+///
+pub fn execute_program<B : EnclaveBackend>(
+	backend : &B,
+	checkers : &TrustedCheckers,
+	input : &[u8],
+	param_list_input : &[u8],
+	oblivious : bool,
+	nonce : &[u8; NONCE_SIZE],
+	counter : &AtomicU64) -> Result<ExecutionReport, String>
+{
+	// ------------------------------------------
+	// 1. Check the verified program:
+	// ------------------------------------------
+	let (prog, byte_code_hash, ranges, confidential_prog) = load_verified_program(backend, checkers, input)?;
+
+	// ------------------------------------------
+	// 2. Prepare entry function call from input bytes:
+	// ------------------------------------------
+	let (param_list_bytes_left, param_list) = Vec::from_bytes(param_list_input)?;
+
+	if !param_list_bytes_left.is_empty()
+	{
+		return Result::Err(format!("The parameter list is followed by {} unexpected byte(s).", param_list_bytes_left.len()));
+	}
+
+	let param_list_hash = backend.sha256(param_list_input)?;
+
+	// ------------------------------------------
+	// 3. Make entry function call:
+	// ------------------------------------------
+	let func_ret = run_entry_call(&prog, &ranges, param_list, oblivious, false, confidential_prog)?;
+
+	let func_ret_hash = backend.sha256(&interpreter::exp::func_ret_to_bytes(&func_ret)?)?;
+
+	// ------------------------------------------
+	// 4. Generate report:
+	// ------------------------------------------
+	let report = sign_report(backend, checkers, param_list_hash, byte_code_hash, func_ret_hash, nonce, counter)?;
+
+	Result::Ok(ExecutionReport { func_ret : func_ret, ..report })
+}
+
+/// Execution in a session with a client, see `session`.
+///
+pub struct SessionExecution
+{
+	/// Report of the execution; its parameter list hash, and result hash,
+	/// are the hashes of the sealed parameters, and the sealed result, so
+	/// they tell the host nothing.
+	pub report        : ExecutionReport,
+	/// Result bytes, see `interpreter::exp::func_ret_to_bytes`, sealed for
+	/// the client.
+	pub sealed_result : Vec<u8>,
+}
+
+/// Execute the entry function of a verified program, like
+/// `execute_program`, with the parameter list sealed by a client for the key
+/// of the backend, and seal the result for the client.
+///
+pub fn execute_program_in_session<B : EnclaveBackend>(
+	backend : &B,
+	checkers : &TrustedCheckers,
+	input : &[u8],
+	sealed_params : &[u8],
+	oblivious : bool,
+	nonce : &[u8; NONCE_SIZE],
+	counter : &AtomicU64) -> Result<SessionExecution, String>
+{
+	// ------------------------------------------
+	// 1. Check the verified program:
+	// ------------------------------------------
+	let (prog, byte_code_hash, ranges, confidential_prog) = load_verified_program(backend, checkers, input)?;
+
+	// ------------------------------------------
+	// 2. Open the parameter list:
+	// ------------------------------------------
+	let (session, param_list_bytes) = EnclaveSession::open_params(backend, sealed_params)?;
+
+	let (param_list_bytes_left, param_list) = Vec::from_bytes(&param_list_bytes)?;
+
+	if !param_list_bytes_left.is_empty()
+	{
+		return Result::Err(format!("The parameter list is followed by {} unexpected byte(s).", param_list_bytes_left.len()));
+	}
+
+	println!("[Enclave]: {}", "Opened the sealed parameter list.");
+
+	// ------------------------------------------
+	// 3. Make entry function call, and seal its result:
+	// ------------------------------------------
+	let func_ret = run_entry_call(&prog, &ranges, param_list, oblivious, true, confidential_prog)?;
+
+	let sealed_result = session.seal_result(backend, &interpreter::exp::func_ret_to_bytes(&func_ret)?)?;
+
+	// ------------------------------------------
+	// 4. Generate report:
+	// ------------------------------------------
+	let report = sign_report(backend, checkers, backend.sha256(sealed_params)?, byte_code_hash, backend.sha256(&sealed_result)?, nonce, counter)?;
+
+	Result::Ok(SessionExecution
+	{
+		report        : ExecutionReport { func_ret : func_ret, ..report },
+		sealed_result : sealed_result,
+	})
+}
@@ -0,0 +1,203 @@
+//! Long-lived identity keys of the enclaves.
+//!
+//! An enclave generates its identity key pair once, seals it with its sealing
+//! key, and hands the sealed identity to the host to store. Later instances of
+//! the enclave get the sealed identity back from the host, and unseal it, so
+//! the enclave keeps signing with the same key across ECALLs and restarts,
+//! and verifiers can pin that key.
+//!
+//! The sealing key is derived from the measurement of the enclave
+//! (`SealPolicy::MrEnclave`), i.e. only the very same enclave can unseal, or
+//! from the key of its signer (`SealPolicy::MrSigner`), i.e. later versions
+//! of the enclave can unseal as well.
+
+use std::fmt;
+use std::vec::Vec;
+use std::string::String;
+
+use super::init_slot::InitSlot;
+
+/// Magic bytes a sealed identity starts with.
+pub const SEALED_IDENTITY_MAGIC : [u8; 4] = *b"VSID";
+
+/// Size of the header of a sealed identity, kept in clear, but authenticated.
+pub const SEALED_IDENTITY_HEADER_SIZE : usize = 4 + 1 + 64;
+
+/// Upper bound of the size of a sealed identity, for the buffers of the host.
+pub const SEALED_IDENTITY_MAX_SIZE : usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SealPolicy
+{
+	/// Sealed to the measurement of the enclave.
+	MrEnclave,
+	/// Sealed to the signer of the enclave.
+	MrSigner,
+}
+
+impl SealPolicy
+{
+	pub fn to_byte(&self) -> u8
+	{
+		match self
+		{
+			SealPolicy::MrEnclave => 1u8,
+			SealPolicy::MrSigner  => 2u8,
+		}
+	}
+
+	pub fn from_byte(b : u8) -> Result<SealPolicy, String>
+	{
+		match b
+		{
+			1u8 => Result::Ok(SealPolicy::MrEnclave),
+			2u8 => Result::Ok(SealPolicy::MrSigner),
+			_   => Result::Err(format!("Unknown seal policy {}.", b)),
+		}
+	}
+
+	/// Parse a seal policy, either `mrenclave` or `mrsigner`.
+	///
+	pub fn parse(s : &str) -> Result<SealPolicy, String>
+	{
+		match s.trim()
+		{
+			"mrenclave" => Result::Ok(SealPolicy::MrEnclave),
+			"mrsigner"  => Result::Ok(SealPolicy::MrSigner),
+			_           => Result::Err(format!("Unknown seal policy {}.", s)),
+		}
+	}
+}
+
+impl fmt::Display for SealPolicy
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match self
+		{
+			SealPolicy::MrEnclave => write!(f, "mrenclave"),
+			SealPolicy::MrSigner  => write!(f, "mrsigner"),
+		}
+	}
+}
+
+/// Identity key pair of an enclave, in the layout of its backend.
+///
+#[derive(Clone)]
+pub struct Identity
+{
+	pub prv_key : [u8; 32],
+	pub pub_key : [u8; 64],
+}
+
+/// Sealing, and unsealing, with the sealing key of an enclave.
+///
+pub trait Sealer
+{
+	/// Seal the data with the sealing key of the given policy; the additional
+	/// data is authenticated, but not included in the result.
+	fn seal(&self, policy : SealPolicy, aad : &[u8], data : &[u8]) -> Result<Vec<u8>, String>;
+
+	/// Unseal data sealed by `seal` with the same policy, and additional data.
+	fn unseal(&self, policy : SealPolicy, aad : &[u8], sealed : &[u8]) -> Result<Vec<u8>, String>;
+}
+
+fn header(policy : SealPolicy, pub_key : &[u8; 64]) -> Vec<u8>
+{
+	let mut res : Vec<u8> = Vec::with_capacity(SEALED_IDENTITY_HEADER_SIZE);
+
+	res.extend_from_slice(&SEALED_IDENTITY_MAGIC);
+	res.push(policy.to_byte());
+	res.extend_from_slice(pub_key);
+
+	res
+}
+
+/// The policy, and the public key, of a sealed identity, without unsealing
+/// it; for hosts to tell which key an enclave will sign with.
+///
+/// # Sealed identity layout
+/// ```text
+/// | magic "VSID" - 4 Bytes | policy - 1 Byte | pub_key - 64 Bytes | sealed private key |
+/// ```
+///
+pub fn sealed_public_key(bytes : &[u8]) -> Result<(SealPolicy, [u8; 64]), String>
+{
+	if bytes.len() < SEALED_IDENTITY_HEADER_SIZE
+	{
+		return Result::Err(format!("{}", "Failed to parse sealed identity. Bytes are shorter than expected."));
+	}
+
+	if bytes[0..4] != SEALED_IDENTITY_MAGIC
+	{
+		return Result::Err(format!("{}", "Failed to parse sealed identity. Magic bytes mismatch."));
+	}
+
+	let policy = SealPolicy::from_byte(bytes[4])?;
+
+	let mut pub_key : [u8; 64] = [0; 64];
+	pub_key.copy_from_slice(&bytes[5..SEALED_IDENTITY_HEADER_SIZE]);
+
+	Result::Ok((policy, pub_key))
+}
+
+/// Seal the identity; the public key stays readable by the host.
+///
+pub fn seal_identity<S : Sealer>(sealer : &S, policy : SealPolicy, identity : &Identity) -> Result<Vec<u8>, String>
+{
+	let mut res = header(policy, &identity.pub_key);
+
+	let mut sealed = sealer.seal(policy, &res, &identity.prv_key)?;
+	res.append(&mut sealed);
+
+	Result::Ok(res)
+}
+
+/// Unseal an identity sealed by `seal_identity`.
+///
+pub fn unseal_identity<S : Sealer>(sealer : &S, bytes : &[u8]) -> Result<(SealPolicy, Identity), String>
+{
+	let (policy, pub_key) = sealed_public_key(bytes)?;
+
+	let prv_bytes = sealer.unseal(policy, &bytes[0..SEALED_IDENTITY_HEADER_SIZE], &bytes[SEALED_IDENTITY_HEADER_SIZE..])?;
+	if prv_bytes.len() != 32
+	{
+		return Result::Err(format!("The sealed private key has {} byte(s) instead of 32.", prv_bytes.len()));
+	}
+
+	let mut prv_key : [u8; 32] = [0; 32];
+	prv_key.copy_from_slice(&prv_bytes);
+
+	Result::Ok((policy, Identity { prv_key : prv_key, pub_key : pub_key }))
+}
+
+/// Unseal the sealed identity given by the host if any, or generate a new
+/// identity and seal it with the given policy. Returns the identity, and the
+/// sealed identity for the host to store.
+///
+/// A sealed identity keeps the policy it was sealed with; a different policy
+/// is refused, rather than silently changing how the key is protected.
+///
+pub fn load_or_generate<S, F>(sealer : &S, sealed : &[u8], policy : SealPolicy, generate : F) -> Result<(Identity, Vec<u8>), String>
+	where S : Sealer, F : FnOnce() -> Result<Identity, String>
+{
+	if !sealed.is_empty()
+	{
+		let (sealed_policy, identity) = unseal_identity(sealer, sealed)?;
+		if sealed_policy != policy
+		{
+			return Result::Err(format!("The identity is sealed with policy {}, while {} is requested.", sealed_policy, policy));
+		}
+
+		return Result::Ok((identity, sealed.to_vec()));
+	}
+
+	let identity = generate()?;
+	let sealed = seal_identity(sealer, policy, &identity)?;
+
+	Result::Ok((identity, sealed))
+}
+
+/// The identity of an enclave instance, set once by its initialization ECALL
+/// and kept for the lifetime of the enclave.
+pub type IdentitySlot = InitSlot<Identity>;
@@ -0,0 +1,54 @@
+//! State of an enclave instance, set once by an initialization ECALL, e.g.
+//! its identity, and kept for the lifetime of the enclave.
+
+use std::string::String;
+use std::boxed::Box;
+use std::sync::atomic::AtomicPtr;
+use std::sync::atomic::Ordering;
+
+pub struct InitSlot<T>
+{
+	value : AtomicPtr<T>,
+}
+
+impl<T> InitSlot<T>
+{
+	pub const fn new() -> InitSlot<T>
+	{
+		InitSlot { value : AtomicPtr::new(std::ptr::null_mut()) }
+	}
+
+	/// Set the value; it can't be replaced once set.
+	///
+	pub fn set(&self, value : T) -> Result<(), String>
+	{
+		let ptr = Box::into_raw(Box::new(value));
+
+		match self.value.compare_exchange(std::ptr::null_mut(), ptr, Ordering::SeqCst, Ordering::SeqCst)
+		{
+			Result::Ok(_)  => Result::Ok(()),
+			Result::Err(_) =>
+			{
+				// Nobody else saw the pointer, so it's safe to take it back.
+				drop(unsafe { Box::from_raw(ptr) });
+				Result::Err(format!("{}", "Already initialized; it can only be set once."))
+			},
+		}
+	}
+
+	pub fn get(&self) -> Option<&T>
+	{
+		let ptr = self.value.load(Ordering::SeqCst);
+
+		// The value is never freed once set.
+		unsafe { ptr.as_ref() }
+	}
+}
+
+impl<T> Default for InitSlot<T>
+{
+	fn default() -> InitSlot<T>
+	{
+		InitSlot::new()
+	}
+}
@@ -0,0 +1,80 @@
+//! Logic of the type-checker and interpreter enclaves, independent of the SGX
+//! SDK.
+//!
+//! The ECALLs of the enclaves only marshal their buffers, and run the steps
+//! in `checker` and `executor` with an `EnclaveBackend` providing the
+//! cryptography: `sgx::SgxBackend` inside the enclaves, or
+//! `software::SoftwareBackend` on hosts, so the whole pipeline can run, and be
+//! tested, without SGX.
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::attestation::QuoteBody;
+use super::attestation::QuoteKind;
+use super::attestation::REPORT_DATA_SIZE;
+use super::session::SESSION_IV_SIZE;
+use super::session::SESSION_KEY_SIZE;
+
+pub mod checker;
+pub mod executor;
+pub mod identity;
+pub mod init_slot;
+pub mod allowlist;
+
+#[cfg(feature = "sgx_env_ver")]
+pub mod sgx;
+
+#[cfg(feature = "host_verifier")]
+pub mod software;
+
+/// Cryptography of an enclave, around its EC key pair, either generated for
+/// the ECALL, or its sealed identity.
+///
+/// Keys and signatures are in the layout of the SGX SDK, i.e. the x and y
+/// components, 32 bytes each, in little-endian; signatures are ECDSA over the
+/// P-256 curve and SHA256.
+///
+pub trait EnclaveBackend
+{
+	/// Public key of the enclave.
+	fn public_key(&self) -> [u8; 64];
+
+	fn sha256(&self, bytes : &[u8]) -> Result<[u8; 32], String>;
+
+	/// Sign the message with the private key of the enclave.
+	fn sign(&self, msg : &[u8]) -> Result<[u8; 64], String>;
+
+	/// Check a signature over the message, by the given public key.
+	fn verify(&self, msg : &[u8], pkey : &[u8; 64], sign : &[u8; 64]) -> Result<bool, String>;
+
+	/// Report, or quote, of the enclave with the given report data.
+	fn attest(&self, report_data : &[u8; REPORT_DATA_SIZE]) -> Result<(QuoteKind, Vec<u8>), String>;
+
+	/// MRENCLAVE of the enclave itself.
+	fn mr_enclave(&self) -> Result<[u8; 32], String>;
+
+	/// Check a report, or quote, of another enclave, and return its report
+	/// body; kinds the backend can't check are refused.
+	fn verify_quote(&self, quote_kind : QuoteKind, quote : &[u8]) -> Result<QuoteBody, String>;
+
+	/// ECDH shared secret of the private key of the enclave and the public
+	/// key of a peer, i.e. the x component of the shared point, in
+	/// little-endian.
+	fn shared_secret(&self, peer_pkey : &[u8; 64]) -> Result<[u8; 32], String>;
+
+	/// Generate a key pair for a single message, and return its public key,
+	/// and its ECDH shared secret with the public key of a peer; the private
+	/// key is dropped.
+	fn ephemeral_shared_secret(&self, peer_pkey : &[u8; 64]) -> Result<([u8; 64], [u8; 32]), String>;
+
+	/// Encrypt with AES-128-GCM; the result is the ciphertext followed by the
+	/// tag.
+	fn aes_gcm_encrypt(&self, key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], msg : &[u8]) -> Result<Vec<u8>, String>;
+
+	/// Decrypt, and authenticate, the result of `aes_gcm_encrypt`.
+	fn aes_gcm_decrypt(&self, key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], ciphertext : &[u8]) -> Result<Vec<u8>, String>;
+
+	/// Fill the buffer with random bytes.
+	fn random_bytes(&self, buf : &mut [u8]) -> Result<(), String>;
+}
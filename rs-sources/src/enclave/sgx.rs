@@ -0,0 +1,392 @@
+//! Enclave backend on the cryptography of the SGX SDK.
+
+use std::vec::Vec;
+use std::string::String;
+
+use sgx_types::*;
+use sgx_tcrypto::*;
+use sgx_tseal::SgxSealedData;
+
+use super::EnclaveBackend;
+use super::identity::Identity;
+use super::identity::SealPolicy;
+use super::identity::Sealer;
+use super::super::attestation::QuoteBody;
+use super::super::attestation::QuoteKind;
+use super::super::attestation::REPORT_DATA_SIZE;
+use super::super::session::SESSION_IV_SIZE;
+use super::super::session::SESSION_KEY_SIZE;
+use super::super::session::SESSION_TAG_SIZE;
+
+pub struct SgxBackend
+{
+	ecc_ctx     : SgxEccHandle,
+	prv_key     : sgx_ec256_private_t,
+	pub_key     : sgx_ec256_public_t,
+	/// Target of the reports of the backend.
+	target_info : sgx_target_info_t,
+}
+
+impl SgxBackend
+{
+	/// Backend with a fresh EC key pair.
+	///
+	pub fn new() -> Result<SgxBackend, sgx_status_t>
+	{
+		let ecc_ctx = SgxEccHandle::new();
+		ecc_ctx.open()?;
+
+		let (prv_key, pub_key) = ecc_ctx.create_key_pair()?;
+
+		Result::Ok(SgxBackend
+		{
+			ecc_ctx     : ecc_ctx,
+			prv_key     : prv_key,
+			pub_key     : pub_key,
+			target_info : sgx_target_info_t::default(),
+		})
+	}
+
+	/// Target the reports of the backend at the enclave of the target info,
+	/// e.g. the quoting enclave, which can then check them.
+	///
+	pub fn with_target_info(mut self, target_info : sgx_target_info_t) -> SgxBackend
+	{
+		self.target_info = target_info;
+
+		self
+	}
+
+	/// Target the reports at the enclave of the target info given by the
+	/// host as bytes of `sgx_target_info_t`, if any.
+	///
+	pub fn with_target_info_bytes(self, bytes : &[u8]) -> Result<SgxBackend, String>
+	{
+		if bytes.is_empty()
+		{
+			return Result::Ok(self);
+		}
+
+		if bytes.len() != std::mem::size_of::<sgx_target_info_t>()
+		{
+			return Result::Err(format!("The target info has {} byte(s) instead of {}.", bytes.len(), std::mem::size_of::<sgx_target_info_t>()));
+		}
+
+		let target_info = unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const sgx_target_info_t) };
+
+		Result::Ok(self.with_target_info(target_info))
+	}
+
+	/// Backend with the given identity key pair, e.g. unsealed by
+	/// `identity::unseal_identity`.
+	///
+	pub fn from_identity(identity : &Identity) -> Result<SgxBackend, sgx_status_t>
+	{
+		let ecc_ctx = SgxEccHandle::new();
+		ecc_ctx.open()?;
+
+		let prv_key = sgx_ec256_private_t { r : identity.prv_key };
+		let mut pub_key = sgx_ec256_public_t { gx : [0; 32], gy : [0; 32] };
+		pub_key.gx.copy_from_slice(&identity.pub_key[0..32]);
+		pub_key.gy.copy_from_slice(&identity.pub_key[32..64]);
+
+		if !ecc_ctx.check_point(&pub_key)?
+		{
+			return Result::Err(sgx_status_t::SGX_ERROR_INVALID_PARAMETER);
+		}
+
+		Result::Ok(SgxBackend
+		{
+			ecc_ctx     : ecc_ctx,
+			prv_key     : prv_key,
+			pub_key     : pub_key,
+			target_info : sgx_target_info_t::default(),
+		})
+	}
+
+	/// Identity key pair of the backend, to be sealed.
+	///
+	pub fn identity(&self) -> Identity
+	{
+		Identity { prv_key : self.prv_key.r, pub_key : self.public_key() }
+	}
+}
+
+/// Sealing with the sealing keys of the enclave, by `sgx_tseal`.
+///
+pub struct SgxSealer;
+
+impl Sealer for SgxSealer
+{
+	fn seal(&self, policy : SealPolicy, aad : &[u8], data : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let key_policy = match policy
+		{
+			SealPolicy::MrEnclave => SGX_KEYPOLICY_MRENCLAVE,
+			SealPolicy::MrSigner  => SGX_KEYPOLICY_MRSIGNER,
+		};
+		let attribute_mask = sgx_attributes_t { flags : TSEAL_DEFAULT_FLAGSMASK, xfrm : 0 };
+
+		let sealed = match SgxSealedData::<[u8]>::seal_data_ex(key_policy, attribute_mask, TSEAL_DEFAULT_MISCMASK, aad, data)
+		{
+			Result::Ok(val)  => val,
+			Result::Err(err) => return Result::Err(format!("Failed to seal. {}", err.as_str())),
+		};
+
+		let sealed_size = SgxSealedData::<[u8]>::calc_raw_sealed_data_size(aad.len() as u32, data.len() as u32);
+		if sealed_size == u32::MAX
+		{
+			return Result::Err(format!("{}", "Failed to seal. The data is too large."));
+		}
+
+		// sgx_sealed_data_t is aligned to 4 bytes, which a Vec<u8> isn't.
+		let mut buf : Vec<u32> = vec![0; (sealed_size as usize + 3) / 4];
+		let written = unsafe { sealed.to_raw_sealed_data_t(buf.as_mut_ptr() as *mut sgx_sealed_data_t, sealed_size) };
+		if written.is_none()
+		{
+			return Result::Err(format!("{}", "Failed to seal. Couldn't write the sealed data."));
+		}
+
+		let buf_bytes = unsafe { std::slice::from_raw_parts(buf.as_ptr() as *const u8, sealed_size as usize) };
+
+		Result::Ok(buf_bytes.to_vec())
+	}
+
+	/// The policy is kept in the key request of the sealed data, and isn't
+	/// needed to unseal.
+	///
+	fn unseal(&self, _policy : SealPolicy, aad : &[u8], sealed : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let mut buf : Vec<u32> = vec![0; (sealed.len() + 3) / 4];
+		unsafe { std::ptr::copy_nonoverlapping(sealed.as_ptr(), buf.as_mut_ptr() as *mut u8, sealed.len()) };
+
+		let sealed_data = match unsafe { SgxSealedData::<[u8]>::from_raw_sealed_data_t(buf.as_mut_ptr() as *mut sgx_sealed_data_t, sealed.len() as u32) }
+		{
+			Option::Some(val) => val,
+			Option::None      => return Result::Err(format!("{}", "Failed to unseal. The sealed data is malformed.")),
+		};
+
+		let unsealed = match sealed_data.unseal_data()
+		{
+			Result::Ok(val)  => val,
+			Result::Err(err) => return Result::Err(format!("Failed to unseal. {}", err.as_str())),
+		};
+
+		if unsealed.get_additional_txt() != aad
+		{
+			return Result::Err(format!("{}", "Failed to unseal. The additional data doesn't match."));
+		}
+
+		Result::Ok(unsealed.get_decrypt_txt().to_vec())
+	}
+}
+
+fn to_words(bytes : &[u8]) -> [u32; 8]
+{
+	let mut res : [u32; 8] = [0; 8];
+
+	for (i, word) in res.iter_mut().enumerate()
+	{
+		let mut word_bytes : [u8; 4] = [0; 4];
+		word_bytes.copy_from_slice(&bytes[(i * 4)..((i + 1) * 4)]);
+		*word = u32::from_le_bytes(word_bytes);
+	}
+
+	res
+}
+
+fn from_words(words : &[u32; 8]) -> [u8; 32]
+{
+	let mut res : [u8; 32] = [0; 32];
+
+	for (i, word) in words.iter().enumerate()
+	{
+		res[(i * 4)..((i + 1) * 4)].copy_from_slice(&word.to_le_bytes());
+	}
+
+	res
+}
+
+impl EnclaveBackend for SgxBackend
+{
+	fn public_key(&self) -> [u8; 64]
+	{
+		let mut res : [u8; 64] = [0; 64];
+		res[0..32].copy_from_slice(&self.pub_key.gx);
+		res[32..64].copy_from_slice(&self.pub_key.gy);
+
+		res
+	}
+
+	fn sha256(&self, bytes : &[u8]) -> Result<[u8; 32], String>
+	{
+		match rsgx_sha256_slice(bytes)
+		{
+			Result::Ok(val)  => Result::Ok(val),
+			Result::Err(err) => Result::Err(format!("Failed to compute SHA256. {}", err.as_str())),
+		}
+	}
+
+	fn sign(&self, msg : &[u8]) -> Result<[u8; 64], String>
+	{
+		let sign = match self.ecc_ctx.ecdsa_sign_slice(msg, &self.prv_key)
+		{
+			Result::Ok(val)  => val,
+			Result::Err(err) => return Result::Err(format!("Failed to sign. {}", err.as_str())),
+		};
+
+		let mut res : [u8; 64] = [0; 64];
+		res[0..32].copy_from_slice(&from_words(&sign.x));
+		res[32..64].copy_from_slice(&from_words(&sign.y));
+
+		Result::Ok(res)
+	}
+
+	fn verify(&self, msg : &[u8], pkey : &[u8; 64], sign : &[u8; 64]) -> Result<bool, String>
+	{
+		let mut sgx_pkey = sgx_ec256_public_t { gx : [0; 32], gy : [0; 32] };
+		sgx_pkey.gx.copy_from_slice(&pkey[0..32]);
+		sgx_pkey.gy.copy_from_slice(&pkey[32..64]);
+
+		let sgx_sign = sgx_ec256_signature_t { x : to_words(&sign[0..32]), y : to_words(&sign[32..64]) };
+
+		match self.ecc_ctx.ecdsa_verify_slice(msg, &sgx_pkey, &sgx_sign)
+		{
+			Result::Ok(val)  => Result::Ok(val),
+			Result::Err(err) => Result::Err(format!("Failed to verify the signature. {}", err.as_str())),
+		}
+	}
+
+	fn attest(&self, report_data : &[u8; REPORT_DATA_SIZE]) -> Result<(QuoteKind, Vec<u8>), String>
+	{
+		let mut sgx_report_data = sgx_report_data_t::default();
+		sgx_report_data.d.copy_from_slice(report_data);
+
+		let report = match sgx_tse::rsgx_create_report(&self.target_info, &sgx_report_data)
+		{
+			Result::Ok(val)  => val,
+			Result::Err(err) => return Result::Err(format!("Failed to create report. {}", err.as_str())),
+		};
+
+		// sgx_report_t is a C struct without padding, i.e. its bytes are the
+		// report as the quoting enclave expects it.
+		let report_bytes = unsafe
+		{
+			std::slice::from_raw_parts(&report as *const sgx_report_t as *const u8, std::mem::size_of::<sgx_report_t>())
+		};
+
+		Result::Ok((QuoteKind::SgxReport, report_bytes.to_vec()))
+	}
+
+	fn mr_enclave(&self) -> Result<[u8; 32], String>
+	{
+		Result::Ok(sgx_tse::rsgx_self_report().body.mr_enclave.m)
+	}
+
+	/// Only SGX reports targeted at this enclave can be checked, by local
+	/// attestation; quotes need an attestation service.
+	///
+	fn verify_quote(&self, quote_kind : QuoteKind, quote : &[u8]) -> Result<QuoteBody, String>
+	{
+		if quote_kind != QuoteKind::SgxReport
+		{
+			return Result::Err(format!("A {} can't be checked in the enclave.", quote_kind));
+		}
+
+		if quote.len() != std::mem::size_of::<sgx_report_t>()
+		{
+			return Result::Err(format!("The report has {} byte(s) instead of {}.", quote.len(), std::mem::size_of::<sgx_report_t>()));
+		}
+
+		let report = unsafe { std::ptr::read_unaligned(quote.as_ptr() as *const sgx_report_t) };
+
+		match sgx_tse::rsgx_verify_report(&report)
+		{
+			Result::Ok(_)    => QuoteBody::from_report_body(quote),
+			Result::Err(err) => Result::Err(format!("Failed to verify the report; it must target this enclave. {}", err.as_str())),
+		}
+	}
+
+	fn shared_secret(&self, peer_pkey : &[u8; 64]) -> Result<[u8; 32], String>
+	{
+		let mut sgx_pkey = sgx_ec256_public_t { gx : [0; 32], gy : [0; 32] };
+		sgx_pkey.gx.copy_from_slice(&peer_pkey[0..32]);
+		sgx_pkey.gy.copy_from_slice(&peer_pkey[32..64]);
+
+		match self.ecc_ctx.compute_shared_dhkey(&self.prv_key, &sgx_pkey)
+		{
+			Result::Ok(val)  => Result::Ok(val.s),
+			Result::Err(err) => Result::Err(format!("Failed to compute the shared secret. {}", err.as_str())),
+		}
+	}
+
+	fn ephemeral_shared_secret(&self, peer_pkey : &[u8; 64]) -> Result<([u8; 64], [u8; 32]), String>
+	{
+		let (prv_key, pub_key) = match self.ecc_ctx.create_key_pair()
+		{
+			Result::Ok(val)  => val,
+			Result::Err(err) => return Result::Err(format!("Failed to generate an ephemeral key pair. {}", err.as_str())),
+		};
+
+		let mut sgx_pkey = sgx_ec256_public_t { gx : [0; 32], gy : [0; 32] };
+		sgx_pkey.gx.copy_from_slice(&peer_pkey[0..32]);
+		sgx_pkey.gy.copy_from_slice(&peer_pkey[32..64]);
+
+		let shared = match self.ecc_ctx.compute_shared_dhkey(&prv_key, &sgx_pkey)
+		{
+			Result::Ok(val)  => val,
+			Result::Err(err) => return Result::Err(format!("Failed to compute the shared secret. {}", err.as_str())),
+		};
+
+		let mut res : [u8; 64] = [0; 64];
+		res[0..32].copy_from_slice(&pub_key.gx);
+		res[32..64].copy_from_slice(&pub_key.gy);
+
+		Result::Ok((res, shared.s))
+	}
+
+	fn aes_gcm_encrypt(&self, key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], msg : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = vec![0; msg.len() + SESSION_TAG_SIZE];
+		let mut tag : sgx_aes_gcm_128bit_tag_t = [0; SESSION_TAG_SIZE];
+
+		if let Result::Err(err) = rsgx_rijndael128GCM_encrypt(key, msg, iv, aad, &mut res[0..msg.len()], &mut tag)
+		{
+			return Result::Err(format!("Failed to encrypt. {}", err.as_str()));
+		}
+
+		res[msg.len()..].copy_from_slice(&tag);
+
+		Result::Ok(res)
+	}
+
+	fn aes_gcm_decrypt(&self, key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], ciphertext : &[u8]) -> Result<Vec<u8>, String>
+	{
+		if ciphertext.len() < SESSION_TAG_SIZE
+		{
+			return Result::Err(format!("{}", "Failed to decrypt. The ciphertext is shorter than its tag."));
+		}
+
+		let msg_len = ciphertext.len() - SESSION_TAG_SIZE;
+
+		let mut tag : sgx_aes_gcm_128bit_tag_t = [0; SESSION_TAG_SIZE];
+		tag.copy_from_slice(&ciphertext[msg_len..]);
+
+		let mut res : Vec<u8> = vec![0; msg_len];
+
+		match rsgx_rijndael128GCM_decrypt(key, &ciphertext[0..msg_len], iv, aad, &tag, &mut res[..])
+		{
+			Result::Ok(_)    => Result::Ok(res),
+			Result::Err(err) => Result::Err(format!("Failed to decrypt. The ciphertext is tampered, or for another key. {}", err.as_str())),
+		}
+	}
+
+	fn random_bytes(&self, buf : &mut [u8]) -> Result<(), String>
+	{
+		match unsafe { sgx_read_rand(buf.as_mut_ptr(), buf.len()) }
+		{
+			sgx_status_t::SGX_SUCCESS => Result::Ok(()),
+			err                       => Result::Err(format!("Failed to generate random bytes. {}", err.as_str())),
+		}
+	}
+}
@@ -0,0 +1,296 @@
+//! Enclave backend in pure Rust, for running and testing the enclave logic
+//! on hosts; it offers none of the protection of an enclave.
+//!
+//! `SoftwareSealer` stands in for SGX sealing: it derives its sealing keys
+//! from a root key, in place of the key fused into the CPU, and the
+//! measurements the software enclave claims.
+
+use std::vec::Vec;
+use std::string::String;
+
+use p256::ecdsa::Signature;
+use p256::ecdsa::SigningKey;
+use p256::ecdsa::signature::Signer;
+
+use aes_gcm::Aes256Gcm;
+use aes_gcm::Nonce;
+use aes_gcm::KeyInit;
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::Payload;
+use hkdf::Hkdf;
+use rand_core::RngCore;
+use sha2::Sha256;
+
+use super::EnclaveBackend;
+use super::identity::Identity;
+use super::identity::SealPolicy;
+use super::identity::Sealer;
+use super::super::host_verify;
+use super::super::attestation;
+use super::super::attestation::QuoteBody;
+use super::super::attestation::QuoteKind;
+use super::super::attestation::REPORT_DATA_SIZE;
+use super::super::session;
+use super::super::session::SESSION_IV_SIZE;
+use super::super::session::SESSION_KEY_SIZE;
+
+pub struct SoftwareBackend
+{
+	sign_key   : SigningKey,
+	pub_key    : [u8; 64],
+	/// Measurements reported in the mock quotes of the backend.
+	mr_enclave : [u8; 32],
+	mr_signer  : [u8; 32],
+}
+
+impl SoftwareBackend
+{
+	/// Backend with a fresh random key pair, as an enclave generates on
+	/// every ECALL.
+	///
+	pub fn generate() -> SoftwareBackend
+	{
+		Self::from_sign_key(SigningKey::random(&mut rand_core::OsRng))
+	}
+
+	/// Backend with the key pair of the given secret scalar, in big-endian;
+	/// for reproducible keys.
+	///
+	pub fn from_secret(secret : &[u8; 32]) -> Result<SoftwareBackend, String>
+	{
+		match SigningKey::from_bytes(&(*secret).into())
+		{
+			Result::Ok(val) => Result::Ok(Self::from_sign_key(val)),
+			Result::Err(_)  => Result::Err(format!("{}", "The secret isn't a valid P-256 scalar.")),
+		}
+	}
+
+	fn from_sign_key(sign_key : SigningKey) -> SoftwareBackend
+	{
+		let mut pub_key : [u8; 64] = [0; 64];
+		pub_key.copy_from_slice(&sign_key.verifying_key().to_encoded_point(false).as_bytes()[1..]);
+
+		SoftwareBackend
+		{
+			sign_key   : sign_key,
+			pub_key    : host_verify::to_sgx_layout(&pub_key),
+			mr_enclave : [0; 32],
+			mr_signer  : [0; 32],
+		}
+	}
+
+	/// Report the given measurements in the mock quotes of the backend, as
+	/// if it ran in the enclave of those measurements.
+	///
+	pub fn with_measurements(mut self, mr_enclave : [u8; 32], mr_signer : [u8; 32]) -> SoftwareBackend
+	{
+		self.mr_enclave = mr_enclave;
+		self.mr_signer = mr_signer;
+
+		self
+	}
+
+	/// Backend with the given identity key pair, e.g. unsealed by
+	/// `identity::unseal_identity`.
+	///
+	pub fn from_identity(identity : &Identity) -> Result<SoftwareBackend, String>
+	{
+		let res = Self::from_secret(&identity.prv_key)?;
+
+		if res.pub_key[..] != identity.pub_key[..]
+		{
+			return Result::Err(format!("{}", "The public key of the identity doesn't match its private key."));
+		}
+
+		Result::Ok(res)
+	}
+
+	/// Identity key pair of the backend, to be sealed.
+	///
+	pub fn identity(&self) -> Identity
+	{
+		let mut prv_key : [u8; 32] = [0; 32];
+		prv_key.copy_from_slice(&self.sign_key.to_bytes()[..]);
+
+		Identity { prv_key : prv_key, pub_key : self.pub_key }
+	}
+}
+
+/// Root key of the sealing keys of software enclaves. It is public, so
+/// software sealing protects nothing; it only exercises the same steps as
+/// SGX sealing.
+pub const SOFTWARE_SEAL_ROOT_KEY : [u8; 32] = *b"enclave_verifier seal root key..";
+
+/// Size of the nonce of AES-GCM, in bytes.
+const SEAL_NONCE_SIZE : usize = 12;
+
+pub struct SoftwareSealer
+{
+	root_key   : [u8; 32],
+	mr_enclave : [u8; 32],
+	mr_signer  : [u8; 32],
+}
+
+impl SoftwareSealer
+{
+	/// Sealer of a software enclave of the given measurements.
+	///
+	pub fn new(root_key : [u8; 32], mr_enclave : [u8; 32], mr_signer : [u8; 32]) -> SoftwareSealer
+	{
+		SoftwareSealer
+		{
+			root_key   : root_key,
+			mr_enclave : mr_enclave,
+			mr_signer  : mr_signer,
+		}
+	}
+
+	/// Sealing key of the policy, `HKDF-SHA256(root_key, "seal" | policy | measurement)`.
+	///
+	fn sealing_key(&self, policy : SealPolicy) -> Result<[u8; 32], String>
+	{
+		let mut info : Vec<u8> = b"seal".to_vec();
+		info.push(policy.to_byte());
+		match policy
+		{
+			SealPolicy::MrEnclave => info.extend_from_slice(&self.mr_enclave),
+			SealPolicy::MrSigner  => info.extend_from_slice(&self.mr_signer),
+		}
+
+		let mut res : [u8; 32] = [0; 32];
+		match Hkdf::<Sha256>::new(None, &self.root_key).expand(&info, &mut res)
+		{
+			Result::Ok(_)  => Result::Ok(res),
+			Result::Err(_) => Result::Err(format!("{}", "Failed to derive the sealing key.")),
+		}
+	}
+}
+
+impl Sealer for SoftwareSealer
+{
+	/// # Sealed data layout
+	/// ```text
+	/// | nonce - 12 Bytes | AES-256-GCM ciphertext and tag |
+	/// ```
+	///
+	fn seal(&self, policy : SealPolicy, aad : &[u8], data : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let cipher = Aes256Gcm::new(&self.sealing_key(policy)?.into());
+
+		let mut nonce : [u8; SEAL_NONCE_SIZE] = [0; SEAL_NONCE_SIZE];
+		rand_core::OsRng.fill_bytes(&mut nonce);
+
+		let mut ciphertext = match cipher.encrypt(&Nonce::from(nonce), Payload { msg : data, aad : aad })
+		{
+			Result::Ok(val)  => val,
+			Result::Err(_)   => return Result::Err(format!("{}", "Failed to seal.")),
+		};
+
+		let mut res : Vec<u8> = nonce.to_vec();
+		res.append(&mut ciphertext);
+
+		Result::Ok(res)
+	}
+
+	fn unseal(&self, policy : SealPolicy, aad : &[u8], sealed : &[u8]) -> Result<Vec<u8>, String>
+	{
+		if sealed.len() < SEAL_NONCE_SIZE
+		{
+			return Result::Err(format!("{}", "Failed to unseal. Bytes are shorter than expected."));
+		}
+
+		let cipher = Aes256Gcm::new(&self.sealing_key(policy)?.into());
+
+		let mut nonce : [u8; SEAL_NONCE_SIZE] = [0; SEAL_NONCE_SIZE];
+		nonce.copy_from_slice(&sealed[0..SEAL_NONCE_SIZE]);
+
+		match cipher.decrypt(&Nonce::from(nonce), Payload { msg : &sealed[SEAL_NONCE_SIZE..], aad : aad })
+		{
+			Result::Ok(val)  => Result::Ok(val),
+			Result::Err(_)   => Result::Err(format!("{}", "Failed to unseal. The data is sealed by another enclave, or tampered.")),
+		}
+	}
+}
+
+impl EnclaveBackend for SoftwareBackend
+{
+	fn public_key(&self) -> [u8; 64]
+	{
+		self.pub_key
+	}
+
+	fn sha256(&self, bytes : &[u8]) -> Result<[u8; 32], String>
+	{
+		Result::Ok(host_verify::sha256(bytes))
+	}
+
+	fn sign(&self, msg : &[u8]) -> Result<[u8; 64], String>
+	{
+		let sign : Signature = self.sign_key.sign(msg);
+
+		let mut res : [u8; 64] = [0; 64];
+		res.copy_from_slice(&sign.to_bytes()[..]);
+
+		Result::Ok(host_verify::to_sgx_layout(&res))
+	}
+
+	fn verify(&self, msg : &[u8], pkey : &[u8; 64], sign : &[u8; 64]) -> Result<bool, String>
+	{
+		Result::Ok(host_verify::verify_signature(msg, pkey, sign).is_ok())
+	}
+
+	fn attest(&self, report_data : &[u8; REPORT_DATA_SIZE]) -> Result<(QuoteKind, Vec<u8>), String>
+	{
+		let body = attestation::QuoteBody
+		{
+			mr_enclave  : self.mr_enclave,
+			mr_signer   : self.mr_signer,
+			report_data : *report_data,
+		};
+
+		Result::Ok((QuoteKind::Mock, attestation::mock::mock_quote(&body)))
+	}
+
+	fn mr_enclave(&self) -> Result<[u8; 32], String>
+	{
+		Result::Ok(self.mr_enclave)
+	}
+
+	/// Only mock quotes can be checked without SGX.
+	///
+	fn verify_quote(&self, quote_kind : QuoteKind, quote : &[u8]) -> Result<QuoteBody, String>
+	{
+		match quote_kind
+		{
+			QuoteKind::Mock => attestation::mock::verify_mock_quote(quote),
+			_               => Result::Err(format!("A {} can't be checked by a software enclave.", quote_kind)),
+		}
+	}
+
+	fn shared_secret(&self, peer_pkey : &[u8; 64]) -> Result<[u8; 32], String>
+	{
+		session::client::shared_secret(self.sign_key.as_nonzero_scalar(), peer_pkey)
+	}
+
+	fn ephemeral_shared_secret(&self, peer_pkey : &[u8; 64]) -> Result<([u8; 64], [u8; 32]), String>
+	{
+		session::client::ephemeral_shared_secret(peer_pkey)
+	}
+
+	fn aes_gcm_encrypt(&self, key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], msg : &[u8]) -> Result<Vec<u8>, String>
+	{
+		session::client::aes_gcm_encrypt(key, iv, aad, msg)
+	}
+
+	fn aes_gcm_decrypt(&self, key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], ciphertext : &[u8]) -> Result<Vec<u8>, String>
+	{
+		session::client::aes_gcm_decrypt(key, iv, aad, ciphertext)
+	}
+
+	fn random_bytes(&self, buf : &mut [u8]) -> Result<(), String>
+	{
+		rand_core::OsRng.fill_bytes(buf);
+
+		Result::Ok(())
+	}
+}
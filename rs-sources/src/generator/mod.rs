@@ -0,0 +1,3 @@
+pub mod rng;
+pub mod random;
+pub mod shrink;
@@ -0,0 +1,492 @@
+//! Generation of random well-typed programs, e.g. for fuzzing the type
+//! checker, the interpreter and the deserializer.
+//!
+//! The programs generated are accepted by the type checker: variables are
+//! declared, and given a value, before they're used; functions are declared
+//! at global scope, and only call the functions declared before them, so
+//! they aren't recursive; and the overloads of a function have different
+//! parameter types, and are called with arguments of exactly these types.
+//! Every loop counts up to a small bound, so the programs terminate.
+
+use std::rc::Rc;
+
+use std::vec::Vec;
+use std::boxed::Box;
+use std::string::String;
+use std::string::ToString;
+
+use super::super::ast::aexp::Aexp;
+use super::super::ast::bexp::Bexp;
+use super::super::ast::cmd::Cmd;
+use super::super::ast::data_type::DataType;
+use super::super::ast::exp::Exp;
+use super::super::ast::func_general::FnCall;
+use super::super::ast::func_general::FnProtoType;
+use super::super::ast::var_general::VarDecl;
+use super::super::ast::var_general::VarRef;
+
+use super::rng::Rng;
+
+const VAL_TYPES : [DataType; 3] = [DataType::Int32, DataType::Float32, DataType::Bool];
+
+/// Maximum depth of the expressions generated.
+const MAX_EXP_DEPTH : usize = 3;
+
+/// Maximum number of iterations of the loops generated.
+const MAX_LOOP_ITER : i32 = 3;
+
+/// Maximum number of parameters of the functions generated.
+const MAX_PARAMS : usize = 3;
+
+/// A program generated, with the argument lists to call its `entry` function
+/// with.
+///
+pub struct GeneratedProgram
+{
+	pub prog        : Cmd,
+	pub param_lists : Vec<Vec<Exp>>,
+}
+
+struct Var
+{
+	name     : String,
+	t        : DataType,
+	/// Loop counters are read, but never assigned, by the loop bodies.
+	writable : bool,
+}
+
+struct Generator
+{
+	rng     : Rng,
+	next_id : usize,
+	/// Functions declared so far.
+	funcs   : Vec<Rc<FnProtoType>>,
+	/// Variables in scope, the global ones first and the innermost ones last.
+	scopes  : Vec<Vec<Var>>,
+}
+
+/// Generate a program of about `size` commands from `seed`; the same size
+/// and seed always generate the same program.
+///
+pub fn generate(size : usize, seed : u64) -> GeneratedProgram
+{
+	let mut gen = Generator { rng : Rng::new(seed), next_id : 0, funcs : Vec::new(), scopes : vec![Vec::new()] };
+
+	let num_funcs = gen.rng.below(size / 10 + 1) + 1;
+	let budget = size / (num_funcs + 1) + 1;
+
+	// Global variables.
+	let mut cmds : Vec<Cmd> = Vec::new();
+	for _ in 0..gen.rng.below(3)
+	{
+		cmds.push(gen.var_decl());
+	}
+
+	// Functions, the last one of which is the entry function.
+	for i in 0..num_funcs
+	{
+		let prototype = if i + 1 < num_funcs { gen.prototype() } else { gen.entry_prototype() };
+		let body = gen.fn_body(&prototype, if i + 1 < num_funcs { budget } else { budget * 2 });
+		let prototype = Rc::new(prototype);
+
+		gen.funcs.push(prototype.clone());
+		cmds.push(Cmd::FnDecl{ prototype : prototype, fn_cmd : Rc::new(body) });
+	}
+
+	let entry = gen.funcs.last().unwrap().clone();
+	let param_lists = (0..2).map(|_|
+		entry.var_decl_list.iter().map(|d| gen.constant(d.var_type)).collect()).collect();
+
+	GeneratedProgram { prog : seq_all(cmds), param_lists : param_lists }
+}
+
+/// Commands in sequence.
+///
+fn seq_all(cmds : Vec<Cmd>) -> Cmd
+{
+	cmds.into_iter().rev().fold(Option::None, |snd : Option<Cmd>, fst| match snd
+	{
+		Option::Some(snd) => Option::Some(Cmd::Seq{ fst_cmd : Box::new(fst), snd_cmd : Box::new(snd) }),
+		Option::None      => Option::Some(fst),
+	}).unwrap_or(Cmd::Skip)
+}
+
+impl Generator
+{
+	fn fresh_name(&mut self, prefix : &str) -> String
+	{
+		self.next_id += 1;
+		format!("{}{}", prefix, self.next_id)
+	}
+
+	fn val_type(&mut self) -> DataType
+	{
+		*self.rng.choose(&VAL_TYPES)
+	}
+
+	fn vars_of_type(&self, t : DataType, writable : bool) -> Vec<(String, DataType)>
+	{
+		self.scopes.iter().flat_map(|s| s.iter())
+			.filter(|v| (v.t == t) && (v.writable || !writable))
+			.map(|v| (v.name.clone(), v.t))
+			.collect()
+	}
+
+	fn funcs_returning(&self, t : DataType) -> Vec<Rc<FnProtoType>>
+	{
+		self.funcs.iter().filter(|f| f.ret_type == t).cloned().collect()
+	}
+
+	/// A prototype whose name is either new, or the one of a function
+	/// declared before, with parameter types different from the ones of each
+	/// overload of it.
+	fn prototype(&mut self) -> FnProtoType
+	{
+		let ret_type = if self.rng.chance(1, 4) { DataType::Void } else { self.val_type() };
+		let num_params = self.rng.below(MAX_PARAMS + 1);
+		let param_types : Vec<DataType> = (0..num_params).map(|_| self.val_type()).collect();
+
+		let mut name = self.fresh_name("f");
+		if !self.funcs.is_empty() && self.rng.chance(1, 3)
+		{
+			let overloaded = self.rng.choose(&self.funcs).name.clone();
+			let is_new = !self.funcs.iter().any(|f|
+				f.name == overloaded && f.var_decl_list.iter().map(|d| d.var_type).eq(param_types.iter().cloned()));
+			if is_new
+			{
+				name = overloaded;
+			}
+		}
+
+		let params = param_types.into_iter().map(|t| VarDecl::new(t, self.fresh_name("p"))).collect();
+		FnProtoType::new(ret_type, name, params)
+	}
+
+	fn entry_prototype(&mut self) -> FnProtoType
+	{
+		let ret_type = self.val_type();
+		let num_params = self.rng.below(MAX_PARAMS + 1);
+		let params = (0..num_params).map(|_|
+		{
+			let t = self.val_type();
+			VarDecl::new(t, self.fresh_name("p"))
+		}).collect();
+
+		FnProtoType::new(ret_type, "entry".to_string(), params)
+	}
+
+	/// The body of a function, which ends with a return of a value of its
+	/// return type.
+	fn fn_body(&mut self, prototype : &FnProtoType, budget : usize) -> Cmd
+	{
+		let params = prototype.var_decl_list.iter()
+			.map(|d| Var { name : d.name.clone(), t : d.var_type, writable : true }).collect();
+		self.scopes.push(params);
+
+		let mut cmds = vec![self.block_cmds(prototype.ret_type, budget)];
+		if prototype.ret_type != DataType::Void
+		{
+			let e = self.exp(prototype.ret_type, 0);
+			cmds.push(Cmd::Return{ e : Option::Some(Box::new(e)) });
+		}
+
+		self.scopes.pop();
+
+		seq_all(cmds)
+	}
+
+	/// Commands of a block, whose variables are out of scope after it.
+	fn block(&mut self, ret_type : DataType, budget : usize) -> Cmd
+	{
+		self.scopes.push(Vec::new());
+		let cmd = self.block_cmds(ret_type, budget);
+		self.scopes.pop();
+
+		cmd
+	}
+
+	fn block_cmds(&mut self, ret_type : DataType, budget : usize) -> Cmd
+	{
+		let mut cmds : Vec<Cmd> = Vec::new();
+		let mut budget = budget;
+
+		while budget > 0
+		{
+			let sub_budget = self.rng.below(budget) + 1;
+			budget -= sub_budget;
+			cmds.push(self.cmd(ret_type, sub_budget));
+		}
+
+		seq_all(cmds)
+	}
+
+	/// A command using about `budget` commands, in a function returning
+	/// `ret_type`.
+	fn cmd(&mut self, ret_type : DataType, budget : usize) -> Cmd
+	{
+		let choice = if budget > 2 { self.rng.below(7) } else { self.rng.below(4) };
+
+		match choice
+		{
+			0 | 1 => self.var_decl(),
+			2     => self.assign(),
+			3     =>
+			{
+				match self.fn_call(Option::None, 0)
+				{
+					Option::Some(fc) => Cmd::FnCall{ fc : fc },
+					Option::None     => self.var_decl(),
+				}
+			},
+			4 | 5 =>
+			{
+				let cond = self.bexp(0);
+				let tr_cmd = self.block(ret_type, budget / 2);
+				let mut fa_cmd = self.block(ret_type, budget / 2);
+				if self.rng.chance(1, 4)
+				{
+					// An early return from the function.
+					let e = match ret_type
+					{
+						DataType::Void => Option::None,
+						_              => Option::Some(Box::new(self.exp(ret_type, 0))),
+					};
+					fa_cmd = Cmd::Seq{ fst_cmd : Box::new(fa_cmd), snd_cmd : Box::new(Cmd::Return{ e : e }) };
+				}
+
+				Cmd::IfElse{ cond : Box::new(cond), tr_cmd : Box::new(tr_cmd), fa_cmd : Box::new(fa_cmd) }
+			},
+			_     => self.while_loop(ret_type, budget),
+		}
+	}
+
+	/// Declaration of a new variable, followed by an assignment to it.
+	fn var_decl(&mut self) -> Cmd
+	{
+		let t = self.val_type();
+		let name = self.fresh_name("v");
+		let e = self.assigned_exp(t);
+
+		self.scopes.last_mut().unwrap().push(Var { name : name.clone(), t : t, writable : true });
+
+		Cmd::Seq{
+			fst_cmd : Box::new(Cmd::VarDecl{ d : Box::new(VarDecl::new(t, name.clone())) }),
+			snd_cmd : Box::new(Cmd::Assign{ var : Box::new(VarRef::from_str(&name)), e : Box::new(e) }),
+		}
+	}
+
+	fn assign(&mut self) -> Cmd
+	{
+		let t = self.val_type();
+		let vars = self.vars_of_type(t, true);
+		if vars.is_empty()
+		{
+			return self.var_decl();
+		}
+
+		let (name, _) = self.rng.choose(&vars).clone();
+		let e = self.assigned_exp(t);
+
+		Cmd::Assign{ var : Box::new(VarRef::from_str(&name)), e : Box::new(e) }
+	}
+
+	/// `Int32 c; c = 0; while c < n bound n { body; c = c + 1; }`
+	fn while_loop(&mut self, ret_type : DataType, budget : usize) -> Cmd
+	{
+		let counter = self.fresh_name("c");
+		let num_iter = self.rng.range_i32(1, MAX_LOOP_ITER);
+
+		self.scopes.last_mut().unwrap().push(Var { name : counter.clone(), t : DataType::Int32, writable : false });
+
+		let counter_var = || Aexp::Var{ v : VarRef::from_str(&counter) };
+		let body = self.block(ret_type, budget - 1);
+		let incr = Cmd::Assign{
+			var : Box::new(VarRef::from_str(&counter)),
+			e   : Box::new(Exp::A{ e : Aexp::Add{ l : Box::new(counter_var()), r : Box::new(Aexp::IntConst{ v : 1 }) } }),
+		};
+		let cond = Bexp::Lt{ l : Box::new(counter_var()), r : Box::new(Aexp::IntConst{ v : num_iter }) };
+
+		seq_all(vec![
+			Cmd::VarDecl{ d : Box::new(VarDecl::new(DataType::Int32, counter.clone())) },
+			Cmd::Assign{ var : Box::new(VarRef::from_str(&counter)), e : Box::new(Exp::A{ e : Aexp::IntConst{ v : 0 } }) },
+			Cmd::WhileLoop{
+				cond       : Box::new(cond),
+				lp_cmd     : Box::new(Cmd::Seq{ fst_cmd : Box::new(body), snd_cmd : Box::new(incr) }),
+				bound      : Option::Some(num_iter as u64),
+				invariants : Vec::new(),
+			},
+		])
+	}
+
+	/// An expression assigned to a variable of type `t`, i.e. of type `t`, or
+	/// of type `Int32` promoted to `Float32`.
+	fn assigned_exp(&mut self, t : DataType) -> Exp
+	{
+		if t == DataType::Float32 && self.rng.chance(1, 4)
+		{
+			self.exp(DataType::Int32, 0)
+		}
+		else
+		{
+			self.exp(t, 0)
+		}
+	}
+
+	fn constant(&mut self, t : DataType) -> Exp
+	{
+		let v = self.rng.range_i32(-9, 9);
+		match t
+		{
+			DataType::Int32   => Exp::A{ e : Aexp::IntConst{ v : v } },
+			DataType::Float32 => Exp::A{ e : Aexp::FloConst{ v : v as f32 / 2.0 } },
+			_                 => Exp::B{ e : Bexp::BoolConst{ v : v >= 0 } },
+		}
+	}
+
+	/// An expression of exactly the type `t`.
+	fn exp(&mut self, t : DataType, depth : usize) -> Exp
+	{
+		match t
+		{
+			DataType::Bool => Exp::B{ e : self.bexp(depth) },
+			_              => Exp::A{ e : self.aexp(t, depth) },
+		}
+	}
+
+	/// A call to a function returning `ret_type`, or to any function if
+	/// `ret_type` is `None`, with arguments of exactly the types of its
+	/// parameters, so that the call resolves to it.
+	fn fn_call(&mut self, ret_type : Option<DataType>, depth : usize) -> Option<FnCall>
+	{
+		let funcs = match ret_type
+		{
+			Option::Some(t) => self.funcs_returning(t),
+			Option::None    => self.funcs.clone(),
+		};
+		if funcs.is_empty()
+		{
+			return Option::None;
+		}
+
+		let func = self.rng.choose(&funcs).clone();
+		let args = func.var_decl_list.iter().map(|d| self.exp(d.var_type, depth + 1)).collect();
+
+		Option::Some(FnCall::new(func.name.clone(), args))
+	}
+
+	fn aexp_leaf(&mut self, t : DataType) -> Aexp
+	{
+		let vars = self.vars_of_type(t, false);
+		if !vars.is_empty() && self.rng.chance(1, 2)
+		{
+			let (name, _) = self.rng.choose(&vars).clone();
+			return Aexp::Var{ v : VarRef::from_str(&name) };
+		}
+
+		match self.constant(t)
+		{
+			Exp::A{ e } => e,
+			Exp::B{ .. } => Aexp::IntConst{ v : 0 },
+		}
+	}
+
+	/// An arithmetic expression of exactly the type `t`; the divisors are
+	/// non-zero constants, and integers are only multiplied by small ones.
+	fn aexp(&mut self, t : DataType, depth : usize) -> Aexp
+	{
+		if depth >= MAX_EXP_DEPTH || self.rng.chance(1, 3)
+		{
+			return self.aexp_leaf(t);
+		}
+
+		let divisor = self.rng.range_i32(1, 5);
+		match (t, self.rng.below(6))
+		{
+			(DataType::Int32, 0) => Aexp::Add{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(self.aexp(t, depth + 1)) },
+			(DataType::Int32, 1) => Aexp::Sub{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(self.aexp(t, depth + 1)) },
+			(DataType::Int32, 2) => Aexp::Mul{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(Aexp::IntConst{ v : divisor % 3 }) },
+			(DataType::Int32, 3) => Aexp::Div{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(Aexp::IntConst{ v : divisor }) },
+			(DataType::Int32, 4) => Aexp::Mod{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(Aexp::IntConst{ v : divisor }) },
+			(_, 3)               => Aexp::Div{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(Aexp::FloConst{ v : divisor as f32 / 2.0 }) },
+			(_, 4)               =>
+			{
+				// A Float32 operand with an Int32 one, which is promoted.
+				let l = self.aexp(t, depth + 1);
+				let r = self.aexp(DataType::Int32, depth + 1);
+				if self.rng.chance(1, 2)
+				{
+					Aexp::Add{ l : Box::new(l), r : Box::new(r) }
+				}
+				else
+				{
+					Aexp::Sub{ l : Box::new(r), r : Box::new(l) }
+				}
+			},
+			(_, 0)               => Aexp::Add{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(self.aexp(t, depth + 1)) },
+			(_, 1)               => Aexp::Mul{ l : Box::new(self.aexp(t, depth + 1)), r : Box::new(self.aexp(t, depth + 1)) },
+			_                    =>
+			{
+				match self.fn_call(Option::Some(t), depth)
+				{
+					Option::Some(fc) => Aexp::FnCall{ fc : fc },
+					Option::None     => self.aexp_leaf(t),
+				}
+			},
+		}
+	}
+
+	fn bexp_leaf(&mut self) -> Bexp
+	{
+		let vars = self.vars_of_type(DataType::Bool, false);
+		if !vars.is_empty() && self.rng.chance(1, 2)
+		{
+			let (name, _) = self.rng.choose(&vars).clone();
+			return Bexp::Var{ v : VarRef::from_str(&name) };
+		}
+
+		Bexp::BoolConst{ v : self.rng.chance(1, 2) }
+	}
+
+	fn bexp(&mut self, depth : usize) -> Bexp
+	{
+		if depth >= MAX_EXP_DEPTH || self.rng.chance(1, 4)
+		{
+			return self.bexp_leaf();
+		}
+
+		let choice = self.rng.below(7);
+		match choice
+		{
+			0 => Bexp::Not{ e : Box::new(self.bexp(depth + 1)) },
+			1 => Bexp::And{ l : Box::new(self.bexp(depth + 1)), r : Box::new(self.bexp(depth + 1)) },
+			2 => Bexp::Or { l : Box::new(self.bexp(depth + 1)), r : Box::new(self.bexp(depth + 1)) },
+			3 => Bexp::Beq{ l : Box::new(self.bexp(depth + 1)), r : Box::new(self.bexp(depth + 1)) },
+			4 =>
+			{
+				match self.fn_call(Option::Some(DataType::Bool), depth)
+				{
+					Option::Some(fc) => Bexp::FnCall{ fc : fc },
+					Option::None     => self.bexp_leaf(),
+				}
+			},
+			_ =>
+			{
+				// Comparison of two numbers, of any of the number types.
+				let l_t = if self.rng.chance(1, 2) { DataType::Int32 } else { DataType::Float32 };
+				let r_t = if self.rng.chance(1, 2) { DataType::Int32 } else { DataType::Float32 };
+				let l = Box::new(self.aexp(l_t, depth + 1));
+				let r = Box::new(self.aexp(r_t, depth + 1));
+
+				match self.rng.below(6)
+				{
+					0 => Bexp::Aeq { l : l, r : r },
+					1 => Bexp::Aneq{ l : l, r : r },
+					2 => Bexp::Lt  { l : l, r : r },
+					3 => Bexp::Lte { l : l, r : r },
+					4 => Bexp::Gt  { l : l, r : r },
+					_ => Bexp::Gte { l : l, r : r },
+				}
+			},
+		}
+	}
+}
@@ -0,0 +1,54 @@
+/// Pseudo-random number generator (SplitMix64); the numbers it generates
+/// only depend on its seed, so a program generated from a seed can be
+/// generated again from the same seed.
+///
+pub struct Rng
+{
+	state : u64,
+}
+
+impl Rng
+{
+	pub fn new(seed : u64) -> Rng
+	{
+		Rng { state : seed }
+	}
+
+	pub fn next_u64(&mut self) -> u64
+	{
+		self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+
+	/// A number in `[0, n)`; `n` must not be 0.
+	///
+	pub fn below(&mut self, n : usize) -> usize
+	{
+		(self.next_u64() % (n as u64)) as usize
+	}
+
+	/// A number in `[lo, hi]`.
+	///
+	pub fn range_i32(&mut self, lo : i32, hi : i32) -> i32
+	{
+		lo + (self.next_u64() % ((hi - lo) as u64 + 1)) as i32
+	}
+
+	/// True with the probability `num / den`.
+	///
+	pub fn chance(&mut self, num : u64, den : u64) -> bool
+	{
+		self.next_u64() % den < num
+	}
+
+	/// An item of a non-empty slice.
+	///
+	pub fn choose<'a, T>(&mut self, items : &'a [T]) -> &'a T
+	{
+		&items[self.below(items.len())]
+	}
+}
@@ -0,0 +1,267 @@
+//! Shrinking of programs, e.g. of the counterexamples found by fuzzing,
+//! into smaller programs which still fail the same way.
+
+use std::rc::Rc;
+use std::boxed::Box;
+
+use std::vec::Vec;
+
+use super::super::ast::aexp;
+use super::super::ast::bexp;
+use super::super::ast::cmd;
+use super::super::ast::data_type::DataType;
+use super::super::ast::exp;
+use super::super::ast::func_general;
+use super::super::ast::IndentString;
+use super::super::ast::indent_lines_to_string;
+use super::super::type_checker::type_checker;
+
+/// Rewrites of single nodes, from which `cmd_variants` derives the rewrites
+/// of whole programs, one node at a time.
+///
+pub struct Rewrites
+{
+	pub cmd  : fn(&cmd::Cmd) -> Vec<cmd::Cmd>,
+	pub exp  : fn(&exp::Exp) -> Vec<exp::Exp>,
+	pub aexp : fn(&aexp::Aexp) -> Vec<aexp::Aexp>,
+	pub bexp : fn(&bexp::Bexp) -> Vec<bexp::Bexp>,
+}
+
+/// Operands of a binary operator, and the constructor of the operator.
+///
+type Binary<'a, O, E> = (&'a O, &'a O, fn(Box<O>, Box<O>) -> E);
+
+/// The programs obtained by rewriting one node of the program.
+///
+pub fn cmd_variants(c : &cmd::Cmd, rw : &Rewrites) -> Vec<cmd::Cmd>
+{
+	use cmd::Cmd;
+
+	let mut out = (rw.cmd)(c);
+
+	match c
+	{
+		Cmd::Skip | Cmd::VarDecl{..} | Cmd::Return{e : Option::None} => {},
+		Cmd::Assign{var, e} =>
+			out.extend(exp_variants(e, rw).into_iter().map(|e| Cmd::Assign{ var : var.clone(), e : Box::new(e) })),
+		Cmd::FnCall{fc} =>
+			out.extend(fn_call_variants(fc, rw).into_iter().map(|fc| Cmd::FnCall{ fc })),
+		Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+		{
+			out.extend(bexp_variants(cond, rw).into_iter().map(|cond|
+				Cmd::IfElse{ cond : Box::new(cond), tr_cmd : tr_cmd.clone(), fa_cmd : fa_cmd.clone() }));
+			out.extend(cmd_variants(tr_cmd, rw).into_iter().map(|tr|
+				Cmd::IfElse{ cond : cond.clone(), tr_cmd : Box::new(tr), fa_cmd : fa_cmd.clone() }));
+			out.extend(cmd_variants(fa_cmd, rw).into_iter().map(|fa|
+				Cmd::IfElse{ cond : cond.clone(), tr_cmd : tr_cmd.clone(), fa_cmd : Box::new(fa) }));
+		},
+		Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
+		{
+			out.extend(bexp_variants(cond, rw).into_iter().map(|cond|
+				Cmd::WhileLoop{ cond : Box::new(cond), lp_cmd : lp_cmd.clone(), bound : *bound, invariants : invariants.clone() }));
+			out.extend(cmd_variants(lp_cmd, rw).into_iter().map(|lp|
+				Cmd::WhileLoop{ cond : cond.clone(), lp_cmd : Box::new(lp), bound : *bound, invariants : invariants.clone() }));
+		},
+		Cmd::Seq{fst_cmd, snd_cmd} =>
+		{
+			out.extend(cmd_variants(fst_cmd, rw).into_iter().map(|fst|
+				Cmd::Seq{ fst_cmd : Box::new(fst), snd_cmd : snd_cmd.clone() }));
+			out.extend(cmd_variants(snd_cmd, rw).into_iter().map(|snd|
+				Cmd::Seq{ fst_cmd : fst_cmd.clone(), snd_cmd : Box::new(snd) }));
+		},
+		Cmd::FnDecl{prototype, fn_cmd} =>
+			out.extend(cmd_variants(fn_cmd, rw).into_iter().map(|body|
+				Cmd::FnDecl{ prototype : prototype.clone(), fn_cmd : Rc::new(body) })),
+		Cmd::Return{e : Option::Some(e)} =>
+			out.extend(exp_variants(e, rw).into_iter().map(|e| Cmd::Return{ e : Option::Some(Box::new(e)) })),
+		Cmd::Assert{cond} =>
+			out.extend(bexp_variants(cond, rw).into_iter().map(|cond| Cmd::Assert{ cond : Box::new(cond) })),
+	}
+
+	out
+}
+
+fn fn_call_variants(fc : &func_general::FnCall, rw : &Rewrites) -> Vec<func_general::FnCall>
+{
+	let mut out = Vec::new();
+
+	for (i, e) in fc.exp_list.iter().enumerate()
+	{
+		for v in exp_variants(e, rw).into_iter()
+		{
+			let mut fc = fc.clone();
+			fc.exp_list[i] = v;
+			out.push(fc);
+		}
+	}
+
+	out
+}
+
+fn exp_variants(e : &exp::Exp, rw : &Rewrites) -> Vec<exp::Exp>
+{
+	let mut out = (rw.exp)(e);
+
+	match e
+	{
+		exp::Exp::A{e} => out.extend(aexp_variants(e, rw).into_iter().map(|e| exp::Exp::A{ e })),
+		exp::Exp::B{e} => out.extend(bexp_variants(e, rw).into_iter().map(|e| exp::Exp::B{ e })),
+	}
+
+	out
+}
+
+fn aexp_variants(a : &aexp::Aexp, rw : &Rewrites) -> Vec<aexp::Aexp>
+{
+	use aexp::Aexp;
+
+	let mut out = (rw.aexp)(a);
+
+	let (l, r, rebuild) : Binary<Aexp, Aexp> = match a
+	{
+		Aexp::Add{l, r} => (l, r, |l, r| Aexp::Add{ l, r }),
+		Aexp::Sub{l, r} => (l, r, |l, r| Aexp::Sub{ l, r }),
+		Aexp::Mul{l, r} => (l, r, |l, r| Aexp::Mul{ l, r }),
+		Aexp::Div{l, r} => (l, r, |l, r| Aexp::Div{ l, r }),
+		Aexp::Mod{l, r} => (l, r, |l, r| Aexp::Mod{ l, r }),
+		Aexp::FnCall{fc} =>
+		{
+			out.extend(fn_call_variants(fc, rw).into_iter().map(|fc| Aexp::FnCall{ fc }));
+			return out;
+		},
+		_ => return out,
+	};
+
+	out.extend(aexp_variants(l, rw).into_iter().map(|v| rebuild(Box::new(v), Box::new(r.clone()))));
+	out.extend(aexp_variants(r, rw).into_iter().map(|v| rebuild(Box::new(l.clone()), Box::new(v))));
+
+	out
+}
+
+fn bexp_variants(b : &bexp::Bexp, rw : &Rewrites) -> Vec<bexp::Bexp>
+{
+	use aexp::Aexp;
+	use bexp::Bexp;
+
+	let mut out = (rw.bexp)(b);
+
+	let (l, r, rebuild) : Binary<Bexp, Bexp> = match b
+	{
+		Bexp::Beq{l, r}  => (l, r, |l, r| Bexp::Beq{ l, r }),
+		Bexp::Bneq{l, r} => (l, r, |l, r| Bexp::Bneq{ l, r }),
+		Bexp::And{l, r}  => (l, r, |l, r| Bexp::And{ l, r }),
+		Bexp::Or{l, r}   => (l, r, |l, r| Bexp::Or{ l, r }),
+		Bexp::Not{e}     =>
+		{
+			out.extend(bexp_variants(e, rw).into_iter().map(|e| Bexp::Not{ e : Box::new(e) }));
+			return out;
+		},
+		Bexp::FnCall{fc} =>
+		{
+			out.extend(fn_call_variants(fc, rw).into_iter().map(|fc| Bexp::FnCall{ fc }));
+			return out;
+		},
+		_ =>
+		{
+			let (l, r, rebuild) : Binary<Aexp, Bexp> = match b
+			{
+				Bexp::Aeq{l, r}  => (l, r, |l, r| Bexp::Aeq{ l, r }),
+				Bexp::Aneq{l, r} => (l, r, |l, r| Bexp::Aneq{ l, r }),
+				Bexp::Lt{l, r}   => (l, r, |l, r| Bexp::Lt{ l, r }),
+				Bexp::Lte{l, r}  => (l, r, |l, r| Bexp::Lte{ l, r }),
+				Bexp::Gt{l, r}   => (l, r, |l, r| Bexp::Gt{ l, r }),
+				Bexp::Gte{l, r}  => (l, r, |l, r| Bexp::Gte{ l, r }),
+				_                => return out,
+			};
+
+			out.extend(aexp_variants(l, rw).into_iter().map(|v| rebuild(Box::new(v), Box::new(r.clone()))));
+			out.extend(aexp_variants(r, rw).into_iter().map(|v| rebuild(Box::new(l.clone()), Box::new(v))));
+			return out;
+		},
+	};
+
+	out.extend(bexp_variants(l, rw).into_iter().map(|v| rebuild(Box::new(v), Box::new(r.clone()))));
+	out.extend(bexp_variants(r, rw).into_iter().map(|v| rebuild(Box::new(l.clone()), Box::new(v))));
+
+	out
+}
+
+/// Reductions of a node into a smaller one: a command into one of its
+/// sub-commands or `Skip`, and an expression into one of its operands or a
+/// constant.
+///
+pub const REDUCTIONS : Rewrites = Rewrites
+{
+	cmd : |c| match c
+	{
+		cmd::Cmd::Skip                      => vec![],
+		cmd::Cmd::Seq{fst_cmd, snd_cmd}     => vec![(**fst_cmd).clone(), (**snd_cmd).clone(), cmd::Cmd::Skip],
+		cmd::Cmd::IfElse{tr_cmd, fa_cmd, ..} => vec![(**tr_cmd).clone(), (**fa_cmd).clone(), cmd::Cmd::Skip],
+		cmd::Cmd::WhileLoop{lp_cmd, ..}     => vec![(**lp_cmd).clone(), cmd::Cmd::Skip],
+		_                                   => vec![cmd::Cmd::Skip],
+	},
+	exp : |_| vec![],
+	aexp : |a| match a
+	{
+		aexp::Aexp::Add{l, r} | aexp::Aexp::Sub{l, r} | aexp::Aexp::Mul{l, r} |
+		aexp::Aexp::Div{l, r} | aexp::Aexp::Mod{l, r} => vec![(**l).clone(), (**r).clone()],
+		aexp::Aexp::FnCall{..}                        => vec![aexp::Aexp::IntConst{ v : 0 }],
+		_ => vec![],
+	},
+	bexp : |b| match b
+	{
+		bexp::Bexp::Beq{l, r} | bexp::Bexp::Bneq{l, r} |
+		bexp::Bexp::And{l, r} | bexp::Bexp::Or{l, r} => vec![(**l).clone(), (**r).clone()],
+		bexp::Bexp::Not{e}                           => vec![(**e).clone()],
+		bexp::Bexp::BoolConst{..}                    => vec![],
+		_                                            => vec![bexp::Bexp::BoolConst{ v : true }],
+	},
+};
+
+
+/// Size of a program, as the length of its source.
+///
+pub fn size(prog : &cmd::Cmd) -> usize
+{
+	let mut lines : Vec<IndentString> = Vec::new();
+	prog.to_indent_lines(&mut lines);
+	indent_lines_to_string(&lines, '\t').len()
+}
+
+
+/// Reduce the program, as long as one of its reductions still fails, into a
+/// program none of whose reductions fails.
+///
+pub fn minimize<F>(prog : cmd::Cmd, fails : F) -> cmd::Cmd
+	where F : Fn(&cmd::Cmd) -> bool
+{
+	let mut prog = prog;
+
+	loop
+	{
+		let prog_size = size(&prog);
+		match cmd_variants(&prog, &REDUCTIONS).into_iter().find(|p| size(p) < prog_size && fails(p))
+		{
+			Option::Some(p) => prog = p,
+			Option::None    => return prog,
+		}
+	}
+}
+
+fn type_checks(prog : &cmd::Cmd) -> bool
+{
+	let mut glvar_types : Vec<type_checker::VarTypePair> = Vec::new();
+	let mut fn_types : Vec<type_checker::FuncIdentifierTuple> = Vec::new();
+
+	type_checker::gather_fn_types(prog, &mut glvar_types, &mut fn_types).is_ok() &&
+		type_checker::iterate_through_ast(prog.clone(), true, Vec::new(), &fn_types, DataType::Void).is_ok()
+}
+
+/// Same as `minimize`, but only through well-typed programs, so that a
+/// well-typed program is shrunk into a well-typed one.
+///
+pub fn shrink<F>(prog : cmd::Cmd, fails : F) -> cmd::Cmd
+	where F : Fn(&cmd::Cmd) -> bool
+{
+	minimize(prog, |p| type_checks(p) && fails(p))
+}
@@ -0,0 +1,393 @@
+//! Verification of the signatures of the enclaves, on hosts without the SGX
+//! SDK.
+//!
+//! The enclaves sign with ECDSA over the P-256 curve and SHA256, but keys and
+//! signatures are laid out as the SGX SDK stores them: the x and y components,
+//! 32 bytes each, in little-endian. They are converted to big-endian here
+//! before being handed to the `p256` crate.
+//!
+//! Two kinds of signatures are checked:
+//! - the certificate of a verified program (`.vimpc`), signed by the
+//!   type-checker enclave over `Certificate::signed_bytes`; for programs
+//!   with sealed bytecode, only the interpreter enclave can check that the
+//!   certificate is for the bytecode;
+//! - the report of an execution, signed by the interpreter enclave over
+//!   `SHA256(SHA256(param_list) | SHA256(byte_code) | SHA256(func_ret) |
+//!   SHA256(allowlist) | nonce | counter)`, where the allowlist is the one of
+//!   the type-checkers provisioned to the interpreter, and the nonce the one
+//!   of the client. For executions in a session, see `session`, the sealed
+//!   parameters, and the sealed result, stand in for the parameter list, and
+//!   the result.
+//!
+//! The keys of the enclaves are bound to the enclaves by their evidence,
+//! checked by `verify_evidence`; its quote is authenticated as the
+//! `EvidencePolicy` of the caller allows.
+
+use std::vec::Vec;
+use std::string::String;
+
+use p256::PublicKey;
+use p256::ecdsa::Signature;
+use p256::ecdsa::VerifyingKey;
+use p256::ecdsa::signature::Verifier;
+use sha2::Digest;
+use sha2::Sha256;
+
+use super::ast::cmd::Cmd;
+use super::ast::exp::Exp;
+use super::ast::Deserializible;
+use super::certificate;
+use super::certificate::Certificate;
+use super::enclave::executor::Freshness;
+use super::enclave::executor::ReportBody;
+use super::enclave::executor::NONCE_SIZE;
+use super::session::program;
+use super::attestation;
+use super::attestation::Evidence;
+use super::attestation::QuoteBody;
+use super::attestation::QuoteKind;
+
+pub fn sha256(bytes : &[u8]) -> [u8; 32]
+{
+	let mut res : [u8; 32] = [0; 32];
+	res.copy_from_slice(&Sha256::digest(bytes)[..]);
+
+	res
+}
+
+/// Reverse each 32-byte half of a key or a signature, i.e. convert between
+/// the little-endian layout of SGX and the big-endian one of SEC1.
+///
+fn swap_halves_endian(bytes : &[u8; 64]) -> [u8; 64]
+{
+	let mut res : [u8; 64] = [0; 64];
+
+	for i in 0..32
+	{
+		res[i]      = bytes[31 - i];
+		res[32 + i] = bytes[63 - i];
+	}
+
+	res
+}
+
+/// Convert a P-256 public key, or signature, in the big-endian layout of
+/// SEC1 to the little-endian layout of SGX.
+///
+pub fn to_sgx_layout(bytes : &[u8; 64]) -> [u8; 64]
+{
+	swap_halves_endian(bytes)
+}
+
+/// Parse a P-256 public key in the layout of SGX.
+///
+pub fn parse_public_key(pkey : &[u8; 64]) -> Result<PublicKey, String>
+{
+	let mut point : Vec<u8> = vec![0x04u8];
+	point.extend_from_slice(&swap_halves_endian(pkey));
+
+	match PublicKey::from_sec1_bytes(&point[..])
+	{
+		Result::Ok(val)  => Result::Ok(val),
+		Result::Err(_)   => Result::Err(format!("{}", "The public key isn't a point on the P-256 curve.")),
+	}
+}
+
+/// Verify the ECDSA-SHA256 signature over the message; the key and the
+/// signature are in the layout of SGX.
+///
+pub fn verify_signature(msg : &[u8], pkey : &[u8; 64], sign : &[u8; 64]) -> Result<(), String>
+{
+	let vrfy_key = VerifyingKey::from(parse_public_key(pkey)?);
+	let sign_be = swap_halves_endian(sign);
+
+	let signature = match Signature::from_slice(&sign_be[..])
+	{
+		Result::Ok(val)  => val,
+		Result::Err(_)   => return Result::Err(format!("{}", "The signature is malformed.")),
+	};
+
+	match vrfy_key.verify(msg, &signature)
+	{
+		Result::Ok(_)    => Result::Ok(()),
+		Result::Err(_)   => Result::Err(format!("{}", "The signature doesn't match.")),
+	}
+}
+
+/// Verify a verified program, i.e. check that its certificate is for its
+/// bytecode, and the ranges of its parameters, and is signed by the checker
+/// key in the certificate.
+///
+/// The checker key is only as trustworthy as the way it was obtained; callers
+/// should compare it with the key of a checker they trust.
+///
+pub fn verify_certificate(bytes : &[u8]) -> Result<(Cmd, Certificate), String>
+{
+	let (prog, byte_code, ranges_bytes, cert) = certificate::parse_verified_byte_code(bytes)?;
+
+	cert.check_prog_hash(&sha256(byte_code))?;
+	cert.check_ranges_hash(&sha256(ranges_bytes))?;
+
+	match verify_signature(&cert.signed_bytes(), &cert.checker_pkey, &cert.signature)
+	{
+		Result::Ok(_)    => Result::Ok((prog, cert)),
+		Result::Err(why) => Result::Err(format!("Invalid certificate signature. {}", why)),
+	}
+}
+
+/// Verify the certificate of a verified program with sealed bytecode, see
+/// `session::program`, like `verify_certificate`. The certificate is over
+/// the bytecode in clear, which the host can't check; the interpreter
+/// enclave does, once it opened the bytecode. The ranges are in clear.
+///
+pub fn verify_sealed_certificate(bytes : &[u8]) -> Result<Certificate, String>
+{
+	let (_, ranges_bytes, cert) = certificate::parse_sealed_verified_byte_code(bytes)?;
+
+	cert.check_ranges_hash(&sha256(ranges_bytes))?;
+
+	match verify_signature(&cert.signed_bytes(), &cert.checker_pkey, &cert.signature)
+	{
+		Result::Ok(_)    => Result::Ok(cert),
+		Result::Err(why) => Result::Err(format!("Invalid certificate signature. {}", why)),
+	}
+}
+
+/// The hash of the bytecode of a verified program, as in the reports of its
+/// executions; for sealed bytecode, the hash in its certificate, which the
+/// interpreter enclave checks.
+///
+pub fn verified_program_hash(bytes : &[u8]) -> Result<[u8; 32], String>
+{
+	if program::is_sealed_program(bytes)
+	{
+		let (_, _, cert) = certificate::parse_sealed_verified_byte_code(bytes)?;
+
+		Result::Ok(cert.prog_hash)
+	}
+	else
+	{
+		let (_, byte_code, _, _) = certificate::parse_verified_byte_code(bytes)?;
+
+		Result::Ok(sha256(byte_code))
+	}
+}
+
+/// The hash signed in an execution report, `SHA256(SHA256(param_list) |
+/// SHA256(byte_code) | SHA256(func_ret) | SHA256(allowlist) | nonce |
+/// counter)`, with the bytecode hash given by `verified_program_hash`.
+///
+/// The parameter list must not be followed by any byte, as the enclave
+/// refuses to execute it otherwise.
+///
+pub fn report_hash(param_bytes : &[u8], byte_code_hash : &[u8; 32], ret_bytes : &[u8], allowlist_bytes : &[u8], freshness : &Freshness) -> Result<[u8; 32], String>
+{
+	let (param_bytes_left, _) = Vec::<Exp>::from_bytes(param_bytes)?;
+
+	if !param_bytes_left.is_empty()
+	{
+		return Result::Err(format!("The parameter list is followed by {} unexpected byte(s).", param_bytes_left.len()));
+	}
+
+	Result::Ok(combine_report_hash(param_bytes, byte_code_hash, ret_bytes, allowlist_bytes, freshness))
+}
+
+/// The hash signed in the report of an execution in a session, see
+/// `session`, i.e. `report_hash` over the sealed parameters, and the sealed
+/// result, as a whole.
+///
+pub fn session_report_hash(sealed_params : &[u8], byte_code_hash : &[u8; 32], sealed_result : &[u8], allowlist_bytes : &[u8], freshness : &Freshness) -> [u8; 32]
+{
+	combine_report_hash(sealed_params, byte_code_hash, sealed_result, allowlist_bytes, freshness)
+}
+
+fn combine_report_hash(param_bytes : &[u8], byte_code_hash : &[u8; 32], ret_bytes : &[u8], allowlist_bytes : &[u8], freshness : &Freshness) -> [u8; 32]
+{
+	let mut combined_bytes : Vec<u8> = Vec::new();
+
+	combined_bytes.extend_from_slice(&sha256(param_bytes));
+	combined_bytes.extend_from_slice(byte_code_hash);
+	combined_bytes.extend_from_slice(&sha256(ret_bytes));
+	combined_bytes.extend_from_slice(&sha256(allowlist_bytes));
+	combined_bytes.extend_from_slice(&freshness.to_bytes());
+
+	sha256(&combined_bytes)
+}
+
+/// Verify the report of an execution of a verified program, with the given
+/// parameters, and result bytes, as given by
+/// `interpreter::exp::func_ret_to_bytes`, by an interpreter provisioned with
+/// the given allowlist of type-checkers, for the nonce the client chose.
+///
+/// The counter is the one reported by the enclave; clients keeping the last
+/// counter they saw from an enclave instance can also check it increases.
+///
+pub fn verify_report(bytes : &[u8], param_bytes : &[u8], ret_bytes : &[u8], allowlist_bytes : &[u8], freshness : &Freshness, enclave_pkey : &[u8; 64], sign : &[u8; 64]) -> Result<(), String>
+{
+	let hash = report_hash(param_bytes, &verified_program_hash(bytes)?, ret_bytes, allowlist_bytes, freshness)?;
+
+	match verify_signature(&hash, enclave_pkey, sign)
+	{
+		Result::Ok(_)    => Result::Ok(()),
+		Result::Err(why) => Result::Err(format!("Invalid report signature. {}", why)),
+	}
+}
+
+/// Verify the report of an execution in a session, like `verify_report`,
+/// with the sealed parameters, and the sealed result, as the host relayed
+/// them; the client then opens the result with its session.
+///
+pub fn verify_session_report(bytes : &[u8], sealed_params : &[u8], sealed_result : &[u8], allowlist_bytes : &[u8], freshness : &Freshness, enclave_pkey : &[u8; 64], sign : &[u8; 64]) -> Result<(), String>
+{
+	let hash = session_report_hash(sealed_params, &verified_program_hash(bytes)?, sealed_result, allowlist_bytes, freshness);
+
+	match verify_signature(&hash, enclave_pkey, sign)
+	{
+		Result::Ok(_)    => Result::Ok(()),
+		Result::Err(why) => Result::Err(format!("Invalid report signature. {}", why)),
+	}
+}
+
+/// Verify a report body, as output by the interpreter enclave, and its
+/// signature, given the report hash of the execution the client expects,
+/// see `report_hash`, or `session_report_hash`, with the freshness of the
+/// body, and the nonce of the client.
+///
+pub fn verify_report_body(body : &ReportBody, hash : &[u8; 32], nonce : &[u8; NONCE_SIZE], enclave_pkey : &[u8; 64], sign : &[u8; 64]) -> Result<(), String>
+{
+	if body.freshness.nonce != *nonce
+	{
+		return Result::Err(format!("{}", "The report is for another nonce."));
+	}
+
+	if body.report_hash != *hash
+	{
+		return Result::Err(format!("{}", "The report is for another execution."));
+	}
+
+	match verify_signature(hash, enclave_pkey, sign)
+	{
+		Result::Ok(_)    => Result::Ok(()),
+		Result::Err(why) => Result::Err(format!("Invalid report signature. {}", why)),
+	}
+}
+
+/// Check of the signature of an SGX quote, e.g. by DCAP quote verification,
+/// or an attestation report of IAS, given the quote.
+///
+pub type QuoteVerifier<'a> = &'a dyn Fn(&[u8]) -> Result<(), String>;
+
+/// How `verify_evidence` authenticates the quote of evidence.
+///
+#[derive(Clone, Copy)]
+pub struct EvidencePolicy<'a>
+{
+	/// Accept mock quotes. Their key is public, so anyone can make one, for
+	/// any enclave and any key: only for testing without SGX.
+	pub allow_mock : bool,
+	/// Check of the signature of SGX quotes; SGX quotes are refused without it.
+	pub verify_quote : Option<QuoteVerifier<'a>>,
+}
+
+impl<'a> EvidencePolicy<'a>
+{
+	/// Accept SGX quotes checked by `verify_quote` only.
+	pub fn sgx(verify_quote : QuoteVerifier<'a>) -> EvidencePolicy<'a>
+	{
+		EvidencePolicy { allow_mock : false, verify_quote : Option::Some(verify_quote) }
+	}
+
+	/// Accept mock quotes only.
+	pub fn mock() -> EvidencePolicy<'a>
+	{
+		EvidencePolicy { allow_mock : true, verify_quote : Option::None }
+	}
+}
+
+/// Verify that evidence binds its public key to an enclave, of the given
+/// MRENCLAVE if any, and return the report body of its quote.
+///
+/// The quote is authenticated as the policy allows, and refused otherwise:
+/// mock quotes only if they are allowed, and SGX quotes only by the quote
+/// verifier of the policy. SGX reports are always refused, as their MAC can
+/// only be checked by the enclave they target, e.g. the quoting enclave.
+///
+pub fn verify_evidence(evidence : &Evidence, mr_enclave : Option<&[u8; 32]>, policy : &EvidencePolicy) -> Result<QuoteBody, String>
+{
+	let body = match (evidence.quote_kind, policy.verify_quote)
+	{
+		(QuoteKind::Mock, _) if policy.allow_mock => attestation::mock::verify_mock_quote(&evidence.quote[..])?,
+		(QuoteKind::Mock, _)                      => return Result::Err(format!("{}", "Mock quotes aren't accepted; they prove nothing about the enclave.")),
+		(QuoteKind::SgxReport, _)                 => return Result::Err(format!("{}", "SGX reports can't be authenticated on the host; a quote is needed.")),
+		(QuoteKind::SgxQuote, Option::Some(verify_quote)) =>
+		{
+			if let Result::Err(why) = verify_quote(&evidence.quote[..])
+			{
+				return Result::Err(format!("Invalid SGX quote. {}", why));
+			}
+			evidence.quote_body()?
+		},
+		(QuoteKind::SgxQuote, Option::None)       => return Result::Err(format!("{}", "SGX quotes can't be authenticated without a quote verifier.")),
+	};
+
+	if body.report_data != attestation::bind_report_data(&sha256(&evidence.pub_key))
+	{
+		return Result::Err(format!("The {} doesn't commit to the public key of the evidence.", evidence.quote_kind));
+	}
+
+	if let Option::Some(expected) = mr_enclave
+	{
+		if body.mr_enclave != *expected
+		{
+			return Result::Err(format!("The {} is for another enclave.", evidence.quote_kind));
+		}
+	}
+
+	Result::Ok(body)
+}
+
+/// Encode a key or a signature as printed by the enclaves, i.e. the base64
+/// of the x component immediately followed by the base64 of the y component.
+///
+pub fn encode_b64_pair(bytes : &[u8; 64]) -> String
+{
+	use base64::Engine;
+	use base64::engine::general_purpose::STANDARD;
+
+	format!("{}{}", STANDARD.encode(&bytes[0..32]), STANDARD.encode(&bytes[32..64]))
+}
+
+/// Decode a key or a signature as printed by the enclaves, i.e. the base64
+/// of the x component immediately followed by the base64 of the y component.
+///
+pub fn decode_b64_pair(s : &str) -> Result<[u8; 64], String>
+{
+	use base64::Engine;
+	use base64::engine::general_purpose::STANDARD;
+
+	let s = s.trim().trim_end_matches('.');
+	if s.len() != 88 || !s.is_char_boundary(44)
+	{
+		return Result::Err(format!("Expecting 88 base64 characters, while {} are given.", s.len()));
+	}
+
+	let mut res : [u8; 64] = [0; 64];
+
+	for (i, part) in [&s[0..44], &s[44..88]].iter().enumerate()
+	{
+		let part_bytes = match STANDARD.decode(part)
+		{
+			Result::Ok(val)  => val,
+			Result::Err(why) => return Result::Err(format!("Invalid base64 {}. {}", part, why)),
+		};
+
+		if part_bytes.len() != 32
+		{
+			return Result::Err(format!("Invalid base64 {}. Expecting 32 bytes.", part));
+		}
+
+		res[(i * 32)..((i + 1) * 32)].copy_from_slice(&part_bytes);
+	}
+
+	Result::Ok(res)
+}
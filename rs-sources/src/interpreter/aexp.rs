@@ -10,6 +10,7 @@ use super::super::ast::aexp;
 use super::super::ast::data_type;
 use super::super::ast::states::FuncStatesStack;
 use super::super::ast::states::VarStatesStack;
+use super::super::typing;
 
 use super::exp::ExpValue;
 use super::states;
@@ -116,6 +117,24 @@ impl AexpValue
 			AexpValue::Float32(_) => self,
 		}
 	}
+
+	/// Promote the value to the given type, following `typing::promotes_to`.
+	///
+	pub fn promote_to(self, t : &data_type::DataType) -> Result<AexpValue, String>
+	{
+		if self.get_type() == *t
+		{
+			Result::Ok(self)
+		}
+		else if typing::promotes_to(&self.get_type(), t)
+		{
+			Result::Ok(self.promote_to_flo32())
+		}
+		else
+		{
+			Result::Err(format!("Value of {} type can't be promoted to {} type.", self.get_type(), t))
+		}
+	}
 }
 
 impl super::exp::CanConvertToExpVal for AexpValue
@@ -349,36 +368,41 @@ impl CanEvalToAexpVal for aexp::Aexp
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val + r_val)
+				Result::Ok(l_val.promote_to(&t)? + r_val.promote_to(&t)?)
 			},
 			Aexp::Sub{l, r} =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val - r_val)
+				Result::Ok(l_val.promote_to(&t)? - r_val.promote_to(&t)?)
 			},
 			Aexp::Mul{l, r} =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val * r_val)
+				Result::Ok(l_val.promote_to(&t)? * r_val.promote_to(&t)?)
 			},
 			Aexp::Div{l, r} =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val / r_val)
+				Result::Ok(l_val.promote_to(&t)? / r_val.promote_to(&t)?)
 			},
 			Aexp::Mod{l, r} =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val % r_val)
+				Result::Ok(l_val.promote_to(&t)? % r_val.promote_to(&t)?)
 			},
 			Aexp::Var{v} =>
 			{
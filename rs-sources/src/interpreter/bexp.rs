@@ -5,6 +5,7 @@ use std::string::String;
 use super::super::ast::bexp;
 use super::super::ast::states::FuncStatesStack;
 use super::super::ast::states::VarStatesStack;
+use super::super::typing;
 
 use super::exp::ExpValue;
 use super::states;
@@ -82,43 +83,49 @@ impl CanEvalToBexpVal for bexp::Bexp
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val == r_val)
+				Result::Ok(l_val.promote_to(&t)? == r_val.promote_to(&t)?)
 			},
 			Bexp::Aneq{ l, r }   =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val != r_val)
+				Result::Ok(l_val.promote_to(&t)? != r_val.promote_to(&t)?)
 			},
 			Bexp::Lt  { l, r }   =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val < r_val)
+				Result::Ok(l_val.promote_to(&t)? < r_val.promote_to(&t)?)
 			},
 			Bexp::Lte { l, r }   =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val <= r_val)
+				Result::Ok(l_val.promote_to(&t)? <= r_val.promote_to(&t)?)
 			},
 			Bexp::Gt  { l, r }   =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val > r_val)
+				Result::Ok(l_val.promote_to(&t)? > r_val.promote_to(&t)?)
 			},
 			Bexp::Gte { l, r }   =>
 			{
 				let l_val = l.eval_to_aexp_val(func_states, var_states)?;
 				let r_val = r.eval_to_aexp_val(func_states, var_states)?;
+				let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
 
-				Result::Ok(l_val >= r_val)
+				Result::Ok(l_val.promote_to(&t)? >= r_val.promote_to(&t)?)
 			},
 			Bexp::Var { v }      =>
 			{
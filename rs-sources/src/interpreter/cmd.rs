@@ -88,9 +88,10 @@ impl CanEvalToExpVal for cmd::Cmd
 					return fa_cmd.eval_to_exp_val(&mut inner_func_states, &mut inner_var_states)
 				}
 			},
-			Cmd::WhileLoop{ cond, lp_cmd }         =>
+			Cmd::WhileLoop{ cond, lp_cmd, bound, .. }  =>
 			{
 				let mut cond_val = cond.eval_to_bexp_val(func_states, var_states)?;
+				let mut iter_count : u64 = 0;
 
 				// {
 				// 	println!("[DEBUG]: Original Var states:\n{}\n-----END-----", var_states.borrow());
@@ -98,6 +99,15 @@ impl CanEvalToExpVal for cmd::Cmd
 
 				while cond_val
 				{
+					if let Option::Some(max_iter) = bound
+					{
+						if iter_count >= *max_iter
+						{
+							return Result::Err(format!("While loop exceeded its declared bound of {} iterations.", max_iter));
+						}
+					}
+					iter_count += 1;
+
 					let mut inner_func_states = Rc::new(FuncStatesStack::new_level(func_states.clone()));
 					let mut inner_var_states  = Rc::new(RefCell::new(VarStatesStack::new_level(var_states.clone())));
 
@@ -149,6 +159,13 @@ impl CanEvalToExpVal for cmd::Cmd
 				}
 
 			},
+			Cmd::Assert   { cond }                 =>
+			{
+				if !cond.eval_to_bexp_val(func_states, var_states)?
+				{
+					return Result::Err(format!("Assertion {} failed.", cond));
+				}
+			},
 		}
 
 		return Result::Ok(Option::None)
@@ -66,19 +66,16 @@ impl ExpValue
 				},
 				1u8 =>
 				{
-					if bytes.len() > 2
+					let res_val = if bytes[1] == 0
 					{
-						let res_val = if bytes[1] == 0
-						{
-							false
-						}
-						else
-						{
-							true
-						};
-
-						return Result::Ok((&bytes[2..], ExpValue::B(res_val)))
+						false
 					}
+					else
+					{
+						true
+					};
+
+					return Result::Ok((&bytes[2..], ExpValue::B(res_val)))
 				},
 				_   => {},
 			}
@@ -127,6 +124,18 @@ impl ExpValue
 		}
 	}
 
+	/// Promote the value to the given type, following `typing::promotes_to`.
+	///
+	pub fn promote_to(self, t : &data_type::DataType) -> Result<ExpValue, String>
+	{
+		match self
+		{
+			ExpValue::A(val)                         => Result::Ok(ExpValue::A(val.promote_to(t)?)),
+			ExpValue::B(_) if *t == data_type::DataType::Bool => Result::Ok(self),
+			ExpValue::B(_)                           => Result::Err(format!("Value of {} type can't be promoted to {} type.", self.get_type(), t)),
+		}
+	}
+
 	pub fn to_bexp_val(self) -> Result<bool, String>
 	{
 		match self
@@ -137,6 +146,55 @@ impl ExpValue
 	}
 }
 
+/// Upper bound of the size of the bytes of the result of a function call,
+/// i.e. an arithmetic value, for the buffers of the host.
+pub const FUNC_RET_MAX_SIZE : usize = 1 + 1 + 1 + 4;
+
+/// Bytes of the result of a function call, as hashed into execution reports;
+/// a byte 1 followed by the bytes of the value, or a byte 0 if the function
+/// didn't return any value.
+///
+pub fn func_ret_to_bytes(res : &Option<ExpValue>) -> Result<Vec<u8>, String>
+{
+	let mut res_vec : Vec<u8> = Vec::new();
+
+	match res
+	{
+		Option::Some(v) =>
+		{
+			res_vec.push(1u8);
+			res_vec.append(&mut (v.to_bytes()?));
+		},
+		Option::None    =>
+		{
+			res_vec.push(0u8);
+		},
+	}
+
+	Result::Ok(res_vec)
+}
+
+/// Parse the bytes of the result of a function call, see `func_ret_to_bytes`.
+///
+pub fn func_ret_from_bytes(bytes : &[u8]) -> Result<(&[u8], Option<ExpValue>), String>
+{
+	if bytes.is_empty()
+	{
+		return Result::Err(format!("{}", "Failed to parse function call result. Bytes are shorter than expected."));
+	}
+
+	match bytes[0]
+	{
+		0u8 => Result::Ok((&bytes[1..], Option::None)),
+		1u8 =>
+		{
+			let (bytes_left, v) = ExpValue::from_bytes(&bytes[1..])?;
+			Result::Ok((bytes_left, Option::Some(v)))
+		},
+		_   => Result::Err(format!("Failed to parse function call result. Unknown flag {}.", bytes[0])),
+	}
+}
+
 impl super::aexp::CanConvertToAexpVal for ExpValue
 {
 	fn to_aexp_val(self) -> Result<super::aexp::AexpValue, String>
@@ -21,3 +21,6 @@ pub mod bexp;
 pub mod exp;
 pub mod states;
 pub mod cmd;
+pub mod oblivious;
+pub mod typed;
+pub mod unit_test;
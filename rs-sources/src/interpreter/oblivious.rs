@@ -0,0 +1,732 @@
+//! Oblivious execution mode of the interpreter.
+//!
+//! In this mode the sequence of executed AST nodes and the variables being
+//! read and written do not depend on the values of the entry parameters:
+//!
+//! * both arms of an `if` are always executed; each arm runs under an
+//!   *active* flag, and every assignment is turned into
+//!   `x = select(active, new_value, old_value)`, which merges the variable
+//!   states of the two arms;
+//! * a `while` loop must carry a declared bound and its body is always
+//!   executed exactly `bound` times, with iterations after the loop
+//!   condition became false being inactive;
+//! * `return` only records the returned value (again with a select), and the
+//!   rest of the function body keeps running inactively.
+//!
+//! Whether a variable has been initialized is tracked by a hidden flag, which
+//! is updated with a select as well, so an assignment always writes the
+//! variable, even when it hasn't been initialized yet.
+//!
+//! Runtime faults (e.g. division by zero, reading an uninitialized variable)
+//! on an inactive path are replaced by a dummy value, since that path would
+//! not have been taken by the normal interpreter. Faults on an active path
+//! are reported as errors, just like the normal interpreter does.
+//!
+//! Since every call is executed regardless of the branch it is in, the base
+//! case of a recursion would not stop it, so recursive programs are rejected
+//! by `check_program` before being executed; `MAX_CALL_DEPTH` only guards the
+//! nesting of calls of programs that weren't checked.
+//!
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::vec::Vec;
+use std::string::String;
+
+use super::super::ast::aexp;
+use super::super::ast::bexp;
+use super::super::ast::exp;
+use super::super::ast::cmd;
+use super::super::ast::data_type;
+use super::super::ast::func_general;
+use super::super::ast::var_general::VarDecl;
+use super::super::ast::states::AnyFunc;
+use super::super::ast::states::FuncStatesStack;
+use super::super::ast::states::VarStatesStack;
+use super::super::analyzer::call_graph;
+use super::super::typing;
+
+use super::aexp::AexpValue;
+use super::aexp::CanConvertToAexpVal;
+use super::exp::ExpValue;
+use super::states::FuncState;
+use super::states::VarState;
+
+/// Maximum depth of nested function calls in oblivious mode.
+///
+pub const MAX_CALL_DEPTH : usize = 64;
+
+/// Checks the program can be executed obliviously, i.e. it has no recursive
+/// function; returns an error naming them otherwise.
+///
+pub fn check_program(prog : &cmd::Cmd) -> Result<(), String>
+{
+	let graph = call_graph::CallGraph::from_program(prog)?;
+
+	let recursive : Vec<String> = graph.recursive_sccs().into_iter()
+		.flatten()
+		.map(|i| call_graph::signature(&graph.funcs[i]))
+		.collect();
+
+	if !recursive.is_empty()
+	{
+		return Result::Err(format!("Recursive functions can't be executed obliviously: {}.", recursive.join(", ")));
+	}
+
+	Result::Ok(())
+}
+
+/// Selects `a` if `c` is true, or `b` otherwise, without branching on `c`.
+///
+pub fn select_i32(c : bool, a : i32, b : i32) -> i32
+{
+	let mask = (c as i32).wrapping_neg();
+	(a & mask) | (b & !mask)
+}
+
+/// Selects `a` if `c` is true, or `b` otherwise, without branching on `c`.
+///
+pub fn select_f32(c : bool, a : f32, b : f32) -> f32
+{
+	let mask = (c as u32).wrapping_neg();
+	f32::from_bits((a.to_bits() & mask) | (b.to_bits() & !mask))
+}
+
+/// Selects `a` if `c` is true, or `b` otherwise, without branching on `c`.
+///
+pub fn select_bool(c : bool, a : bool, b : bool) -> bool
+{
+	(c & a) | (!c & b)
+}
+
+/// Selects between two values of the same type.
+///
+pub fn select_val(c : bool, a : ExpValue, b : ExpValue) -> Result<ExpValue, String>
+{
+	match (a, b)
+	{
+		(ExpValue::A(AexpValue::Int32(av)),   ExpValue::A(AexpValue::Int32(bv)))   =>
+			Result::Ok(ExpValue::A(AexpValue::Int32(select_i32(c, av, bv)))),
+		(ExpValue::A(AexpValue::Float32(av)), ExpValue::A(AexpValue::Float32(bv))) =>
+			Result::Ok(ExpValue::A(AexpValue::Float32(select_f32(c, av, bv)))),
+		(ExpValue::B(av),                     ExpValue::B(bv))                     =>
+			Result::Ok(ExpValue::B(select_bool(c, av, bv))),
+		(av, bv) =>
+			Result::Err(format!("Cannot select between values of type {} and {}.", av.get_type(), bv.get_type())),
+	}
+}
+
+/// The value used in place of a missing value on an inactive path.
+///
+fn dummy_val(t : &data_type::DataType) -> Result<ExpValue, String>
+{
+	match t
+	{
+		data_type::DataType::Int32   => Result::Ok(ExpValue::A(AexpValue::Int32(0))),
+		data_type::DataType::Float32 => Result::Ok(ExpValue::A(AexpValue::Float32(0.0))),
+		data_type::DataType::Bool    => Result::Ok(ExpValue::B(false)),
+		data_type::DataType::Void    => Result::Err(format!("{}", "There is no value of void type.")),
+	}
+}
+
+/// Return state of the function currently being executed.
+///
+struct FrameRet
+{
+	returned : bool,
+	ret_val  : Option<ExpValue>,
+}
+
+/// A variable access made by an oblivious execution.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Access
+{
+	Read(String),
+	Write(String),
+}
+
+/// Where the variable accesses are recorded, if they are.
+///
+type Trace<'a> = Option<&'a RefCell<Vec<Access> > >;
+
+fn record(trace : Trace, access : Access)
+{
+	if let Option::Some(t) = trace
+	{
+		t.borrow_mut().push(access);
+	}
+}
+
+/// Name of the hidden flag telling whether a variable has been initialized;
+/// it is declared next to the variable, at the same level of the stack.
+///
+fn init_flag_name(name : & String) -> String
+{
+	format!("{}'init", name)
+}
+
+/// Level of the stack where a variable is declared.
+///
+fn var_level(
+	var_states : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	name       : & String)
+	-> Result<Rc<RefCell<VarStatesStack<ExpValue, VarState> > >, String>
+{
+	let mut curr = var_states.clone();
+
+	while !curr.borrow().state.has_var(name)
+	{
+		curr = match VarStatesStack::get_level(&curr, 1)
+		{
+			Option::Some(parent) => parent,
+			Option::None         => return Result::Err(format!("Variable {} hasn't been declared.", name)),
+		};
+	}
+
+	Result::Ok(curr)
+}
+
+/// Whether a variable has been initialized; variables that have never been
+/// assigned obliviously (e.g. parameters and globals) have no flag, and are
+/// initialized if they hold a value.
+///
+fn is_initialized(
+	level : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	name  : & String,
+	val   : & Option<ExpValue>)
+	-> bool
+{
+	match level.borrow().state.read(&init_flag_name(name))
+	{
+		Option::Some(Option::Some(ExpValue::B(init))) => init,
+		_                                             => val.is_some(),
+	}
+}
+
+fn set_initialized(
+	level : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	name  : & String,
+	init  : bool)
+	-> Result<(), String>
+{
+	let flag_name = init_flag_name(name);
+	let mut level = level.borrow_mut();
+
+	if !level.state.has_var(&flag_name)
+	{
+		level.state.decl(VarDecl::new(data_type::DataType::Bool, flag_name.clone()));
+	}
+
+	match level.state.assign(&flag_name, ExpValue::B(init))
+	{
+		Result::Ok(assi_res) => assi_res,
+		Result::Err(_)       => Result::Err(format!("Failed to flag variable {} as initialized.", name)),
+	}
+}
+
+fn read_var(
+	var_states : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	name       : & String,
+	active     : bool,
+	trace      : Trace)
+	-> Result<ExpValue, String>
+{
+	let level = var_level(var_states, name)?;
+	let val_opt = match level.borrow().state.read(name)
+	{
+		Option::Some(v) => v,
+		Option::None    => return Result::Err(format!("Variable {} hasn't been declared.", name)),
+	};
+
+	record(trace, Access::Read(name.clone()));
+
+	if active & !is_initialized(&level, name, &val_opt)
+	{
+		return Result::Err(format!("Variable {} hasn't been initialized.", name));
+	}
+
+	match val_opt
+	{
+		Option::Some(v) => Result::Ok(v),
+		Option::None    =>
+		{
+			match level.borrow().state.get_type(name)
+			{
+				Option::Some(t) => dummy_val(&t),
+				Option::None    => Result::Err(format!("Variable {} hasn't been declared.", name)),
+			}
+		},
+	}
+}
+
+fn eval_arith(
+	op     : &aexp::Aexp,
+	l_val  : AexpValue,
+	r_val  : AexpValue,
+	active : bool)
+	-> Result<AexpValue, String>
+{
+	use aexp::Aexp;
+
+	match (l_val, r_val)
+	{
+		(AexpValue::Int32(lv), AexpValue::Int32(rv)) =>
+		{
+			let res = match op
+			{
+				Aexp::Add{..} => lv.wrapping_add(rv),
+				Aexp::Sub{..} => lv.wrapping_sub(rv),
+				Aexp::Mul{..} => lv.wrapping_mul(rv),
+				Aexp::Div{..} | Aexp::Mod{..} =>
+				{
+					let is_fault = (rv == 0) | ((lv == i32::MIN) & (rv == -1));
+					if is_fault & active
+					{
+						return Result::Err(format!("Invalid division {} by {}.", lv, rv));
+					}
+
+					let safe_rv = select_i32(is_fault, 1, rv);
+					match op
+					{
+						Aexp::Div{..} => lv / safe_rv,
+						_             => lv % safe_rv,
+					}
+				},
+				_ => return Result::Err(format!("{}", "Unexpected arithmetic operator.")),
+			};
+
+			Result::Ok(AexpValue::Int32(res))
+		},
+		(l_val, r_val) =>
+		{
+			let t = typing::arith_type(&l_val.get_type(), &r_val.get_type())?;
+			let (l_val, r_val) = (l_val.promote_to(&t)?, r_val.promote_to(&t)?);
+
+			match op
+			{
+				Aexp::Add{..} => Result::Ok(l_val + r_val),
+				Aexp::Sub{..} => Result::Ok(l_val - r_val),
+				Aexp::Mul{..} => Result::Ok(l_val * r_val),
+				Aexp::Div{..} => Result::Ok(l_val / r_val),
+				Aexp::Mod{..} => Result::Ok(l_val % r_val),
+				_             => Result::Err(format!("{}", "Unexpected arithmetic operator.")),
+			}
+		},
+	}
+}
+
+fn eval_aexp(
+	e           : & aexp::Aexp,
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	active      : bool,
+	depth       : usize,
+	trace       : Trace)
+	-> Result<AexpValue, String>
+{
+	use aexp::Aexp;
+
+	match e
+	{
+		Aexp::IntConst{v} => Result::Ok(AexpValue::Int32(*v)),
+		Aexp::FloConst{v} => Result::Ok(AexpValue::Float32(*v)),
+		Aexp::Add{l, r} | Aexp::Sub{l, r} | Aexp::Mul{l, r} | Aexp::Div{l, r} | Aexp::Mod{l, r} =>
+		{
+			let l_val = eval_aexp(l, func_states, var_states, active, depth, trace)?;
+			let r_val = eval_aexp(r, func_states, var_states, active, depth, trace)?;
+
+			eval_arith(e, l_val, r_val, active)
+		},
+		Aexp::Var{v} => read_var(var_states, &v.name, active, trace)?.to_aexp_val(),
+		Aexp::FnCall{fc} =>
+		{
+			match func_call_internal(func_states, var_states, fc, active, depth, trace)?
+			{
+				Option::Some(ret_val) => ret_val.to_aexp_val(),
+				Option::None          => Result::Err(format!("Function {} doesn't return a value.", fc.name)),
+			}
+		},
+	}
+}
+
+fn eval_bexp(
+	e           : & bexp::Bexp,
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	active      : bool,
+	depth       : usize,
+	trace       : Trace)
+	-> Result<bool, String>
+{
+	use bexp::Bexp;
+
+	match e
+	{
+		Bexp::BoolConst{v} => Result::Ok(*v),
+		Bexp::Beq{l, r} | Bexp::Bneq{l, r} | Bexp::And{l, r} | Bexp::Or{l, r} =>
+		{
+			// Both operands are always evaluated, i.e. there is no short-circuit.
+			let l_val = eval_bexp(l, func_states, var_states, active, depth, trace)?;
+			let r_val = eval_bexp(r, func_states, var_states, active, depth, trace)?;
+
+			match e
+			{
+				Bexp::Beq{..}  => Result::Ok(l_val == r_val),
+				Bexp::Bneq{..} => Result::Ok(l_val != r_val),
+				Bexp::And{..}  => Result::Ok(l_val & r_val),
+				_              => Result::Ok(l_val | r_val),
+			}
+		},
+		Bexp::Not{e} => Result::Ok(!eval_bexp(e, func_states, var_states, active, depth, trace)?),
+		Bexp::Aeq{l, r} | Bexp::Aneq{l, r} | Bexp::Lt{l, r} | Bexp::Lte{l, r} | Bexp::Gt{l, r} | Bexp::Gte{l, r} =>
+		{
+			let l_val = eval_aexp(l, func_states, var_states, active, depth, trace)?;
+			let r_val = eval_aexp(r, func_states, var_states, active, depth, trace)?;
+			let t = typing::arith_cmp_type(&l_val.get_type(), &r_val.get_type())?;
+			let (l_val, r_val) = (l_val.promote_to(&t)?, r_val.promote_to(&t)?);
+
+			match e
+			{
+				Bexp::Aeq{..}  => Result::Ok(l_val == r_val),
+				Bexp::Aneq{..} => Result::Ok(l_val != r_val),
+				Bexp::Lt{..}   => Result::Ok(l_val <  r_val),
+				Bexp::Lte{..}  => Result::Ok(l_val <= r_val),
+				Bexp::Gt{..}   => Result::Ok(l_val >  r_val),
+				_              => Result::Ok(l_val >= r_val),
+			}
+		},
+		Bexp::Var{v} => read_var(var_states, &v.name, active, trace)?.to_bexp_val(),
+		Bexp::FnCall{fc} =>
+		{
+			match func_call_internal(func_states, var_states, fc, active, depth, trace)?
+			{
+				Option::Some(ret_val) => ret_val.to_bexp_val(),
+				Option::None          => Result::Err(format!("Function {} doesn't return a value.", fc.name)),
+			}
+		},
+	}
+}
+
+fn eval_exp(
+	e           : & exp::Exp,
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	active      : bool,
+	depth       : usize,
+	trace       : Trace)
+	-> Result<ExpValue, String>
+{
+	match e
+	{
+		exp::Exp::A{e} => Result::Ok(ExpValue::A(eval_aexp(e, func_states, var_states, active, depth, trace)?)),
+		exp::Exp::B{e} => Result::Ok(ExpValue::B(eval_bexp(e, func_states, var_states, active, depth, trace)?)),
+	}
+}
+
+fn exec_cmd(
+	c           : & cmd::Cmd,
+	func_states : & mut Rc<FuncStatesStack<FuncState> >,
+	var_states  : & mut Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	active      : bool,
+	frame       : & mut FrameRet,
+	depth       : usize,
+	trace       : Trace)
+	-> Result<(), String>
+{
+	use cmd::Cmd;
+
+	// Commands after a return statement are still executed, but inactively.
+	let active = active & !frame.returned;
+
+	match c
+	{
+		Cmd::Skip                              => {},
+		Cmd::VarDecl  { d }                    =>
+		{
+			match var_states.borrow_mut().decl_var((**d).clone())
+			{
+				Option::None           => {},
+				Option::Some(ret_decl) =>
+					return Result::Err(format!("Failed to declare variable {}; It probably already declared at current stack.", ret_decl.name)),
+			}
+		},
+		Cmd::Assign   { var, e }               =>
+		{
+			let new_val = eval_exp(e, func_states, var_states, active, depth, trace)?;
+
+			let level = match var_level(var_states, &var.name)
+			{
+				Result::Ok(l)  => l,
+				Result::Err(_) => return Result::Err(format!("Failed to assign: Variable {} hasn't been declared.", var.name)),
+			};
+			let (var_type, old_val) = match (level.borrow().state.get_type(&var.name), level.borrow().state.read(&var.name))
+			{
+				(Option::Some(t), Option::Some(v)) => (t, v),
+				_                                  => return Result::Err(format!("Failed to assign: Variable {} hasn't been declared.", var.name)),
+			};
+			let new_val = new_val.promote_to(&var_type)?;
+
+			// The variable is written on both paths, so the accesses don't
+			// tell which one is active; an uninitialized variable gets a
+			// dummy value on the inactive path, and its init flag keeps it
+			// uninitialized, as it would be in the normal interpreter.
+			let was_init = is_initialized(&level, &var.name, &old_val);
+			let old_val = match old_val
+			{
+				Option::Some(v) => v,
+				Option::None    => dummy_val(&var_type)?,
+			};
+			let merged_val = select_val(active, new_val, old_val)?;
+
+			record(trace, Access::Write(var.name.clone()));
+
+			let assi_ret = level.borrow_mut().state.assign(&var.name, merged_val);
+			match assi_ret
+			{
+				Result::Ok(assi_res) => assi_res?,
+				Result::Err(_)       =>
+					return Result::Err(format!("Failed to assign: Variable {} hasn't been declared.", var.name)),
+			}
+
+			set_initialized(&level, &var.name, select_bool(active, true, was_init))?;
+		},
+		Cmd::FnCall   { fc }                   =>
+		{
+			func_call_internal(func_states, var_states, fc, active, depth, trace)?;
+		},
+		Cmd::IfElse   { cond, tr_cmd, fa_cmd } =>
+		{
+			let cond_val = eval_bexp(cond, func_states, var_states, active, depth, trace)?;
+
+			{
+				let mut inner_func_states = Rc::new(FuncStatesStack::new_level(func_states.clone()));
+				let mut inner_var_states  = Rc::new(RefCell::new(VarStatesStack::new_level(var_states.clone())));
+
+				exec_cmd(tr_cmd, &mut inner_func_states, &mut inner_var_states, active & cond_val, frame, depth, trace)?;
+			}
+
+			{
+				let mut inner_func_states = Rc::new(FuncStatesStack::new_level(func_states.clone()));
+				let mut inner_var_states  = Rc::new(RefCell::new(VarStatesStack::new_level(var_states.clone())));
+
+				exec_cmd(fa_cmd, &mut inner_func_states, &mut inner_var_states, active & !cond_val, frame, depth, trace)?;
+			}
+		},
+		Cmd::WhileLoop{ cond, lp_cmd, bound, .. }  =>
+		{
+			let max_iter = match bound
+			{
+				Option::Some(b) => *b,
+				Option::None    =>
+					return Result::Err(format!("While loop {} needs a declared bound to be executed obliviously.", cond)),
+			};
+
+			let mut running = true;
+
+			for _ in 0..max_iter
+			{
+				let iter_active = active & running & !frame.returned;
+				let cond_val = eval_bexp(cond, func_states, var_states, iter_active, depth, trace)?;
+				running &= cond_val;
+
+				let mut inner_func_states = Rc::new(FuncStatesStack::new_level(func_states.clone()));
+				let mut inner_var_states  = Rc::new(RefCell::new(VarStatesStack::new_level(var_states.clone())));
+
+				exec_cmd(lp_cmd, &mut inner_func_states, &mut inner_var_states, iter_active & cond_val, frame, depth, trace)?;
+			}
+
+			let final_active = active & running & !frame.returned;
+			let cond_val = eval_bexp(cond, func_states, var_states, final_active, depth, trace)?;
+			if final_active & cond_val
+			{
+				return Result::Err(format!("While loop exceeded its declared bound of {} iterations.", max_iter));
+			}
+		},
+		Cmd::Seq      { fst_cmd, snd_cmd }     =>
+		{
+			exec_cmd(fst_cmd, func_states, var_states, active, frame, depth, trace)?;
+			exec_cmd(snd_cmd, func_states, var_states, active, frame, depth, trace)?;
+		},
+		Cmd::FnDecl   { prototype, fn_cmd }    =>
+		{
+			let func_states_ref = match Rc::get_mut(func_states)
+			{
+				Some(v) => v,
+				None    => return Result::Err(format!("Failed to unwrap the RC."))
+			};
+
+			match func_states_ref.decl_fn(prototype.clone(), fn_cmd.clone())
+			{
+				Option::None              => {},
+				Option::Some((ret_pt, _)) =>
+					return Result::Err(format!("Function named {} has already been declared.", ret_pt.name)),
+			}
+		},
+		Cmd::Return   { e }                    =>
+		{
+			if let Option::Some(e_v) = e
+			{
+				let new_val = eval_exp(e_v, func_states, var_states, active, depth, trace)?;
+				frame.ret_val = match frame.ret_val.take()
+				{
+					Option::Some(old_val) => Option::Some(select_val(active, new_val, old_val)?),
+					Option::None          => Option::Some(new_val),
+				};
+			}
+
+			frame.returned |= active;
+		},
+		Cmd::Assert   { cond }                 =>
+		{
+			// Like an exceeded loop bound, a failed assertion aborts the
+			// whole execution, so it can only be hidden on inactive paths.
+			let cond_val = eval_bexp(cond, func_states, var_states, active, depth, trace)?;
+			if active & !cond_val
+			{
+				return Result::Err(format!("Assertion {} failed.", cond));
+			}
+		},
+	}
+
+	Result::Ok(())
+}
+
+fn func_call_internal(
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	call        : & func_general::FnCall,
+	active      : bool,
+	depth       : usize,
+	trace       : Trace)
+	-> Result<Option<ExpValue>, String>
+{
+	if depth >= MAX_CALL_DEPTH
+	{
+		return Result::Err(format!("Function call {} exceeded the maximum call depth ({}) of oblivious execution.", call.name, MAX_CALL_DEPTH));
+	}
+
+	let mut val_list : Vec<ExpValue> = Vec::with_capacity(call.exp_list.len());
+
+	for e in call.exp_list.iter()
+	{
+		val_list.push(eval_exp(e, func_states, var_states, active, depth, trace)?);
+	}
+
+	func_call_by_vals(func_states, var_states, call, val_list, active, depth + 1, trace)
+}
+
+fn func_call_by_vals(
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	call        : & func_general::FnCall,
+	val_list    : Vec<ExpValue>,
+	active      : bool,
+	depth       : usize,
+	trace       : Trace)
+	-> Result<Option<ExpValue>, String>
+{
+	let (func_defined_func_states, func_defined_level, mangled_func_name) =
+		super::states::resolve_func_call(func_states, call, &val_list)?;
+
+	let callee = match func_defined_func_states.get_fn_at_curr_level(&mangled_func_name)
+	{
+		Option::Some(v) => v,
+		Option::None    => return Result::Err(format!("The function {} called is undefined.", mangled_func_name)),
+	};
+
+	let func_defined_var_states = match VarStatesStack::get_level(var_states, func_defined_level)
+	{
+		Option::Some(v) => v,
+		Option::None    => return Result::Err(format!("Func states stack and var states stack mismatch."))
+	};
+
+	let func_pt = callee.get_prototype_ref();
+
+	if func_pt.var_decl_list.len() != val_list.len()
+	{
+		return Result::Err(
+			format!(
+				"Function {} expects {} parameters, but {} are given.",
+				func_pt.name, func_pt.var_decl_list.len(), val_list.len()
+			)
+		);
+	}
+
+	let mut callee_func_states = Rc::new(FuncStatesStack::new_level(func_defined_func_states.clone()));
+	let mut callee_var_states  = Rc::new(RefCell::new(VarStatesStack::new_level(func_defined_var_states)));
+
+	for (var_decl, val) in func_pt.var_decl_list.iter().zip(val_list)
+	{
+		if let Option::Some(ret_decl) = callee_var_states.borrow_mut().decl_var(var_decl.clone())
+		{
+			return Result::Err(format!("Function parameter {} is declared repeatedly.", ret_decl.name));
+		}
+
+		match callee_var_states.borrow_mut().var_assign(&var_decl.name, val)
+		{
+			Result::Ok(a_res) => a_res?,
+			Result::Err(_)    => return Result::Err(
+				format!("Cann't find function parameter {} that just declared.", var_decl.name))
+		}
+	}
+
+	let mut frame = FrameRet { returned : false, ret_val : Option::None };
+
+	exec_cmd(callee.get_cmd_ref(), &mut callee_func_states, &mut callee_var_states, active, &mut frame, depth, trace)?;
+
+	match func_pt.ret_type
+	{
+		data_type::DataType::Void => Result::Ok(Option::None),
+		_                         =>
+		{
+			if active & !frame.returned
+			{
+				return Result::Err(format!("Function {} doesn't return a value.", func_pt.name));
+			}
+
+			match frame.ret_val
+			{
+				Option::Some(v) => Result::Ok(Option::Some(v.promote_to(&func_pt.ret_type)?)),
+				// No return statement has been reached, not even an inactive one.
+				Option::None    => Result::Ok(Option::Some(dummy_val(&func_pt.ret_type)?)),
+			}
+		},
+	}
+}
+
+/// Calls a function in oblivious mode.
+///
+/// The arguments of the call must already be evaluated (i.e. be constants),
+/// which is the case for the entry call made by the interpreter enclave.
+///
+pub fn func_call(
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	call        : & func_general::FnCall)
+	-> Result<Option<ExpValue>, String>
+{
+	func_call_traced(func_states, var_states, call, Option::None)
+}
+
+/// Calls a function in oblivious mode, like `func_call`, and records the
+/// variables read and written, in order, into `trace`.
+///
+pub fn func_call_traced(
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
+	call        : & func_general::FnCall,
+	trace       : Option<&RefCell<Vec<Access> > >)
+	-> Result<Option<ExpValue>, String>
+{
+	use super::exp::CanEvalToExpVal;
+
+	let mut val_list : Vec<ExpValue> = Vec::with_capacity(call.exp_list.len());
+
+	for e in call.exp_list.iter()
+	{
+		val_list.push(e.simp_eval_to_exp_val()?);
+	}
+
+	func_call_by_vals(func_states, var_states, call, val_list, true, 1, trace)
+}
@@ -6,6 +6,7 @@ use std::rc::Rc;
 use std::cell::RefCell;
 
 use super::super::ast;
+use super::super::typing;
 use ast::func_general;
 use ast::data_type;
 use ast::var_general;
@@ -16,7 +17,6 @@ use ast::states::VarStatesStack;
 use ast::cmd;
 
 use super::exp::ExpValue;
-use super::aexp::CanConvertToAexpVal;
 
 #[derive(Clone)]
 pub struct FuncState
@@ -66,7 +66,14 @@ impl FuncState
 			match func_cmd.eval_to_exp_val(&mut callee_func_states, &mut callee_var_states)?
 			{
 				Option::Some(v) => //Commands in the function returns void or something:
-					Result::Ok(v),
+				{
+					let ret_type = typing::return_type(&func_pt.ret_type, v.as_ref().map(|v| v.get_type()).as_ref())?;
+					match v
+					{
+						Option::Some(v) => Result::Ok(Option::Some(v.promote_to(&ret_type)?)),
+						Option::None    => Result::Ok(Option::None),
+					}
+				},
 				Option::None    => //Commands in the function doesn't have return statement. That's fine, it's just a void function
 					Result::Ok(Option::None),
 			}
@@ -129,6 +136,56 @@ pub fn get_mangled_func_name_from_name_n_exp_val(func_name : &String, exp_val_li
 	mangled_fun_name
 }
 
+pub fn get_mangled_func_name_from_name_n_types(func_name : &String, types : &Vec<data_type::DataType>) -> String
+{
+	let mut mangled_fun_name = String::new();
+	mangled_fun_name.push_str(func_name);
+	mangled_fun_name.push('_');
+
+	for t in types.iter()
+	{
+		mangled_fun_name.push_str(&t.to_string());
+		mangled_fun_name.push('_');
+	}
+
+	mangled_fun_name
+}
+
+/// Find the function called by `call` with the argument values `val_list`,
+/// i.e. the level declaring it, the depth of that level and its mangled name.
+///
+/// A call recorded by the type checker refers to the overload recorded;
+/// otherwise, the overload is resolved by `typing::resolve_call` among the
+/// ones declared at the innermost level declaring a function of that name.
+///
+pub fn resolve_func_call(
+	func_states : & Rc<FuncStatesStack<FuncState> >,
+	call        : & func_general::FnCall,
+	val_list    : & Vec<ExpValue>)
+	-> Result<(Rc<FuncStatesStack<FuncState> >, usize, String), String>
+{
+	let arg_types : Vec<data_type::DataType> = val_list.iter().map(|v| v.get_type()).collect();
+
+	let callee_opt = match &call.param_types
+	{
+		Option::Some(param_types) =>
+			FuncStatesStack::search_fn(func_states, &get_mangled_func_name_from_name_n_types(&call.name, param_types)),
+		Option::None =>
+			FuncStatesStack::search_fn_name(func_states, &call.name),
+	};
+	let (func_defined_func_states, func_defined_level) = match callee_opt
+	{
+		Option::Some(v) => v,
+		Option::None    =>
+			return Result::Err(format!("The function {} called is undefined.", get_mangled_func_name_from_name_n_types(&call.name, &arg_types))),
+	};
+
+	let param_types = typing::resolve_call(call, &arg_types, func_defined_func_states.state.prototypes())?;
+	let mangled_func_name = get_mangled_func_name_from_name_n_types(&call.name, &param_types);
+
+	Result::Ok((func_defined_func_states, func_defined_level, mangled_func_name))
+}
+
 pub fn func_call(
 	func_states : & Rc<FuncStatesStack<FuncState> >,
 	var_states  : & Rc<RefCell<VarStatesStack<ExpValue, VarState> > >,
@@ -155,14 +212,7 @@ pub fn func_call(
 		}
 	}
 
-	let mangled_func_name = get_mangled_func_name_from_name_n_exp_val(&call.name, &val_list);
-
-	let callee_opt = FuncStatesStack::search_fn(func_states, &mangled_func_name);
-	let (func_defined_func_states, func_defined_level) = match callee_opt
-	{
-		Option::Some(v) => v,
-		Option::None    => return Result::Err(format!("The function {} called is undefined.", mangled_func_name)),
-	};
+	let (func_defined_func_states, func_defined_level, mangled_func_name) = resolve_func_call(func_states, call, &val_list)?;
 
 	let func_defined_func_states_2 = func_defined_func_states.clone();
 	let callee = match func_defined_func_states_2.get_fn_at_curr_level(&mangled_func_name)
@@ -200,28 +250,15 @@ impl AnyVariable<ExpValue> for VarState
 
 	fn to_decl(self, name : String) -> var_general::VarDecl
 	{
-		var_general::VarDecl{ var_type : self.t, name : name }
+		var_general::VarDecl::new(self.t, name)
 	}
 
 	fn assign(&mut self, v : ExpValue) -> Result<(), String>
 	{
-		if v.get_type() == self.t
-		{
-			self.s = Option::Some(v);
-
-			Result::Ok(())
-		}
-		else if v.get_type() == data_type::DataType::Int32 && self.t == data_type::DataType::Float32
-		{
-			self.s = Option::Some(ExpValue::from_aexp_val((v.to_aexp_val()?).promote_to_flo32()));
-
-			Result::Ok(())
-		}
-		else
-		{
-			Result::Err(format!("Assignment expecting {} type, but {} type is given.", self.t, v.get_type()))
-		}
+		let t = typing::assign_type(&self.t, &v.get_type())?;
+		self.s = Option::Some(v.promote_to(&t)?);
 
+		Result::Ok(())
 	}
 
 	fn read(&self) -> Option<ExpValue>
@@ -0,0 +1,296 @@
+//! Execution of the annotated AST produced by `type_checker::annotate`.
+//!
+//! Since the program has been type checked and its promotions are explicit,
+//! values are used as they are, without checking their types again, and
+//! variables are read and written through their slots, without looking up
+//! their names. The results are the same as the ones of `states::func_call`.
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::super::ast::func_general;
+use super::super::ast::typed::TypedBlock;
+use super::super::ast::typed::TypedCmd;
+use super::super::ast::typed::TypedExp;
+use super::super::ast::typed::TypedExpKind;
+use super::super::ast::typed::TypedFnCall;
+use super::super::ast::typed::TypedProgram;
+use super::super::ast::typed::VarSlot;
+use super::super::ast::data_type::DataType;
+use super::super::typing;
+
+use super::aexp::AexpValue;
+use super::aexp::CanConvertToAexpVal;
+use super::exp::ExpValue;
+
+/// Variables of an annotated program being executed.
+///
+pub struct TypedStates
+{
+	pub globals : Vec<Option<ExpValue>>,
+	/// Scopes of the blocks being executed, innermost last.
+	scopes : Vec<Vec<Option<ExpValue>>>,
+	/// Index in `scopes` of the scope of the body of the function being
+	/// executed; the scopes before it belong to its callers.
+	base : usize,
+}
+
+impl TypedStates
+{
+	pub fn new(prog : &TypedProgram) -> TypedStates
+	{
+		TypedStates { globals : vec![Option::None; prog.num_globals], scopes : Vec::new(), base : 0 }
+	}
+
+	/// The scopes of the function being executed are the ones after `base`,
+	/// and the one just out of them is the global scope.
+	///
+	fn slot(&mut self, v : &VarSlot) -> &mut Option<ExpValue>
+	{
+		let num_scopes = self.scopes.len() - self.base;
+		if v.depth < num_scopes
+		{
+			let idx = self.scopes.len() - 1 - v.depth;
+			&mut self.scopes[idx][v.slot]
+		}
+		else
+		{
+			&mut self.globals[v.slot]
+		}
+	}
+}
+
+/// Execute the global commands of the program.
+///
+pub fn gen_prog_states(prog : &TypedProgram) -> Result<TypedStates, String>
+{
+	let mut states = TypedStates::new(prog);
+
+	match exec_cmd(prog, &mut states, &prog.cmd)?
+	{
+		Option::Some(_) => Result::Err(format!("Program root shouldn't contain return statement.")),
+		Option::None    => Result::Ok(states),
+	}
+}
+
+/// Make a call, whose arguments are values, e.g. the entry call, to a
+/// function of the program; the overload called is resolved the same way
+/// `states::func_call` resolves it.
+///
+pub fn func_call(prog : &TypedProgram, states : &mut TypedStates, call : &func_general::FnCall) -> Result<Option<ExpValue>, String>
+{
+	use super::exp::CanEvalToExpVal;
+
+	let mut val_list : Vec<ExpValue> = Vec::with_capacity(call.exp_list.len());
+	for e in call.exp_list.iter()
+	{
+		val_list.push(e.simp_eval_to_exp_val()?);
+	}
+
+	let arg_types : Vec<DataType> = val_list.iter().map(|v| v.get_type()).collect();
+	let param_types = typing::resolve_call(call, &arg_types, prog.funcs.iter().map(|func| &*func.prototype))?;
+	let func = match prog.funcs.iter().position(|func|
+		func.prototype.name == call.name && func.prototype.var_decl_list.iter().map(|d| d.var_type).eq(param_types.iter().cloned()))
+	{
+		Option::Some(v) => v,
+		Option::None    => return Result::Err(format!("The function {} called is undefined.", call.name)),
+	};
+
+	let mut args : Vec<ExpValue> = Vec::with_capacity(val_list.len());
+	for (val, t) in val_list.into_iter().zip(param_types.iter())
+	{
+		let t = typing::assign_type(t, &val.get_type())?;
+		args.push(val.promote_to(&t)?);
+	}
+
+	call_by_vals(prog, states, func, args)
+}
+
+fn call_by_vals(prog : &TypedProgram, states : &mut TypedStates, func : usize, args : Vec<ExpValue>) -> Result<Option<ExpValue>, String>
+{
+	let body = &prog.funcs[func].body;
+
+	let mut scope : Vec<Option<ExpValue>> = args.into_iter().map(Option::Some).collect();
+	scope.resize(body.num_slots, Option::None);
+
+	let caller_base = states.base;
+	states.base = states.scopes.len();
+	states.scopes.push(scope);
+
+	let res = exec_cmd(prog, states, &body.cmd);
+
+	states.scopes.pop();
+	states.base = caller_base;
+
+	// A function without return statement returns nothing.
+	Result::Ok(res?.unwrap_or(Option::None))
+}
+
+fn fn_call(prog : &TypedProgram, states : &mut TypedStates, fc : &TypedFnCall) -> Result<Option<ExpValue>, String>
+{
+	let mut args : Vec<ExpValue> = Vec::with_capacity(fc.exp_list.len());
+	for e in fc.exp_list.iter()
+	{
+		args.push(eval_exp(prog, states, e)?);
+	}
+
+	call_by_vals(prog, states, fc.func, args)
+}
+
+fn exec_block(prog : &TypedProgram, states : &mut TypedStates, block : &TypedBlock) -> Result<Option<Option<ExpValue> >, String>
+{
+	states.scopes.push(vec![Option::None; block.num_slots]);
+	let res = exec_cmd(prog, states, &block.cmd);
+	states.scopes.pop();
+
+	res
+}
+
+/// Execute a command; returns `Option::Some` with the value returned, if the
+/// command returns from the function.
+///
+fn exec_cmd(prog : &TypedProgram, states : &mut TypedStates, c : &TypedCmd) -> Result<Option<Option<ExpValue> >, String>
+{
+	match c
+	{
+		TypedCmd::Skip                         => {},
+		TypedCmd::VarDecl{v, ..}               => *states.slot(v) = Option::None,
+		TypedCmd::Assign{v, e}                 =>
+		{
+			let val = eval_exp(prog, states, e)?;
+			*states.slot(v) = Option::Some(val);
+		},
+		TypedCmd::FnCall{fc}                   =>
+		{
+			fn_call(prog, states, fc)?;
+		},
+		TypedCmd::IfElse{cond, tr_cmd, fa_cmd} =>
+		{
+			if eval_bool(prog, states, cond)?
+			{
+				return exec_block(prog, states, tr_cmd);
+			}
+			else
+			{
+				return exec_block(prog, states, fa_cmd);
+			}
+		},
+		TypedCmd::WhileLoop{cond, lp_cmd, bound} =>
+		{
+			let mut iter_count : u64 = 0;
+
+			while eval_bool(prog, states, cond)?
+			{
+				if let Option::Some(max_iter) = bound
+				{
+					if iter_count >= *max_iter
+					{
+						return Result::Err(format!("While loop exceeded its declared bound of {} iterations.", max_iter));
+					}
+				}
+				iter_count += 1;
+
+				if let Option::Some(v) = exec_block(prog, states, lp_cmd)?
+				{
+					return Result::Ok(Option::Some(v));
+				}
+			}
+		},
+		TypedCmd::Seq{fst_cmd, snd_cmd}        =>
+		{
+			match exec_cmd(prog, states, fst_cmd)?
+			{
+				Option::Some(v) => return Result::Ok(Option::Some(v)),
+				Option::None    => return exec_cmd(prog, states, snd_cmd),
+			}
+		},
+		TypedCmd::FnDecl{..}                   => {},
+		TypedCmd::Return{e}                    =>
+		{
+			match e
+			{
+				Option::Some(e) => return Result::Ok(Option::Some(Option::Some(eval_exp(prog, states, e)?))),
+				Option::None    => return Result::Ok(Option::Some(Option::None)),
+			}
+		},
+		TypedCmd::Assert{cond}                 =>
+		{
+			if !eval_bool(prog, states, cond)?
+			{
+				return Result::Err(format!("Assertion {} failed.", cond));
+			}
+		},
+	}
+
+	Result::Ok(Option::None)
+}
+
+fn eval_arith(prog : &TypedProgram, states : &mut TypedStates, l : &TypedExp, r : &TypedExp) -> Result<(AexpValue, AexpValue), String>
+{
+	let l_val = eval_exp(prog, states, l)?.to_aexp_val()?;
+	let r_val = eval_exp(prog, states, r)?.to_aexp_val()?;
+
+	Result::Ok((l_val, r_val))
+}
+
+fn eval_bools(prog : &TypedProgram, states : &mut TypedStates, l : &TypedExp, r : &TypedExp) -> Result<(bool, bool), String>
+{
+	let l_val = eval_bool(prog, states, l)?;
+	let r_val = eval_bool(prog, states, r)?;
+
+	Result::Ok((l_val, r_val))
+}
+
+fn eval_bool(prog : &TypedProgram, states : &mut TypedStates, e : &TypedExp) -> Result<bool, String>
+{
+	match eval_exp(prog, states, e)?
+	{
+		ExpValue::B(v) => Result::Ok(v),
+		ExpValue::A(v) => Result::Err(format!("Expecting a Bool value for {}, but {} is given.", e, v)),
+	}
+}
+
+fn eval_exp(prog : &TypedProgram, states : &mut TypedStates, e : &TypedExp) -> Result<ExpValue, String>
+{
+	let val = match &e.e
+	{
+		TypedExpKind::IntConst{v}  => ExpValue::A(AexpValue::Int32(*v)),
+		TypedExpKind::FloConst{v}  => ExpValue::A(AexpValue::Float32(*v)),
+		TypedExpKind::BoolConst{v} => ExpValue::B(*v),
+		TypedExpKind::Var{v}       =>
+		{
+			match states.slot(v)
+			{
+				Option::Some(val) => val.clone(),
+				Option::None      => return Result::Err(format!("Variable {} hasn't been initialized.", v.name)),
+			}
+		},
+		TypedExpKind::Promote{e}   => ExpValue::A(eval_exp(prog, states, e)?.to_aexp_val()?.promote_to_flo32()),
+		TypedExpKind::Add{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::A(l + r) },
+		TypedExpKind::Sub{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::A(l - r) },
+		TypedExpKind::Mul{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::A(l * r) },
+		TypedExpKind::Div{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::A(l / r) },
+		TypedExpKind::Mod{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::A(l % r) },
+		TypedExpKind::Not{e}       => ExpValue::B(!eval_bool(prog, states, e)?),
+		TypedExpKind::Beq{l, r}    => { let (l, r) = eval_bools(prog, states, l, r)?; ExpValue::B(l == r) },
+		TypedExpKind::Bneq{l, r}   => { let (l, r) = eval_bools(prog, states, l, r)?; ExpValue::B(l != r) },
+		TypedExpKind::And{l, r}    => { let (l, r) = eval_bools(prog, states, l, r)?; ExpValue::B(l && r) },
+		TypedExpKind::Or{l, r}     => { let (l, r) = eval_bools(prog, states, l, r)?; ExpValue::B(l || r) },
+		TypedExpKind::Aeq{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::B(l == r) },
+		TypedExpKind::Aneq{l, r}   => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::B(l != r) },
+		TypedExpKind::Lt{l, r}     => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::B(l < r) },
+		TypedExpKind::Lte{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::B(l <= r) },
+		TypedExpKind::Gt{l, r}     => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::B(l > r) },
+		TypedExpKind::Gte{l, r}    => { let (l, r) = eval_arith(prog, states, l, r)?; ExpValue::B(l >= r) },
+		TypedExpKind::FnCall{fc}   =>
+		{
+			match fn_call(prog, states, fc)?
+			{
+				Option::Some(val) => val,
+				Option::None      => return Result::Err(format!("Function {} doesn't return a value.", fc.name)),
+			}
+		},
+	};
+
+	Result::Ok(val)
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::super::ast::cmd;
+use super::super::ast::func_general;
+use super::super::ast::test_fn::TestFn;
+
+/// Outcome of one test function; a test fails if the program root or the
+/// test function fails, e.g. on a failed assertion.
+///
+pub struct TestResult
+{
+	pub name   : String,
+	pub result : Result<(), String>,
+}
+
+impl TestResult
+{
+	pub fn passed(&self) -> bool
+	{
+		self.result.is_ok()
+	}
+}
+
+impl fmt::Display for TestResult
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+	{
+		match &self.result
+		{
+			Result::Ok(_)    => write!(f, "test {} ... PASS", self.name),
+			Result::Err(why) => write!(f, "test {} ... FAIL: {}", self.name, why),
+		}
+	}
+}
+
+/// Run one test function, on fresh global states of the program.
+///
+fn run_test(prog : &cmd::Cmd, name : &String) -> Result<(), String>
+{
+	use super::cmd::CanEvalToExpVal;
+
+	let mut prog_inter = super::Program::new();
+
+	if prog.eval_to_exp_val(&mut prog_inter.func_states, &mut prog_inter.var_states)?.is_some()
+	{
+		return Result::Err(format!("Program root shouldn't contain return statement."));
+	}
+
+	let test_call = func_general::FnCall::new(name.clone(), Vec::new());
+
+	match super::states::func_call(&prog_inter.func_states, &prog_inter.var_states, &test_call, false)?
+	{
+		Option::Some(v) => Result::Err(format!("Test function {} returned {}.", name, v)),
+		Option::None    => Result::Ok(()),
+	}
+}
+
+/// Run the test functions of a program, in order; every test runs on fresh
+/// global states, so tests can't affect each other.
+///
+pub fn run_tests(prog : &cmd::Cmd, tests : &Vec<TestFn>) -> Vec<TestResult>
+{
+	let prog_with_tests = super::super::ast::test_fn::with_tests(prog, tests);
+
+	tests.iter()
+		.map(|t| TestResult { name : t.name.clone(), result : run_test(&prog_with_tests, &t.name) })
+		.collect()
+}
@@ -7,5 +7,17 @@
 extern crate sgx_tstd as std;
 
 pub mod ast;
+pub mod typing;
 pub mod type_checker;
 pub mod interpreter;
+pub mod analyzer;
+#[cfg(feature = "host_verifier")]
+pub mod generator;
+pub mod certificate;
+#[cfg(feature = "host_verifier")]
+pub mod host_verify;
+pub mod enclave;
+pub mod attestation;
+pub mod session;
+#[cfg(not(feature = "sgx_env_ver"))]
+pub mod sealed_store;
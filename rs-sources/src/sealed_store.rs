@@ -0,0 +1,67 @@
+//! Store of the sealed blobs of the enclaves, e.g. their sealed identities,
+//! on the host.
+//!
+//! Blobs are sealed by the enclaves, so the store needs no protection of its
+//! own; it only has to hand the same bytes back. Each blob is a file
+//! `<name>.sealed` in the directory of the store, replaced atomically.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+pub struct SealedStore
+{
+	dir : PathBuf,
+}
+
+impl SealedStore
+{
+	pub fn new<P : AsRef<Path>>(dir : P) -> SealedStore
+	{
+		SealedStore { dir : dir.as_ref().to_path_buf() }
+	}
+
+	fn blob_path(&self, name : &str) -> PathBuf
+	{
+		self.dir.join(format!("{}.sealed", name))
+	}
+
+	/// The blob of the given name, if it was stored.
+	///
+	pub fn load(&self, name : &str) -> Result<Option<Vec<u8>>, String>
+	{
+		let path = self.blob_path(name);
+
+		match fs::read(&path)
+		{
+			Result::Ok(val)                                    => Result::Ok(Option::Some(val)),
+			Result::Err(why) if why.kind() == ErrorKind::NotFound => Result::Ok(Option::None),
+			Result::Err(why)                                   => Result::Err(format!("Couldn't read {}: {}", path.display(), why)),
+		}
+	}
+
+	/// Store the blob under the given name, replacing the previous one.
+	///
+	pub fn store(&self, name : &str, bytes : &[u8]) -> Result<(), String>
+	{
+		let path = self.blob_path(name);
+		let tmp_path = self.dir.join(format!("{}.sealed.tmp", name));
+
+		if let Result::Err(why) = fs::create_dir_all(&self.dir)
+		{
+			return Result::Err(format!("Couldn't create {}: {}", self.dir.display(), why));
+		}
+
+		if let Result::Err(why) = fs::write(&tmp_path, bytes)
+		{
+			return Result::Err(format!("Couldn't write {}: {}", tmp_path.display(), why));
+		}
+
+		match fs::rename(&tmp_path, &path)
+		{
+			Result::Ok(_)    => Result::Ok(()),
+			Result::Err(why) => Result::Err(format!("Couldn't replace {}: {}", path.display(), why)),
+		}
+	}
+}
@@ -0,0 +1,219 @@
+//! Client half of a session, and owner half of the delivery of a program, on
+//! hosts; and the cryptography of sessions in pure Rust, shared with
+//! `enclave::software::SoftwareBackend`.
+
+use std::vec::Vec;
+use std::string::String;
+
+use aes_gcm::Aes128Gcm;
+use aes_gcm::Nonce;
+use aes_gcm::KeyInit;
+use aes_gcm::aead::Aead;
+use aes_gcm::aead::Payload;
+use p256::NonZeroScalar;
+use p256::SecretKey;
+use rand_core::RngCore;
+
+use super::program;
+use super::program::SealedProgram;
+use super::SealedParams;
+use super::SealedResult;
+use super::SessionKeys;
+use super::SESSION_IV_SIZE;
+use super::SESSION_KEY_SIZE;
+use super::super::ast::Serializible;
+use super::super::ast::Deserializible;
+use super::super::host_verify;
+
+/// ECDH shared secret of the private key and the public key of the peer, in
+/// the layout of SGX, i.e. the x component of the shared point, in
+/// little-endian.
+///
+pub fn shared_secret(prv_key : &NonZeroScalar, peer_pkey : &[u8; 64]) -> Result<[u8; 32], String>
+{
+	let peer = host_verify::parse_public_key(peer_pkey)?;
+
+	let shared = p256::ecdh::diffie_hellman(prv_key, peer.as_affine());
+
+	let mut res : [u8; 32] = [0; 32];
+	for (i, b) in shared.raw_secret_bytes().iter().rev().enumerate()
+	{
+		res[i] = *b;
+	}
+
+	Result::Ok(res)
+}
+
+/// Public key of the secret key, in the layout of SGX.
+///
+fn public_key_of(secret : &SecretKey) -> [u8; 64]
+{
+	let mut res : [u8; 64] = [0; 64];
+	res.copy_from_slice(&secret.public_key().to_sec1_bytes()[1..]);
+
+	host_verify::to_sgx_layout(&res)
+}
+
+/// Public key of a fresh random key pair, and the ECDH shared secret of its
+/// private key and the public key of the peer; the private key is dropped.
+///
+pub fn ephemeral_shared_secret(peer_pkey : &[u8; 64]) -> Result<([u8; 64], [u8; 32]), String>
+{
+	let secret = SecretKey::random(&mut rand_core::OsRng);
+	let shared = shared_secret(&secret.to_nonzero_scalar(), peer_pkey)?;
+
+	Result::Ok((public_key_of(&secret), shared))
+}
+
+/// Seal the bytecode of a program, with its test section if any, for the
+/// key of a type-checker enclave, whose evidence the owner checked; the
+/// type-checker only delivers it to an interpreter enclave of the given
+/// MRENCLAVE. See `program`.
+///
+pub fn seal_program(checker_pkey : &[u8; 64], mr_enclave : &[u8; 32], prog_bytes : &[u8]) -> Result<Vec<u8>, String>
+{
+	let (sender_pkey, shared) = ephemeral_shared_secret(checker_pkey)?;
+	let key = program::derive_program_key(|b| Result::Ok(host_verify::sha256(b)), &shared, &sender_pkey, checker_pkey)?;
+
+	let mut res = SealedProgram { sender_pkey : sender_pkey, mr_enclave : *mr_enclave, iv : [0; SESSION_IV_SIZE], ciphertext : Vec::new() };
+	rand_core::OsRng.fill_bytes(&mut res.iv);
+
+	res.ciphertext = aes_gcm_encrypt(&key, &res.iv, &res.aad(), prog_bytes)?;
+
+	res.to_bytes()
+}
+
+/// Encrypt with AES-128-GCM; the result is the ciphertext followed by the
+/// tag.
+///
+pub fn aes_gcm_encrypt(key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], msg : &[u8]) -> Result<Vec<u8>, String>
+{
+	let cipher = Aes128Gcm::new(&(*key).into());
+
+	match cipher.encrypt(&Nonce::from(*iv), Payload { msg : msg, aad : aad })
+	{
+		Result::Ok(val)  => Result::Ok(val),
+		Result::Err(_)   => Result::Err(format!("{}", "Failed to encrypt.")),
+	}
+}
+
+/// Decrypt, and authenticate, the result of `aes_gcm_encrypt`.
+///
+pub fn aes_gcm_decrypt(key : &[u8; SESSION_KEY_SIZE], iv : &[u8; SESSION_IV_SIZE], aad : &[u8], ciphertext : &[u8]) -> Result<Vec<u8>, String>
+{
+	let cipher = Aes128Gcm::new(&(*key).into());
+
+	match cipher.decrypt(&Nonce::from(*iv), Payload { msg : ciphertext, aad : aad })
+	{
+		Result::Ok(val)  => Result::Ok(val),
+		Result::Err(_)   => Result::Err(format!("{}", "Failed to decrypt. The ciphertext is tampered, or for another key.")),
+	}
+}
+
+/// Session of a client with an interpreter enclave, whose key the client
+/// checked the evidence of.
+///
+pub struct ClientSession
+{
+	secret       : SecretKey,
+	client_pkey  : [u8; 64],
+	enclave_pkey : [u8; 64],
+	keys         : SessionKeys,
+}
+
+impl ClientSession
+{
+	/// Session with a fresh random key pair of the client.
+	///
+	pub fn new(enclave_pkey : &[u8; 64]) -> Result<ClientSession, String>
+	{
+		Self::from_secret_key(SecretKey::random(&mut rand_core::OsRng), enclave_pkey)
+	}
+
+	/// Session with the key pair of the given secret scalar, in big-endian,
+	/// e.g. as kept by the client between sealing the parameters, and
+	/// opening the result.
+	///
+	pub fn from_secret(secret : &[u8; 32], enclave_pkey : &[u8; 64]) -> Result<ClientSession, String>
+	{
+		match SecretKey::from_bytes(&(*secret).into())
+		{
+			Result::Ok(val) => Self::from_secret_key(val, enclave_pkey),
+			Result::Err(_)  => Result::Err(format!("{}", "The secret isn't a valid P-256 scalar.")),
+		}
+	}
+
+	fn from_secret_key(secret : SecretKey, enclave_pkey : &[u8; 64]) -> Result<ClientSession, String>
+	{
+		let client_pkey = public_key_of(&secret);
+
+		let shared = shared_secret(&secret.to_nonzero_scalar(), enclave_pkey)?;
+		let keys = super::derive_keys(|b| Result::Ok(host_verify::sha256(b)), &shared, &client_pkey, enclave_pkey)?;
+
+		Result::Ok(ClientSession
+		{
+			secret       : secret,
+			client_pkey  : client_pkey,
+			enclave_pkey : *enclave_pkey,
+			keys         : keys,
+		})
+	}
+
+	/// Secret scalar of the key pair of the client, in big-endian; it must
+	/// stay with the client.
+	///
+	pub fn secret(&self) -> [u8; 32]
+	{
+		let mut res : [u8; 32] = [0; 32];
+		res.copy_from_slice(&self.secret.to_bytes()[..]);
+
+		res
+	}
+
+	/// Public key of the client, in the layout of SGX.
+	///
+	pub fn client_public_key(&self) -> [u8; 64]
+	{
+		self.client_pkey
+	}
+
+	/// Public key of the enclave of the session, in the layout of SGX.
+	///
+	pub fn enclave_public_key(&self) -> [u8; 64]
+	{
+		self.enclave_pkey
+	}
+
+	/// Seal the parameter list bytes for the enclave.
+	///
+	pub fn seal_params(&self, param_bytes : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let mut res = SealedParams { client_pkey : self.client_pkey, iv : [0; SESSION_IV_SIZE], ciphertext : Vec::new() };
+		rand_core::OsRng.fill_bytes(&mut res.iv);
+
+		res.ciphertext = aes_gcm_encrypt(&self.keys.param_key, &res.iv, &res.aad(), param_bytes)?;
+
+		res.to_bytes()
+	}
+
+	/// Open the result the enclave sealed in answer to the given sealed
+	/// parameters, and return the result bytes.
+	///
+	pub fn open_result(&self, sealed_params : &[u8], sealed_result : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let (bytes_left, sealed) = SealedResult::from_bytes(sealed_result)?;
+
+		if !bytes_left.is_empty()
+		{
+			return Result::Err(format!("The sealed result is followed by {} unexpected byte(s).", bytes_left.len()));
+		}
+
+		let aad = SealedResult::aad(&host_verify::sha256(sealed_params));
+
+		match aes_gcm_decrypt(&self.keys.result_key, &sealed.iv, &aad, &sealed.ciphertext)
+		{
+			Result::Ok(val)  => Result::Ok(val),
+			Result::Err(why) => Result::Err(format!("Couldn't open the sealed result; it answers other parameters, or is tampered. {}", why)),
+		}
+	}
+}
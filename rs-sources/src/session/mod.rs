@@ -0,0 +1,293 @@
+//! Encrypted channel of the parameters, and the result, of an execution, so
+//! the host relaying them learns neither.
+//!
+//! The client checks the evidence of the key of the interpreter enclave, and
+//! does ECDH between that key and a key pair of its own, generated for the
+//! session. Both sides derive two AES-128-GCM keys from the shared secret,
+//! and both public keys, `SHA256(label | shared | client_pkey | enclave_pkey)`
+//! truncated to 16 bytes, with the labels `SESSION_PARAM_LABEL` and
+//! `SESSION_RESULT_LABEL`. The client sends its public key along with the
+//! parameter list encrypted with the parameter key; the enclave answers with
+//! the result encrypted with the result key, and bound to the sealed
+//! parameters it answers.
+//!
+//! The enclave half runs on an `EnclaveBackend`; the client half, in
+//! `client`, on hosts. AES-128-GCM is used as it is the one of the SGX SDK.
+//! Programs themselves are delivered sealed the same way, see `program`.
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::ast::Serializible;
+use super::ast::Deserializible;
+use super::enclave::EnclaveBackend;
+
+pub mod program;
+
+#[cfg(feature = "host_verifier")]
+pub mod client;
+
+/// Magic bytes sealed parameters start with.
+pub const SEALED_PARAMS_MAGIC : [u8; 4] = *b"VSEP";
+
+/// Magic bytes a sealed result starts with.
+pub const SEALED_RESULT_MAGIC : [u8; 4] = *b"VSER";
+
+/// Label of the derivation of the parameter key.
+pub const SESSION_PARAM_LABEL : &[u8] = b"enclave_verifier session param";
+
+/// Label of the derivation of the result key.
+pub const SESSION_RESULT_LABEL : &[u8] = b"enclave_verifier session result";
+
+/// Size of the AES-128-GCM keys of a session, in bytes.
+pub const SESSION_KEY_SIZE : usize = 16;
+
+/// Size of the IVs of AES-GCM, in bytes.
+pub const SESSION_IV_SIZE : usize = 12;
+
+/// Size of the tags of AES-GCM, in bytes.
+pub const SESSION_TAG_SIZE : usize = 16;
+
+/// Upper bound of the size of a sealed result, for the buffers of the host.
+pub const SEALED_RESULT_MAX_SIZE : usize = 256;
+
+/// Keys of a session, one for each direction.
+///
+pub struct SessionKeys
+{
+	pub param_key  : [u8; SESSION_KEY_SIZE],
+	pub result_key : [u8; SESSION_KEY_SIZE],
+}
+
+fn derive_key<H>(sha256 : &H, label : &[u8], shared : &[u8; 32], client_pkey : &[u8; 64], enclave_pkey : &[u8; 64]) -> Result<[u8; SESSION_KEY_SIZE], String>
+	where H : Fn(&[u8]) -> Result<[u8; 32], String>
+{
+	let mut info : Vec<u8> = label.to_vec();
+	info.extend_from_slice(shared);
+	info.extend_from_slice(client_pkey);
+	info.extend_from_slice(enclave_pkey);
+
+	let mut res : [u8; SESSION_KEY_SIZE] = [0; SESSION_KEY_SIZE];
+	res.copy_from_slice(&sha256(&info)?[0..SESSION_KEY_SIZE]);
+
+	Result::Ok(res)
+}
+
+/// Keys of the session of the given ECDH shared secret, in the layout of
+/// SGX, and public keys; the hash is the one of the side deriving them.
+///
+pub fn derive_keys<H>(sha256 : H, shared : &[u8; 32], client_pkey : &[u8; 64], enclave_pkey : &[u8; 64]) -> Result<SessionKeys, String>
+	where H : Fn(&[u8]) -> Result<[u8; 32], String>
+{
+	Result::Ok(SessionKeys
+	{
+		param_key  : derive_key(&sha256, SESSION_PARAM_LABEL, shared, client_pkey, enclave_pkey)?,
+		result_key : derive_key(&sha256, SESSION_RESULT_LABEL, shared, client_pkey, enclave_pkey)?,
+	})
+}
+
+fn read_ciphertext<'a>(bytes : &'a [u8], what : &str) -> Result<(&'a [u8], Vec<u8>), String>
+{
+	if bytes.len() < 4
+	{
+		return Result::Err(format!("Failed to parse {}. Bytes are shorter than expected.", what));
+	}
+
+	let mut len_bytes : [u8; 4] = [0; 4];
+	len_bytes.copy_from_slice(&bytes[0..4]);
+	let len = u32::from_le_bytes(len_bytes) as usize;
+
+	if bytes.len() - 4 < len || len < SESSION_TAG_SIZE
+	{
+		return Result::Err(format!("Failed to parse {}. The ciphertext is shorter than expected.", what));
+	}
+
+	Result::Ok((&bytes[(4 + len)..], bytes[4..(4 + len)].to_vec()))
+}
+
+/// Parameter list sealed by a client for the enclave.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SealedParams
+{
+	/// Public key of the client for the session, in the layout of SGX.
+	pub client_pkey : [u8; 64],
+	pub iv          : [u8; SESSION_IV_SIZE],
+	/// Parameter list bytes, encrypted with the parameter key, and the tag.
+	pub ciphertext  : Vec<u8>,
+}
+
+impl SealedParams
+{
+	/// The additional data authenticated with the parameter list.
+	///
+	pub fn aad(&self) -> Vec<u8>
+	{
+		let mut res : Vec<u8> = SEALED_PARAMS_MAGIC.to_vec();
+		res.extend_from_slice(&self.client_pkey);
+
+		res
+	}
+}
+
+impl Serializible for SealedParams
+{
+	/// # Sealed parameters layout
+	/// ```text
+	/// | magic "VSEP" - 4 Bytes | client_pkey - 64 Bytes | IV - 12 Bytes |
+	/// | ciphertext size - 4 Bytes | ciphertext, then tag - 16 Bytes |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = SEALED_PARAMS_MAGIC.to_vec();
+
+		res.extend_from_slice(&self.client_pkey);
+		res.extend_from_slice(&self.iv);
+		res.extend_from_slice(&(self.ciphertext.len() as u32).to_le_bytes());
+		res.extend_from_slice(&self.ciphertext);
+
+		Result::Ok(res)
+	}
+}
+
+impl Deserializible for SealedParams
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], SealedParams), String>
+	{
+		if bytes.len() < 4 + 64 + SESSION_IV_SIZE || bytes[0..4] != SEALED_PARAMS_MAGIC
+		{
+			return Result::Err(format!("{}", "Failed to parse sealed parameters. Magic bytes mismatch."));
+		}
+
+		let mut res = SealedParams { client_pkey : [0; 64], iv : [0; SESSION_IV_SIZE], ciphertext : Vec::new() };
+
+		res.client_pkey.copy_from_slice(&bytes[4..68]);
+		res.iv.copy_from_slice(&bytes[68..(68 + SESSION_IV_SIZE)]);
+
+		let (bytes_left, ciphertext) = read_ciphertext(&bytes[(68 + SESSION_IV_SIZE)..], "sealed parameters")?;
+		res.ciphertext = ciphertext;
+
+		Result::Ok((bytes_left, res))
+	}
+}
+
+/// Result of an execution sealed by the enclave for the client.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SealedResult
+{
+	pub iv         : [u8; SESSION_IV_SIZE],
+	/// Result bytes, see `interpreter::exp::func_ret_to_bytes`, encrypted
+	/// with the result key, and the tag.
+	pub ciphertext : Vec<u8>,
+}
+
+impl SealedResult
+{
+	/// The additional data authenticated with the result, i.e. the magic
+	/// bytes, and the hash of the sealed parameters answered.
+	///
+	pub fn aad(sealed_params_hash : &[u8; 32]) -> Vec<u8>
+	{
+		let mut res : Vec<u8> = SEALED_RESULT_MAGIC.to_vec();
+		res.extend_from_slice(sealed_params_hash);
+
+		res
+	}
+}
+
+impl Serializible for SealedResult
+{
+	/// # Sealed result layout
+	/// ```text
+	/// | magic "VSER" - 4 Bytes | IV - 12 Bytes |
+	/// | ciphertext size - 4 Bytes | ciphertext, then tag - 16 Bytes |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = SEALED_RESULT_MAGIC.to_vec();
+
+		res.extend_from_slice(&self.iv);
+		res.extend_from_slice(&(self.ciphertext.len() as u32).to_le_bytes());
+		res.extend_from_slice(&self.ciphertext);
+
+		Result::Ok(res)
+	}
+}
+
+impl Deserializible for SealedResult
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], SealedResult), String>
+	{
+		if bytes.len() < 4 + SESSION_IV_SIZE || bytes[0..4] != SEALED_RESULT_MAGIC
+		{
+			return Result::Err(format!("{}", "Failed to parse sealed result. Magic bytes mismatch."));
+		}
+
+		let mut iv : [u8; SESSION_IV_SIZE] = [0; SESSION_IV_SIZE];
+		iv.copy_from_slice(&bytes[4..(4 + SESSION_IV_SIZE)]);
+
+		let (bytes_left, ciphertext) = read_ciphertext(&bytes[(4 + SESSION_IV_SIZE)..], "sealed result")?;
+
+		Result::Ok((bytes_left, SealedResult { iv : iv, ciphertext : ciphertext }))
+	}
+}
+
+/// Session of the enclave with the client of some sealed parameters.
+///
+pub struct EnclaveSession
+{
+	keys               : SessionKeys,
+	/// `SHA256` of the sealed parameters, which the result is bound to.
+	sealed_params_hash : [u8; 32],
+}
+
+impl EnclaveSession
+{
+	/// Open parameters sealed for the key of the backend, and return the
+	/// session with their client, and the parameter list bytes.
+	///
+	pub fn open_params<B : EnclaveBackend>(backend : &B, sealed_params : &[u8]) -> Result<(EnclaveSession, Vec<u8>), String>
+	{
+		let (bytes_left, sealed) = SealedParams::from_bytes(sealed_params)?;
+
+		// The sealed parameters are hashed as a whole.
+		if !bytes_left.is_empty()
+		{
+			return Result::Err(format!("The sealed parameters are followed by {} unexpected byte(s).", bytes_left.len()));
+		}
+
+		let shared = backend.shared_secret(&sealed.client_pkey)?;
+		let keys = derive_keys(|b| backend.sha256(b), &shared, &sealed.client_pkey, &backend.public_key())?;
+
+		let param_bytes = match backend.aes_gcm_decrypt(&keys.param_key, &sealed.iv, &sealed.aad(), &sealed.ciphertext)
+		{
+			Result::Ok(val)  => val,
+			Result::Err(why) => return Result::Err(format!("Couldn't open the sealed parameters; they are sealed for another enclave, or tampered. {}", why)),
+		};
+
+		let session = EnclaveSession
+		{
+			keys               : keys,
+			sealed_params_hash : backend.sha256(sealed_params)?,
+		};
+
+		Result::Ok((session, param_bytes))
+	}
+
+	/// Seal the result bytes for the client of the session.
+	///
+	pub fn seal_result<B : EnclaveBackend>(&self, backend : &B, ret_bytes : &[u8]) -> Result<Vec<u8>, String>
+	{
+		let mut iv : [u8; SESSION_IV_SIZE] = [0; SESSION_IV_SIZE];
+		backend.random_bytes(&mut iv)?;
+
+		let ciphertext = backend.aes_gcm_encrypt(&self.keys.result_key, &iv, &SealedResult::aad(&self.sealed_params_hash), ret_bytes)?;
+
+		SealedResult { iv : iv, ciphertext : ciphertext }.to_bytes()
+	}
+}
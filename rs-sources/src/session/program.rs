@@ -0,0 +1,160 @@
+//! Confidential delivery of programs, so the host relaying them never learns
+//! the bytecode.
+//!
+//! The owner of a program checks the evidence of the key of the type-checker
+//! enclave, and seals the bytecode, with its test section if any, for that
+//! key; along with the MRENCLAVE of the interpreter enclave the program may
+//! be delivered to. The type-checker opens it, checks it, and once the
+//! evidence of the interpreter key checks out for that MRENCLAVE, seals the
+//! bytecode again for the interpreter key. The verified program is then the
+//! sealed bytecode followed by the certificate, which is over the hash of
+//! the bytecode in clear, see `certificate::parse_sealed_verified_byte_code`.
+//!
+//! The sender does ECDH between a key pair generated for the program, and
+//! the key of the recipient; the key of the program is derived like the keys
+//! of a session, with the label `SESSION_PROGRAM_LABEL`.
+
+use std::vec::Vec;
+use std::string::String;
+
+use super::SESSION_IV_SIZE;
+use super::SESSION_KEY_SIZE;
+use super::SESSION_TAG_SIZE;
+use super::super::ast::Serializible;
+use super::super::ast::Deserializible;
+use super::super::enclave::EnclaveBackend;
+
+/// Magic bytes a sealed program starts with; bytecode never starts with
+/// them, so verified programs with sealed bytecode are told apart.
+pub const SEALED_PROGRAM_MAGIC : [u8; 4] = *b"VSPG";
+
+/// Label of the derivation of the program key.
+pub const SESSION_PROGRAM_LABEL : &[u8] = b"enclave_verifier session program";
+
+/// Size a sealed program has in addition to the bytecode, in bytes.
+pub const SEALED_PROGRAM_OVERHEAD : usize = 4 + 64 + 32 + SESSION_IV_SIZE + 4 + SESSION_TAG_SIZE;
+
+/// Bytecode sealed for an enclave.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct SealedProgram
+{
+	/// Public key of the sender for the program, in the layout of SGX.
+	pub sender_pkey : [u8; 64],
+	/// MRENCLAVE of the interpreter enclave the program may be delivered to.
+	pub mr_enclave  : [u8; 32],
+	pub iv          : [u8; SESSION_IV_SIZE],
+	/// Bytecode, encrypted with the program key, and the tag.
+	pub ciphertext  : Vec<u8>,
+}
+
+impl SealedProgram
+{
+	/// The additional data authenticated with the bytecode.
+	///
+	pub fn aad(&self) -> Vec<u8>
+	{
+		let mut res : Vec<u8> = SEALED_PROGRAM_MAGIC.to_vec();
+		res.extend_from_slice(&self.sender_pkey);
+		res.extend_from_slice(&self.mr_enclave);
+
+		res
+	}
+}
+
+impl Serializible for SealedProgram
+{
+	/// # Sealed program layout
+	/// ```text
+	/// | magic "VSPG" - 4 Bytes | sender_pkey - 64 Bytes | mr_enclave - 32 Bytes | IV - 12 Bytes |
+	/// | ciphertext size - 4 Bytes | ciphertext, then tag - 16 Bytes |
+	/// ```
+	/// Integers are in little-endian.
+	///
+	fn to_bytes(&self) -> Result<Vec<u8>, String>
+	{
+		let mut res : Vec<u8> = self.aad();
+
+		res.extend_from_slice(&self.iv);
+		res.extend_from_slice(&(self.ciphertext.len() as u32).to_le_bytes());
+		res.extend_from_slice(&self.ciphertext);
+
+		Result::Ok(res)
+	}
+}
+
+impl Deserializible for SealedProgram
+{
+	fn from_bytes(bytes : &[u8]) -> Result<(&[u8], SealedProgram), String>
+	{
+		if bytes.len() < 4 + 64 + 32 + SESSION_IV_SIZE || !is_sealed_program(bytes)
+		{
+			return Result::Err(format!("{}", "Failed to parse sealed program. Magic bytes mismatch."));
+		}
+
+		let mut res = SealedProgram { sender_pkey : [0; 64], mr_enclave : [0; 32], iv : [0; SESSION_IV_SIZE], ciphertext : Vec::new() };
+
+		res.sender_pkey.copy_from_slice(&bytes[4..68]);
+		res.mr_enclave.copy_from_slice(&bytes[68..100]);
+		res.iv.copy_from_slice(&bytes[100..(100 + SESSION_IV_SIZE)]);
+
+		let (bytes_left, ciphertext) = super::read_ciphertext(&bytes[(100 + SESSION_IV_SIZE)..], "sealed program")?;
+		res.ciphertext = ciphertext;
+
+		Result::Ok((bytes_left, res))
+	}
+}
+
+/// Whether the bytes start as a sealed program.
+///
+pub fn is_sealed_program(bytes : &[u8]) -> bool
+{
+	bytes.len() >= 4 && bytes[0..4] == SEALED_PROGRAM_MAGIC
+}
+
+/// Key of a program of the given ECDH shared secret, in the layout of SGX,
+/// and public keys; the hash is the one of the side deriving it.
+///
+pub fn derive_program_key<H>(sha256 : H, shared : &[u8; 32], sender_pkey : &[u8; 64], recipient_pkey : &[u8; 64]) -> Result<[u8; SESSION_KEY_SIZE], String>
+	where H : Fn(&[u8]) -> Result<[u8; 32], String>
+{
+	super::derive_key(&sha256, SESSION_PROGRAM_LABEL, shared, sender_pkey, recipient_pkey)
+}
+
+/// Open bytecode sealed for the key of the backend, and return the
+/// MRENCLAVE it may be delivered to, and the bytecode.
+///
+pub fn open_program<B : EnclaveBackend>(backend : &B, sealed_program : &[u8]) -> Result<([u8; 32], Vec<u8>), String>
+{
+	let (bytes_left, sealed) = SealedProgram::from_bytes(sealed_program)?;
+
+	if !bytes_left.is_empty()
+	{
+		return Result::Err(format!("The sealed program is followed by {} unexpected byte(s).", bytes_left.len()));
+	}
+
+	let shared = backend.shared_secret(&sealed.sender_pkey)?;
+	let key = derive_program_key(|b| backend.sha256(b), &shared, &sealed.sender_pkey, &backend.public_key())?;
+
+	match backend.aes_gcm_decrypt(&key, &sealed.iv, &sealed.aad(), &sealed.ciphertext)
+	{
+		Result::Ok(val)  => Result::Ok((sealed.mr_enclave, val)),
+		Result::Err(why) => Result::Err(format!("Couldn't open the sealed program; it is sealed for another enclave, or tampered. {}", why)),
+	}
+}
+
+/// Seal bytecode for the key of another enclave, of the given MRENCLAVE,
+/// with a key pair generated by the backend for the program.
+///
+pub fn seal_program<B : EnclaveBackend>(backend : &B, recipient_pkey : &[u8; 64], mr_enclave : &[u8; 32], byte_code : &[u8]) -> Result<Vec<u8>, String>
+{
+	let (sender_pkey, shared) = backend.ephemeral_shared_secret(recipient_pkey)?;
+	let key = derive_program_key(|b| backend.sha256(b), &shared, &sender_pkey, recipient_pkey)?;
+
+	let mut res = SealedProgram { sender_pkey : sender_pkey, mr_enclave : *mr_enclave, iv : [0; SESSION_IV_SIZE], ciphertext : Vec::new() };
+	backend.random_bytes(&mut res.iv)?;
+
+	res.ciphertext = backend.aes_gcm_encrypt(&key, &res.iv, &res.aad(), byte_code)?;
+
+	res.to_bytes()
+}
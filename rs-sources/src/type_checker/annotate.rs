@@ -0,0 +1,294 @@
+use crate::ast;
+use crate::typing;
+use ast::data_type::DataType;
+use ast::typed::TypedBlock;
+use ast::typed::TypedCmd;
+use ast::typed::TypedExp;
+use ast::typed::TypedExpKind;
+use ast::typed::TypedFn;
+use ast::typed::TypedFnCall;
+use ast::typed::TypedProgram;
+use ast::typed::VarSlot;
+
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+use std::boxed::Box;
+
+use super::type_checker;
+
+/* Builds the annotated AST (see ast::typed) of a program, once it's been
+ * type checked, so that the type information isn't thrown away:
+ * - every expression is tagged with its type, and the promotions of its
+ *   operands are made explicit;
+ * - every call refers to the overload it resolves to (see
+ *   type_checker::resolve_fn_call), by its index in TypedProgram::funcs;
+ * - every variable reference refers to the variable by its scope depth
+ *   and slot, laid out the same way the interpreter stacks its scopes. */
+pub fn annotate(prog : &ast::cmd::Cmd) -> Result<TypedProgram, String> {
+    let mut glvar_types : Vec<type_checker::VarTypePair> = vec![];
+    let mut fn_types : Vec<type_checker::FuncIdentifierTuple> = vec![];
+    type_checker::gather_fn_types(prog, &mut glvar_types, &mut fn_types)?;
+    type_checker::iterate_through_ast(prog.clone(), true, vec![], &fn_types, DataType::Void)?;
+
+    let mut protos : Vec<Rc<ast::func_general::FnProtoType>> = vec![];
+    gather_protos(prog, &mut protos);
+
+    let mut annotator = Annotator {
+        protos : protos,
+        funcs : vec![],
+        scopes : vec![vec![]],
+        ret_type : None,
+    };
+    let cmd = annotator.cmd(prog)?;
+
+    Ok(TypedProgram {
+        funcs : annotator.funcs,
+        num_globals : annotator.scopes[0].len(),
+        cmd : cmd,
+    })
+}
+
+/* Functions are only declared at the global scope, and are numbered in
+ * order of declaration. */
+fn gather_protos(cmd : &ast::cmd::Cmd, protos : &mut Vec<Rc<ast::func_general::FnProtoType>>) {
+    match cmd {
+        ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+            gather_protos(fst_cmd, protos);
+            gather_protos(snd_cmd, protos);
+        },
+        ast::cmd::Cmd::FnDecl{prototype, ..} => protos.push(prototype.clone()),
+        _ => {},
+    }
+}
+
+fn promote(e : TypedExp, t : DataType) -> Result<TypedExp, String> {
+    if e.t == t {
+        Ok(e)
+    } else if typing::promotes_to(&e.t, &t) {
+        Ok(TypedExp { t : t, e : TypedExpKind::Promote{ e : Box::new(e) } })
+    } else {
+        Err(format!("Error: expression '{}' of type {} is used as {}.", e, e.t, t))
+    }
+}
+
+struct Annotator {
+    protos : Vec<Rc<ast::func_general::FnProtoType>>,
+    funcs : Vec<TypedFn>,
+    /* Variables declared in each scope, in order of their slots; the
+     * global scope is first, and the innermost one is last. */
+    scopes : Vec<Vec<(String, DataType)>>,
+    /* Return type of the function being annotated, if any. */
+    ret_type : Option<DataType>,
+}
+
+impl Annotator {
+    fn var(&self, name : &String) -> Result<(VarSlot, DataType), String> {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(slot) = scope.iter().position(|v| v.0 == *name) {
+                return Ok((VarSlot { name : name.clone(), depth : depth, slot : slot }, scope[slot].1));
+            }
+        }
+        Err(format!("Error: use of variable '{}' before declared.", name))
+    }
+
+    fn block(&mut self, cmd : &ast::cmd::Cmd, vars : Vec<(String, DataType)>) -> Result<TypedBlock, String> {
+        self.scopes.push(vars);
+        let res = self.cmd(cmd);
+        let scope = self.scopes.pop().unwrap();
+
+        Ok(TypedBlock { num_slots : scope.len(), cmd : Box::new(res?) })
+    }
+
+    fn cmd(&mut self, cmd : &ast::cmd::Cmd) -> Result<TypedCmd, String> {
+        match cmd {
+            ast::cmd::Cmd::Skip => Ok(TypedCmd::Skip),
+
+            ast::cmd::Cmd::VarDecl{d} => {
+                let scope = self.scopes.last_mut().unwrap();
+                scope.push((d.name.clone(), d.var_type));
+                let v = VarSlot { name : d.name.clone(), depth : 0, slot : scope.len() - 1 };
+                Ok(TypedCmd::VarDecl{ v : v, t : d.var_type })
+            },
+
+            ast::cmd::Cmd::Assign{var, e} => {
+                let (v, var_t) = self.var(&var.name)?;
+                let e = self.exp(e)?;
+                let t = typing::assign_type(&var_t, &e.t)?;
+                Ok(TypedCmd::Assign{ v : v, e : promote(e, t)? })
+            },
+
+            ast::cmd::Cmd::FnCall{fc} => {
+                let (fc, _) = self.fn_call(fc)?;
+                Ok(TypedCmd::FnCall{ fc : fc })
+            },
+
+            ast::cmd::Cmd::IfElse{cond, tr_cmd, fa_cmd} => {
+                Ok(TypedCmd::IfElse{
+                    cond : self.bexp(cond)?,
+                    tr_cmd : self.block(tr_cmd, vec![])?,
+                    fa_cmd : self.block(fa_cmd, vec![])?,
+                })
+            },
+
+            ast::cmd::Cmd::WhileLoop{cond, lp_cmd, bound, ..} => {
+                Ok(TypedCmd::WhileLoop{
+                    cond : self.bexp(cond)?,
+                    lp_cmd : self.block(lp_cmd, vec![])?,
+                    bound : *bound,
+                })
+            },
+
+            ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+                let fst_cmd = self.cmd(fst_cmd)?;
+                let snd_cmd = self.cmd(snd_cmd)?;
+                Ok(TypedCmd::Seq{ fst_cmd : Box::new(fst_cmd), snd_cmd : Box::new(snd_cmd) })
+            },
+
+            ast::cmd::Cmd::FnDecl{prototype, fn_cmd} => {
+                /* The function body only sees the globals declared before
+                 * it, and the parameters, which take the first slots. */
+                let params : Vec<(String, DataType)> = prototype.var_decl_list.iter().map(|d| (d.name.clone(), d.var_type)).collect();
+                let outer_scopes = std::mem::take(&mut self.scopes);
+                self.scopes.push(outer_scopes[0].clone());
+                let outer_ret_type = self.ret_type.replace(prototype.ret_type);
+
+                let body = self.block(fn_cmd, params);
+
+                self.scopes = outer_scopes;
+                self.ret_type = outer_ret_type;
+
+                let func = self.funcs.len();
+                self.funcs.push(TypedFn { prototype : prototype.clone(), body : body? });
+                Ok(TypedCmd::FnDecl{ func : func })
+            },
+
+            ast::cmd::Cmd::Return{e} => {
+                let ret_type = match self.ret_type {
+                    Some(t) => t,
+                    None    => return Err(format!("Error: making a return call at the global scope.")),
+                };
+                match e {
+                    None => {
+                        typing::return_type(&ret_type, None)?;
+                        Ok(TypedCmd::Return{ e : None })
+                    },
+                    Some(expr) => {
+                        let e = self.exp(expr)?;
+                        let t = typing::return_type(&ret_type, Some(&e.t))?;
+                        Ok(TypedCmd::Return{ e : Some(promote(e, t)?) })
+                    },
+                }
+            },
+
+            ast::cmd::Cmd::Assert{cond} => Ok(TypedCmd::Assert{ cond : self.bexp(cond)? }),
+        }
+    }
+
+    fn exp(&mut self, exp : &ast::exp::Exp) -> Result<TypedExp, String> {
+        match exp {
+            ast::exp::Exp::A{e} => self.aexp(e),
+            ast::exp::Exp::B{e} => self.bexp(e),
+        }
+    }
+
+    /* Operands of an arithmetic operator, promoted to the type of its result. */
+    fn arith(&mut self, l : &ast::aexp::Aexp, r : &ast::aexp::Aexp) -> Result<(DataType, Box<TypedExp>, Box<TypedExp>), String> {
+        let l = self.aexp(l)?;
+        let r = self.aexp(r)?;
+        let t = typing::arith_type(&l.t, &r.t)?;
+        Ok((t, Box::new(promote(l, t)?), Box::new(promote(r, t)?)))
+    }
+
+    fn aexp(&mut self, aexp : &ast::aexp::Aexp) -> Result<TypedExp, String> {
+        let (t, e) = match aexp {
+            ast::aexp::Aexp::IntConst{v} => (DataType::Int32, TypedExpKind::IntConst{ v : *v }),
+            ast::aexp::Aexp::FloConst{v} => (DataType::Float32, TypedExpKind::FloConst{ v : *v }),
+            ast::aexp::Aexp::Add{l, r} => { let (t, l, r) = self.arith(l, r)?; (t, TypedExpKind::Add{ l : l, r : r }) },
+            ast::aexp::Aexp::Sub{l, r} => { let (t, l, r) = self.arith(l, r)?; (t, TypedExpKind::Sub{ l : l, r : r }) },
+            ast::aexp::Aexp::Mul{l, r} => { let (t, l, r) = self.arith(l, r)?; (t, TypedExpKind::Mul{ l : l, r : r }) },
+            ast::aexp::Aexp::Div{l, r} => { let (t, l, r) = self.arith(l, r)?; (t, TypedExpKind::Div{ l : l, r : r }) },
+            ast::aexp::Aexp::Mod{l, r} => { let (t, l, r) = self.arith(l, r)?; (t, TypedExpKind::Mod{ l : l, r : r }) },
+            ast::aexp::Aexp::Var{v} => {
+                let (v, t) = self.var(&v.name)?;
+                (typing::aexp_type(&t)?, TypedExpKind::Var{ v : v })
+            },
+            ast::aexp::Aexp::FnCall{fc} => {
+                let (fc, t) = self.fn_call(fc)?;
+                (typing::aexp_type(&t)?, TypedExpKind::FnCall{ fc : fc })
+            },
+        };
+        Ok(TypedExp { t : t, e : e })
+    }
+
+    /* Operands of a comparison, promoted to the same type. */
+    fn arith_cmp(&mut self, l : &ast::aexp::Aexp, r : &ast::aexp::Aexp) -> Result<(Box<TypedExp>, Box<TypedExp>), String> {
+        let l = self.aexp(l)?;
+        let r = self.aexp(r)?;
+        let t = typing::arith_cmp_type(&l.t, &r.t)?;
+        Ok((Box::new(promote(l, t)?), Box::new(promote(r, t)?)))
+    }
+
+    fn bool_op(&mut self, l : &ast::bexp::Bexp, r : &ast::bexp::Bexp) -> Result<(Box<TypedExp>, Box<TypedExp>), String> {
+        let l = self.bexp(l)?;
+        let r = self.bexp(r)?;
+        typing::bool_op_type(&l.t, &r.t)?;
+        Ok((Box::new(l), Box::new(r)))
+    }
+
+    fn bexp(&mut self, bexp : &ast::bexp::Bexp) -> Result<TypedExp, String> {
+        let e = match bexp {
+            ast::bexp::Bexp::BoolConst{v} => TypedExpKind::BoolConst{ v : *v },
+            ast::bexp::Bexp::Not{e} => {
+                let e = self.bexp(e)?;
+                typing::not_type(&e.t)?;
+                TypedExpKind::Not{ e : Box::new(e) }
+            },
+            ast::bexp::Bexp::Beq{l, r}  => { let (l, r) = self.bool_op(l, r)?; TypedExpKind::Beq{ l : l, r : r } },
+            ast::bexp::Bexp::Bneq{l, r} => { let (l, r) = self.bool_op(l, r)?; TypedExpKind::Bneq{ l : l, r : r } },
+            ast::bexp::Bexp::And{l, r}  => { let (l, r) = self.bool_op(l, r)?; TypedExpKind::And{ l : l, r : r } },
+            ast::bexp::Bexp::Or{l, r}   => { let (l, r) = self.bool_op(l, r)?; TypedExpKind::Or{ l : l, r : r } },
+            ast::bexp::Bexp::Aeq{l, r}  => { let (l, r) = self.arith_cmp(l, r)?; TypedExpKind::Aeq{ l : l, r : r } },
+            ast::bexp::Bexp::Aneq{l, r} => { let (l, r) = self.arith_cmp(l, r)?; TypedExpKind::Aneq{ l : l, r : r } },
+            ast::bexp::Bexp::Lt{l, r}   => { let (l, r) = self.arith_cmp(l, r)?; TypedExpKind::Lt{ l : l, r : r } },
+            ast::bexp::Bexp::Lte{l, r}  => { let (l, r) = self.arith_cmp(l, r)?; TypedExpKind::Lte{ l : l, r : r } },
+            ast::bexp::Bexp::Gt{l, r}   => { let (l, r) = self.arith_cmp(l, r)?; TypedExpKind::Gt{ l : l, r : r } },
+            ast::bexp::Bexp::Gte{l, r}  => { let (l, r) = self.arith_cmp(l, r)?; TypedExpKind::Gte{ l : l, r : r } },
+            ast::bexp::Bexp::Var{v} => {
+                let (v, t) = self.var(&v.name)?;
+                typing::bexp_type(&t)?;
+                TypedExpKind::Var{ v : v }
+            },
+            ast::bexp::Bexp::FnCall{fc} => {
+                let (fc, t) = self.fn_call(fc)?;
+                typing::bexp_type(&t)?;
+                TypedExpKind::FnCall{ fc : fc }
+            },
+        };
+        Ok(TypedExp { t : DataType::Bool, e : e })
+    }
+
+    /* Returns the annotated call, and the return type of the function called. */
+    fn fn_call(&mut self, fc : &ast::func_general::FnCall) -> Result<(TypedFnCall, DataType), String> {
+        let mut args : Vec<TypedExp> = vec![];
+        for arg in &fc.exp_list {
+            args.push(self.exp(arg)?);
+        }
+        let arg_types : Vec<DataType> = args.iter().map(|e| e.t).collect();
+
+        let param_types = typing::resolve_call(fc, &arg_types, self.protos.iter().map(|pt| &**pt))?;
+        let func = match self.protos.iter().position(|pt|
+            pt.name == fc.name && pt.var_decl_list.iter().map(|d| d.var_type).eq(param_types.iter().cloned())) {
+            Some(func) => func,
+            None       => return Err(format!("Error: function call '{}', but no matching declaration.", fc)),
+        };
+
+        let mut exp_list : Vec<TypedExp> = vec![];
+        for (arg, t) in args.into_iter().zip(param_types.iter()) {
+            let t = typing::assign_type(t, &arg.t)?;
+            exp_list.push(promote(arg, t)?);
+        }
+
+        Ok((TypedFnCall { name : fc.name.clone(), func : func, exp_list : exp_list }, self.protos[func].ret_type))
+    }
+}
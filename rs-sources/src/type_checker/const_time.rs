@@ -0,0 +1,496 @@
+use crate::ast;
+use crate::typing;
+use ast::data_type::DataType;
+use ast::sec_label::SecLabel;
+
+use std::fmt;
+use std::rc::Rc;
+use std::string::String;
+use std::string::ToString;
+use std::vec::Vec;
+
+/* Kinds of secret-dependent operations that break the
+ * constant-time discipline. */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CtViolationKind {
+    /* Condition of an if-else. */
+    Branch,
+    /* Condition of a while loop. */
+    Loop,
+    /* Operand of && or ||, which may be short-circuited. */
+    ShortCircuit,
+    /* Operand of / or %, whose timing depends on the values. */
+    DivOperand,
+}
+
+impl fmt::Display for CtViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CtViolationKind::Branch       => write!(f, "secret-dependent branch"),
+            CtViolationKind::Loop         => write!(f, "secret-dependent loop condition"),
+            CtViolationKind::ShortCircuit => write!(f, "secret-dependent short-circuit operand"),
+            CtViolationKind::DivOperand   => write!(f, "secret-dependent division operand"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CtViolation {
+    pub kind : CtViolationKind,
+    /* Name of the function containing the operation; empty for global commands. */
+    pub func : String,
+    /* The condition or expression depending on secrets. */
+    pub exp : String,
+}
+
+impl fmt::Display for CtViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.func.is_empty() {
+            write!(f, "{} at global scope: {}", self.kind, self.exp)
+        } else {
+            write!(f, "{} in function '{}': {}", self.kind, self.func, self.exp)
+        }
+    }
+}
+
+/* Report of the constant-time checker; the program is clean if it
+ * has no violation. */
+pub struct CtReport {
+    pub violations : Vec<CtViolation>,
+}
+
+impl CtReport {
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for CtReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for v in &self.violations {
+            writeln!(f, "{}", v)?;
+        }
+        write!(f, "{} constant-time violation(s).", self.violations.len())
+    }
+}
+
+/* Taint summary of a declared function. A parameter is tainted if
+ * it's labeled secret, or if a tainted value is passed to it at any
+ * call site; the return value is tainted if any return may be. */
+struct CtFuncInfo {
+    name : String,
+    arg_types : Vec<DataType>,
+    ret_type : DataType,
+    param_taint : Vec<bool>,
+    ret_taint : bool,
+    prototype : Rc<ast::func_general::FnProtoType>,
+    fn_cmd : Rc<ast::cmd::Cmd>,
+}
+
+/* Members of VarTaint:
+ * 1) Variable name
+ * 2) Type of variable
+ * 3) Whether the variable may hold a secret-dependent value
+ * 4) Whether the variable is labeled secret; it's then always tainted */
+#[derive(Clone, PartialEq)]
+struct VarTaint(String, DataType, bool, bool);
+
+/* Local scopes of the function being checked; innermost scope is last. */
+type Scopes = Vec<Vec<VarTaint>>;
+
+/* Joins the taints of two states with the same scope structure. */
+fn join_scopes(l : &mut Scopes, r : &Scopes) {
+    for (l_scope, r_scope) in l.iter_mut().zip(r.iter()) {
+        for (l_var, r_var) in l_scope.iter_mut().zip(r_scope.iter()) {
+            l_var.2 = l_var.2 || r_var.2;
+        }
+    }
+}
+
+/* Taint analysis: secret labeled parameters and variables are the
+ * sources; taints flow through assignments, calls, returns, and
+ * assignments made under secret-dependent control flow. Global
+ * variables are tracked flow-insensitively. */
+struct CtChecker {
+    funcs : Vec<CtFuncInfo>,
+    globals : Vec<VarTaint>,
+    /* Whether any summary grew during the current pass. */
+    changed : bool,
+    /* Violations are only recorded in the last pass. */
+    record : bool,
+    curr_func : String,
+    violations : Vec<CtViolation>,
+    reported : Vec<(usize, CtViolationKind)>,
+}
+
+impl CtChecker {
+    fn report(&mut self, node : usize, kind : CtViolationKind, exp : String) {
+        if !self.record || self.reported.contains(&(node, kind)) {
+            return;
+        }
+        self.reported.push((node, kind));
+        self.violations.push(CtViolation { kind, func : self.curr_func.clone(), exp });
+    }
+
+    fn get_var(&self, scopes : &Scopes, name : &String) -> Result<(DataType, bool), String> {
+        for scope in scopes.iter().rev() {
+            if let Some(v) = scope.iter().find(|v| v.0 == *name) {
+                return Ok((v.1, v.2));
+            }
+        }
+        match self.globals.iter().find(|v| v.0 == *name) {
+            Some(v) => Ok((v.1, v.2)),
+            None    => Err(format!("Error: use of variable '{}' before declared.", name)),
+        }
+    }
+
+    fn set_taint(&mut self, scopes : &mut Scopes, name : &String, taint : bool) -> Result<(), String> {
+        for scope in scopes.iter_mut().rev() {
+            if let Some(v) = scope.iter_mut().find(|v| v.0 == *name) {
+                v.2 = taint || v.3;
+                return Ok(());
+            }
+        }
+        match self.globals.iter_mut().find(|v| v.0 == *name) {
+            Some(v) => {
+                /* Globals may be read anywhere, so they are never untainted. */
+                if taint && !v.2 {
+                    v.2 = true;
+                    self.changed = true;
+                }
+                Ok(())
+            },
+            None    => Err(format!("Error: an assign uses variable '{}' which has not yet been declared.", name)),
+        }
+    }
+
+    /* Returns the type of an expression and whether it's tainted. */
+    fn check_exp(&mut self, e : &ast::exp::Exp, scopes : &Scopes) -> Result<(DataType, bool), String> {
+        match e {
+            ast::exp::Exp::A{e} => self.check_aexp(e, scopes),
+            ast::exp::Exp::B{e} => self.check_bexp(e, scopes),
+        }
+    }
+
+    fn check_aexp(&mut self, e : &ast::aexp::Aexp, scopes : &Scopes) -> Result<(DataType, bool), String> {
+        match e {
+            ast::aexp::Aexp::IntConst{v : _} => Ok((DataType::Int32, false)),
+            ast::aexp::Aexp::FloConst{v : _} => Ok((DataType::Float32, false)),
+            ast::aexp::Aexp::Add{l, r} | ast::aexp::Aexp::Sub{l, r} | ast::aexp::Aexp::Mul{l, r} |
+            ast::aexp::Aexp::Div{l, r} | ast::aexp::Aexp::Mod{l, r} => {
+                let (l_type, l_taint) = self.check_aexp(l, scopes)?;
+                let (r_type, r_taint) = self.check_aexp(r, scopes)?;
+
+                match e {
+                    ast::aexp::Aexp::Div{l : _, r : _} | ast::aexp::Aexp::Mod{l : _, r : _} if l_taint || r_taint => {
+                        self.report(e as *const ast::aexp::Aexp as usize, CtViolationKind::DivOperand, e.to_string());
+                    },
+                    _ => {},
+                }
+
+                let res_type = if l_type == DataType::Int32 && r_type == DataType::Int32 {
+                    DataType::Int32
+                } else {
+                    DataType::Float32
+                };
+                Ok((res_type, l_taint || r_taint))
+            },
+            ast::aexp::Aexp::Var{v} => self.get_var(scopes, &v.name),
+            ast::aexp::Aexp::FnCall{fc} => self.check_fn_call(fc, scopes),
+        }
+    }
+
+    fn check_bexp(&mut self, e : &ast::bexp::Bexp, scopes : &Scopes) -> Result<(DataType, bool), String> {
+        match e {
+            ast::bexp::Bexp::BoolConst{v : _} => Ok((DataType::Bool, false)),
+            ast::bexp::Bexp::Not{e} => self.check_bexp(e, scopes),
+            ast::bexp::Bexp::Beq{l, r} | ast::bexp::Bexp::Bneq{l, r} | ast::bexp::Bexp::And{l, r} | ast::bexp::Bexp::Or{l, r} => {
+                let (_, l_taint) = self.check_bexp(l, scopes)?;
+                let (_, r_taint) = self.check_bexp(r, scopes)?;
+
+                match e {
+                    ast::bexp::Bexp::And{l : _, r : _} | ast::bexp::Bexp::Or{l : _, r : _} if l_taint || r_taint => {
+                        self.report(e as *const ast::bexp::Bexp as usize, CtViolationKind::ShortCircuit, e.to_string());
+                    },
+                    _ => {},
+                }
+
+                Ok((DataType::Bool, l_taint || r_taint))
+            },
+            ast::bexp::Bexp::Aeq{l, r} | ast::bexp::Bexp::Aneq{l, r} | ast::bexp::Bexp::Lt{l ,r} |
+            ast::bexp::Bexp::Lte{l, r} | ast::bexp::Bexp::Gt{l, r}   | ast::bexp::Bexp::Gte{l, r}  => {
+                let (_, l_taint) = self.check_aexp(l, scopes)?;
+                let (_, r_taint) = self.check_aexp(r, scopes)?;
+                Ok((DataType::Bool, l_taint || r_taint))
+            },
+            ast::bexp::Bexp::Var{v} => self.get_var(scopes, &v.name),
+            ast::bexp::Bexp::FnCall{fc} => self.check_fn_call(fc, scopes),
+        }
+    }
+
+    fn check_fn_call(&mut self, fc : &ast::func_general::FnCall, scopes : &Scopes) -> Result<(DataType, bool), String> {
+        let mut arg_types : Vec<DataType> = vec![];
+        let mut arg_taints : Vec<bool> = vec![];
+        for arg in &fc.exp_list {
+            let (t, taint) = self.check_exp(arg, scopes)?;
+            arg_types.push(t);
+            arg_taints.push(taint);
+        }
+
+        let param_types = typing::resolve_call(fc, &arg_types, self.funcs.iter().map(|f| &*f.prototype))
+            .map_err(|e| format!("Error: {}", e))?;
+        let idx = match self.funcs.iter().position(|f| f.name == fc.name && f.arg_types == param_types) {
+            Some(idx) => idx,
+            None      => return Err(format!("Error: function call '{}', but no matching declaration.", fc)),
+        };
+
+        for (i, taint) in arg_taints.iter().enumerate() {
+            if *taint && !self.funcs[idx].param_taint[i] {
+                self.funcs[idx].param_taint[i] = true;
+                self.changed = true;
+            }
+        }
+
+        Ok((self.funcs[idx].ret_type, self.funcs[idx].ret_taint))
+    }
+
+    /* Checks a command under the control flow taint pc; returns the
+     * state after the command. */
+    fn check_cmd(&mut self, cmd : &ast::cmd::Cmd, mut scopes : Scopes, pc : bool, ret_idx : Option<usize>) -> Result<Scopes, String> {
+        match cmd {
+            ast::cmd::Cmd::Skip => {},
+
+            ast::cmd::Cmd::VarDecl{d} => {
+                /* Globals are gathered beforehand. */
+                if let Some(scope) = scopes.last_mut() {
+                    scope.push(VarTaint(d.name.clone(), d.var_type, d.label == SecLabel::Secret, d.label == SecLabel::Secret));
+                }
+            },
+
+            ast::cmd::Cmd::Assign{var, e} => {
+                let (_, e_taint) = self.check_exp(e, &scopes)?;
+                self.set_taint(&mut scopes, &var.name, e_taint || pc)?;
+            },
+
+            ast::cmd::Cmd::FnCall{fc} => {
+                self.check_fn_call(fc, &scopes)?;
+            },
+
+            ast::cmd::Cmd::IfElse{cond, tr_cmd, fa_cmd} => {
+                let (_, cond_taint) = self.check_bexp(cond, &scopes)?;
+                if cond_taint {
+                    self.report(&**cond as *const ast::bexp::Bexp as usize, CtViolationKind::Branch, cond.to_string());
+                }
+
+                let mut tr_scopes = scopes.clone();
+                tr_scopes.push(vec![]);
+                let mut tr_scopes = self.check_cmd(tr_cmd, tr_scopes, pc || cond_taint, ret_idx)?;
+                tr_scopes.pop();
+
+                let mut fa_scopes = scopes;
+                fa_scopes.push(vec![]);
+                let mut fa_scopes = self.check_cmd(fa_cmd, fa_scopes, pc || cond_taint, ret_idx)?;
+                fa_scopes.pop();
+
+                join_scopes(&mut tr_scopes, &fa_scopes);
+                scopes = tr_scopes;
+            },
+
+            ast::cmd::Cmd::WhileLoop{cond, lp_cmd, ..} => {
+                /* Iterate until the taints of the loop head are stable. */
+                loop {
+                    let (_, cond_taint) = self.check_bexp(cond, &scopes)?;
+                    if cond_taint {
+                        self.report(&**cond as *const ast::bexp::Bexp as usize, CtViolationKind::Loop, cond.to_string());
+                    }
+
+                    let mut lp_scopes = scopes.clone();
+                    lp_scopes.push(vec![]);
+                    let mut lp_scopes = self.check_cmd(lp_cmd, lp_scopes, pc || cond_taint, ret_idx)?;
+                    lp_scopes.pop();
+
+                    let mut next = scopes.clone();
+                    join_scopes(&mut next, &lp_scopes);
+                    if next == scopes {
+                        break;
+                    }
+                    scopes = next;
+                }
+            },
+
+            ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+                let fst_scopes = self.check_cmd(fst_cmd, scopes, pc, ret_idx)?;
+                scopes = self.check_cmd(snd_cmd, fst_scopes, pc, ret_idx)?;
+            },
+
+            /* Function bodies are checked separately. */
+            ast::cmd::Cmd::FnDecl{prototype : _, fn_cmd : _} => {},
+
+            ast::cmd::Cmd::Return{e} => {
+                let mut taint = pc;
+                if let Some(expr) = e {
+                    let (_, e_taint) = self.check_exp(expr, &scopes)?;
+                    taint = taint || e_taint;
+                }
+                if let Some(idx) = ret_idx {
+                    if taint && !self.funcs[idx].ret_taint {
+                        self.funcs[idx].ret_taint = true;
+                        self.changed = true;
+                    }
+                }
+            },
+
+            /* Whether the program aborts depends on the condition. */
+            ast::cmd::Cmd::Assert{cond} => {
+                let (_, cond_taint) = self.check_bexp(cond, &scopes)?;
+                if cond_taint {
+                    self.report(&**cond as *const ast::bexp::Bexp as usize, CtViolationKind::Branch, cond.to_string());
+                }
+            },
+        }
+
+        Ok(scopes)
+    }
+
+    fn check_fn(&mut self, idx : usize) -> Result<(), String> {
+        let prototype = self.funcs[idx].prototype.clone();
+        let fn_cmd = self.funcs[idx].fn_cmd.clone();
+
+        let mut params : Vec<VarTaint> = vec![];
+        for (i, var_decl) in prototype.var_decl_list.iter().enumerate() {
+            let taint = var_decl.label == SecLabel::Secret || self.funcs[idx].param_taint[i];
+            params.push(VarTaint(var_decl.name.clone(), var_decl.var_type, taint, taint));
+        }
+
+        self.curr_func = prototype.name.clone();
+        self.check_cmd(&fn_cmd, vec![params], false, Some(idx))?;
+
+        Ok(())
+    }
+
+    /* One pass over the global commands and every function body. */
+    fn check_program(&mut self, cmd : &ast::cmd::Cmd) -> Result<(), String> {
+        self.curr_func = String::new();
+        self.check_cmd(cmd, vec![], false, None)?;
+
+        for idx in 0..self.funcs.len() {
+            self.check_fn(idx)?;
+        }
+
+        Ok(())
+    }
+}
+
+/* Collects global variables and functions declared at the global scope. */
+fn gather_decls(cmd : &ast::cmd::Cmd, globals : &mut Vec<VarTaint>, funcs : &mut Vec<CtFuncInfo>) {
+    match cmd {
+        ast::cmd::Cmd::VarDecl{d} => {
+            globals.push(VarTaint(d.name.clone(), d.var_type, d.label == SecLabel::Secret, d.label == SecLabel::Secret));
+        },
+        ast::cmd::Cmd::FnDecl{prototype, fn_cmd} => {
+            funcs.push(CtFuncInfo {
+                name : prototype.name.clone(),
+                arg_types : prototype.var_decl_list.iter().map(|d| d.var_type).collect(),
+                ret_type : prototype.ret_type,
+                param_taint : prototype.var_decl_list.iter().map(|d| d.label == SecLabel::Secret).collect(),
+                ret_taint : false,
+                prototype : prototype.clone(),
+                fn_cmd : fn_cmd.clone(),
+            });
+        },
+        ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+            gather_decls(fst_cmd, globals, funcs);
+            gather_decls(snd_cmd, globals, funcs);
+        },
+        _ => {},
+    }
+}
+
+/* Checks that no branch, loop condition, short-circuit operand or
+ * division operand depends on a secret labeled parameter or variable.
+ * The program is expected to be well-typed (see iterate_through_ast).
+ * All violations are returned in the report; an error is returned
+ * only if the program is malformed. */
+pub fn check_const_time(cmd : &ast::cmd::Cmd) -> Result<CtReport, String> {
+    let mut globals : Vec<VarTaint> = vec![];
+    let mut funcs : Vec<CtFuncInfo> = vec![];
+    gather_decls(cmd, &mut globals, &mut funcs);
+
+    let mut checker = CtChecker {
+        funcs,
+        globals,
+        changed : true,
+        record : false,
+        curr_func : String::new(),
+        violations : vec![],
+        reported : vec![],
+    };
+
+    /* 1. Propagate taints until function summaries and globals are
+     *    stable; taints only grow, so this terminates. */
+    while checker.changed {
+        checker.changed = false;
+        checker.check_program(cmd)?;
+    }
+
+    /* 2. Record the violations with the final taints. */
+    checker.record = true;
+    checker.check_program(cmd)?;
+
+    Ok(CtReport { violations : checker.violations })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::aexp::constructor_helper::*;
+    use ast::exp::constructor_helper::*;
+    use ast::cmd::constructor_helper::*;
+    use ast::func_general::FnProtoType;
+    use ast::var_general::VarDecl;
+
+    #[test]
+    fn secret_branch_is_reported() {
+        /* Program:
+         * fn entry(secret s : Int32, p : Int32) -> secret Int32 {
+         *   if (s > 0) { return 1; } else { return 0; }
+         * } */
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Secret, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string()),
+                 VarDecl::new_labeled(DataType::Int32, SecLabel::Public, "p".to_string())]);
+        let if_cmd = if_el("s".to_aexp().gt(0i32.to_aexp()),
+            ret(Some(1i32.to_aexp().to_exp())),
+            ret(Some(0i32.to_aexp().to_exp())));
+        let prog = fn_dc(fn_prototype, if_cmd);
+
+        let report = check_const_time(&prog).unwrap();
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].kind, CtViolationKind::Branch);
+        assert_eq!(report.violations[0].func, "entry");
+    }
+
+    #[test]
+    fn secret_division_operand_is_reported() {
+        /* Program:
+         * fn entry(secret s : Int32, p : Int32) -> secret Int32 { return p / s; }
+         */
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Secret, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string()),
+                 VarDecl::new_labeled(DataType::Int32, SecLabel::Public, "p".to_string())]);
+        let prog = fn_dc(fn_prototype, ret(Some(("p".to_aexp() / "s".to_aexp()).to_exp())));
+
+        let report = check_const_time(&prog).unwrap();
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].kind, CtViolationKind::DivOperand);
+
+        /* Program:
+         * fn entry(secret s : Int32, p : Int32) -> secret Int32 { return s + p / 2; }
+         */
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Secret, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string()),
+                 VarDecl::new_labeled(DataType::Int32, SecLabel::Public, "p".to_string())]);
+        let prog = fn_dc(fn_prototype, ret(Some(("s".to_aexp() + "p".to_aexp() / 2i32.to_aexp()).to_exp())));
+
+        assert!(check_const_time(&prog).unwrap().is_clean());
+    }
+}
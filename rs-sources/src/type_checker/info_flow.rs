@@ -0,0 +1,460 @@
+use crate::ast;
+use crate::typing;
+use ast::data_type::DataType;
+use ast::sec_label::SecLabel;
+
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+
+/* Members of VarLabelPair:
+ * 1) Variable name
+ * 2) Type of variable
+ * 3) Security label of variable */
+#[derive(Clone)]
+pub struct VarLabelPair(pub String, pub DataType, pub SecLabel);
+
+/* Security signature of a declared function.
+ * write_lb is the lowest label of any global variable the function
+ * (or anything it calls) may assign to; a call made under a control
+ * flow of label pc is only allowed if pc flows to write_lb. */
+#[derive(Clone)]
+pub struct FuncLabelInfo {
+    pub name : String,
+    pub arg_types : Vec<DataType>,
+    pub arg_labels : Vec<SecLabel>,
+    pub ret_type : DataType,
+    pub ret_label : SecLabel,
+    pub write_lb : SecLabel,
+    prototype : Rc<ast::func_general::FnProtoType>,
+    fn_cmd : Rc<ast::cmd::Cmd>,
+}
+
+/* Information-flow checker.
+ *
+ * Security labels are fixed per declaration (VarDecl, function parameters
+ * and return values). The checker rejects:
+ * - explicit flows: assigning/returning/passing an expression whose label
+ *   doesn't flow to the label of the destination;
+ * - implicit flows: assigning to a public variable, returning a public value
+ *   or calling a function that writes public globals, under a control flow
+ *   that depends on a secret (if-else/while conditions, and returns taken
+ *   inside such branches, which make the rest of the function conditional).
+ *
+ * Accepted programs are (termination-insensitive) noninterferent: public
+ * outputs don't depend on secret inputs. */
+struct FlowChecker {
+    globals : Vec<VarLabelPair>,
+    funcs : Vec<FuncLabelInfo>,
+    /* While computing write_lb, flow errors are not reported. */
+    report_errors : bool,
+    /* Meet of labels of the globals written by the function being checked. */
+    curr_writes : SecLabel,
+}
+
+/* Local scopes of the function being checked; innermost scope is last. */
+type Scopes = Vec<Vec<VarLabelPair>>;
+
+impl FlowChecker {
+    fn get_var(&self, scopes : &Scopes, name : &String) -> Result<(DataType, SecLabel, bool), String> {
+        /* Returns type, label and whether the variable is global. */
+        for scope in scopes.iter().rev() {
+            if let Some(v) = scope.iter().find(|v| v.0 == *name) {
+                return Ok((v.1, v.2, false));
+            }
+        }
+        match self.globals.iter().find(|v| v.0 == *name) {
+            Some(v) => Ok((v.1, v.2, true)),
+            None    => Err(format!("Error: use of variable '{}' before declared.", name)),
+        }
+    }
+
+    /* Resolves the overload called, as the type checker does. */
+    fn find_fn(&self, fc : &ast::func_general::FnCall, arg_types : &Vec<DataType>) -> Result<usize, String> {
+        let param_types = typing::resolve_call(fc, arg_types, self.funcs.iter().map(|f| &*f.prototype))
+            .map_err(|e| format!("Error: {}", e))?;
+        match self.funcs.iter().position(|f| f.name == fc.name && f.arg_types == param_types) {
+            Some(idx) => Ok(idx),
+            None      => Err(format!("Error: function call '{}', but no matching declaration.", fc.name)),
+        }
+    }
+
+    fn flow_err(&self, msg : String) -> Result<(), String> {
+        if self.report_errors {
+            Err(msg)
+        } else {
+            Ok(())
+        }
+    }
+
+    /* Returns the type and the label of an expression. */
+    fn check_exp(&mut self, e : &ast::exp::Exp, scopes : &Scopes, pc : SecLabel) -> Result<(DataType, SecLabel), String> {
+        match e {
+            ast::exp::Exp::A{e} => self.check_aexp(e, scopes, pc),
+            ast::exp::Exp::B{e} => self.check_bexp(e, scopes, pc),
+        }
+    }
+
+    fn check_aexp(&mut self, e : &ast::aexp::Aexp, scopes : &Scopes, pc : SecLabel) -> Result<(DataType, SecLabel), String> {
+        match e {
+            ast::aexp::Aexp::IntConst{v : _} => Ok((DataType::Int32, SecLabel::Public)),
+            ast::aexp::Aexp::FloConst{v : _} => Ok((DataType::Float32, SecLabel::Public)),
+            ast::aexp::Aexp::Add{l, r} | ast::aexp::Aexp::Sub{l, r} | ast::aexp::Aexp::Mul{l, r} |
+            ast::aexp::Aexp::Div{l, r} | ast::aexp::Aexp::Mod{l, r} => {
+                let (l_type, l_label) = self.check_aexp(l, scopes, pc)?;
+                let (r_type, r_label) = self.check_aexp(r, scopes, pc)?;
+                let res_type = if l_type == DataType::Int32 && r_type == DataType::Int32 {
+                    DataType::Int32
+                } else {
+                    DataType::Float32
+                };
+                Ok((res_type, l_label.join(&r_label)))
+            },
+            ast::aexp::Aexp::Var{v} => {
+                let (t, label, _) = self.get_var(scopes, &v.name)?;
+                Ok((t, label))
+            },
+            ast::aexp::Aexp::FnCall{fc} => self.check_fn_call(fc, scopes, pc),
+        }
+    }
+
+    fn check_bexp(&mut self, e : &ast::bexp::Bexp, scopes : &Scopes, pc : SecLabel) -> Result<(DataType, SecLabel), String> {
+        match e {
+            ast::bexp::Bexp::BoolConst{v : _} => Ok((DataType::Bool, SecLabel::Public)),
+            ast::bexp::Bexp::Not{e} => self.check_bexp(e, scopes, pc),
+            ast::bexp::Bexp::Beq{l, r} | ast::bexp::Bexp::Bneq{l, r} | ast::bexp::Bexp::And{l, r} | ast::bexp::Bexp::Or{l, r} => {
+                let (_, l_label) = self.check_bexp(l, scopes, pc)?;
+                let (_, r_label) = self.check_bexp(r, scopes, pc)?;
+                Ok((DataType::Bool, l_label.join(&r_label)))
+            },
+            ast::bexp::Bexp::Aeq{l, r} | ast::bexp::Bexp::Aneq{l, r} | ast::bexp::Bexp::Lt{l ,r} |
+            ast::bexp::Bexp::Lte{l, r} | ast::bexp::Bexp::Gt{l, r}   | ast::bexp::Bexp::Gte{l, r}  => {
+                let (_, l_label) = self.check_aexp(l, scopes, pc)?;
+                let (_, r_label) = self.check_aexp(r, scopes, pc)?;
+                Ok((DataType::Bool, l_label.join(&r_label)))
+            },
+            ast::bexp::Bexp::Var{v} => {
+                let (t, label, _) = self.get_var(scopes, &v.name)?;
+                Ok((t, label))
+            },
+            ast::bexp::Bexp::FnCall{fc} => self.check_fn_call(fc, scopes, pc),
+        }
+    }
+
+    fn check_fn_call(&mut self, fc : &ast::func_general::FnCall, scopes : &Scopes, pc : SecLabel) -> Result<(DataType, SecLabel), String> {
+        let mut arg_types : Vec<DataType> = vec![];
+        let mut arg_labels : Vec<SecLabel> = vec![];
+        for arg in &fc.exp_list {
+            let (t, label) = self.check_exp(arg, scopes, pc)?;
+            arg_types.push(t);
+            arg_labels.push(label);
+        }
+
+        let idx = self.find_fn(fc, &arg_types)?;
+        let func = self.funcs[idx].clone();
+
+        /* Explicit flow from arguments into parameters. */
+        for (i, (arg_label, param_label)) in arg_labels.iter().zip(func.arg_labels.iter()).enumerate() {
+            if !arg_label.flows_to(param_label) {
+                self.flow_err(format!("Error: {} argument '{}' is passed to {} parameter '{}' of function '{}'.",
+                    arg_label, fc.exp_list[i], param_label, func.prototype.var_decl_list[i].name, fc.name))?;
+            }
+        }
+
+        /* Implicit flow through the globals written by the callee. */
+        if !pc.flows_to(&func.write_lb) {
+            self.flow_err(format!("Error: function '{}' writes {} global variables, but it's called under {} control flow.",
+                fc.name, func.write_lb, pc))?;
+        }
+        self.curr_writes = self.curr_writes.meet(&func.write_lb);
+
+        Ok((func.ret_type, func.ret_label))
+    }
+
+    /* Checks a command under the control flow label pc.
+     * Returns the join of the control flow labels under which the
+     * command may return from the function; commands following it
+     * only execute if it didn't return, so they depend on that label. */
+    fn check_cmd(&mut self, cmd : &ast::cmd::Cmd, scopes : &mut Scopes, pc : SecLabel, ret_label : SecLabel) -> Result<SecLabel, String> {
+        match cmd {
+            ast::cmd::Cmd::Skip => Ok(SecLabel::Public),
+
+            ast::cmd::Cmd::VarDecl{d} => {
+                /* Globals are gathered beforehand. */
+                if let Some(scope) = scopes.last_mut() {
+                    scope.push(VarLabelPair(d.name.clone(), d.var_type, d.label));
+                }
+                Ok(SecLabel::Public)
+            },
+
+            ast::cmd::Cmd::Assign{var, e} => {
+                let (_, e_label) = self.check_exp(e, scopes, pc)?;
+                let (_, var_label, is_global) = self.get_var(scopes, &var.name)?;
+
+                if !e_label.join(&pc).flows_to(&var_label) {
+                    if !e_label.flows_to(&var_label) {
+                        self.flow_err(format!("Error: {} expression '{}' is assigned to {} variable '{}'.", e_label, e, var_label, var))?;
+                    } else {
+                        self.flow_err(format!("Error: {} variable '{}' is assigned under {} control flow.", var_label, var, pc))?;
+                    }
+                }
+                if is_global {
+                    self.curr_writes = self.curr_writes.meet(&var_label);
+                }
+                Ok(SecLabel::Public)
+            },
+
+            ast::cmd::Cmd::FnCall{fc} => {
+                self.check_fn_call(fc, scopes, pc)?;
+                Ok(SecLabel::Public)
+            },
+
+            ast::cmd::Cmd::IfElse{cond, tr_cmd, fa_cmd} => {
+                let (_, cond_label) = self.check_bexp(cond, scopes, pc)?;
+                let branch_pc = pc.join(&cond_label);
+
+                scopes.push(vec![]);
+                let tr_ret = self.check_cmd(tr_cmd, scopes, branch_pc, ret_label);
+                scopes.pop();
+                scopes.push(vec![]);
+                let fa_ret = self.check_cmd(fa_cmd, scopes, branch_pc, ret_label);
+                scopes.pop();
+
+                Ok(tr_ret?.join(&fa_ret?))
+            },
+
+            ast::cmd::Cmd::WhileLoop{cond, lp_cmd, ..} => {
+                /* A return in the body makes later iterations (and thus
+                 * the condition) conditional on it as well, so the
+                 * condition is checked again under the raised pc. */
+                let mut body_pc = pc;
+                loop {
+                    let (_, cond_label) = self.check_bexp(cond, scopes, body_pc)?;
+                    body_pc = body_pc.join(&cond_label);
+
+                    scopes.push(vec![]);
+                    let lp_ret = self.check_cmd(lp_cmd, scopes, body_pc, ret_label);
+                    scopes.pop();
+                    let lp_ret = lp_ret?;
+
+                    if lp_ret.flows_to(&body_pc) {
+                        return Ok(lp_ret);
+                    }
+                    body_pc = body_pc.join(&lp_ret);
+                }
+            },
+
+            ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+                let fst_ret = self.check_cmd(fst_cmd, scopes, pc, ret_label)?;
+                let snd_ret = self.check_cmd(snd_cmd, scopes, pc.join(&fst_ret), ret_label)?;
+                Ok(fst_ret.join(&snd_ret))
+            },
+
+            /* Function bodies are checked separately. */
+            ast::cmd::Cmd::FnDecl{prototype : _, fn_cmd : _} => Ok(SecLabel::Public),
+
+            ast::cmd::Cmd::Return{e} => {
+                if let Some(expr) = e {
+                    let (_, e_label) = self.check_exp(expr, scopes, pc)?;
+                    if !e_label.flows_to(&ret_label) {
+                        self.flow_err(format!("Error: 'return {}' returns a {} value from a function with {} return value.", expr, e_label, ret_label))?;
+                    } else if !pc.flows_to(&ret_label) {
+                        self.flow_err(format!("Error: 'return {}' is under {} control flow, but the function has {} return value.", expr, pc, ret_label))?;
+                    }
+                }
+                Ok(pc)
+            },
+
+            /* A failing assertion aborts the whole program, which is
+             * observable regardless of the labels in scope. */
+            ast::cmd::Cmd::Assert{cond} => {
+                let (_, cond_label) = self.check_bexp(cond, scopes, pc)?;
+                if !cond_label.join(&pc).flows_to(&SecLabel::Public) {
+                    self.flow_err(format!("Error: assertion '{}' depends on {} data, but its failure is observable.", cond, cond_label.join(&pc)))?;
+                }
+                Ok(SecLabel::Public)
+            },
+        }
+    }
+
+    /* Checks a function body; returns the label of globals it writes. */
+    fn check_fn(&mut self, idx : usize) -> Result<SecLabel, String> {
+        let func = self.funcs[idx].clone();
+
+        let mut params : Vec<VarLabelPair> = vec![];
+        for var_decl in &func.prototype.var_decl_list {
+            params.push(VarLabelPair(var_decl.name.clone(), var_decl.var_type, var_decl.label));
+        }
+        let mut scopes : Scopes = vec![params];
+
+        self.curr_writes = SecLabel::Secret;
+        self.check_cmd(&func.fn_cmd, &mut scopes, SecLabel::Public, func.ret_label)?;
+
+        Ok(self.curr_writes)
+    }
+}
+
+/* Collects global variables and functions declared at the global scope. */
+fn gather_decls(cmd : &ast::cmd::Cmd, globals : &mut Vec<VarLabelPair>, funcs : &mut Vec<FuncLabelInfo>) {
+    match cmd {
+        ast::cmd::Cmd::VarDecl{d} => {
+            globals.push(VarLabelPair(d.name.clone(), d.var_type, d.label));
+        },
+        ast::cmd::Cmd::FnDecl{prototype, fn_cmd} => {
+            funcs.push(FuncLabelInfo {
+                name : prototype.name.clone(),
+                arg_types : prototype.var_decl_list.iter().map(|d| d.var_type).collect(),
+                arg_labels : prototype.var_decl_list.iter().map(|d| d.label).collect(),
+                ret_type : prototype.ret_type,
+                ret_label : prototype.ret_label,
+                write_lb : SecLabel::Secret,
+                prototype : prototype.clone(),
+                fn_cmd : fn_cmd.clone(),
+            });
+        },
+        ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+            gather_decls(fst_cmd, globals, funcs);
+            gather_decls(snd_cmd, globals, funcs);
+        },
+        _ => {},
+    }
+}
+
+/* Checks that the program doesn't leak secret values into public
+ * variables, public return values, or public function parameters.
+ * The program is expected to be well-typed (see iterate_through_ast).
+ * On success, returns the security signature of every function. */
+pub fn check_info_flow(cmd : &ast::cmd::Cmd) -> Result<Vec<FuncLabelInfo>, String> {
+    let mut globals : Vec<VarLabelPair> = vec![];
+    let mut funcs : Vec<FuncLabelInfo> = vec![];
+    gather_decls(cmd, &mut globals, &mut funcs);
+
+    let mut checker = FlowChecker {
+        globals,
+        funcs,
+        report_errors : false,
+        curr_writes : SecLabel::Secret,
+    };
+
+    /* 1. Compute the write effects of functions. They can only decrease,
+     *    so the iteration terminates. */
+    loop {
+        let mut changed = false;
+        for idx in 0..checker.funcs.len() {
+            let write_lb = checker.check_fn(idx)?;
+            if write_lb != checker.funcs[idx].write_lb {
+                checker.funcs[idx].write_lb = write_lb;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    /* 2. Check the flows in every function, and in the global commands. */
+    checker.report_errors = true;
+    for idx in 0..checker.funcs.len() {
+        match checker.check_fn(idx) {
+            Ok(_)    => {},
+            Err(why) => return Err(format!("{}\nIn function '{}'.", why, checker.funcs[idx].prototype)),
+        }
+    }
+
+    let mut global_scopes : Scopes = vec![];
+    checker.check_cmd(cmd, &mut global_scopes, SecLabel::Public, SecLabel::Public)?;
+
+    Ok(checker.funcs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::aexp::constructor_helper::*;
+    use ast::exp::constructor_helper::*;
+    use ast::cmd::constructor_helper::*;
+    use ast::func_general::FnProtoType;
+    use ast::var_general::VarDecl;
+    use ast::var_general::VarRef;
+
+    #[test]
+    fn explicit_leak_is_rejected() {
+        /* Program:
+         * fn entry(secret s : Int32) -> Int32 { return s; }
+         */
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Public, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string())]);
+        let prog = fn_dc(fn_prototype, ret(Some("s".to_aexp().to_exp())));
+
+        assert!(check_info_flow(&prog).is_err());
+
+        /* Program:
+         * fn entry(secret s : Int32) -> secret Int32 { return s; }
+         */
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Secret, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string())]);
+        let prog = fn_dc(fn_prototype, ret(Some("s".to_aexp().to_exp())));
+
+        let sigs = check_info_flow(&prog).unwrap();
+        assert_eq!(sigs.len(), 1);
+        assert_eq!(sigs[0].arg_labels, vec![SecLabel::Secret]);
+        assert_eq!(sigs[0].ret_label, SecLabel::Secret);
+    }
+
+    #[test]
+    fn implicit_leak_is_rejected() {
+        /* Program:
+         * Int32 p;
+         * fn entry(secret s : Int32) -> Int32 { if (s > 0) { p = 1; } else { skip; } return 0; }
+         */
+        let p_dec = var_dc(VarDecl::new_labeled(DataType::Int32, SecLabel::Public, "p".to_string()));
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Public, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string())]);
+        let if_cmd = if_el("s".to_aexp().gt(0i32.to_aexp()),
+            assign(VarRef::from_str("p"), 1i32.to_aexp().to_exp()),
+            skip());
+        let prog = seq(p_dec, fn_dc(fn_prototype, seq(if_cmd, ret(Some(0i32.to_aexp().to_exp())))));
+
+        assert!(check_info_flow(&prog).is_err());
+
+        /* Program:
+         * Int32 p;
+         * fn entry(secret s : Int32) -> Int32 { p = 1; return 0; }
+         */
+        let p_dec = var_dc(VarDecl::new_labeled(DataType::Int32, SecLabel::Public, "p".to_string()));
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Public, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string())]);
+        let p_asg = assign(VarRef::from_str("p"), 1i32.to_aexp().to_exp());
+        let prog = seq(p_dec, fn_dc(fn_prototype, seq(p_asg, ret(Some(0i32.to_aexp().to_exp())))));
+
+        assert!(check_info_flow(&prog).is_ok());
+    }
+
+    #[test]
+    fn loop_condition_after_secret_return_is_rejected() {
+        /* Program:
+         * Int32 p;
+         * fn bump() -> Bool { p = p + 1; return true; }
+         * fn entry(secret s : Int32) -> secret Int32 {
+         *   while (bump()) { if (s > 0) { return 0; } else { skip; } }
+         *   return 1;
+         * }
+         */
+        let p_dec = var_dc(VarDecl::new_labeled(DataType::Int32, SecLabel::Public, "p".to_string()));
+        let bump = fn_dc(FnProtoType::new_labeled(DataType::Bool, SecLabel::Public, "bump".to_string(), vec![]),
+            seq(assign(VarRef::from_str("p"), ("p".to_aexp() + 1i32.to_aexp()).to_exp()),
+                ret(Some(ast::bexp::Bexp::BoolConst{ v : true }.to_exp()))));
+        let fn_prototype = FnProtoType::new_labeled(DataType::Int32, SecLabel::Secret, "entry".to_string(),
+            vec![VarDecl::new_labeled(DataType::Int32, SecLabel::Secret, "s".to_string())]);
+        let if_cmd = if_el("s".to_aexp().gt(0i32.to_aexp()),
+            ret(Some(0i32.to_aexp().to_exp())),
+            skip());
+        let lp = wh_lp(ast::bexp::Bexp::FnCall{ fc : ast::func_general::FnCall::new("bump".to_string(), vec![]) }, if_cmd);
+        let prog = seq(p_dec, seq(bump, fn_dc(fn_prototype, seq(lp, ret(Some(1i32.to_aexp().to_exp()))))));
+
+        match check_info_flow(&prog) {
+            Err(why) => assert!(why.contains("'bump' writes"), "{}", why),
+            Ok(_) => panic!("The call in the loop condition is accepted."),
+        }
+    }
+}
@@ -1 +1,5 @@
 pub mod type_checker;
+pub mod policy;
+pub mod info_flow;
+pub mod const_time;
+pub mod annotate;
@@ -0,0 +1,109 @@
+use std::string::String;
+
+/* Flags of the checks that a program must pass, in addition to
+ * type-checking, before the type-checker enclave signs it.
+ * The flags are passed into the enclave as a single u32. */
+pub const POLICY_INTERVAL_SAFE : u32 = 0x0000_0001;
+pub const POLICY_NONINTERFERENCE : u32 = 0x0000_0002;
+pub const POLICY_CONSTANT_TIME : u32 = 0x0000_0004;
+pub const POLICY_CONTRACTS : u32 = 0x0000_0008;
+pub const POLICY_RESOURCES : u32 = 0x0000_0010;
+pub const POLICY_TESTS : u32 = 0x0000_0020;
+
+const POLICY_ALL : u32 = POLICY_INTERVAL_SAFE | POLICY_NONINTERFERENCE | POLICY_CONSTANT_TIME | POLICY_CONTRACTS |
+    POLICY_RESOURCES | POLICY_TESTS;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CheckerPolicy {
+    /* The interval analysis must prove that no division by zero
+     * and no i32 overflow can happen. */
+    pub require_interval_safe : bool,
+    /* The information-flow checker must accept the program, i.e.
+     * secret values never flow into public outputs. */
+    pub require_noninterference : bool,
+    /* The constant-time checker must report no secret-dependent
+     * branch, loop, short-circuit or division. */
+    pub require_constant_time : bool,
+    /* Every verification condition generated from the requires/ensures
+     * clauses and loop invariants must be proved, with i32 arithmetic
+     * wrapping around as in the interpreter. */
+    pub require_contracts : bool,
+    /* The stack and heap usage of the program must be bounded, and fit in
+     * the resources of the interpreter enclave. */
+    pub require_resource_bounds : bool,
+    /* The program must come with test functions, and all of them
+     * must pass in the interpreter. */
+    pub require_tests_pass : bool,
+}
+
+impl CheckerPolicy {
+    /* Policy with type-checking only. */
+    pub fn type_check_only() -> CheckerPolicy {
+        CheckerPolicy {
+            require_interval_safe : false,
+            require_noninterference : false,
+            require_constant_time : false,
+            require_contracts : false,
+            require_resource_bounds : false,
+            require_tests_pass : false,
+        }
+    }
+
+    pub fn from_flags(flags : u32) -> Result<CheckerPolicy, String> {
+        if (flags & !POLICY_ALL) != 0 {
+            return Err(format!("Unknown checker policy flags {:#x}.", flags));
+        }
+
+        Ok(CheckerPolicy {
+            require_interval_safe : (flags & POLICY_INTERVAL_SAFE) != 0,
+            require_noninterference : (flags & POLICY_NONINTERFERENCE) != 0,
+            require_constant_time : (flags & POLICY_CONSTANT_TIME) != 0,
+            require_contracts : (flags & POLICY_CONTRACTS) != 0,
+            require_resource_bounds : (flags & POLICY_RESOURCES) != 0,
+            require_tests_pass : (flags & POLICY_TESTS) != 0,
+        })
+    }
+
+    pub fn to_flags(&self) -> u32 {
+        let mut flags : u32 = 0;
+        if self.require_interval_safe {
+            flags |= POLICY_INTERVAL_SAFE;
+        }
+        if self.require_noninterference {
+            flags |= POLICY_NONINTERFERENCE;
+        }
+        if self.require_constant_time {
+            flags |= POLICY_CONSTANT_TIME;
+        }
+        if self.require_contracts {
+            flags |= POLICY_CONTRACTS;
+        }
+        if self.require_resource_bounds {
+            flags |= POLICY_RESOURCES;
+        }
+        if self.require_tests_pass {
+            flags |= POLICY_TESTS;
+        }
+        flags
+    }
+
+    /* Parse a policy from a comma separated list of check names,
+     * e.g. "interval,noninterference". An empty string means type-checking only. */
+    pub fn parse(s : &str) -> Result<CheckerPolicy, String> {
+        let mut policy = CheckerPolicy::type_check_only();
+
+        for item in s.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            match item {
+                "interval"        => policy.require_interval_safe = true,
+                "noninterference" => policy.require_noninterference = true,
+                "constant_time"   => policy.require_constant_time = true,
+                "contracts"       => policy.require_contracts = true,
+                "resources"       => policy.require_resource_bounds = true,
+                "tests"           => policy.require_tests_pass = true,
+                _                 => return Err(format!("Unknown checker policy {}.", item)),
+            }
+        }
+
+        Ok(policy)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::ast;
+use crate::typing;
 
 use std::string::String;
 use std::string::ToString;
@@ -50,7 +51,7 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
              * 2. Figure out type of RHS + make sure it is well-typed.
              * 3. Check to make sure LHS var is:
              *   a. Already declared.
-             *   b. Type(LHS) = Type(RHS). */
+             *   b. Type(RHS) can be promoted to Type(LHS). */
 
             let (is_prev_decl, decl_type, _set) = get_var_type(&var_types, &(*var).name);
 
@@ -69,7 +70,7 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
 
                 match res {
                     Ok(etype) => {
-                        if etype == decl_type {
+                        if typing::assign_type(&decl_type, &etype).is_ok() {
                             // Make sure to set var's pair to show it has been set.
                             for var_info in &mut var_types {
                                 if var_info.0 == (*var).name {
@@ -79,7 +80,7 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
                             // Then return updated var_types.
                             Ok(var_types)
                         } else {
-                            Err(format!("Error: variable '{}' being assigned to does not have same type as RHS type '{}'.", var, e))
+                            Err(format!("Error: variable '{}' being assigned to can't hold the type of RHS '{}'.", var, e))
                         }
                     },
                     Err(why) => Err(why)
@@ -120,12 +121,10 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
             if err {
                 Err(err_string)
             } else {
-                // Check to see if a matching FnDecl exists.
-                let (found, _) = get_fn_return_type(fn_types, &fc.name, &fncall_arg_types);
-                if found {
-                    Ok(var_types)
-                } else {
-                    Err(format!("Error: function call {}, but no matching declaration", fc.name))
+                // Check to see if a matching FnDecl exists, and it's the only best match.
+                match resolve_fn_call(fn_types, &fc, &fncall_arg_types) {
+                    Ok(_)    => Ok(var_types),
+                    Err(why) => Err(why),
                 }
             }
         },
@@ -175,7 +174,10 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
         },
 
         // While loop
-        ast::cmd::Cmd::WhileLoop{cond, lp_cmd} => {
+        ast::cmd::Cmd::WhileLoop{cond, lp_cmd, invariants, ..} => {
+            /* Loop invariants are checked in the scope before the loop. */
+            check_contract_types(&invariants, &var_types, fn_types, "loop invariant")?;
+
             match check_bexpr_type(&cond, &var_types, fn_types) {
                 Ok(ast::data_type::DataType::Bool) => {
                     /* FIXME: This isn't perfect. This will tell if you the types are correct
@@ -227,6 +229,18 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
                 var_types_clone.push(VarTypePair(var_decl.name.clone(), var_decl.var_type, true));
             }
 
+            /* The contract is checked in the scope of the fn's args, plus
+             * 'result' of the fn's return type in the ensures clauses. */
+            check_contract_types(&prototype.requires, &var_types_clone, fn_types, "requires clause")?;
+            let mut ensures_var_types = var_types_clone.clone();
+            if prototype.ret_type != ast::data_type::DataType::Void {
+                if prototype.var_decl_list.iter().any(|d| d.name == ast::func_general::RESULT_VAR_NAME) {
+                    return Err(format!("Error: function '{}' has an arg named '{}', which is reserved for ensures clauses.", prototype.name, ast::func_general::RESULT_VAR_NAME));
+                }
+                ensures_var_types.insert(0, VarTypePair(ast::func_general::RESULT_VAR_NAME.to_string(), prototype.ret_type, true));
+            }
+            check_contract_types(&prototype.ensures, &ensures_var_types, fn_types, "ensures clause")?;
+
             let fn_cmd_cp : ast::cmd::Cmd = (*fn_cmd).clone();
 
             match iterate_through_ast(fn_cmd_cp, false, var_types_clone, fn_types, (*prototype).ret_type) {
@@ -244,7 +258,7 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
 
             match e {
                 None => {
-                    if curr_fn_type == ast::data_type::DataType::Void {
+                    if typing::return_type(&curr_fn_type, None).is_ok() {
                         Ok(var_types)
                     } else {
                         Err(format!("Error: 'return' has void type, which is not function's return type."))
@@ -261,11 +275,8 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
                     };
                     match res {
                         Ok(ret_type) => {
-                            if ret_type == curr_fn_type {
-                                // If return type matches func type, it type checks.
-                                Ok(var_types)
-                            } else if curr_fn_type == ast::data_type::DataType::Float32 && ret_type == ast::data_type::DataType::Int32 {
-                                // If return type = Int32 and func_type = Float32, type check is OK.
+                            if typing::return_type(&curr_fn_type, Some(&ret_type)).is_ok() {
+                                // If return type matches func type, or is promoted to it, it type checks.
                                 Ok(var_types)
                             } else {
                                 Err(format!("Error: 'return {}' does not have same type as function type.", expr))
@@ -276,7 +287,30 @@ pub fn iterate_through_ast(cmd: ast::cmd::Cmd, is_cmd_global : bool, mut var_typ
                 },
             }
         },
+
+        // Assert
+        ast::cmd::Cmd::Assert{cond} => {
+            match check_bexpr_type(&cond, &var_types, fn_types) {
+                Ok(ast::data_type::DataType::Bool) => Ok(var_types),
+                Ok(_) => Err(format!("Error: use of expression '{}' as assertion, but it's not a boolean.", cond)),
+                Err(cond_why) => Err(cond_why),
+            }
+        },
+    }
+}
+
+/* Make sure each clause of a contract (or loop invariant) is a
+ * well-typed boolean expression. */
+fn check_contract_types(clauses: &std::vec::Vec<ast::bexp::Bexp>, var_types: &std::vec::Vec<VarTypePair>,
+                        fn_types: &std::vec::Vec<FuncIdentifierTuple>, what: &str) -> Result<(), String> {
+    for clause in clauses {
+        match check_bexpr_type(clause, var_types, fn_types) {
+            Ok(ast::data_type::DataType::Bool) => (),
+            Ok(_) => return Err(format!("Error: use of expression '{}' as {}, but it's not a boolean.", clause, what)),
+            Err(why) => return Err(why),
+        }
     }
+    Ok(())
 }
 
 /* This function checks to make sure a given Bexp is well-typed.
@@ -293,10 +327,8 @@ fn check_bexpr_type(bexp: &ast::bexp::Bexp, var_types: &std::vec::Vec<VarTypePai
         // Unary bool comparison
         ast::bexp::Bexp::Not{e} => {
             match check_bexpr_type(e, var_types, fn_types) {
-                Ok(ast::data_type::DataType::Bool) => {
-                    Ok(ast::data_type::DataType::Bool)
-                },
-                Ok(_) => Err(format!("Error: expression '{}' is not of type bool, but used as such with not operator.", e)),
+                Ok(e_type) => typing::not_type(&e_type).map_err(|_|
+                    format!("Error: expression '{}' is not of type bool, but used as such with not operator.", e)),
                 Err(why) => Err(why),
             }
         },
@@ -306,16 +338,16 @@ fn check_bexpr_type(bexp: &ast::bexp::Bexp, var_types: &std::vec::Vec<VarTypePai
             let l_type = check_bexpr_type(l, var_types, fn_types);
             let r_type = check_bexpr_type(r, var_types, fn_types);
 
-            if l_type.is_ok() && r_type.is_ok() {
-                if l_type != Ok(ast::data_type::DataType::Bool) {
+            if let (Ok(lt), Ok(rt)) = (&l_type, &r_type) {
+                if typing::bexp_type(lt).is_err() {
                     // l_type is incorrect type.
                     Err(format!("Error: expression '{}' is not of type bool, but used as such in bool comparison.", l))
-                } else if r_type != Ok(ast::data_type::DataType::Bool)  {
+                } else if typing::bexp_type(rt).is_err() {
                     // r_type is incorrect type.
                     Err(format!("Error: expression '{}' is not of type bool, but used as such in bool comparison.", r))
                 } else {
                     // l_type and r_type are both of Bool type. Therefore entire expr type is Bool.
-                    Ok(ast::data_type::DataType::Bool)
+                    typing::bool_op_type(lt, rt)
                 }
             } else if !l_type.is_ok() {
                 // If we reach here, l_type equals some error message.
@@ -332,16 +364,16 @@ fn check_bexpr_type(bexp: &ast::bexp::Bexp, var_types: &std::vec::Vec<VarTypePai
             let l_type = check_aexpr_type(l, var_types, fn_types);
             let r_type = check_aexpr_type(r, var_types, fn_types);
 
-            if l_type.is_ok() && r_type.is_ok() {
-                if l_type != Ok(ast::data_type::DataType::Int32) && l_type != Ok(ast::data_type::DataType::Float32) {
+            if let (Ok(lt), Ok(rt)) = (&l_type, &r_type) {
+                if typing::aexp_type(lt).is_err() {
                     // l_type is incorrect type.
                     Err(format!("Error: expression '{}' is not of type Int32/Float32, but used as such in arith comparison.", l))
-                } else if r_type != Ok(ast::data_type::DataType::Int32) && r_type != Ok(ast::data_type::DataType::Float32) {
+                } else if typing::aexp_type(rt).is_err() {
                     // r_type is incorrect type.
                     Err(format!("Error: expression '{}' is not of type Int32/Float32, but used as such in arith comparison.", r))
                 } else {
                     // l_type and r_type are both of Int32/Float32 type. Therefore entire expr type is Bool.
-                    Ok(ast::data_type::DataType::Bool)
+                    typing::arith_cmp_type(lt, rt).map(|_| ast::data_type::DataType::Bool)
                 }
             } else if !l_type.is_ok() {
                 // If we reach here, l_type equals some error message.
@@ -354,14 +386,14 @@ fn check_bexpr_type(bexp: &ast::bexp::Bexp, var_types: &std::vec::Vec<VarTypePai
 
         // Variable
         ast::bexp::Bexp::Var{v} => {
-            //Check to make sure variable is of type Float32 or Int32.
+            //Check to make sure variable is of type Bool.
             let (is_prev_decl, decl_type, set) = get_var_type(var_types, &(*v).name);
             if !is_prev_decl {
                 Err(format!("Error: use of variable {} before declared.", v))
             } else if !set {
                 Err(format!("Error: use of variable {} before given value.", v))
             } else {
-                Ok(decl_type)
+                typing::bexp_type(&decl_type).map_err(|why| format!("Error: use of variable {} as a bool. {}", v, why))
             }
         },
         ast::bexp::Bexp::FnCall{fc} => {
@@ -397,15 +429,13 @@ fn check_bexpr_type(bexp: &ast::bexp::Bexp, var_types: &std::vec::Vec<VarTypePai
             if arg_has_err {
                 Err(arg_err)
             } else {
-                /* Check to see if a function declaration exists with name
-                 * fc.name and matching argument type list (compared to
-                 * fncall_arg_types). If so, return the type returned
-                 * by that function. */
-                let (found, fn_ret_type) = get_fn_return_type(fn_types, &fc.name, &fncall_arg_types);
-                if found {
-                    Ok(fn_ret_type)
-                } else {
-                    Err(format!("Error: function call '{}', but no matching declaration.", fc))
+                /* Resolve the overload of fc.name called with arguments of
+                 * types fncall_arg_types (see typing::resolve_overload).
+                 * If there's one, return the type returned by it. */
+                match resolve_fn_call(fn_types, fc, &fncall_arg_types) {
+                    Ok((fn_ret_type, _)) =>
+                        typing::bexp_type(&fn_ret_type).map_err(|why| format!("Error: use of function call '{}' as a bool. {}", fc, why)),
+                    Err(why) => Err(why),
                 }
             }
         },
@@ -431,22 +461,18 @@ fn check_aexpr_type(aexp: &ast::aexp::Aexp, var_types: &std::vec::Vec<VarTypePai
         ast::aexp::Aexp::Div{l, r} | ast::aexp::Aexp::Mod{l, r} => {
             let l_type = check_aexpr_type(l, var_types, fn_types);
             let r_type = check_aexpr_type(r, var_types, fn_types);
-            if l_type.is_ok() && r_type.is_ok() {
-                if l_type != Ok(ast::data_type::DataType::Int32) && l_type != Ok(ast::data_type::DataType::Float32) {
+            if let (Ok(lt), Ok(rt)) = (&l_type, &r_type) {
+                if typing::aexp_type(lt).is_err() {
                     // l_type is incorrect type.
                     Err(format!("Error: expression '{}' is not an Int32/Float32 type, but used as such in arith operations.", l))
-                } else if r_type != Ok(ast::data_type::DataType::Int32) && r_type != Ok(ast::data_type::DataType::Float32) {
+                } else if typing::aexp_type(rt).is_err() {
                     // r_type is incorrect type.
                     Err(format!("Error: expression '{}' is not an Int32/Float32 type, but used as such in arith operations.", r))
                 } else {
                     /* l_type and r_type are either Int32 or Float32.
                      * If both are Int32, then expr type is Int32.
                      * Otherwise, expr type is Float32. */
-                    if l_type == Ok(ast::data_type::DataType::Int32) && r_type == Ok(ast::data_type::DataType::Int32) {
-                        Ok(ast::data_type::DataType::Int32)
-                    } else {
-                        Ok(ast::data_type::DataType::Float32)
-                    }
+                    typing::arith_type(lt, rt)
                 }
             } else if !l_type.is_ok() {
                 // If we reach here, l_type equals some error message.
@@ -466,7 +492,7 @@ fn check_aexpr_type(aexp: &ast::aexp::Aexp, var_types: &std::vec::Vec<VarTypePai
             } else if !set {
                 Err(format!("Error: use of variable '{}' before given value.", v))
             } else {
-                Ok(decl_type)
+                typing::aexp_type(&decl_type).map_err(|why| format!("Error: use of variable '{}' as an Int32/Float32. {}", v, why))
             }
         },
 
@@ -504,15 +530,13 @@ fn check_aexpr_type(aexp: &ast::aexp::Aexp, var_types: &std::vec::Vec<VarTypePai
             if arg_has_err {
                 Err(arg_err)
             } else {
-                /* Check to see if a function declaration exists with name
-                 * fc.name and matching argument type list (compared to
-                 * fncall_arg_types). If so, return the type returned
-                 * by that function. */
-                let (found, fn_ret_type) = get_fn_return_type(fn_types, &fc.name, &fncall_arg_types);
-                if found {
-                    Ok(fn_ret_type)
-                } else {
-                    Err(format!("Error: function call '{}', but no matching declaration.", fc))
+                /* Resolve the overload of fc.name called with arguments of
+                 * types fncall_arg_types (see typing::resolve_overload).
+                 * If there's one, return the type returned by it. */
+                match resolve_fn_call(fn_types, fc, &fncall_arg_types) {
+                    Ok((fn_ret_type, _)) =>
+                        typing::aexp_type(&fn_ret_type).map_err(|why| format!("Error: use of function call '{}' as an Int32/Float32. {}", fc, why)),
+                    Err(why) => Err(why),
                 }
             }
         },
@@ -611,12 +635,10 @@ pub fn gather_fn_types(cmd: &ast::cmd::Cmd, glvar_types: &mut std::vec::Vec<VarT
             if err {
                 Err(err_string)
             } else {
-                // Check to see if a matching FnDecl exists.
-                let (found, _) = get_fn_return_type(fn_types, &fc.name, &fncall_arg_types);
-                if found {
-                    Ok(true)
-                } else {
-                    Err(format!("Error: global function call {}, but no matching declaration or declaration came after function call.", fc.name))
+                // Check to see if a matching FnDecl exists (among the ones declared so far).
+                match resolve_fn_call(fn_types, fc, &fncall_arg_types) {
+                    Ok(_)    => Ok(true),
+                    Err(why) => Err(format!("{} Note: a global function call can't use a declaration that comes after it.", why)),
                 }
             }
         },
@@ -667,3 +689,176 @@ fn get_fn_return_type(fn_types: &std::vec::Vec<FuncIdentifierTuple>, fn_name: &S
     }
     (found, f_type)
 }
+
+/* This resolves a function call among the overloads of the function
+ * declared in fn_types, given the types of the call's arguments: the
+ * overload needing the fewest Int32 to Float32 promotions of arguments
+ * is picked, and a call with several such overloads is ambiguous.
+ * A call that has recorded its overload (see record_overloads) must
+ * have recorded the one picked. Returns the return type and the
+ * parameter types of the overload. */
+fn resolve_fn_call(fn_types: &std::vec::Vec<FuncIdentifierTuple>, fc: &ast::func_general::FnCall,
+                   arg_types: &Vec<ast::data_type::DataType>) -> Result<(ast::data_type::DataType, Vec<ast::data_type::DataType>), String> {
+    let overloads : Vec<&FuncIdentifierTuple> = fn_types.iter().filter(|func| func.0 == fc.name).collect();
+    let param_types : Vec<Vec<ast::data_type::DataType>> = overloads.iter().map(|func| func.2.clone()).collect();
+
+    let idx = match typing::resolve_overload(&fc.name, arg_types, &param_types) {
+        Ok(idx)  => idx,
+        Err(why) => return Err(format!("Error: function call '{}'. {}", fc, why)),
+    };
+
+    if let Some(recorded) = &fc.param_types {
+        if *recorded != overloads[idx].2 {
+            return Err(format!("Error: function call '{}' records an overload other than the one it resolves to.", fc));
+        }
+    }
+
+    Ok((overloads[idx].1, overloads[idx].2.clone()))
+}
+
+/* This records in each function call of a well-typed program the
+ * parameter types of the overload it resolves to (see resolve_fn_call),
+ * so the interpreter calls that overload without resolving it again. */
+pub fn record_overloads(prog: &mut ast::cmd::Cmd) -> Result<(), String> {
+    let mut glvar_types : Vec<VarTypePair> = vec![];
+    let mut fn_types : Vec<FuncIdentifierTuple> = vec![];
+    gather_fn_types(prog, &mut glvar_types, &mut fn_types)?;
+
+    record_cmd_overloads(prog, vec![], &fn_types)?;
+    Ok(())
+}
+
+/* Walks the commands the same way iterate_through_ast does, except
+ * every declared variable is taken as set, since the program has
+ * already been checked. */
+fn record_cmd_overloads(cmd: &mut ast::cmd::Cmd, mut var_types: std::vec::Vec<VarTypePair>,
+                        fn_types: &std::vec::Vec<FuncIdentifierTuple>) -> Result<std::vec::Vec<VarTypePair>, String> {
+    match cmd {
+        ast::cmd::Cmd::Skip => Ok(var_types),
+
+        ast::cmd::Cmd::VarDecl{d} => {
+            var_types.push(VarTypePair(d.name.clone(), d.var_type, true));
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::Assign{var : _, e} => {
+            record_exp_overloads(e, &var_types, fn_types)?;
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::FnCall{fc} => {
+            record_fn_call_overload(fc, &var_types, fn_types)?;
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::IfElse{cond, tr_cmd, fa_cmd} => {
+            record_bexp_overloads(cond, &var_types, fn_types)?;
+            record_cmd_overloads(tr_cmd, var_types.clone(), fn_types)?;
+            record_cmd_overloads(fa_cmd, var_types.clone(), fn_types)?;
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::WhileLoop{cond, lp_cmd, invariants, ..} => {
+            for inv in invariants.iter_mut() {
+                record_bexp_overloads(inv, &var_types, fn_types)?;
+            }
+            record_bexp_overloads(cond, &var_types, fn_types)?;
+            record_cmd_overloads(lp_cmd, var_types.clone(), fn_types)?;
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::Seq{fst_cmd, snd_cmd} => {
+            let var_types_1 = record_cmd_overloads(fst_cmd, var_types, fn_types)?;
+            record_cmd_overloads(snd_cmd, var_types_1, fn_types)
+        },
+
+        ast::cmd::Cmd::FnDecl{prototype, fn_cmd} => {
+            let mut var_types_clone = var_types.clone();
+            for var_decl in &prototype.var_decl_list {
+                var_types_clone.push(VarTypePair(var_decl.name.clone(), var_decl.var_type, true));
+            }
+
+            let prototype = std::rc::Rc::make_mut(prototype);
+            for clause in prototype.requires.iter_mut() {
+                record_bexp_overloads(clause, &var_types_clone, fn_types)?;
+            }
+            let mut ensures_var_types = var_types_clone.clone();
+            if prototype.ret_type != ast::data_type::DataType::Void {
+                ensures_var_types.insert(0, VarTypePair(ast::func_general::RESULT_VAR_NAME.to_string(), prototype.ret_type, true));
+            }
+            for clause in prototype.ensures.iter_mut() {
+                record_bexp_overloads(clause, &ensures_var_types, fn_types)?;
+            }
+
+            record_cmd_overloads(std::rc::Rc::make_mut(fn_cmd), var_types_clone, fn_types)?;
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::Return{e} => {
+            if let Some(expr) = e {
+                record_exp_overloads(expr, &var_types, fn_types)?;
+            }
+            Ok(var_types)
+        },
+
+        ast::cmd::Cmd::Assert{cond} => {
+            record_bexp_overloads(cond, &var_types, fn_types)?;
+            Ok(var_types)
+        },
+    }
+}
+
+fn record_exp_overloads(exp: &mut ast::exp::Exp, var_types: &std::vec::Vec<VarTypePair>,
+                        fn_types: &std::vec::Vec<FuncIdentifierTuple>) -> Result<(), String> {
+    match exp {
+        ast::exp::Exp::A{e} => record_aexp_overloads(e, var_types, fn_types),
+        ast::exp::Exp::B{e} => record_bexp_overloads(e, var_types, fn_types),
+    }
+}
+
+fn record_aexp_overloads(aexp: &mut ast::aexp::Aexp, var_types: &std::vec::Vec<VarTypePair>,
+                         fn_types: &std::vec::Vec<FuncIdentifierTuple>) -> Result<(), String> {
+    match aexp {
+        ast::aexp::Aexp::Add{l, r} | ast::aexp::Aexp::Sub{l, r} | ast::aexp::Aexp::Mul{l, r} |
+        ast::aexp::Aexp::Div{l, r} | ast::aexp::Aexp::Mod{l, r} => {
+            record_aexp_overloads(l, var_types, fn_types)?;
+            record_aexp_overloads(r, var_types, fn_types)
+        },
+        ast::aexp::Aexp::FnCall{fc} => record_fn_call_overload(fc, var_types, fn_types),
+        _ => Ok(()),
+    }
+}
+
+fn record_bexp_overloads(bexp: &mut ast::bexp::Bexp, var_types: &std::vec::Vec<VarTypePair>,
+                         fn_types: &std::vec::Vec<FuncIdentifierTuple>) -> Result<(), String> {
+    match bexp {
+        ast::bexp::Bexp::Not{e} => record_bexp_overloads(e, var_types, fn_types),
+        ast::bexp::Bexp::Beq{l, r} | ast::bexp::Bexp::Bneq{l, r} | ast::bexp::Bexp::And{l, r} | ast::bexp::Bexp::Or{l, r} => {
+            record_bexp_overloads(l, var_types, fn_types)?;
+            record_bexp_overloads(r, var_types, fn_types)
+        },
+        ast::bexp::Bexp::Aeq{l, r} | ast::bexp::Bexp::Aneq{l, r} | ast::bexp::Bexp::Lt{l ,r} |
+        ast::bexp::Bexp::Lte{l, r} | ast::bexp::Bexp::Gt{l, r}   | ast::bexp::Bexp::Gte{l, r}  => {
+            record_aexp_overloads(l, var_types, fn_types)?;
+            record_aexp_overloads(r, var_types, fn_types)
+        },
+        ast::bexp::Bexp::FnCall{fc} => record_fn_call_overload(fc, var_types, fn_types),
+        _ => Ok(()),
+    }
+}
+
+fn record_fn_call_overload(fc: &mut ast::func_general::FnCall, var_types: &std::vec::Vec<VarTypePair>,
+                           fn_types: &std::vec::Vec<FuncIdentifierTuple>) -> Result<(), String> {
+    let mut fncall_arg_types : std::vec::Vec<ast::data_type::DataType> = vec![];
+    for arg in fc.exp_list.iter_mut() {
+        record_exp_overloads(arg, var_types, fn_types)?;
+        fncall_arg_types.push(match arg {
+            ast::exp::Exp::A{e} => check_aexpr_type(e, var_types, fn_types)?,
+            ast::exp::Exp::B{e} => check_bexpr_type(e, var_types, fn_types)?,
+        });
+    }
+
+    let (_, param_types) = resolve_fn_call(fn_types, fc, &fncall_arg_types)?;
+    fc.param_types = Some(param_types);
+    Ok(())
+}
@@ -0,0 +1,225 @@
+//! Typing rules of the language, shared by the type checker and the
+//! interpreter, so that a program accepted by the former never fails in
+//! the latter because of a type mismatch.
+//!
+//! The only implicit conversion is the promotion of an `Int32` value into
+//! `Float32`, which happens when an operand of an arithmetic operator or
+//! of a comparison is mixed with a `Float32` one, and when an `Int32`
+//! value is assigned, passed or returned as a `Float32` one.
+
+use std::vec::Vec;
+use std::string::String;
+use std::string::ToString;
+
+use super::ast::data_type::DataType;
+use super::ast::func_general::FnCall;
+use super::ast::func_general::FnProtoType;
+
+pub fn is_numeric(t : &DataType) -> bool
+{
+	match t
+	{
+		DataType::Int32 | DataType::Float32 => true,
+		DataType::Void  | DataType::Bool    => false,
+	}
+}
+
+/// A value of type `from` can be used where a value of type `to` is
+/// expected, i.e. the types are the same, or `from` is promoted to `to`.
+///
+pub fn promotes_to(from : &DataType, to : &DataType) -> bool
+{
+	from == to || (*from == DataType::Int32 && *to == DataType::Float32)
+}
+
+/// The type of an arithmetic expression, e.g. a variable or a function call
+/// used as an operand of `+`, must be numeric.
+///
+pub fn aexp_type(t : &DataType) -> Result<DataType, String>
+{
+	if is_numeric(t)
+	{
+		Result::Ok(*t)
+	}
+	else
+	{
+		Result::Err(format!("Expecting an Int32/Float32 type for an arithmetic expression, but {} type is given.", t))
+	}
+}
+
+/// The type of a boolean expression, e.g. a variable or a function call
+/// used as an operand of `&&`, must be `Bool`.
+///
+pub fn bexp_type(t : &DataType) -> Result<DataType, String>
+{
+	match t
+	{
+		DataType::Bool => Result::Ok(DataType::Bool),
+		_              => Result::Err(format!("Expecting a Bool type for a boolean expression, but {} type is given.", t)),
+	}
+}
+
+/// Result type of `+`, `-`, `*`, `/` and `%`; the operands are promoted to it.
+///
+pub fn arith_type(l : &DataType, r : &DataType) -> Result<DataType, String>
+{
+	let l = aexp_type(l)?;
+	let r = aexp_type(r)?;
+
+	if l == DataType::Int32 && r == DataType::Int32
+	{
+		Result::Ok(DataType::Int32)
+	}
+	else
+	{
+		Result::Ok(DataType::Float32)
+	}
+}
+
+/// Type the operands of `==`, `!=`, `<`, `<=`, `>` and `>=` on arithmetic
+/// expressions are promoted to before being compared; the result is `Bool`.
+///
+pub fn arith_cmp_type(l : &DataType, r : &DataType) -> Result<DataType, String>
+{
+	arith_type(l, r)
+}
+
+/// Result type of `==`, `!=`, `&&` and `||` on boolean expressions.
+///
+pub fn bool_op_type(l : &DataType, r : &DataType) -> Result<DataType, String>
+{
+	bexp_type(l)?;
+	bexp_type(r)
+}
+
+/// Result type of `!`.
+///
+pub fn not_type(t : &DataType) -> Result<DataType, String>
+{
+	bexp_type(t)
+}
+
+/// Type of the value stored by assigning a value of type `val_t` to a
+/// variable of type `var_t`, which is also how parameters are passed.
+///
+pub fn assign_type(var_t : &DataType, val_t : &DataType) -> Result<DataType, String>
+{
+	if *val_t != DataType::Void && promotes_to(val_t, var_t)
+	{
+		Result::Ok(*var_t)
+	}
+	else
+	{
+		Result::Err(format!("Assignment expecting {} type, but {} type is given.", var_t, val_t))
+	}
+}
+
+/// Type of the value returned by `return e`, where `e` is of type `val_t`
+/// (`Option::None` for a plain `return`), in a function returning `ret_t`.
+///
+pub fn return_type(ret_t : &DataType, val_t : Option<&DataType>) -> Result<DataType, String>
+{
+	match val_t
+	{
+		Option::None    if *ret_t == DataType::Void => Result::Ok(DataType::Void),
+		Option::None    => Result::Err(format!("Function expecting a return value of {} type, but nothing is returned.", ret_t)),
+		Option::Some(t) if *ret_t != DataType::Void && *t != DataType::Void && promotes_to(t, ret_t) => Result::Ok(*ret_t),
+		Option::Some(t) => Result::Err(format!("Function expecting a return value of {} type, but {} type is returned.", ret_t, t)),
+	}
+}
+
+/// Number of arguments promoted to pass arguments of types `arg_types` to
+/// parameters of types `param_types`, or `Option::None` if they can't be passed.
+///
+pub fn promotion_count(arg_types : &[DataType], param_types : &[DataType]) -> Option<usize>
+{
+	if arg_types.len() != param_types.len()
+	{
+		return Option::None;
+	}
+
+	let mut count : usize = 0;
+	for (arg_t, param_t) in arg_types.iter().zip(param_types.iter())
+	{
+		if arg_t == param_t
+		{
+			continue;
+		}
+		if !promotes_to(arg_t, param_t)
+		{
+			return Option::None;
+		}
+		count += 1;
+	}
+
+	Option::Some(count)
+}
+
+fn fmt_types(types : &[DataType]) -> String
+{
+	types.iter().map(|t| t.to_string()).collect::<Vec<String>>().join(", ")
+}
+
+/// Pick the overload of function `name` called with arguments of types
+/// `arg_types`, among the parameter types of its declarations, and return
+/// its index in `overloads`.
+///
+/// The overload needing the fewest promotions is picked; the call is
+/// ambiguous, i.e. an error, if several overloads need as few promotions.
+///
+pub fn resolve_overload(name : &str, arg_types : &[DataType], overloads : &[Vec<DataType>]) -> Result<usize, String>
+{
+	let mut best : Option<(usize, usize)> = Option::None;
+	let mut tied : Vec<usize> = Vec::new();
+
+	for (i, param_types) in overloads.iter().enumerate()
+	{
+		let count = match promotion_count(arg_types, param_types)
+		{
+			Option::Some(c) => c,
+			Option::None    => continue,
+		};
+
+		match best
+		{
+			Option::Some((_, best_count)) if best_count < count => {},
+			Option::Some((_, best_count)) if best_count == count => tied.push(i),
+			_ =>
+			{
+				best = Option::Some((i, count));
+				tied.clear();
+			},
+		}
+	}
+
+	match best
+	{
+		Option::None            =>
+			Result::Err(format!("No declaration of {} accepts arguments of types ({}).", name, fmt_types(arg_types))),
+		Option::Some((i, _)) if tied.is_empty() => Result::Ok(i),
+		Option::Some((i, _))    =>
+			Result::Err(format!("Call of {}({}) is ambiguous between {}({}) and {}({}).",
+				name, fmt_types(arg_types), name, fmt_types(&overloads[i]), name, fmt_types(&overloads[tied[0]]))),
+	}
+}
+
+/// Parameter types of the overload called by `fc` with arguments of types
+/// `arg_types`: the recorded ones if the call is resolved, or else the ones
+/// picked by `resolve_overload` among the declarations named after the call.
+///
+pub fn resolve_call<'a, I>(fc : &FnCall, arg_types : &[DataType], decls : I) -> Result<Vec<DataType>, String>
+	where I : Iterator<Item = &'a FnProtoType>
+{
+	if let Option::Some(types) = &fc.param_types
+	{
+		return Result::Ok(types.clone());
+	}
+
+	let mut overloads : Vec<Vec<DataType>> = decls
+		.filter(|pt| pt.name == fc.name)
+		.map(|pt| pt.var_decl_list.iter().map(|d| d.var_type).collect())
+		.collect();
+
+	let idx = resolve_overload(&fc.name, arg_types, &overloads)?;
+	Result::Ok(overloads.swap_remove(idx))
+}
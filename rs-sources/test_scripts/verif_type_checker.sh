@@ -1,6 +1,6 @@
 #!/bin/bash
 
-tests='is_prime scope_test test_bexps test_fcall_1 test_fcall_2 test_glvar_and_returnv test_ifel test_overloading'
+tests='is_prime scope_test test_bexps test_fcall_1 test_fcall_2 test_glvar_and_returnv test_ifel test_overloading test_sec_label test_oblivious test_contract'
 
 for curr_test in $tests
 do
@@ -0,0 +1,128 @@
+#![cfg(feature = "host_verifier")]
+
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::attestation;
+use enclave_verifier::attestation::Evidence;
+use enclave_verifier::attestation::QuoteBody;
+use enclave_verifier::attestation::QuoteKind;
+use enclave_verifier::enclave::software::SoftwareBackend;
+use enclave_verifier::host_verify;
+use enclave_verifier::host_verify::EvidencePolicy;
+
+fn backend(seed : u8) -> SoftwareBackend
+{
+	SoftwareBackend::from_secret(&[seed; 32]).unwrap().with_measurements([0xAAu8; 32], [0xBBu8; 32])
+}
+
+/// Evidence of the software backend survives encoding, and verifies for its
+/// measurement only.
+#[test]
+fn mock_evidence_is_verified()
+{
+	let evidence = attestation::gen_evidence(&backend(1u8)).unwrap();
+	assert_eq!(evidence.quote_kind, QuoteKind::Mock);
+
+	let bytes = evidence.to_bytes().unwrap();
+	let (bytes_left, decoded) = Evidence::from_bytes(&bytes[..]).unwrap();
+	assert!(bytes_left.is_empty());
+	assert_eq!(decoded.pub_key[..], evidence.pub_key[..]);
+
+	let body = host_verify::verify_evidence(&decoded, Some(&[0xAAu8; 32]), &EvidencePolicy::mock()).unwrap();
+	assert_eq!(body.mr_signer, [0xBBu8; 32]);
+
+	assert!(host_verify::verify_evidence(&decoded, Some(&[0xCCu8; 32]), &EvidencePolicy::mock()).is_err());
+}
+
+/// Evidence doesn't verify once its key is swapped, or its quote tampered.
+#[test]
+fn tampered_evidence_is_refused()
+{
+	let evidence = attestation::gen_evidence(&backend(1u8)).unwrap();
+	let other = attestation::gen_evidence(&backend(2u8)).unwrap();
+
+	let mut swapped = evidence.clone();
+	swapped.pub_key = other.pub_key;
+	assert!(host_verify::verify_evidence(&swapped, None, &EvidencePolicy::mock()).is_err());
+
+	let mut tampered = evidence.clone();
+	let last = tampered.quote.len() - 1;
+	tampered.quote[last] ^= 1u8;
+	assert!(host_verify::verify_evidence(&tampered, None, &EvidencePolicy::mock()).is_err());
+
+	assert!(Evidence::from_bytes(&evidence.to_bytes().unwrap()[1..]).is_err());
+}
+
+/// Mock quotes are only accepted when the policy allows them, as anyone can
+/// make one for any enclave.
+#[test]
+fn mock_evidence_needs_opt_in()
+{
+	let evidence = attestation::gen_evidence(&backend(1u8)).unwrap();
+	let accept_all = |_ : &[u8]| Ok(());
+
+	assert!(host_verify::verify_evidence(&evidence, None, &EvidencePolicy::sgx(&accept_all)).is_err());
+	assert!(host_verify::verify_evidence(&evidence, None, &EvidencePolicy { allow_mock : false, verify_quote : None }).is_err());
+}
+
+/// Evidence with the given kind of SGX quote, whose report body binds the
+/// key of the software backend to the MRENCLAVE 0x11...
+fn sgx_evidence(quote_kind : QuoteKind) -> Evidence
+{
+	let evidence = attestation::gen_evidence(&backend(1u8)).unwrap();
+	let body = QuoteBody
+	{
+		mr_enclave  : [0x11u8; 32],
+		mr_signer   : [0x22u8; 32],
+		report_data : attestation::bind_report_data(&host_verify::sha256(&evidence.pub_key)),
+	};
+
+	let quote = match quote_kind
+	{
+		QuoteKind::SgxQuote =>
+		{
+			let mut quote = vec![0u8; 48];
+			quote.append(&mut body.to_report_body());
+			quote
+		},
+		_ =>
+		{
+			let mut report = body.to_report_body();
+			report.resize(attestation::SGX_REPORT_SIZE, 0u8);
+			report
+		},
+	};
+
+	Evidence { pub_key : evidence.pub_key, quote_kind : quote_kind, quote : quote }
+}
+
+/// SGX reports can't be authenticated on hosts, whatever the policy.
+#[test]
+fn sgx_report_is_refused_on_hosts()
+{
+	let sgx_evidence = sgx_evidence(QuoteKind::SgxReport);
+	assert_eq!(sgx_evidence.to_bytes().unwrap().len(), attestation::SGX_REPORT_EVIDENCE_SIZE);
+	assert_eq!(sgx_evidence.quote_body().unwrap().mr_signer, [0x22u8; 32]);
+
+	let accept_all = |_ : &[u8]| Ok(());
+	assert!(host_verify::verify_evidence(&sgx_evidence, None, &EvidencePolicy::mock()).is_err());
+	assert!(host_verify::verify_evidence(&sgx_evidence, None, &EvidencePolicy::sgx(&accept_all)).is_err());
+}
+
+/// SGX quotes are accepted once the quote verifier accepts them, and
+/// refused without a quote verifier.
+#[test]
+fn sgx_quote_needs_quote_verifier()
+{
+	let sgx_evidence = sgx_evidence(QuoteKind::SgxQuote);
+
+	let accept_all = |_ : &[u8]| Ok(());
+	let refuse_all = |_ : &[u8]| Err("Invalid signature.".to_string());
+
+	let parsed = host_verify::verify_evidence(&sgx_evidence, Some(&[0x11u8; 32]), &EvidencePolicy::sgx(&accept_all)).unwrap();
+	assert_eq!(parsed.mr_signer, [0x22u8; 32]);
+
+	assert!(host_verify::verify_evidence(&sgx_evidence, Some(&[0xCCu8; 32]), &EvidencePolicy::sgx(&accept_all)).is_err());
+	assert!(host_verify::verify_evidence(&sgx_evidence, None, &EvidencePolicy::sgx(&refuse_all)).is_err());
+	assert!(host_verify::verify_evidence(&sgx_evidence, None, &EvidencePolicy::mock()).is_err());
+}
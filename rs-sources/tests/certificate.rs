@@ -0,0 +1,78 @@
+use enclave_verifier::ast::*;
+use enclave_verifier::certificate;
+use enclave_verifier::certificate::Certificate;
+use enclave_verifier::analyzer::interval::{Interval, ParamRanges};
+use enclave_verifier::type_checker::policy::CheckerPolicy;
+
+fn signed_cert() -> Certificate
+{
+	let policy = CheckerPolicy::parse("interval,contracts").unwrap();
+	let mut cert = Certificate::new([7u8; 32], [5u8; 32], [3u8; 64], &policy, 42);
+	cert.signature = [9u8; 64];
+	cert
+}
+
+/// Certificates survive encoding, and the signed bytes are the encoded
+/// certificate without the signature.
+#[test]
+fn certificate_round_trip()
+{
+	let cert = signed_cert();
+	let bytes = cert.to_bytes().unwrap();
+	assert_eq!(bytes.len(), certificate::CERT_SIZE);
+	assert_eq!(&bytes[..(certificate::CERT_SIZE - 64)], &cert.signed_bytes()[..]);
+
+	let (bytes_left, parsed) = Certificate::from_bytes(&bytes[..]).unwrap();
+	assert!(bytes_left.is_empty());
+	assert_eq!(parsed, cert);
+	assert!(parsed.policy().unwrap().require_contracts);
+	assert!(!parsed.policy().unwrap().require_noninterference);
+
+	assert!(parsed.check_prog_hash(&[7u8; 32]).is_ok());
+	assert!(parsed.check_prog_hash(&[8u8; 32]).is_err());
+	assert!(parsed.check_ranges_hash(&[5u8; 32]).is_ok());
+	assert!(parsed.check_ranges_hash(&[7u8; 32]).is_err());
+}
+
+/// Every malformed field is rejected.
+#[test]
+fn malformed_certificates_are_rejected()
+{
+	let bytes = signed_cert().to_bytes().unwrap();
+
+	// Magic, version, hash algorithm and policy flags.
+	for (pos, val) in [(0usize, b'X'), (4, 1u8), (5, 0u8), (137, 0x80u8)].iter()
+	{
+		let mut bad = bytes.clone();
+		bad[*pos] = *val;
+		assert!(Certificate::from_bytes(&bad[..]).is_err(), "byte {}", pos);
+	}
+
+	assert!(Certificate::from_bytes(&bytes[..(bytes.len() - 1)]).is_err());
+
+	let mut unsigned = signed_cert();
+	unsigned.signature = [0u8; 64];
+	assert!(Certificate::from_bytes(&unsigned.to_bytes().unwrap()[..]).is_err());
+}
+
+/// A verified program is the program followed by the ranges of its
+/// parameters, and its certificate, and nothing else.
+#[test]
+fn verified_byte_code_is_split()
+{
+	use cmd::constructor_helper::*;
+
+	let prog = fn_dc(func_general::FnProtoType::new(data_type::DataType::Void, "entry".to_string(), vec![]), skip());
+	let prog_bytes = prog.to_bytes().unwrap();
+
+	let ranges = ParamRanges::new(vec![Interval::new(0, 10)]);
+	let mut vimpc = certificate::verified_byte_code(&prog_bytes[..], &ranges, &signed_cert()).unwrap();
+
+	let (_, byte_code, ranges_bytes, cert) = certificate::parse_verified_byte_code(&vimpc[..]).unwrap();
+	assert_eq!(byte_code, &prog_bytes[..]);
+	assert_eq!(ranges_bytes, &ranges.to_bytes().unwrap()[..]);
+	assert_eq!(cert, signed_cert());
+
+	vimpc.push(0u8);
+	assert!(certificate::parse_verified_byte_code(&vimpc[..]).is_err());
+}
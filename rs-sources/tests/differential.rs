@@ -0,0 +1,352 @@
+#![cfg(feature = "host_verifier")]
+//! Differential tests between the verdicts of the type checker and the
+//! behavior of the interpreter.
+//!
+//! The property tested is the soundness of the type checker: a program it
+//! accepts never fails in the interpreter with a type-related error. The
+//! programs tested are the example programs of `gen_byte_code`, the mutants
+//! of them obtained by changing one type, one constant or the kind of one
+//! expression, and random programs of `generator::random`. A counterexample is
+//! minimized before being reported.
+
+use std::cell::Cell;
+use std::panic;
+use std::rc::Rc;
+use std::sync::Once;
+
+use enclave_verifier::analyzer::call_graph::CallGraph;
+use enclave_verifier::ast::*;
+use enclave_verifier::interpreter;
+use enclave_verifier::generator::random;
+use enclave_verifier::generator::shrink;
+use enclave_verifier::generator::shrink::Rewrites;
+use enclave_verifier::interpreter::exp::ExpValue;
+use enclave_verifier::type_checker::type_checker;
+
+use data_type::DataType;
+
+#[path = "../src/bin/gen_byte_code.rs"]
+#[allow(dead_code)]
+mod gen_byte_code;
+
+/// Messages of the interpreter errors, which a well-typed program never hits.
+const TYPE_ERRORS : [&str; 15] = [
+	"Assignment expecting",
+	"Expecting an AExp value",
+	"Expecting an BExp value",
+	"Expecting an Int32/Float32 type",
+	"Expecting a Bool type",
+	"Function expecting a return value",
+	"can't be promoted",
+	"hasn't been declared",
+	"hasn't been initialized",
+	"is undefined",
+	"No declaration of",
+	"is ambiguous",
+	"doesn't return a value",
+	"has already been declared",
+	"declared repeatedly",
+];
+
+/// Bound given to the loops without one, so that the reductions of a program
+/// removing the updates of a loop condition can't make it run forever.
+const LOOP_BOUND : u64 = 10000;
+
+/// Number of random programs tested.
+const NUM_RANDOM_PROGS : u64 = 200;
+
+const TYPES : [DataType; 3] = [DataType::Int32, DataType::Float32, DataType::Bool];
+
+fn is_type_error(msg : &str) -> bool
+{
+	TYPE_ERRORS.iter().any(|e| msg.contains(e))
+}
+
+fn type_check(prog : &cmd::Cmd) -> Result<(), String>
+{
+	let mut glvar_vec : Vec<type_checker::VarTypePair> = Vec::new();
+	let mut fn_vec : Vec<type_checker::FuncIdentifierTuple> = Vec::new();
+	type_checker::gather_fn_types(prog, &mut glvar_vec, &mut fn_vec)?;
+	type_checker::iterate_through_ast(prog.clone(), true, Vec::new(), &fn_vec, DataType::Void)?;
+	Ok(())
+}
+
+/// The program with a bound on each of its loops.
+fn bound_loops(c : &cmd::Cmd) -> cmd::Cmd
+{
+	use cmd::Cmd;
+
+	match c
+	{
+		Cmd::IfElse{cond, tr_cmd, fa_cmd} =>
+			Cmd::IfElse{ cond : cond.clone(), tr_cmd : Box::new(bound_loops(tr_cmd)), fa_cmd : Box::new(bound_loops(fa_cmd)) },
+		Cmd::WhileLoop{cond, lp_cmd, bound, invariants} =>
+			Cmd::WhileLoop{ cond : cond.clone(), lp_cmd : Box::new(bound_loops(lp_cmd)),
+				bound : Some(bound.unwrap_or(LOOP_BOUND)), invariants : invariants.clone() },
+		Cmd::Seq{fst_cmd, snd_cmd} =>
+			Cmd::Seq{ fst_cmd : Box::new(bound_loops(fst_cmd)), snd_cmd : Box::new(bound_loops(snd_cmd)) },
+		Cmd::FnDecl{prototype, fn_cmd} =>
+			Cmd::FnDecl{ prototype : prototype.clone(), fn_cmd : Rc::new(bound_loops(fn_cmd)) },
+		_ => c.clone(),
+	}
+}
+
+thread_local!
+{
+	static QUIET : Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run the entry function of the program; the panics of the interpreter,
+/// e.g. on an integer overflow or a division by zero, are not type errors,
+/// and are caught without being printed.
+fn interpret(prog : &cmd::Cmd, args : &[exp::Exp]) -> Option<Result<Option<ExpValue>, String>>
+{
+	use interpreter::cmd::CanEvalToExpVal;
+
+	static HOOK : Once = Once::new();
+	HOOK.call_once(||
+	{
+		let default_hook = panic::take_hook();
+		panic::set_hook(Box::new(move |info|
+		{
+			if !QUIET.with(|q| q.get())
+			{
+				default_hook(info);
+			}
+		}));
+	});
+
+	QUIET.with(|q| q.set(true));
+	let res = panic::catch_unwind(panic::AssertUnwindSafe(||
+	{
+		let mut prog_inter = interpreter::Program::new();
+		bound_loops(prog).eval_to_exp_val(&mut prog_inter.func_states, &mut prog_inter.var_states)?;
+
+		let entry_call = func_general::FnCall::new("entry".to_string(), args.to_vec());
+		interpreter::states::func_call(&prog_inter.func_states, &prog_inter.var_states, &entry_call, false)
+	}));
+	QUIET.with(|q| q.set(false));
+
+	res.ok()
+}
+
+/// The prototype of the entry function declared at global scope.
+fn entry_prototype(prog : &cmd::Cmd) -> Option<Rc<func_general::FnProtoType>>
+{
+	match prog
+	{
+		cmd::Cmd::Seq{fst_cmd, snd_cmd}               => entry_prototype(fst_cmd).or_else(|| entry_prototype(snd_cmd)),
+		cmd::Cmd::FnDecl{prototype, ..} if prototype.name == "entry" => Some(prototype.clone()),
+		_                                              => None,
+	}
+}
+
+/// Argument lists for the entry function, with a value of the type of each
+/// parameter.
+fn entry_args(prototype : &func_general::FnProtoType) -> Vec<Vec<exp::Exp>>
+{
+	use aexp::constructor_helper::*;
+	use bexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let arg = |t : DataType, is_fst : bool| match (t, is_fst)
+	{
+		(DataType::Int32, true)    => 5i32.to_aexp().to_exp(),
+		(DataType::Int32, false)   => 8i32.to_aexp().to_exp(),
+		(DataType::Float32, true)  => 2.5f32.to_aexp().to_exp(),
+		(DataType::Float32, false) => (-1.5f32).to_aexp().to_exp(),
+		(_, is_fst)                => is_fst.to_bexp().to_exp(),
+	};
+
+	vec![
+		prototype.var_decl_list.iter().map(|d| arg(d.var_type, true)).collect(),
+		prototype.var_decl_list.iter().map(|d| arg(d.var_type, false)).collect(),
+	]
+}
+
+/// A type error the interpreter hits running the program, with the arguments
+/// of the entry call hitting it.
+fn runtime_type_error(prog : &cmd::Cmd, arg_lists : &[Vec<exp::Exp>]) -> Option<(Vec<exp::Exp>, String)>
+{
+	entry_prototype(prog)?;
+
+	arg_lists.iter().find_map(|args| match interpret(prog, args)
+	{
+		Some(Err(msg)) if is_type_error(&msg) => Some((args.clone(), msg)),
+		_                                     => None,
+	})
+}
+
+/// A counterexample to the soundness of the type checker.
+fn unsound(prog : &cmd::Cmd, arg_lists : &[Vec<exp::Exp>]) -> Option<(Vec<exp::Exp>, String)>
+{
+	match type_check(prog)
+	{
+		Ok(_)  => runtime_type_error(prog, arg_lists),
+		Err(_) => None,
+	}
+}
+
+/// Mutations changing one type, one constant or the kind of one expression,
+/// without changing the values computed, so that the mutants terminate
+/// whenever the original program does.
+const MUTATIONS : Rewrites = Rewrites
+{
+	cmd : |c| match c
+	{
+		cmd::Cmd::VarDecl{d} => TYPES.iter().filter(|t| **t != d.var_type).map(|t|
+		{
+			let mut d = d.clone();
+			d.var_type = *t;
+			cmd::Cmd::VarDecl{ d }
+		}).collect(),
+		cmd::Cmd::FnDecl{prototype, fn_cmd} =>
+		{
+			let mut out = Vec::new();
+			for t in [DataType::Void, DataType::Int32, DataType::Float32, DataType::Bool].iter().filter(|t| **t != prototype.ret_type)
+			{
+				let mut p = (**prototype).clone();
+				p.ret_type = *t;
+				out.push(cmd::Cmd::FnDecl{ prototype : Rc::new(p), fn_cmd : fn_cmd.clone() });
+			}
+			for (i, d) in prototype.var_decl_list.iter().enumerate()
+			{
+				for t in TYPES.iter().filter(|t| **t != d.var_type)
+				{
+					let mut p = (**prototype).clone();
+					p.var_decl_list[i].var_type = *t;
+					out.push(cmd::Cmd::FnDecl{ prototype : Rc::new(p), fn_cmd : fn_cmd.clone() });
+				}
+			}
+			out
+		},
+		cmd::Cmd::Return{e : Some(_)} => vec![cmd::Cmd::Return{ e : None }],
+		_ => vec![],
+	},
+	exp : |e| match e
+	{
+		exp::Exp::A{e : aexp::Aexp::Var{v}}      => vec![exp::Exp::B{ e : bexp::Bexp::Var{ v : v.clone() } }],
+		exp::Exp::B{e : bexp::Bexp::Var{v}}      => vec![exp::Exp::A{ e : aexp::Aexp::Var{ v : v.clone() } }],
+		exp::Exp::A{e : aexp::Aexp::FnCall{fc}}  => vec![exp::Exp::B{ e : bexp::Bexp::FnCall{ fc : fc.clone() } }],
+		exp::Exp::B{e : bexp::Bexp::FnCall{fc}}  => vec![exp::Exp::A{ e : aexp::Aexp::FnCall{ fc : fc.clone() } }],
+		exp::Exp::A{e : aexp::Aexp::IntConst{v}} => vec![exp::Exp::B{ e : bexp::Bexp::BoolConst{ v : *v != 0 } }],
+		exp::Exp::B{e : bexp::Bexp::BoolConst{v}} => vec![exp::Exp::A{ e : aexp::Aexp::IntConst{ v : *v as i32 } }],
+		_ => vec![],
+	},
+	aexp : |a| match a
+	{
+		aexp::Aexp::IntConst{v} => vec![aexp::Aexp::FloConst{ v : *v as f32 }],
+		aexp::Aexp::FloConst{v} => vec![aexp::Aexp::IntConst{ v : *v as i32 }],
+		_ => vec![],
+	},
+	bexp : |_| vec![],
+};
+
+/// Whether the program has no recursive function; reducing the base case of
+/// a recursive function could make it recurse until it overflows the stack.
+fn is_non_recursive(prog : &cmd::Cmd) -> bool
+{
+	match CallGraph::from_program(prog)
+	{
+		Ok(graph) => !graph.has_recursion(),
+		Err(_)    => false,
+	}
+}
+
+fn report(name : &str, prog : &cmd::Cmd, args : &[exp::Exp], msg : &str) -> String
+{
+	let mut lines : Vec<IndentString> = Vec::new();
+	prog.to_indent_lines(&mut lines);
+	let args : Vec<String> = args.iter().map(|a| a.to_string()).collect();
+
+	format!("{} (entry({})): {}\n{}", name, args.join(", "), msg, indent_lines_to_string(&lines, '\t'))
+}
+
+/// Same as `shrink::minimize`, but only through programs without recursion.
+fn minimize<F>(prog : cmd::Cmd, fails : F) -> cmd::Cmd
+	where F : Fn(&cmd::Cmd) -> bool
+{
+	shrink::minimize(prog, |p| is_non_recursive(p) && fails(p))
+}
+
+/// The programs tested, with the argument lists of their entry calls: the
+/// example programs and their mutants, and random programs.
+fn test_progs() -> Vec<(String, cmd::Cmd, Vec<Vec<exp::Exp>>)>
+{
+	let mut progs = Vec::new();
+
+	for (name, prog) in gen_byte_code::example_progs().into_iter()
+	{
+		for (i, mutant) in shrink::cmd_variants(&prog, &MUTATIONS).into_iter().enumerate()
+		{
+			let arg_lists = entry_prototype(&mutant).map(|p| entry_args(&p)).unwrap_or_default();
+			progs.push((format!("{} (mutant {})", name, i), mutant, arg_lists));
+		}
+		let arg_lists = entry_prototype(&prog).map(|p| entry_args(&p)).unwrap_or_default();
+		progs.push((name.to_string(), prog, arg_lists));
+	}
+
+	for seed in 0..NUM_RANDOM_PROGS
+	{
+		let gen = random::generate(5 + (seed as usize) % 40, seed);
+		progs.push((format!("random program (seed {})", seed), gen.prog, gen.param_lists));
+	}
+
+	progs
+}
+
+/// Programs accepted by the type checker never hit a type error at runtime.
+#[test]
+fn checker_is_sound()
+{
+	let mut num_accepted = 0;
+	let mut counterexamples : Vec<String> = Vec::new();
+
+	for (name, prog, arg_lists) in test_progs().into_iter()
+	{
+		if type_check(&prog).is_err() || entry_prototype(&prog).is_none()
+		{
+			continue;
+		}
+		num_accepted += 1;
+
+		if unsound(&prog, &arg_lists).is_some()
+		{
+			let prog = minimize(prog, |p| unsound(p, &arg_lists).is_some());
+			let (args, msg) = unsound(&prog, &arg_lists).unwrap();
+			let cex = report(&name, &prog, &args, &msg);
+			if !counterexamples.contains(&cex)
+			{
+				counterexamples.push(cex);
+			}
+		}
+	}
+
+	// The random programs are all accepted.
+	assert!(num_accepted > NUM_RANDOM_PROGS as usize, "Only {} programs accepted", num_accepted);
+	assert!(counterexamples.is_empty(), "Type checker accepts programs failing with type errors:\n\n{}", counterexamples.join("\n\n"));
+}
+
+/// The mutants catch type errors, which the type checker rejects, and
+/// minimizing them keeps the type error.
+#[test]
+fn mutants_hit_type_errors()
+{
+	let mut num_errors = 0;
+
+	for (_, prog, arg_lists) in test_progs().into_iter()
+	{
+		if runtime_type_error(&prog, &arg_lists).is_none()
+		{
+			continue;
+		}
+		num_errors += 1;
+		assert!(type_check(&prog).is_err());
+
+		let min_prog = minimize(prog.clone(), |p| runtime_type_error(p, &arg_lists).is_some());
+		assert!(shrink::size(&min_prog) <= shrink::size(&prog));
+		assert!(runtime_type_error(&min_prog, &arg_lists).is_some());
+	}
+
+	assert!(num_errors > 0);
+}
@@ -0,0 +1,349 @@
+#![cfg(feature = "host_verifier")]
+
+use enclave_verifier::ast::*;
+use enclave_verifier::analyzer;
+use enclave_verifier::interpreter;
+use enclave_verifier::host_verify;
+use enclave_verifier::certificate;
+use enclave_verifier::type_checker::policy::CheckerPolicy;
+
+use enclave_verifier::attestation;
+use enclave_verifier::enclave::EnclaveBackend;
+use enclave_verifier::enclave::allowlist::CheckerAllowlist;
+use enclave_verifier::enclave::allowlist::TrustedCheckers;
+use enclave_verifier::enclave::checker;
+use enclave_verifier::enclave::executor;
+use enclave_verifier::enclave::executor::Freshness;
+use enclave_verifier::enclave::software::SoftwareBackend;
+use enclave_verifier::session::client;
+use enclave_verifier::session::client::ClientSession;
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+#[path = "../src/bin/gen_byte_code.rs"]
+#[allow(dead_code)]
+mod gen_byte_code;
+
+/// The example program, followed by its tests.
+fn example_input() -> Vec<u8>
+{
+	let prog = gen_byte_code::example_progs().remove(0).1;
+
+	let mut bytes = prog.to_bytes().unwrap();
+	bytes.append(&mut gen_byte_code::construct_example_prog_1_tests().to_bytes().unwrap());
+	bytes
+}
+
+fn check(backend : &SoftwareBackend, input : &[u8], policy : &str, counter : &AtomicU64) -> Result<Vec<u8>, String>
+{
+	check_with_ranges(backend, input, policy, &analyzer::interval::ParamRanges::new(Vec::new()), counter)
+}
+
+fn check_with_ranges(backend : &SoftwareBackend, input : &[u8], policy : &str, ranges : &analyzer::interval::ParamRanges, counter : &AtomicU64) -> Result<Vec<u8>, String>
+{
+	let policy = CheckerPolicy::parse(policy)?;
+
+	let output = checker::check_program(backend, input, &policy, ranges, counter)?;
+
+	certificate::verified_byte_code(&input[0..output.bytes_read], ranges, &output.cert)
+}
+
+/// Allowlist pinning the given type-checker keys.
+fn pin_keys(keys : Vec<[u8; 64]>) -> Vec<u8>
+{
+	CheckerAllowlist { keys : keys, mr_enclaves : vec![], evidence : vec![] }.to_bytes().unwrap()
+}
+
+/// Type check, sign, verify, execute and report, on software backends; the
+/// certificate and the report verify on the host.
+#[test]
+fn pipeline_runs_on_software_backends()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let checker_backend = SoftwareBackend::from_secret(&[1u8; 32]).unwrap();
+	let counter = AtomicU64::new(0);
+
+	let vimpc = check(&checker_backend, &example_input()[..], "tests", &counter).unwrap();
+
+	let (_, cert) = host_verify::verify_certificate(&vimpc[..]).unwrap();
+	assert_eq!(cert.checker_pkey, checker_backend.public_key());
+	assert!(cert.policy().unwrap().require_tests_pass);
+	assert_eq!(cert.counter, 0);
+
+	let params = vec![211i32.to_aexp().to_exp()].to_bytes().unwrap();
+
+	let interp_backend = SoftwareBackend::generate();
+	let allowlist = pin_keys(vec![checker_backend.public_key()]);
+	let checkers = TrustedCheckers::provision(&interp_backend, &allowlist[..]).unwrap();
+	let reports = AtomicU64::new(5);
+	let report = executor::execute_program(&interp_backend, &checkers, &vimpc[..], &params[..], false, &[7u8; 32], &reports).unwrap();
+	assert_eq!(report.freshness, Freshness { nonce : [7u8; 32], counter : 5 });
+
+	match &report.func_ret
+	{
+		Some(interpreter::exp::ExpValue::B(true)) => {},
+		other                                    => panic!("is_prime(211) returned {:?}", other),
+	}
+
+	let ret = interpreter::exp::func_ret_to_bytes(&report.func_ret).unwrap();
+	let pkey = interp_backend.public_key();
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &report.freshness, &pkey, &report.signature).is_ok());
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &report.freshness, &checker_backend.public_key(), &report.signature).is_err());
+
+	// The report is only fresh for the nonce of the client, and its number.
+	let other_nonce = Freshness { nonce : [8u8; 32], counter : 5 };
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &other_nonce, &pkey, &report.signature).is_err());
+	let other_counter = Freshness { nonce : [7u8; 32], counter : 4 };
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &other_counter, &pkey, &report.signature).is_err());
+}
+
+/// The report body, and the result bytes, as output to the host, parse back,
+/// and verify with the signature.
+#[test]
+fn report_output_verifies_on_the_host()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let checker_backend = SoftwareBackend::generate();
+	let vimpc = check(&checker_backend, &example_input()[..], "", &AtomicU64::new(0)).unwrap();
+
+	let interp_backend = SoftwareBackend::generate();
+	let allowlist = pin_keys(vec![checker_backend.public_key()]);
+	let checkers = TrustedCheckers::provision(&interp_backend, &allowlist[..]).unwrap();
+
+	let params = vec![211i32.to_aexp().to_exp()].to_bytes().unwrap();
+	let report = executor::execute_program(&interp_backend, &checkers, &vimpc[..], &params[..], false, &[3u8; 32], &AtomicU64::new(9)).unwrap();
+
+	let body_bytes = report.body().to_bytes().unwrap();
+	assert_eq!(body_bytes.len(), executor::REPORT_BODY_SIZE);
+	let (_, body) = executor::ReportBody::from_bytes(&body_bytes[..]).unwrap();
+	assert_eq!(body, report.body());
+	assert_eq!(body.freshness.counter, 9);
+
+	let ret = interpreter::exp::func_ret_to_bytes(&report.func_ret).unwrap();
+	assert!(ret.len() <= interpreter::exp::FUNC_RET_MAX_SIZE);
+	let byte_code_hash = host_verify::verified_program_hash(&vimpc[..]).unwrap();
+	let hash = host_verify::report_hash(&params[..], &byte_code_hash, &ret[..], &allowlist[..], &body.freshness).unwrap();
+
+	let pkey = interp_backend.public_key();
+	assert!(host_verify::verify_report_body(&body, &hash, &[3u8; 32], &pkey, &report.signature).is_ok());
+	assert!(host_verify::verify_report_body(&body, &hash, &[4u8; 32], &pkey, &report.signature).is_err());
+
+	// A body of another execution is refused, even with a valid signature.
+	let other_ret = interpreter::exp::func_ret_to_bytes(&None).unwrap();
+	let other_hash = host_verify::report_hash(&params[..], &byte_code_hash, &other_ret[..], &allowlist[..], &body.freshness).unwrap();
+	assert!(host_verify::verify_report_body(&body, &other_hash, &[3u8; 32], &pkey, &report.signature).is_err());
+}
+
+/// Parameters, and the result, pass the host sealed; the report is over the
+/// sealed bytes, and verifies on the host before the client opens the result.
+#[test]
+fn sealed_execution_runs_on_software_backends()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let checker_backend = SoftwareBackend::generate();
+	let vimpc = check(&checker_backend, &example_input()[..], "", &AtomicU64::new(0)).unwrap();
+
+	let interp_backend = SoftwareBackend::generate();
+	let allowlist = pin_keys(vec![checker_backend.public_key()]);
+	let checkers = TrustedCheckers::provision(&interp_backend, &allowlist[..]).unwrap();
+
+	let client = ClientSession::new(&interp_backend.public_key()).unwrap();
+	let params = vec![211i32.to_aexp().to_exp()].to_bytes().unwrap();
+	let sealed_params = client.seal_params(&params[..]).unwrap();
+
+	let execution = executor::execute_program_in_session(&interp_backend, &checkers, &vimpc[..], &sealed_params[..], false, &[0u8; 32], &AtomicU64::new(0)).unwrap();
+	let report = &execution.report;
+	assert_eq!(report.param_list_hash, host_verify::sha256(&sealed_params[..]));
+
+	let pkey = interp_backend.public_key();
+	assert!(host_verify::verify_session_report(&vimpc[..], &sealed_params[..], &execution.sealed_result[..], &allowlist[..], &report.freshness, &pkey, &report.signature).is_ok());
+
+	let ret = client.open_result(&sealed_params[..], &execution.sealed_result[..]).unwrap();
+	let (_, func_ret) = interpreter::exp::func_ret_from_bytes(&ret[..]).unwrap();
+	match func_ret
+	{
+		Some(interpreter::exp::ExpValue::B(true)) => {},
+		other                                    => panic!("is_prime(211) returned {:?}", other),
+	}
+
+	// Reports over the plain parameters, and result, don't verify.
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &report.freshness, &pkey, &report.signature).is_err());
+
+	// Parameters sealed for another enclave don't open.
+	let other_client = ClientSession::new(&checker_backend.public_key()).unwrap();
+	let other_sealed = other_client.seal_params(&params[..]).unwrap();
+	assert!(executor::execute_program_in_session(&interp_backend, &checkers, &vimpc[..], &other_sealed[..], false, &[0u8; 32], &AtomicU64::new(0)).is_err());
+
+	// Sealed parameters followed by other bytes are refused, as in the clear.
+	let mut long_params = params.clone();
+	long_params.push(0u8);
+	let long_sealed = client.seal_params(&long_params[..]).unwrap();
+	match executor::execute_program_in_session(&interp_backend, &checkers, &vimpc[..], &long_sealed[..], false, &[0u8; 32], &AtomicU64::new(0))
+	{
+		Err(why) => assert!(why.contains("unexpected byte"), "{}", why),
+		Ok(_)    => panic!("Sealed parameters followed by other bytes are accepted."),
+	}
+}
+
+/// A program sealed by its owner for the type-checker is checked, and
+/// delivered to the interpreter of the MRENCLAVE the owner chose, without
+/// the bytecode ever passing the host in clear.
+#[test]
+fn sealed_program_is_delivered_to_the_interpreter()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let checker_backend = SoftwareBackend::generate();
+	let interp_backend = SoftwareBackend::from_secret(&[9u8; 32]).unwrap().with_measurements([0xAAu8; 32], [0xBBu8; 32]);
+	let interp_evidence = attestation::gen_evidence(&interp_backend).unwrap();
+
+	let input = example_input();
+	let policy = CheckerPolicy::parse("tests").unwrap();
+	let ranges = analyzer::interval::ParamRanges::new(Vec::new());
+	let counter = AtomicU64::new(0);
+
+	let sealed_input = client::seal_program(&checker_backend.public_key(), &[0xAAu8; 32], &input[..]).unwrap();
+	let output = checker::check_sealed_program(&checker_backend, &sealed_input[..], &interp_evidence, &policy, &ranges, &counter).unwrap();
+
+	let vimpc = certificate::verified_byte_code(&output.sealed_byte_code[..], &ranges, &output.cert).unwrap();
+
+	// The certificate is over the bytecode in clear, without the tests.
+	let prog_bytes = gen_byte_code::example_progs().remove(0).1.to_bytes().unwrap();
+	assert!(!vimpc.windows(prog_bytes.len()).any(|w| w == &prog_bytes[..]));
+	assert_eq!(host_verify::verify_sealed_certificate(&vimpc[..]).unwrap().prog_hash, host_verify::sha256(&prog_bytes[..]));
+	assert!(host_verify::verify_certificate(&vimpc[..]).is_err());
+
+	let allowlist = pin_keys(vec![checker_backend.public_key()]);
+	let checkers = TrustedCheckers::provision(&interp_backend, &allowlist[..]).unwrap();
+	let params = vec![211i32.to_aexp().to_exp()].to_bytes().unwrap();
+	let report = executor::execute_program(&interp_backend, &checkers, &vimpc[..], &params[..], false, &[0u8; 32], &AtomicU64::new(0)).unwrap();
+
+	let ret = interpreter::exp::func_ret_to_bytes(&report.func_ret).unwrap();
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &report.freshness, &interp_backend.public_key(), &report.signature).is_ok());
+
+	// Another interpreter can't open the bytecode.
+	let other_backend = SoftwareBackend::generate();
+	let other_checkers = TrustedCheckers::provision(&other_backend, &allowlist[..]).unwrap();
+	assert!(executor::execute_program(&other_backend, &other_checkers, &vimpc[..], &params[..], false, &[0u8; 32], &AtomicU64::new(0)).is_err());
+
+	// Nor does an enclave of another MRENCLAVE run it, even holding the key.
+	let same_key_backend = SoftwareBackend::from_secret(&[9u8; 32]).unwrap().with_measurements([0xCCu8; 32], [0xBBu8; 32]);
+	let same_key_checkers = TrustedCheckers::provision(&same_key_backend, &allowlist[..]).unwrap();
+	assert!(executor::execute_program(&same_key_backend, &same_key_checkers, &vimpc[..], &params[..], false, &[0u8; 32], &AtomicU64::new(0)).is_err());
+
+	// Nor is it delivered to an interpreter the owner didn't choose.
+	let other_evidence = attestation::gen_evidence(&other_backend).unwrap();
+	assert!(checker::check_sealed_program(&checker_backend, &sealed_input[..], &other_evidence, &policy, &ranges, &counter).is_err());
+	assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+/// Rejected programs don't take a certificate number, and tampered
+/// certificates are refused by the interpreter.
+#[test]
+fn pipeline_refuses_bad_input()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let backend = SoftwareBackend::from_secret(&[2u8; 32]).unwrap();
+	let counter = AtomicU64::new(0);
+
+	// Without its test section, the program fails the tests policy.
+	let prog_bytes = gen_byte_code::example_progs().remove(0).1.to_bytes().unwrap();
+	assert!(check(&backend, &prog_bytes[..], "tests", &counter).is_err());
+
+	let mut vimpc = check(&backend, &prog_bytes[..], "", &counter).unwrap();
+	assert_eq!(host_verify::verify_certificate(&vimpc[..]).unwrap().1.counter, 0);
+
+	let params = vec![7i32.to_aexp().to_exp()].to_bytes().unwrap();
+	let checkers = TrustedCheckers::provision(&backend, &pin_keys(vec![backend.public_key()])[..]).unwrap();
+	let reports = AtomicU64::new(0);
+	assert!(executor::execute_program(&backend, &checkers, &vimpc[..], &params[..], false, &[0u8; 32], &reports).is_ok());
+
+	// Bytes after the parameter list aren't ignored.
+	let mut long_params = params.clone();
+	long_params.push(0u8);
+	assert!(executor::execute_program(&backend, &checkers, &vimpc[..], &long_params[..], false, &[0u8; 32], &reports).is_err());
+
+	// Claim a policy the program wasn't checked against.
+	let policy_pos = vimpc.len() - 76;
+	vimpc[policy_pos] |= 0x20u8;
+	assert!(executor::execute_program(&backend, &checkers, &vimpc[..], &params[..], false, &[0u8; 32], &reports).is_err());
+
+	// Refused executions don't take a report number.
+	assert_eq!(reports.load(Ordering::SeqCst), 1);
+}
+
+/// The interpreter only runs calls within the parameter ranges the program
+/// was certified for, and the ranges can't be changed after the fact.
+#[test]
+fn parameters_are_held_to_certified_ranges()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use analyzer::interval::{Interval, ParamRanges};
+
+	let backend = SoftwareBackend::from_secret(&[4u8; 32]).unwrap();
+	let checkers = TrustedCheckers::provision(&backend, &pin_keys(vec![backend.public_key()])[..]).unwrap();
+	let reports = AtomicU64::new(0);
+
+	let prog_bytes = gen_byte_code::example_progs().remove(0).1.to_bytes().unwrap();
+	let ranges = ParamRanges::new(vec![Interval::new(0, 1000000)]);
+	let mut vimpc = check_with_ranges(&backend, &prog_bytes[..], "interval", &ranges, &AtomicU64::new(0)).unwrap();
+
+	let run = |vimpc : &[u8], x : i32| {
+		let params = vec![x.to_aexp().to_exp()].to_bytes().unwrap();
+		executor::execute_program(&backend, &checkers, vimpc, &params[..], false, &[0u8; 32], &reports)
+	};
+
+	assert!(run(&vimpc[..], 7).is_ok());
+	assert!(run(&vimpc[..], -1).is_err());
+	assert!(run(&vimpc[..], 1000001).is_err());
+	assert_eq!(reports.load(Ordering::SeqCst), 1);
+
+	// The lower bound follows the list length, and the type of the bound.
+	let lo_pos = prog_bytes.len() + 9 + 1;
+	vimpc[lo_pos + 3] ^= 0x80u8;
+	assert!(host_verify::verify_certificate(&vimpc[..]).is_err());
+	assert!(run(&vimpc[..], -1).is_err());
+	assert_eq!(reports.load(Ordering::SeqCst), 1);
+}
+
+/// The interpreter only runs programs certified by type-checkers of its
+/// allowlist, pinned, or backed by evidence for an allowed MRENCLAVE.
+#[test]
+fn interpreter_trusts_only_allowlisted_checkers()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let checker_backend = SoftwareBackend::from_secret(&[3u8; 32]).unwrap().with_measurements([0xAAu8; 32], [0xBBu8; 32]);
+	let interp_backend = SoftwareBackend::generate();
+
+	let prog_bytes = gen_byte_code::example_progs().remove(0).1.to_bytes().unwrap();
+	let vimpc = check(&checker_backend, &prog_bytes[..], "", &AtomicU64::new(0)).unwrap();
+	let params = vec![7i32.to_aexp().to_exp()].to_bytes().unwrap();
+
+	// A certificate signed with a key of its own choosing isn't enough.
+	let stranger = pin_keys(vec![SoftwareBackend::generate().public_key()]);
+	let checkers = TrustedCheckers::provision(&interp_backend, &stranger[..]).unwrap();
+	assert!(executor::execute_program(&interp_backend, &checkers, &vimpc[..], &params[..], false, &[0u8; 32], &AtomicU64::new(0)).is_err());
+
+	let evidence = attestation::gen_evidence(&checker_backend).unwrap();
+	let by_evidence = CheckerAllowlist { keys : vec![], mr_enclaves : vec![[0xAAu8; 32]], evidence : vec![evidence.clone()] };
+	let checkers = TrustedCheckers::provision(&interp_backend, &by_evidence.to_bytes().unwrap()[..]).unwrap();
+	assert!(executor::execute_program(&interp_backend, &checkers, &vimpc[..], &params[..], false, &[0u8; 32], &AtomicU64::new(0)).is_ok());
+
+	let wrong_enclave = CheckerAllowlist { keys : vec![], mr_enclaves : vec![[0xCCu8; 32]], evidence : vec![evidence] };
+	assert!(TrustedCheckers::provision(&interp_backend, &wrong_enclave.to_bytes().unwrap()[..]).is_err());
+
+	assert!(TrustedCheckers::provision(&interp_backend, &pin_keys(vec![])[..]).is_err());
+}
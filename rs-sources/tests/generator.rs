@@ -0,0 +1,88 @@
+#![cfg(feature = "host_verifier")]
+
+use enclave_verifier::ast::*;
+use enclave_verifier::generator::random;
+use enclave_verifier::generator::shrink;
+use enclave_verifier::interpreter;
+use enclave_verifier::type_checker::type_checker;
+
+use data_type::DataType;
+
+fn type_check(prog : &cmd::Cmd) -> Result<(), String>
+{
+	let mut glvar_vec : Vec<type_checker::VarTypePair> = Vec::new();
+	let mut fn_vec : Vec<type_checker::FuncIdentifierTuple> = Vec::new();
+	type_checker::gather_fn_types(prog, &mut glvar_vec, &mut fn_vec)?;
+	type_checker::iterate_through_ast(prog.clone(), true, Vec::new(), &fn_vec, DataType::Void)?;
+	Ok(())
+}
+
+fn has_loop(c : &cmd::Cmd) -> bool
+{
+	match c
+	{
+		cmd::Cmd::WhileLoop{..}            => true,
+		cmd::Cmd::IfElse{tr_cmd, fa_cmd, ..} => has_loop(tr_cmd) || has_loop(fa_cmd),
+		cmd::Cmd::Seq{fst_cmd, snd_cmd}    => has_loop(fst_cmd) || has_loop(snd_cmd),
+		cmd::Cmd::FnDecl{fn_cmd, ..}       => has_loop(fn_cmd),
+		_                                  => false,
+	}
+}
+
+/// Generated programs are well-typed, only depend on the seed, survive
+/// serialization, and come with argument lists their entry function accepts.
+#[test]
+fn generated_programs_are_well_typed()
+{
+	use interpreter::cmd::CanEvalToExpVal;
+
+	for seed in 0..100
+	{
+		for size in [1, 10, 50].iter()
+		{
+			let gen = random::generate(*size, seed);
+			assert!(type_check(&gen.prog).is_ok(), "seed {}: {:?}\n{}", seed, type_check(&gen.prog), gen.prog);
+
+			let bytes = gen.prog.to_bytes().unwrap();
+			assert_eq!(random::generate(*size, seed).prog.to_bytes().unwrap(), bytes);
+
+			let (_, prog) = cmd::Cmd::from_bytes(&bytes[..]).unwrap();
+			assert_eq!(prog.to_bytes().unwrap(), bytes);
+
+			assert!(!gen.param_lists.is_empty());
+			for args in gen.param_lists.iter()
+			{
+				let mut prog_inter = interpreter::Program::new();
+				prog.eval_to_exp_val(&mut prog_inter.func_states, &mut prog_inter.var_states).unwrap();
+
+				let entry_call = func_general::FnCall::new("entry".to_string(), args.clone());
+				let res = interpreter::states::func_call(&prog_inter.func_states, &prog_inter.var_states, &entry_call, false);
+				assert!(res.is_ok(), "seed {}: {:?}\n{}", seed, res, prog);
+			}
+		}
+	}
+}
+
+/// Shrinking keeps the programs well-typed, and failing.
+#[test]
+fn shrunk_programs_are_well_typed()
+{
+	let mut num_shrunk = 0;
+
+	for seed in 0..50
+	{
+		let gen = random::generate(40, seed);
+		if !has_loop(&gen.prog)
+		{
+			continue;
+		}
+
+		let prog = shrink::shrink(gen.prog.clone(), has_loop);
+		assert!(type_check(&prog).is_ok(), "seed {}:\n{}", seed, prog);
+		assert!(has_loop(&prog));
+		assert!(shrink::size(&prog) < shrink::size(&gen.prog));
+		num_shrunk += 1;
+	}
+
+	assert!(num_shrunk > 0);
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "host_verifier")]
+
+use enclave_verifier::ast::*;
+use enclave_verifier::certificate;
+use enclave_verifier::certificate::Certificate;
+use enclave_verifier::analyzer::interval::{Interval, ParamRanges};
+use enclave_verifier::enclave::allowlist::CheckerAllowlist;
+use enclave_verifier::enclave::executor::Freshness;
+use enclave_verifier::host_verify;
+use enclave_verifier::interpreter;
+use enclave_verifier::type_checker::policy::CheckerPolicy;
+
+use p256::ecdsa::Signature;
+use p256::ecdsa::SigningKey;
+use p256::ecdsa::signature::Signer;
+
+/// A fixed key, and its public key in the layout of SGX.
+fn enclave_key(seed : u8) -> (SigningKey, [u8; 64])
+{
+	let sign_key = SigningKey::from_bytes(&[seed; 32].into()).unwrap();
+
+	let mut pkey : [u8; 64] = [0; 64];
+	pkey.copy_from_slice(&sign_key.verifying_key().to_encoded_point(false).as_bytes()[1..]);
+
+	(sign_key, host_verify::to_sgx_layout(&pkey))
+}
+
+/// Sign like the enclaves do, i.e. ECDSA-SHA256 with the SGX layout.
+fn sign(sign_key : &SigningKey, msg : &[u8]) -> [u8; 64]
+{
+	let sign : Signature = sign_key.sign(msg);
+
+	let mut res : [u8; 64] = [0; 64];
+	res.copy_from_slice(&sign.to_bytes()[..]);
+
+	host_verify::to_sgx_layout(&res)
+}
+
+/// fn entry(Int32 x) -> Int32 { return x; }
+fn verified_prog(sign_key : &SigningKey, pkey : [u8; 64]) -> Vec<u8>
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let prog = fn_dc(func_general::FnProtoType::new(data_type::DataType::Int32, "entry".to_string(),
+		vec![var_general::VarDecl::new(data_type::DataType::Int32, "x".to_string())]),
+		ret(Some("x".to_aexp().to_exp())));
+	let bytes = prog.to_bytes().unwrap();
+	let ranges = ParamRanges::new(vec![Interval::new(-10, 10)]);
+
+	let policy = CheckerPolicy::parse("contracts").unwrap();
+	let mut cert = Certificate::new(host_verify::sha256(&bytes[..]), host_verify::sha256(&ranges.to_bytes().unwrap()[..]), pkey, &policy, 0);
+	cert.signature = sign(sign_key, &cert.signed_bytes());
+
+	certificate::verified_byte_code(&bytes[..], &ranges, &cert).unwrap()
+}
+
+/// Certificates signed by the checker key verify; tampered ones don't.
+#[test]
+fn certificates_are_verified()
+{
+	let (sign_key, pkey) = enclave_key(1u8);
+	let vimpc = verified_prog(&sign_key, pkey);
+
+	let (_, cert) = host_verify::verify_certificate(&vimpc[..]).unwrap();
+	assert!(cert.policy().unwrap().require_contracts);
+
+	// Counter of the certificate, ranges, and bytecode of the program.
+	for pos in [vimpc.len() - 70, vimpc.len() - certificate::CERT_SIZE - 1, 5].iter()
+	{
+		let mut bad = vimpc.clone();
+		bad[*pos] ^= 1u8;
+		assert!(host_verify::verify_certificate(&bad[..]).is_err(), "byte {}", pos);
+	}
+
+	// Signed by another key than the one in the certificate.
+	let (other_key, _) = enclave_key(2u8);
+	assert!(host_verify::verify_certificate(&verified_prog(&other_key, pkey)[..]).is_err());
+}
+
+/// Reports verify only for the parameters and result the enclave signed.
+#[test]
+fn reports_are_verified()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use interpreter::exp::ExpValue;
+	use interpreter::aexp::AexpValue;
+
+	let (checker_key, checker_pkey) = enclave_key(1u8);
+	let vimpc = verified_prog(&checker_key, checker_pkey);
+	let byte_code_hash = host_verify::verified_program_hash(&vimpc[..]).unwrap();
+
+	let params = vec![7i32.to_aexp().to_exp()].to_bytes().unwrap();
+	let ret = interpreter::exp::func_ret_to_bytes(&Some(ExpValue::A(AexpValue::Int32(7)))).unwrap();
+
+	let allowlist = CheckerAllowlist { keys : vec![checker_pkey], mr_enclaves : vec![], evidence : vec![] }.to_bytes().unwrap();
+
+	let freshness = Freshness { nonce : [9u8; 32], counter : 0 };
+
+	let (encl_key, encl_pkey) = enclave_key(3u8);
+	let sign = sign(&encl_key, &host_verify::report_hash(&params[..], &byte_code_hash, &ret[..], &allowlist[..], &freshness).unwrap());
+
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &freshness, &encl_pkey, &sign).is_ok());
+
+	let wrong_ret = interpreter::exp::func_ret_to_bytes(&Some(ExpValue::A(AexpValue::Int32(8)))).unwrap();
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &wrong_ret[..], &allowlist[..], &freshness, &encl_pkey, &sign).is_err());
+
+	let wrong_params = vec![8i32.to_aexp().to_exp()].to_bytes().unwrap();
+	assert!(host_verify::verify_report(&vimpc[..], &wrong_params[..], &ret[..], &allowlist[..], &freshness, &encl_pkey, &sign).is_err());
+
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &allowlist[..], &freshness, &checker_pkey, &sign).is_err());
+
+	// Reported under another allowlist of type-checkers.
+	let (_, other_pkey) = enclave_key(4u8);
+	let other_allowlist = CheckerAllowlist { keys : vec![other_pkey], mr_enclaves : vec![], evidence : vec![] }.to_bytes().unwrap();
+	assert!(host_verify::verify_report(&vimpc[..], &params[..], &ret[..], &other_allowlist[..], &freshness, &encl_pkey, &sign).is_err());
+}
+
+/// Keys and signatures are printed as base64(x) followed by base64(y).
+#[test]
+fn base64_pairs_are_decoded()
+{
+	let (_, pkey) = enclave_key(1u8);
+	let printed = format!("{}{}.", base64_of(&pkey[0..32]), base64_of(&pkey[32..64]));
+
+	assert_eq!(&host_verify::decode_b64_pair(&printed).unwrap()[..], &pkey[..]);
+	assert!(host_verify::decode_b64_pair(&printed[0..44]).is_err());
+}
+
+/// A bool value takes two bytes, and parses even when it ends the buffer,
+/// as a bool result does.
+#[test]
+fn bool_value_parses_at_end_of_buffer()
+{
+	use interpreter::exp::ExpValue;
+
+	for v in [false, true].iter()
+	{
+		let bytes = ExpValue::B(*v).to_bytes().unwrap();
+		assert_eq!(bytes.len(), 2);
+
+		let (bytes_left, parsed) = ExpValue::from_bytes(&bytes[..]).unwrap();
+		assert!(bytes_left.is_empty());
+		assert_eq!(parsed.to_string(), ExpValue::B(*v).to_string());
+	}
+
+	assert!(ExpValue::from_bytes(&[1u8]).is_err());
+}
+
+fn base64_of(bytes : &[u8]) -> String
+{
+	use base64::Engine;
+	base64::engine::general_purpose::STANDARD.encode(bytes)
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "host_verifier")]
+
+use enclave_verifier::enclave::EnclaveBackend;
+use enclave_verifier::enclave::identity;
+use enclave_verifier::enclave::identity::IdentitySlot;
+use enclave_verifier::enclave::identity::SealPolicy;
+use enclave_verifier::enclave::software::SoftwareBackend;
+use enclave_verifier::enclave::software::SoftwareSealer;
+use enclave_verifier::enclave::software::SOFTWARE_SEAL_ROOT_KEY;
+use enclave_verifier::sealed_store::SealedStore;
+
+fn sealer(mr_enclave : u8, mr_signer : u8) -> SoftwareSealer
+{
+	SoftwareSealer::new(SOFTWARE_SEAL_ROOT_KEY, [mr_enclave; 32], [mr_signer; 32])
+}
+
+fn generate() -> Result<identity::Identity, String>
+{
+	Ok(SoftwareBackend::generate().identity())
+}
+
+/// A sealed identity is unsealed to the same key pair, and the backend keeps
+/// signing with it.
+#[test]
+fn sealed_identity_is_reloaded()
+{
+	let (first, sealed) = identity::load_or_generate(&sealer(1u8, 2u8), &[], SealPolicy::MrEnclave, generate).unwrap();
+	let (second, resealed) = identity::load_or_generate(&sealer(1u8, 2u8), &sealed[..], SealPolicy::MrEnclave, generate).unwrap();
+
+	assert_eq!(first.pub_key[..], second.pub_key[..]);
+	assert_eq!(sealed, resealed);
+	assert_eq!(identity::sealed_public_key(&sealed[..]).unwrap(), (SealPolicy::MrEnclave, first.pub_key));
+
+	let backend = SoftwareBackend::from_identity(&second).unwrap();
+	assert_eq!(backend.public_key()[..], first.pub_key[..]);
+
+	let sign = backend.sign(b"msg").unwrap();
+	assert!(backend.verify(b"msg", &first.pub_key, &sign).unwrap());
+}
+
+/// Identities sealed to MRENCLAVE only unseal in the same enclave; sealed to
+/// MRSIGNER, in any enclave of the same signer.
+#[test]
+fn seal_policies_are_enforced()
+{
+	let (_, by_enclave) = identity::load_or_generate(&sealer(1u8, 2u8), &[], SealPolicy::MrEnclave, generate).unwrap();
+	assert!(identity::unseal_identity(&sealer(3u8, 2u8), &by_enclave[..]).is_err());
+
+	let (id, by_signer) = identity::load_or_generate(&sealer(1u8, 2u8), &[], SealPolicy::MrSigner, generate).unwrap();
+	let (_, unsealed) = identity::unseal_identity(&sealer(3u8, 2u8), &by_signer[..]).unwrap();
+	assert_eq!(unsealed.pub_key[..], id.pub_key[..]);
+	assert!(identity::unseal_identity(&sealer(1u8, 4u8), &by_signer[..]).is_err());
+
+	// The policy of a sealed identity can't be switched.
+	assert!(identity::load_or_generate(&sealer(1u8, 2u8), &by_signer[..], SealPolicy::MrEnclave, generate).is_err());
+}
+
+/// The public key in clear is authenticated with the sealed private key.
+#[test]
+fn tampered_sealed_identity_is_refused()
+{
+	let (_, sealed) = identity::load_or_generate(&sealer(1u8, 2u8), &[], SealPolicy::MrEnclave, generate).unwrap();
+	let other = SoftwareBackend::generate().public_key();
+
+	let mut swapped = sealed.clone();
+	swapped[5..69].copy_from_slice(&other);
+	assert!(identity::unseal_identity(&sealer(1u8, 2u8), &swapped[..]).is_err());
+
+	let mut tampered = sealed.clone();
+	let last = tampered.len() - 1;
+	tampered[last] ^= 1u8;
+	assert!(identity::unseal_identity(&sealer(1u8, 2u8), &tampered[..]).is_err());
+}
+
+/// The identity of an enclave instance is set once.
+#[test]
+fn identity_slot_is_set_once()
+{
+	let slot = IdentitySlot::new();
+	assert!(slot.get().is_none());
+
+	let id = generate().unwrap();
+	slot.set(id.clone()).unwrap();
+	assert!(slot.set(generate().unwrap()).is_err());
+	assert_eq!(slot.get().unwrap().pub_key[..], id.pub_key[..]);
+}
+
+#[test]
+fn sealed_store_round_trips()
+{
+	let dir = std::env::temp_dir().join(format!("enclave_verifier_store_{}", std::process::id()));
+	let store = SealedStore::new(&dir);
+
+	assert_eq!(store.load("checker").unwrap(), None);
+
+	store.store("checker", b"first").unwrap();
+	store.store("checker", b"second").unwrap();
+	assert_eq!(store.load("checker").unwrap(), Some(b"second".to_vec()));
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
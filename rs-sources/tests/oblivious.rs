@@ -0,0 +1,153 @@
+use enclave_verifier::ast::*;
+use enclave_verifier::enclave::executor;
+use enclave_verifier::interpreter;
+use enclave_verifier::interpreter::exp::ExpValue;
+use enclave_verifier::interpreter::aexp::AexpValue;
+
+use data_type::DataType;
+
+fn entry_call(prog : &cmd::Cmd, x : i32, oblivious : bool) -> Result<Option<ExpValue>, String>
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let mut prog_inter = interpreter::Program::new();
+	executor::gen_prog_states(&mut prog_inter, prog)?;
+
+	let call = func_general::FnCall::new("entry".to_string(), vec![x.to_aexp().to_exp()]);
+	executor::make_entry_call(&prog_inter, &call, oblivious)
+}
+
+fn ret_i32(ret : Result<Option<ExpValue>, String>) -> i32
+{
+	match ret.unwrap()
+	{
+		Some(ExpValue::A(AexpValue::Int32(v))) => v,
+		other                                  => panic!("Unexpected return value {:?}.", other.map(|v| v.to_string())),
+	}
+}
+
+/// fn fib(n : Int32) -> Int32 { if (n < 2) { return n; } else { return fib(n - 1) + fib(n - 2); } }
+/// fn entry(x : Int32) -> Int32 { return fib(x); }
+fn fib_prog() -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let fib_call = |e : aexp::Aexp| aexp::Aexp::FnCall{ fc : func_general::FnCall::new("fib".to_string(), vec![e.to_exp()]) };
+
+	let fib = fn_dc(func_general::FnProtoType::new(DataType::Int32, "fib".to_string(), vec![var_general::VarDecl::new(DataType::Int32, "n".to_string())]),
+		if_el("n".to_aexp().lt(2i32.to_aexp()),
+			ret(Some("n".to_aexp().to_exp())),
+			ret(Some((fib_call("n".to_aexp() - 1i32.to_aexp()) + fib_call("n".to_aexp() - 2i32.to_aexp())).to_exp()))));
+	let entry = fn_dc(func_general::FnProtoType::new(DataType::Int32, "entry".to_string(), vec![var_general::VarDecl::new(DataType::Int32, "x".to_string())]),
+		ret(Some(fib_call("x".to_aexp()).to_exp())));
+
+	seq(fib, entry)
+}
+
+/// Int32 y; fn entry(x : Int32) -> Int32 { if (x > 0) { y = 1; } else { skip; } return y; }
+fn cond_init_prog() -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let y_dec = var_dc(var_general::VarDecl::new(DataType::Int32, "y".to_string()));
+	let entry = fn_dc(func_general::FnProtoType::new(DataType::Int32, "entry".to_string(), vec![var_general::VarDecl::new(DataType::Int32, "x".to_string())]),
+		seq(if_el("x".to_aexp().gt(0i32.to_aexp()),
+				assign(var_general::VarRef::from_str("y"), 1i32.to_aexp().to_exp()),
+				skip()),
+			ret(Some("y".to_aexp().to_exp()))));
+
+	seq(y_dec, entry)
+}
+
+/// Recursion is rejected before the oblivious execution, rather than
+/// running into the call depth limit in the branch not taken.
+#[test]
+fn recursion_is_rejected_statically()
+{
+	let prog = fib_prog();
+
+	assert_eq!(ret_i32(entry_call(&prog, 10, false)), 55);
+
+	let why = interpreter::oblivious::check_program(&prog).unwrap_err();
+	assert!(why.contains("fib(i32) -> i32"), "{}", why);
+
+	assert!(interpreter::oblivious::check_program(&cond_init_prog()).is_ok());
+}
+
+/// An assignment on the inactive path leaves an uninitialized variable
+/// uninitialized, as the normal interpreter does.
+#[test]
+fn inactive_assignment_keeps_variable_uninitialized()
+{
+	let prog = cond_init_prog();
+
+	for oblivious in [false, true].iter()
+	{
+		assert_eq!(ret_i32(entry_call(&prog, 1, *oblivious)), 1);
+		assert!(entry_call(&prog, -1, *oblivious).unwrap_err().contains("hasn't been initialized"));
+	}
+}
+
+/// Whether the branch is taken doesn't show in the variables accessed,
+/// even when it is the first assignment of a variable.
+#[test]
+fn access_trace_does_not_depend_on_secret()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	/* Program:
+	 * fn entry(x : Int32) -> Int32 { Int32 z; if (x > 0) { z = x; } else { skip; } return 0; }
+	 */
+	let fn_prototype = func_general::FnProtoType::new(DataType::Int32, "entry".to_string(), vec![var_general::VarDecl::new(DataType::Int32, "x".to_string())]);
+	let if_cmd = if_el("x".to_aexp().gt(0i32.to_aexp()),
+		assign(var_general::VarRef::from_str("z"), "x".to_aexp().to_exp()),
+		skip());
+	let prog = fn_dc(fn_prototype,
+		seq(var_dc(var_general::VarDecl::new(DataType::Int32, "z".to_string())),
+			seq(if_cmd, ret(Some(0i32.to_aexp().to_exp())))));
+
+	let traced_call = |x : i32|
+	{
+		let mut prog_inter = interpreter::Program::new();
+		executor::gen_prog_states(&mut prog_inter, &prog).unwrap();
+
+		let call = func_general::FnCall::new("entry".to_string(), vec![x.to_aexp().to_exp()]);
+		let trace = std::cell::RefCell::new(Vec::new());
+		let ret = interpreter::oblivious::func_call_traced(&prog_inter.func_states, &prog_inter.var_states, &call, Some(&trace));
+
+		assert_eq!(ret_i32(ret), 0);
+		trace.into_inner()
+	};
+
+	let trace = traced_call(5);
+	assert_eq!(trace, traced_call(-5));
+	assert!(trace.contains(&interpreter::oblivious::Access::Write("z".to_string())));
+}
+
+/// The flag telling whether a while loop has a bound is 0 or 1.
+#[test]
+fn while_loop_bound_flag_is_checked()
+{
+	use cmd::constructor_helper::*;
+
+	let cond = bexp::Bexp::BoolConst{ v : false };
+	let lp = wh_lp_bounded(cond.clone(), skip(), 3);
+	let mut bytes = lp.to_bytes().unwrap();
+
+	let (bytes_left, parsed) = cmd::Cmd::from_bytes(&bytes[..]).unwrap();
+	assert!(bytes_left.is_empty());
+	assert_eq!(parsed.to_string(), lp.to_string());
+
+	let flag_pos = 1 + cond.to_bytes().unwrap().len() + skip().to_bytes().unwrap().len();
+	assert_eq!(bytes[flag_pos], 1u8);
+	bytes[flag_pos] = 2u8;
+
+	assert!(cmd::Cmd::from_bytes(&bytes[..]).is_err());
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "host_verifier")]
+
+use enclave_verifier::enclave::EnclaveBackend;
+use enclave_verifier::enclave::software::SoftwareBackend;
+use enclave_verifier::interpreter::exp;
+use enclave_verifier::ast::Serializible;
+use enclave_verifier::ast::Deserializible;
+use enclave_verifier::session::EnclaveSession;
+use enclave_verifier::session::program;
+use enclave_verifier::session::client;
+use enclave_verifier::session::client::ClientSession;
+
+/// Parameters sealed by the client open in the enclave, and the result sealed
+/// by the enclave opens for the client.
+#[test]
+fn session_round_trips()
+{
+	let backend = SoftwareBackend::generate();
+	let client = ClientSession::new(&backend.public_key()).unwrap();
+
+	let sealed_params = client.seal_params(b"params").unwrap();
+	assert!(!sealed_params.windows(6).any(|w| w == b"params"));
+
+	let (session, params) = EnclaveSession::open_params(&backend, &sealed_params[..]).unwrap();
+	assert_eq!(params, b"params".to_vec());
+
+	let ret = exp::func_ret_to_bytes(&Some(exp::ExpValue::B(true))).unwrap();
+	let sealed_result = session.seal_result(&backend, &ret[..]).unwrap();
+	assert_eq!(client.open_result(&sealed_params[..], &sealed_result[..]).unwrap(), ret);
+
+	// The client can reopen the session from its secret.
+	let reopened = ClientSession::from_secret(&client.secret(), &backend.public_key()).unwrap();
+	assert_eq!(reopened.client_public_key(), client.client_public_key());
+	assert_eq!(reopened.open_result(&sealed_params[..], &sealed_result[..]).unwrap(), ret);
+}
+
+/// Only the enclave of the key opens the parameters, and results only open
+/// for the parameters they answer.
+#[test]
+fn session_refuses_other_keys_and_tampering()
+{
+	let backend = SoftwareBackend::generate();
+	let client = ClientSession::new(&backend.public_key()).unwrap();
+
+	let sealed_params = client.seal_params(b"params").unwrap();
+	assert!(EnclaveSession::open_params(&SoftwareBackend::generate(), &sealed_params[..]).is_err());
+
+	let mut tampered = sealed_params.clone();
+	let last = tampered.len() - 1;
+	tampered[last] ^= 1u8;
+	assert!(EnclaveSession::open_params(&backend, &tampered[..]).is_err());
+
+	let (session, _) = EnclaveSession::open_params(&backend, &sealed_params[..]).unwrap();
+	let sealed_result = session.seal_result(&backend, b"result").unwrap();
+
+	let other_params = client.seal_params(b"params").unwrap();
+	assert!(client.open_result(&other_params[..], &sealed_result[..]).is_err());
+
+	let mut tampered = sealed_result.clone();
+	let last = tampered.len() - 1;
+	tampered[last] ^= 1u8;
+	assert!(client.open_result(&sealed_params[..], &tampered[..]).is_err());
+}
+
+/// Programs sealed by their owner open in the enclave of the key, with the
+/// MRENCLAVE they may be delivered to, and can't be redirected to another.
+#[test]
+fn sealed_program_opens_only_as_sealed()
+{
+	let backend = SoftwareBackend::generate();
+
+	let sealed = client::seal_program(&backend.public_key(), &[7u8; 32], b"byte code").unwrap();
+	assert_eq!(sealed.len(), b"byte code".len() + program::SEALED_PROGRAM_OVERHEAD);
+	assert!(program::is_sealed_program(&sealed[..]));
+
+	let (mr_enclave, byte_code) = program::open_program(&backend, &sealed[..]).unwrap();
+	assert_eq!(mr_enclave, [7u8; 32]);
+	assert_eq!(byte_code, b"byte code".to_vec());
+
+	// The MRENCLAVE is authenticated along with the bytecode.
+	let (_, mut parsed) = program::SealedProgram::from_bytes(&sealed[..]).unwrap();
+	parsed.mr_enclave = [8u8; 32];
+	assert!(program::open_program(&backend, &parsed.to_bytes().unwrap()[..]).is_err());
+
+	// Bytecode sealed again by an enclave opens in the recipient only.
+	let recipient = SoftwareBackend::generate();
+	let resealed = program::seal_program(&backend, &recipient.public_key(), &mr_enclave, &byte_code[..]).unwrap();
+	assert_eq!(program::open_program(&recipient, &resealed[..]).unwrap().1, byte_code);
+	assert!(program::open_program(&backend, &resealed[..]).is_err());
+}
@@ -0,0 +1,273 @@
+use enclave_verifier::ast::*;
+use enclave_verifier::interpreter;
+use enclave_verifier::interpreter::exp::ExpValue;
+use enclave_verifier::type_checker::type_checker;
+use enclave_verifier::typing;
+
+use data_type::DataType;
+
+const TYPES : [DataType; 3] = [DataType::Int32, DataType::Float32, DataType::Bool];
+
+/// Expressions over the parameters `a` and `b` of the test program.
+fn test_exps() -> Vec<exp::Exp>
+{
+	use aexp::constructor_helper::*;
+	use bexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	vec![
+		("a".to_aexp() + "b".to_aexp()).to_exp(),
+		("a".to_aexp() - "b".to_aexp()).to_exp(),
+		("a".to_aexp() * "b".to_aexp()).to_exp(),
+		("a".to_aexp() / "b".to_aexp()).to_exp(),
+		("a".to_aexp() % "b".to_aexp()).to_exp(),
+		"a".to_aexp().to_exp(),
+		"a".to_bexp().to_exp(),
+		"a".to_bexp().not().to_exp(),
+		"a".to_bexp().and("b".to_bexp()).to_exp(),
+		"a".to_bexp().beq("b".to_bexp()).to_exp(),
+		"a".to_aexp().lt("b".to_aexp()).to_exp(),
+		"a".to_aexp().aeq("b".to_aexp()).to_exp(),
+	]
+}
+
+/// fn entry(a : a_t, b : b_t) -> ret_t { var_t r; r = e; return r; }
+fn test_prog(a_t : DataType, b_t : DataType, var_t : DataType, ret_t : DataType, e : exp::Exp) -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let ret_e = match var_t
+	{
+		DataType::Bool => bexp::Bexp::Var{ v : var_general::VarRef::from_str("r") }.to_exp(),
+		_              => "r".to_aexp().to_exp(),
+	};
+
+	let params = vec![
+		var_general::VarDecl::new(a_t, "a".to_string()),
+		var_general::VarDecl::new(b_t, "b".to_string()),
+	];
+	let body = seq(
+		var_dc(var_general::VarDecl::new(var_t, "r".to_string())),
+		seq(assign(var_general::VarRef::from_str("r"), e), ret(Some(ret_e))));
+
+	fn_dc(func_general::FnProtoType::new(ret_t, "entry".to_string(), params), body)
+}
+
+fn test_arg(t : DataType, is_fst : bool) -> exp::Exp
+{
+	use aexp::constructor_helper::*;
+	use bexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	match (t, is_fst)
+	{
+		(DataType::Int32, true)    => 7i32.to_aexp().to_exp(),
+		(DataType::Int32, false)   => 3i32.to_aexp().to_exp(),
+		(DataType::Float32, true)  => 7.5f32.to_aexp().to_exp(),
+		(DataType::Float32, false) => 2.0f32.to_aexp().to_exp(),
+		(_, is_fst)                => is_fst.to_bexp().to_exp(),
+	}
+}
+
+fn type_check(prog : &cmd::Cmd) -> Result<(), String>
+{
+	let mut glvar_vec : Vec<type_checker::VarTypePair> = Vec::new();
+	let mut fn_vec : Vec<type_checker::FuncIdentifierTuple> = Vec::new();
+	type_checker::gather_fn_types(prog, &mut glvar_vec, &mut fn_vec)?;
+	type_checker::iterate_through_ast(prog.clone(), true, Vec::new(), &fn_vec, DataType::Void)?;
+	Ok(())
+}
+
+fn interpret(prog : &cmd::Cmd, args : Vec<exp::Exp>) -> Result<Option<ExpValue>, String>
+{
+	use interpreter::cmd::CanEvalToExpVal;
+
+	let mut prog_inter = interpreter::Program::new();
+	prog.eval_to_exp_val(&mut prog_inter.func_states, &mut prog_inter.var_states)?;
+
+	let entry_call = func_general::FnCall::new("entry".to_string(), args);
+	interpreter::states::func_call(&prog_inter.func_states, &prog_inter.var_states, &entry_call, false)
+}
+
+#[test]
+fn promotion_rules()
+{
+	assert!(typing::promotes_to(&DataType::Int32, &DataType::Float32));
+	assert!(!typing::promotes_to(&DataType::Float32, &DataType::Int32));
+	assert!(!typing::promotes_to(&DataType::Bool, &DataType::Int32));
+
+	assert_eq!(typing::arith_type(&DataType::Int32, &DataType::Int32), Ok(DataType::Int32));
+	assert_eq!(typing::arith_type(&DataType::Int32, &DataType::Float32), Ok(DataType::Float32));
+	assert!(typing::arith_type(&DataType::Bool, &DataType::Int32).is_err());
+
+	assert_eq!(typing::assign_type(&DataType::Float32, &DataType::Int32), Ok(DataType::Float32));
+	assert!(typing::assign_type(&DataType::Int32, &DataType::Float32).is_err());
+	assert!(typing::assign_type(&DataType::Int32, &DataType::Void).is_err());
+
+	assert_eq!(typing::return_type(&DataType::Void, None), Ok(DataType::Void));
+	assert!(typing::return_type(&DataType::Int32, None).is_err());
+	assert!(typing::return_type(&DataType::Void, Some(&DataType::Int32)).is_err());
+}
+
+/// A program accepted by the type checker runs without error and returns a
+/// value of the declared type, and a program rejected by it fails to run.
+#[test]
+fn checker_agrees_with_interpreter()
+{
+	let mut num_accepted = 0;
+
+	for e in test_exps().into_iter()
+	{
+		for a_t in TYPES.iter()
+		{
+			for b_t in TYPES.iter()
+			{
+				for var_t in TYPES.iter()
+				{
+					for ret_t in TYPES.iter()
+					{
+						let prog = test_prog(*a_t, *b_t, *var_t, *ret_t, e.clone());
+						let checked = type_check(&prog);
+						let ran = interpret(&prog, vec![test_arg(*a_t, true), test_arg(*b_t, false)]);
+
+						match (&checked, &ran)
+						{
+							(Ok(_), Ok(Some(v))) =>
+							{
+								assert_eq!(v.get_type(), *ret_t, "{} returned {}", prog, v);
+								num_accepted += 1;
+							},
+							(Err(_), Err(_)) => {},
+							_ => panic!("Type checker and interpreter disagree on {}:\n{:?}\n{:?}", prog, checked, ran),
+						}
+					}
+				}
+			}
+		}
+	}
+
+	assert!(num_accepted > 0);
+}
+
+/// fn f(x : Int32, y : Float32) -> Int32 { return 1; }
+/// fn f(x : Float32, y : Int32) -> Int32 { return 2; }
+/// fn f(x : Float32, y : Float32) -> Int32 { return 3; }
+/// fn entry() -> Int32 { return f(a, b); }
+fn overload_prog(a : exp::Exp, b : exp::Exp) -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let overload = |x_t : DataType, y_t : DataType, v : i32|
+	{
+		let params = vec![
+			var_general::VarDecl::new(x_t, "x".to_string()),
+			var_general::VarDecl::new(y_t, "y".to_string()),
+		];
+		fn_dc(func_general::FnProtoType::new(DataType::Int32, "f".to_string(), params), ret(Some(v.to_aexp().to_exp())))
+	};
+
+	let call = aexp::Aexp::FnCall{ fc : func_general::FnCall::new("f".to_string(), vec![a, b]) };
+	let entry = fn_dc(func_general::FnProtoType::new(DataType::Int32, "entry".to_string(), vec![]), ret(Some(call.to_exp())));
+
+	seq(overload(DataType::Int32, DataType::Float32, 1),
+		seq(overload(DataType::Float32, DataType::Int32, 2),
+			seq(overload(DataType::Float32, DataType::Float32, 3), entry)))
+}
+
+/// Calls resolve to the overload with the fewest promotions, ambiguous calls
+/// are rejected, and the overload recorded in the AST is the one called.
+#[test]
+fn overload_resolution()
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let cases = vec![
+		(1i32.to_aexp().to_exp(),   2.0f32.to_aexp().to_exp(), Some(1)),
+		(1.0f32.to_aexp().to_exp(), 2i32.to_aexp().to_exp(),   Some(2)),
+		(1.0f32.to_aexp().to_exp(), 2.0f32.to_aexp().to_exp(), Some(3)),
+		// f(Int32, Float32) and f(Float32, Int32) both promote one argument.
+		(1i32.to_aexp().to_exp(),   2i32.to_aexp().to_exp(),   None),
+	];
+
+	for (a, b, expected) in cases.into_iter()
+	{
+		let mut prog = overload_prog(a, b);
+		let checked = type_check(&prog);
+		let ran = interpret(&prog, vec![]);
+		let recorded = type_checker::record_overloads(&mut prog);
+
+		match expected
+		{
+			Some(v) =>
+			{
+				assert!(checked.is_ok() && recorded.is_ok(), "{:?} {:?}", checked, recorded);
+				assert_eq!(ran.unwrap().unwrap().to_string(), v.to_string());
+
+				// The recorded overload survives serialization and is the one called.
+				let bytes = prog.to_bytes().unwrap();
+				let (_, prog) = cmd::Cmd::from_bytes(&bytes[..]).unwrap();
+				assert!(type_check(&prog).is_ok());
+				assert_eq!(interpret(&prog, vec![]).unwrap().unwrap().to_string(), v.to_string());
+			},
+			None =>
+			{
+				assert!(checked.unwrap_err().contains("ambiguous"));
+				assert!(ran.unwrap_err().contains("ambiguous"));
+				assert!(recorded.is_err());
+			},
+		}
+	}
+}
+
+fn interpret_typed(prog : &cmd::Cmd, args : Vec<exp::Exp>) -> Result<Option<ExpValue>, String>
+{
+	use enclave_verifier::type_checker::annotate;
+
+	let typed_prog = annotate::annotate(prog)?;
+	let mut states = interpreter::typed::gen_prog_states(&typed_prog)?;
+
+	let entry_call = func_general::FnCall::new("entry".to_string(), args);
+	interpreter::typed::func_call(&typed_prog, &mut states, &entry_call)
+}
+
+/// The annotated AST of a program accepted by the type checker runs to the
+/// same result as the program itself.
+#[test]
+fn annotated_program_agrees_with_interpreter()
+{
+	for e in test_exps().into_iter()
+	{
+		for a_t in TYPES.iter()
+		{
+			for b_t in TYPES.iter()
+			{
+				let prog = test_prog(*a_t, *b_t, DataType::Float32, DataType::Float32, e.clone());
+				if type_check(&prog).is_err()
+				{
+					continue;
+				}
+
+				let args = vec![test_arg(*a_t, true), test_arg(*b_t, false)];
+				let ran = interpret(&prog, args.clone()).unwrap().unwrap();
+				let ran_typed = interpret_typed(&prog, args).unwrap().unwrap();
+
+				assert_eq!(ran.get_type(), ran_typed.get_type(), "{}", prog);
+				assert_eq!(ran.to_string(), ran_typed.to_string(), "{}", prog);
+			}
+		}
+	}
+
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+
+	let prog = overload_prog(1.0f32.to_aexp().to_exp(), 2i32.to_aexp().to_exp());
+	assert_eq!(interpret_typed(&prog, vec![]).unwrap().unwrap().to_string(), "2");
+
+	let prog = overload_prog(1i32.to_aexp().to_exp(), 2i32.to_aexp().to_exp());
+	assert!(interpret_typed(&prog, vec![]).is_err());
+}
@@ -0,0 +1,113 @@
+use enclave_verifier::ast::*;
+use enclave_verifier::interpreter;
+use enclave_verifier::type_checker::type_checker;
+
+use data_type::DataType;
+use test_fn::TestFn;
+
+#[path = "../src/bin/gen_byte_code.rs"]
+#[allow(dead_code)]
+mod gen_byte_code;
+
+fn type_check(prog : &cmd::Cmd) -> Result<(), String>
+{
+	let mut glvar_vec : Vec<type_checker::VarTypePair> = Vec::new();
+	let mut fn_vec : Vec<type_checker::FuncIdentifierTuple> = Vec::new();
+	type_checker::gather_fn_types(prog, &mut glvar_vec, &mut fn_vec)?;
+	type_checker::iterate_through_ast(prog.clone(), true, Vec::new(), &fn_vec, DataType::Void)?;
+	Ok(())
+}
+
+/// Int32 counter; counter = 0; fn bump() -> Int32 { counter = counter + 1; return counter; }
+fn counter_prog() -> cmd::Cmd
+{
+	use aexp::constructor_helper::*;
+	use exp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let dec = var_dc(var_general::VarDecl::new(DataType::Int32, "counter".to_string()));
+	let init = assign(var_general::VarRef::from_str("counter"), 0i32.to_aexp().to_exp());
+	let bump = fn_dc(func_general::FnProtoType::new(DataType::Int32, "bump".to_string(), vec![]),
+		seq(assign(var_general::VarRef::from_str("counter"), ("counter".to_aexp() + 1i32.to_aexp()).to_exp()),
+			ret(Some("counter".to_aexp().to_exp()))));
+
+	seq(dec, seq(init, bump))
+}
+
+/// #[test] fn name() { assert(bump() == 1); }
+fn bump_test(name : &str, expected : i32) -> TestFn
+{
+	use aexp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let bump_call = aexp::Aexp::FnCall{ fc : func_general::FnCall::new("bump".to_string(), vec![]) };
+	TestFn::new(name.to_string(), assert(bump_call.aeq(expected.to_aexp())))
+}
+
+/// The tests of the example program survive serialization after the program,
+/// are well-typed, and pass.
+#[test]
+fn example_tests_pass()
+{
+	let prog = gen_byte_code::example_progs().remove(0).1;
+	let tests = gen_byte_code::construct_example_prog_1_tests();
+
+	let mut bytes = prog.to_bytes().unwrap();
+	bytes.append(&mut tests.to_bytes().unwrap());
+
+	let (bytes_left, prog) = cmd::Cmd::from_bytes(&bytes[..]).unwrap();
+	let tests = test_fn::tests_from_bytes(bytes_left).unwrap();
+	assert_eq!(tests.len(), 4);
+
+	assert!(type_check(&test_fn::with_tests(&prog, &tests)).is_ok());
+
+	let results = interpreter::unit_test::run_tests(&prog, &tests);
+	assert_eq!(results.len(), 4);
+	for result in results.iter()
+	{
+		assert!(result.passed(), "{}", result);
+	}
+}
+
+/// A failed assertion fails its test only, and every test starts from fresh
+/// global states.
+#[test]
+fn failures_are_reported_per_test()
+{
+	let prog = counter_prog();
+	let tests = vec![bump_test("first", 1), bump_test("wrong", 2), bump_test("again", 1)];
+	assert!(type_check(&test_fn::with_tests(&prog, &tests)).is_ok());
+
+	let results = interpreter::unit_test::run_tests(&prog, &tests);
+	let verdicts : Vec<(&str, bool)> = results.iter().map(|r| (r.name.as_str(), r.passed())).collect();
+	assert_eq!(verdicts, vec![("first", true), ("wrong", false), ("again", true)]);
+
+	let why = results[1].result.as_ref().unwrap_err();
+	assert!(why.contains("Assertion"), "{}", why);
+	assert!(results[1].to_string().contains("FAIL"));
+}
+
+/// Programs without test section have no tests, and the type checker only
+/// accepts boolean assertions.
+#[test]
+fn test_section_and_assertions()
+{
+	use aexp::constructor_helper::*;
+	use cmd::constructor_helper::*;
+
+	let prog = counter_prog();
+	let bytes = prog.to_bytes().unwrap();
+	let (bytes_left, _) = cmd::Cmd::from_bytes(&bytes[..]).unwrap();
+	assert!(test_fn::tests_from_bytes(bytes_left).unwrap().is_empty());
+
+	let mut bytes = vec![bump_test("first", 1)].to_bytes().unwrap();
+	bytes.push(0u8);
+	assert!(test_fn::tests_from_bytes(&bytes[..]).is_err());
+
+	let not_bool = bexp::Bexp::FnCall{ fc : func_general::FnCall::new("bump".to_string(), vec![]) };
+	let tests = vec![TestFn::new("not_bool".to_string(), assert(not_bool))];
+	assert!(type_check(&test_fn::with_tests(&prog, &tests)).is_err());
+
+	let tests = vec![TestFn::new("uses_undeclared".to_string(), assert("x".to_aexp().aeq(0i32.to_aexp())))];
+	assert!(type_check(&test_fn::with_tests(&prog, &tests)).is_err());
+}